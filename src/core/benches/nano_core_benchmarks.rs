@@ -0,0 +1,60 @@
+//! Benchmarks de SAAI Core
+//!
+//! Comparación de códecs del Cognitive Fabric sobre un evento de métricas
+//! representativo de las rutas de alta frecuencia.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use saai_core::communication::{CognitiveEvent, EventCodec, EventPriority, EventType};
+use uuid::Uuid;
+
+fn metrics_event() -> CognitiveEvent {
+    let payload = serde_json::to_vec(&serde_json::json!({
+        "cpu_count": 16,
+        "cpu_usage": 42.5,
+        "total_memory": 34_359_738_368u64,
+        "used_memory": 17_179_869_184u64,
+        "available_memory": 17_179_869_184u64,
+        "total_swap": 8_589_934_592u64,
+        "used_swap": 0,
+        "load_average": [1.25, 0.98, 0.75],
+    }))
+    .unwrap();
+
+    CognitiveEvent {
+        id: Uuid::new_v4(),
+        event_type: EventType::SystemMetrics,
+        source: "OS-0".to_string(),
+        target: None,
+        timestamp: chrono::Utc::now(),
+        payload,
+        priority: EventPriority::Normal,
+        correlation_id: Some(Uuid::new_v4()),
+        headers: std::collections::HashMap::new(),
+    }
+}
+
+fn bench_codecs(c: &mut Criterion) {
+    let event = metrics_event();
+    let codecs = [EventCodec::Json, EventCodec::Cbor, EventCodec::Protobuf];
+
+    let mut encode = c.benchmark_group("fabric_encode");
+    for codec in codecs {
+        encode.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", codec)), &codec, |b, codec| {
+            b.iter(|| codec.encode(black_box(&event)).unwrap())
+        });
+    }
+    encode.finish();
+
+    let mut decode = c.benchmark_group("fabric_decode");
+    for codec in codecs {
+        let encoded = codec.encode(&event).unwrap();
+        println!("📦 {:?}: {} bytes", codec, encoded.len());
+        decode.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", codec)), &encoded, |b, data| {
+            b.iter(|| codec.decode(black_box(data)).unwrap())
+        });
+    }
+    decode.finish();
+}
+
+criterion_group!(benches, bench_codecs);
+criterion_main!(benches);
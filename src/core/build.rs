@@ -27,6 +27,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .collect();
 
         if !proto_files.is_empty() {
+            // tonic-build no crea el directorio de salida
+            std::fs::create_dir_all("src/generated")?;
+
             tonic_build::configure()
                 .build_server(true)
                 .build_client(true)
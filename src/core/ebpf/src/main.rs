@@ -0,0 +1,80 @@
+//! Programas eBPF del monitor de procesos de OSCore
+//!
+//! Tracepoints de exec, exit y entrada de syscalls que envían un
+//! `ProcessEvent` por `EVENTS`; el lado de usuario vive en
+//! `nano_cores::ebpf_monitor`, que decide qué syscalls se trazan rellenando
+//! `WATCHED_SYSCALLS`.
+
+#![no_std]
+#![no_main]
+
+use aya_ebpf::{
+    helpers::{bpf_get_current_comm, bpf_get_current_pid_tgid},
+    macros::{map, tracepoint},
+    maps::{HashMap, PerfEventArray},
+    programs::TracePointContext,
+};
+
+const EVENT_EXEC: u32 = 0;
+const EVENT_EXIT: u32 = 1;
+const EVENT_SYSCALL: u32 = 2;
+
+/// Debe coincidir con `ProcessEvent` de `ebpf_monitor.rs`
+#[repr(C)]
+pub struct ProcessEvent {
+    pub kind: u32,
+    pub pid: u32,
+    pub syscall: i64,
+    pub comm: [u8; 16],
+}
+
+#[map]
+static EVENTS: PerfEventArray<ProcessEvent> = PerfEventArray::new(0);
+
+/// Números de syscall a reportar, rellenado desde el lado de usuario
+#[map]
+static WATCHED_SYSCALLS: HashMap<u32, u8> = HashMap::with_max_entries(64, 0);
+
+/// Offset de `id` en `raw_syscalls/sys_enter` (tras la cabecera común de 8 bytes)
+const SYS_ENTER_ID_OFFSET: usize = 8;
+
+#[inline(always)]
+fn emit(ctx: &TracePointContext, kind: u32, syscall: i64) {
+    let pid = (bpf_get_current_pid_tgid() >> 32) as u32;
+    let comm = bpf_get_current_comm().unwrap_or([0u8; 16]);
+    let event = ProcessEvent { kind, pid, syscall, comm };
+    EVENTS.output(ctx, &event, 0);
+}
+
+#[tracepoint]
+pub fn saai_exec(ctx: TracePointContext) -> u32 {
+    emit(&ctx, EVENT_EXEC, -1);
+    0
+}
+
+#[tracepoint]
+pub fn saai_exit(ctx: TracePointContext) -> u32 {
+    // Solo la salida del líder del grupo cuenta como fin del proceso
+    let pid_tgid = bpf_get_current_pid_tgid();
+    if (pid_tgid >> 32) as u32 == pid_tgid as u32 {
+        emit(&ctx, EVENT_EXIT, -1);
+    }
+    0
+}
+
+#[tracepoint]
+pub fn saai_sys_enter(ctx: TracePointContext) -> u32 {
+    let id: i64 = match unsafe { ctx.read_at(SYS_ENTER_ID_OFFSET) } {
+        Ok(id) => id,
+        Err(_) => return 0,
+    };
+    if unsafe { WATCHED_SYSCALLS.get(&(id as u32)) }.is_some() {
+        emit(&ctx, EVENT_SYSCALL, id);
+    }
+    0
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
@@ -0,0 +1,204 @@
+//! Control de contrapresión para suscripciones
+//!
+//! Cada suscripción entrega sus mensajes a través de una cola acotada con
+//! política de desbordamiento configurable, de modo que un consumidor lento
+//! no pueda agotar la memoria del proceso.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use super::EventFilter;
+
+/// Política aplicada cuando la cola de una suscripción está llena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// Esperar a que el consumidor libere espacio
+    Block,
+    /// Descartar el mensaje más antiguo en cola
+    DropOldest,
+    /// Descartar el mensaje entrante
+    DropNewest,
+}
+
+/// Opciones de una suscripción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionOptions {
+    pub filter: EventFilter,
+    pub queue_capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+    /// Suprimir eventos ya entregados a esta suscripción (por `id`)
+    pub exactly_once: bool,
+}
+
+impl Default for SubscriptionOptions {
+    fn default() -> Self {
+        Self {
+            filter: EventFilter::Any,
+            queue_capacity: 1024,
+            overflow_policy: OverflowPolicy::Block,
+            exactly_once: false,
+        }
+    }
+}
+
+/// Estadísticas de la cola de una suscripción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionQueueStats {
+    pub subject: String,
+    pub depth: usize,
+    pub capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+    pub delivered: u64,
+    pub dropped: u64,
+}
+
+/// Cola acotada entre el lector NATS y el manejador de la suscripción
+pub struct SubscriptionQueue<T> {
+    subject: String,
+    items: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    readable: Notify,
+    writable: Notify,
+    closed: AtomicBool,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl<T> SubscriptionQueue<T> {
+    /// Crear cola para un tema
+    pub fn new(subject: &str, capacity: usize, policy: OverflowPolicy) -> Self {
+        let capacity = capacity.max(1);
+
+        Self {
+            subject: subject.to_string(),
+            items: Mutex::new(VecDeque::with_capacity(capacity.min(1024))),
+            capacity,
+            policy,
+            readable: Notify::new(),
+            writable: Notify::new(),
+            closed: AtomicBool::new(false),
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Encolar mensaje aplicando la política de desbordamiento
+    ///
+    /// Devuelve `false` si el mensaje entrante fue descartado.
+    pub async fn push(&self, item: T) -> bool {
+        let mut item = Some(item);
+
+        loop {
+            {
+                let mut items = self.items.lock().unwrap();
+
+                if items.len() < self.capacity {
+                    items.push_back(item.take().unwrap());
+                    self.readable.notify_one();
+                    return true;
+                }
+
+                match self.policy {
+                    OverflowPolicy::DropNewest => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return false;
+                    }
+                    OverflowPolicy::DropOldest => {
+                        items.pop_front();
+                        items.push_back(item.take().unwrap());
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.readable.notify_one();
+                        return true;
+                    }
+                    OverflowPolicy::Block => {}
+                }
+            }
+
+            if self.closed.load(Ordering::Acquire) {
+                return false;
+            }
+
+            self.writable.notified().await;
+        }
+    }
+
+    /// Extraer el siguiente mensaje, o `None` si la cola se cerró y está vacía
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            {
+                let mut items = self.items.lock().unwrap();
+
+                if let Some(item) = items.pop_front() {
+                    self.delivered.fetch_add(1, Ordering::Relaxed);
+                    self.writable.notify_one();
+                    return Some(item);
+                }
+
+                if self.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+
+            self.readable.notified().await;
+        }
+    }
+
+    /// Cerrar la cola despertando a productor y consumidor
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.readable.notify_one();
+        self.writable.notify_one();
+    }
+
+    /// Indica si la cola fue cerrada
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Número de mensajes pendientes
+    pub fn depth(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    /// Instantánea de estadísticas
+    pub fn stats(&self) -> SubscriptionQueueStats {
+        SubscriptionQueueStats {
+            subject: self.subject.clone(),
+            depth: self.depth(),
+            capacity: self.capacity,
+            overflow_policy: self.policy,
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overflow_policies() {
+        let oldest = SubscriptionQueue::new("saai.test", 2, OverflowPolicy::DropOldest);
+        for i in 0..3 {
+            assert!(oldest.push(i).await);
+        }
+        assert_eq!(oldest.pop().await, Some(1));
+        assert_eq!(oldest.stats().dropped, 1);
+
+        let newest = SubscriptionQueue::new("saai.test", 2, OverflowPolicy::DropNewest);
+        for i in 0..3 {
+            newest.push(i).await;
+        }
+        assert_eq!(newest.pop().await, Some(0));
+        assert_eq!(newest.depth(), 1);
+
+        newest.close();
+        assert_eq!(newest.pop().await, Some(1));
+        assert_eq!(newest.pop().await, None);
+    }
+}
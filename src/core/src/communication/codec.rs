@@ -0,0 +1,240 @@
+//! Códecs de eventos del Cognitive Fabric
+//!
+//! Serialización de `CognitiveEvent` en JSON, CBOR o Protobuf. El códec se
+//! selecciona por tema al publicar y se negocia mediante la cabecera
+//! `Content-Type` al consumir.
+
+use anyhow::{Result, anyhow};
+use chrono::TimeZone;
+use nats::HeaderMap;
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{CognitiveEvent, EventPriority, EventType};
+
+/// Mensajes generados por tonic-build a partir de `proto/fabric.proto`
+pub mod proto {
+    include!("../generated/saai.fabric.rs");
+}
+
+/// Cabecera NATS usada para negociar el códec
+pub const CONTENT_TYPE_HEADER: &str = "Content-Type";
+
+/// Códecs soportados por el Cognitive Fabric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventCodec {
+    Json,
+    Cbor,
+    Protobuf,
+}
+
+impl Default for EventCodec {
+    fn default() -> Self {
+        // JSON por compatibilidad con consumidores existentes
+        EventCodec::Json
+    }
+}
+
+impl EventCodec {
+    /// Valor de `Content-Type` asociado al códec
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            EventCodec::Json => "application/json",
+            EventCodec::Cbor => "application/cbor",
+            EventCodec::Protobuf => "application/x-protobuf",
+        }
+    }
+
+    /// Resolver códec a partir de un `Content-Type`
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        match mime.as_str() {
+            "application/json" => Some(EventCodec::Json),
+            "application/cbor" => Some(EventCodec::Cbor),
+            "application/x-protobuf" | "application/protobuf" => Some(EventCodec::Protobuf),
+            _ => None,
+        }
+    }
+
+    /// Codificar evento
+    pub fn encode(&self, event: &CognitiveEvent) -> Result<Vec<u8>> {
+        match self {
+            EventCodec::Json => Ok(serde_json::to_vec(event)?),
+            EventCodec::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(event, &mut buffer)
+                    .map_err(|e| anyhow!("Error codificando CBOR: {}", e))?;
+                Ok(buffer)
+            }
+            EventCodec::Protobuf => Ok(proto::CognitiveEvent::from(event).encode_to_vec()),
+        }
+    }
+
+    /// Decodificar evento
+    pub fn decode(&self, data: &[u8]) -> Result<CognitiveEvent> {
+        match self {
+            EventCodec::Json => Ok(serde_json::from_slice(data)?),
+            EventCodec::Cbor => ciborium::from_reader(data)
+                .map_err(|e| anyhow!("Error decodificando CBOR: {}", e)),
+            EventCodec::Protobuf => CognitiveEvent::try_from(proto::CognitiveEvent::decode(data)?),
+        }
+    }
+
+    /// Negociar códec a partir de las cabeceras de un mensaje
+    pub fn negotiate(headers: Option<&HeaderMap>) -> Self {
+        headers
+            .and_then(|h| h.get(CONTENT_TYPE_HEADER))
+            .and_then(|value| Self::from_content_type(value))
+            .unwrap_or_default()
+    }
+
+    /// Cabeceras NATS que anuncian este códec
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE_HEADER, self.content_type());
+        headers
+    }
+}
+
+/// Selección de códec por tema
+#[derive(Debug, Clone, Default)]
+pub struct CodecSelector {
+    default_codec: EventCodec,
+    subject_codecs: HashMap<String, EventCodec>,
+}
+
+impl CodecSelector {
+    /// Crear selector con un códec por defecto
+    pub fn new(default_codec: EventCodec) -> Self {
+        Self {
+            default_codec,
+            subject_codecs: HashMap::new(),
+        }
+    }
+
+    /// Asignar códec a un tema específico
+    pub fn set_subject_codec(&mut self, subject: &str, codec: EventCodec) {
+        self.subject_codecs.insert(subject.to_string(), codec);
+    }
+
+    /// Obtener códec para un tema
+    pub fn codec_for_subject(&self, subject: &str) -> EventCodec {
+        self.subject_codecs
+            .get(subject)
+            .copied()
+            .unwrap_or(self.default_codec)
+    }
+}
+
+impl From<&CognitiveEvent> for proto::CognitiveEvent {
+    fn from(event: &CognitiveEvent) -> Self {
+        let (event_type, custom_type) = match &event.event_type {
+            EventType::Custom(name) => ("Custom".to_string(), name.clone()),
+            other => (format!("{:?}", other), String::new()),
+        };
+
+        Self {
+            id: event.id.to_string(),
+            event_type,
+            custom_type,
+            source: event.source.clone(),
+            target: event.target.clone(),
+            timestamp_nanos: event.timestamp.timestamp_nanos_opt().unwrap_or_default(),
+            payload: event.payload.clone(),
+            priority: event.priority.clone() as i32,
+            correlation_id: event.correlation_id.map(|id| id.to_string()),
+            headers: event.headers.clone(),
+        }
+    }
+}
+
+impl TryFrom<proto::CognitiveEvent> for CognitiveEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(message: proto::CognitiveEvent) -> Result<Self> {
+        let event_type = match message.event_type.as_str() {
+            "SystemMetrics" => EventType::SystemMetrics,
+            "AgentCommand" => EventType::AgentCommand,
+            "ConsensusVote" => EventType::ConsensusVote,
+            "MutationRequest" => EventType::MutationRequest,
+            "HealthCheck" => EventType::HealthCheck,
+            "SecurityAlert" => EventType::SecurityAlert,
+            "UserInteraction" => EventType::UserInteraction,
+            "Custom" => EventType::Custom(message.custom_type),
+            other => return Err(anyhow!("Tipo de evento desconocido: {}", other)),
+        };
+
+        let priority = match message.priority {
+            0 => EventPriority::Critical,
+            1 => EventPriority::High,
+            2 => EventPriority::Normal,
+            3 => EventPriority::Low,
+            other => return Err(anyhow!("Prioridad de evento desconocida: {}", other)),
+        };
+
+        Ok(Self {
+            id: Uuid::parse_str(&message.id)?,
+            event_type,
+            source: message.source,
+            target: message.target,
+            timestamp: chrono::Utc.timestamp_nanos(message.timestamp_nanos),
+            payload: message.payload,
+            priority,
+            correlation_id: message
+                .correlation_id
+                .map(|id| Uuid::parse_str(&id))
+                .transpose()?,
+            headers: message.headers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_event() -> CognitiveEvent {
+        CognitiveEvent {
+            id: Uuid::new_v4(),
+            event_type: EventType::Custom("probe".to_string()),
+            source: "test".to_string(),
+            target: Some("OS-1".to_string()),
+            timestamp: chrono::Utc::now(),
+            payload: vec![1, 2, 3],
+            priority: EventPriority::High,
+            correlation_id: Some(Uuid::new_v4()),
+            headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_codec_roundtrip() {
+        let event = sample_event();
+
+        for codec in [EventCodec::Json, EventCodec::Cbor, EventCodec::Protobuf] {
+            let decoded = codec.decode(&codec.encode(&event).unwrap()).unwrap();
+            assert_eq!(decoded.id, event.id);
+            assert_eq!(decoded.target, event.target);
+            assert_eq!(decoded.payload, event.payload);
+            assert_eq!(decoded.priority, event.priority);
+            assert_eq!(decoded.correlation_id, event.correlation_id);
+        }
+    }
+
+    #[test]
+    fn test_negotiation_defaults_to_json() {
+        assert_eq!(EventCodec::negotiate(None), EventCodec::Json);
+        assert_eq!(
+            EventCodec::negotiate(Some(&EventCodec::Cbor.headers())),
+            EventCodec::Cbor
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Filtros de suscripción del Cognitive Fabric
+//!
+//! Pequeño DSL de predicados sobre eventos (origen, prioridad, correlación,
+//! tipo, tema) evaluado antes de invocar al manejador del usuario, junto con
+//! la semántica de comodines de NATS (`*` y `>`).
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CognitiveEvent, EventPriority};
+
+/// Predicado sobre eventos del Cognitive Fabric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EventFilter {
+    /// Acepta cualquier evento
+    Any,
+    /// Origen con patrón glob (`*` coincide con cualquier secuencia)
+    Source(String),
+    /// Destino con patrón glob; no coincide con eventos sin destino
+    Target(String),
+    /// Prioridad igual o más urgente que la indicada
+    AtLeastPriority(EventPriority),
+    /// Identificador de correlación exacto
+    CorrelationId(Uuid),
+    /// Tipo de evento por nombre (`SystemMetrics`, `Custom:<nombre>`, ...)
+    EventType(String),
+    /// Tema NATS con comodines (`saai.*.alerts`, `saai.>`)
+    Subject(String),
+    All(Vec<EventFilter>),
+    AnyOf(Vec<EventFilter>),
+    Not(Box<EventFilter>),
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        EventFilter::Any
+    }
+}
+
+impl EventFilter {
+    pub fn source(pattern: &str) -> Self {
+        EventFilter::Source(pattern.to_string())
+    }
+
+    pub fn target(pattern: &str) -> Self {
+        EventFilter::Target(pattern.to_string())
+    }
+
+    pub fn at_least(priority: EventPriority) -> Self {
+        EventFilter::AtLeastPriority(priority)
+    }
+
+    pub fn correlation(id: Uuid) -> Self {
+        EventFilter::CorrelationId(id)
+    }
+
+    pub fn event_type(name: &str) -> Self {
+        EventFilter::EventType(name.to_string())
+    }
+
+    pub fn subject(pattern: &str) -> Self {
+        EventFilter::Subject(pattern.to_string())
+    }
+
+    /// Conjunción con otro filtro
+    pub fn and(self, other: EventFilter) -> Self {
+        match self {
+            EventFilter::Any => other,
+            EventFilter::All(mut filters) => {
+                filters.push(other);
+                EventFilter::All(filters)
+            }
+            filter => EventFilter::All(vec![filter, other]),
+        }
+    }
+
+    /// Disyunción con otro filtro
+    pub fn or(self, other: EventFilter) -> Self {
+        match self {
+            EventFilter::AnyOf(mut filters) => {
+                filters.push(other);
+                EventFilter::AnyOf(filters)
+            }
+            filter => EventFilter::AnyOf(vec![filter, other]),
+        }
+    }
+
+    /// Negación del filtro
+    pub fn negate(self) -> Self {
+        EventFilter::Not(Box::new(self))
+    }
+
+    /// Evaluar el filtro contra un evento recibido en `subject`
+    pub fn matches(&self, subject: &str, event: &CognitiveEvent) -> bool {
+        match self {
+            EventFilter::Any => true,
+            EventFilter::Source(pattern) => glob_matches(pattern, &event.source),
+            EventFilter::Target(pattern) => event
+                .target
+                .as_deref()
+                .map(|target| glob_matches(pattern, target))
+                .unwrap_or(false),
+            EventFilter::AtLeastPriority(priority) => event.priority <= *priority,
+            EventFilter::CorrelationId(id) => event.correlation_id == Some(*id),
+            EventFilter::EventType(name) => event.event_type.name() == *name,
+            EventFilter::Subject(pattern) => subject_matches(pattern, subject),
+            EventFilter::All(filters) => filters.iter().all(|f| f.matches(subject, event)),
+            EventFilter::AnyOf(filters) => filters.iter().any(|f| f.matches(subject, event)),
+            EventFilter::Not(filter) => !filter.matches(subject, event),
+        }
+    }
+}
+
+/// Verificar si un tema coincide con un patrón NATS
+///
+/// `*` coincide con exactamente un token y `>` con uno o más tokens finales.
+pub fn subject_matches(pattern: &str, subject: &str) -> bool {
+    let mut pattern_tokens = pattern.split('.');
+    let mut subject_tokens = subject.split('.');
+
+    loop {
+        match (pattern_tokens.next(), subject_tokens.next()) {
+            (Some(">"), Some(_)) => return pattern_tokens.next().is_none(),
+            (Some("*"), Some(_)) => continue,
+            (Some(p), Some(s)) if p == s => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Coincidencia glob simple donde `*` representa cualquier secuencia
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let mut remaining = value;
+
+    if let Some(prefix) = parts.first() {
+        if !remaining.starts_with(prefix) {
+            return false;
+        }
+        remaining = &remaining[prefix.len()..];
+    }
+
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    parts.last().map(|suffix| remaining.ends_with(suffix)).unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::EventType;
+
+    fn event(source: &str, priority: EventPriority) -> CognitiveEvent {
+        CognitiveEvent {
+            id: Uuid::new_v4(),
+            event_type: EventType::SecurityAlert,
+            source: source.to_string(),
+            target: None,
+            timestamp: chrono::Utc::now(),
+            payload: Vec::new(),
+            priority,
+            correlation_id: None,
+            headers: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_subject_wildcards() {
+        assert!(subject_matches("saai.>", "saai.security.alerts"));
+        assert!(subject_matches("saai.*.alerts", "saai.network.alerts"));
+        assert!(!subject_matches("saai.*.alerts", "saai.network.metrics"));
+        assert!(!subject_matches("saai.>", "saai"));
+        assert!(!subject_matches("saai.*", "saai.security.alerts"));
+    }
+
+    #[test]
+    fn test_filter_composition() {
+        let filter = EventFilter::source("Security-*")
+            .and(EventFilter::at_least(EventPriority::High))
+            .and(EventFilter::subject("saai.*.alerts"));
+
+        assert!(filter.matches("saai.security.alerts", &event("Security-2", EventPriority::Critical)));
+        assert!(!filter.matches("saai.security.alerts", &event("Security-2", EventPriority::Normal)));
+        assert!(!filter.matches("saai.security.alerts", &event("Network-0", EventPriority::High)));
+        assert!(!filter.matches("saai.security.metrics", &event("Security-1", EventPriority::High)));
+    }
+}
@@ -0,0 +1,106 @@
+//! Supresión de eventos duplicados
+//!
+//! Caché de identificadores de evento con TTL usada por las suscripciones
+//! `exactly_once`, de modo que reconexiones y replays no entreguen dos veces
+//! el mismo `CognitiveEvent`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Ventana por defecto durante la que se recuerda un evento
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// Máximo de identificadores recordados antes de expulsar los más antiguos
+const DEFAULT_MAX_ENTRIES: usize = 100_000;
+
+struct CacheState {
+    ttl: Duration,
+    seen: HashMap<(String, Uuid), Instant>,
+    order: VecDeque<((String, Uuid), Instant)>,
+}
+
+/// Caché de idempotencia por ámbito (suscripción) e identificador de evento
+pub struct IdempotencyCache {
+    state: Mutex<CacheState>,
+    max_entries: usize,
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                ttl,
+                seen: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            max_entries: max_entries.max(1),
+        }
+    }
+
+    /// Cambiar la ventana de deduplicación
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.state.lock().unwrap().ttl = ttl;
+    }
+
+    /// Registrar evento; devuelve `false` si ya se vio dentro de la ventana
+    pub fn check_and_insert(&self, scope: &str, id: Uuid) -> bool {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let ttl = state.ttl;
+
+        // Purgar entradas expiradas o que exceden la capacidad
+        while let Some((key, inserted)) = state.order.front().cloned() {
+            if now.duration_since(inserted) < ttl && state.order.len() < self.max_entries {
+                break;
+            }
+            state.order.pop_front();
+            if state.seen.get(&key) == Some(&inserted) {
+                state.seen.remove(&key);
+            }
+        }
+
+        let key = (scope.to_string(), id);
+        if state.seen.contains_key(&key) {
+            return false;
+        }
+
+        state.seen.insert(key.clone(), now);
+        state.order.push_back((key, now));
+        true
+    }
+
+    /// Número de eventos recordados
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_MAX_ENTRIES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_suppressed_per_scope() {
+        let cache = IdempotencyCache::default();
+        let id = Uuid::new_v4();
+
+        assert!(cache.check_and_insert("saai.health", id));
+        assert!(!cache.check_and_insert("saai.health", id));
+        assert!(cache.check_and_insert("saai.>", id));
+
+        cache.set_ttl(Duration::ZERO);
+        assert!(cache.check_and_insert("saai.health", id));
+    }
+}
@@ -6,13 +6,38 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use nats::asynk::{Connection, Subscription};
+use nats::HeaderMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, OnceCell, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use uuid::Uuid;
 
+use crate::metrics::{FabricMetrics, StatusProvider};
+
+pub mod backpressure;
+pub mod codec;
+pub mod filter;
+pub mod idempotency;
+pub mod router;
+pub mod streams;
+pub mod trace_context;
+pub mod transport;
+
+pub use backpressure::{
+    OverflowPolicy, SubscriptionOptions, SubscriptionQueue, SubscriptionQueueStats,
+};
+pub use codec::{CodecSelector, EventCodec};
+pub use filter::{subject_matches, EventFilter};
+pub use idempotency::IdempotencyCache;
+pub use streams::{DurableStreamConfig, DurableStreams};
+pub use trace_context::{TraceContext, TRACEPARENT_HEADER};
+pub use transport::{FabricBackend, PeerTransport, TransportConfig, TransportFrame, TransportHub};
+
 /// Tipos de eventos en el Cognitive Fabric
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EventType {
@@ -26,6 +51,16 @@ pub enum EventType {
     Custom(String),
 }
 
+impl EventType {
+    /// Nombre del tipo para filtros y etiquetas de métricas
+    pub fn name(&self) -> String {
+        match self {
+            EventType::Custom(name) => format!("Custom:{}", name),
+            other => format!("{:?}", other),
+        }
+    }
+}
+
 /// Estructura de evento en el Cognitive Fabric
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CognitiveEvent {
@@ -37,6 +72,32 @@ pub struct CognitiveEvent {
     pub payload: Vec<u8>,
     pub priority: EventPriority,
     pub correlation_id: Option<Uuid>,
+    /// Metadatos de transporte (contexto de traza, etc.)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl CognitiveEvent {
+    /// Crear evento derivado que conserva la correlación y continúa la traza
+    pub fn follow_up(&self, event_type: EventType, source: &str, payload: Vec<u8>) -> Self {
+        let parent = TraceContext::from_event(self)
+            .unwrap_or_else(|| TraceContext::new_root(self.correlation_id));
+
+        let mut event = Self {
+            id: Uuid::new_v4(),
+            event_type,
+            source: source.to_string(),
+            target: None,
+            timestamp: chrono::Utc::now(),
+            payload,
+            priority: self.priority.clone(),
+            correlation_id: Some(self.correlation_id.unwrap_or(self.id)),
+            headers: HashMap::new(),
+        };
+
+        parent.child().inject(&mut event);
+        event
+    }
 }
 
 /// Prioridad de eventos para QoS
@@ -54,11 +115,30 @@ pub trait EventHandler: Send + Sync {
     async fn handle_event(&self, event: &CognitiveEvent) -> Result<()>;
 }
 
+/// Suscripción activa con su cola acotada
+struct ActiveSubscription {
+    /// Suscripción NATS, ausente en despliegues sin NATS
+    subscription: Option<Arc<Subscription>>,
+    queue: Arc<SubscriptionQueue<TransportFrame>>,
+}
+
 /// Cliente del Cognitive Fabric
 pub struct CognitiveFabricClient {
     connection: Arc<RwLock<Option<Connection>>>,
-    subscriptions: Arc<RwLock<HashMap<String, Subscription>>>,
+    subscriptions: Arc<RwLock<HashMap<String, ActiveSubscription>>>,
     handlers: Arc<RwLock<HashMap<String, Box<dyn EventHandler>>>>,
+    codecs: Arc<RwLock<CodecSelector>>,
+    streams: Arc<RwLock<Option<Arc<DurableStreams>>>>,
+    peers: Arc<RwLock<HashMap<String, Arc<dyn FabricBackend>>>>,
+    inbound: TransportHub,
+    outbound: TransportHub,
+    transport_servers: Arc<RwLock<Vec<JoinHandle<()>>>>,
+    metrics: Arc<OnceLock<Arc<FabricMetrics>>>,
+    idempotency: Arc<IdempotencyCache>,
+    /// Pings en vuelo por ID
+    pings: Arc<Mutex<HashMap<Uuid, oneshot::Sender<()>>>>,
+    /// Tema propio de los pings, suscrito al primer `ping`
+    ping_subject: OnceCell<String>,
     client_id: String,
     nats_url: String,
 }
@@ -70,6 +150,16 @@ impl CognitiveFabricClient {
             connection: Arc::new(RwLock::new(None)),
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             handlers: Arc::new(RwLock::new(HashMap::new())),
+            codecs: Arc::new(RwLock::new(CodecSelector::default())),
+            streams: Arc::new(RwLock::new(None)),
+            peers: Arc::new(RwLock::new(HashMap::new())),
+            inbound: TransportHub::new(),
+            outbound: TransportHub::new(),
+            transport_servers: Arc::new(RwLock::new(Vec::new())),
+            metrics: Arc::new(OnceLock::new()),
+            idempotency: Arc::new(IdempotencyCache::default()),
+            pings: Arc::new(Mutex::new(HashMap::new())),
+            ping_subject: OnceCell::new(),
             client_id: format!("saai-{}", Uuid::new_v4()),
             nats_url: nats_url.to_string(),
         }
@@ -87,12 +177,70 @@ impl CognitiveFabricClient {
         Ok(())
     }
 
+    /// Asociar las métricas etiquetadas del fabric
+    pub fn attach_metrics(&self, metrics: Arc<FabricMetrics>) {
+        if self.metrics.set(metrics).is_err() {
+            warn!("⚠️  Métricas del fabric ya asociadas");
+        }
+    }
+
+    /// Configurar la ventana de deduplicación de las suscripciones `exactly_once`
+    pub fn set_idempotency_ttl(&self, ttl: std::time::Duration) {
+        self.idempotency.set_ttl(ttl);
+        info!("🔁 Ventana de idempotencia: {:?}", ttl);
+    }
+
+    /// Configurar transportes alternativos por par y servidores locales
+    ///
+    /// Las suscripciones creadas después reciben también las tramas de los
+    /// pares y de los servidores gRPC/UDS locales.
+    pub async fn configure_transports(&self, config: TransportConfig) -> Result<()> {
+        for (peer, transport) in &config.peers {
+            let backend = transport.build()?;
+            backend.connect().await?;
+
+            info!("🔗 Par {} alcanzable vía {}", peer, backend.name());
+            self.peers.write().await.insert(peer.clone(), backend);
+        }
+
+        let mut servers = self.transport_servers.write().await;
+
+        if let Some(listen) = &config.grpc_listen {
+            let server = transport::GrpcTransportServer::new(self.inbound.clone(), self.outbound.clone());
+            servers.push(server.start(listen)?);
+        }
+
+        if let Some(path) = &config.uds_listen {
+            #[cfg(unix)]
+            {
+                let server = transport::UdsTransportServer::new(self.inbound.clone(), self.outbound.clone());
+                servers.push(server.start(path)?);
+            }
+            #[cfg(not(unix))]
+            warn!("⚠️  Transporte UDS no disponible en esta plataforma: {}", path);
+        }
+
+        Ok(())
+    }
+
     /// Publicar evento en el fabric
     pub async fn publish(&self, subject: &str, data: &[u8]) -> Result<()> {
+        self.publish_with_headers(subject, None, data).await
+    }
+
+    /// Publicar datos con cabeceras NATS opcionales
+    pub async fn publish_with_headers(
+        &self,
+        subject: &str,
+        headers: Option<&HeaderMap>,
+        data: &[u8],
+    ) -> Result<()> {
         let connection_guard = self.connection.read().await;
         
         if let Some(connection) = connection_guard.as_ref() {
-            connection.publish(subject, data).await?;
+            connection
+                .publish_with_reply_or_headers(subject, None, headers, data)
+                .await?;
             debug!("📤 Evento publicado en {}: {} bytes", subject, data.len());
             Ok(())
         } else {
@@ -101,20 +249,98 @@ impl CognitiveFabricClient {
     }
 
     /// Publicar evento estructurado
+    ///
+    /// Los eventos con `target` se entregan en el buzón del destino en lugar
+    /// del tema general de su tipo.
     pub async fn publish_event(&self, event: &CognitiveEvent) -> Result<()> {
-        let subject = self.get_subject_for_event(&event.event_type);
-        let data = serde_json::to_vec(event)?;
-        
-        self.publish(&subject, &data).await?;
-        
-        debug!(
-            "📤 Evento {} publicado: {} -> {}",
-            event.id,
-            event.source,
-            subject
+        let subject = match &event.target {
+            Some(target) => router::inbox_for_target(target),
+            None => self.get_subject_for_event(&event.event_type),
+        };
+        let start_time = std::time::Instant::now();
+
+        let result = self.send_event(&subject, event).await;
+
+        if let Some(metrics) = self.metrics.get() {
+            let event_type = event.event_type.name();
+            match &result {
+                Ok(bytes) => metrics.record_publish(
+                    &subject,
+                    &event_type,
+                    *bytes,
+                    start_time.elapsed().as_secs_f64(),
+                ),
+                Err(_) => metrics.record_publish_error(&subject, &event_type),
+            }
+        }
+
+        result.map(|_| ())
+    }
+
+    /// Codificar y enviar un evento; devuelve el tamaño de la trama
+    async fn send_event(&self, subject: &str, event: &CognitiveEvent) -> Result<usize> {
+        let codec = self.codecs.read().await.codec_for_subject(subject);
+
+        let mut event = event.clone();
+        let trace = TraceContext::ensure(&mut event);
+
+        let span = info_span!(
+            "fabric.publish",
+            subject = %subject,
+            event_id = %event.id,
+            trace_id = %trace.trace_id_hex(),
+            span_id = %trace.span_id_hex(),
         );
+        span.set_parent(trace.otel_context());
+
+        async move {
+            let mut headers = HashMap::new();
+            headers.insert(codec::CONTENT_TYPE_HEADER.to_string(), codec.content_type().to_string());
+            headers.insert(TRACEPARENT_HEADER.to_string(), trace.to_traceparent());
+
+            let frame = TransportFrame {
+                subject: subject.to_string(),
+                headers,
+                data: codec.encode(&event)?,
+            };
+            let size = frame.data.len();
+
+            // Los pares con transporte propio se alcanzan directamente
+            let peer = match &event.target {
+                Some(target) => self.peers.read().await.get(target).cloned(),
+                None => None,
+            };
+
+            if let Some(backend) = peer {
+                backend.publish(frame).await?;
+            } else {
+                self.outbound.publish(frame.clone());
+
+                let nats_connected = self.connection.read().await.is_some();
+                if nats_connected || self.transport_servers.read().await.is_empty() {
+                    self.publish_with_headers(subject, Some(&frame.nats_headers()), &frame.data)
+                        .await?;
+                }
+            }
         
-        Ok(())
+            debug!(
+                "📤 Evento {} publicado ({:?}): {} -> {}",
+                event.id,
+                codec,
+                event.source,
+                subject
+            );
+        
+            Ok(size)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Configurar el códec usado al publicar en un tema
+    pub async fn set_subject_codec(&self, subject: &str, codec: EventCodec) {
+        self.codecs.write().await.set_subject_codec(subject, codec);
+        info!("🧬 Códec {:?} asignado a: {}", codec, subject);
     }
 
     /// Suscribirse a un tema
@@ -122,43 +348,263 @@ impl CognitiveFabricClient {
     where
         F: Fn(&[u8]) + Send + Sync + 'static,
     {
-        let connection_guard = self.connection.read().await;
-        
-        if let Some(connection) = connection_guard.as_ref() {
-            let subscription = connection.subscribe(subject).await?;
-            
-            // Procesar mensajes en background
-            let handler = Arc::new(handler);
-            tokio::spawn({
-                let handler = handler.clone();
-                let subject = subject.to_string();
-                async move {
-                    while let Some(message) = subscription.next().await {
-                        handler(&message.data);
+        self.subscribe_messages(subject, &SubscriptionOptions::default(), move |frame| {
+            handler(&frame.data)
+        })
+        .await
+    }
+
+    /// Suscribirse a eventos estructurados, negociando el códec por cabecera
+    pub async fn subscribe_events<F>(&self, subject: &str, handler: F) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        self.subscribe_filtered(subject, EventFilter::Any, handler).await
+    }
+
+    /// Suscribirse a eventos que cumplan un filtro
+    ///
+    /// El tema admite comodines NATS (`saai.>`, `saai.*.alerts`); el filtro se
+    /// evalúa tras decodificar y antes de invocar al manejador.
+    pub async fn subscribe_filtered<F>(
+        &self,
+        subject: &str,
+        filter: EventFilter,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        let options = SubscriptionOptions {
+            filter,
+            ..SubscriptionOptions::default()
+        };
+
+        self.subscribe_with_options(subject, options, handler).await
+    }
+
+    /// Suscribirse con filtro, capacidad de cola y política de desbordamiento
+    pub async fn subscribe_with_options<F>(
+        &self,
+        subject: &str,
+        options: SubscriptionOptions,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        let filter = options.filter.clone();
+        let metrics = self.metrics.clone();
+        let idempotency = options.exactly_once.then(|| self.idempotency.clone());
+        let scope = subject.to_string();
+
+        self.subscribe_messages(subject, &options, move |frame| {
+            let codec = frame.codec();
+            match codec.decode(&frame.data) {
+                Ok(event) => {
+                    if filter.matches(&frame.subject, &event) {
+                        if let Some(cache) = &idempotency {
+                            if !cache.check_and_insert(&scope, event.id) {
+                                debug!("🔁 Evento duplicado {} suprimido en {}", event.id, scope);
+                                if let Some(metrics) = metrics.get() {
+                                    metrics.record_duplicate(&scope);
+                                }
+                                return;
+                            }
+                        }
+
+                        let event_type = event.event_type.name();
+                        let trace = TraceContext::from_event(&event);
+                        let span = info_span!(
+                            "fabric.handle",
+                            subject = %frame.subject,
+                            event_id = %event.id,
+                            trace_id = %trace.map(|t| t.trace_id_hex()).unwrap_or_default(),
+                            parent_span_id = %trace.map(|t| t.span_id_hex()).unwrap_or_default(),
+                        );
+                        if let Some(trace) = trace {
+                            span.set_parent(trace.otel_context());
+                        }
+                        let _enter = span.enter();
+
+                        let start_time = std::time::Instant::now();
+                        handler(event);
+
+                        if let Some(metrics) = metrics.get() {
+                            metrics.record_consume(
+                                &frame.subject,
+                                &event_type,
+                                start_time.elapsed().as_secs_f64(),
+                            );
+                        }
                     }
-                    warn!("🔌 Suscripción a {} terminada", subject);
                 }
-            });
-            
-            // Guardar suscripción
-            self.subscriptions.write().await.insert(
-                subject.to_string(),
-                subscription,
-            );
-            
-            info!("📥 Suscrito a: {}", subject);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("No hay conexión al Cognitive Fabric"))
+                Err(e) => {
+                    if let Some(metrics) = metrics.get() {
+                        metrics.record_consume_error(&frame.subject);
+                    }
+                    warn!(
+                        "⚠️  Evento inválido en {} ({:?}): {}",
+                        frame.subject, codec, e
+                    );
+                }
+            }
+        })
+        .await
+    }
+
+    /// Suscribirse entregando la trama completa (datos y cabeceras)
+    ///
+    /// Los lectores de NATS, de los pares y de los servidores locales trasladan
+    /// las tramas a una cola acotada y otra tarea las entrega al manejador,
+    /// aplicando la política de desbordamiento cuando el consumidor no da abasto.
+    async fn subscribe_messages<F>(
+        &self,
+        subject: &str,
+        options: &SubscriptionOptions,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(&TransportFrame) + Send + Sync + 'static,
+    {
+        let connection_guard = self.connection.read().await;
+        let peers = self.peers.read().await;
+        let has_servers = !self.transport_servers.read().await.is_empty();
+
+        if connection_guard.is_none() && peers.is_empty() && !has_servers {
+            return Err(anyhow::anyhow!("No hay conexión al Cognitive Fabric"));
         }
+
+        let queue = Arc::new(SubscriptionQueue::new(
+            subject,
+            options.queue_capacity,
+            options.overflow_policy,
+        ));
+
+        // Leer mensajes NATS hacia la cola acotada
+        let subscription = match connection_guard.as_ref() {
+            Some(connection) => {
+                let subscription = Arc::new(connection.subscribe(subject).await?);
+                tokio::spawn({
+                    let subscription = subscription.clone();
+                    let queue = queue.clone();
+                    let metrics = self.metrics.clone();
+                    let subject = subject.to_string();
+                    async move {
+                        while let Some(message) = subscription.next().await {
+                            if !queue.push(TransportFrame::from_nats(&message)).await {
+                                debug!("🗑️  Mensaje descartado en {} (cola llena)", subject);
+                                if let Some(metrics) = metrics.get() {
+                                    metrics.record_subscription_queue(&queue.stats());
+                                }
+                            }
+                        }
+                        queue.close();
+                        warn!("🔌 Suscripción a {} terminada", subject);
+                    }
+                });
+                Some(subscription)
+            }
+            None => None,
+        };
+
+        // Tramas de los pares y de los servidores gRPC/UDS locales
+        for backend in peers.values() {
+            let receiver = backend.subscribe(subject).await?;
+            Self::forward_frames(subject, receiver, queue.clone());
+        }
+        Self::forward_frames(subject, self.inbound.subscribe(subject), queue.clone());
+
+        // Procesar mensajes en background
+        tokio::spawn({
+            let queue = queue.clone();
+            let metrics = self.metrics.clone();
+            async move {
+                while let Some(frame) = queue.pop().await {
+                    handler(&frame);
+
+                    if let Some(metrics) = metrics.get() {
+                        metrics.record_subscription_queue(&queue.stats());
+                    }
+                }
+            }
+        });
+
+        // Guardar suscripción
+        self.subscriptions.write().await.insert(
+            subject.to_string(),
+            ActiveSubscription { subscription, queue },
+        );
+
+        info!(
+            "📥 Suscrito a: {} (cola {} / {:?})",
+            subject, options.queue_capacity, options.overflow_policy
+        );
+        Ok(())
+    }
+
+    /// Trasladar tramas de un transporte a la cola de una suscripción
+    fn forward_frames(
+        subject: &str,
+        mut receiver: mpsc::Receiver<TransportFrame>,
+        queue: Arc<SubscriptionQueue<TransportFrame>>,
+    ) {
+        let subject = subject.to_string();
+
+        tokio::spawn(async move {
+            while let Some(frame) = receiver.recv().await {
+                if queue.is_closed() {
+                    break;
+                }
+                if !queue.push(frame).await {
+                    debug!("🗑️  Trama descartada en {} (cola llena)", subject);
+                }
+            }
+        });
+    }
+
+    /// Tiempo de ida y vuelta de un mensaje propio a través del bus
+    pub async fn ping(&self, timeout: Duration) -> Result<Duration> {
+        let subject = self.ping_subject
+            .get_or_try_init(|| async {
+                let subject = format!("saai.health.ping.{}", self.client_id);
+                let pings = self.pings.clone();
+                self.subscribe(&subject, move |data| {
+                    let Ok(id) = Uuid::from_slice(data) else { return };
+                    if let Some(reply) = pings.lock().unwrap().remove(&id) {
+                        let _ = reply.send(());
+                    }
+                })
+                .await?;
+                Ok::<_, anyhow::Error>(subject)
+            })
+            .await?;
+
+        let id = Uuid::new_v4();
+        let (reply, response) = oneshot::channel();
+        self.pings.lock().unwrap().insert(id, reply);
+
+        let start_time = Instant::now();
+        let result = match self.publish(subject, id.as_bytes()).await {
+            Ok(()) => match tokio::time::timeout(timeout, response).await {
+                Ok(Ok(())) => Ok(start_time.elapsed()),
+                _ => Err(anyhow::anyhow!("Sin respuesta al ping del Cognitive Fabric en {:?}", timeout)),
+            },
+            Err(e) => Err(e),
+        };
+        self.pings.lock().unwrap().remove(&id);
+        result
     }
 
     /// Desuscribirse de un tema
     pub async fn unsubscribe(&self, subject: &str) -> Result<()> {
         let mut subscriptions = self.subscriptions.write().await;
         
-        if let Some(subscription) = subscriptions.remove(subject) {
-            subscription.unsubscribe().await?;
+        if let Some(active) = subscriptions.remove(subject) {
+            active.queue.close();
+            if let Some(subscription) = active.subscription {
+                subscription.unsubscribe().await?;
+            }
             info!("📤 Desuscrito de: {}", subject);
         }
         
@@ -171,12 +617,25 @@ impl CognitiveFabricClient {
         
         // Cerrar todas las suscripciones
         let mut subscriptions = self.subscriptions.write().await;
-        for (subject, subscription) in subscriptions.drain() {
-            if let Err(e) = subscription.unsubscribe().await {
-                error!("❌ Error cerrando suscripción {}: {}", subject, e);
+        for (subject, active) in subscriptions.drain() {
+            active.queue.close();
+            if let Some(subscription) = active.subscription {
+                if let Err(e) = subscription.unsubscribe().await {
+                    error!("❌ Error cerrando suscripción {}: {}", subject, e);
+                }
             }
         }
         
+        // Cerrar transportes alternativos
+        for (peer, backend) in self.peers.write().await.drain() {
+            if let Err(e) = backend.shutdown().await {
+                error!("❌ Error cerrando transporte {} de {}: {}", backend.name(), peer, e);
+            }
+        }
+        for server in self.transport_servers.write().await.drain(..) {
+            server.abort();
+        }
+        
         // Cerrar conexión
         let mut connection_guard = self.connection.write().await;
         if let Some(connection) = connection_guard.take() {
@@ -187,6 +646,63 @@ impl CognitiveFabricClient {
         Ok(())
     }
 
+    /// Activar persistencia de eventos en streams duraderos
+    pub async fn enable_durable_streams(&self, config: DurableStreamConfig) -> Result<()> {
+        let streams = DurableStreams::connect(&self.nats_url, config).await?;
+        *self.streams.write().await = Some(Arc::new(streams));
+
+        info!("💾 Streams duraderos activados");
+        Ok(())
+    }
+
+    /// Re-consumir eventos históricos de un tema en un rango temporal
+    pub async fn replay<F>(
+        &self,
+        subject: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        handler: F,
+    ) -> Result<u64>
+    where
+        F: Fn(CognitiveEvent) + Send + 'static,
+    {
+        let streams = self
+            .streams
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Streams duraderos no activados"))?;
+
+        info!("⏪ Replay de {} desde {} hasta {}", subject, from, to);
+        let metrics = self.metrics.get().cloned();
+        streams.replay(subject, from, to, metrics, handler).await
+    }
+
+    /// Obtener profundidad y descartes de las colas de suscripción
+    pub async fn subscription_stats(&self) -> Vec<SubscriptionQueueStats> {
+        self.subscriptions
+            .read()
+            .await
+            .values()
+            .map(|active| active.queue.stats())
+            .collect()
+    }
+
+    /// Resumen del estado del cliente para `/api/v1/status`
+    pub async fn status(&self) -> serde_json::Value {
+        let peers: Vec<String> = self.peers.read().await.keys().cloned().collect();
+
+        serde_json::json!({
+            "client_id": self.client_id,
+            "nats_url": self.nats_url,
+            "connected": self.connection.read().await.is_some(),
+            "durable_streams": self.streams.read().await.is_some(),
+            "peers": peers,
+            "transport_servers": self.transport_servers.read().await.len(),
+            "subscriptions": self.subscription_stats().await,
+        })
+    }
+
     /// Obtener el tema NATS para un tipo de evento
     fn get_subject_for_event(&self, event_type: &EventType) -> String {
         match event_type {
@@ -205,16 +721,6 @@ impl CognitiveFabricClient {
 /// Cognitive Fabric principal del sistema
 pub struct CognitiveFabric {
     client: CognitiveFabricClient,
-    event_stats: Arc<RwLock<EventStatistics>>,
-}
-
-/// Estadísticas de eventos
-#[derive(Debug, Default)]
-pub struct EventStatistics {
-    pub total_events: u64,
-    pub events_by_type: HashMap<String, u64>,
-    pub average_latency_ms: f64,
-    pub error_count: u64,
 }
 
 impl CognitiveFabric {
@@ -222,10 +728,7 @@ impl CognitiveFabric {
     pub async fn new(nats_url: &str) -> Result<Self> {
         let client = CognitiveFabricClient::new(nats_url);
         
-        Ok(Self {
-            client,
-            event_stats: Arc::new(RwLock::new(EventStatistics::default())),
-        })
+        Ok(Self { client })
     }
 
     /// Conectar al fabric
@@ -233,21 +736,19 @@ impl CognitiveFabric {
         self.client.connect().await
     }
 
-    /// Publicar evento con estadísticas
+    /// Asociar las métricas etiquetadas del fabric
+    pub fn attach_metrics(&self, metrics: Arc<FabricMetrics>) {
+        self.client.attach_metrics(metrics)
+    }
+
+    /// Publicar evento registrando métricas por tema y tipo
     pub async fn publish_event(&self, event: CognitiveEvent) -> Result<()> {
-        let start_time = std::time::Instant::now();
-        
-        match self.client.publish_event(&event).await {
-            Ok(()) => {
-                let latency = start_time.elapsed().as_millis() as f64;
-                self.update_stats(&event, latency, false).await;
-                Ok(())
-            }
-            Err(e) => {
-                self.update_stats(&event, 0.0, true).await;
-                Err(e)
-            }
-        }
+        self.client.publish_event(&event).await
+    }
+
+    /// Publicar datos sin estructura en un tema
+    pub async fn publish(&self, subject: &str, data: &[u8]) -> Result<()> {
+        self.client.publish(subject, data).await
     }
 
     /// Suscribirse con manejo de errores
@@ -258,43 +759,102 @@ impl CognitiveFabric {
         self.client.subscribe(subject, handler).await
     }
 
-    /// Obtener estadísticas del fabric
-    pub async fn get_statistics(&self) -> EventStatistics {
-        self.event_stats.read().await.clone()
+    /// Desuscribirse de un tema
+    pub async fn unsubscribe(&self, subject: &str) -> Result<()> {
+        self.client.unsubscribe(subject).await
+    }
+
+    /// Tiempo de ida y vuelta de un mensaje propio a través del bus
+    pub async fn ping(&self, timeout: Duration) -> Result<Duration> {
+        self.client.ping(timeout).await
+    }
+
+    /// Suscribirse a eventos estructurados
+    pub async fn subscribe_events<F>(&self, subject: &str, handler: F) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        self.client.subscribe_events(subject, handler).await
+    }
+
+    /// Suscribirse a eventos que cumplan un filtro
+    pub async fn subscribe_filtered<F>(
+        &self,
+        subject: &str,
+        filter: EventFilter,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        self.client.subscribe_filtered(subject, filter, handler).await
+    }
+
+    /// Suscribirse con opciones de filtro y contrapresión
+    pub async fn subscribe_with_options<F>(
+        &self,
+        subject: &str,
+        options: SubscriptionOptions,
+        handler: F,
+    ) -> Result<()>
+    where
+        F: Fn(CognitiveEvent) + Send + Sync + 'static,
+    {
+        self.client.subscribe_with_options(subject, options, handler).await
+    }
+
+    /// Configurar el códec usado al publicar en un tema
+    pub async fn set_subject_codec(&self, subject: &str, codec: EventCodec) {
+        self.client.set_subject_codec(subject, codec).await
+    }
+
+    /// Configurar la ventana de deduplicación de las suscripciones `exactly_once`
+    pub fn set_idempotency_ttl(&self, ttl: std::time::Duration) {
+        self.client.set_idempotency_ttl(ttl)
+    }
+
+    /// Configurar transportes alternativos por par y servidores locales
+    pub async fn configure_transports(&self, config: TransportConfig) -> Result<()> {
+        self.client.configure_transports(config).await
+    }
+
+    /// Activar persistencia de eventos en streams duraderos
+    pub async fn enable_durable_streams(&self, config: DurableStreamConfig) -> Result<()> {
+        self.client.enable_durable_streams(config).await
+    }
+
+    /// Re-consumir eventos históricos (p. ej. la última hora de `saai.security.alerts`)
+    pub async fn replay<F>(
+        &self,
+        subject: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        handler: F,
+    ) -> Result<u64>
+    where
+        F: Fn(CognitiveEvent) + Send + 'static,
+    {
+        self.client.replay(subject, from, to, handler).await
+    }
+
+    /// Obtener profundidad y descartes de las colas de suscripción
+    pub async fn subscription_stats(&self) -> Vec<SubscriptionQueueStats> {
+        self.client.subscription_stats().await
     }
 
     /// Shutdown del fabric
     pub async fn shutdown(&self) -> Result<()> {
         self.client.shutdown().await
     }
+}
 
-    /// Actualizar estadísticas de eventos
-    async fn update_stats(&self, event: &CognitiveEvent, latency: f64, is_error: bool) {
-        let mut stats = self.event_stats.write().await;
-        
-        stats.total_events += 1;
-        
-        let event_type_key = format!("{:?}", event.event_type);
-        *stats.events_by_type.entry(event_type_key).or_insert(0) += 1;
-        
-        if is_error {
-            stats.error_count += 1;
-        } else {
-            // Actualizar latencia promedio (media móvil simple)
-            stats.average_latency_ms = 
-                (stats.average_latency_ms * (stats.total_events - 1) as f64 + latency) 
-                / stats.total_events as f64;
-        }
+#[async_trait]
+impl StatusProvider for CognitiveFabric {
+    fn section(&self) -> &'static str {
+        "fabric"
     }
-}
 
-impl Clone for EventStatistics {
-    fn clone(&self) -> Self {
-        Self {
-            total_events: self.total_events,
-            events_by_type: self.events_by_type.clone(),
-            average_latency_ms: self.average_latency_ms,
-            error_count: self.error_count,
-        }
+    async fn status(&self) -> serde_json::Value {
+        self.client.status().await
     }
-}
\ No newline at end of file
+}
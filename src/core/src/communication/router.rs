@@ -0,0 +1,88 @@
+//! Enrutado por destino del Cognitive Fabric
+//!
+//! Traduce el `target` de un `CognitiveEvent` a temas de buzón por
+//! instancia (`saai.inbox.SecurityCore.2`) o por grupo
+//! (`saai.inbox.SecurityCore.all`), de modo que los comandos dirigidos solo
+//! lleguen al nano-core indicado.
+
+/// Prefijo de los temas de buzón
+pub const INBOX_PREFIX: &str = "saai.inbox";
+
+/// Token de buzón compartido por todas las instancias de un nano-core
+const GROUP_TOKEN: &str = "all";
+
+/// Buzón de una instancia concreta (`SecurityCore`, 2)
+pub fn instance_inbox(core_name: &str, instance: usize) -> String {
+    format!("{}.{}.{}", INBOX_PREFIX, sanitize_token(core_name), instance)
+}
+
+/// Buzón compartido por todas las instancias de un nano-core
+pub fn group_inbox(core_name: &str) -> String {
+    format!("{}.{}.{}", INBOX_PREFIX, sanitize_token(core_name), GROUP_TOKEN)
+}
+
+/// Buzones de todas las instancias y del grupo de un nano-core (`saai.inbox.SecurityCore.*`)
+pub fn inbox_wildcard(core_name: &str) -> String {
+    format!("{}.{}.*", INBOX_PREFIX, sanitize_token(core_name))
+}
+
+/// Nano-core e instancia de un destino; sin instancia se dirige a todo el grupo
+///
+/// `SecurityCore-2` es la instancia 2; `SecurityCore` o `SecurityCore-*`,
+/// todas las instancias del grupo.
+pub fn parse_target(target: &str) -> (&str, Option<usize>) {
+    let target = target.trim();
+
+    if let Some(core_name) = target.strip_suffix("-*") {
+        return (core_name, None);
+    }
+
+    match target.rsplit_once('-') {
+        Some((core_name, instance)) if !core_name.is_empty() => match instance.parse::<usize>() {
+            Ok(instance) => (core_name, Some(instance)),
+            Err(_) => (target, None),
+        },
+        _ => (target, None),
+    }
+}
+
+/// Tema de entrega para un destino
+pub fn inbox_for_target(target: &str) -> String {
+    match parse_target(target) {
+        (core_name, Some(instance)) => instance_inbox(core_name, instance),
+        (core_name, None) => group_inbox(core_name),
+    }
+}
+
+/// Nombre de destino de una instancia, inverso de `inbox_for_target`
+pub fn target_name(core_name: &str, instance: usize) -> String {
+    format!("{}-{}", core_name, instance)
+}
+
+/// Reemplazar caracteres con significado especial en temas NATS
+fn sanitize_token(token: &str) -> String {
+    token
+        .chars()
+        .map(|c| match c {
+            '.' | '*' | '>' | ' ' => '_',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_routing() {
+        assert_eq!(inbox_for_target("SecurityCore-2"), "saai.inbox.SecurityCore.2");
+        assert_eq!(inbox_for_target("SecurityCore"), "saai.inbox.SecurityCore.all");
+        assert_eq!(inbox_for_target("SecurityCore-*"), "saai.inbox.SecurityCore.all");
+        assert_eq!(inbox_for_target("nano-core-manager"), "saai.inbox.nano-core-manager.all");
+        assert_eq!(inbox_for_target(&target_name("OSCore", 0)), instance_inbox("OSCore", 0));
+        assert_eq!(parse_target("nano-core-manager"), ("nano-core-manager", None));
+        assert_eq!(parse_target("OSCore-0"), ("OSCore", Some(0)));
+        assert_eq!(inbox_wildcard("OSCore"), "saai.inbox.OSCore.*");
+    }
+}
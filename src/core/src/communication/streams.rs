@@ -0,0 +1,169 @@
+//! Streams duraderos del Cognitive Fabric
+//!
+//! Persistencia de eventos en JetStream para que nano-cores recién
+//! iniciados o sesiones de depuración puedan re-consumir el histórico de un
+//! tema dentro de un rango temporal.
+
+use anyhow::{Result, anyhow};
+use nats::jetstream::{JetStream, StreamConfig, SubscribeOptions};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::{CognitiveEvent, EventCodec};
+use crate::metrics::FabricMetrics;
+
+/// Configuración del stream duradero
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurableStreamConfig {
+    pub name: String,
+    pub subjects: Vec<String>,
+    pub max_age_hours: u64,
+    pub max_bytes: i64,
+    /// Tiempo sin mensajes tras el cual se da por terminado un replay
+    pub replay_idle_timeout_ms: u64,
+}
+
+impl Default for DurableStreamConfig {
+    fn default() -> Self {
+        Self {
+            name: "SAAI_EVENTS".to_string(),
+            subjects: vec!["saai.>".to_string()],
+            max_age_hours: 24,
+            max_bytes: 1024 * 1024 * 1024, // 1GB
+            replay_idle_timeout_ms: 2000,
+        }
+    }
+}
+
+/// Acceso a los streams duraderos sobre JetStream
+pub struct DurableStreams {
+    jetstream: JetStream,
+    config: DurableStreamConfig,
+}
+
+impl DurableStreams {
+    /// Conectar a JetStream y garantizar que el stream existe
+    pub async fn connect(nats_url: &str, config: DurableStreamConfig) -> Result<Self> {
+        let nats_url = nats_url.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = nats::connect(nats_url.as_str())?;
+            let streams = Self {
+                jetstream: nats::jetstream::new(connection),
+                config,
+            };
+            streams.ensure_stream()?;
+            Ok(streams)
+        })
+        .await?
+    }
+
+    /// Crear el stream si no existe
+    fn ensure_stream(&self) -> Result<()> {
+        if self.jetstream.stream_info(&self.config.name).is_ok() {
+            return Ok(());
+        }
+
+        self.jetstream.add_stream(StreamConfig {
+            name: self.config.name.clone(),
+            subjects: self.config.subjects.clone(),
+            max_age: Duration::from_secs(self.config.max_age_hours * 3600),
+            max_bytes: self.config.max_bytes,
+            ..Default::default()
+        })?;
+
+        info!(
+            "💾 Stream duradero {} creado para {:?}",
+            self.config.name, self.config.subjects
+        );
+        Ok(())
+    }
+
+    /// Re-entregar los eventos de `subject` publicados entre `from` y `to`
+    ///
+    /// Devuelve el número de eventos entregados al manejador. Si se indican
+    /// métricas, el retraso del consumidor se publica durante el replay.
+    pub async fn replay<F>(
+        &self,
+        subject: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        metrics: Option<Arc<FabricMetrics>>,
+        handler: F,
+    ) -> Result<u64>
+    where
+        F: Fn(CognitiveEvent) + Send + 'static,
+    {
+        if from > to {
+            return Err(anyhow!("Rango de replay inválido: {} > {}", from, to));
+        }
+
+        let start = time::OffsetDateTime::from_unix_timestamp_nanos(
+            from.timestamp_nanos_opt().unwrap_or_default() as i128,
+        )?;
+        let end_nanos = to.timestamp_nanos_opt().unwrap_or(i64::MAX) as i128;
+        let idle_timeout = Duration::from_millis(self.config.replay_idle_timeout_ms);
+
+        let options = SubscribeOptions::bind_stream(self.config.name.clone())
+            .deliver_by_start_time(start)
+            .replay_instant()
+            .ack_none();
+
+        let jetstream = self.jetstream.clone();
+        let subject = subject.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let subscription = jetstream.subscribe_with_options(&subject, &options)?;
+            let mut delivered = 0;
+
+            loop {
+                let message = match subscription.next_timeout(idle_timeout) {
+                    Ok(message) => message,
+                    Err(e) if e.kind() == ErrorKind::TimedOut => break,
+                    Err(e) => return Err(e.into()),
+                };
+
+                let (published, pending) = match message.jetstream_message_info() {
+                    Some(info) => (info.published.unix_timestamp_nanos(), info.pending),
+                    None => continue,
+                };
+
+                if let Some(metrics) = &metrics {
+                    metrics.record_consumer_lag(&subject, pending);
+                }
+
+                if published > end_nanos {
+                    break;
+                }
+
+                let codec = EventCodec::negotiate(message.headers.as_ref());
+                match codec.decode(&message.data) {
+                    Ok(event) => {
+                        handler(event);
+                        delivered += 1;
+                    }
+                    Err(e) => warn!(
+                        "⚠️  Evento inválido en replay de {} ({:?}): {}",
+                        message.subject, codec, e
+                    ),
+                }
+
+                if pending == 0 {
+                    break;
+                }
+            }
+
+            if let Some(metrics) = &metrics {
+                metrics.record_consumer_lag(&subject, 0);
+            }
+
+            subscription.unsubscribe()?;
+            info!("⏪ Replay de {} completado: {} eventos", subject, delivered);
+            Ok(delivered)
+        })
+        .await?
+    }
+}
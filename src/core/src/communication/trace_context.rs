@@ -0,0 +1,153 @@
+//! Propagación de contexto de traza en el Cognitive Fabric
+//!
+//! Contexto compatible con W3C Trace Context (`traceparent`) transportado en
+//! las cabeceras de `CognitiveEvent`. El `trace_id` se deriva del
+//! `correlation_id` para que una cadena propuesta → votos → resultado →
+//! acción se observe como una única traza.
+
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use uuid::Uuid;
+
+use super::CognitiveEvent;
+
+/// Cabecera W3C con el contexto de traza
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Contexto de traza de un evento
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: u128,
+    pub span_id: u64,
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// Crear contexto raíz, reutilizando el `correlation_id` como traza
+    pub fn new_root(correlation_id: Option<Uuid>) -> Self {
+        let trace_id = correlation_id.unwrap_or_else(Uuid::new_v4).as_u128();
+
+        Self {
+            trace_id,
+            span_id: new_span_id(),
+            sampled: true,
+        }
+    }
+
+    /// Crear contexto hijo dentro de la misma traza
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: new_span_id(),
+            sampled: self.sampled,
+        }
+    }
+
+    /// Parsear cabecera `traceparent` (`00-<trace>-<span>-<flags>`)
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        if parts.len() != 4 || parts[0] != "00" || parts[1].len() != 32 || parts[2].len() != 16 {
+            return None;
+        }
+
+        let trace_id = u128::from_str_radix(parts[1], 16).ok()?;
+        let span_id = u64::from_str_radix(parts[2], 16).ok()?;
+        let flags = u8::from_str_radix(parts[3], 16).ok()?;
+
+        if trace_id == 0 || span_id == 0 {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            span_id,
+            sampled: flags & 0x01 == 0x01,
+        })
+    }
+
+    /// Serializar como cabecera `traceparent`
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id,
+            self.span_id,
+            if self.sampled { 1 } else { 0 }
+        )
+    }
+
+    /// Identificador de traza en hexadecimal, para campos de spans
+    pub fn trace_id_hex(&self) -> String {
+        format!("{:032x}", self.trace_id)
+    }
+
+    /// Identificador de span en hexadecimal, para campos de spans
+    pub fn span_id_hex(&self) -> String {
+        format!("{:016x}", self.span_id)
+    }
+
+    /// Contexto OpenTelemetry remoto, para colgar de él los spans de `tracing`
+    pub fn otel_context(&self) -> opentelemetry::Context {
+        let flags = if self.sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+        let span_context = SpanContext::new(
+            TraceId::from(self.trace_id),
+            SpanId::from(self.span_id),
+            flags,
+            true,
+            TraceState::default(),
+        );
+        opentelemetry::Context::new().with_remote_span_context(span_context)
+    }
+
+    /// Extraer contexto de las cabeceras de un evento
+    pub fn from_event(event: &CognitiveEvent) -> Option<Self> {
+        event
+            .headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| Self::parse(value))
+    }
+
+    /// Escribir contexto en las cabeceras de un evento
+    pub fn inject(&self, event: &mut CognitiveEvent) {
+        event
+            .headers
+            .insert(TRACEPARENT_HEADER.to_string(), self.to_traceparent());
+    }
+
+    /// Garantizar que el evento lleva contexto de traza
+    pub fn ensure(event: &mut CognitiveEvent) -> Self {
+        match Self::from_event(event) {
+            Some(context) => context,
+            None => {
+                let context = Self::new_root(event.correlation_id);
+                context.inject(event);
+                context
+            }
+        }
+    }
+}
+
+/// Generar identificador de span distinto de cero
+fn new_span_id() -> u64 {
+    loop {
+        let span_id = Uuid::new_v4().as_u128() as u64;
+        if span_id != 0 {
+            return span_id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otel_context_is_remote_parent() {
+        let trace = TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        let context = trace.otel_context();
+        let span = context.span();
+        let parent = span.span_context();
+
+        assert!(parent.is_valid() && parent.is_remote() && parent.is_sampled());
+        assert_eq!(parent.trace_id().to_string(), trace.trace_id_hex());
+        assert_eq!(parent.span_id().to_string(), trace.span_id_hex());
+    }
+}
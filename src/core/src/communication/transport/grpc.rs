@@ -0,0 +1,174 @@
+//! Transporte gRPC en streaming
+//!
+//! Cliente y servidor del servicio `FabricTransport` generado por
+//! tonic-build a partir de `proto/fabric.proto`.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use futures::Stream;
+use std::pin::Pin;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tonic::transport::{Channel, Server};
+use tonic::{Request, Response, Status};
+use tracing::{debug, error, info};
+
+use super::super::codec::proto::fabric_transport_client::FabricTransportClient;
+use super::super::codec::proto::fabric_transport_server::{FabricTransport, FabricTransportServer};
+use super::super::codec::proto::{PublishAck, SubscribeRequest};
+use super::{FabricBackend, TransportFrame, TransportHub, FRAME_CHANNEL_CAPACITY};
+
+/// Backend gRPC hacia un par remoto
+pub struct GrpcBackend {
+    endpoint: String,
+    client: RwLock<Option<FabricTransportClient<Channel>>>,
+}
+
+impl GrpcBackend {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            client: RwLock::new(None),
+        }
+    }
+
+    async fn client(&self) -> Result<FabricTransportClient<Channel>> {
+        self.client
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow!("Backend gRPC {} no conectado", self.endpoint))
+    }
+}
+
+#[async_trait]
+impl FabricBackend for GrpcBackend {
+    fn name(&self) -> &'static str {
+        "grpc"
+    }
+
+    async fn connect(&self) -> Result<()> {
+        let client = FabricTransportClient::connect(self.endpoint.clone()).await?;
+        *self.client.write().await = Some(client);
+
+        info!("✅ Backend gRPC conectado: {}", self.endpoint);
+        Ok(())
+    }
+
+    async fn publish(&self, frame: TransportFrame) -> Result<()> {
+        let subject = frame.subject.clone();
+        let size = frame.data.len();
+
+        let ack = self.client().await?.publish(frame).await?.into_inner();
+        if !ack.accepted {
+            return Err(anyhow!("Trama rechazada por {}: {}", self.endpoint, subject));
+        }
+
+        debug!("📤 Trama gRPC enviada a {}: {} bytes", subject, size);
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<mpsc::Receiver<TransportFrame>> {
+        let mut stream = self
+            .client()
+            .await?
+            .subscribe(SubscribeRequest {
+                subject: subject.to_string(),
+            })
+            .await?
+            .into_inner();
+
+        let (tx, rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+        let endpoint = self.endpoint.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match stream.message().await {
+                    Ok(Some(frame)) => {
+                        if tx.send(frame).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("❌ Stream gRPC de {} interrumpido: {}", endpoint, e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.client.write().await.take();
+        Ok(())
+    }
+}
+
+/// Servidor gRPC que expone el fabric local a pares remotos
+#[derive(Clone)]
+pub struct GrpcTransportServer {
+    /// Tramas publicadas por pares remotos hacia las suscripciones locales
+    inbound: TransportHub,
+    /// Tramas publicadas localmente hacia los suscriptores remotos
+    outbound: TransportHub,
+}
+
+impl GrpcTransportServer {
+    pub fn new(inbound: TransportHub, outbound: TransportHub) -> Self {
+        Self { inbound, outbound }
+    }
+
+    /// Iniciar el servidor en background
+    pub fn start(self, listen: &str) -> Result<JoinHandle<()>> {
+        let addr = listen.parse()?;
+
+        let handle = tokio::spawn(async move {
+            info!("📡 Transporte gRPC escuchando en {}", addr);
+            if let Err(e) = Server::builder()
+                .add_service(FabricTransportServer::new(self))
+                .serve(addr)
+                .await
+            {
+                error!("❌ Servidor gRPC del fabric detenido: {}", e);
+            }
+        });
+
+        Ok(handle)
+    }
+}
+
+type FrameStream = Pin<Box<dyn Stream<Item = std::result::Result<TransportFrame, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl FabricTransport for GrpcTransportServer {
+    async fn publish(
+        &self,
+        request: Request<TransportFrame>,
+    ) -> std::result::Result<Response<PublishAck>, Status> {
+        self.inbound.publish(request.into_inner());
+        Ok(Response::new(PublishAck { accepted: true }))
+    }
+
+    type SubscribeStream = FrameStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> std::result::Result<Response<Self::SubscribeStream>, Status> {
+        let subject = request.into_inner().subject;
+        if subject.is_empty() {
+            return Err(Status::invalid_argument("Tema de suscripción vacío"));
+        }
+
+        let receiver = self.outbound.subscribe(&subject);
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|frame| (Ok(frame), receiver))
+        });
+
+        debug!("📥 Par remoto suscrito vía gRPC a {}", subject);
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
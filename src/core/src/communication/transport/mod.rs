@@ -0,0 +1,174 @@
+//! Transportes del Cognitive Fabric
+//!
+//! Abstracción `FabricBackend` sobre la que viajan las tramas del fabric,
+//! con implementaciones NATS, gRPC en streaming y sockets Unix para
+//! nano-cores del mismo host. El transporte se configura por par.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use nats::asynk::Message;
+use nats::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tracing::warn;
+
+use super::codec::CONTENT_TYPE_HEADER;
+use super::{subject_matches, EventCodec};
+
+pub mod grpc;
+pub mod nats_backend;
+#[cfg(unix)]
+pub mod uds;
+
+pub use super::codec::proto::TransportFrame;
+pub use grpc::{GrpcBackend, GrpcTransportServer};
+pub use nats_backend::NatsBackend;
+#[cfg(unix)]
+pub use uds::{UdsBackend, UdsTransportServer};
+
+/// Capacidad de los canales internos de tramas
+const FRAME_CHANNEL_CAPACITY: usize = 1024;
+
+/// Backend de transporte del fabric
+#[async_trait]
+pub trait FabricBackend: Send + Sync {
+    /// Nombre del transporte para logs
+    fn name(&self) -> &'static str;
+
+    /// Establecer conexión con el par
+    async fn connect(&self) -> Result<()>;
+
+    /// Enviar una trama al par
+    async fn publish(&self, frame: TransportFrame) -> Result<()>;
+
+    /// Recibir del par las tramas que coincidan con `subject`
+    async fn subscribe(&self, subject: &str) -> Result<mpsc::Receiver<TransportFrame>>;
+
+    /// Cerrar la conexión
+    async fn shutdown(&self) -> Result<()>;
+}
+
+/// Transporte usado para alcanzar un par
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PeerTransport {
+    Nats { url: String },
+    Grpc { endpoint: String },
+    Uds { path: String },
+}
+
+impl PeerTransport {
+    /// Construir el backend correspondiente
+    pub fn build(&self) -> Result<Arc<dyn FabricBackend>> {
+        match self {
+            PeerTransport::Nats { url } => Ok(Arc::new(NatsBackend::new(url))),
+            PeerTransport::Grpc { endpoint } => Ok(Arc::new(GrpcBackend::new(endpoint))),
+            #[cfg(unix)]
+            PeerTransport::Uds { path } => Ok(Arc::new(UdsBackend::new(path))),
+            #[cfg(not(unix))]
+            PeerTransport::Uds { path } => Err(anyhow::anyhow!(
+                "Transporte UDS no disponible en esta plataforma: {}",
+                path
+            )),
+        }
+    }
+}
+
+/// Configuración de transportes del fabric
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransportConfig {
+    /// Transporte por par, indexado por el `target` de los eventos
+    pub peers: HashMap<String, PeerTransport>,
+    /// Dirección de escucha gRPC (p. ej. `0.0.0.0:50051`)
+    pub grpc_listen: Option<String>,
+    /// Ruta del socket Unix de escucha
+    pub uds_listen: Option<String>,
+}
+
+/// Concentrador de tramas entre los servidores locales y el cliente
+#[derive(Clone)]
+pub struct TransportHub {
+    sender: broadcast::Sender<TransportFrame>,
+}
+
+impl TransportHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(FRAME_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Difundir trama a los suscriptores del concentrador
+    pub fn publish(&self, frame: TransportFrame) {
+        // Sin suscriptores la trama simplemente se descarta
+        let _ = self.sender.send(frame);
+    }
+
+    /// Recibir las tramas cuyo tema coincida con `subject`
+    pub fn subscribe(&self, subject: &str) -> mpsc::Receiver<TransportFrame> {
+        let (tx, rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+        let mut receiver = self.sender.subscribe();
+        let subject = subject.to_string();
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(frame) => {
+                        if subject_matches(&subject, &frame.subject) && tx.send(frame).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("⚠️  Suscriptor de {} retrasado: {} tramas perdidas", subject, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+impl Default for TransportHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportFrame {
+    /// Crear trama a partir de un mensaje NATS
+    pub fn from_nats(message: &Message) -> Self {
+        let headers = message
+            .headers
+            .as_ref()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|(key, values)| {
+                        values.iter().next().map(|value| (key.clone(), value.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            subject: message.subject.clone(),
+            headers,
+            data: message.data.clone(),
+        }
+    }
+
+    /// Cabeceras en formato NATS
+    pub fn nats_headers(&self) -> HeaderMap {
+        self.headers.iter().collect()
+    }
+
+    /// Códec anunciado por la trama
+    pub fn codec(&self) -> EventCodec {
+        self.headers
+            .get(CONTENT_TYPE_HEADER)
+            .and_then(|value| EventCodec::from_content_type(value))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,85 @@
+//! Transporte NATS
+//!
+//! Backend para pares alcanzables en un clúster NATS distinto del
+//! principal del cliente.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use nats::asynk::Connection;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info};
+
+use super::{FabricBackend, TransportFrame, FRAME_CHANNEL_CAPACITY};
+
+/// Backend NATS
+pub struct NatsBackend {
+    url: String,
+    connection: RwLock<Option<Connection>>,
+}
+
+impl NatsBackend {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            connection: RwLock::new(None),
+        }
+    }
+
+    async fn connection(&self) -> Result<Connection> {
+        self.connection
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow!("Backend NATS {} no conectado", self.url))
+    }
+}
+
+#[async_trait]
+impl FabricBackend for NatsBackend {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn connect(&self) -> Result<()> {
+        let connection = nats::asynk::connect(&self.url).await?;
+        *self.connection.write().await = Some(connection);
+
+        info!("✅ Backend NATS conectado: {}", self.url);
+        Ok(())
+    }
+
+    async fn publish(&self, frame: TransportFrame) -> Result<()> {
+        let connection = self.connection().await?;
+        let headers = frame.nats_headers();
+
+        connection
+            .publish_with_reply_or_headers(&frame.subject, None, Some(&headers), &frame.data)
+            .await?;
+
+        debug!("📤 Trama NATS enviada a {}: {} bytes", frame.subject, frame.data.len());
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<mpsc::Receiver<TransportFrame>> {
+        let subscription = self.connection().await?.subscribe(subject).await?;
+        let (tx, rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+
+        tokio::spawn(async move {
+            while let Some(message) = subscription.next().await {
+                if tx.send(TransportFrame::from_nats(&message)).await.is_err() {
+                    break;
+                }
+            }
+            let _ = subscription.unsubscribe().await;
+        });
+
+        Ok(rx)
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        if let Some(connection) = self.connection.write().await.take() {
+            connection.close().await?;
+        }
+        Ok(())
+    }
+}
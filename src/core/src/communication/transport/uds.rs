@@ -0,0 +1,195 @@
+//! Transporte por sockets Unix
+//!
+//! Canal local para nano-cores del mismo host. Cada conexión comienza con
+//! un `SubscribeRequest`: con tema vacío la conexión solo publica; con tema
+//! el servidor le reenvía las tramas que coincidan.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use prost::Message as ProstMessage;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use super::super::codec::proto::SubscribeRequest;
+use super::{FabricBackend, TransportFrame, TransportHub, FRAME_CHANNEL_CAPACITY};
+
+/// Tamaño máximo de trama aceptado (16MB)
+const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Escribir mensaje con prefijo de longitud
+async fn write_frame<M: ProstMessage>(stream: &mut UnixStream, message: &M) -> Result<()> {
+    let data = message.encode_to_vec();
+    stream.write_u32(data.len() as u32).await?;
+    stream.write_all(&data).await?;
+    Ok(())
+}
+
+/// Leer mensaje con prefijo de longitud; `None` al cerrar el par
+async fn read_frame<M: ProstMessage + Default>(stream: &mut UnixStream) -> Result<Option<M>> {
+    let len = match stream.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    if len > MAX_FRAME_SIZE {
+        return Err(anyhow!("Trama UDS demasiado grande: {} bytes", len));
+    }
+
+    let mut buffer = vec![0u8; len];
+    stream.read_exact(&mut buffer).await?;
+    Ok(Some(M::decode(buffer.as_slice())?))
+}
+
+/// Backend UDS hacia un nano-core local
+pub struct UdsBackend {
+    path: String,
+    writer: Mutex<Option<UnixStream>>,
+}
+
+impl UdsBackend {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            writer: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl FabricBackend for UdsBackend {
+    fn name(&self) -> &'static str {
+        "uds"
+    }
+
+    async fn connect(&self) -> Result<()> {
+        let mut stream = UnixStream::connect(&self.path).await?;
+        write_frame(&mut stream, &SubscribeRequest::default()).await?;
+        *self.writer.lock().await = Some(stream);
+
+        info!("✅ Backend UDS conectado: {}", self.path);
+        Ok(())
+    }
+
+    async fn publish(&self, frame: TransportFrame) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        let stream = writer
+            .as_mut()
+            .ok_or_else(|| anyhow!("Backend UDS {} no conectado", self.path))?;
+
+        write_frame(stream, &frame).await?;
+        debug!("📤 Trama UDS enviada a {}: {} bytes", frame.subject, frame.data.len());
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<mpsc::Receiver<TransportFrame>> {
+        let mut stream = UnixStream::connect(&self.path).await?;
+        write_frame(
+            &mut stream,
+            &SubscribeRequest {
+                subject: subject.to_string(),
+            },
+        )
+        .await?;
+
+        let (tx, rx) = mpsc::channel(FRAME_CHANNEL_CAPACITY);
+        let path = self.path.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match read_frame::<TransportFrame>(&mut stream).await {
+                    Ok(Some(frame)) => {
+                        if tx.send(frame).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("❌ Conexión UDS {} interrumpida: {}", path, e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        if let Some(mut stream) = self.writer.lock().await.take() {
+            stream.shutdown().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Servidor UDS que expone el fabric local a nano-cores del host
+#[derive(Clone)]
+pub struct UdsTransportServer {
+    inbound: TransportHub,
+    outbound: TransportHub,
+}
+
+impl UdsTransportServer {
+    pub fn new(inbound: TransportHub, outbound: TransportHub) -> Self {
+        Self { inbound, outbound }
+    }
+
+    /// Iniciar el servidor en background
+    pub fn start(self, path: &str) -> Result<JoinHandle<()>> {
+        // Eliminar socket huérfano de una ejecución anterior
+        if Path::new(path).exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        info!("📡 Transporte UDS escuchando en {}", path);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let server = self.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.handle_connection(stream).await {
+                                warn!("⚠️  Conexión UDS cerrada con error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!("❌ Error aceptando conexión UDS: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(handle)
+    }
+
+    /// Atender una conexión según su solicitud inicial
+    async fn handle_connection(&self, mut stream: UnixStream) -> Result<()> {
+        let request = match read_frame::<SubscribeRequest>(&mut stream).await? {
+            Some(request) => request,
+            None => return Ok(()),
+        };
+
+        if request.subject.is_empty() {
+            while let Some(frame) = read_frame::<TransportFrame>(&mut stream).await? {
+                self.inbound.publish(frame);
+            }
+        } else {
+            debug!("📥 Nano-core local suscrito vía UDS a {}", request.subject);
+            let mut receiver = self.outbound.subscribe(&request.subject);
+            while let Some(frame) = receiver.recv().await {
+                write_frame(&mut stream, &frame).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! Detección del entorno de ejecución
+//!
+//! Contenedor (Docker, Podman, LXC, Kubernetes) a partir de los marcadores
+//! del runtime y de `/proc/1/cgroup`, máquina virtual a partir de DMI y del
+//! flag `hypervisor` de la CPU, y límites de memoria y CPU del cgroup, que
+//! `optimize_for_hardware` respeta en lugar de los del host.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Límite v1 que el kernel usa como "sin límite" (cercano a `i64::MAX`)
+const CGROUP_V1_UNLIMITED: u64 = 1 << 60;
+
+/// Entorno en el que corre SAAI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeEnvironment {
+    /// Runtime de contenedor (`docker`, `podman`, `lxc`, `containerd`...)
+    pub container: Option<String>,
+    pub kubernetes: bool,
+    /// Hipervisor (`kvm`, `vmware`, `virtualbox`, `hyperv`, `xen`...) o `unknown`
+    pub virtualization: Option<String>,
+    /// Límite de memoria del cgroup, en bytes
+    pub memory_limit: Option<u64>,
+    /// CPUs asignadas por la cuota del cgroup (`cpu.max`)
+    pub cpu_limit: Option<f64>,
+}
+
+impl RuntimeEnvironment {
+    /// Detectar el entorno actual; fuera de Linux solo se informa lo que da el sistema
+    pub fn detect() -> Self {
+        if cfg!(target_os = "linux") {
+            Self::detect_at(Path::new("/"), std::env::var_os("KUBERNETES_SERVICE_HOST").is_some())
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Detección sobre un sistema de archivos montado en `root`
+    fn detect_at(root: &Path, kubernetes_env: bool) -> Self {
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).ok();
+        let cgroup = read("proc/1/cgroup").unwrap_or_default();
+
+        let container = if root.join(".dockerenv").exists() {
+            Some("docker".to_string())
+        } else if root.join("run/.containerenv").exists() {
+            Some("podman".to_string())
+        } else if let Some(runtime) = read("run/systemd/container").map(|c| c.trim().to_string()).filter(|c| !c.is_empty()) {
+            Some(runtime)
+        } else {
+            ["docker", "libpod", "lxc", "containerd", "kubepods"]
+                .into_iter()
+                .find(|marker| cgroup.contains(marker))
+                .map(|marker| match marker {
+                    "libpod" => "podman",
+                    "kubepods" => "containerd",
+                    other => other,
+                }.to_string())
+        };
+
+        let kubernetes = kubernetes_env || cgroup.contains("kubepods");
+
+        let dmi = [read("sys/class/dmi/id/sys_vendor"), read("sys/class/dmi/id/product_name")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let virtualization = hypervisor_from_dmi(&dmi).map(str::to_string).or_else(|| {
+            read("proc/cpuinfo")
+                .filter(|cpuinfo| cpuinfo.lines().any(|line| line.starts_with("flags") && line.split_whitespace().any(|f| f == "hypervisor")))
+                .map(|_| "unknown".to_string())
+        });
+
+        let memory_limit = read("sys/fs/cgroup/memory.max")
+            .and_then(|max| max.trim().parse::<u64>().ok())
+            .or_else(|| {
+                read("sys/fs/cgroup/memory/memory.limit_in_bytes")
+                    .and_then(|limit| limit.trim().parse::<u64>().ok())
+                    .filter(|limit| *limit < CGROUP_V1_UNLIMITED)
+            });
+
+        let cpu_limit = read("sys/fs/cgroup/cpu.max")
+            .and_then(|max| {
+                let mut fields = max.split_whitespace();
+                let quota = fields.next()?.parse::<f64>().ok()?;
+                let period = fields.next()?.parse::<f64>().ok()?;
+                Some(quota / period)
+            })
+            .or_else(|| {
+                let quota = read("sys/fs/cgroup/cpu/cpu.cfs_quota_us")?.trim().parse::<i64>().ok().filter(|q| *q > 0)?;
+                let period = read("sys/fs/cgroup/cpu/cpu.cfs_period_us")?.trim().parse::<i64>().ok().filter(|p| *p > 0)?;
+                Some(quota as f64 / period as f64)
+            });
+
+        Self {
+            container,
+            kubernetes,
+            virtualization,
+            memory_limit,
+            cpu_limit,
+        }
+    }
+
+    pub fn is_container(&self) -> bool {
+        self.container.is_some() || self.kubernetes
+    }
+
+    /// CPUs utilizables: las del host acotadas por la cuota del cgroup
+    pub fn effective_cpus(&self, host_cpus: usize) -> usize {
+        match self.cpu_limit {
+            Some(limit) => (limit.ceil() as usize).clamp(1, host_cpus.max(1)),
+            None => host_cpus,
+        }
+    }
+
+    /// Memoria utilizable: la disponible acotada por el límite del cgroup
+    pub fn effective_memory(&self, available: u64) -> u64 {
+        self.memory_limit.map_or(available, |limit| available.min(limit))
+    }
+}
+
+fn hypervisor_from_dmi(dmi: &str) -> Option<&'static str> {
+    let dmi = dmi.to_lowercase();
+    [
+        ("kvm", "kvm"),
+        ("qemu", "qemu"),
+        ("vmware", "vmware"),
+        ("virtualbox", "virtualbox"),
+        ("innotek", "virtualbox"),
+        ("xen", "xen"),
+        ("amazon ec2", "amazon"),
+        ("google compute engine", "google"),
+        ("virtual machine", "hyperv"),
+    ]
+    .into_iter()
+    .find(|(marker, _)| dmi.contains(marker))
+    .map(|(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect_container_limits() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        assert_eq!(RuntimeEnvironment::detect_at(root.path(), false), RuntimeEnvironment::default());
+
+        write("proc/1/cgroup", "0::/kubepods/burstable/pod1234/abcd\n");
+        write("sys/fs/cgroup/memory.max", "536870912\n");
+        write("sys/fs/cgroup/cpu.max", "150000 100000\n");
+        write("sys/class/dmi/id/sys_vendor", "QEMU\n");
+
+        let environment = RuntimeEnvironment::detect_at(root.path(), false);
+        assert_eq!(environment.container.as_deref(), Some("containerd"));
+        assert!(environment.kubernetes);
+        assert_eq!(environment.virtualization.as_deref(), Some("qemu"));
+        assert_eq!(environment.memory_limit, Some(536870912));
+        assert_eq!(environment.effective_cpus(8), 2);
+        assert_eq!(environment.effective_memory(8 << 30), 536870912);
+
+        // Sin límite en cgroup v2
+        write("sys/fs/cgroup/memory.max", "max\n");
+        write("sys/fs/cgroup/cpu.max", "max 100000\n");
+        write(".dockerenv", "");
+        let environment = RuntimeEnvironment::detect_at(root.path(), false);
+        assert_eq!(environment.container.as_deref(), Some("docker"));
+        assert_eq!(environment.memory_limit, None);
+        assert_eq!(environment.effective_cpus(8), 8);
+    }
+}
@@ -0,0 +1,103 @@
+//! Formatos del archivo de configuración
+//!
+//! TOML es el formato nativo; YAML y JSON se admiten para equipos que
+//! gestionan manifiestos. El formato se deduce de la extensión salvo que se
+//! indique explícitamente.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Formato de serialización de `CoreConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    #[default]
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Deducir el formato de la extensión; TOML si no se reconoce
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Interpretar el contenido como árbol JSON genérico
+    pub fn parse(&self, content: &str) -> Result<Value> {
+        Ok(match self {
+            Self::Toml => toml::from_str(content)?,
+            Self::Yaml => serde_yaml::from_str(content)?,
+            Self::Json => serde_json::from_str(content)?,
+        })
+    }
+
+    /// Serializar un valor en este formato
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            Self::Toml => toml::to_string_pretty(value)?,
+            Self::Yaml => serde_yaml::to_string(value)?,
+            Self::Json => serde_json::to_string_pretty(value)?,
+        })
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "json" => Ok(Self::Json),
+            other => Err(anyhow!("Formato de configuración no soportado: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+            Self::Json => "json",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CoreConfig;
+
+    #[tokio::test]
+    async fn test_formats_round_trip() {
+        assert_eq!(ConfigFormat::from_path("config/core.yml"), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path("config/core.JSON"), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path("config/core"), ConfigFormat::Toml);
+        assert!("ini".parse::<ConfigFormat>().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = CoreConfig::default();
+        config.consensus.replica_count = 5;
+
+        for name in ["core.toml", "core.yaml", "core.json"] {
+            let path = dir.path().join(name);
+            config.save(&path).await.unwrap();
+
+            let loaded = CoreConfig::read_file(&path).await.unwrap();
+            assert_eq!(loaded.consensus.replica_count, 5);
+        }
+
+        let yaml = std::fs::read_to_string(dir.path().join("core.yaml")).unwrap();
+        assert!(yaml.contains("replica_count: 5"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Sincronización GitOps
+//!
+//! Mantiene un checkout superficial de un repositorio Git con la
+//! configuración, lo actualiza periódicamente y aplica el archivo indicado
+//! vía `ConfigManager`, registrando el commit en cada `ConfigVersion`.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::ConfigManager;
+use crate::communication::CognitiveFabric;
+
+/// Origen GitOps de la configuración
+#[derive(Clone)]
+pub struct GitSyncOptions {
+    pub url: String,
+    pub branch: String,
+    /// Ruta del archivo de configuración dentro del repositorio
+    pub path: String,
+    pub interval_ms: u64,
+    /// Fabric donde publicar los `ConfigChange`
+    pub fabric: Option<Arc<CognitiveFabric>>,
+}
+
+impl GitSyncOptions {
+    pub fn new(url: &str, branch: &str, path: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            branch: branch.to_string(),
+            path: path.to_string(),
+            interval_ms: 60_000,
+            fabric: None,
+        }
+    }
+}
+
+/// Actualizar el checkout a la punta de `branch` y devolver su commit
+pub async fn fetch(url: &str, branch: &str, checkout: &Path) -> Result<String> {
+    let same_remote = checkout.join(".git").exists()
+        && git(checkout, &["remote", "get-url", "origin"]).await.ok().as_deref() == Some(url);
+
+    if same_remote {
+        git(checkout, &["fetch", "--depth", "1", "origin", branch]).await?;
+        git(checkout, &["reset", "--hard", "FETCH_HEAD"]).await?;
+    } else {
+        if checkout.exists() {
+            tokio::fs::remove_dir_all(checkout).await?;
+        }
+        if let Some(parent) = checkout.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let target = checkout.to_string_lossy();
+        git(
+            Path::new("."),
+            &["clone", "--depth", "1", "--single-branch", "--branch", branch, url, &target],
+        )
+        .await?;
+    }
+
+    git(checkout, &["rev-parse", "HEAD"]).await
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output().await?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} falló: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Iniciar sincronización periódica en background
+///
+/// El fetch se hace sin bloquear el gestor; solo la aplicación del cambio
+/// toma el lock de escritura.
+pub fn start_git_sync(manager: Arc<RwLock<ConfigManager>>, options: GitSyncOptions) -> JoinHandle<()> {
+    info!(
+        "🔀 Sincronización GitOps desde {} ({}) cada {}ms",
+        options.url, options.branch, options.interval_ms
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(options.interval_ms.max(1)));
+
+        loop {
+            interval.tick().await;
+
+            let checkout: PathBuf = manager.read().await.gitops_dir();
+            let commit = match fetch(&options.url, &options.branch, &checkout).await {
+                Ok(commit) => commit,
+                Err(e) => {
+                    warn!("⚠️  Error sincronizando configuración desde Git: {}", e);
+                    continue;
+                }
+            };
+
+            let result = manager
+                .write()
+                .await
+                .apply_git_checkout(&checkout.join(&options.path), &commit)
+                .await;
+
+            match result {
+                Ok(Some(change)) => {
+                    if let Some(fabric) = &options.fabric {
+                        if let Err(e) = change.publish(fabric).await {
+                            warn!("⚠️  No se pudo publicar el cambio de configuración: {}", e);
+                        }
+                    }
+                }
+                Ok(None) => debug!("🔀 Configuración al día con el commit {}", commit),
+                Err(e) => warn!("⚠️  Configuración del commit {} rechazada: {}", commit, e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CoreConfig;
+
+    #[tokio::test]
+    async fn test_sync_from_git_records_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote = dir.path().join("remote");
+        std::fs::create_dir_all(remote.join("config")).unwrap();
+
+        let mut remote_config = CoreConfig::default();
+        remote_config.consensus.replica_count = 5;
+        remote_config.save(remote.join("config/core.toml")).await.unwrap();
+
+        for args in [
+            vec!["init", "-q", "-b", "main"],
+            vec!["add", "."],
+            vec!["-c", "user.name=saai", "-c", "user.email=saai@localhost", "commit", "-q", "-m", "config"],
+        ] {
+            git(&remote, &args).await.unwrap();
+        }
+        let head = git(&remote, &["rev-parse", "HEAD"]).await.unwrap();
+
+        let local = dir.path().join("local/core.toml");
+        let mut manager = ConfigManager::new(local.to_str().unwrap()).await.unwrap();
+        let change = manager
+            .sync_from_git(remote.to_str().unwrap(), "main", "config/core.toml")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(change.source, "git");
+        assert_eq!(manager.get_config().consensus.replica_count, 5);
+        assert_eq!(manager.get_version_history()[0].commit.as_deref(), Some(head.as_str()));
+
+        // Sin commits nuevos no hay cambios
+        assert!(manager
+            .sync_from_git(remote.to_str().unwrap(), "main", "config/core.toml")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,71 @@
+//! Detección de hardware
+//!
+//! Memoria disponible del sistema vía sysinfo en Linux, macOS y Windows,
+//! usada por `optimize_for_hardware` y el informe de validación.
+
+use anyhow::{anyhow, Result};
+use sysinfo::{System, SystemExt};
+
+/// Memoria disponible en bytes
+///
+/// Si el sistema no informa la memoria disponible se usa la libre.
+pub(crate) fn available_memory() -> Result<u64> {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    match system.available_memory() {
+        0 => match system.free_memory() {
+            0 => Err(anyhow!("No se pudo obtener memoria disponible")),
+            free => Ok(free),
+        },
+        available => Ok(available),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_memory() -> u64 {
+        let mut system = System::new();
+        system.refresh_memory();
+        system.total_memory()
+    }
+
+    #[test]
+    fn test_available_memory_within_total() {
+        let available = available_memory().unwrap();
+        assert!(available > 0);
+        assert!(available <= total_memory());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_available_memory_linux() {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap();
+        let total_kb: u64 = meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix("MemTotal:"))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|kb| kb.parse().ok())
+            .unwrap();
+
+        // En contenedores el límite del cgroup puede dejarla por debajo de MemAvailable
+        assert!(available_memory().unwrap() <= total_kb * 1024);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_available_memory_macos() {
+        // Ya no se asumen 8GB fijos
+        assert_ne!(available_memory().unwrap(), 8 * 1024 * 1024 * 1024);
+        assert!(total_memory() >= 1024 * 1024 * 1024);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_available_memory_windows() {
+        assert_ne!(available_memory().unwrap(), 8 * 1024 * 1024 * 1024);
+        assert!(total_memory() >= 1024 * 1024 * 1024);
+    }
+}
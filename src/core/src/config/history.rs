@@ -0,0 +1,146 @@
+//! Historial persistente de versiones
+//!
+//! Cada `ConfigVersion` se guarda como JSON en `config/history/` junto al
+//! archivo de configuración, con un límite de versiones retenidas, para que
+//! `rollback()` sobreviva a reinicios del proceso.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tracing::{debug, warn};
+
+use super::ConfigVersion;
+
+/// Versiones retenidas por defecto
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Almacén de versiones en disco
+#[derive(Debug, Clone)]
+pub struct VersionStore {
+    dir: PathBuf,
+    max_versions: usize,
+}
+
+impl VersionStore {
+    pub fn new<P: AsRef<Path>>(dir: P, max_versions: usize) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_versions: max_versions.max(1),
+        }
+    }
+
+    /// Almacén `history/` junto al archivo de configuración
+    pub fn for_config<P: AsRef<Path>>(config_path: P) -> Self {
+        let dir = match config_path.as_ref().parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.join("history"),
+            _ => PathBuf::from("history"),
+        };
+
+        Self::new(dir, DEFAULT_HISTORY_LIMIT)
+    }
+
+    pub fn max_versions(&self) -> usize {
+        self.max_versions
+    }
+
+    /// Cargar las versiones guardadas, de la más antigua a la más reciente
+    pub async fn load(&self) -> Result<Vec<ConfigVersion>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        let mut entries = fs::read_dir(&self.dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+
+            match read_version(&path).await {
+                Ok(version) => versions.push(version),
+                Err(e) => warn!("⚠️  Versión de configuración ilegible {}: {}", path.display(), e),
+            }
+        }
+
+        versions.sort_by_key(|version| version.timestamp);
+        debug!("📚 {} versiones de configuración cargadas", versions.len());
+        Ok(versions)
+    }
+
+    /// Guardar una versión y podar las que excedan el límite
+    pub async fn save(&self, version: &ConfigVersion) -> Result<()> {
+        fs::create_dir_all(&self.dir).await?;
+
+        let path = self.version_path(&version.version);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&redacted(version)?)?).await?;
+        fs::rename(&tmp, &path).await?;
+
+        self.prune().await
+    }
+
+    async fn prune(&self) -> Result<()> {
+        let versions = self.load().await?;
+        let excess = versions.len().saturating_sub(self.max_versions);
+
+        for version in &versions[..excess] {
+            fs::remove_file(self.version_path(&version.version)).await?;
+            debug!("🗑️  Versión de configuración podada: {}", version.version);
+        }
+
+        Ok(())
+    }
+
+    fn version_path(&self, version: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", version))
+    }
+}
+
+/// Versión serializada con las referencias en lugar de los secretos
+fn redacted(version: &ConfigVersion) -> Result<serde_json::Value> {
+    let mut document = serde_json::to_value(version)?;
+    document["config"] = version.config.redacted()?;
+    Ok(document)
+}
+
+async fn read_version(path: &Path) -> Result<ConfigVersion> {
+    let bytes = fs::read(path).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigManager, CoreConfig};
+
+    #[tokio::test]
+    async fn test_history_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        let path = path.to_str().unwrap();
+
+        let mut manager = ConfigManager::new(path).await.unwrap();
+        for replicas in [4, 5, 6] {
+            let mut config = CoreConfig::default();
+            config.consensus.replica_count = replicas;
+            manager.update_config(config).await.unwrap();
+        }
+        assert_eq!(manager.get_version_history().len(), 3);
+
+        // Nuevo proceso: el historial se recupera de disco
+        let mut restarted = ConfigManager::new(path).await.unwrap();
+        let history = restarted.get_version_history().to_vec();
+        assert_eq!(history.len(), 3);
+        assert_eq!(restarted.get_config().consensus.replica_count, 6);
+
+        restarted.rollback(&history[0].version).await.unwrap();
+        assert_eq!(restarted.get_config().consensus.replica_count, 3);
+
+        // Límite de retención
+        let store = VersionStore::new(dir.path().join("history"), 2);
+        store.save(&history[2]).await.unwrap();
+        assert_eq!(store.load().await.unwrap().len(), 2);
+    }
+}
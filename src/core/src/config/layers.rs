@@ -0,0 +1,303 @@
+//! Capas de configuración
+//!
+//! `CoreConfig::load` combina, de menor a mayor precedencia: valores por
+//! defecto, archivo, perfil seleccionado (`--profile staging`), variables de
+//! entorno (`SAAI__CONSENSUS__REPLICA_COUNT`) y overrides de CLI
+//! (`--set consensus.replica_count=5`).
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::path::Path;
+use tracing::warn;
+
+use super::{profiles, ConfigFormat, CoreConfig};
+
+/// Overrides aplicados sobre el archivo de configuración
+#[derive(Debug, Clone)]
+pub struct ConfigOverrides {
+    /// Prefijo de las variables de entorno (`SAAI` → `SAAI__CLAVE`)
+    pub env_prefix: String,
+    /// Pares `ruta.con.puntos` → valor, de mayor precedencia
+    pub cli: Vec<(String, String)>,
+    /// Formato explícito del archivo; si falta se deduce de la extensión
+    pub format: Option<ConfigFormat>,
+    /// Rechazar claves que no existen en el esquema en lugar de advertir
+    pub strict: bool,
+    /// Perfil de `[profiles.<nombre>]` a fusionar sobre el archivo
+    pub profile: Option<String>,
+}
+
+impl Default for ConfigOverrides {
+    fn default() -> Self {
+        Self {
+            env_prefix: "SAAI".to_string(),
+            cli: Vec::new(),
+            format: None,
+            strict: false,
+            profile: None,
+        }
+    }
+}
+
+impl ConfigOverrides {
+    /// Construir a partir de argumentos `clave=valor`
+    pub fn from_cli(args: &[String]) -> Result<Self> {
+        let cli = args
+            .iter()
+            .map(|arg| {
+                arg.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+                    .ok_or_else(|| anyhow!("Override inválido (se esperaba clave=valor): {}", arg))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            cli,
+            ..Default::default()
+        })
+    }
+
+    /// Añadir un override de CLI
+    pub fn with(mut self, key: &str, value: impl ToString) -> Self {
+        self.cli.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Formato con el que leer y escribir `path`
+    pub fn format_for(&self, path: &Path) -> ConfigFormat {
+        self.format.unwrap_or_else(|| ConfigFormat::from_path(path))
+    }
+
+    /// Variables `<PREFIJO>__SECCION__CLAVE` como rutas con puntos
+    fn env_entries(&self) -> Vec<(String, String)> {
+        let prefix = format!("{}__", self.env_prefix);
+        let mut entries: Vec<(String, String)> = std::env::vars()
+            .filter_map(|(key, value)| {
+                let path = key.strip_prefix(&prefix)?;
+                let path: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+                Some((path.join("."), value))
+            })
+            .collect();
+
+        entries.sort();
+        entries
+    }
+}
+
+/// Claves renombradas: ruta antigua → ruta actual
+const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("security.intrusion_detection", "security.threat_detection"),
+    ("security.audit_log_enabled", "security.audit_logging"),
+];
+
+/// Ruta actual de una clave que pudo haberse renombrado
+fn current_path(path: &str) -> &str {
+    RENAMED_KEYS
+        .iter()
+        .find(|(old, _)| *old == path)
+        .map_or(path, |(_, new)| new)
+}
+
+/// Migrar las claves antiguas del documento y de sus perfiles
+///
+/// El archivo se reescribe con los nombres actuales en el próximo guardado.
+pub(crate) fn migrate(document: &mut Value) {
+    migrate_tree(document, "");
+
+    if let Some(profiles) = document.get_mut("profiles").and_then(Value::as_object_mut) {
+        for (name, overlay) in profiles.iter_mut() {
+            migrate_tree(overlay, &format!("profiles.{}.", name));
+        }
+    }
+}
+
+fn migrate_tree(tree: &mut Value, prefix: &str) {
+    for (old, new) in RENAMED_KEYS {
+        let (section, old_key) = old.split_once('.').unwrap_or(("", old));
+        let (_, new_key) = new.split_once('.').unwrap_or(("", new));
+        let Some(fields) = tree.get_mut(section).and_then(Value::as_object_mut) else { continue };
+        let Some(value) = fields.remove(old_key) else { continue };
+
+        warn!("⚠️  Clave obsoleta {}{}: use {}{}", prefix, old, prefix, new);
+        fields.entry(new_key.to_string()).or_insert(value);
+    }
+
+    // Antes `encryption_key_size = 0` deshabilitaba la encriptación
+    if let Some(security) = tree.get_mut("security").and_then(Value::as_object_mut) {
+        if security.get("encryption_key_size") == Some(&Value::from(0)) && !security.contains_key("encryption_enabled") {
+            security.insert("encryption_enabled".to_string(), Value::Bool(false));
+        }
+    }
+}
+
+/// Combinar las capas en el árbol de la configuración
+pub(crate) fn resolve(file: Option<Value>, overrides: &ConfigOverrides) -> Result<Value> {
+    let mut config = serde_json::to_value(CoreConfig::default())?;
+
+    if let Some(file) = file {
+        merge(&mut config, file);
+    }
+
+    if let Some(profile) = &overrides.profile {
+        profiles::apply(&mut config, profile)?;
+    }
+
+    for (path, raw) in overrides.env_entries().iter().chain(&overrides.cli) {
+        set_path(&mut config, current_path(path), raw)?;
+    }
+
+    Ok(config)
+}
+
+/// Fusionar `overlay` sobre `base` recursivamente en los objetos
+pub(crate) fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Asignar un valor textual en una ruta, respetando el tipo existente
+fn set_path(root: &mut Value, path: &str, raw: &str) -> Result<()> {
+    let mut current = root;
+
+    for segment in path.split('.') {
+        // Mapas nuevos, como `nano_cores.instances.<seccion>.<n>`
+        if current.is_null() {
+            *current = Value::Object(Default::default());
+        }
+
+        let object = current
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("Ruta de configuración inválida: {}", path))?;
+        current = object.entry(segment.to_string()).or_insert(Value::Null);
+    }
+
+    *current = coerce(raw, current);
+    Ok(())
+}
+
+/// Interpretar el valor según el tipo del campo que reemplaza
+fn coerce(raw: &str, existing: &Value) -> Value {
+    match existing {
+        Value::String(_) => Value::String(raw.to_string()),
+        Value::Array(_) => serde_json::from_str(raw).unwrap_or_else(|_| {
+            Value::Array(
+                raw.split(',')
+                    .map(|item| Value::String(item.trim().to_string()))
+                    .collect(),
+            )
+        }),
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_precedence() {
+        std::env::set_var("SAAI_LAYERS_TEST__CONSENSUS__REPLICA_COUNT", "5");
+        std::env::set_var("SAAI_LAYERS_TEST__NATS_URL", "nats://env:4222");
+        std::env::set_var(
+            "SAAI_LAYERS_TEST__NANO_CORES__OS_CORE__PROCESS_WHITELIST",
+            "saai-core, sshd",
+        );
+
+        let file = serde_json::json!({
+            "nats_url": "nats://file:4222",
+            "log_level": "warn",
+            "consensus": { "replica_count": 4 }
+        });
+        let overrides = ConfigOverrides {
+            env_prefix: "SAAI_LAYERS_TEST".to_string(),
+            ..Default::default()
+        }
+        .with("consensus.replica_count", 7);
+
+        let config: CoreConfig = serde_json::from_value(resolve(Some(file), &overrides).unwrap()).unwrap();
+        assert_eq!(config.consensus.replica_count, 7);
+        assert_eq!(config.nats_url, "nats://env:4222");
+        assert_eq!(config.log_level, "warn");
+        assert_eq!(config.metrics_port, 9090);
+        assert_eq!(
+            config.nano_cores.os_core.process_whitelist,
+            vec!["saai-core".to_string(), "sshd".to_string()]
+        );
+
+        assert!(ConfigOverrides::from_cli(&["sin_valor".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_instance_overrides() {
+        let file = serde_json::json!({
+            "nano_cores": { "instances": { "network_core": { "2": { "enable_dpdk": true } } } }
+        });
+        let overrides = ConfigOverrides {
+            env_prefix: "SAAI_INSTANCES_TEST".to_string(),
+            ..Default::default()
+        }
+        .with("nano_cores.instances.os_core.1.monitor_interval_ms", 250);
+
+        let config: CoreConfig = serde_json::from_value(resolve(Some(file), &overrides).unwrap()).unwrap();
+        config.validate().unwrap();
+
+        let shared = config.nano_cores.for_instance(0).unwrap();
+        assert!(!shared.network_core.enable_dpdk);
+        assert_eq!(shared.os_core.monitor_interval_ms, 1000);
+
+        assert!(config.nano_cores.for_instance(2).unwrap().network_core.enable_dpdk);
+        assert_eq!(config.nano_cores.for_instance(1).unwrap().os_core.monitor_interval_ms, 250);
+
+        // Secciones desconocidas e instancias fuera de rango se rechazan
+        let mut invalid = config.clone();
+        invalid.nano_cores.instances.insert("gpu_core".to_string(), Default::default());
+        assert!(invalid.nano_cores.for_instance(0).is_err());
+
+        // ...salvo las de nano-núcleos externos configurados en `custom`
+        let mut custom = config.clone();
+        custom.nano_cores.custom.insert("gpu_core".to_string(), serde_json::json!({ "devices": 1, "fan": "auto" }));
+        custom.nano_cores.instances.insert("gpu_core".to_string(), std::collections::BTreeMap::from([(1, serde_json::json!({ "devices": 2 }))]));
+        assert_eq!(custom.nano_cores.for_instance(0).unwrap().custom["gpu_core"]["devices"], 1);
+        let overridden = custom.nano_cores.for_instance(1).unwrap();
+        assert_eq!(overridden.custom["gpu_core"], serde_json::json!({ "devices": 2, "fan": "auto" }));
+
+        let mut invalid = config;
+        invalid.nano_cores.instances.get_mut("network_core").unwrap().insert(7, serde_json::json!({}));
+        assert!(invalid.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_security_keys() {
+        let legacy = "[security]\nencryption_key_size = 0\nintrusion_detection = false\naudit_log_enabled = false\n\n\
+                      [profiles.audit.security]\naudit_log_enabled = true\n";
+        let strict = ConfigOverrides {
+            strict: true,
+            profile: Some("audit".to_string()),
+            ..Default::default()
+        };
+
+        let config = CoreConfig::parse_with(legacy, ConfigFormat::Toml, &strict).await.unwrap();
+        assert!(!config.security.threat_detection);
+        assert!(config.security.audit_logging);
+        assert!(!config.security.encryption_enabled);
+
+        // Las versiones antiguas del historial se leen sin migrar
+        let stored: crate::config::SecurityConfig =
+            serde_json::from_str(r#"{"enable_sandboxing": true, "encryption_key_size": 256, "intrusion_detection": false}"#).unwrap();
+        assert!(!stored.threat_detection && stored.encryption_enabled);
+
+        let saved = ConfigFormat::Toml.serialize(&config.redacted().unwrap()).unwrap();
+        assert!(saved.contains("threat_detection") && !saved.contains("intrusion_detection"));
+    }
+}
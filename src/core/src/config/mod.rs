@@ -4,16 +4,62 @@
 //! versionado GitOps y rollback atómico.
 
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+use uuid::Uuid;
+
+use crate::communication::{CognitiveEvent, CognitiveFabric, EventPriority, EventType};
 use crate::consensus::ConsensusConfig;
+use crate::nano_cores::{
+    CheckpointConfig, FailurePredictorConfig, HealthConfig, PlacementConfig, ProcessHistoryConfig, ScheduledCommand, StartupConfig, SupervisorConfig,
+    AddressFamilyConfig, BandwidthConfig, CaptureConfig, DnsMonitorConfig, FirewallConfig, LinkEventsConfig, QosConfig, ThroughputConfig,
+    WatchdogConfig,
+};
+pub use crate::security::SecurityConfig;
+
+pub mod environment;
+pub mod format;
+pub mod gitops;
+mod hardware;
+pub mod history;
+pub mod layers;
+pub mod profiles;
+pub mod schema;
+pub mod secrets;
+pub mod soak;
+pub mod source;
+pub mod subscriber;
+pub mod topology;
+pub mod validation;
+pub mod watcher;
+
+pub use environment::RuntimeEnvironment;
+pub use format::ConfigFormat;
+pub use gitops::GitSyncOptions;
+pub use history::VersionStore;
+pub use layers::ConfigOverrides;
+pub use secrets::SecretResolver;
+pub use soak::{HealthProbe, SoakOptions};
+pub use source::{ConfigSource, SourceSyncOptions};
+use secrets::SecretRefs;
+pub use subscriber::{ConfigSection, ConfigSubscriber, ConfigUpdate};
+pub use topology::CpuTopology;
+pub use validation::{Severity, ValidationIssue, ValidationReport};
+pub use watcher::{ConfigWatcher, WatchOptions};
 
 /// Configuración principal del núcleo SAAI
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CoreConfig {
     pub nats_url: String,
     pub metrics_port: u16,
@@ -22,73 +68,218 @@ pub struct CoreConfig {
     pub nano_cores: NanoCoresConfig,
     pub security: SecurityConfig,
     pub performance: PerformanceConfig,
+    /// Overlays seleccionables con `--profile` (`[profiles.<nombre>]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Value>,
+    /// Referencias de los secretos resueltos al cargar
+    #[serde(skip)]
+    pub(crate) secret_refs: SecretRefs,
 }
 
 /// Configuración de nano-núcleos
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NanoCoresConfig {
     pub os_core: OSCoreConfig,
     pub hardware_core: HardwareCoreConfig,
     pub network_core: NetworkCoreConfig,
     pub security_core: SecurityCoreConfig,
+    /// Políticas de reinicio y detección de crash loops
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    /// Dependencias entre nano-núcleos y espera de arranque
+    #[serde(default)]
+    pub startup: StartupConfig,
+    /// Umbrales, pesos e histéresis de los estados de salud
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// Afinidad de CPU y nice por sección (`[nano_cores.placement.network_core]`)
+    #[serde(default)]
+    pub placement: BTreeMap<String, PlacementConfig>,
+    /// Checkpoints del estado de los nano-núcleos
+    #[serde(default)]
+    pub checkpoint: CheckpointConfig,
+    /// Overrides por réplica: sección → instancia → campos
+    /// (`[nano_cores.instances.network_core.2]`)
+    #[serde(default)]
+    pub instances: BTreeMap<String, BTreeMap<usize, Value>>,
+    /// Configuración de los nano-núcleos externos por nombre de fábrica
+    /// (`[nano_cores.custom.<nombre>]`)
+    #[serde(default)]
+    pub custom: BTreeMap<String, Value>,
 }
 
 /// Configuración del nano-núcleo OS
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OSCoreConfig {
     pub enable_ebpf: bool,
     pub monitor_interval_ms: u64,
     pub process_whitelist: Vec<String>,
     pub resource_limits: ResourceLimits,
+    /// Objeto BPF compilado desde `src/core/ebpf` (feature `ebpf`)
+    #[serde(default = "default_ebpf_object")]
+    pub ebpf_object: String,
+    /// Syscalls trazadas por el monitor eBPF
+    #[serde(default = "default_ebpf_syscalls")]
+    pub ebpf_syscalls: Vec<String>,
+    /// Terminar los procesos fuera de `process_whitelist` en lugar de solo reportarlos
+    #[serde(default)]
+    pub enforce_process_whitelist: bool,
+    /// Aplicar `resource_limits` con un cgroup v2 propio (Linux)
+    #[serde(default)]
+    pub enable_cgroups: bool,
+    /// Cgroup creado para el árbol de procesos de SAAI
+    #[serde(default = "default_cgroup_path")]
+    pub cgroup_path: String,
+    /// Servicios que `StartService`/`StopService`/`RestartService` pueden
+    /// controlar; vacío permite solo consultarlos
+    #[serde(default)]
+    pub controllable_services: Vec<String>,
+    #[serde(default)]
+    pub process_history: ProcessHistoryConfig,
+    /// Comandos recurrentes con expresión cron
+    #[serde(default)]
+    pub schedules: Vec<ScheduledCommand>,
+}
+
+fn default_ebpf_object() -> String {
+    "/usr/lib/saai/saai-ebpf.o".to_string()
+}
+
+fn default_cgroup_path() -> String {
+    "/sys/fs/cgroup/saai".to_string()
+}
+
+fn default_ebpf_syscalls() -> Vec<String> {
+    ["ptrace", "init_module", "finit_module", "kexec_load", "mount", "setuid", "bpf"]
+        .map(str::to_string)
+        .to_vec()
 }
 
 /// Configuración del nano-núcleo Hardware
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HardwareCoreConfig {
     pub temperature_threshold: f64,
     pub cpu_usage_threshold: f64,
     pub memory_usage_threshold: f64,
     pub enable_predictive_monitoring: bool,
+    /// Ocupación de disco que dispara la alerta, en porcentaje
+    #[serde(default = "default_disk_usage_threshold")]
+    pub disk_usage_threshold: f64,
+    /// Umbrales por punto de montaje o nombre de disco (`"/var" = 85.0`)
+    #[serde(default)]
+    pub disk_thresholds: BTreeMap<String, f64>,
+    /// Margen bajo el umbral para dar una alerta por resuelta (°C o puntos porcentuales)
+    #[serde(default = "default_alert_hysteresis")]
+    pub alert_hysteresis: f64,
+    /// Segundos mínimos entre repeticiones de una misma alerta
+    #[serde(default = "default_alert_cooldown_secs")]
+    pub alert_cooldown_secs: u64,
+    /// Leer la salud SMART de los discos con `smartctl`
+    #[serde(default = "default_enable_smart")]
+    pub enable_smart: bool,
+    /// Segundos entre lecturas SMART
+    #[serde(default = "default_smart_interval_secs")]
+    pub smart_interval_secs: u64,
+    /// Leer GPUs NVIDIA (feature `nvml`) y AMD (amdgpu)
+    #[serde(default = "default_enable_gpu_monitoring")]
+    pub enable_gpu_monitoring: bool,
+    /// Volver al perfil de energía original al detener la instancia que lo cambió
+    #[serde(default)]
+    pub restore_power_mode_on_shutdown: bool,
+    /// Historial persistente y tendencias del predictor de fallos
+    #[serde(default)]
+    pub predictor: FailurePredictorConfig,
+}
+
+fn default_disk_usage_threshold() -> f64 {
+    95.0
+}
+
+fn default_alert_hysteresis() -> f64 {
+    5.0
+}
+
+fn default_alert_cooldown_secs() -> u64 {
+    300
+}
+
+fn default_enable_smart() -> bool {
+    true
+}
+
+fn default_smart_interval_secs() -> u64 {
+    1800
+}
+
+fn default_enable_gpu_monitoring() -> bool {
+    true
 }
 
 /// Configuración del nano-núcleo Network
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkCoreConfig {
     pub enable_dpdk: bool,
     pub max_connections: u32,
     pub timeout_ms: u64,
     pub qos_enabled: bool,
+    /// Clases de tráfico y shaping con tc
+    #[serde(default)]
+    pub qos: QosConfig,
+    /// Sondeo periódico de los resolvers DNS
+    #[serde(default)]
+    pub dns_monitor: DnsMonitorConfig,
+    /// Receptor y límites de `TestThroughput`
+    #[serde(default)]
+    pub throughput: ThroughputConfig,
+    /// Tabla y modo dry-run de `ConfigureFirewall`
+    #[serde(default)]
+    pub firewall: FirewallConfig,
+    /// Límites de `CapturePackets`
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    /// Muestreo y ventana de la contabilidad por proceso y remoto
+    #[serde(default)]
+    pub bandwidth: BandwidthConfig,
+    /// Familias IPv4/IPv6 habilitadas y preferida
+    #[serde(default)]
+    pub address_family: AddressFamilyConfig,
+    /// Alertas de subida, caída y cambio de direcciones de las interfaces
+    #[serde(default)]
+    pub link_events: LinkEventsConfig,
+    /// Sondeo de alcanzabilidad y remediación por consenso
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
 }
 
 /// Configuración del nano-núcleo Security
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SecurityCoreConfig {
     pub sandbox_enabled: bool,
     pub encryption_algorithm: String,
     pub key_rotation_interval_hours: u64,
+    /// Claves anteriores conservadas para descifrar mensajes en vuelo
+    pub retained_keys: usize,
     pub threat_detection_enabled: bool,
 }
 
 /// Límites de recursos
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceLimits {
     pub max_cpu_percent: f64,
     pub max_memory_mb: u64,
     pub max_file_descriptors: u32,
     pub max_network_connections: u32,
+    /// `pids.max` del cgroup de SAAI
+    #[serde(default = "default_max_pids")]
+    pub max_pids: u32,
 }
 
-/// Configuración de seguridad
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SecurityConfig {
-    pub enable_sandboxing: bool,
-    pub encryption_key_size: u32,
-    pub audit_log_enabled: bool,
-    pub intrusion_detection: bool,
+fn default_max_pids() -> u32 {
+    4096
 }
 
 /// Configuración de rendimiento
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PerformanceConfig {
     pub thread_pool_size: usize,
     pub async_runtime_threads: usize,
@@ -106,6 +297,8 @@ impl Default for CoreConfig {
             nano_cores: NanoCoresConfig::default(),
             security: SecurityConfig::default(),
             performance: PerformanceConfig::default(),
+            profiles: BTreeMap::new(),
+            secret_refs: SecretRefs::new(),
         }
     }
 }
@@ -117,10 +310,48 @@ impl Default for NanoCoresConfig {
             hardware_core: HardwareCoreConfig::default(),
             network_core: NetworkCoreConfig::default(),
             security_core: SecurityCoreConfig::default(),
+            supervisor: SupervisorConfig::default(),
+            startup: StartupConfig::default(),
+            health: HealthConfig::default(),
+            placement: BTreeMap::new(),
+            checkpoint: CheckpointConfig::default(),
+            instances: BTreeMap::new(),
+            custom: BTreeMap::new(),
         }
     }
 }
 
+/// Secciones de `NanoCoresConfig` que admiten overrides por instancia
+const INSTANCE_SECTIONS: [&str; 4] = ["os_core", "hardware_core", "network_core", "security_core"];
+
+impl NanoCoresConfig {
+    /// Si `section` es un nano-núcleo integrado o uno externo configurado
+    pub fn has_section(&self, section: &str) -> bool {
+        INSTANCE_SECTIONS.contains(&section) || self.custom.contains_key(section)
+    }
+    
+    /// Configuración efectiva de una réplica: la común con sus overrides
+    pub fn for_instance(&self, instance: usize) -> Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        
+        for (section, overrides) in &self.instances {
+            if !self.has_section(section) {
+                return Err(anyhow!("Sección de nano-núcleo desconocida en instances: {}", section));
+            }
+            if let Some(patch) = overrides.get(&instance) {
+                let target = if INSTANCE_SECTIONS.contains(&section.as_str()) {
+                    &mut value[section.as_str()]
+                } else {
+                    &mut value["custom"][section.as_str()]
+                };
+                layers::merge(target, patch.clone());
+            }
+        }
+        
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
 impl Default for OSCoreConfig {
     fn default() -> Self {
         Self {
@@ -131,6 +362,14 @@ impl Default for OSCoreConfig {
                 "saai-agents".to_string(),
             ],
             resource_limits: ResourceLimits::default(),
+            ebpf_object: default_ebpf_object(),
+            ebpf_syscalls: default_ebpf_syscalls(),
+            enforce_process_whitelist: false,
+            enable_cgroups: false,
+            cgroup_path: default_cgroup_path(),
+            controllable_services: Vec::new(),
+            process_history: ProcessHistoryConfig::default(),
+            schedules: Vec::new(),
         }
     }
 }
@@ -142,6 +381,15 @@ impl Default for HardwareCoreConfig {
             cpu_usage_threshold: 90.0,
             memory_usage_threshold: 85.0,
             enable_predictive_monitoring: true,
+            disk_usage_threshold: default_disk_usage_threshold(),
+            disk_thresholds: BTreeMap::new(),
+            alert_hysteresis: default_alert_hysteresis(),
+            alert_cooldown_secs: default_alert_cooldown_secs(),
+            enable_smart: default_enable_smart(),
+            smart_interval_secs: default_smart_interval_secs(),
+            enable_gpu_monitoring: default_enable_gpu_monitoring(),
+            restore_power_mode_on_shutdown: false,
+            predictor: FailurePredictorConfig::default(),
         }
     }
 }
@@ -153,6 +401,15 @@ impl Default for NetworkCoreConfig {
             max_connections: 10000,
             timeout_ms: 30000,
             qos_enabled: true,
+            qos: QosConfig::default(),
+            dns_monitor: DnsMonitorConfig::default(),
+            throughput: ThroughputConfig::default(),
+            firewall: FirewallConfig::default(),
+            capture: CaptureConfig::default(),
+            bandwidth: BandwidthConfig::default(),
+            address_family: AddressFamilyConfig::default(),
+            link_events: LinkEventsConfig::default(),
+            watchdog: WatchdogConfig::default(),
         }
     }
 }
@@ -163,6 +420,7 @@ impl Default for SecurityCoreConfig {
             sandbox_enabled: true,
             encryption_algorithm: "AES-256-GCM".to_string(),
             key_rotation_interval_hours: 24,
+            retained_keys: 3,
             threat_detection_enabled: true,
         }
     }
@@ -175,17 +433,7 @@ impl Default for ResourceLimits {
             max_memory_mb: 4096,
             max_file_descriptors: 1024,
             max_network_connections: 1000,
-        }
-    }
-}
-
-impl Default for SecurityConfig {
-    fn default() -> Self {
-        Self {
-            enable_sandboxing: true,
-            encryption_key_size: 256,
-            audit_log_enabled: true,
-            intrusion_detection: true,
+            max_pids: default_max_pids(),
         }
     }
 }
@@ -204,29 +452,62 @@ impl Default for PerformanceConfig {
 impl CoreConfig {
     /// Cargar configuración desde archivo
     pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_with(path, &ConfigOverrides::default()).await
+    }
+    
+    /// Cargar configuración por capas
+    ///
+    /// Precedencia: CLI > entorno (`SAAI__SECCION__CLAVE`) > archivo > defaults.
+    /// Sin archivo se crea uno con los valores por defecto.
+    pub async fn load_with<P: AsRef<Path>>(path: P, overrides: &ConfigOverrides) -> Result<Self> {
         let path = path.as_ref();
         
         info!("📋 Cargando configuración desde: {}", path.display());
         
         if !path.exists() {
             warn!("⚠️  Archivo de configuración no encontrado, creando configuración por defecto");
-            let default_config = Self::default();
-            default_config.save(path).await?;
-            return Ok(default_config);
+            Self::default().save_as(path, overrides.format_for(path)).await?;
         }
         
+        let config = Self::read_file_with(path, overrides).await?;
+        
+        info!("✅ Configuración cargada y validada");
+        Ok(config)
+    }
+    
+    /// Leer y validar configuración de un archivo existente
+    pub async fn read_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_file_with(path, &ConfigOverrides::default()).await
+    }
+    
+    /// Leer un archivo existente aplicando los overrides de entorno y CLI
+    pub async fn read_file_with<P: AsRef<Path>>(path: P, overrides: &ConfigOverrides) -> Result<Self> {
+        let path = path.as_ref();
         let content = fs::read_to_string(path).await?;
-        let config: CoreConfig = toml::from_str(&content)?;
+        
+        Self::parse_with(&content, overrides.format_for(path), overrides).await
+    }
+    
+    /// Interpretar y validar un documento aplicando los overrides de entorno y CLI
+    pub async fn parse_with(content: &str, format: ConfigFormat, overrides: &ConfigOverrides) -> Result<Self> {
+        let mut document = format.parse(content)?;
+        layers::migrate(&mut document);
+        schema::check_unknown_keys(&document, overrides.strict)?;
+        let config = Self::from_resolved(layers::resolve(Some(document), overrides)?).await?;
         
         // Validar configuración
         config.validate()?;
         
-        info!("✅ Configuración cargada y validada");
         Ok(config)
     }
     
-    /// Guardar configuración a archivo
+    /// Guardar configuración a archivo en el formato de su extensión
     pub async fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save_as(path.as_ref(), ConfigFormat::from_path(path.as_ref())).await
+    }
+    
+    /// Guardar configuración a archivo en un formato explícito
+    pub async fn save_as<P: AsRef<Path>>(&self, path: P, format: ConfigFormat) -> Result<()> {
         let path = path.as_ref();
         
         // Crear directorio padre si no existe
@@ -234,86 +515,77 @@ impl CoreConfig {
             fs::create_dir_all(parent).await?;
         }
         
-        let content = toml::to_string_pretty(self)?;
+        let content = format.serialize(&self.redacted()?)?;
         fs::write(path, content).await?;
         
         info!("💾 Configuración guardada en: {}", path.display());
         Ok(())
     }
     
+    /// Construir a partir de un árbol resolviendo sus referencias a secretos
+    async fn from_resolved(mut value: Value) -> Result<Self> {
+        let refs = secrets::resolve_value(&mut value, &SecretResolver::from_env()).await?;
+        let mut config: Self = serde_json::from_value(value)?;
+        config.secret_refs = refs;
+        Ok(config)
+    }
+    
+    /// Resolver las referencias que aún contenga (p. ej. versiones leídas de disco)
+    pub async fn resolve_secrets(self) -> Result<Self> {
+        let mut refs = self.secret_refs.clone();
+        let mut config = Self::from_resolved(serde_json::to_value(&self)?).await?;
+        refs.append(&mut config.secret_refs);
+        config.secret_refs = refs;
+        Ok(config)
+    }
+    
+    /// Árbol serializable con las referencias en lugar de los secretos
+    pub fn redacted(&self) -> Result<Value> {
+        let mut value = serde_json::to_value(self)?;
+        secrets::restore_refs(&mut value, &self.secret_refs);
+        Ok(value)
+    }
+    
     /// Validar configuración
+    ///
+    /// Falla con todos los errores encontrados; las advertencias solo se registran.
+    /// `validation_report()` devuelve el informe completo.
     pub fn validate(&self) -> Result<()> {
-        // Validar URL de NATS
-        if self.nats_url.is_empty() {
-            return Err(anyhow!("URL de NATS no puede estar vacía"));
-        }
-        
-        // Validar puerto de métricas
-        if self.metrics_port == 0 {
-            return Err(anyhow!("Puerto de métricas debe ser mayor que 0"));
-        }
-        
-        // Validar configuración de consenso
-        if self.consensus.replica_count < 3 {
-            return Err(anyhow!("Número de réplicas debe ser al menos 3"));
-        }
-        
-        if self.consensus.byzantine_tolerance <= 0.0 || self.consensus.byzantine_tolerance >= 0.5 {
-            return Err(anyhow!("Tolerancia bizantina debe estar entre 0.0 y 0.5"));
-        }
-        
-        // Validar límites de recursos
-        let limits = &self.nano_cores.os_core.resource_limits;
-        if limits.max_cpu_percent <= 0.0 || limits.max_cpu_percent > 100.0 {
-            return Err(anyhow!("Límite de CPU debe estar entre 0 y 100"));
-        }
-        
-        if limits.max_memory_mb == 0 {
-            return Err(anyhow!("Límite de memoria debe ser mayor que 0"));
-        }
-        
-        // Validar configuración de hardware
-        let hw_config = &self.nano_cores.hardware_core;
-        if hw_config.temperature_threshold <= 0.0 {
-            return Err(anyhow!("Umbral de temperatura debe ser mayor que 0"));
-        }
-        
-        // Validar configuración de red
-        let net_config = &self.nano_cores.network_core;
-        if net_config.max_connections == 0 {
-            return Err(anyhow!("Máximo de conexiones debe ser mayor que 0"));
-        }
-        
-        // Validar configuración de seguridad
-        let sec_config = &self.nano_cores.security_core;
-        if sec_config.encryption_algorithm.is_empty() {
-            return Err(anyhow!("Algoritmo de encriptación no puede estar vacío"));
-        }
-        
-        if sec_config.key_rotation_interval_hours == 0 {
-            return Err(anyhow!("Intervalo de rotación de claves debe ser mayor que 0"));
-        }
-        
-        // Validar configuración de rendimiento
-        if self.performance.thread_pool_size == 0 {
-            return Err(anyhow!("Tamaño del pool de hilos debe ser mayor que 0"));
-        }
-        
-        if self.performance.async_runtime_threads == 0 {
-            return Err(anyhow!("Número de hilos del runtime async debe ser mayor que 0"));
+        let report = self.validation_report();
+        for issue in report.warnings() {
+            warn!("⚠️  Configuración: {}", issue);
         }
         
+        report.into_result()?;
         debug!("✅ Configuración validada correctamente");
         Ok(())
     }
     
     /// Obtener configuración optimizada para el hardware actual
+    ///
+    /// En contenedores se usan las CPUs y la memoria del cgroup, no las del host.
     pub fn optimize_for_hardware(&mut self) -> Result<()> {
-        let cpu_count = num_cpus::get();
-        let available_memory = Self::get_available_memory()?;
+        let environment = RuntimeEnvironment::detect();
+        let cpu_count = environment.effective_cpus(num_cpus::get());
+        let available_memory = environment.effective_memory(hardware::available_memory()?);
         
         info!("🔧 Optimizando configuración para hardware: {} CPUs, {} MB RAM", 
               cpu_count, available_memory / 1024 / 1024);
+        if environment.is_container() {
+            info!(
+                "📦 Ejecutando en contenedor ({}{})",
+                environment.container.as_deref().unwrap_or("desconocido"),
+                if environment.kubernetes { ", Kubernetes" } else { "" }
+            );
+        }
+        
+        let topology = CpuTopology::detect();
+        if topology.numa_nodes.len() > 1 {
+            info!(
+                "🧩 Topología NUMA: {} sockets, {} núcleos físicos, {} nodos",
+                topology.sockets, topology.physical_cores, topology.numa_nodes.len()
+            );
+        }
         
         // Optimizar configuración de rendimiento
         self.performance.thread_pool_size = cpu_count;
@@ -333,31 +605,6 @@ impl CoreConfig {
         Ok(())
     }
     
-    /// Obtener memoria disponible del sistema
-    fn get_available_memory() -> Result<u64> {
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
-            let meminfo = fs::read_to_string("/proc/meminfo")?;
-            for line in meminfo.lines() {
-                if line.starts_with("MemAvailable:") {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        let kb: u64 = parts[1].parse()?;
-                        return Ok(kb * 1024); // Convertir a bytes
-                    }
-                }
-            }
-            Err(anyhow!("No se pudo obtener memoria disponible"))
-        }
-        
-        #[cfg(not(target_os = "linux"))]
-        {
-            // Fallback para otros sistemas operativos
-            Ok(8 * 1024 * 1024 * 1024) // Asumir 8GB por defecto
-        }
-    }
-    
     /// Crear configuración para desarrollo
     pub fn development() -> Self {
         let mut config = Self::default();
@@ -375,17 +622,136 @@ impl CoreConfig {
         config.consensus.replica_count = 5; // Mayor redundancia
         config.nano_cores.security_core.sandbox_enabled = true;
         config.nano_cores.security_core.threat_detection_enabled = true;
-        config.security.intrusion_detection = true;
+        config.security.threat_detection = true;
         config.performance.cache_size_mb = 1024; // Mayor cache
         config
     }
 }
 
+/// Cambio de un campo de configuración (ruta con puntos, p. ej. `consensus.replica_count`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+impl FieldChange {
+    /// Sección de primer nivel afectada
+    pub fn section(&self) -> ConfigSection {
+        ConfigSection::of(&self.path)
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.old, self.new)
+    }
+}
+
+/// Cambio de configuración aplicado, publicado en el Cognitive Fabric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    /// Versión de respaldo con la configuración anterior (destino de rollback)
+    pub rollback_version: String,
+    /// Origen del cambio (`file`, `api`...)
+    pub source: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Nombre del evento `Custom` con el que se publican los cambios
+pub const CONFIG_CHANGE_EVENT: &str = "config_change";
+
+/// Capacidad del canal de difusión de cambios
+const CONFIG_UPDATES_CAPACITY: usize = 16;
+
+impl ConfigChange {
+    /// Secciones afectadas por el cambio
+    pub fn sections(&self) -> BTreeSet<ConfigSection> {
+        self.changes.iter().map(FieldChange::section).collect()
+    }
+    
+    /// Publicar el cambio en el Cognitive Fabric (`saai.custom.config_change`)
+    pub async fn publish(&self, fabric: &CognitiveFabric) -> Result<()> {
+        let event = CognitiveEvent {
+            id: Uuid::new_v4(),
+            event_type: EventType::Custom(CONFIG_CHANGE_EVENT.to_string()),
+            source: "config-manager".to_string(),
+            target: None,
+            timestamp: chrono::Utc::now(),
+            payload: serde_json::to_vec(self)?,
+            priority: EventPriority::High,
+            correlation_id: None,
+            headers: HashMap::new(),
+        };
+        
+        fabric.publish_event(event).await
+    }
+}
+
+/// Aprobación previa de un cambio de configuración (p. ej. por consenso)
+#[async_trait]
+pub trait ConfigChangeGate: Send + Sync {
+    /// Decidir si se aplica la configuración propuesta
+    async fn approve(&self, config: &CoreConfig, changes: &[FieldChange]) -> Result<bool>;
+}
+
+/// Calcular los campos que difieren entre dos configuraciones
+pub fn diff_configs(old: &CoreConfig, new: &CoreConfig) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    
+    match (serde_json::to_value(old), serde_json::to_value(new)) {
+        (Ok(old), Ok(new)) => diff_values("", &old, &new, &mut changes),
+        (Err(e), _) | (_, Err(e)) => error!("❌ Error comparando configuraciones: {}", e),
+    }
+    
+    // Los secretos nunca salen en logs, historial ni eventos
+    for change in &mut changes {
+        if secrets::is_secret_path(&old.secret_refs, &change.path)
+            || secrets::is_secret_path(&new.secret_refs, &change.path)
+        {
+            change.old = Value::String(secrets::REDACTED.to_string());
+            change.new = Value::String(secrets::REDACTED.to_string());
+        }
+    }
+    
+    changes
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields)) => {
+            let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+            
+            for key in keys {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff_values(
+                    &child,
+                    old_fields.get(key).unwrap_or(&Value::Null),
+                    new_fields.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if old != new => changes.push(FieldChange {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
 /// Gestor de configuración con capacidades GitOps
 pub struct ConfigManager {
     current_config: CoreConfig,
     config_path: String,
+    overrides: ConfigOverrides,
     version_history: Vec<ConfigVersion>,
+    history: VersionStore,
+    updates: broadcast::Sender<ConfigUpdate>,
+    gate: Option<Arc<dyn ConfigChangeGate>>,
 }
 
 /// Versión de configuración para historial
@@ -395,27 +761,105 @@ pub struct ConfigVersion {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub config: CoreConfig,
     pub changes: Vec<String>,
+    /// Commit GitOps que introdujo los cambios
+    #[serde(default)]
+    pub commit: Option<String>,
 }
 
 impl ConfigManager {
     /// Crear nuevo gestor de configuración
     pub async fn new(config_path: &str) -> Result<Self> {
-        let current_config = CoreConfig::load(config_path).await?;
+        Self::with_overrides(config_path, ConfigOverrides::default()).await
+    }
+    
+    /// Crear gestor aplicando overrides de entorno y CLI sobre el archivo
+    pub async fn with_overrides(config_path: &str, overrides: ConfigOverrides) -> Result<Self> {
+        Self::with_store(config_path, overrides, VersionStore::for_config(config_path)).await
+    }
+    
+    /// Crear gestor con un almacén de historial explícito
+    pub async fn with_store(
+        config_path: &str,
+        overrides: ConfigOverrides,
+        history: VersionStore,
+    ) -> Result<Self> {
+        let current_config = CoreConfig::load_with(config_path, &overrides).await?;
+        let version_history = history.load().await?;
+        
+        if !version_history.is_empty() {
+            info!("📚 {} versiones de configuración recuperadas", version_history.len());
+        }
         
         Ok(Self {
             current_config,
             config_path: config_path.to_string(),
-            version_history: Vec::new(),
+            overrides,
+            version_history,
+            history,
+            updates: broadcast::channel(CONFIG_UPDATES_CAPACITY).0,
+            gate: None,
         })
     }
     
+    /// JSON Schema de la configuración gestionada
+    pub fn schema() -> schemars::schema::RootSchema {
+        schema::schema()
+    }
+    
     /// Obtener configuración actual
     pub fn get_config(&self) -> &CoreConfig {
         &self.current_config
     }
     
+    /// Ruta del archivo de configuración gestionado
+    pub fn config_path(&self) -> &str {
+        &self.config_path
+    }
+    
+    /// Overrides de entorno y CLI aplicados al releer el archivo
+    pub fn overrides(&self) -> &ConfigOverrides {
+        &self.overrides
+    }
+    
+    /// Exigir aprobación (p. ej. del ConsensusManager) antes de aplicar cambios
+    pub fn set_gate(&mut self, gate: Arc<dyn ConfigChangeGate>) {
+        self.gate = Some(gate);
+    }
+    
+    /// Aprobación exigida a los cambios, si la hay
+    pub fn gate(&self) -> Option<Arc<dyn ConfigChangeGate>> {
+        self.gate.clone()
+    }
+    
+    /// Recibir todos los cambios aplicados a partir de ahora
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigUpdate> {
+        self.updates.subscribe()
+    }
+    
+    /// Registrar un subsistema para reconfigurarlo en caliente
+    ///
+    /// La tarea devuelta termina cuando el gestor se destruye.
+    pub fn register_subscriber(&self, subscriber: Arc<dyn ConfigSubscriber>) -> JoinHandle<()> {
+        info!(
+            "📡 {} suscrito a cambios de configuración en {:?}",
+            subscriber.subscriber_name(), subscriber.sections()
+        );
+        subscriber::dispatch(subscriber, self.updates.subscribe())
+    }
+    
     /// Actualizar configuración con validación
     pub async fn update_config(&mut self, new_config: CoreConfig) -> Result<()> {
+        self.commit_update(new_config, "api", None).await?;
+        Ok(())
+    }
+    
+    /// Validar, aplicar y guardar a disco; `None` si no hay cambios
+    async fn commit_update(
+        &mut self,
+        new_config: CoreConfig,
+        source: &str,
+        commit: Option<String>,
+    ) -> Result<Option<ConfigChange>> {
         // Validar nueva configuración
         new_config.validate()?;
         
@@ -424,59 +868,132 @@ impl ConfigManager {
         
         if changes.is_empty() {
             debug!("📋 No hay cambios en la configuración");
-            return Ok(());
+            return Ok(None);
         }
         
         info!("📋 Actualizando configuración: {} cambios detectados", changes.len());
         
-        // Crear versión de respaldo
-        let version = ConfigVersion {
-            version: format!("v{}", chrono::Utc::now().timestamp()),
-            timestamp: chrono::Utc::now(),
-            config: self.current_config.clone(),
-            changes: changes.clone(),
-        };
-        
-        self.version_history.push(version);
+        if let Some(gate) = &self.gate {
+            if !gate.approve(&new_config, &changes).await? {
+                warn!("🗳️  Cambio de configuración rechazado ({} campos)", changes.len());
+                return Err(anyhow!("Cambio de configuración no aprobado ({} campos)", changes.len()));
+            }
+        }
         
-        // Aplicar nueva configuración
-        self.current_config = new_config;
+        let change = self.apply_config(new_config, changes, source, commit).await?;
         
         // Guardar a disco
-        self.current_config.save(&self.config_path).await?;
+        self.save_current().await?;
         
         info!("✅ Configuración actualizada exitosamente");
-        for change in changes {
-            info!("  📝 {}", change);
+        for field in &change.changes {
+            info!("  📝 {}", field);
         }
         
-        Ok(())
+        Ok(Some(change))
     }
     
-    /// Detectar cambios entre configuraciones
-    fn detect_changes(&self, old: &CoreConfig, new: &CoreConfig) -> Vec<String> {
-        let mut changes = Vec::new();
+    /// Sincronizar una vez desde un repositorio Git
+    ///
+    /// Actualiza el checkout en `gitops/` junto al archivo de configuración,
+    /// valida `path` y lo aplica registrando el commit en la versión.
+    pub async fn sync_from_git(
+        &mut self,
+        url: &str,
+        branch: &str,
+        path: &str,
+    ) -> Result<Option<ConfigChange>> {
+        let checkout = self.gitops_dir();
+        let commit = gitops::fetch(url, branch, &checkout).await?;
+        
+        self.apply_git_checkout(&checkout.join(path), &commit).await
+    }
+    
+    /// Aplicar el archivo de un checkout GitOps ya actualizado
+    pub async fn apply_git_checkout(&mut self, file: &Path, commit: &str) -> Result<Option<ConfigChange>> {
+        let new_config = CoreConfig::read_file_with(file, &self.overrides).await?;
         
-        if old.nats_url != new.nats_url {
-            changes.push(format!("NATS URL: {} -> {}", old.nats_url, new.nats_url));
+        self.commit_update(new_config, "git", Some(commit.to_string())).await
+    }
+    
+    /// Aplicar un documento leído de un `ConfigSource` (etcd, Consul...)
+    pub async fn apply_source_document(
+        &mut self,
+        source: &str,
+        content: &str,
+        format: ConfigFormat,
+    ) -> Result<Option<ConfigChange>> {
+        let new_config = CoreConfig::parse_with(content, format, &self.overrides).await?;
+        
+        self.commit_update(new_config, source, None).await
+    }
+    
+    /// Reescribir el archivo gestionado con la configuración actual
+    async fn save_current(&self) -> Result<()> {
+        let format = self.overrides.format_for(Path::new(&self.config_path));
+        self.current_config.save_as(&self.config_path, format).await
+    }
+    
+    /// Directorio del checkout GitOps
+    pub fn gitops_dir(&self) -> PathBuf {
+        match Path::new(&self.config_path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.join("gitops"),
+            _ => PathBuf::from("gitops"),
         }
-        
-        if old.metrics_port != new.metrics_port {
-            changes.push(format!("Puerto métricas: {} -> {}", old.metrics_port, new.metrics_port));
+    }
+    
+    /// Aplicar configuración ya validada guardando la actual como versión
+    ///
+    /// La versión de respaldo se persiste en el historial y el cambio se
+    /// difunde a los suscriptores, pero el archivo no se reescribe.
+    pub(crate) async fn apply_config(
+        &mut self,
+        new_config: CoreConfig,
+        changes: Vec<FieldChange>,
+        source: &str,
+        commit: Option<String>,
+    ) -> Result<ConfigChange> {
+        let timestamp = chrono::Utc::now();
+        let mut id = format!("v{}", timestamp.timestamp_millis());
+        if self.version_history.iter().any(|v| v.version == id) {
+            id = format!("{}-{}", id, self.version_history.len());
         }
         
-        if old.log_level != new.log_level {
-            changes.push(format!("Nivel log: {} -> {}", old.log_level, new.log_level));
-        }
+        let version = ConfigVersion {
+            version: id.clone(),
+            timestamp,
+            config: self.current_config.clone(),
+            changes: changes.iter().map(ToString::to_string).collect(),
+            commit,
+        };
         
-        if old.consensus.replica_count != new.consensus.replica_count {
-            changes.push(format!("Réplicas consenso: {} -> {}", 
-                                old.consensus.replica_count, new.consensus.replica_count));
-        }
+        self.history.save(&version).await?;
+        self.version_history.push(version);
+        
+        let excess = self.version_history.len().saturating_sub(self.history.max_versions());
+        self.version_history.drain(..excess);
+        
+        self.current_config = new_config;
+        
+        let change = ConfigChange {
+            rollback_version: id,
+            source: source.to_string(),
+            timestamp,
+            changes,
+        };
         
-        // TODO: Agregar más detección de cambios para otros campos
+        // Sin receptores el envío falla, lo cual no es un error
+        let _ = self.updates.send(ConfigUpdate {
+            change: change.clone(),
+            config: Arc::new(self.current_config.clone()),
+        });
         
-        changes
+        Ok(change)
+    }
+    
+    /// Detectar cambios entre configuraciones
+    fn detect_changes(&self, old: &CoreConfig, new: &CoreConfig) -> Vec<FieldChange> {
+        diff_configs(old, new)
     }
     
     /// Rollback a versión anterior
@@ -486,8 +1003,11 @@ impl ConfigManager {
             
             info!("🔄 Realizando rollback a versión: {}", version);
             
-            self.current_config = config_version.config.clone();
-            self.current_config.save(&self.config_path).await?;
+            // El rollback también queda versionado y puede deshacerse
+            let target = config_version.config.clone().resolve_secrets().await?;
+            let changes = diff_configs(&self.current_config, &target);
+            self.apply_config(target, changes, "rollback", None).await?;
+            self.save_current().await?;
             
             info!("✅ Rollback completado a versión {}", version);
             Ok(())
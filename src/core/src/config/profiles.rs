@@ -0,0 +1,138 @@
+//! Perfiles de configuración
+//!
+//! `[profiles.<nombre>]` define un overlay que se fusiona sobre el archivo
+//! al seleccionarlo con `--profile`. Un perfil puede heredar de otro con
+//! `inherits = "..."`, incluidos los integrados `development` y `production`.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use tracing::info;
+
+use super::{diff_configs, layers, CoreConfig};
+
+/// Clave de un perfil con el nombre de su perfil padre
+pub const INHERITS_KEY: &str = "inherits";
+
+/// Overlay de un perfil integrado, derivado de `CoreConfig::development()`/`production()`
+pub fn builtin(name: &str) -> Option<Value> {
+    let preset = match name {
+        "development" => CoreConfig::development(),
+        "production" => CoreConfig::production(),
+        _ => return None,
+    };
+
+    let mut overlay = Value::Object(Map::new());
+    for change in diff_configs(&CoreConfig::default(), &preset) {
+        let mut current = &mut overlay;
+        for segment in change.path.split('.') {
+            if !current.is_object() {
+                *current = Value::Object(Map::new());
+            }
+            let Value::Object(object) = current else { break };
+            current = object.entry(segment.to_string()).or_insert(Value::Null);
+        }
+        *current = change.new;
+    }
+
+    Some(overlay)
+}
+
+/// Fusionar sobre `config` el perfil `name` y, antes, sus ancestros
+pub(crate) fn apply(config: &mut Value, name: &str) -> Result<()> {
+    let defined = config.get("profiles").cloned().unwrap_or(Value::Null);
+    let mut chain: Vec<(String, Value)> = Vec::new();
+    let mut next = Some(name.to_string());
+
+    while let Some(current) = next.take() {
+        if chain.iter().any(|(ancestor, _)| *ancestor == current) {
+            return Err(anyhow!("Herencia circular en el perfil {}", current));
+        }
+
+        let mut overlay = defined
+            .get(&current)
+            .cloned()
+            .or_else(|| builtin(&current))
+            .ok_or_else(|| anyhow!("Perfil de configuración no definido: {}", current))?;
+
+        if let Some(fields) = overlay.as_object_mut() {
+            next = match fields.remove(INHERITS_KEY) {
+                Some(Value::String(parent)) => Some(parent),
+                Some(other) => return Err(anyhow!("`{}` del perfil {} debe ser texto: {}", INHERITS_KEY, current, other)),
+                None => None,
+            };
+        }
+
+        chain.push((current, overlay));
+    }
+
+    let names: Vec<&str> = chain.iter().rev().map(|(name, _)| name.as_str()).collect();
+    info!("🎭 Aplicando perfil de configuración: {}", names.join(" → "));
+
+    for (_, overlay) in chain.into_iter().rev() {
+        layers::merge(config, overlay);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+
+    #[tokio::test]
+    async fn test_profile_inheritance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        std::fs::write(
+            &path,
+            r#"
+metrics_port = 9100
+
+[profiles.staging]
+inherits = "production"
+log_level = "info"
+
+[profiles.staging.consensus]
+replica_count = 7
+
+[profiles.canary]
+inherits = "staging"
+metrics_port = 9200
+
+[profiles.loop_a]
+inherits = "loop_b"
+
+[profiles.loop_b]
+inherits = "loop_a"
+"#,
+        )
+        .unwrap();
+
+        let with_profile = |name: &str| ConfigOverrides {
+            profile: Some(name.to_string()),
+            ..Default::default()
+        };
+
+        let canary = CoreConfig::read_file_with(&path, &with_profile("canary")).await.unwrap();
+        assert_eq!(canary.metrics_port, 9200);
+        assert_eq!(canary.log_level, "info");
+        assert_eq!(canary.consensus.replica_count, 7);
+        // Heredado del perfil integrado `production`
+        assert!(canary.security.threat_detection);
+        assert_eq!(canary.performance.cache_size_mb, 1024);
+        assert!(canary.profiles.contains_key("staging"));
+
+        // Sin perfil solo cuenta la base
+        let base = CoreConfig::read_file(&path).await.unwrap();
+        assert_eq!(base.metrics_port, 9100);
+        assert_eq!(base.log_level, CoreConfig::default().log_level);
+
+        let development = CoreConfig::read_file_with(&path, &with_profile("development")).await.unwrap();
+        assert_eq!(development.log_level, "debug");
+        assert_eq!(development.metrics_port, 9100);
+
+        assert!(CoreConfig::read_file_with(&path, &with_profile("loop_a")).await.is_err());
+        assert!(CoreConfig::read_file_with(&path, &with_profile("missing")).await.is_err());
+    }
+}
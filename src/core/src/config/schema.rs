@@ -0,0 +1,166 @@
+//! Esquema de la configuración
+//!
+//! JSON Schema de `CoreConfig` generado con schemars, y detección de claves
+//! desconocidas: sin modo estricto una errata como `replcia_count` se
+//! ignora y el campo cae silenciosamente a su valor por defecto.
+
+use anyhow::{anyhow, Result};
+use schemars::schema::RootSchema;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use super::{profiles, CoreConfig};
+
+/// JSON Schema de `CoreConfig`
+pub fn schema() -> RootSchema {
+    schemars::schema_for!(CoreConfig)
+}
+
+/// Rechazar (modo estricto) o advertir de las claves que no aparecen en el esquema
+pub(crate) fn check_unknown_keys(document: &Value, strict: bool) -> Result<()> {
+    let unknown = unknown_keys(document);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+
+    if strict {
+        return Err(anyhow!("Claves de configuración desconocidas: {}", unknown.join("; ")));
+    }
+
+    for key in &unknown {
+        warn!("⚠️  Clave de configuración desconocida ignorada: {}", key);
+    }
+    Ok(())
+}
+
+/// Rutas del documento sin campo correspondiente, con sugerencia si la hay
+pub fn unknown_keys(document: &Value) -> Vec<String> {
+    let schema = serde_json::to_value(schema()).unwrap_or_default();
+    let mut unknown = Vec::new();
+    walk(document, &schema, &schema, "", &mut unknown);
+
+    // Los perfiles son overlays de la propia configuración
+    let overlays = document.get("profiles").and_then(Value::as_object);
+    for (name, overlay) in overlays.into_iter().flatten() {
+        let mut overlay = overlay.clone();
+        if let Some(fields) = overlay.as_object_mut() {
+            fields.remove(profiles::INHERITS_KEY);
+        }
+        walk(&overlay, &schema, &schema, &format!("profiles.{}", name), &mut unknown);
+    }
+
+    unknown
+}
+
+fn walk(value: &Value, schema: &Value, root: &Value, path: &str, unknown: &mut Vec<String>) {
+    let Value::Object(fields) = value else { return };
+
+    let candidates = candidates(schema, root);
+    let properties: Vec<&Map<String, Value>> = candidates
+        .iter()
+        .filter_map(|candidate| candidate.get("properties").and_then(Value::as_object))
+        .collect();
+    let additional: Vec<&Value> = candidates
+        .iter()
+        .filter_map(|candidate| candidate.get("additionalProperties"))
+        .filter(|additional| !matches!(additional, Value::Bool(false)))
+        .collect();
+
+    // Valores libres (`serde_json::Value`): nada que comprobar
+    if properties.is_empty() && additional.is_empty() {
+        return;
+    }
+
+    for (key, field) in fields {
+        let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+
+        if let Some(field_schema) = properties.iter().find_map(|props| props.get(key)) {
+            walk(field, field_schema, root, &child, unknown);
+        } else if let Some(field_schema) = additional.first() {
+            walk(field, field_schema, root, &child, unknown);
+        } else {
+            let known = properties.iter().flat_map(|props| props.keys());
+            match closest(key, known) {
+                Some(suggestion) => unknown.push(format!("{} (¿quiso decir {}?)", child, suggestion)),
+                None => unknown.push(child),
+            }
+        }
+    }
+}
+
+/// Subesquemas efectivos tras resolver `$ref`, `allOf`, `anyOf` y `oneOf`
+fn candidates<'a>(schema: &'a Value, root: &'a Value) -> Vec<&'a Value> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return match reference.strip_prefix('#').and_then(|pointer| root.pointer(pointer)) {
+            Some(target) => candidates(target, root),
+            None => Vec::new(),
+        };
+    }
+
+    let mut found = vec![schema];
+    for combinator in ["allOf", "anyOf", "oneOf"] {
+        for sub in schema.get(combinator).and_then(Value::as_array).into_iter().flatten() {
+            found.extend(candidates(sub, root));
+        }
+    }
+    found
+}
+
+/// Campo conocido a distancia de edición ≤ 2
+fn closest<'a>(key: &str, known: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    known
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigManager, ConfigOverrides};
+
+    #[tokio::test]
+    async fn test_schema_and_strict_mode() {
+        let schema = serde_json::to_value(ConfigManager::schema()).unwrap();
+        assert!(schema.pointer("/properties/consensus").is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        std::fs::write(
+            &path,
+            "[consensus]\nreplcia_count = 5\n\n[nano_cores.instances.network_core.1]\nmax_connections = 10\n\n\
+             [profiles.staging]\ninherits = \"production\"\nlog_levl = \"info\"\n",
+        )
+        .unwrap();
+
+        // Sin modo estricto la errata solo se advierte
+        let lenient = CoreConfig::read_file(&path).await.unwrap();
+        assert_eq!(lenient.consensus.replica_count, 3);
+
+        let strict = ConfigOverrides {
+            strict: true,
+            ..Default::default()
+        };
+        let error = CoreConfig::read_file_with(&path, &strict).await.unwrap_err().to_string();
+        assert!(error.contains("consensus.replcia_count (¿quiso decir replica_count?)"));
+        assert!(error.contains("profiles.staging.log_levl (¿quiso decir log_level?)"));
+        assert!(!error.contains("instances") && !error.contains("inherits"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Periodo de observación tras un cambio
+//!
+//! Después de cada cambio aplicado se vigila la salud de los nano-núcleos
+//! durante una ventana; si la mayoría se degrada, se vuelve automáticamente
+//! a la versión anterior.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+use super::{ConfigChange, ConfigManager, ConfigUpdate};
+
+/// Fuente de salud de los nano-núcleos
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    /// Instancias degradadas y totales
+    async fn degraded_cores(&self) -> (usize, usize);
+}
+
+/// Opciones del periodo de observación
+#[derive(Clone)]
+pub struct SoakOptions {
+    /// Duración de la ventana tras aplicar un cambio
+    pub window_ms: u64,
+    /// Intervalo entre consultas de salud
+    pub check_interval_ms: u64,
+    pub probe: Arc<dyn HealthProbe>,
+}
+
+impl SoakOptions {
+    pub fn new(probe: Arc<dyn HealthProbe>) -> Self {
+        Self {
+            window_ms: 60_000,
+            check_interval_ms: 5_000,
+            probe,
+        }
+    }
+}
+
+/// Vigilar cada cambio aplicado y revertirlo si degrada a la mayoría
+///
+/// Un cambio nuevo durante la ventana reinicia la observación sobre él.
+pub async fn start_soak_monitor(manager: Arc<RwLock<ConfigManager>>, options: SoakOptions) -> JoinHandle<()> {
+    let mut updates = manager.read().await.subscribe();
+
+    info!("🩺 Observación de {}ms tras cada cambio de configuración", options.window_ms);
+
+    tokio::spawn(async move {
+        let mut pending = None;
+
+        loop {
+            let change = match pending.take() {
+                Some(change) => change,
+                None => match next_change(&mut updates).await {
+                    Some(change) => change,
+                    None => break,
+                },
+            };
+
+            tokio::select! {
+                degraded = observe(&options) => {
+                    if let Some((degraded, total)) = degraded {
+                        warn!(
+                            "🚨 {}/{} nano-núcleos degradados tras el cambio; revirtiendo a {}",
+                            degraded, total, change.rollback_version
+                        );
+                        if let Err(e) = manager.write().await.rollback(&change.rollback_version).await {
+                            error!("❌ Rollback automático fallido: {}", e);
+                        }
+                    }
+                }
+                next = next_change(&mut updates) => match next {
+                    Some(next) => pending = Some(next),
+                    None => break,
+                },
+            }
+        }
+    })
+}
+
+/// Siguiente cambio a observar; los rollbacks no se observan
+async fn next_change(updates: &mut broadcast::Receiver<ConfigUpdate>) -> Option<ConfigChange> {
+    loop {
+        match updates.recv().await {
+            Ok(update) if update.change.source == "rollback" => continue,
+            Ok(update) => return Some(update.change),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Consultar la salud durante la ventana; `Some` si la mayoría se degrada
+async fn observe(options: &SoakOptions) -> Option<(usize, usize)> {
+    let deadline = Instant::now() + Duration::from_millis(options.window_ms);
+    let interval = Duration::from_millis(options.check_interval_ms.max(1));
+
+    while Instant::now() < deadline {
+        tokio::time::sleep(interval).await;
+
+        let (degraded, total) = options.probe.degraded_cores().await;
+        if total > 0 && degraded * 2 > total {
+            return Some((degraded, total));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigChangeGate, CoreConfig, FieldChange};
+    use anyhow::Result;
+
+    struct Reject;
+
+    #[async_trait]
+    impl ConfigChangeGate for Reject {
+        async fn approve(&self, _config: &CoreConfig, _changes: &[FieldChange]) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    struct Degraded;
+
+    #[async_trait]
+    impl HealthProbe for Degraded {
+        async fn degraded_cores(&self) -> (usize, usize) {
+            (3, 4)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gate_and_soak_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        let mut manager = ConfigManager::new(path.to_str().unwrap()).await.unwrap();
+
+        let mut config = CoreConfig::default();
+        config.consensus.replica_count = 5;
+
+        // Un cambio no aprobado no se aplica
+        manager.set_gate(Arc::new(Reject));
+        assert!(manager.update_config(config.clone()).await.is_err());
+        assert_eq!(manager.get_config().consensus.replica_count, 3);
+
+        let manager = Arc::new(RwLock::new(ConfigManager::new(path.to_str().unwrap()).await.unwrap()));
+        let monitor = start_soak_monitor(
+            manager.clone(),
+            SoakOptions {
+                window_ms: 500,
+                check_interval_ms: 10,
+                probe: Arc::new(Degraded),
+            },
+        )
+        .await;
+
+        manager.write().await.update_config(config).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // La mayoría degradada provoca el rollback automático
+        assert_eq!(manager.read().await.get_config().consensus.replica_count, 3);
+        monitor.abort();
+    }
+}
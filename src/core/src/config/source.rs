@@ -0,0 +1,458 @@
+//! Orígenes remotos de configuración
+//!
+//! Un `ConfigSource` entrega el documento de configuración y avisa de sus
+//! cambios: archivo local (notify), etcd (watch v3) o Consul KV (blocking
+//! queries). `start_source_sync` aplica cada revisión vía `ConfigManager`
+//! para configurar una flota de nodos desde un punto central.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::{ConfigFormat, ConfigManager};
+use crate::communication::CognitiveFabric;
+use crate::util::{rng, Backoff};
+
+/// Espera máxima de un watch antes de volver a lanzarlo
+const WATCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Documento leído de un origen
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceDocument {
+    pub content: String,
+    /// Revisión monótona del documento (mtime, revisión de etcd, índice de Consul)
+    pub revision: u64,
+}
+
+/// Origen del documento de configuración
+#[async_trait]
+pub trait ConfigSource: Send + Sync {
+    /// Nombre registrado como `source` de los `ConfigChange`
+    fn name(&self) -> &str;
+
+    /// Formato del documento
+    fn format(&self) -> ConfigFormat;
+
+    /// Documento actual; `None` si aún no existe
+    async fn load(&self) -> Result<Option<SourceDocument>>;
+
+    /// Esperar un documento posterior a `revision`; `None` si el watch expira sin cambios
+    async fn watch(&self, revision: u64) -> Result<Option<SourceDocument>>;
+}
+
+/// Construir un origen a partir de `etcd://host:puerto/clave`,
+/// `consul://host:puerto/clave` o una ruta local
+pub fn source_from_url(url: &str) -> Result<Arc<dyn ConfigSource>> {
+    let remote = |rest: &str| -> Result<(String, String)> {
+        let (host, key) = rest
+            .split_once('/')
+            .filter(|(host, key)| !host.is_empty() && !key.is_empty())
+            .ok_or_else(|| anyhow!("Origen de configuración sin host o clave: {}", url))?;
+        Ok((format!("http://{}", host), key.to_string()))
+    };
+
+    if let Some(rest) = url.strip_prefix("etcd://") {
+        let (endpoint, key) = remote(rest)?;
+        Ok(Arc::new(EtcdSource::new(&endpoint, &format!("/{}", key))))
+    } else if let Some(rest) = url.strip_prefix("consul://") {
+        let (endpoint, key) = remote(rest)?;
+        Ok(Arc::new(ConsulSource::new(&endpoint, &key)))
+    } else {
+        Ok(Arc::new(FileSource::new(url.strip_prefix("file://").unwrap_or(url))))
+    }
+}
+
+/// Archivo local vigilado con notify
+pub struct FileSource {
+    pub path: PathBuf,
+    pub format: ConfigFormat,
+}
+
+impl FileSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            format: ConfigFormat::from_path(&path),
+            path,
+        }
+    }
+
+    /// mtime en nanosegundos; 0 si el archivo no existe
+    fn revision(&self) -> u64 {
+        std::fs::metadata(&self.path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |mtime| mtime.as_nanos() as u64)
+    }
+}
+
+#[async_trait]
+impl ConfigSource for FileSource {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn format(&self) -> ConfigFormat {
+        self.format
+    }
+
+    async fn load(&self) -> Result<Option<SourceDocument>> {
+        let revision = self.revision();
+        if revision == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(SourceDocument {
+            content: tokio::fs::read_to_string(&self.path).await?,
+            revision,
+        }))
+    }
+
+    async fn watch(&self, revision: u64) -> Result<Option<SourceDocument>> {
+        let file_name = self.path.file_name().map(|name| name.to_owned());
+        let dir = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if let Ok(event) = event {
+                let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|p| p.file_name() == file_name.as_deref());
+                if relevant {
+                    let _ = tx.send(());
+                }
+            }
+        })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        let deadline = tokio::time::Instant::now() + WATCH_TIMEOUT;
+        loop {
+            // Comprobar también antes del primer evento: el cambio pudo llegar antes del watch
+            if self.revision() > revision {
+                return self.load().await;
+            }
+
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(())) => continue,
+                Ok(None) | Err(_) => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Clave de etcd leída por la pasarela HTTP/JSON de la API v3
+pub struct EtcdSource {
+    pub endpoint: String,
+    pub key: String,
+    pub format: ConfigFormat,
+    client: reqwest::Client,
+}
+
+impl EtcdSource {
+    pub fn new(endpoint: &str, key: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            key: key.to_string(),
+            format: ConfigFormat::from_path(key),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Documento de un `mvccpb.KeyValue` (valor en base64, enteros como texto)
+    fn document(kv: &Value) -> Result<SourceDocument> {
+        let value = kv.get("value").and_then(Value::as_str).unwrap_or_default();
+        let content = String::from_utf8(STANDARD.decode(value)?)?;
+        let revision = kv
+            .get("mod_revision")
+            .and_then(|rev| rev.as_str().and_then(|rev| rev.parse().ok()).or_else(|| rev.as_u64()))
+            .ok_or_else(|| anyhow!("Respuesta de etcd sin mod_revision"))?;
+
+        Ok(SourceDocument { content, revision })
+    }
+}
+
+#[async_trait]
+impl ConfigSource for EtcdSource {
+    fn name(&self) -> &str {
+        "etcd"
+    }
+
+    fn format(&self) -> ConfigFormat {
+        self.format
+    }
+
+    async fn load(&self) -> Result<Option<SourceDocument>> {
+        let response: Value = self
+            .client
+            .post(format!("{}/v3/kv/range", self.endpoint))
+            .json(&json!({ "key": STANDARD.encode(&self.key) }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response.pointer("/kvs/0").map(Self::document).transpose()
+    }
+
+    async fn watch(&self, revision: u64) -> Result<Option<SourceDocument>> {
+        let request = json!({
+            "create_request": {
+                "key": STANDARD.encode(&self.key),
+                "start_revision": (revision + 1).to_string(),
+            }
+        });
+
+        let watch = async {
+            let mut response = self
+                .client
+                .post(format!("{}/v3/watch", self.endpoint))
+                .json(&request)
+                .send()
+                .await?
+                .error_for_status()?;
+
+            // Flujo de mensajes JSON separados por saltos de línea
+            let mut buffer = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                buffer.extend_from_slice(&chunk);
+
+                while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=end).collect();
+                    let message: Value = match serde_json::from_slice(&line) {
+                        Ok(message) => message,
+                        Err(_) => continue,
+                    };
+
+                    let events = message.pointer("/result/events").and_then(Value::as_array);
+                    for event in events.into_iter().flatten() {
+                        if event.get("type").and_then(Value::as_str) == Some("DELETE") {
+                            warn!("⚠️  Clave de configuración {} eliminada en etcd; se mantiene la actual", self.key);
+                        } else if let Some(kv) = event.get("kv") {
+                            return Self::document(kv).map(Some);
+                        }
+                    }
+                }
+            }
+
+            Ok(None)
+        };
+
+        tokio::time::timeout(WATCH_TIMEOUT, watch).await.unwrap_or(Ok(None))
+    }
+}
+
+/// Clave de Consul KV vigilada con blocking queries
+pub struct ConsulSource {
+    pub endpoint: String,
+    pub key: String,
+    pub format: ConfigFormat,
+    /// Token ACL (`CONSUL_HTTP_TOKEN`)
+    pub token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl ConsulSource {
+    pub fn new(endpoint: &str, key: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            key: key.trim_start_matches('/').to_string(),
+            format: ConfigFormat::from_path(key),
+            token: std::env::var("CONSUL_HTTP_TOKEN").ok(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Leer la clave; con `index` la petición se bloquea hasta un cambio
+    async fn get(&self, index: Option<u64>) -> Result<Option<SourceDocument>> {
+        let mut request = self.client.get(format!("{}/v1/kv/{}?raw", self.endpoint, self.key));
+        if let Some(index) = index {
+            request = request
+                .query(&[("index", index.to_string()), ("wait", format!("{}s", WATCH_TIMEOUT.as_secs()))])
+                .timeout(WATCH_TIMEOUT + Duration::from_secs(30));
+        }
+        if let Some(token) = &self.token {
+            request = request.header("X-Consul-Token", token);
+        }
+
+        let response = request.send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let response = response.error_for_status()?;
+        let revision = response
+            .headers()
+            .get("X-Consul-Index")
+            .and_then(|index| index.to_str().ok())
+            .and_then(|index| index.parse().ok())
+            .ok_or_else(|| anyhow!("Respuesta de Consul sin X-Consul-Index"))?;
+
+        Ok(Some(SourceDocument {
+            content: response.text().await?,
+            revision,
+        }))
+    }
+}
+
+#[async_trait]
+impl ConfigSource for ConsulSource {
+    fn name(&self) -> &str {
+        "consul"
+    }
+
+    fn format(&self) -> ConfigFormat {
+        self.format
+    }
+
+    async fn load(&self) -> Result<Option<SourceDocument>> {
+        self.get(None).await
+    }
+
+    async fn watch(&self, revision: u64) -> Result<Option<SourceDocument>> {
+        // Consul devuelve el mismo índice si la espera expira sin cambios
+        Ok(self.get(Some(revision)).await?.filter(|doc| doc.revision != revision))
+    }
+}
+
+/// Opciones de la sincronización con un origen
+#[derive(Clone)]
+pub struct SourceSyncOptions {
+    /// Espera mientras el documento no existe y tras un error; los errores seguidos la duplican
+    pub retry_ms: u64,
+    /// Fabric donde publicar los `ConfigChange`
+    pub fabric: Option<Arc<CognitiveFabric>>,
+}
+
+impl Default for SourceSyncOptions {
+    fn default() -> Self {
+        Self {
+            retry_ms: 5_000,
+            fabric: None,
+        }
+    }
+}
+
+/// Aplicar el documento del origen y cada una de sus revisiones posteriores
+pub fn start_source_sync(
+    manager: Arc<RwLock<ConfigManager>>,
+    source: Arc<dyn ConfigSource>,
+    options: SourceSyncOptions,
+) -> JoinHandle<()> {
+    info!("🌐 Sincronizando configuración desde el origen {}", source.name());
+
+    tokio::spawn(async move {
+        let retry = Duration::from_millis(options.retry_ms.max(1));
+        // Los errores repetidos espacian la reconexión hasta 8 veces `retry_ms`
+        let mut backoff = Backoff::new(retry).with_max(retry.saturating_mul(8)).with_jitter(0.5);
+        let mut revision = None;
+
+        loop {
+            let document = match revision {
+                None => source.load().await,
+                Some(revision) => source.watch(revision).await,
+            };
+
+            let document = match document {
+                Ok(Some(document)) => document,
+                Ok(None) if revision.is_some() => continue,
+                Ok(None) => {
+                    debug!("🌐 El origen {} aún no tiene configuración", source.name());
+                    tokio::time::sleep(rng::jitter(retry, 0.2)).await;
+                    continue;
+                }
+                Err(e) => {
+                    warn!("⚠️  Error leyendo configuración desde {}: {}", source.name(), e);
+                    tokio::time::sleep(backoff.next_delay()).await;
+                    continue;
+                }
+            };
+
+            // Una revisión rechazada no se reintenta hasta que cambie
+            backoff.reset();
+            revision = Some(document.revision);
+
+            let result = manager
+                .write()
+                .await
+                .apply_source_document(source.name(), &document.content, source.format())
+                .await;
+
+            match result {
+                Ok(Some(change)) => {
+                    info!("🌐 Revisión {} de {} aplicada", document.revision, source.name());
+                    if let Some(fabric) = &options.fabric {
+                        if let Err(e) = change.publish(fabric).await {
+                            warn!("⚠️  No se pudo publicar el cambio de configuración: {}", e);
+                        }
+                    }
+                }
+                Ok(None) => debug!("🌐 Revisión {} de {} sin cambios", document.revision, source.name()),
+                Err(e) => warn!(
+                    "⚠️  Revisión {} de {} rechazada: {}",
+                    document.revision, source.name(), e
+                ),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CoreConfig;
+
+    #[tokio::test]
+    async fn test_file_source_sync() {
+        assert_eq!(source_from_url("etcd://etcd:2379/saai/core.yaml").unwrap().format(), ConfigFormat::Yaml);
+        assert_eq!(source_from_url("consul://consul:8500/saai/core").unwrap().name(), "consul");
+        assert!(source_from_url("etcd://etcd:2379").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let central = dir.path().join("central/core.json");
+        let mut config = CoreConfig::default();
+        config.consensus.replica_count = 5;
+        config.save(&central).await.unwrap();
+
+        let local = dir.path().join("core.toml");
+        let manager = Arc::new(RwLock::new(ConfigManager::new(local.to_str().unwrap()).await.unwrap()));
+        let source = source_from_url(central.to_str().unwrap()).unwrap();
+        let sync = start_source_sync(manager.clone(), source, SourceSyncOptions::default());
+
+        let replicas = |expected: usize| {
+            let manager = manager.clone();
+            async move {
+                for _ in 0..100 {
+                    if manager.read().await.get_config().consensus.replica_count == expected {
+                        return true;
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                false
+            }
+        };
+
+        assert!(replicas(5).await);
+        assert_eq!(manager.read().await.get_version_history()[0].changes.len(), 1);
+
+        // Los cambios posteriores llegan por el watch
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        config.consensus.replica_count = 7;
+        config.save(&central).await.unwrap();
+        assert!(replicas(7).await);
+
+        sync.abort();
+    }
+}
@@ -0,0 +1,155 @@
+//! Propagación de cambios de configuración
+//!
+//! `ConfigManager` difunde cada cambio aplicado por un canal broadcast; los
+//! subsistemas que implementan `ConfigSubscriber` reciben solo los campos de
+//! las secciones que declaran y los aplican en caliente.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use super::{ConfigChange, CoreConfig, FieldChange};
+
+/// Sección de primer nivel de `CoreConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ConfigSection {
+    /// `nats_url`, `metrics_port`, `log_level`
+    General,
+    Consensus,
+    NanoCores,
+    Security,
+    Performance,
+}
+
+impl ConfigSection {
+    /// Sección a la que pertenece una ruta con puntos
+    pub fn of(path: &str) -> Self {
+        match path.split('.').next().unwrap_or(path) {
+            "consensus" => Self::Consensus,
+            "nano_cores" => Self::NanoCores,
+            "security" => Self::Security,
+            "performance" => Self::Performance,
+            _ => Self::General,
+        }
+    }
+}
+
+/// Cambio difundido a los suscriptores, con la configuración resultante
+#[derive(Debug, Clone)]
+pub struct ConfigUpdate {
+    pub change: ConfigChange,
+    pub config: Arc<CoreConfig>,
+}
+
+/// Subsistema que se reconfigura sin reiniciar el proceso
+#[async_trait]
+pub trait ConfigSubscriber: Send + Sync {
+    /// Nombre para logs
+    fn subscriber_name(&self) -> &str;
+
+    /// Secciones cuyos cambios interesan al suscriptor
+    fn sections(&self) -> &[ConfigSection];
+
+    /// Aplicar la nueva configuración; `changes` contiene solo sus secciones
+    async fn on_config_change(&self, config: &CoreConfig, changes: &[FieldChange]) -> Result<()>;
+}
+
+/// Despachar las actualizaciones del canal a un suscriptor
+pub(crate) fn dispatch(
+    subscriber: Arc<dyn ConfigSubscriber>,
+    mut updates: broadcast::Receiver<ConfigUpdate>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let update = match updates.recv().await {
+                Ok(update) => update,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "⚠️  {} perdió {} cambios de configuración",
+                        subscriber.subscriber_name(), skipped
+                    );
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let changes: Vec<FieldChange> = update
+                .change
+                .changes
+                .iter()
+                .filter(|change| subscriber.sections().contains(&change.section()))
+                .cloned()
+                .collect();
+
+            if changes.is_empty() {
+                continue;
+            }
+
+            match subscriber.on_config_change(&update.config, &changes).await {
+                Ok(()) => debug!(
+                    "📋 {} aplicó {} cambios de configuración",
+                    subscriber.subscriber_name(), changes.len()
+                ),
+                Err(e) => error!(
+                    "❌ {} no pudo aplicar la configuración: {}",
+                    subscriber.subscriber_name(), e
+                ),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigManager;
+    use std::sync::Mutex;
+
+    struct Recorder {
+        seen: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ConfigSubscriber for Recorder {
+        fn subscriber_name(&self) -> &str {
+            "recorder"
+        }
+
+        fn sections(&self) -> &[ConfigSection] {
+            &[ConfigSection::Consensus]
+        }
+
+        async fn on_config_change(&self, _config: &CoreConfig, changes: &[FieldChange]) -> Result<()> {
+            self.seen
+                .lock()
+                .unwrap()
+                .extend(changes.iter().map(|change| change.path.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_receives_its_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        let mut manager = ConfigManager::new(path.to_str().unwrap()).await.unwrap();
+
+        let recorder = Arc::new(Recorder {
+            seen: Mutex::new(Vec::new()),
+        });
+        let handle = manager.register_subscriber(recorder.clone());
+
+        let mut config = manager.get_config().clone();
+        config.consensus.replica_count = 5;
+        config.log_level = "debug".to_string();
+        manager.update_config(config).await.unwrap();
+
+        drop(manager);
+        handle.await.unwrap();
+        assert_eq!(*recorder.seen.lock().unwrap(), vec!["consensus.replica_count"]);
+    }
+}
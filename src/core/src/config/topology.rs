@@ -0,0 +1,290 @@
+//! Topología de CPU y memoria NUMA
+//!
+//! Sockets, núcleos, nodos NUMA y cachés leídos de sysfs en Linux; en otras
+//! plataformas solo se informa el número de CPUs lógicas. HardwareCore la
+//! expone en `CpuInfo` para que la fijación de hilos y el dimensionado de
+//! cachés puedan tener en cuenta la localidad de memoria.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Topología de los procesadores
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTopology {
+    pub sockets: usize,
+    pub physical_cores: usize,
+    pub logical_cpus: usize,
+    /// Socket de cada CPU lógica, por índice de CPU
+    pub cpu_sockets: Vec<usize>,
+    pub numa_nodes: Vec<NumaNode>,
+    pub caches: Vec<CacheInfo>,
+}
+
+/// Nodo NUMA y sus CPUs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub id: usize,
+    pub cpus: Vec<usize>,
+}
+
+/// Memoria de un nodo NUMA, en bytes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumaMemory {
+    pub node: usize,
+    pub total: u64,
+    pub free: u64,
+    pub used: u64,
+}
+
+/// Nivel de caché (todas las instancias de un nivel y tipo son iguales)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub level: u8,
+    /// `Data`, `Instruction` o `Unified`
+    pub kind: String,
+    /// Tamaño de cada instancia, en bytes
+    pub size: u64,
+    pub instances: usize,
+    /// CPUs lógicas que comparten cada instancia
+    pub shared_cpus: usize,
+}
+
+impl CpuTopology {
+    pub fn detect() -> Self {
+        #[cfg(target_os = "linux")]
+        if let Some(topology) = Self::detect_at(Path::new("/sys/devices/system")) {
+            return topology;
+        }
+        Self::fallback()
+    }
+
+    /// Un socket y un nodo con todas las CPUs
+    fn fallback() -> Self {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self {
+            sockets: 1,
+            physical_cores: cpus,
+            logical_cpus: cpus,
+            cpu_sockets: vec![0; cpus],
+            numa_nodes: vec![NumaNode {
+                id: 0,
+                cpus: (0..cpus).collect(),
+            }],
+            caches: Vec::new(),
+        }
+    }
+
+    /// Topología de un sysfs montado en `root` (`/sys/devices/system`)
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn detect_at(root: &Path) -> Option<Self> {
+        let cpus = numbered_dirs(&root.join("cpu"), "cpu");
+        if cpus.is_empty() {
+            return None;
+        }
+
+        let mut cpu_sockets = vec![0; cpus.last().map_or(0, |(cpu, _)| cpu + 1)];
+        let mut cores = BTreeSet::new();
+        let mut caches: BTreeMap<(u8, String), (u64, BTreeSet<String>)> = BTreeMap::new();
+
+        for (cpu, dir) in &cpus {
+            let socket = read_number(&dir.join("topology/physical_package_id")).unwrap_or(0) as usize;
+            let core = read_number(&dir.join("topology/core_id")).unwrap_or(*cpu as u64);
+            cpu_sockets[*cpu] = socket;
+            cores.insert((socket, core));
+
+            for (_, index) in numbered_dirs(&dir.join("cache"), "index") {
+                let (Some(level), Some(kind), Some(size), Some(shared)) = (
+                    read_number(&index.join("level")),
+                    read(&index.join("type")),
+                    read(&index.join("size")).and_then(|size| parse_size(&size)),
+                    read(&index.join("shared_cpu_list")),
+                ) else {
+                    continue;
+                };
+                caches.entry((level as u8, kind)).or_insert((size, BTreeSet::new())).1.insert(shared);
+            }
+        }
+
+        let numa_nodes = numbered_dirs(&root.join("node"), "node")
+            .into_iter()
+            .map(|(id, dir)| NumaNode {
+                id,
+                cpus: read(&dir.join("cpulist")).map(|list| parse_cpu_list(&list)).unwrap_or_default(),
+            })
+            .collect();
+
+        Some(Self {
+            sockets: cpu_sockets.iter().collect::<BTreeSet<_>>().len(),
+            physical_cores: cores.len(),
+            logical_cpus: cpus.len(),
+            cpu_sockets,
+            numa_nodes,
+            caches: caches
+                .into_iter()
+                .map(|((level, kind), (size, instances))| CacheInfo {
+                    level,
+                    kind,
+                    size,
+                    shared_cpus: instances.iter().next().map_or(1, |list| parse_cpu_list(list).len()),
+                    instances: instances.len(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Nodo NUMA de una CPU lógica
+    pub fn node_of_cpu(&self, cpu: usize) -> Option<usize> {
+        self.numa_nodes.iter().find(|node| node.cpus.contains(&cpu)).map(|node| node.id)
+    }
+
+    /// Caché de último nivel
+    pub fn last_level_cache(&self) -> Option<&CacheInfo> {
+        self.caches.iter().filter(|cache| cache.kind != "Instruction").max_by_key(|cache| cache.level)
+    }
+
+    /// Uso medio por socket a partir del uso de cada CPU lógica
+    pub fn usage_per_socket(&self, usage_per_cpu: &[f32]) -> Vec<f32> {
+        let mut totals = vec![(0.0f32, 0usize); self.sockets.max(1)];
+        for (cpu, usage) in usage_per_cpu.iter().enumerate() {
+            let socket = self.cpu_sockets.get(cpu).copied().unwrap_or(0).min(totals.len() - 1);
+            totals[socket].0 += usage;
+            totals[socket].1 += 1;
+        }
+        totals.into_iter().map(|(sum, count)| if count > 0 { sum / count as f32 } else { 0.0 }).collect()
+    }
+}
+
+/// Memoria de cada nodo NUMA; vacío fuera de Linux
+pub fn numa_memory() -> Vec<NumaMemory> {
+    if cfg!(target_os = "linux") {
+        numa_memory_at(Path::new("/sys/devices/system"))
+    } else {
+        Vec::new()
+    }
+}
+
+/// `node*/meminfo`: "Node 0 MemTotal:  16318588 kB"
+fn numa_memory_at(root: &Path) -> Vec<NumaMemory> {
+    numbered_dirs(&root.join("node"), "node")
+        .into_iter()
+        .filter_map(|(node, dir)| {
+            let meminfo = read(&dir.join("meminfo"))?;
+            let field = |name: &str| {
+                meminfo.lines().find_map(|line| {
+                    let mut parts = line.split_whitespace().skip(2);
+                    (parts.next()? == name).then(|| parts.next()?.parse::<u64>().ok()).flatten().map(|kb| kb * 1024)
+                })
+            };
+            let total = field("MemTotal:")?;
+            let free = field("MemFree:").unwrap_or(0);
+            Some(NumaMemory {
+                node,
+                total,
+                free,
+                used: field("MemUsed:").unwrap_or(total.saturating_sub(free)),
+            })
+        })
+        .collect()
+}
+
+/// Subdirectorios `<prefix>N`, ordenados por N
+fn numbered_dirs(dir: &Path, prefix: &str) -> Vec<(usize, std::path::PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let number = entry.file_name().to_str()?.strip_prefix(prefix)?.parse().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok().map(|value| value.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    read(path)?.parse().ok()
+}
+
+/// `0-3,8,10-11`
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .filter_map(|range| match range.trim().split_once('-') {
+            Some((start, end)) => Some((start.parse().ok()?..=end.parse().ok()?).collect::<Vec<usize>>()),
+            None => Some(vec![range.trim().parse().ok()?]),
+        })
+        .flatten()
+        .collect()
+}
+
+/// `48K`, `2048K`, `32M`
+fn parse_size(size: &str) -> Option<u64> {
+    let (number, unit) = size.split_at(size.find(|c: char| !c.is_ascii_digit()).unwrap_or(size.len()));
+    let multiplier = match unit {
+        "" => 1,
+        "K" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some(number.parse::<u64>().ok()? * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_topology_from_sysfs() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        // 2 sockets x 2 núcleos, sin SMT
+        for cpu in 0..4 {
+            let socket = cpu / 2;
+            write(&format!("cpu/cpu{}/topology/physical_package_id", cpu), &format!("{}\n", socket));
+            write(&format!("cpu/cpu{}/topology/core_id", cpu), &format!("{}\n", cpu % 2));
+            write(&format!("cpu/cpu{}/cache/index0/level", cpu), "1\n");
+            write(&format!("cpu/cpu{}/cache/index0/type", cpu), "Data\n");
+            write(&format!("cpu/cpu{}/cache/index0/size", cpu), "48K\n");
+            write(&format!("cpu/cpu{}/cache/index0/shared_cpu_list", cpu), &format!("{}\n", cpu));
+            write(&format!("cpu/cpu{}/cache/index3/level", cpu), "3\n");
+            write(&format!("cpu/cpu{}/cache/index3/type", cpu), "Unified\n");
+            write(&format!("cpu/cpu{}/cache/index3/size", cpu), "32M\n");
+            write(&format!("cpu/cpu{}/cache/index3/shared_cpu_list", cpu), if socket == 0 { "0-1\n" } else { "2-3\n" });
+        }
+        write("cpu/cpufreq/policy0/scaling_governor", "schedutil\n");
+        write("node/node0/cpulist", "0-1\n");
+        write("node/node1/cpulist", "2-3\n");
+        write("node/node0/meminfo", "Node 0 MemTotal:       8000 kB\nNode 0 MemFree:        3000 kB\nNode 0 MemUsed:        5000 kB\n");
+        write("node/node1/meminfo", "Node 1 MemTotal:       8000 kB\nNode 1 MemFree:        7000 kB\n");
+
+        let topology = CpuTopology::detect_at(root.path()).unwrap();
+        assert_eq!(topology.sockets, 2);
+        assert_eq!(topology.physical_cores, 4);
+        assert_eq!(topology.logical_cpus, 4);
+        assert_eq!(topology.node_of_cpu(3), Some(1));
+        assert_eq!(topology.caches.len(), 2);
+        let llc = topology.last_level_cache().unwrap();
+        assert_eq!((llc.level, llc.size, llc.instances, llc.shared_cpus), (3, 32 << 20, 2, 2));
+        assert_eq!(topology.usage_per_socket(&[10.0, 30.0, 50.0, 70.0]), vec![20.0, 60.0]);
+
+        let memory = numa_memory_at(root.path());
+        assert_eq!(memory[0], NumaMemory { node: 0, total: 8000 * 1024, free: 3000 * 1024, used: 5000 * 1024 });
+        assert_eq!(memory[1].used, 1000 * 1024);
+
+        assert_eq!(parse_cpu_list("0-2,5"), vec![0, 1, 2, 5]);
+        assert!(CpuTopology::detect_at(&root.path().join("missing")).is_none());
+    }
+}
@@ -0,0 +1,669 @@
+//! Informe de validación
+//!
+//! Reúne todos los errores y advertencias de una configuración, cada uno con
+//! la ruta afectada y, cuando existe, una corrección segura que `auto_fix()`
+//! puede aplicar.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fmt;
+use tracing::{info, warn};
+
+use super::{hardware, CoreConfig, FieldChange};
+use crate::nano_cores::os_core::OSCommand;
+use crate::nano_cores::watchdog::ProbeKind;
+use crate::nano_cores::{firewall, scheduler, shaping, NanoCoreType};
+
+/// Gravedad de un problema de configuración
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    /// Impide aplicar la configuración
+    Error,
+    /// Aplicable, pero probablemente no deseado
+    Warning,
+}
+
+/// Problema detectado en un campo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+    /// Valor corregido sugerido
+    pub suggestion: Option<Value>,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (sugerido: {})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resultado completo de validar una configuración
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    fn error(&mut self, path: &str, message: impl Into<String>, suggestion: Option<Value>) {
+        self.push(Severity::Error, path, message.into(), suggestion);
+    }
+
+    fn warning(&mut self, path: &str, message: impl Into<String>, suggestion: Option<Value>) {
+        self.push(Severity::Warning, path, message.into(), suggestion);
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, message: String, suggestion: Option<Value>) {
+        self.issues.push(ValidationIssue {
+            severity,
+            path: path.to_string(),
+            message,
+            suggestion,
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Sin errores (puede tener advertencias)
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Convertir en error con todos los errores encontrados
+    pub fn into_result(self) -> Result<()> {
+        let errors: Vec<String> = self.errors().map(ToString::to_string).collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Configuración inválida: {}", errors.join("; ")))
+        }
+    }
+}
+
+impl CoreConfig {
+    /// Validar todos los campos sin detenerse en el primer error
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let cpu_count = num_cpus::get();
+        let memory_mb = hardware::available_memory().ok().map(|bytes| bytes / 1024 / 1024);
+
+        // General
+        if self.nats_url.is_empty() {
+            report.error("nats_url", "URL de NATS no puede estar vacía", None);
+        }
+
+        if self.metrics_port == 0 {
+            report.error("metrics_port", "Puerto de métricas debe ser mayor que 0", Some(json!(9090)));
+        }
+
+        // Consenso
+        let replicas = self.consensus.replica_count;
+        if replicas < 3 {
+            report.error("consensus.replica_count", "Número de réplicas debe ser al menos 3", Some(json!(3)));
+        } else if replicas.is_multiple_of(2) {
+            report.warning(
+                "consensus.replica_count",
+                "Un número par de réplicas no mejora la tolerancia a fallos y permite empates",
+                Some(json!(replicas + 1)),
+            );
+        }
+
+        if self.consensus.byzantine_tolerance <= 0.0 || self.consensus.byzantine_tolerance >= 0.5 {
+            report.error(
+                "consensus.byzantine_tolerance",
+                "Tolerancia bizantina debe estar entre 0.0 y 0.5",
+                Some(json!(0.33)),
+            );
+        }
+
+        // Límites de recursos
+        let limits = &self.nano_cores.os_core.resource_limits;
+        if limits.max_cpu_percent <= 0.0 || limits.max_cpu_percent > 100.0 {
+            report.error(
+                "nano_cores.os_core.resource_limits.max_cpu_percent",
+                "Límite de CPU debe estar entre 0 y 100",
+                Some(json!(80.0)),
+            );
+        }
+
+        // Misma corrección que `optimize_for_hardware`: 80% de la RAM, máximo 8GB
+        let safe_memory_mb = memory_mb.map(|mb| (mb * 80 / 100).clamp(1, 8192));
+        if limits.max_memory_mb == 0 {
+            report.error(
+                "nano_cores.os_core.resource_limits.max_memory_mb",
+                "Límite de memoria debe ser mayor que 0",
+                Some(json!(safe_memory_mb.unwrap_or(4096))),
+            );
+        } else if let Some(memory_mb) = memory_mb.filter(|mb| limits.max_memory_mb > *mb) {
+            report.warning(
+                "nano_cores.os_core.resource_limits.max_memory_mb",
+                format!("Límite de memoria ({} MB) mayor que la RAM disponible ({} MB)", limits.max_memory_mb, memory_mb),
+                safe_memory_mb.map(|mb| json!(mb)),
+            );
+        }
+
+        if limits.max_pids == 0 {
+            report.error(
+                "nano_cores.os_core.resource_limits.max_pids",
+                "Límite de procesos debe ser mayor que 0",
+                Some(json!(4096)),
+            );
+        }
+
+        // Monitor eBPF
+        let os_core = &self.nano_cores.os_core;
+        if os_core.enforce_process_whitelist && !os_core.enable_ebpf {
+            report.warning(
+                "nano_cores.os_core.enforce_process_whitelist",
+                "La lista blanca solo se aplica con el monitor eBPF (enable_ebpf)",
+                Some(json!(false)),
+            );
+        }
+        if os_core.enable_ebpf && os_core.ebpf_object.is_empty() {
+            report.error(
+                "nano_cores.os_core.ebpf_object",
+                "Ruta del objeto eBPF vacía",
+                Some(json!("/usr/lib/saai/saai-ebpf.o")),
+            );
+        }
+
+        // Comandos programados
+        let mut schedule_ids = BTreeSet::new();
+        for (i, schedule) in os_core.schedules.iter().enumerate() {
+            let path = format!("nano_cores.os_core.schedules.{}", i);
+            if !schedule_ids.insert(schedule.id.as_str()) {
+                report.error(&format!("{}.id", path), format!("Programación duplicada: {}", schedule.id), None);
+            }
+            if let Err(e) = scheduler::parse_cron(&schedule.cron) {
+                report.error(&format!("{}.cron", path), e.to_string(), None);
+            }
+            if let Err(e) = serde_json::from_value::<OSCommand>(schedule.command.clone()) {
+                report.error(&format!("{}.command", path), format!("Comando OSCore inválido: {}", e), None);
+            }
+        }
+
+        // Nano-núcleos
+        if self.nano_cores.hardware_core.temperature_threshold <= 0.0 {
+            report.error(
+                "nano_cores.hardware_core.temperature_threshold",
+                "Umbral de temperatura debe ser mayor que 0",
+                Some(json!(80.0)),
+            );
+        }
+
+        let hardware_core = &self.nano_cores.hardware_core;
+        let percentages = [
+            ("cpu_usage_threshold".to_string(), hardware_core.cpu_usage_threshold, 90.0),
+            ("memory_usage_threshold".to_string(), hardware_core.memory_usage_threshold, 85.0),
+            ("disk_usage_threshold".to_string(), hardware_core.disk_usage_threshold, 95.0),
+        ];
+        let disks = hardware_core
+            .disk_thresholds
+            .iter()
+            .map(|(disk, threshold)| (format!("disk_thresholds.{}", disk), *threshold, hardware_core.disk_usage_threshold));
+        for (field, threshold, default) in percentages.into_iter().chain(disks) {
+            if threshold <= 0.0 || threshold > 100.0 {
+                report.error(
+                    &format!("nano_cores.hardware_core.{}", field),
+                    "El umbral debe ser un porcentaje entre 0 y 100",
+                    Some(json!(default)),
+                );
+            }
+        }
+
+        if hardware_core.alert_hysteresis < 0.0 {
+            report.error(
+                "nano_cores.hardware_core.alert_hysteresis",
+                "La histéresis de las alertas no puede ser negativa",
+                Some(json!(5.0)),
+            );
+        }
+
+        if self.nano_cores.hardware_core.enable_smart &&self.nano_cores.hardware_core.smart_interval_secs == 0 {
+            report.error(
+                "nano_cores.hardware_core.smart_interval_secs",
+                "El intervalo de lectura SMART debe ser mayor que 0",
+                Some(json!(1800)),
+            );
+        }
+
+        if self.nano_cores.network_core.max_connections == 0 {
+            report.error(
+                "nano_cores.network_core.max_connections",
+                "Máximo de conexiones debe ser mayor que 0",
+                Some(json!(cpu_count * 1000)),
+            );
+        }
+
+        let throughput = &self.nano_cores.network_core.throughput;
+        if throughput.max_bandwidth_mbps <= 0.0 || !throughput.max_bandwidth_mbps.is_finite() {
+            report.error(
+                "nano_cores.network_core.throughput.max_bandwidth_mbps",
+                "El ancho de banda máximo de las pruebas debe ser mayor que 0",
+                Some(json!(100.0)),
+            );
+        }
+        if throughput.max_duration_secs == 0 || throughput.default_duration_secs > throughput.max_duration_secs {
+            report.error(
+                "nano_cores.network_core.throughput.max_duration_secs",
+                "La duración máxima de las pruebas debe ser mayor que 0 y no menor que la duración por defecto",
+                Some(json!(throughput.default_duration_secs.max(30))),
+            );
+        }
+        if throughput.listen && throughput.listen_addr.parse::<std::net::SocketAddr>().is_err() {
+            report.error(
+                "nano_cores.network_core.throughput.listen_addr",
+                "Dirección de escucha inválida (se espera ip:puerto)",
+                Some(json!("0.0.0.0:5201")),
+            );
+        }
+
+        let capture = &self.nano_cores.network_core.capture;
+        if capture.max_duration_secs == 0 || capture.default_duration_secs > capture.max_duration_secs {
+            report.error(
+                "nano_cores.network_core.capture.max_duration_secs",
+                "La duración máxima de captura debe ser mayor que 0 y no menor que la duración por defecto",
+                Some(json!(capture.default_duration_secs.max(60))),
+            );
+        }
+        if capture.max_packets == 0 || capture.default_max_packets > capture.max_packets {
+            report.error(
+                "nano_cores.network_core.capture.max_packets",
+                "El límite de paquetes debe ser mayor que 0 y no menor que el límite por defecto",
+                Some(json!(capture.default_max_packets.max(100_000))),
+            );
+        }
+
+        let bandwidth = &self.nano_cores.network_core.bandwidth;
+        if bandwidth.sample_interval_secs == 0 || bandwidth.retention_secs < bandwidth.sample_interval_secs {
+            report.error(
+                "nano_cores.network_core.bandwidth.retention_secs",
+                "El intervalo de muestreo debe ser mayor que 0 y no superar la retención",
+                Some(json!(bandwidth.sample_interval_secs.max(1) * 90)),
+            );
+        }
+        if bandwidth.default_window_secs > bandwidth.retention_secs {
+            report.warning(
+                "nano_cores.network_core.bandwidth.default_window_secs",
+                "La ventana por defecto supera la retención y se recortará",
+                Some(json!(bandwidth.retention_secs)),
+            );
+        }
+
+        let families = &self.nano_cores.network_core.address_family;
+        if !families.ipv4 && !families.ipv6 {
+            report.error(
+                "nano_cores.network_core.address_family.ipv4",
+                "Debe haber al menos una familia de direcciones habilitada",
+                Some(json!(true)),
+            );
+        } else if !families.allows(families.prefer) {
+            report.warning(
+                "nano_cores.network_core.address_family.prefer",
+                "La familia preferida está deshabilitada; se usará la otra",
+                families.preferred().map(|family| json!(family)),
+            );
+        }
+
+        let link_events = &self.nano_cores.network_core.link_events;
+        if link_events.enabled && link_events.poll_interval_secs == 0 {
+            report.error(
+                "nano_cores.network_core.link_events.poll_interval_secs",
+                "El intervalo de sondeo de enlaces debe ser mayor que 0",
+                Some(json!(3)),
+            );
+        }
+
+        let watchdog = &self.nano_cores.network_core.watchdog;
+        if watchdog.enabled {
+            if watchdog.interval_secs == 0 || watchdog.timeout_ms == 0 || watchdog.window == 0 {
+                report.error(
+                    "nano_cores.network_core.watchdog.interval_secs",
+                    "El intervalo, el timeout y la ventana del watchdog deben ser mayores que 0",
+                    Some(json!(15)),
+                );
+            } else if watchdog.timeout_ms >= watchdog.interval_secs * 1000 {
+                report.warning(
+                    "nano_cores.network_core.watchdog.timeout_ms",
+                    "Un timeout mayor que el intervalo solapa las rondas del watchdog",
+                    Some(json!(watchdog.interval_secs * 1000 / 2)),
+                );
+            }
+            for (path, score) in [("degraded_score", watchdog.degraded_score), ("remediation_score", watchdog.remediation_score)] {
+                if !(0.0..=1.0).contains(&score) {
+                    report.error(
+                        &format!("nano_cores.network_core.watchdog.{}", path),
+                        "La puntuación debe estar entre 0 y 1",
+                        Some(json!(0.5)),
+                    );
+                }
+            }
+            if watchdog.remediation && watchdog.remediation_score > watchdog.degraded_score {
+                report.warning(
+                    "nano_cores.network_core.watchdog.remediation_score",
+                    "Se propondrían remediaciones sin que el núcleo llegue a degradarse",
+                    Some(json!(watchdog.degraded_score)),
+                );
+            }
+            for (i, target) in watchdog.targets.iter().enumerate() {
+                let path = format!("nano_cores.network_core.watchdog.targets.{}", i);
+                if target.name.is_empty() || (target.address.is_empty() && target.probe != ProbeKind::Nats) {
+                    report.error(&path, "Cada destino del watchdog necesita nombre y dirección host:puerto", None);
+                }
+                if target.weight <= 0.0 || !target.weight.is_finite() {
+                    report.error(&format!("{}.weight", path), "El peso de un destino debe ser mayor que 0", Some(json!(1.0)));
+                }
+            }
+        }
+
+        let dns = &self.nano_cores.network_core.dns_monitor;
+        if dns.enabled {
+            if dns.probe_names.is_empty() {
+                report.error(
+                    "nano_cores.network_core.dns_monitor.probe_names",
+                    "El monitor DNS necesita al menos un nombre de sondeo",
+                    Some(json!(["example.com"])),
+                );
+            }
+            if dns.interval_secs == 0 || dns.timeout_ms == 0 {
+                report.error(
+                    "nano_cores.network_core.dns_monitor.interval_secs",
+                    "El intervalo y el timeout del monitor DNS deben ser mayores que 0",
+                    Some(json!(60)),
+                );
+            } else if dns.timeout_ms >= dns.interval_secs * 1000 {
+                report.warning(
+                    "nano_cores.network_core.dns_monitor.timeout_ms",
+                    "Un timeout mayor que el intervalo solapa las rondas de sondeo",
+                    Some(json!(dns.interval_secs * 1000 / 2)),
+                );
+            }
+        }
+
+        let qos = &self.nano_cores.network_core.qos;
+        let mut class_names = BTreeSet::new();
+        for (i, class) in qos.traffic_classes.iter().enumerate() {
+            let path = format!("nano_cores.network_core.qos.traffic_classes.{}", i);
+            if !class_names.insert(class.name.as_str()) {
+                report.error(&format!("{}.name", path), format!("Clase de tráfico duplicada: {}", class.name), None);
+            }
+            if class.max_bandwidth < class.bandwidth_guarantee {
+                report.error(
+                    &format!("{}.max_bandwidth", path),
+                    "El ancho de banda máximo no puede ser menor que la garantía",
+                    Some(json!(class.bandwidth_guarantee)),
+                );
+            }
+            if !(class.latency_target_ms > 0.0 && class.latency_target_ms <= shaping::MAX_LATENCY_TARGET_MS) {
+                report.error(
+                    &format!("{}.latency_target_ms", path),
+                    format!("El objetivo de latencia debe estar entre 0 y {} ms", shaping::MAX_LATENCY_TARGET_MS),
+                    Some(json!(10.0)),
+                );
+            }
+        }
+        let guaranteed_mbit: u64 = qos.traffic_classes.iter().map(|class| class.bandwidth_guarantee * 8 / 1_000_000).sum();
+        if let Some(link_mbps) = qos.link_mbps.filter(|link| guaranteed_mbit > *link) {
+            report.error(
+                "nano_cores.network_core.qos.link_mbps",
+                format!("Las garantías de las clases ({} Mbit/s) superan el enlace ({} Mbit/s)", guaranteed_mbit, link_mbps),
+                Some(json!(guaranteed_mbit)),
+            );
+        }
+
+        if !firewall::validate_table(&self.nano_cores.network_core.firewall.table) {
+            report.error(
+                "nano_cores.network_core.firewall.table",
+                "Nombre de tabla de firewall inválido (letras, dígitos y '_', hasta 32)",
+                Some(json!("saai")),
+            );
+        }
+
+        // Los nano-núcleos externos pueden no tener sección en `custom`
+        for section in self.nano_cores.supervisor.policies.keys() {
+            if !self.nano_cores.has_section(section) {
+                report.warning(
+                    &format!("nano_cores.supervisor.policies.{}", section),
+                    "Sección de nano-núcleo desconocida: solo se aplica si se registra una fábrica con ese nombre",
+                    None,
+                );
+            }
+        }
+
+        for (section, placement) in &self.nano_cores.placement {
+            let path = format!("nano_cores.placement.{}", section);
+            if !self.nano_cores.has_section(section) {
+                report.warning(
+                    &path,
+                    "Sección de nano-núcleo desconocida: solo se aplica si se registra una fábrica con ese nombre",
+                    None,
+                );
+            }
+
+            // La sección y sus overrides por réplica
+            let mut entries = vec![(path.clone(), placement.cpu_affinity.as_slice(), placement.nice)];
+            for (instance, overrides) in &placement.instances {
+                let cpu_affinity = overrides.cpu_affinity.as_deref().unwrap_or_default();
+                entries.push((format!("{}.instances.{}", path, instance), cpu_affinity, overrides.nice));
+            }
+
+            for (path, cpu_affinity, nice) in entries {
+                if let Some(cpu) = cpu_affinity.iter().find(|cpu| **cpu >= cpu_count) {
+                    report.error(
+                        &format!("{}.cpu_affinity", path),
+                        format!("La CPU {} no existe: el host tiene {}", cpu, cpu_count),
+                        Some(json!((0..cpu_count).collect::<Vec<_>>())),
+                    );
+                }
+                if nice.is_some_and(|nice| !(-20..=19).contains(&nice)) {
+                    report.error(&format!("{}.nice", path), "nice debe estar entre -20 y 19", Some(json!(0)));
+                }
+            }
+        }
+
+        let checkpoint = &self.nano_cores.checkpoint;
+        if checkpoint.enabled && checkpoint.dir.trim().is_empty() {
+            report.error(
+                "nano_cores.checkpoint.dir",
+                "Directorio de checkpoints no puede estar vacío",
+                Some(json!("data/checkpoints")),
+            );
+        }
+        if checkpoint.enabled && checkpoint.interval_secs == 0 {
+            report.error(
+                "nano_cores.checkpoint.interval_secs",
+                "Intervalo de checkpoints debe ser mayor que 0",
+                Some(json!(60)),
+            );
+        }
+
+        if self.nano_cores.supervisor.drain_timeout_ms == 0 {
+            report.warning(
+                "nano_cores.supervisor.drain_timeout_ms",
+                "Sin espera, drain detiene las instancias con comandos aún en curso",
+                Some(json!(30_000)),
+            );
+        }
+
+        let health = &self.nano_cores.health;
+        if !(0.0..=1.0).contains(&health.degraded_score) || !(0.0..=1.0).contains(&health.healthy_score) || health.degraded_score >= health.healthy_score {
+            report.error(
+                "nano_cores.health.healthy_score",
+                "Se necesita 0 <= degraded_score < healthy_score <= 1",
+                Some(json!(0.8)),
+            );
+        }
+
+        if health.hysteresis < 0.0 || health.hysteresis >= health.healthy_score - health.degraded_score {
+            report.error(
+                "nano_cores.health.hysteresis",
+                "La histéresis debe ser positiva y menor que la distancia entre umbrales",
+                Some(json!(0.05)),
+            );
+        }
+
+        // Dependencias de arranque: secciones conocidas y sin ciclos
+        let startup = &self.nano_cores.startup;
+        let mut sections: BTreeSet<&str> = NanoCoreType::BUILTIN.iter().map(|t| t.config_section()).collect();
+        for (section, dependencies) in &startup.dependencies {
+            for name in std::iter::once(section).chain(dependencies) {
+                if !self.nano_cores.has_section(name) {
+                    report.warning(
+                        &format!("nano_cores.startup.dependencies.{}", section),
+                        format!("Sección de nano-núcleo desconocida: {}", name),
+                        None,
+                    );
+                }
+                sections.insert(name.as_str());
+            }
+        }
+        let core_types: Vec<_> = sections.into_iter().map(NanoCoreType::from_section).collect();
+        if let Err(e) = startup.startup_order(&core_types) {
+            report.error("nano_cores.startup.dependencies", e.to_string(), None);
+        }
+
+        let sec_config = &self.nano_cores.security_core;
+        if sec_config.encryption_algorithm.is_empty() {
+            report.error(
+                "nano_cores.security_core.encryption_algorithm",
+                "Algoritmo de encriptación no puede estar vacío",
+                Some(json!("AES-256-GCM")),
+            );
+        }
+
+        if sec_config.key_rotation_interval_hours == 0 {
+            report.error(
+                "nano_cores.security_core.key_rotation_interval_hours",
+                "Intervalo de rotación de claves debe ser mayor que 0",
+                Some(json!(24)),
+            );
+        }
+
+        if let Err(e) = crate::security::Rbac::from_roles(&self.security.roles) {
+            report.error("security.roles", e.to_string(), None);
+        }
+
+        if let Err(e) = crate::security::keystore::provider_from_config(&self.security.key_provider) {
+            report.error("security.key_provider", e.to_string(), None);
+        }
+
+        for (action, rule) in &self.security.rate_limits {
+            if rule.max_requests == 0 || rule.window_secs == 0 {
+                report.error(
+                    &format!("security.rate_limits.{}", action),
+                    "El límite necesita max_requests y window_secs mayores que 0",
+                    None,
+                );
+            }
+        }
+
+        // Overrides por instancia
+        let instances: BTreeSet<usize> = self.nano_cores.instances.values().flat_map(|o| o.keys().copied()).collect();
+        for instance in instances {
+            let path = format!("nano_cores.instances.*.{}", instance);
+            if instance >= replicas {
+                report.error(&path, format!("Override para la instancia {} con solo {} réplicas", instance, replicas), None);
+            } else if let Err(e) = self.nano_cores.for_instance(instance) {
+                report.error(&path, format!("Override inválido: {}", e), None);
+            }
+        }
+
+        // Rendimiento
+        if self.performance.thread_pool_size == 0 {
+            report.error("performance.thread_pool_size", "Tamaño del pool de hilos debe ser mayor que 0", Some(json!(cpu_count)));
+        }
+
+        if self.performance.async_runtime_threads == 0 {
+            report.error(
+                "performance.async_runtime_threads",
+                "Número de hilos del runtime async debe ser mayor que 0",
+                Some(json!(cpu_count)),
+            );
+        }
+
+        if let Some(memory_mb) = memory_mb.filter(|mb| self.performance.cache_size_mb > *mb) {
+            report.warning(
+                "performance.cache_size_mb",
+                format!("Cache ({} MB) mayor que la RAM disponible ({} MB)", self.performance.cache_size_mb, memory_mb),
+                Some(json!((memory_mb / 8).min(2048))),
+            );
+        }
+
+        report
+    }
+
+    /// Aplicar las correcciones sugeridas y devolver los campos modificados
+    pub fn auto_fix(&mut self) -> Result<Vec<FieldChange>> {
+        let report = self.validation_report();
+        let mut value = serde_json::to_value(&*self)?;
+        let mut changes = Vec::new();
+
+        for issue in report.issues {
+            let Some(suggestion) = issue.suggestion else { continue };
+            let Some(slot) = value.pointer_mut(&format!("/{}", issue.path.replace('.', "/"))) else { continue };
+
+            info!("🔧 Corrección automática: {} -> {} ({})", issue.path, suggestion, issue.message);
+            changes.push(FieldChange {
+                path: issue.path,
+                old: std::mem::replace(slot, suggestion.clone()),
+                new: suggestion,
+            });
+        }
+
+        let mut fixed: CoreConfig = serde_json::from_value(value)?;
+        fixed.secret_refs = std::mem::take(&mut self.secret_refs);
+        *self = fixed;
+
+        for issue in self.validation_report().errors() {
+            warn!("⚠️  Sin corrección automática: {}", issue);
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_collects_all_and_fixes() {
+        let mut config = CoreConfig::default();
+        config.nats_url.clear();
+        config.consensus.replica_count = 4;
+        config.nano_cores.security_core.key_rotation_interval_hours = 0;
+        config.performance.thread_pool_size = 0;
+        config.performance.cache_size_mb = 1 << 40;
+
+        let report = config.validation_report();
+        assert_eq!(report.errors().count(), 3);
+        assert!(report.warnings().any(|issue| issue.path == "consensus.replica_count"));
+        assert!(!report.is_valid());
+
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("nats_url") && error.contains("thread_pool_size"));
+
+        let changes = config.auto_fix().unwrap();
+        assert!(changes.iter().any(|change| change.path == "performance.thread_pool_size"));
+        assert_eq!(config.consensus.replica_count, 5);
+        assert_eq!(config.nano_cores.security_core.key_rotation_interval_hours, 24);
+
+        // Sin corrección segura para la URL de NATS
+        let remaining: Vec<String> = config.validation_report().errors().map(|issue| issue.path.clone()).collect();
+        assert_eq!(remaining, vec!["nats_url"]);
+    }
+}
@@ -0,0 +1,173 @@
+//! Recarga en caliente de la configuración
+//!
+//! Vigila el archivo gestionado por `ConfigManager` con notify, valida la
+//! nueva versión, calcula el diff y, tras la aprobación opcional, la aplica
+//! y publica un `ConfigChange` en el Cognitive Fabric.
+
+use anyhow::{anyhow, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::{diff_configs, ConfigChange, ConfigChangeGate, ConfigManager, CoreConfig};
+use crate::communication::CognitiveFabric;
+
+/// Opciones de la recarga en caliente
+#[derive(Clone)]
+pub struct WatchOptions {
+    /// Espera tras un evento para agrupar escrituras sucesivas
+    pub debounce_ms: u64,
+    /// Fabric donde publicar los `ConfigChange`
+    pub fabric: Option<Arc<CognitiveFabric>>,
+    /// Aprobación previa del cambio; por defecto la del gestor
+    pub gate: Option<Arc<dyn ConfigChangeGate>>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 500,
+            fabric: None,
+            gate: None,
+        }
+    }
+}
+
+/// Vigilante activo del archivo de configuración
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    handle: JoinHandle<()>,
+}
+
+impl ConfigWatcher {
+    /// Empezar a vigilar el archivo de configuración del gestor
+    pub async fn start(manager: Arc<RwLock<ConfigManager>>, options: WatchOptions) -> Result<Self> {
+        let path = PathBuf::from(manager.read().await.config_path());
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Ruta de configuración sin nombre de archivo: {}", path.display()))?
+            .to_owned();
+
+        // Vigilar el directorio: los editores suelen reemplazar el archivo
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str()));
+                if relevant {
+                    let _ = tx.send(());
+                }
+            }
+            Err(e) => warn!("⚠️  Error vigilando configuración: {}", e),
+        })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        let debounce = Duration::from_millis(options.debounce_ms);
+        let handle = tokio::spawn(async move {
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(debounce).await;
+                while rx.try_recv().is_ok() {}
+
+                if let Err(e) = reload(&manager, &options).await {
+                    warn!("⚠️  Configuración recargada rechazada: {}", e);
+                }
+            }
+        });
+
+        info!("👀 Vigilando cambios de configuración en {}", path.display());
+        Ok(Self {
+            _watcher: watcher,
+            handle,
+        })
+    }
+
+    /// Dejar de vigilar
+    pub fn stop(self) {
+        self.handle.abort();
+    }
+}
+
+/// Releer el archivo y aplicar los cambios; `None` si no hay diferencias
+async fn reload(manager: &RwLock<ConfigManager>, options: &WatchOptions) -> Result<Option<ConfigChange>> {
+    let (path, overrides, current, gate) = {
+        let manager = manager.read().await;
+        (
+            manager.config_path().to_string(),
+            manager.overrides().clone(),
+            manager.get_config().clone(),
+            options.gate.clone().or_else(|| manager.gate()),
+        )
+    };
+
+    let new_config = CoreConfig::read_file_with(Path::new(&path), &overrides).await?;
+    let changes = diff_configs(&current, &new_config);
+
+    if changes.is_empty() {
+        debug!("📋 Archivo de configuración modificado sin cambios efectivos");
+        return Ok(None);
+    }
+
+    if let Some(gate) = gate {
+        if !gate.approve(&new_config, &changes).await? {
+            return Err(anyhow!("Cambio de configuración no aprobado ({} campos)", changes.len()));
+        }
+    }
+
+    let change = manager.write().await.apply_config(new_config, changes, "file", None).await?;
+
+    info!("🔄 Configuración recargada: {} cambios", change.changes.len());
+    for field in &change.changes {
+        info!("  📝 {}", field);
+    }
+
+    if let Some(fabric) = &options.fabric {
+        if let Err(e) = change.publish(fabric).await {
+            warn!("⚠️  No se pudo publicar el cambio de configuración: {}", e);
+        }
+    }
+
+    Ok(Some(change))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigSection;
+
+    #[tokio::test]
+    async fn test_reload_applies_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        CoreConfig::default().save(&path).await.unwrap();
+
+        let manager = RwLock::new(ConfigManager::new(path.to_str().unwrap()).await.unwrap());
+        assert!(reload(&manager, &WatchOptions::default()).await.unwrap().is_none());
+
+        let mut edited = CoreConfig::default();
+        edited.consensus.replica_count = 5;
+        edited.save(&path).await.unwrap();
+
+        let change = reload(&manager, &WatchOptions::default()).await.unwrap().unwrap();
+        assert_eq!(change.changes.len(), 1);
+        assert_eq!(change.changes[0].path, "consensus.replica_count");
+        assert_eq!(change.changes[0].section(), ConfigSection::Consensus);
+
+        // Una configuración inválida no se aplica
+        edited.consensus.replica_count = 1;
+        edited.save(&path).await.unwrap();
+        assert!(reload(&manager, &WatchOptions::default()).await.is_err());
+
+        let manager = manager.read().await;
+        assert_eq!(manager.get_config().consensus.replica_count, 5);
+        assert_eq!(manager.get_version_history().len(), 1);
+    }
+}
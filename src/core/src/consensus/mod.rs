@@ -5,19 +5,33 @@
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::communication::{CognitiveFabric, CognitiveEvent, EventType, EventPriority};
-use crate::metrics::MetricsCollector;
+use crate::communication::{CognitiveFabric, CognitiveEvent, EventType, EventPriority, TraceContext};
+use crate::config::{ConfigChangeGate, ConfigSection, ConfigSubscriber, CoreConfig, FieldChange};
+use crate::metrics::{MetricsCollector, StatusProvider};
+
+/// Propuestas recientes de las que se calcula la tasa de decisiones
+const RECENT_DECISIONS: usize = 50;
+
+/// Registrar si una propuesta llegó a una decisión
+async fn record_decision(decisions: &RwLock<VecDeque<bool>>, decided: bool) {
+    let mut decisions = decisions.write().await;
+    decisions.push_back(decided);
+    while decisions.len() > RECENT_DECISIONS {
+        decisions.pop_front();
+    }
+}
 
 /// Configuración del sistema de consenso
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConsensusConfig {
     pub replica_count: usize,
     pub vote_timeout_ms: u64,
@@ -79,6 +93,7 @@ pub enum ProposalType {
     ReplicaReplacement,
     SystemMutation,
     SecurityAction,
+    Remediation,
 }
 
 /// Voto en una propuesta
@@ -129,13 +144,18 @@ pub trait ConsensusParticipant: Send + Sync {
 
 /// Gestor de consenso principal
 pub struct ConsensusManager {
-    config: ConsensusConfig,
+    config: Arc<RwLock<ConsensusConfig>>,
     cognitive_fabric: Arc<CognitiveFabric>,
     metrics: Arc<MetricsCollector>,
     replicas: Arc<RwLock<HashMap<Uuid, ReplicaInfo>>>,
     active_proposals: Arc<RwLock<HashMap<Uuid, ConsensusProposal>>>,
+    /// Evento publicado de cada propuesta activa, del que cuelga la traza del resultado
+    proposal_events: Arc<RwLock<HashMap<Uuid, CognitiveEvent>>>,
     votes: Arc<RwLock<HashMap<Uuid, Vec<Vote>>>>,
     participants: Arc<RwLock<HashMap<Uuid, Box<dyn ConsensusParticipant>>>>,
+    pending_results: Arc<RwLock<HashMap<Uuid, oneshot::Sender<ConsensusResult>>>>,
+    /// Si cada propuesta reciente llegó a una decisión
+    decisions: Arc<RwLock<VecDeque<bool>>>,
 }
 
 impl ConsensusManager {
@@ -146,13 +166,16 @@ impl ConsensusManager {
         metrics: Arc<MetricsCollector>,
     ) -> Result<Self> {
         let manager = Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
             cognitive_fabric,
             metrics,
             replicas: Arc::new(RwLock::new(HashMap::new())),
             active_proposals: Arc::new(RwLock::new(HashMap::new())),
+            proposal_events: Arc::new(RwLock::new(HashMap::new())),
             votes: Arc::new(RwLock::new(HashMap::new())),
             participants: Arc::new(RwLock::new(HashMap::new())),
+            pending_results: Arc::new(RwLock::new(HashMap::new())),
+            decisions: Arc::new(RwLock::new(VecDeque::new())),
         };
 
         // Suscribirse a eventos de consenso
@@ -190,6 +213,21 @@ impl ConsensusManager {
         Ok(())
     }
 
+    /// Retirar un participante (p. ej. una réplica reemplazada)
+    pub async fn unregister_participant(&self, participant_id: Uuid) {
+        self.participants.write().await.remove(&participant_id);
+        self.replicas.write().await.remove(&participant_id);
+        info!("🗳️  Participante retirado del consenso: {}", participant_id);
+    }
+
+    /// Fijar el estado de una réplica; las que están en cuarentena no lo cambian con su salud
+    pub async fn set_replica_state(&self, replica_id: Uuid, state: ReplicaState) {
+        if let Some(replica) = self.replicas.write().await.get_mut(&replica_id) {
+            info!("🗳️  Réplica {} pasa a {:?}", replica_id, state);
+            replica.state = state;
+        }
+    }
+
     /// Proponer una votación
     pub async fn propose(&self, proposal: ConsensusProposal) -> Result<Uuid> {
         let proposal_id = proposal.id;
@@ -201,11 +239,13 @@ impl ConsensusManager {
 
         // Validar que hay suficientes réplicas saludables
         let healthy_replicas = self.count_healthy_replicas().await;
-        if healthy_replicas < self.config.replica_count {
+        let replica_count = self.config.read().await.replica_count;
+        if healthy_replicas < replica_count {
+            record_decision(&self.decisions, false).await;
             return Err(anyhow!(
                 "Insuficientes réplicas saludables: {} < {}",
                 healthy_replicas,
-                self.config.replica_count
+                replica_count
             ));
         }
 
@@ -214,7 +254,7 @@ impl ConsensusManager {
         self.votes.write().await.insert(proposal_id, Vec::new());
 
         // Publicar propuesta en el Cognitive Fabric
-        let event = CognitiveEvent {
+        let mut event = CognitiveEvent {
             id: Uuid::new_v4(),
             event_type: EventType::ConsensusVote,
             source: "consensus-manager".to_string(),
@@ -223,7 +263,10 @@ impl ConsensusManager {
             payload: serde_json::to_vec(&proposal)?,
             priority: EventPriority::High,
             correlation_id: Some(proposal_id),
+            headers: HashMap::new(),
         };
+        TraceContext::ensure(&mut event);
+        self.proposal_events.write().await.insert(proposal_id, event.clone());
 
         self.cognitive_fabric.publish_event(event).await?;
 
@@ -233,6 +276,47 @@ impl ConsensusManager {
         Ok(proposal_id)
     }
 
+    /// Proponer y esperar la decisión, recogiendo los votos de los participantes locales
+    pub async fn propose_and_wait(&self, proposal: ConsensusProposal) -> Result<ConsensusResult> {
+        let proposal_id = proposal.id;
+        let (result_tx, result_rx) = oneshot::channel();
+        self.pending_results.write().await.insert(proposal_id, result_tx);
+
+        if let Err(e) = self.propose(proposal.clone()).await {
+            self.pending_results.write().await.remove(&proposal_id);
+            return Err(e);
+        }
+
+        self.collect_local_votes(&proposal).await;
+
+        let timeout = Duration::from_millis(self.config.read().await.vote_timeout_ms);
+        match tokio::time::timeout(timeout, result_rx).await {
+            Ok(Ok(result)) => Ok(result),
+            _ => {
+                self.pending_results.write().await.remove(&proposal_id);
+                Err(anyhow!("Sin consenso para la propuesta {} antes del timeout", proposal_id))
+            }
+        }
+    }
+
+    /// Pedir su voto a cada participante registrado
+    async fn collect_local_votes(&self, proposal: &ConsensusProposal) {
+        let mut votes = Vec::new();
+        for participant in self.participants.read().await.values() {
+            match participant.vote(proposal).await {
+                Ok(vote) => votes.push(vote),
+                Err(e) => warn!("⚠️  {} no pudo votar: {}", participant.participant_id(), e),
+            }
+        }
+
+        for vote in votes {
+            // Tras alcanzar el consenso la propuesta deja de aceptar votos
+            if let Err(e) = self.process_vote(vote).await {
+                debug!("🗳️  Voto descartado: {}", e);
+            }
+        }
+    }
+
     /// Procesar voto recibido
     pub async fn process_vote(&self, vote: Vote) -> Result<()> {
         let proposal_id = vote.proposal_id;
@@ -312,6 +396,7 @@ impl ConsensusManager {
             );
 
             // Notificar resultado
+            record_decision(&self.decisions, true).await;
             self.notify_consensus_result(&result).await?;
             
             // Limpiar propuesta completada
@@ -345,20 +430,29 @@ impl ConsensusManager {
 
     /// Notificar resultado de consenso
     async fn notify_consensus_result(&self, result: &ConsensusResult) -> Result<()> {
-        // Publicar resultado en Cognitive Fabric
-        let event = CognitiveEvent {
-            id: Uuid::new_v4(),
-            event_type: EventType::ConsensusVote,
-            source: "consensus-manager".to_string(),
-            target: None,
-            timestamp: chrono::Utc::now(),
-            payload: serde_json::to_vec(result)?,
-            priority: EventPriority::High,
-            correlation_id: Some(result.proposal_id),
+        // Publicar resultado en Cognitive Fabric, en la traza de su propuesta
+        let payload = serde_json::to_vec(result)?;
+        let event = match self.proposal_events.write().await.remove(&result.proposal_id) {
+            Some(proposal) => proposal.follow_up(EventType::ConsensusVote, "consensus-manager", payload),
+            None => CognitiveEvent {
+                id: Uuid::new_v4(),
+                event_type: EventType::ConsensusVote,
+                source: "consensus-manager".to_string(),
+                target: None,
+                timestamp: chrono::Utc::now(),
+                payload,
+                priority: EventPriority::High,
+                correlation_id: Some(result.proposal_id),
+                headers: HashMap::new(),
+            },
         };
 
         self.cognitive_fabric.publish_event(event).await?;
 
+        if let Some(waiter) = self.pending_results.write().await.remove(&result.proposal_id) {
+            let _ = waiter.send(result.clone());
+        }
+
         // Notificar a participantes
         let participants = self.participants.read().await;
         for participant in participants.values() {
@@ -394,13 +488,13 @@ impl ConsensusManager {
     async fn start_health_monitoring(&self) {
         let replicas = self.replicas.clone();
         let participants = self.participants.clone();
-        let interval = Duration::from_millis(self.config.health_check_interval_ms);
+        let config = self.config.clone();
 
         tokio::spawn(async move {
-            let mut interval_timer = tokio::time::interval(interval);
-            
             loop {
-                interval_timer.tick().await;
+                // El intervalo se relee en cada ciclo para aplicar cambios en caliente
+                let interval = Duration::from_millis(config.read().await.health_check_interval_ms);
+                tokio::time::sleep(interval).await;
                 
                 // Verificar salud de cada participante
                 let participants_guard = participants.read().await;
@@ -410,7 +504,10 @@ impl ConsensusManager {
                     match participant.health_check().await {
                         Ok(score) => {
                             let mut replicas_guard = replicas.write().await;
-                            if let Some(replica) = replicas_guard.get_mut(&participant_id) {
+                            let replica = replicas_guard
+                                .get_mut(&participant_id)
+                                .filter(|replica| replica.state != ReplicaState::Quarantined);
+                            if let Some(replica) = replica {
                                 replica.last_heartbeat = SystemTime::now();
                                 replica.performance_score = score;
                                 
@@ -428,7 +525,10 @@ impl ConsensusManager {
                             warn!("⚠️  Health check falló para {}: {}", participant_id, e);
                             
                             let mut replicas_guard = replicas.write().await;
-                            if let Some(replica) = replicas_guard.get_mut(&participant_id) {
+                            let replica = replicas_guard
+                                .get_mut(&participant_id)
+                                .filter(|replica| replica.state != ReplicaState::Quarantined);
+                            if let Some(replica) = replica {
                                 replica.failure_count += 1;
                                 replica.state = ReplicaState::Failed;
                             }
@@ -441,9 +541,11 @@ impl ConsensusManager {
 
     /// Programar timeout para votación
     async fn schedule_vote_timeout(&self, proposal_id: Uuid) {
-        let timeout = Duration::from_millis(self.config.vote_timeout_ms);
+        let timeout = Duration::from_millis(self.config.read().await.vote_timeout_ms);
         let active_proposals = self.active_proposals.clone();
+        let proposal_events = self.proposal_events.clone();
         let votes = self.votes.clone();
+        let decisions = self.decisions.clone();
 
         tokio::spawn(async move {
             tokio::time::sleep(timeout).await;
@@ -451,23 +553,108 @@ impl ConsensusManager {
             // Verificar si la propuesta aún está activa
             if active_proposals.read().await.contains_key(&proposal_id) {
                 warn!("⏰ Timeout de votación para propuesta: {}", proposal_id);
+                record_decision(&decisions, false).await;
                 
                 // Limpiar propuesta expirada
                 active_proposals.write().await.remove(&proposal_id);
+                proposal_events.write().await.remove(&proposal_id);
                 votes.write().await.remove(&proposal_id);
             }
         });
     }
 
+    /// Tabla de réplicas registradas
+    pub async fn replicas(&self) -> Vec<ReplicaInfo> {
+        self.replicas.read().await.values().cloned().collect()
+    }
+
+    /// Salud del consenso entre 0 y 1
+    ///
+    /// Fracción de réplicas saludables por la tasa de propuestas recientes
+    /// que llegaron a una decisión (sin propuestas recientes cuenta como 1).
+    /// Sin réplicas registradas no hay consenso posible y vale 0.
+    pub async fn health(&self) -> f64 {
+        let replicas = {
+            let replicas = self.replicas.read().await;
+            if replicas.is_empty() {
+                return 0.0;
+            }
+            let healthy = replicas.values().filter(|replica| replica.state == ReplicaState::Healthy).count();
+            healthy as f64 / replicas.len() as f64
+        };
+
+        let decisions = self.decisions.read().await;
+        let success_rate = if decisions.is_empty() {
+            1.0
+        } else {
+            decisions.iter().filter(|decided| **decided).count() as f64 / decisions.len() as f64
+        };
+
+        replicas * success_rate
+    }
+
     /// Shutdown del gestor de consenso
     pub async fn shutdown(&self) -> Result<()> {
         info!("🛑 Cerrando ConsensusManager");
         
         // Limpiar propuestas activas
         self.active_proposals.write().await.clear();
+        self.proposal_events.write().await.clear();
         self.votes.write().await.clear();
+        self.pending_results.write().await.clear();
         
         info!("✅ ConsensusManager cerrado");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl StatusProvider for ConsensusManager {
+    fn section(&self) -> &'static str {
+        "consensus"
+    }
+
+    async fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "replica_count": self.config.read().await.replica_count,
+            "replicas": self.replicas().await,
+            "active_proposals": self.active_proposals.read().await.len(),
+        })
+    }
+}
+
+#[async_trait]
+impl ConfigSubscriber for ConsensusManager {
+    fn subscriber_name(&self) -> &str {
+        "consensus"
+    }
+
+    fn sections(&self) -> &[ConfigSection] {
+        &[ConfigSection::Consensus]
+    }
+
+    async fn on_config_change(&self, config: &CoreConfig, _changes: &[FieldChange]) -> Result<()> {
+        *self.config.write().await = config.consensus.clone();
+        info!("🗳️  Configuración de consenso actualizada: {} réplicas", config.consensus.replica_count);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ConfigChangeGate for ConsensusManager {
+    async fn approve(&self, _config: &CoreConfig, changes: &[FieldChange]) -> Result<bool> {
+        // Mayoría de las réplicas saludables
+        let required_votes = self.count_healthy_replicas().await / 2 + 1;
+        let proposal = ConsensusProposal {
+            id: Uuid::new_v4(),
+            proposal_type: ProposalType::ConfigChange,
+            proposer: Uuid::nil(),
+            data: serde_json::to_vec(changes)?,
+            timestamp: SystemTime::now(),
+            required_votes,
+        };
+
+        let result = self.propose_and_wait(proposal).await?;
+        Ok(result.decision == VoteDecision::Approve)
+    }
+}
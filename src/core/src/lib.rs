@@ -9,11 +9,13 @@ pub mod communication;
 pub mod metrics;
 pub mod config;
 pub mod security;
+pub mod util;
 
 // Re-exportar tipos principales para facilitar el uso
 pub use nano_cores::{
     NanoCore, NanoCoreManager, NanoCoreType, NanoCoreState, 
-    NanoCoreHealth, SystemHealth
+    NanoCoreHealth, SystemHealth, CommandAuthorization, NanoCoreFactory, NanoCoreContext,
+    NanoCoreError, CoreResult
 };
 
 pub use consensus::{
@@ -23,20 +25,22 @@ pub use consensus::{
 
 pub use communication::{
     CognitiveFabric, CognitiveFabricClient, CognitiveEvent, 
-    EventType, EventPriority
+    EventType, EventPriority, EventCodec, EventFilter
 };
 
 pub use metrics::{
-    MetricsCollector, MetricsConfig, SystemResources
+    MetricsCollector, MetricsConfig, SystemResources, FabricMetrics, OtlpConfig,
+    StatusProvider, TimeSeriesStore, AlertConfig, AlertRule, MetricsAuth
 };
 
 pub use config::{
-    CoreConfig, ConfigManager, NanoCoresConfig
+    CoreConfig, ConfigManager, NanoCoresConfig, ConfigChange, ConfigWatcher, ConfigOverrides,
+    ConfigSubscriber, ValidationReport, ConfigSource
 };
 
 pub use security::{
     SecurityManager, SecurityConfig, SecurityContext, 
-    SecurityLevel, SecurityEvent, SecurityEventType, SecuritySeverity
+    SecurityLevel, SecurityEvent, SecurityEventType, SecuritySeverity, SecurityEventFilter
 };
 
 /// Versión de SAAI Core
@@ -90,7 +94,17 @@ pub fn check_system_compatibility() -> anyhow::Result<()> {
         return Err(anyhow::anyhow!("Se requieren al menos 2 CPUs, encontradas: {}", cpu_count));
     }
     
-    tracing::info!("✅ Sistema compatible: {} {} con {} CPUs", os, arch, cpu_count);
+    // En contenedores la cuota del cgroup puede dejar menos CPUs de las que hay
+    let environment = config::RuntimeEnvironment::detect();
+    let effective_cpus = environment.effective_cpus(cpu_count);
+    if effective_cpus < 2 {
+        tracing::warn!("⚠️  Cuota de CPU del contenedor por debajo de 2 CPUs ({:?})", environment.cpu_limit);
+    }
+    if let Some(hypervisor) = &environment.virtualization {
+        tracing::info!("🖥️  Máquina virtual detectada: {}", hypervisor);
+    }
+    
+    tracing::info!("✅ Sistema compatible: {} {} con {} CPUs", os, arch, effective_cpus);
     Ok(())
 }
 
@@ -8,7 +8,10 @@ use anyhow::Result;
 use clap::Parser;
 use std::sync::Arc;
 use tokio::signal;
+use tokio::sync::RwLock;
 use tracing::{info, error};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
 
 mod nano_cores;
 mod consensus;
@@ -16,12 +19,15 @@ mod communication;
 mod config;
 mod metrics;
 mod security;
+mod util;
 
 use nano_cores::{NanoCoreManager, NanoCoreType};
 use consensus::ConsensusManager;
 use communication::CognitiveFabric;
-use config::CoreConfig;
-use metrics::MetricsCollector;
+use config::{
+    ConfigManager, ConfigOverrides, ConfigWatcher, GitSyncOptions, SoakOptions, SourceSyncOptions, WatchOptions,
+};
+use metrics::{MetricsCollector, MetricsConfig, OtlpConfig, SystemResources};
 use security::SecurityManager;
 
 #[derive(Parser)]
@@ -32,53 +38,243 @@ struct Args {
     #[arg(short, long, default_value = "config/core.toml")]
     config: String,
     
-    /// Nivel de logging
-    #[arg(short, long, default_value = "info")]
-    log_level: String,
+    /// Formato del archivo de configuración (toml, yaml, json); por defecto según la extensión
+    #[arg(long)]
+    config_format: Option<String>,
     
-    /// Puerto para métricas
-    #[arg(short, long, default_value = "9090")]
-    metrics_port: u16,
+    /// Perfil de `[profiles.<nombre>]` a aplicar (p. ej. `staging`, `production`)
+    #[arg(long)]
+    profile: Option<String>,
+    
+    /// Rechazar claves desconocidas en el archivo de configuración
+    #[arg(long)]
+    config_strict: bool,
+    
+    /// Imprimir el JSON Schema de la configuración y salir
+    #[arg(long)]
+    config_schema: bool,
+    
+    /// Nivel de logging (override de `log_level`)
+    #[arg(short, long)]
+    log_level: Option<String>,
+    
+    /// Puerto para métricas (override de `metrics_port`)
+    #[arg(short, long)]
+    metrics_port: Option<u16>,
+    
+    /// Override de configuración `seccion.clave=valor` (repetible)
+    #[arg(long = "set", value_name = "CLAVE=VALOR")]
+    overrides: Vec<String>,
+    
+    /// Repositorio Git del que sincronizar la configuración (GitOps)
+    #[arg(long)]
+    gitops_url: Option<String>,
+    
+    /// Rama del repositorio GitOps
+    #[arg(long, default_value = "main")]
+    gitops_branch: String,
+    
+    /// Archivo de configuración dentro del repositorio GitOps
+    #[arg(long, default_value = "config/core.toml")]
+    gitops_path: String,
+    
+    /// Intervalo de sincronización GitOps en segundos
+    #[arg(long, default_value = "60")]
+    gitops_interval_secs: u64,
+    
+    /// Origen central de la configuración (`etcd://host:2379/clave` o `consul://host:8500/clave`)
+    #[arg(long)]
+    config_source: Option<String>,
+    
+    /// Ventana de observación tras cada cambio de configuración, en segundos
+    #[arg(long, default_value = "60")]
+    config_soak_secs: u64,
+    
+    /// Arrancar solo este nano-núcleo (`network`, `network_core` o el nombre de un externo)
+    #[arg(long, value_name = "NÚCLEO")]
+    only_core: Option<NanoCoreType>,
+    
+    /// Réplicas del nano-núcleo de `--only-core` (por defecto `consensus.replica_count`)
+    #[arg(long, requires = "only_core")]
+    replicas: Option<usize>,
+}
+
+impl Args {
+    /// Overrides de CLI, de mayor precedencia que entorno y archivo
+    fn config_overrides(&self) -> Result<ConfigOverrides> {
+        let mut overrides = ConfigOverrides::from_cli(&self.overrides)?;
+        overrides.format = self.config_format.as_deref().map(str::parse).transpose()?;
+        overrides.strict = self.config_strict;
+        overrides.profile = self.profile.clone();
+        
+        if let Some(log_level) = &self.log_level {
+            overrides = overrides.with("log_level", log_level);
+        }
+        if let Some(port) = self.metrics_port {
+            overrides = overrides.with("metrics_port", port);
+        }
+        
+        Ok(overrides)
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
     
+    if args.config_schema {
+        println!("{}", serde_json::to_string_pretty(&ConfigManager::schema())?);
+        return Ok(());
+    }
+    
+    // Exportación OTLP opcional (OTEL_EXPORTER_OTLP_ENDPOINT)
+    let otlp_config = OtlpConfig::from_env();
+    let otlp_layer = match otlp_config.as_ref().filter(|otlp| otlp.export_traces) {
+        Some(otlp) => Some(metrics::otlp::trace_layer(otlp)?),
+        None => None,
+    };
+    
     // Inicializar logging
-    tracing_subscriber::fmt()
-        .with_env_filter(&args.log_level)
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_file(true)
-        .with_line_number(true)
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(args.log_level.as_deref().unwrap_or("info")))
+        .with(
+            fmt::layer()
+                .with_target(false)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true)
+        )
+        .with(otlp_layer)
         .init();
 
     info!("🚀 Iniciando SAAI Core - Nano-Núcleos Cuánticos");
 
     // Cargar configuración
-    let mut config = CoreConfig::load(&args.config).await?;
+    let config_manager = Arc::new(RwLock::new(
+        ConfigManager::with_overrides(&args.config, args.config_overrides()?).await?
+    ));
+    let mut config = config_manager.read().await.get_config().clone();
     
     // Optimizar configuración para el hardware actual
     config.optimize_for_hardware()?;
     info!("📋 Configuración cargada desde: {}", args.config);
 
     // Inicializar colector de métricas
-    let metrics = Arc::new(MetricsCollector::new(args.metrics_port).await?);
-    info!("📊 Colector de métricas iniciado en puerto: {}", args.metrics_port);
+    let metrics = Arc::new(MetricsCollector::with_config(MetricsConfig {
+        port: config.metrics_port,
+        otlp: otlp_config,
+        ..Default::default()
+    }).await?);
+    info!("📊 Colector de métricas iniciado en puerto: {}", config.metrics_port);
 
     // Inicializar gestor de seguridad
     let security_manager = Arc::new(
         SecurityManager::new(config.security.clone()).await?
     );
+    security_manager.start_pruning().await;
+    security_manager.start_session_sweeper().await;
+    security_manager.start_rules_watch().await;
+    security_manager.start_integrity_monitor().await;
+    security_manager.start_cert_rotation().await;
+    security_manager.start_posture_sampling().await;
+    metrics.register_status_provider(security_manager.clone()).await;
     info!("🔐 Gestor de seguridad inicializado");
 
     // Inicializar Cognitive Fabric (Bus de eventos)
     let cognitive_fabric = Arc::new(
         CognitiveFabric::new(&config.nats_url).await?
     );
+    cognitive_fabric.attach_metrics(metrics.fabric_metrics());
+    metrics.attach_fabric(cognitive_fabric.clone()).await;
+    metrics.register_status_provider(cognitive_fabric.clone()).await;
     info!("🧠 Cognitive Fabric conectado a: {}", config.nats_url);
 
+    // Reglas de amenazas publicadas en el fabric
+    cognitive_fabric
+        .subscribe(security::THREAT_RULES_SUBJECT, {
+            let security = security_manager.clone();
+            move |data| match serde_json::from_slice::<security::ThreatRules>(data) {
+                Ok(rules) => {
+                    let security = security.clone();
+                    tokio::spawn(async move {
+                        security.apply_threat_rules(&rules).await;
+                    });
+                }
+                Err(e) => error!("❌ Reglas de amenazas inválidas recibidas: {}", e),
+            }
+        })
+        .await?;
+
+    // Crecimientos de memoria de procesos detectados por OSCore
+    cognitive_fabric
+        .subscribe(nano_cores::process_history::PROCESS_ANOMALY_SUBJECT, {
+            let security = security_manager.clone();
+            move |data| match serde_json::from_slice::<nano_cores::process_history::ProcessAnomaly>(data) {
+                Ok(anomaly) => {
+                    let security = security.clone();
+                    tokio::spawn(async move {
+                        let source = format!("{}:{}", anomaly.name, anomaly.pid);
+                        security
+                            .record_resource_usage(&source, anomaly.cpu_usage as f64, anomaly.memory_usage)
+                            .await;
+                    });
+                }
+                Err(e) => error!("❌ Anomalía de proceso inválida recibida: {}", e),
+            }
+        })
+        .await?;
+
+    // Comandos programados registrados en OSCore: se guardan en la configuración
+    cognitive_fabric
+        .subscribe(nano_cores::scheduler::SCHEDULES_SUBJECT, {
+            let config_manager = config_manager.clone();
+            move |data| match serde_json::from_slice::<nano_cores::scheduler::ScheduleUpdate>(data) {
+                Ok(update) => {
+                    let config_manager = config_manager.clone();
+                    tokio::spawn(async move {
+                        let mut manager = config_manager.write().await;
+                        let mut new_config = manager.get_config().clone();
+                        update.apply(&mut new_config.nano_cores.os_core.schedules);
+                        if let Err(e) = manager.update_config(new_config).await {
+                            error!("❌ No se pudo guardar la programación: {}", e);
+                        }
+                    });
+                }
+                Err(e) => error!("❌ Cambio de programación inválido recibido: {}", e),
+            }
+        })
+        .await?;
+
+    // Recarga en caliente de la configuración
+    let config_watcher = ConfigWatcher::start(
+        config_manager.clone(),
+        WatchOptions {
+            fabric: Some(cognitive_fabric.clone()),
+            ..Default::default()
+        },
+    ).await?;
+    
+    // Sincronización GitOps opcional
+    let gitops_sync = args.gitops_url.as_ref().map(|url| {
+        let mut options = GitSyncOptions::new(url, &args.gitops_branch, &args.gitops_path);
+        options.interval_ms = args.gitops_interval_secs * 1000;
+        options.fabric = Some(cognitive_fabric.clone());
+        config::gitops::start_git_sync(config_manager.clone(), options)
+    });
+    
+    // Configuración centralizada opcional (etcd/Consul)
+    let source_sync = match &args.config_source {
+        Some(url) => Some(config::source::start_source_sync(
+            config_manager.clone(),
+            config::source::source_from_url(url)?,
+            SourceSyncOptions {
+                fabric: Some(cognitive_fabric.clone()),
+                ..Default::default()
+            },
+        )),
+        None => None,
+    };
+
     // Inicializar ConsensusManager
     let consensus_manager = Arc::new(
         ConsensusManager::new(
@@ -87,7 +283,11 @@ async fn main() -> Result<()> {
             metrics.clone()
         ).await?
     );
+    metrics.register_status_provider(consensus_manager.clone()).await;
     info!("🗳️  ConsensusManager inicializado con {} réplicas", config.consensus.replica_count);
+    
+    // Los cambios de configuración requieren consenso
+    config_manager.write().await.set_gate(consensus_manager.clone());
 
     // Inicializar NanoCoreManager
     let nano_core_manager = Arc::new(
@@ -100,21 +300,75 @@ async fn main() -> Result<()> {
         ).await?
     );
 
-    // Inicializar todos los nano-núcleos con redundancia empresarial
+    // Remediaciones de conectividad propuestas por NetworkCore: se ejecutan solo si el consenso las aprueba
+    cognitive_fabric
+        .subscribe(nano_cores::watchdog::REMEDIATION_SUBJECT, {
+            let manager = nano_core_manager.clone();
+            move |data| match serde_json::from_slice::<nano_cores::watchdog::RemediationRequest>(data) {
+                Ok(request) => {
+                    let manager = manager.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = manager.remediate(request).await {
+                            error!("❌ Remediación de red fallida: {}", e);
+                        }
+                    });
+                }
+                Err(e) => error!("❌ Propuesta de remediación inválida recibida: {}", e),
+            }
+        })
+        .await?;
+
+    // Propagar los cambios de configuración a los subsistemas
+    let config_subscriptions = {
+        let manager = config_manager.read().await;
+        vec![
+            manager.register_subscriber(security_manager.clone()),
+            manager.register_subscriber(metrics.clone()),
+            manager.register_subscriber(consensus_manager.clone()),
+            manager.register_subscriber(nano_core_manager.clone()),
+        ]
+    };
+
+    // Revertir cambios que degraden a la mayoría de nano-núcleos
+    let soak_monitor = config::soak::start_soak_monitor(config_manager.clone(), SoakOptions {
+        window_ms: args.config_soak_secs * 1000,
+        ..SoakOptions::new(nano_core_manager.clone())
+    }).await;
+
+    // Nano-núcleos fuera de proceso y WASM de `[nano_cores.custom.*]`
+    nano_cores::remote_core::register_configured(&nano_core_manager, &config.nano_cores).await?;
+    #[cfg(feature = "wasm")]
+    nano_cores::wasm_core::register_configured(&nano_core_manager, &config.nano_cores).await?;
+
+    // Inicializar todos los nano-núcleos con redundancia empresarial, o solo el de `--only-core`
     info!("⚡ Iniciando nano-núcleos...");
-    nano_core_manager.initialize_all_cores().await?;
+    match args.only_core.clone() {
+        Some(core_type) => nano_core_manager.initialize_single_core(core_type, args.replicas).await?,
+        None => nano_core_manager.initialize_all_cores().await?,
+    }
 
     // Iniciar monitoreo de salud
     let health_monitor = tokio::spawn({
         let manager = nano_core_manager.clone();
         let metrics = metrics.clone();
+        let security = security_manager.clone();
         async move {
+            let total_memory = SystemResources::sample(&mut sysinfo::System::new()).total_memory;
+            
             loop {
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                 
                 let health = manager.get_health_status().await;
                 metrics.record_health_status(&health).await;
                 
+                // Correlación de recursos por núcleo para el patrón ResourceAbuse
+                for cores in health.cores.values() {
+                    for core in cores {
+                        let memory_bytes = (core.memory_usage / 100.0 * total_memory as f64) as u64;
+                        security.record_resource_usage(&core.instance_id.to_string(), core.cpu_usage, memory_bytes).await;
+                    }
+                }
+                
                 if !health.is_healthy() {
                     error!("⚠️  Sistema no saludable: {:?}", health);
                 }
@@ -139,6 +393,17 @@ async fn main() -> Result<()> {
     info!("🔄 Iniciando shutdown graceful...");
     
     health_monitor.abort();
+    config_watcher.stop();
+    if let Some(handle) = gitops_sync {
+        handle.abort();
+    }
+    if let Some(handle) = source_sync {
+        handle.abort();
+    }
+    for handle in config_subscriptions {
+        handle.abort();
+    }
+    soak_monitor.abort();
     nano_core_manager.shutdown().await?;
     consensus_manager.shutdown().await?;
     security_manager.shutdown().await?;
@@ -0,0 +1,153 @@
+//! Métricas de agentes
+//!
+//! Tareas etiquetadas por agente y resultado, errores clasificados por
+//! categoría y una tasa de error móvil por agente para vigilar cada agente
+//! de forma individual.
+
+use anyhow::Result;
+use prometheus::{GaugeVec, IntCounterVec, Opts, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Ventana de la tasa de error móvil
+const ERROR_RATE_WINDOW: Duration = Duration::from_secs(300);
+
+/// Categoría de un error de agente
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AgentErrorCategory {
+    Timeout,
+    Validation,
+    Communication,
+    Resource,
+    Permission,
+    Execution,
+}
+
+impl AgentErrorCategory {
+    /// Clasificar un mensaje de error por palabras clave
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let matches = |keywords: &[&str]| keywords.iter().any(|k| error.contains(k));
+
+        if matches(&["timeout", "timed out", "tiempo de espera", "deadline"]) {
+            Self::Timeout
+        } else if matches(&["invalid", "inválid", "parse", "malformed", "validation", "validación"]) {
+            Self::Validation
+        } else if matches(&["connection", "conexión", "network", "red ", "nats", "unreachable"]) {
+            Self::Communication
+        } else if matches(&["memory", "memoria", "quota", "cuota", "exhausted", "agotad", "limit"]) {
+            Self::Resource
+        } else if matches(&["permission", "permiso", "unauthorized", "no autorizado", "forbidden", "denied"]) {
+            Self::Permission
+        } else {
+            Self::Execution
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Validation => "validation",
+            Self::Communication => "communication",
+            Self::Resource => "resource",
+            Self::Permission => "permission",
+            Self::Execution => "execution",
+        }
+    }
+}
+
+/// Métricas etiquetadas por agente
+pub struct AgentMetrics {
+    tasks_total: IntCounterVec,
+    errors_total: IntCounterVec,
+    error_rate: GaugeVec,
+    outcomes: Mutex<HashMap<String, VecDeque<(Instant, bool)>>>,
+}
+
+impl AgentMetrics {
+    /// Crear y registrar las métricas en el registro indicado
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let tasks_total = IntCounterVec::new(
+            Opts::new("agent_tasks_total", "Total de tareas de agentes"),
+            &["agent", "result"],
+        )?;
+        registry.register(Box::new(tasks_total.clone()))?;
+
+        let errors_total = IntCounterVec::new(
+            Opts::new("agent_errors_total", "Errores de agentes por categoría"),
+            &["agent", "category"],
+        )?;
+        registry.register(Box::new(errors_total.clone()))?;
+
+        let error_rate = GaugeVec::new(
+            Opts::new("agent_error_rate", "Fracción de tareas fallidas en los últimos 5 minutos"),
+            &["agent"],
+        )?;
+        registry.register(Box::new(error_rate.clone()))?;
+
+        Ok(Self {
+            tasks_total,
+            errors_total,
+            error_rate,
+            outcomes: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Registrar resultado de una tarea y actualizar la tasa móvil
+    pub fn record_task(&self, agent: &str, success: bool) {
+        let result = if success { "success" } else { "failure" };
+        self.tasks_total.with_label_values(&[agent, result]).inc();
+
+        let now = Instant::now();
+        let mut outcomes = self.outcomes.lock().unwrap();
+        let window = outcomes.entry(agent.to_string()).or_default();
+
+        window.push_back((now, success));
+        while window
+            .front()
+            .map_or(false, |(at, _)| now.duration_since(*at) > ERROR_RATE_WINDOW)
+        {
+            window.pop_front();
+        }
+
+        let failures = window.iter().filter(|(_, ok)| !ok).count();
+        self.error_rate
+            .with_label_values(&[agent])
+            .set(failures as f64 / window.len() as f64);
+    }
+
+    /// Registrar error clasificado
+    pub fn record_error(&self, agent: &str, category: AgentErrorCategory) {
+        self.errors_total
+            .with_label_values(&[agent, category.as_str()])
+            .inc();
+    }
+
+    /// Tasa de error móvil actual de un agente
+    pub fn error_rate(&self, agent: &str) -> f64 {
+        self.error_rate.with_label_values(&[agent]).get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rate_and_classification() {
+        let metrics = AgentMetrics::new(&Registry::new()).unwrap();
+
+        metrics.record_task("planner", true);
+        metrics.record_task("planner", true);
+        metrics.record_task("planner", false);
+        metrics.record_task("planner", false);
+        assert_eq!(metrics.error_rate("planner"), 0.5);
+        assert_eq!(metrics.error_rate("executor"), 0.0);
+
+        assert_eq!(AgentErrorCategory::classify("request timed out"), AgentErrorCategory::Timeout);
+        assert_eq!(AgentErrorCategory::classify("Conexión NATS perdida"), AgentErrorCategory::Communication);
+        assert_eq!(AgentErrorCategory::classify("boom"), AgentErrorCategory::Execution);
+    }
+}
@@ -0,0 +1,389 @@
+//! Motor de reglas de alerta
+//!
+//! Evalúa periódicamente reglas de umbral y de tasa sobre las series
+//! retenidas (`nano_core_errors_total rate > 5/min`) y, en cada cambio de
+//! estado, publica un `CognitiveEvent` y notifica a un webhook opcional.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::tsdb::TimeSeriesStore;
+use crate::communication::{CognitiveEvent, CognitiveFabric, EventPriority, EventType};
+
+/// Tipo de condición evaluada
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Último valor de la serie
+    Threshold,
+    /// Incremento por minuto dentro de la ventana
+    Rate,
+}
+
+/// Comparación contra el umbral
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+/// Severidad de una alerta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+/// Tipo de evento emitido al disparar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    Security,
+    #[default]
+    Health,
+}
+
+/// Regla de alerta definida en configuración
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    /// Serie retenida: `nombre` o `nombre{k="v"}`
+    pub metric: String,
+    pub condition: AlertCondition,
+    pub comparison: Comparison,
+    /// Umbral; para `Rate`, en unidades por minuto
+    pub threshold: f64,
+    /// Ventana sobre la que se calcula la tasa
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// Tiempo que la condición debe mantenerse antes de disparar
+    #[serde(default)]
+    pub for_secs: u64,
+    #[serde(default)]
+    pub severity: AlertSeverity,
+    #[serde(default)]
+    pub kind: AlertKind,
+}
+
+fn default_window_secs() -> u64 {
+    60
+}
+
+impl AlertRule {
+    /// Interpretar una expresión `metrica [rate] (>|<) valor[/min|/s]`
+    pub fn from_expression(name: &str, expression: &str) -> Result<Self> {
+        let tokens: Vec<&str> = expression.split_whitespace().collect();
+        let (metric, condition, operator, value) = match tokens.as_slice() {
+            [metric, "rate", operator, value] => (*metric, AlertCondition::Rate, *operator, *value),
+            [metric, operator, value] => (*metric, AlertCondition::Threshold, *operator, *value),
+            _ => return Err(anyhow!("Expresión de alerta inválida: {}", expression)),
+        };
+
+        let comparison = match operator {
+            ">" => Comparison::Above,
+            "<" => Comparison::Below,
+            other => return Err(anyhow!("Operador de alerta no soportado: {}", other)),
+        };
+
+        let (value, per_minute_factor) = match value.split_once('/') {
+            Some((value, "min")) => (value, 1.0),
+            Some((value, "s")) => (value, 60.0),
+            Some((_, unit)) => return Err(anyhow!("Unidad de tasa no soportada: {}", unit)),
+            None => (value, 1.0),
+        };
+
+        let threshold = value
+            .parse::<f64>()
+            .map_err(|_| anyhow!("Umbral de alerta inválido: {}", value))?
+            * per_minute_factor;
+
+        Ok(Self {
+            name: name.to_string(),
+            metric: metric.to_string(),
+            condition,
+            comparison,
+            threshold,
+            window_secs: default_window_secs(),
+            for_secs: 0,
+            severity: AlertSeverity::default(),
+            kind: AlertKind::default(),
+        })
+    }
+}
+
+/// Configuración del motor de alertas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertConfig {
+    pub rules: Vec<AlertRule>,
+    pub evaluation_interval_ms: u64,
+    /// Webhook que recibe cada notificación como JSON
+    pub webhook_url: Option<String>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            evaluation_interval_ms: 15_000,
+            webhook_url: None,
+        }
+    }
+}
+
+/// Estado notificado de una alerta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// Notificación emitida en cada cambio de estado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertNotification {
+    pub rule: String,
+    pub metric: String,
+    pub status: AlertStatus,
+    pub value: f64,
+    pub threshold: f64,
+    pub severity: AlertSeverity,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Default)]
+struct RuleState {
+    pending_since: Option<SystemTime>,
+    firing: bool,
+}
+
+/// Motor que evalúa las reglas sobre el almacén de series
+pub struct AlertEngine {
+    config: AlertConfig,
+    tsdb: Arc<TimeSeriesStore>,
+    fabric: OnceLock<Arc<CognitiveFabric>>,
+    http: reqwest::Client,
+    states: Mutex<HashMap<String, RuleState>>,
+}
+
+impl AlertEngine {
+    pub fn new(config: AlertConfig, tsdb: Arc<TimeSeriesStore>) -> Self {
+        Self {
+            config,
+            tsdb,
+            fabric: OnceLock::new(),
+            http: reqwest::Client::new(),
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Publicar las alertas en el Cognitive Fabric
+    pub fn attach_fabric(&self, fabric: Arc<CognitiveFabric>) {
+        let _ = self.fabric.set(fabric);
+    }
+
+    pub fn has_rules(&self) -> bool {
+        !self.config.rules.is_empty()
+    }
+
+    /// Valor actual de la condición de una regla
+    fn rule_value(&self, rule: &AlertRule) -> Option<f64> {
+        let window = Duration::from_secs(rule.window_secs.max(1));
+        let samples = self.tsdb.get_series(&rule.metric, window, Duration::ZERO);
+
+        match rule.condition {
+            AlertCondition::Threshold => samples.last().map(|s| s.value),
+            AlertCondition::Rate => {
+                let (first, last) = (samples.first()?, samples.last()?);
+                let elapsed_ms = last.timestamp_ms.checked_sub(first.timestamp_ms)?;
+                if elapsed_ms == 0 {
+                    return None;
+                }
+
+                // Un contador reiniciado cuenta desde cero
+                let delta = if last.value >= first.value {
+                    last.value - first.value
+                } else {
+                    last.value
+                };
+                Some(delta / elapsed_ms as f64 * 60_000.0)
+            }
+        }
+    }
+
+    /// Evaluar todas las reglas y devolver los cambios de estado
+    pub fn evaluate(&self) -> Vec<AlertNotification> {
+        let now = SystemTime::now();
+        let mut states = self.states.lock().unwrap();
+        let mut notifications = Vec::new();
+
+        for rule in &self.config.rules {
+            let value = match self.rule_value(rule) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let breached = match rule.comparison {
+                Comparison::Above => value > rule.threshold,
+                Comparison::Below => value < rule.threshold,
+            };
+
+            let state = states.entry(rule.name.clone()).or_default();
+            let status = if breached {
+                let since = *state.pending_since.get_or_insert(now);
+                let held = now.duration_since(since).unwrap_or_default();
+                if state.firing || held < Duration::from_secs(rule.for_secs) {
+                    continue;
+                }
+                state.firing = true;
+                AlertStatus::Firing
+            } else {
+                state.pending_since = None;
+                if !state.firing {
+                    continue;
+                }
+                state.firing = false;
+                AlertStatus::Resolved
+            };
+
+            notifications.push(AlertNotification {
+                rule: rule.name.clone(),
+                metric: rule.metric.clone(),
+                status,
+                value,
+                threshold: rule.threshold,
+                severity: rule.severity,
+                timestamp: chrono::Utc::now(),
+            });
+        }
+
+        notifications
+    }
+
+    /// Enviar notificación al fabric y al webhook
+    async fn notify(&self, rule: &AlertRule, notification: &AlertNotification) -> Result<()> {
+        match notification.status {
+            AlertStatus::Firing => warn!(
+                "🚨 Alerta {} disparada: {} = {:.2} (umbral {:.2})",
+                notification.rule, notification.metric, notification.value, notification.threshold
+            ),
+            AlertStatus::Resolved => info!("✅ Alerta {} resuelta", notification.rule),
+        }
+
+        let payload = serde_json::to_vec(notification)?;
+
+        if let Some(fabric) = self.fabric.get() {
+            let event = CognitiveEvent {
+                id: Uuid::new_v4(),
+                event_type: match rule.kind {
+                    AlertKind::Security => EventType::SecurityAlert,
+                    AlertKind::Health => EventType::HealthCheck,
+                },
+                source: "metrics-alerts".to_string(),
+                target: None,
+                timestamp: notification.timestamp,
+                payload: payload.clone(),
+                priority: match rule.severity {
+                    AlertSeverity::Critical => EventPriority::Critical,
+                    AlertSeverity::Warning => EventPriority::High,
+                    AlertSeverity::Info => EventPriority::Normal,
+                },
+                correlation_id: None,
+                headers: HashMap::new(),
+            };
+            fabric.publish_event(event).await?;
+        }
+
+        if let Some(url) = &self.config.webhook_url {
+            self.http
+                .post(url)
+                .header("content-type", "application/json")
+                .body(payload)
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+
+        Ok(())
+    }
+
+    /// Iniciar evaluación periódica en background
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        let interval_ms = self.config.evaluation_interval_ms.max(1);
+        info!("🚨 {} reglas de alerta evaluadas cada {}ms", self.config.rules.len(), interval_ms);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+            loop {
+                interval.tick().await;
+
+                for notification in self.evaluate() {
+                    let rule = match self.config.rules.iter().find(|r| r.name == notification.rule) {
+                        Some(rule) => rule,
+                        None => continue,
+                    };
+
+                    if let Err(e) = self.notify(rule, &notification).await {
+                        warn!("⚠️  Error notificando alerta {}: {}", notification.rule, e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_rule_fires_and_resolves() {
+        let rule = AlertRule::from_expression("errores", "nano_core_errors_total rate > 5/min").unwrap();
+        assert_eq!(rule.condition, AlertCondition::Rate);
+        assert_eq!(rule.threshold, 5.0);
+
+        let tsdb = Arc::new(TimeSeriesStore::new(Duration::from_secs(3600), Duration::from_secs(1)));
+        let engine = AlertEngine::new(
+            AlertConfig {
+                rules: vec![rule],
+                ..Default::default()
+            },
+            tsdb.clone(),
+        );
+
+        let now = SystemTime::now();
+        tsdb.record("nano_core_errors_total", now - Duration::from_secs(30), 0.0);
+        tsdb.record("nano_core_errors_total", now, 10.0);
+
+        let fired = engine.evaluate();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].status, AlertStatus::Firing);
+        assert!(engine.evaluate().is_empty());
+
+        let cpu_rule = AlertRule::from_expression("cpu", "system_cpu_usage_percent > 90").unwrap();
+        let engine = AlertEngine::new(
+            AlertConfig {
+                rules: vec![cpu_rule],
+                ..Default::default()
+            },
+            tsdb.clone(),
+        );
+
+        tsdb.record("system_cpu_usage_percent", now, 95.0);
+        assert_eq!(engine.evaluate()[0].status, AlertStatus::Firing);
+
+        tsdb.record("system_cpu_usage_percent", now + Duration::from_millis(1), 50.0);
+        assert_eq!(engine.evaluate()[0].status, AlertStatus::Resolved);
+
+        assert!(AlertRule::from_expression("x", "cpu >= 5").is_err());
+    }
+}
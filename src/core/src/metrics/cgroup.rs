@@ -0,0 +1,51 @@
+//! Métricas del cgroup de SAAI
+//!
+//! Throttling de CPU, eventos de memoria y procesos del cgroup v2 que gestiona
+//! OSCore, como gauges `cgroup_*` con los valores acumulados que da el kernel.
+
+use anyhow::Result;
+use prometheus::{IntGauge, Opts, Registry};
+
+use crate::nano_cores::cgroup::CgroupStats;
+
+/// Gauges del cgroup gestionado por OSCore
+pub struct CgroupMetrics {
+    cpu_periods: IntGauge,
+    cpu_throttled_periods: IntGauge,
+    cpu_throttled_seconds: IntGauge,
+    memory_current: IntGauge,
+    memory_max_events: IntGauge,
+    oom_kills: IntGauge,
+    pids_current: IntGauge,
+}
+
+impl CgroupMetrics {
+    /// Crear y registrar las métricas en el registro indicado
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let int_gauge = |name: &str, help: &str| -> Result<IntGauge> {
+            let gauge = IntGauge::with_opts(Opts::new(name, help))?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        Ok(Self {
+            cpu_periods: int_gauge("cgroup_cpu_periods", "Periodos de cpu.max transcurridos")?,
+            cpu_throttled_periods: int_gauge("cgroup_cpu_throttled_periods", "Periodos con la CPU limitada")?,
+            cpu_throttled_seconds: int_gauge("cgroup_cpu_throttled_seconds", "Tiempo total con la CPU limitada")?,
+            memory_current: int_gauge("cgroup_memory_bytes", "Memoria usada por el cgroup")?,
+            memory_max_events: int_gauge("cgroup_memory_max_events", "Veces que se alcanzó memory.max")?,
+            oom_kills: int_gauge("cgroup_oom_kills", "Procesos terminados por OOM en el cgroup")?,
+            pids_current: int_gauge("cgroup_pids", "Procesos en el cgroup")?,
+        })
+    }
+
+    pub fn record(&self, stats: &CgroupStats) {
+        self.cpu_periods.set(stats.nr_periods as i64);
+        self.cpu_throttled_periods.set(stats.nr_throttled as i64);
+        self.cpu_throttled_seconds.set((stats.throttled_usec / 1_000_000) as i64);
+        self.memory_current.set(stats.memory_current as i64);
+        self.memory_max_events.set(stats.memory_max_events as i64);
+        self.oom_kills.set(stats.oom_kills as i64);
+        self.pids_current.set(stats.pids_current as i64);
+    }
+}
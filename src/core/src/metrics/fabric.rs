@@ -0,0 +1,161 @@
+//! Métricas del Cognitive Fabric
+//!
+//! Contadores e histogramas etiquetados por tema y tipo de evento para
+//! publicación y consumo, junto con la profundidad de las colas de
+//! suscripción y el retraso de los consumidores de streams duraderos.
+
+use anyhow::Result;
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
+};
+
+use crate::communication::SubscriptionQueueStats;
+
+/// Métricas etiquetadas del Cognitive Fabric
+pub struct FabricMetrics {
+    published_total: IntCounterVec,
+    published_bytes: IntCounterVec,
+    publish_errors: IntCounterVec,
+    publish_latency: HistogramVec,
+    consumed_total: IntCounterVec,
+    consume_errors: IntCounterVec,
+    handler_latency: HistogramVec,
+    queue_depth: IntGaugeVec,
+    queue_dropped: IntCounterVec,
+    consumer_lag: IntGaugeVec,
+    duplicates_suppressed: IntCounterVec,
+}
+
+impl FabricMetrics {
+    /// Crear y registrar las métricas con los buckets de latencia indicados
+    pub fn new(registry: &Registry, latency_buckets: Vec<f64>) -> Result<Self> {
+        let published_total = IntCounterVec::new(
+            Opts::new("fabric_published_total", "Eventos publicados en Cognitive Fabric"),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(published_total.clone()))?;
+
+        let published_bytes = IntCounterVec::new(
+            Opts::new("fabric_published_bytes_total", "Bytes publicados en Cognitive Fabric"),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(published_bytes.clone()))?;
+
+        let publish_errors = IntCounterVec::new(
+            Opts::new("fabric_publish_errors_total", "Errores de publicación en Cognitive Fabric"),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(publish_errors.clone()))?;
+
+        let publish_latency = HistogramVec::new(
+            HistogramOpts::new("fabric_publish_latency_seconds", "Latencia de publicación en Cognitive Fabric")
+                .buckets(latency_buckets.clone()),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(publish_latency.clone()))?;
+
+        let consumed_total = IntCounterVec::new(
+            Opts::new("fabric_consumed_total", "Eventos consumidos del Cognitive Fabric"),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(consumed_total.clone()))?;
+
+        let consume_errors = IntCounterVec::new(
+            Opts::new("fabric_consume_errors_total", "Eventos inválidos recibidos del Cognitive Fabric"),
+            &["subject"],
+        )?;
+        registry.register(Box::new(consume_errors.clone()))?;
+
+        let handler_latency = HistogramVec::new(
+            HistogramOpts::new("fabric_handler_latency_seconds", "Latencia de manejadores de eventos")
+                .buckets(latency_buckets),
+            &["subject", "event_type"],
+        )?;
+        registry.register(Box::new(handler_latency.clone()))?;
+
+        let queue_depth = IntGaugeVec::new(
+            Opts::new("fabric_subscription_queue_depth", "Mensajes pendientes en la cola de suscripción"),
+            &["subject"],
+        )?;
+        registry.register(Box::new(queue_depth.clone()))?;
+
+        let queue_dropped = IntCounterVec::new(
+            Opts::new("fabric_subscription_dropped_total", "Mensajes descartados por desbordamiento de cola"),
+            &["subject"],
+        )?;
+        registry.register(Box::new(queue_dropped.clone()))?;
+
+        let consumer_lag = IntGaugeVec::new(
+            Opts::new("fabric_consumer_lag", "Mensajes pendientes del consumidor en streams duraderos"),
+            &["subject"],
+        )?;
+        registry.register(Box::new(consumer_lag.clone()))?;
+
+        let duplicates_suppressed = IntCounterVec::new(
+            Opts::new("fabric_duplicates_suppressed_total", "Eventos duplicados suprimidos en suscripciones exactly_once"),
+            &["subject"],
+        )?;
+        registry.register(Box::new(duplicates_suppressed.clone()))?;
+
+        Ok(Self {
+            published_total,
+            published_bytes,
+            publish_errors,
+            publish_latency,
+            consumed_total,
+            consume_errors,
+            handler_latency,
+            queue_depth,
+            queue_dropped,
+            consumer_lag,
+            duplicates_suppressed,
+        })
+    }
+
+    /// Registrar publicación de un evento
+    pub fn record_publish(&self, subject: &str, event_type: &str, bytes: usize, latency_seconds: f64) {
+        let labels = [subject, event_type];
+        self.published_total.with_label_values(&labels).inc();
+        self.published_bytes.with_label_values(&labels).inc_by(bytes as u64);
+        self.publish_latency.with_label_values(&labels).observe(latency_seconds);
+    }
+
+    /// Registrar error de publicación
+    pub fn record_publish_error(&self, subject: &str, event_type: &str) {
+        self.publish_errors.with_label_values(&[subject, event_type]).inc();
+    }
+
+    /// Registrar consumo de un evento y la latencia de su manejador
+    pub fn record_consume(&self, subject: &str, event_type: &str, latency_seconds: f64) {
+        let labels = [subject, event_type];
+        self.consumed_total.with_label_values(&labels).inc();
+        self.handler_latency.with_label_values(&labels).observe(latency_seconds);
+    }
+
+    /// Registrar evento recibido que no pudo decodificarse
+    pub fn record_consume_error(&self, subject: &str) {
+        self.consume_errors.with_label_values(&[subject]).inc();
+    }
+
+    /// Actualizar profundidad y descartes de una cola de suscripción
+    pub fn record_subscription_queue(&self, stats: &SubscriptionQueueStats) {
+        let labels = [stats.subject.as_str()];
+        self.queue_depth.with_label_values(&labels).set(stats.depth as i64);
+
+        // El contador solo avanza con la diferencia respecto a lo ya registrado
+        let dropped = self.queue_dropped.with_label_values(&labels);
+        if stats.dropped > dropped.get() {
+            dropped.inc_by(stats.dropped - dropped.get());
+        }
+    }
+
+    /// Registrar evento duplicado suprimido
+    pub fn record_duplicate(&self, subject: &str) {
+        self.duplicates_suppressed.with_label_values(&[subject]).inc();
+    }
+
+    /// Registrar retraso de un consumidor de stream duradero
+    pub fn record_consumer_lag(&self, subject: &str, pending: u64) {
+        self.consumer_lag.with_label_values(&[subject]).set(pending as i64);
+    }
+}
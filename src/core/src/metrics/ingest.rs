@@ -0,0 +1,189 @@
+//! Ingestión de métricas desde el Cognitive Fabric
+//!
+//! Convierte los payloads JSON publicados en `hardware.metrics`,
+//! `network.metrics` y `saai.metrics` en gauges Prometheus: cada hoja
+//! numérica es una serie nombrada por su ruta, y los elementos de arrays se
+//! distinguen con la etiqueta `key` (su campo `name`/`id` o su índice).
+
+use anyhow::Result;
+use prometheus::{GaugeVec, Opts, Registry};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Máximo de familias creadas por ingestión
+const MAX_FAMILIES: usize = 2_000;
+
+/// Una hoja numérica del payload
+struct Point {
+    name: String,
+    labels: Vec<(String, String)>,
+    value: f64,
+}
+
+/// Traductor de payloads del bus a gauges registrados bajo demanda
+pub struct FabricIngestor {
+    registry: Registry,
+    families: Mutex<HashMap<String, (Vec<String>, GaugeVec)>>,
+}
+
+impl FabricIngestor {
+    pub fn new(registry: Registry) -> Self {
+        Self {
+            registry,
+            families: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Ingerir un payload JSON bajo el espacio de nombres `namespace`
+    ///
+    /// `labels` se añade a todas las series (p. ej. `source` del evento).
+    /// Devuelve el número de series actualizadas.
+    pub fn ingest(&self, namespace: &str, labels: &[(&str, &str)], payload: &[u8]) -> usize {
+        let value: Value = match serde_json::from_slice(payload) {
+            Ok(value) => value,
+            Err(e) => {
+                debug!("📊 Payload de métricas no JSON en {}: {}", namespace, e);
+                return 0;
+            }
+        };
+
+        let base_labels: Vec<(String, String)> = labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        let mut points = Vec::new();
+        flatten(&sanitize(namespace), &value, &base_labels, &mut points);
+
+        points
+            .into_iter()
+            .filter(|point| self.set(point))
+            .count()
+    }
+
+    fn set(&self, point: &Point) -> bool {
+        let label_names: Vec<String> = point.labels.iter().map(|(name, _)| name.clone()).collect();
+        let mut families = self.families.lock().unwrap();
+
+        if !families.contains_key(&point.name) {
+            if families.len() >= MAX_FAMILIES {
+                return false;
+            }
+
+            let gauge = match self.register(&point.name, &label_names) {
+                Ok(gauge) => gauge,
+                Err(e) => {
+                    debug!("📊 Serie {} no registrada: {}", point.name, e);
+                    return false;
+                }
+            };
+
+            families.insert(point.name.clone(), (label_names.clone(), gauge));
+        }
+
+        let (registered_labels, gauge) = &families[&point.name];
+        if *registered_labels != label_names {
+            return false;
+        }
+
+        let values: Vec<&str> = point.labels.iter().map(|(_, value)| value.as_str()).collect();
+        gauge.with_label_values(&values).set(point.value);
+        true
+    }
+
+    fn register(&self, name: &str, label_names: &[String]) -> Result<GaugeVec> {
+        let names: Vec<&str> = label_names.iter().map(String::as_str).collect();
+        let gauge = GaugeVec::new(
+            Opts::new(name, "Métrica publicada en el Cognitive Fabric"),
+            &names,
+        )?;
+        self.registry.register(Box::new(gauge.clone()))?;
+        Ok(gauge)
+    }
+}
+
+/// Recorrer el JSON acumulando hojas numéricas y booleanas
+fn flatten(name: &str, value: &Value, labels: &[(String, String)], out: &mut Vec<Point>) {
+    match value {
+        Value::Number(number) => {
+            if let Some(value) = number.as_f64() {
+                out.push(Point {
+                    name: name.to_string(),
+                    labels: labels.to_vec(),
+                    value,
+                });
+            }
+        }
+        Value::Bool(flag) => out.push(Point {
+            name: name.to_string(),
+            labels: labels.to_vec(),
+            value: if *flag { 1.0 } else { 0.0 },
+        }),
+        Value::Object(fields) => {
+            for (field, value) in fields {
+                flatten(&format!("{}_{}", name, sanitize(field)), value, labels, out);
+            }
+        }
+        Value::Array(items) => {
+            let depth = labels.iter().filter(|(label, _)| label.starts_with("key")).count();
+            let label = if depth == 0 { "key".to_string() } else { format!("key{}", depth) };
+
+            for (index, item) in items.iter().enumerate() {
+                let key = item
+                    .get("name")
+                    .or_else(|| item.get("id"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| index.to_string());
+
+                let mut item_labels = labels.to_vec();
+                item_labels.push((label.clone(), key));
+                flatten(name, item, &item_labels, out);
+            }
+        }
+        Value::String(_) | Value::Null => {}
+    }
+}
+
+/// Normalizar a `[a-zA-Z0-9_]` en minúsculas
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_payload_ingestion() {
+        let registry = Registry::new();
+        let ingestor = FabricIngestor::new(registry.clone());
+
+        let payload = serde_json::json!({
+            "cpu_info": { "cores": 8, "average_usage": 12.5, "model": "x86" },
+            "interfaces": [
+                { "name": "eth0", "statistics": { "bytes_sent": 100 } },
+                { "name": "wlan0", "statistics": { "bytes_sent": 40 } }
+            ],
+            "online": true
+        });
+
+        let updated = ingestor.ingest("hardware", &[], &serde_json::to_vec(&payload).unwrap());
+        assert_eq!(updated, 5);
+
+        let families = registry.gather();
+        let sent = families
+            .iter()
+            .find(|f| f.get_name() == "hardware_interfaces_statistics_bytes_sent")
+            .unwrap();
+        assert_eq!(sent.get_metric().len(), 2);
+        assert!(families.iter().any(|f| f.get_name() == "hardware_cpu_info_average_usage"));
+        assert!(!families.iter().any(|f| f.get_name() == "hardware_cpu_info_model"));
+
+        assert_eq!(ingestor.ingest("hardware", &[], b"not json"), 0);
+    }
+}
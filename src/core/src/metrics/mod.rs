@@ -4,40 +4,127 @@
 //! para monitoreo en tiempo real y análisis predictivo.
 
 use anyhow::Result;
+use async_trait::async_trait;
 use prometheus::{
-    Counter, Gauge, Histogram, IntCounter, IntGauge, Registry, 
-    Encoder, TextEncoder, HistogramOpts, Opts
+    Counter, Gauge, Histogram, IntCounter, IntGauge, IntGaugeVec, Registry, 
+    Encoder, TextEncoder, HistogramOpts, Opts, IntCounterVec, HistogramVec
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use sysinfo::{System, SystemExt, CpuExt};
+use tokio::sync::{oneshot, RwLock};
+use tracing::{debug, error, info, warn};
 use warp::{Filter, Reply};
 
-use crate::nano_cores::{NanoCoreType, SystemHealth};
+use crate::communication::CognitiveFabric;
+use crate::config::{ConfigSection, ConfigSubscriber, CoreConfig, FieldChange};
+use crate::nano_cores::cgroup::CgroupStats;
+use crate::nano_cores::shaping::TrafficClassStats;
+use crate::nano_cores::smart::SmartHealth;
+use crate::nano_cores::{NanoCoreState, NanoCoreType, SystemHealth};
+
+pub mod agents;
+pub mod alerts;
+pub mod auth;
+pub mod cgroup;
+pub mod fabric;
+pub mod ingest;
+pub mod otlp;
+pub mod process;
+pub mod push;
+pub mod qos;
+pub mod smart;
+pub mod status;
+pub mod tsdb;
+
+pub use agents::{AgentErrorCategory, AgentMetrics};
+pub use alerts::{AlertConfig, AlertEngine, AlertRule};
+pub use auth::MetricsAuth;
+pub use cgroup::CgroupMetrics;
+pub use fabric::FabricMetrics;
+pub use ingest::FabricIngestor;
+pub use otlp::OtlpConfig;
+pub use process::ProcessMetrics;
+pub use push::{PushConfig, PushMode};
+pub use qos::QosMetrics;
+pub use smart::SmartMetrics;
+pub use status::StatusProvider;
+pub use tsdb::{Sample, TimeSeriesStore};
 
 /// Configuración del colector de métricas
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
     pub port: u16,
+    /// Exponer `/metrics` por HTTP al crear el colector
+    pub serve_http: bool,
+    pub bind_address: IpAddr,
+    /// Certificado y clave PEM para servir por HTTPS
+    pub tls_cert_path: Option<String>,
+    pub tls_key_path: Option<String>,
+    /// Credenciales exigidas en `/metrics` y las APIs JSON
+    pub auth: Option<MetricsAuth>,
     pub collection_interval_ms: u64,
     pub retention_hours: u64,
+    /// Intervalo entre muestras retenidas en la serie temporal local
+    pub retention_resolution_ms: u64,
     pub enable_detailed_metrics: bool,
+    /// Prefijo de los nombres de métrica (`saai_...`); vacío para omitirlo
+    pub prefix: String,
+    /// Etiquetas constantes añadidas a todas las métricas (cluster, node_id...)
+    pub const_labels: HashMap<String, String>,
+    /// Buckets de latencia de nano-núcleos (segundos)
+    pub latency_buckets: Vec<f64>,
+    /// Buckets de latencia del Cognitive Fabric (segundos, sub-milisegundo)
+    pub fabric_latency_buckets: Vec<f64>,
+    /// Reglas de alerta evaluadas sobre las series retenidas
+    pub alerts: AlertConfig,
+    /// Exportación OTLP opcional (métricas y trazas)
+    pub otlp: Option<OtlpConfig>,
+    /// Envío activo a Pushgateway o `remote_write` para nodos sin scrape
+    pub push: Option<PushConfig>,
 }
 
 impl Default for MetricsConfig {
     fn default() -> Self {
         Self {
             port: 9090,
+            serve_http: true,
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            tls_cert_path: None,
+            tls_key_path: None,
+            auth: None,
             collection_interval_ms: 1000,
             retention_hours: 24,
+            retention_resolution_ms: 15_000,
             enable_detailed_metrics: true,
+            prefix: "saai".to_string(),
+            const_labels: HashMap::new(),
+            latency_buckets: prometheus::DEFAULT_BUCKETS.to_vec(),
+            // 50µs .. ~0.8s
+            fabric_latency_buckets: prometheus::exponential_buckets(0.00005, 2.0, 15)
+                .unwrap_or_else(|_| prometheus::DEFAULT_BUCKETS.to_vec()),
+            alerts: AlertConfig::default(),
+            otlp: None,
+            push: None,
         }
     }
 }
 
+/// Tiempo máximo de espera al cierre ordenado del servidor HTTP
+const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Temas con payload JSON crudo ingeridos como series, y su espacio de nombres
+const INGESTED_SUBJECTS: [(&str, &str); 2] = [
+    ("hardware.metrics", "hardware"),
+    ("network.metrics", "network"),
+];
+
+/// Tema de eventos `SystemMetrics`, ingeridos bajo `reported_*` con su `source`
+const SYSTEM_METRICS_SUBJECT: &str = "saai.metrics";
+
 /// Métricas de recursos del sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemResources {
@@ -51,6 +138,27 @@ pub struct SystemResources {
     pub load_average: [f64; 3],
 }
 
+impl SystemResources {
+    /// Muestrear recursos actuales del sistema
+    pub fn sample(system: &mut System) -> Self {
+        system.refresh_cpu();
+        system.refresh_memory();
+        
+        let load_avg = system.load_average();
+        
+        Self {
+            cpu_count: system.cpus().len(),
+            cpu_usage: system.global_cpu_info().cpu_usage(),
+            total_memory: system.total_memory(),
+            used_memory: system.used_memory(),
+            available_memory: system.available_memory(),
+            total_swap: system.total_swap(),
+            used_swap: system.used_swap(),
+            load_average: [load_avg.one, load_avg.five, load_avg.fifteen],
+        }
+    }
+}
+
 /// Colector principal de métricas
 pub struct MetricsCollector {
     config: MetricsConfig,
@@ -61,10 +169,22 @@ pub struct MetricsCollector {
     system_memory_usage: Gauge,
     system_load_average: Gauge,
     
-    // Métricas de nano-núcleos
-    nano_core_executions: IntCounter,
-    nano_core_errors: IntCounter,
-    nano_core_latency: Histogram,
+    // Métricas del propio proceso y del runtime tokio
+    process: Arc<ProcessMetrics>,
+    
+    // Límites y throttling del cgroup de OSCore
+    cgroup: CgroupMetrics,
+    
+    // Salud SMART de los discos informada por HardwareCore
+    smart: SmartMetrics,
+    
+    // Clases de tráfico moldeadas por NetworkCore
+    qos: QosMetrics,
+    
+    // Métricas de nano-núcleos (por core_type e instance)
+    nano_core_executions: IntCounterVec,
+    nano_core_errors: IntCounterVec,
+    nano_core_latency: HistogramVec,
     
     // Métricas de consenso
     consensus_proposals: IntCounter,
@@ -72,168 +192,252 @@ pub struct MetricsCollector {
     consensus_decisions: IntCounter,
     
     // Métricas de Cognitive Fabric
-    fabric_events_total: IntCounter,
-    fabric_events_by_type: Arc<RwLock<HashMap<String, IntCounter>>>,
-    fabric_latency: Histogram,
+    fabric: Arc<FabricMetrics>,
     
     // Métricas de agentes
-    agent_tasks: IntCounter,
-    agent_successes: IntCounter,
-    agent_failures: IntCounter,
+    agents: AgentMetrics,
     
     // Estado del sistema
     system_health_score: Gauge,
     uptime_seconds: IntGauge,
+    start_time: SystemTime,
+    
+    // Última instantánea de salud publicada por NanoCoreManager
+    latest_health: Arc<RwLock<Option<SystemHealth>>>,
+    
+    // Series temporales locales
+    tsdb: Arc<TimeSeriesStore>,
+    
+    // Reglas de alerta
+    alerts: Arc<AlertEngine>,
+    
+    // Métricas publicadas en el Cognitive Fabric
+    ingestor: Arc<FabricIngestor>,
+    
+    // Subsistemas expuestos en /api/v1/status
+    status_providers: Arc<RwLock<Vec<Arc<dyn StatusProvider>>>>,
     
-    // Servidor HTTP para exposición
+    // Servidor HTTP para exposición (el puerto puede cambiar en caliente)
+    port: RwLock<u16>,
     server_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    server_shutdown: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    
+    // Exportación OTLP en background
+    otlp_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    
+    // Muestreo periódico de recursos del sistema
+    sampler_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    
+    // Retención periódica en la serie temporal local
+    retention_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    
+    // Evaluación periódica de alertas
+    alerts_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    
+    // Envío activo de métricas
+    push_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 impl MetricsCollector {
-    /// Crear nuevo colector de métricas
+    /// Crear nuevo colector de métricas y exponerlo en el puerto indicado
     pub async fn new(port: u16) -> Result<Self> {
-        let config = MetricsConfig {
+        Self::with_config(MetricsConfig {
             port,
             ..Default::default()
-        };
-        
-        let registry = Registry::new();
+        }).await
+    }
+
+    /// Crear colector con configuración completa
+    ///
+    /// Inicia el muestreo del sistema, el servidor HTTP (si `serve_http`)
+    /// y las exportaciones OTLP o push configuradas.
+    pub async fn with_config(config: MetricsConfig) -> Result<Self> {
+        let prefix = Some(config.prefix.clone()).filter(|prefix| !prefix.is_empty());
+        let const_labels = Some(config.const_labels.clone()).filter(|labels| !labels.is_empty());
+        let registry = Registry::new_custom(prefix, const_labels)?;
+        
+        let tsdb = Arc::new(TimeSeriesStore::new(
+            Duration::from_secs(config.retention_hours * 3600),
+            Duration::from_millis(config.retention_resolution_ms),
+        ));
+        let alerts = Arc::new(AlertEngine::new(config.alerts.clone(), tsdb.clone()));
         
         // Inicializar métricas de sistema
         let system_cpu_usage = Gauge::with_opts(Opts::new(
-            "saai_system_cpu_usage_percent",
+            "system_cpu_usage_percent",
             "Uso de CPU del sistema"
         ))?;
         registry.register(Box::new(system_cpu_usage.clone()))?;
         
         let system_memory_usage = Gauge::with_opts(Opts::new(
-            "saai_system_memory_usage_bytes",
+            "system_memory_usage_bytes",
             "Uso de memoria del sistema"
         ))?;
         registry.register(Box::new(system_memory_usage.clone()))?;
         
         let system_load_average = Gauge::with_opts(Opts::new(
-            "saai_system_load_average",
+            "system_load_average",
             "Promedio de carga del sistema"
         ))?;
         registry.register(Box::new(system_load_average.clone()))?;
         
+        let process = Arc::new(ProcessMetrics::new(&registry)?);
+        let cgroup = CgroupMetrics::new(&registry)?;
+        let smart = SmartMetrics::new(&registry)?;
+        let qos = QosMetrics::new(&registry)?;
+        
         // Métricas de nano-núcleos
-        let nano_core_executions = IntCounter::with_opts(Opts::new(
-            "saai_nano_core_executions_total",
-            "Total de ejecuciones de nano-núcleos"
-        ))?;
+        let nano_core_executions = IntCounterVec::new(
+            Opts::new(
+                "nano_core_executions_total",
+                "Total de ejecuciones de nano-núcleos"
+            ),
+            &["core_type", "instance", "result"]
+        )?;
         registry.register(Box::new(nano_core_executions.clone()))?;
         
-        let nano_core_errors = IntCounter::with_opts(Opts::new(
-            "saai_nano_core_errors_total",
-            "Total de errores en nano-núcleos"
-        ))?;
+        let nano_core_errors = IntCounterVec::new(
+            Opts::new(
+                "nano_core_errors_total",
+                "Total de errores en nano-núcleos"
+            ),
+            &["core_type", "instance"]
+        )?;
         registry.register(Box::new(nano_core_errors.clone()))?;
         
-        let nano_core_latency = Histogram::with_opts(HistogramOpts::new(
-            "saai_nano_core_latency_seconds",
-            "Latencia de ejecución de nano-núcleos"
-        ))?;
+        let nano_core_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "nano_core_latency_seconds",
+                "Latencia de ejecución de nano-núcleos"
+            ).buckets(config.latency_buckets.clone()),
+            &["core_type", "instance"]
+        )?;
         registry.register(Box::new(nano_core_latency.clone()))?;
         
         // Métricas de consenso
         let consensus_proposals = IntCounter::with_opts(Opts::new(
-            "saai_consensus_proposals_total",
+            "consensus_proposals_total",
             "Total de propuestas de consenso"
         ))?;
         registry.register(Box::new(consensus_proposals.clone()))?;
         
         let consensus_votes = IntCounter::with_opts(Opts::new(
-            "saai_consensus_votes_total",
+            "consensus_votes_total",
             "Total de votos de consenso"
         ))?;
         registry.register(Box::new(consensus_votes.clone()))?;
         
         let consensus_decisions = IntCounter::with_opts(Opts::new(
-            "saai_consensus_decisions_total",
+            "consensus_decisions_total",
             "Total de decisiones de consenso"
         ))?;
         registry.register(Box::new(consensus_decisions.clone()))?;
         
         // Métricas de Cognitive Fabric
-        let fabric_events_total = IntCounter::with_opts(Opts::new(
-            "saai_fabric_events_total",
-            "Total de eventos en Cognitive Fabric"
-        ))?;
-        registry.register(Box::new(fabric_events_total.clone()))?;
-        
-        let fabric_latency = Histogram::with_opts(HistogramOpts::new(
-            "saai_fabric_latency_seconds",
-            "Latencia de eventos en Cognitive Fabric"
-        ))?;
-        registry.register(Box::new(fabric_latency.clone()))?;
+        let fabric = Arc::new(FabricMetrics::new(&registry, config.fabric_latency_buckets.clone())?);
         
         // Métricas de agentes
-        let agent_tasks = IntCounter::with_opts(Opts::new(
-            "saai_agent_tasks_total",
-            "Total de tareas de agentes"
-        ))?;
-        registry.register(Box::new(agent_tasks.clone()))?;
-        
-        let agent_successes = IntCounter::with_opts(Opts::new(
-            "saai_agent_successes_total",
-            "Total de éxitos de agentes"
-        ))?;
-        registry.register(Box::new(agent_successes.clone()))?;
-        
-        let agent_failures = IntCounter::with_opts(Opts::new(
-            "saai_agent_failures_total",
-            "Total de fallos de agentes"
-        ))?;
-        registry.register(Box::new(agent_failures.clone()))?;
+        let agents = AgentMetrics::new(&registry)?;
         
         // Estado del sistema
         let system_health_score = Gauge::with_opts(Opts::new(
-            "saai_system_health_score",
+            "system_health_score",
             "Puntuación de salud del sistema (0-1)"
         ))?;
         registry.register(Box::new(system_health_score.clone()))?;
         
         let uptime_seconds = IntGauge::with_opts(Opts::new(
-            "saai_uptime_seconds",
+            "uptime_seconds",
             "Tiempo de actividad del sistema en segundos"
         ))?;
         registry.register(Box::new(uptime_seconds.clone()))?;
         
+        // Información de build (valor constante 1, datos en etiquetas)
+        let build_info = IntGaugeVec::new(
+            Opts::new("build_info", "Información de build de SAAI Core"),
+            &["version", "git_hash", "rust_version", "build_timestamp"]
+        )?;
+        registry.register(Box::new(build_info.clone()))?;
+        build_info
+            .with_label_values(&[
+                env!("CARGO_PKG_VERSION"),
+                env!("SAAI_GIT_HASH"),
+                env!("SAAI_RUST_VERSION"),
+                env!("SAAI_BUILD_TIMESTAMP"),
+            ])
+            .set(1);
+        
+        let ingestor = Arc::new(FabricIngestor::new(registry.clone()));
         let collector = Self {
+            port: RwLock::new(config.port),
             config,
             registry,
             system_cpu_usage,
             system_memory_usage,
             system_load_average,
+            process,
+            cgroup,
+            smart,
+            qos,
             nano_core_executions,
             nano_core_errors,
             nano_core_latency,
             consensus_proposals,
             consensus_votes,
             consensus_decisions,
-            fabric_events_total,
-            fabric_events_by_type: Arc::new(RwLock::new(HashMap::new())),
-            fabric_latency,
-            agent_tasks,
-            agent_successes,
-            agent_failures,
+            fabric,
+            agents,
             system_health_score,
             uptime_seconds,
+            start_time: SystemTime::now(),
+            latest_health: Arc::new(RwLock::new(None)),
+            tsdb,
+            alerts,
+            ingestor,
+            status_providers: Arc::new(RwLock::new(Vec::new())),
             server_handle: Arc::new(RwLock::new(None)),
+            server_shutdown: Arc::new(RwLock::new(None)),
+            otlp_handle: Arc::new(RwLock::new(None)),
+            sampler_handle: Arc::new(RwLock::new(None)),
+            retention_handle: Arc::new(RwLock::new(None)),
+            alerts_handle: Arc::new(RwLock::new(None)),
+            push_handle: Arc::new(RwLock::new(None)),
         };
         
+        collector.start_system_sampling().await;
+        collector.start_retention().await;
+        
+        if collector.alerts.has_rules() {
+            *collector.alerts_handle.write().await = Some(collector.alerts.clone().start());
+        }
+        
+        if collector.config.serve_http {
+            collector.start().await?;
+        }
+        
+        collector.start_otlp_export().await?;
+        
+        if let Some(push) = collector.config.push.clone() {
+            let pusher = push::MetricsPusher::new(push, collector.registry.clone())?;
+            *collector.push_handle.write().await = Some(pusher.start());
+        }
+        
         Ok(collector)
     }
 
-    /// Iniciar servidor de métricas
+    /// Iniciar servidor de métricas (sin efecto si ya está activo)
     pub async fn start(&self) -> Result<()> {
+        if self.server_handle.read().await.is_some() {
+            return Ok(());
+        }
+        
         let registry = self.registry.clone();
-        let port = self.config.port;
+        let addr = (self.config.bind_address, *self.port.read().await);
+        let require_auth = auth::require_auth(self.config.auth.clone());
         
         let metrics_route = warp::path("metrics")
             .and(warp::get())
+            .and(require_auth.clone())
             .map(move || {
                 let encoder = TextEncoder::new();
                 let metric_families = registry.gather();
@@ -261,18 +465,220 @@ impl MetricsCollector {
                 "service": "saai-metrics"
             })));
         
-        let routes = metrics_route.or(health_route);
+        let liveness_health = self.latest_health.clone();
+        let liveness_route = warp::path("healthz")
+            .and(warp::get())
+            .and_then(move || {
+                let health = liveness_health.clone();
+                async move {
+                    let health = health.read().await;
+                    Ok::<_, std::convert::Infallible>(probe_response(health.as_ref(), false))
+                }
+            });
+        
+        let readiness_health = self.latest_health.clone();
+        let readiness_route = warp::path("readyz")
+            .and(warp::get())
+            .and_then(move || {
+                let health = readiness_health.clone();
+                async move {
+                    let health = health.read().await;
+                    Ok::<_, std::convert::Infallible>(probe_response(health.as_ref(), true))
+                }
+            });
+        
+        let status_health = self.latest_health.clone();
+        let status_providers = self.status_providers.clone();
+        let status_route = warp::path!("api" / "v1" / "status")
+            .and(warp::get())
+            .and(require_auth)
+            .and_then(move || {
+                let health = status_health.clone();
+                let providers = status_providers.clone();
+                async move {
+                    let providers = providers.read().await.clone();
+                    let health = health.read().await.clone();
+                    let document = status::build_status(health.as_ref(), &providers).await;
+                    Ok::<_, std::convert::Infallible>(warp::reply::json(&document))
+                }
+            });
+        
+        let routes = metrics_route
+            .or(health_route)
+            .or(liveness_route)
+            .or(readiness_route)
+            .or(status_route)
+            .recover(auth::handle_rejection);
         
-        let server = warp::serve(routes)
-            .run(([0, 0, 0, 0], port));
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let shutdown_signal = async {
+            shutdown_rx.await.ok();
+        };
+        
+        let (addr, handle, scheme) = match (&self.config.tls_cert_path, &self.config.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                for path in [cert_path, key_path] {
+                    if !std::path::Path::new(path).exists() {
+                        return Err(anyhow::anyhow!("Fichero TLS no encontrado: {}", path));
+                    }
+                }
+                
+                let (addr, server) = warp::serve(routes)
+                    .tls()
+                    .cert_path(cert_path)
+                    .key_path(key_path)
+                    .try_bind_with_graceful_shutdown(addr, shutdown_signal)?;
+                (addr, tokio::spawn(server), "https")
+            }
+            (None, None) => {
+                let (addr, server) = warp::serve(routes)
+                    .try_bind_with_graceful_shutdown(addr, shutdown_signal)?;
+                (addr, tokio::spawn(server), "http")
+            }
+            _ => return Err(anyhow::anyhow!("TLS requiere tls_cert_path y tls_key_path")),
+        };
         
-        let handle = tokio::spawn(server);
         *self.server_handle.write().await = Some(handle);
+        *self.server_shutdown.write().await = Some(shutdown_tx);
         
-        info!("📊 Servidor de métricas iniciado en puerto {}", port);
+        info!(
+            "📊 Servidor de métricas iniciado en {}://{}{}",
+            scheme, addr, if self.config.auth.is_some() { " (autenticado)" } else { "" }
+        );
         Ok(())
     }
 
+    /// Detener el servidor HTTP dejando terminar las peticiones en curso
+    async fn stop_server(&self) {
+        if let Some(shutdown) = self.server_shutdown.write().await.take() {
+            let _ = shutdown.send(());
+        }
+        
+        if let Some(mut handle) = self.server_handle.write().await.take() {
+            if tokio::time::timeout(SERVER_SHUTDOWN_TIMEOUT, &mut handle).await.is_err() {
+                warn!("⚠️  Servidor de métricas no terminó a tiempo, abortando");
+                handle.abort();
+            }
+        }
+    }
+
+    /// Puerto del servidor HTTP
+    pub async fn port(&self) -> u16 {
+        *self.port.read().await
+    }
+
+    /// Registrar subsistema en el documento de `/api/v1/status`
+    pub async fn register_status_provider(&self, provider: Arc<dyn StatusProvider>) {
+        debug!("📊 Sección de estado registrada: {}", provider.section());
+        self.status_providers.write().await.push(provider);
+    }
+
+    /// Iniciar exportación OTLP de métricas si está configurada
+    async fn start_otlp_export(&self) -> Result<()> {
+        let otlp = match &self.config.otlp {
+            Some(otlp) if otlp.export_metrics => otlp.clone(),
+            _ => return Ok(()),
+        };
+        
+        let exporter = otlp::OtlpMetricsExporter::new(otlp, self.registry.clone())?;
+        *self.otlp_handle.write().await = Some(exporter.start());
+        
+        Ok(())
+    }
+
+    /// Iniciar muestreo de sistema, proceso y uptime cada `collection_interval_ms`
+    async fn start_system_sampling(&self) {
+        let interval_ms = self.config.collection_interval_ms.max(1);
+        let cpu_usage = self.system_cpu_usage.clone();
+        let memory_usage = self.system_memory_usage.clone();
+        let load_average = self.system_load_average.clone();
+        let process = self.process.clone();
+        let uptime_seconds = self.uptime_seconds.clone();
+        let start_time = self.start_time;
+        
+        let handle = tokio::spawn(async move {
+            let mut system = System::new();
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+            
+            loop {
+                interval.tick().await;
+                
+                let resources = SystemResources::sample(&mut system);
+                cpu_usage.set(resources.cpu_usage as f64);
+                memory_usage.set(resources.used_memory as f64);
+                load_average.set(resources.load_average[0]);
+                
+                process.sample().await;
+                
+                if let Ok(uptime) = start_time.elapsed() {
+                    uptime_seconds.set(uptime.as_secs() as i64);
+                }
+            }
+        });
+        
+        *self.sampler_handle.write().await = Some(handle);
+        debug!("📊 Muestreo de recursos del sistema cada {}ms", interval_ms);
+    }
+
+    /// Iniciar retención del registro cada `retention_resolution_ms`
+    async fn start_retention(&self) {
+        let resolution_ms = self.config.retention_resolution_ms.max(1);
+        let registry = self.registry.clone();
+        let tsdb = self.tsdb.clone();
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(resolution_ms));
+            
+            loop {
+                interval.tick().await;
+                tsdb.ingest(&registry.gather(), SystemTime::now());
+            }
+        });
+        
+        *self.retention_handle.write().await = Some(handle);
+    }
+
+    /// Asociar el Cognitive Fabric: publicar alertas e ingerir métricas del bus
+    ///
+    /// Sin conexión al fabric la ingestión queda desactivada con un aviso.
+    pub async fn attach_fabric(&self, fabric: Arc<CognitiveFabric>) {
+        self.alerts.attach_fabric(fabric.clone());
+        
+        for (subject, namespace) in INGESTED_SUBJECTS {
+            let ingestor = self.ingestor.clone();
+            let result = fabric
+                .subscribe(subject, move |payload| {
+                    ingestor.ingest(namespace, &[], payload);
+                })
+                .await;
+            
+            if let Err(e) = result {
+                warn!("⚠️  Ingestión de {} desactivada: {}", subject, e);
+            }
+        }
+        
+        let ingestor = self.ingestor.clone();
+        let result = fabric
+            .subscribe_events(SYSTEM_METRICS_SUBJECT, move |event| {
+                ingestor.ingest("reported", &[("source", &event.source)], &event.payload);
+            })
+            .await;
+        
+        if let Err(e) = result {
+            warn!("⚠️  Ingestión de {} desactivada: {}", SYSTEM_METRICS_SUBJECT, e);
+        }
+    }
+
+    /// Consultar serie retenida (`nombre` o `nombre{k="v"}`) en una ventana
+    pub fn get_series(&self, name: &str, range: Duration, step: Duration) -> Vec<Sample> {
+        self.tsdb.get_series(name, range, step)
+    }
+
+    /// Almacén de series temporales local
+    pub fn time_series(&self) -> Arc<TimeSeriesStore> {
+        self.tsdb.clone()
+    }
+
     /// Registrar recursos del sistema
     pub async fn record_system_resources(&self, resources: &SystemResources) {
         self.system_cpu_usage.set(resources.cpu_usage as f64);
@@ -282,17 +688,40 @@ impl MetricsCollector {
         debug!("📊 Métricas de sistema actualizadas");
     }
 
+    /// Registrar estadísticas del cgroup de SAAI
+    pub async fn record_cgroup_stats(&self, stats: &CgroupStats) {
+        self.cgroup.record(stats);
+    }
+
+    /// Registrar la última lectura SMART de los discos
+    pub async fn record_smart_health(&self, health: &[SmartHealth]) {
+        self.smart.record(health);
+    }
+
+    /// Registrar las estadísticas de las clases de tráfico moldeadas
+    pub async fn record_traffic_classes(&self, stats: &[TrafficClassStats]) {
+        self.qos.record(stats);
+    }
+
     /// Registrar ejecución de nano-núcleo
     pub async fn record_core_execution(
         &self,
-        core_type: NanoCoreType,
+        core_type: &NanoCoreType,
         instance: usize,
         success: bool,
     ) {
-        self.nano_core_executions.inc();
+        let core_label = core_type.to_string();
+        let instance_label = instance.to_string();
+        let result = if success { "success" } else { "error" };
+        
+        self.nano_core_executions
+            .with_label_values(&[&core_label, &instance_label, result])
+            .inc();
         
         if !success {
-            self.nano_core_errors.inc();
+            self.nano_core_errors
+                .with_label_values(&[&core_label, &instance_label])
+                .inc();
         }
         
         debug!(
@@ -302,8 +731,15 @@ impl MetricsCollector {
     }
 
     /// Registrar latencia de nano-núcleo
-    pub async fn record_core_latency(&self, latency_seconds: f64) {
-        self.nano_core_latency.observe(latency_seconds);
+    pub async fn record_core_latency(
+        &self,
+        core_type: &NanoCoreType,
+        instance: usize,
+        latency_seconds: f64,
+    ) {
+        self.nano_core_latency
+            .with_label_values(&[&core_type.to_string(), &instance.to_string()])
+            .observe(latency_seconds);
     }
 
     /// Registrar propuesta de consenso
@@ -321,58 +757,46 @@ impl MetricsCollector {
         self.consensus_decisions.inc();
     }
 
-    /// Registrar evento de Cognitive Fabric
-    pub async fn record_fabric_event(&self, event_type: &str, latency_seconds: f64) {
-        self.fabric_events_total.inc();
-        self.fabric_latency.observe(latency_seconds);
-        
-        // Registrar por tipo de evento
-        let mut events_by_type = self.fabric_events_by_type.write().await;
-        if let Some(counter) = events_by_type.get(event_type) {
-            counter.inc();
-        } else {
-            // Crear nuevo contador para este tipo de evento
-            if let Ok(counter) = IntCounter::with_opts(Opts::new(
-                &format!("saai_fabric_events_{}_total", event_type.to_lowercase()),
-                &format!("Total de eventos {} en Cognitive Fabric", event_type)
-            )) {
-                if self.registry.register(Box::new(counter.clone())).is_ok() {
-                    counter.inc();
-                    events_by_type.insert(event_type.to_string(), counter);
-                }
-            }
-        }
+    /// Métricas etiquetadas del Cognitive Fabric
+    pub fn fabric_metrics(&self) -> Arc<FabricMetrics> {
+        self.fabric.clone()
     }
 
     /// Registrar tarea de agente
-    pub async fn record_agent_task(&self, success: bool) {
-        self.agent_tasks.inc();
-        
-        if success {
-            self.agent_successes.inc();
-        } else {
-            self.agent_failures.inc();
-        }
+    pub async fn record_agent_task(&self, agent_name: &str, success: bool) {
+        self.agents.record_task(agent_name, success);
     }
 
-    /// Registrar error de agente
+    /// Registrar error de agente, clasificado a partir del mensaje
     pub async fn record_agent_error(&self, agent_name: &str, error: &str) {
-        self.agent_failures.inc();
+        let category = AgentErrorCategory::classify(error);
+        self.agents.record_error(agent_name, category);
         
         error!(
-            "❌ Error en agente {}: {}",
-            agent_name, error
+            "❌ Error en agente {} ({}): {}",
+            agent_name, category.as_str(), error
         );
     }
 
+    /// Tasa de error de un agente en los últimos 5 minutos
+    pub fn agent_error_rate(&self, agent_name: &str) -> f64 {
+        self.agents.error_rate(agent_name)
+    }
+
     /// Registrar estado de salud del sistema
     pub async fn record_health_status(&self, health: &SystemHealth) {
-        let health_score = if health.is_healthy() { 1.0 } else { 0.0 };
+        let health_score = health.score;
         self.system_health_score.set(health_score);
+        *self.latest_health.write().await = Some(health.clone());
         
         debug!("📊 Estado de salud registrado: {:.2}", health_score);
     }
 
+    /// Instante de creación del colector, origen del uptime
+    pub fn start_time(&self) -> SystemTime {
+        self.start_time
+    }
+
     /// Actualizar tiempo de actividad
     pub async fn update_uptime(&self, start_time: SystemTime) {
         if let Ok(duration) = SystemTime::now().duration_since(start_time) {
@@ -395,11 +819,94 @@ impl MetricsCollector {
     pub async fn shutdown(&self) -> Result<()> {
         info!("🛑 Cerrando colector de métricas");
         
-        if let Some(handle) = self.server_handle.write().await.take() {
+        self.stop_server().await;
+        
+        if let Some(handle) = self.otlp_handle.write().await.take() {
             handle.abort();
         }
         
+        if let Some(handle) = self.sampler_handle.write().await.take() {
+            handle.abort();
+        }
+        
+        if let Some(handle) = self.retention_handle.write().await.take() {
+            handle.abort();
+        }
+        
+        if let Some(handle) = self.alerts_handle.write().await.take() {
+            handle.abort();
+        }
+        
+        if let Some(handle) = self.push_handle.write().await.take() {
+            handle.abort();
+        }
+        
+        if self.config.otlp.as_ref().map_or(false, |otlp| otlp.export_traces) {
+            otlp::shutdown_tracer();
+        }
+        
         info!("✅ Colector de métricas cerrado");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl ConfigSubscriber for MetricsCollector {
+    fn subscriber_name(&self) -> &str {
+        "metrics"
+    }
+
+    fn sections(&self) -> &[ConfigSection] {
+        &[ConfigSection::General]
+    }
+
+    async fn on_config_change(&self, config: &CoreConfig, _changes: &[FieldChange]) -> Result<()> {
+        let previous = self.port().await;
+        if config.metrics_port == previous {
+            return Ok(());
+        }
+
+        *self.port.write().await = config.metrics_port;
+        if self.server_handle.read().await.is_none() {
+            return Ok(());
+        }
+
+        info!("📊 Reiniciando servidor de métricas: puerto {} -> {}", previous, config.metrics_port);
+        self.stop_server().await;
+
+        if let Err(e) = self.start().await {
+            // Volver al puerto anterior para no quedar sin exposición
+            *self.port.write().await = previous;
+            self.start().await?;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+/// Respuesta de las sondas `/healthz` y `/readyz`
+///
+/// Liveness solo falla con el sistema Degraded/Failed; readiness exige
+/// además una instantánea de salud en estado Running.
+fn probe_response(health: Option<&SystemHealth>, readiness: bool) -> warp::reply::Response {
+    let available = match health.map(|h| &h.overall_state) {
+        Some(NanoCoreState::Degraded) | Some(NanoCoreState::Failed) => false,
+        Some(NanoCoreState::Running) => true,
+        Some(_) | None => !readiness,
+    };
+    
+    let status = if available {
+        warp::http::StatusCode::OK
+    } else {
+        warp::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    
+    let body = warp::reply::json(&serde_json::json!({
+        "status": if available { "ok" } else { "unavailable" },
+        "state": health.map(|h| format!("{:?}", h.overall_state)),
+        "healthy": health.map_or(false, |h| h.is_healthy()),
+    }));
+    
+    warp::reply::with_status(body, status).into_response()
+}
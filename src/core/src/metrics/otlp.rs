@@ -0,0 +1,409 @@
+//! Exportación OTLP de métricas y trazas
+//!
+//! Envía periódicamente el registro Prometheus del colector a un colector
+//! OpenTelemetry (Grafana Cloud, Tempo, Datadog...) por gRPC, y construye la
+//! capa de `tracing` que exporta los spans al mismo endpoint.
+
+use anyhow::{anyhow, Result};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_proto::tonic::collector::metrics::v1::{
+    metrics_service_client::MetricsServiceClient, ExportMetricsServiceRequest,
+};
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue};
+use opentelemetry_proto::tonic::metrics::v1::{
+    metric, number_data_point, summary_data_point, AggregationTemporality, Gauge, Histogram,
+    HistogramDataPoint, Metric, NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary,
+    SummaryDataPoint,
+};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use otlp_tonic::metadata::{AsciiMetadataValue, MetadataKey, MetadataMap};
+use otlp_tonic::transport::Channel;
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::Registry;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Configuración del exportador OTLP
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Endpoint gRPC del colector (`http://localhost:4317`)
+    pub endpoint: String,
+    /// Cabeceras enviadas en cada exportación (p. ej. autenticación)
+    pub headers: HashMap<String, String>,
+    pub service_name: String,
+    pub export_interval_ms: u64,
+    pub timeout_ms: u64,
+    pub export_metrics: bool,
+    pub export_traces: bool,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            headers: HashMap::new(),
+            service_name: "saai-core".to_string(),
+            export_interval_ms: 10_000,
+            timeout_ms: 5_000,
+            export_metrics: true,
+            export_traces: true,
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// Configuración desde las variables estándar `OTEL_EXPORTER_OTLP_*`
+    ///
+    /// Devuelve `None` si `OTEL_EXPORTER_OTLP_ENDPOINT` no está definida.
+    pub fn from_env() -> Option<Self> {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()?;
+        let mut config = Self {
+            endpoint,
+            ..Default::default()
+        };
+
+        if let Ok(headers) = std::env::var("OTEL_EXPORTER_OTLP_HEADERS") {
+            config.headers = parse_headers(&headers);
+        }
+
+        if let Ok(service_name) = std::env::var("OTEL_SERVICE_NAME") {
+            config.service_name = service_name;
+        }
+
+        Some(config)
+    }
+
+    /// Cabeceras como metadatos gRPC
+    fn metadata(&self) -> Result<MetadataMap> {
+        let mut metadata = MetadataMap::new();
+
+        for (key, value) in &self.headers {
+            let key = MetadataKey::from_str(&key.to_lowercase())
+                .map_err(|_| anyhow!("Cabecera OTLP inválida: {}", key))?;
+            let value = value
+                .parse::<AsciiMetadataValue>()
+                .map_err(|_| anyhow!("Valor inválido para cabecera OTLP {}", key))?;
+            metadata.insert(key, value);
+        }
+
+        Ok(metadata)
+    }
+
+    fn resource_attributes(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("service.name", self.service_name.clone()),
+            ("service.version", env!("CARGO_PKG_VERSION").to_string()),
+        ]
+    }
+}
+
+/// Interpretar cabeceras en formato `clave=valor,clave2=valor2`
+fn parse_headers(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// Exportador periódico del registro Prometheus hacia OTLP
+pub struct OtlpMetricsExporter {
+    config: OtlpConfig,
+    registry: Registry,
+    client: MetricsServiceClient<Channel>,
+    metadata: MetadataMap,
+    start_time_unix_nano: u64,
+}
+
+impl OtlpMetricsExporter {
+    /// Crear exportador; la conexión se establece en el primer envío
+    pub fn new(config: OtlpConfig, registry: Registry) -> Result<Self> {
+        let channel = Channel::from_shared(config.endpoint.clone())?
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .connect_lazy();
+
+        Ok(Self {
+            metadata: config.metadata()?,
+            client: MetricsServiceClient::new(channel),
+            config,
+            registry,
+            start_time_unix_nano: unix_nanos(SystemTime::now()),
+        })
+    }
+
+    /// Exportar el estado actual del registro
+    pub async fn export(&mut self) -> Result<()> {
+        let now = unix_nanos(SystemTime::now());
+        let metrics: Vec<Metric> = self
+            .registry
+            .gather()
+            .iter()
+            .filter_map(|family| convert_family(family, self.start_time_unix_nano, now))
+            .collect();
+
+        if metrics.is_empty() {
+            return Ok(());
+        }
+
+        let count = metrics.len();
+        let resource = Resource {
+            attributes: self
+                .config
+                .resource_attributes()
+                .into_iter()
+                .map(|(key, value)| string_attribute(key, value))
+                .collect(),
+            dropped_attributes_count: 0,
+        };
+
+        let mut request = otlp_tonic::Request::new(ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(resource),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(InstrumentationScope {
+                        name: "saai-core".to_string(),
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        ..Default::default()
+                    }),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        });
+        *request.metadata_mut() = self.metadata.clone();
+
+        self.client
+            .export(request)
+            .await
+            .map_err(|status| anyhow!("Exportación OTLP rechazada: {}", status))?;
+
+        debug!("📤 {} familias de métricas exportadas vía OTLP", count);
+        Ok(())
+    }
+
+    /// Iniciar exportación periódica en background
+    pub fn start(mut self) -> JoinHandle<()> {
+        let interval_ms = self.config.export_interval_ms.max(1);
+        info!(
+            "📡 Exportación OTLP de métricas hacia {} cada {}ms",
+            self.config.endpoint, interval_ms
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.export().await {
+                    warn!("⚠️  Error exportando métricas OTLP: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Capa de `tracing` que exporta spans al colector OTLP
+pub fn trace_layer<S>(config: &OtlpConfig) -> Result<OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(config.endpoint.clone())
+        .with_timeout(Duration::from_millis(config.timeout_ms))
+        .with_metadata(config.metadata()?);
+
+    let resource = opentelemetry_sdk::Resource::new(
+        config
+            .resource_attributes()
+            .into_iter()
+            .map(|(key, value)| opentelemetry::KeyValue::new(key, value)),
+    );
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Vaciar y cerrar el proveedor global de trazas
+pub fn shutdown_tracer() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Convertir una familia Prometheus en métrica OTLP
+fn convert_family(family: &MetricFamily, start_time: u64, now: u64) -> Option<Metric> {
+    let samples = family.get_metric();
+    if samples.is_empty() {
+        return None;
+    }
+
+    let number_point = |labels: Vec<KeyValue>, value: f64| NumberDataPoint {
+        attributes: labels,
+        start_time_unix_nano: start_time,
+        time_unix_nano: now,
+        value: Some(number_data_point::Value::AsDouble(value)),
+        ..Default::default()
+    };
+
+    let data = match family.get_field_type() {
+        MetricType::COUNTER => metric::Data::Sum(Sum {
+            data_points: samples
+                .iter()
+                .map(|m| number_point(labels(m), m.get_counter().get_value()))
+                .collect(),
+            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+            is_monotonic: true,
+        }),
+        MetricType::GAUGE => metric::Data::Gauge(Gauge {
+            data_points: samples
+                .iter()
+                .map(|m| number_point(labels(m), m.get_gauge().get_value()))
+                .collect(),
+        }),
+        MetricType::UNTYPED => metric::Data::Gauge(Gauge {
+            data_points: samples
+                .iter()
+                .map(|m| number_point(labels(m), m.get_untyped().get_value()))
+                .collect(),
+        }),
+        MetricType::HISTOGRAM => metric::Data::Histogram(Histogram {
+            data_points: samples
+                .iter()
+                .map(|m| {
+                    let histogram = m.get_histogram();
+                    let (explicit_bounds, bucket_counts) =
+                        split_buckets(histogram.get_bucket(), histogram.get_sample_count());
+
+                    HistogramDataPoint {
+                        attributes: labels(m),
+                        start_time_unix_nano: start_time,
+                        time_unix_nano: now,
+                        count: histogram.get_sample_count(),
+                        sum: Some(histogram.get_sample_sum()),
+                        bucket_counts,
+                        explicit_bounds,
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+        }),
+        MetricType::SUMMARY => metric::Data::Summary(Summary {
+            data_points: samples
+                .iter()
+                .map(|m| {
+                    let summary = m.get_summary();
+                    SummaryDataPoint {
+                        attributes: labels(m),
+                        start_time_unix_nano: start_time,
+                        time_unix_nano: now,
+                        count: summary.get_sample_count(),
+                        sum: summary.get_sample_sum(),
+                        quantile_values: summary
+                            .get_quantile()
+                            .iter()
+                            .map(|q| summary_data_point::ValueAtQuantile {
+                                quantile: q.get_quantile(),
+                                value: q.get_value(),
+                            })
+                            .collect(),
+                        flags: 0,
+                    }
+                })
+                .collect(),
+        }),
+    };
+
+    Some(Metric {
+        name: family.get_name().to_string(),
+        description: family.get_help().to_string(),
+        unit: String::new(),
+        data: Some(data),
+    })
+}
+
+/// Pasar buckets acumulados de Prometheus a límites y conteos OTLP
+///
+/// OTLP espera un conteo por bucket más uno final para `+Inf`.
+fn split_buckets(buckets: &[prometheus::proto::Bucket], sample_count: u64) -> (Vec<f64>, Vec<u64>) {
+    let mut bounds = Vec::with_capacity(buckets.len());
+    let mut counts = Vec::with_capacity(buckets.len() + 1);
+    let mut previous = 0;
+
+    for bucket in buckets.iter().filter(|b| b.get_upper_bound().is_finite()) {
+        bounds.push(bucket.get_upper_bound());
+        counts.push(bucket.get_cumulative_count().saturating_sub(previous));
+        previous = bucket.get_cumulative_count();
+    }
+    counts.push(sample_count.saturating_sub(previous));
+
+    (bounds, counts)
+}
+
+fn labels(metric: &prometheus::proto::Metric) -> Vec<KeyValue> {
+    metric
+        .get_label()
+        .iter()
+        .map(|label| string_attribute(label.get_name(), label.get_value().to_string()))
+        .collect()
+}
+
+fn string_attribute(key: &str, value: String) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value)),
+        }),
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{HistogramOpts, HistogramVec};
+
+    #[test]
+    fn test_histogram_conversion() {
+        let registry = Registry::new();
+        let histogram = HistogramVec::new(
+            HistogramOpts::new("saai_test_latency_seconds", "Latencia de prueba").buckets(vec![0.1, 1.0]),
+            &["core_type"],
+        )
+        .unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+
+        for value in [0.05, 0.5, 0.7, 5.0] {
+            histogram.with_label_values(&["OS"]).observe(value);
+        }
+
+        let metric = convert_family(&registry.gather()[0], 0, 1).unwrap();
+        match metric.data {
+            Some(metric::Data::Histogram(data)) => {
+                let point = &data.data_points[0];
+                assert_eq!(point.explicit_bounds, vec![0.1, 1.0]);
+                assert_eq!(point.bucket_counts, vec![1, 2, 1]);
+                assert_eq!(point.count, 4);
+            }
+            other => panic!("Se esperaba histograma: {:?}", other),
+        }
+
+        let headers = parse_headers("Authorization=Bearer abc, x-scope = saai");
+        assert_eq!(headers.get("Authorization").map(String::as_str), Some("Bearer abc"));
+        assert_eq!(headers.get("x-scope").map(String::as_str), Some("saai"));
+    }
+}
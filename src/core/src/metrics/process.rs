@@ -0,0 +1,114 @@
+//! Métricas del propio proceso
+//!
+//! Memoria residente, descriptores abiertos, hilos y estado del runtime
+//! tokio (tareas vivas, cola global, retardo de planificación) como gauges
+//! `process_*`, para depurar fugas y saturación del runtime.
+
+use anyhow::Result;
+use prometheus::{Gauge, IntGauge, Opts, Registry};
+use std::time::Instant;
+
+/// Gauges del proceso SAAI
+pub struct ProcessMetrics {
+    resident_memory: IntGauge,
+    open_fds: IntGauge,
+    threads: IntGauge,
+    tokio_workers: IntGauge,
+    tokio_alive_tasks: IntGauge,
+    tokio_global_queue_depth: IntGauge,
+    tokio_scheduler_delay: Gauge,
+}
+
+impl ProcessMetrics {
+    /// Crear y registrar las métricas en el registro indicado
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let int_gauge = |name: &str, help: &str| -> Result<IntGauge> {
+            let gauge = IntGauge::with_opts(Opts::new(name, help))?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        let resident_memory = int_gauge("process_resident_memory_bytes", "Memoria residente del proceso")?;
+        let open_fds = int_gauge("process_open_fds", "Descriptores de fichero abiertos")?;
+        let threads = int_gauge("process_threads", "Hilos del proceso")?;
+        let tokio_workers = int_gauge("process_tokio_workers", "Hilos worker del runtime tokio")?;
+        let tokio_alive_tasks = int_gauge("process_tokio_alive_tasks", "Tareas vivas en el runtime tokio")?;
+        let tokio_global_queue_depth = int_gauge(
+            "process_tokio_global_queue_depth",
+            "Tareas pendientes en la cola global de tokio",
+        )?;
+
+        let tokio_scheduler_delay = Gauge::with_opts(Opts::new(
+            "process_tokio_scheduler_delay_seconds",
+            "Retardo entre spawn y primer poll de una tarea sonda",
+        ))?;
+        registry.register(Box::new(tokio_scheduler_delay.clone()))?;
+
+        Ok(Self {
+            resident_memory,
+            open_fds,
+            threads,
+            tokio_workers,
+            tokio_alive_tasks,
+            tokio_global_queue_depth,
+            tokio_scheduler_delay,
+        })
+    }
+
+    /// Muestrear proceso y runtime (debe llamarse dentro del runtime tokio)
+    pub async fn sample(&self) {
+        if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+            let (rss, threads) = parse_proc_status(&status);
+            if let Some(rss) = rss {
+                self.resident_memory.set(rss as i64);
+            }
+            if let Some(threads) = threads {
+                self.threads.set(threads as i64);
+            }
+        }
+
+        if let Ok(fds) = std::fs::read_dir("/proc/self/fd") {
+            self.open_fds.set(fds.count() as i64);
+        }
+
+        let runtime = tokio::runtime::Handle::current().metrics();
+        self.tokio_workers.set(runtime.num_workers() as i64);
+        self.tokio_alive_tasks.set(runtime.num_alive_tasks() as i64);
+        self.tokio_global_queue_depth.set(runtime.global_queue_depth() as i64);
+
+        let spawned = Instant::now();
+        if let Ok(delay) = tokio::spawn(async move { spawned.elapsed() }).await {
+            self.tokio_scheduler_delay.set(delay.as_secs_f64());
+        }
+    }
+}
+
+/// Extraer `VmRSS` (en bytes) y `Threads` de `/proc/self/status`
+fn parse_proc_status(status: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    (field("VmRSS:").map(|kb| kb * 1024), field("Threads:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_process_sampling() {
+        let (rss, threads) = parse_proc_status("Name:\tsaai\nVmRSS:\t  2048 kB\nThreads:\t7\n");
+        assert_eq!(rss, Some(2048 * 1024));
+        assert_eq!(threads, Some(7));
+
+        let metrics = ProcessMetrics::new(&Registry::new()).unwrap();
+        metrics.sample().await;
+        assert!(metrics.tokio_workers.get() >= 1);
+        assert!(metrics.tokio_scheduler_delay.get() >= 0.0);
+    }
+}
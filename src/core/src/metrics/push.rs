@@ -0,0 +1,337 @@
+//! Envío activo de métricas
+//!
+//! Para nodos edge tras NAT que Prometheus no puede scrapear: empuja
+//! periódicamente el registro a un Pushgateway o a un endpoint
+//! `remote_write`, con autenticación y reintentos con backoff exponencial.
+
+use anyhow::Result;
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::{Encoder, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::util::Backoff;
+
+/// Destino del envío
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushMode {
+    /// `PUT /metrics/job/<job>` en formato texto
+    Pushgateway,
+    /// Protocolo `remote_write` de Prometheus (protobuf + snappy)
+    RemoteWrite,
+}
+
+/// Configuración del envío activo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushConfig {
+    pub mode: PushMode,
+    pub url: String,
+    pub job: String,
+    /// Instancia para el agrupamiento del Pushgateway
+    pub instance: Option<String>,
+    pub interval_ms: u64,
+    pub timeout_ms: u64,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub bearer_token: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub max_retries: u32,
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            mode: PushMode::Pushgateway,
+            url: "http://localhost:9091".to_string(),
+            job: "saai-core".to_string(),
+            instance: None,
+            interval_ms: 15_000,
+            timeout_ms: 10_000,
+            username: None,
+            password: None,
+            bearer_token: None,
+            headers: HashMap::new(),
+            max_retries: 3,
+            retry_backoff_ms: 500,
+        }
+    }
+}
+
+/// Mensajes `remote_write` (prompb)
+mod prompb {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+}
+
+/// Cliente de envío periódico
+pub struct MetricsPusher {
+    config: PushConfig,
+    registry: Registry,
+    http: reqwest::Client,
+}
+
+impl MetricsPusher {
+    pub fn new(config: PushConfig, registry: Registry) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()?;
+
+        Ok(Self { config, registry, http })
+    }
+
+    /// Enviar el estado actual del registro, con reintentos
+    pub async fn push(&self) -> Result<()> {
+        let families = self.registry.gather();
+        let mut backoff = Backoff::new(Duration::from_millis(self.config.retry_backoff_ms)).with_jitter(0.5);
+
+        loop {
+            match self.push_once(&families).await {
+                Ok(()) => {
+                    debug!("📤 Métricas enviadas a {}", self.config.url);
+                    return Ok(());
+                }
+                Err(e) if backoff.attempt() < self.config.max_retries && is_retryable(&e) => {
+                    let attempt = backoff.attempt();
+                    let delay = backoff.next_delay();
+                    warn!(
+                        "⚠️  Envío de métricas fallido (intento {}): {}; reintentando en {}ms",
+                        attempt + 1, e, delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn push_once(&self, families: &[MetricFamily]) -> Result<()> {
+        let mut request = match self.config.mode {
+            PushMode::Pushgateway => {
+                let mut buffer = Vec::new();
+                TextEncoder::new().encode(families, &mut buffer)?;
+
+                self.http
+                    .put(self.pushgateway_url())
+                    .header("content-type", TextEncoder::new().format_type())
+                    .body(buffer)
+            }
+            PushMode::RemoteWrite => {
+                let write_request = prompb::WriteRequest {
+                    timeseries: to_timeseries(families, unix_millis(SystemTime::now())),
+                };
+                let body = snap::raw::Encoder::new()
+                    .compress_vec(&prost::Message::encode_to_vec(&write_request))?;
+
+                self.http
+                    .post(&self.config.url)
+                    .header("content-type", "application/x-protobuf")
+                    .header("content-encoding", "snappy")
+                    .header("x-prometheus-remote-write-version", "0.1.0")
+                    .body(body)
+            }
+        };
+
+        for (name, value) in &self.config.headers {
+            request = request.header(name, value);
+        }
+
+        if let Some(token) = &self.config.bearer_token {
+            request = request.bearer_auth(token);
+        } else if let Some(username) = &self.config.username {
+            request = request.basic_auth(username, self.config.password.as_ref());
+        }
+
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    fn pushgateway_url(&self) -> String {
+        let mut url = format!(
+            "{}/metrics/job/{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.job
+        );
+
+        if let Some(instance) = &self.config.instance {
+            url.push_str(&format!("/instance/{}", instance));
+        }
+
+        url
+    }
+
+    /// Iniciar envío periódico en background
+    pub fn start(self) -> JoinHandle<()> {
+        let interval_ms = self.config.interval_ms.max(1);
+        info!(
+            "📡 Envío de métricas ({:?}) hacia {} cada {}ms",
+            self.config.mode, self.config.url, interval_ms
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.push().await {
+                    warn!("⚠️  Error enviando métricas: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Reintentar errores de red, 429 y 5xx; no otros 4xx
+fn is_retryable(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) {
+        Some(status) => status.is_server_error() || status.as_u16() == 429,
+        None => true,
+    }
+}
+
+/// Convertir familias en series `remote_write`
+fn to_timeseries(families: &[MetricFamily], timestamp: i64) -> Vec<prompb::TimeSeries> {
+    let mut series = Vec::new();
+
+    for family in families {
+        let name = family.get_name();
+
+        for metric in family.get_metric() {
+            let labels: Vec<(String, String)> = metric
+                .get_label()
+                .iter()
+                .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+                .collect();
+
+            let mut push = |suffix: &str, extra: Option<(&str, String)>, value: f64| {
+                let mut series_labels = vec![prompb::Label {
+                    name: "__name__".to_string(),
+                    value: format!("{}{}", name, suffix),
+                }];
+                series_labels.extend(labels.iter().map(|(name, value)| prompb::Label {
+                    name: name.clone(),
+                    value: value.clone(),
+                }));
+                if let Some((name, value)) = extra {
+                    series_labels.push(prompb::Label {
+                        name: name.to_string(),
+                        value,
+                    });
+                }
+
+                series.push(prompb::TimeSeries {
+                    labels: series_labels,
+                    samples: vec![prompb::Sample { value, timestamp }],
+                });
+            };
+
+            match family.get_field_type() {
+                MetricType::COUNTER => push("", None, metric.get_counter().get_value()),
+                MetricType::GAUGE => push("", None, metric.get_gauge().get_value()),
+                MetricType::UNTYPED => push("", None, metric.get_untyped().get_value()),
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    for bucket in histogram.get_bucket() {
+                        push(
+                            "_bucket",
+                            Some(("le", bucket.get_upper_bound().to_string())),
+                            bucket.get_cumulative_count() as f64,
+                        );
+                    }
+                    push("_bucket", Some(("le", "+Inf".to_string())), histogram.get_sample_count() as f64);
+                    push("_sum", None, histogram.get_sample_sum());
+                    push("_count", None, histogram.get_sample_count() as f64);
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        push(
+                            "",
+                            Some(("quantile", quantile.get_quantile().to_string())),
+                            quantile.get_value(),
+                        );
+                    }
+                    push("_sum", None, summary.get_sample_sum());
+                    push("_count", None, summary.get_sample_count() as f64);
+                }
+            }
+        }
+    }
+
+    series
+}
+
+fn unix_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{HistogramOpts, HistogramVec};
+
+    #[test]
+    fn test_remote_write_series() {
+        let registry = Registry::new();
+        let latency = HistogramVec::new(
+            HistogramOpts::new("latency_seconds", "Latencia").buckets(vec![0.1]),
+            &["core_type"],
+        )
+        .unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+        latency.with_label_values(&["OS"]).observe(0.05);
+
+        let series = to_timeseries(&registry.gather(), 1);
+        let names: Vec<String> = series
+            .iter()
+            .map(|s| {
+                let le = s.labels.iter().find(|l| l.name == "le").map(|l| l.value.clone());
+                format!("{}{}", s.labels[0].value, le.map(|le| format!("@{}", le)).unwrap_or_default())
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "latency_seconds_bucket@0.1",
+                "latency_seconds_bucket@+Inf",
+                "latency_seconds_sum",
+                "latency_seconds_count",
+            ]
+        );
+        assert!(series[0].labels.iter().any(|l| l.name == "core_type" && l.value == "OS"));
+    }
+}
@@ -0,0 +1,52 @@
+//! Métricas de las clases de tráfico QoS
+//!
+//! Gauges `network_qos_class_*` etiquetados por interfaz y clase con la última
+//! lectura de `tc -s class show` que hace NetworkCore cuando el shaping está
+//! aplicado. Los contadores son los acumulados que da el kernel.
+
+use anyhow::Result;
+use prometheus::{GaugeVec, Opts, Registry};
+
+use crate::nano_cores::shaping::TrafficClassStats;
+
+/// Gauges por clase HTB etiquetados por `interface` y `class`
+pub struct QosMetrics {
+    bytes: GaugeVec,
+    packets: GaugeVec,
+    drops: GaugeVec,
+    overlimits: GaugeVec,
+    backlog: GaugeVec,
+    rate: GaugeVec,
+}
+
+impl QosMetrics {
+    /// Crear y registrar las métricas en el registro indicado
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let gauge = |name: &str, help: &str| -> Result<GaugeVec> {
+            let gauge = GaugeVec::new(Opts::new(name, help), &["interface", "class"])?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        Ok(Self {
+            bytes: gauge("network_qos_class_bytes", "Bytes enviados por la clase")?,
+            packets: gauge("network_qos_class_packets", "Paquetes enviados por la clase")?,
+            drops: gauge("network_qos_class_drops", "Paquetes descartados en la clase")?,
+            overlimits: gauge("network_qos_class_overlimits", "Veces que la clase superó su tasa")?,
+            backlog: gauge("network_qos_class_backlog_bytes", "Bytes en cola de la clase")?,
+            rate: gauge("network_qos_class_rate_bits", "Tasa de la clase en bits/s")?,
+        })
+    }
+
+    pub fn record(&self, stats: &[TrafficClassStats]) {
+        for class in stats {
+            let labels = [class.interface.as_str(), class.class.as_str()];
+            self.bytes.with_label_values(&labels).set(class.bytes as f64);
+            self.packets.with_label_values(&labels).set(class.packets as f64);
+            self.drops.with_label_values(&labels).set(class.drops as f64);
+            self.overlimits.with_label_values(&labels).set(class.overlimits as f64);
+            self.backlog.with_label_values(&labels).set(class.backlog_bytes as f64);
+            self.rate.with_label_values(&labels).set(class.rate_bps);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Métricas SMART por disco
+//!
+//! Gauges `disk_smart_*` etiquetados por dispositivo con la última lectura
+//! de HardwareCore. Los atributos que el disco no informa no se exportan.
+
+use anyhow::Result;
+use prometheus::{GaugeVec, Opts, Registry};
+
+use crate::nano_cores::smart::SmartHealth;
+
+/// Gauges SMART etiquetados por `device`
+pub struct SmartMetrics {
+    healthy: GaugeVec,
+    temperature: GaugeVec,
+    power_on_hours: GaugeVec,
+    reallocated_sectors: GaugeVec,
+    pending_sectors: GaugeVec,
+    media_errors: GaugeVec,
+    wear_level: GaugeVec,
+}
+
+impl SmartMetrics {
+    /// Crear y registrar las métricas en el registro indicado
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let gauge = |name: &str, help: &str| -> Result<GaugeVec> {
+            let gauge = GaugeVec::new(Opts::new(name, help), &["device"])?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok(gauge)
+        };
+
+        Ok(Self {
+            healthy: gauge("disk_smart_healthy", "Autoevaluación SMART superada (1) o fallida (0)")?,
+            temperature: gauge("disk_smart_temperature_celsius", "Temperatura del disco")?,
+            power_on_hours: gauge("disk_smart_power_on_hours", "Horas de funcionamiento del disco")?,
+            reallocated_sectors: gauge("disk_smart_reallocated_sectors", "Sectores reasignados")?,
+            pending_sectors: gauge("disk_smart_pending_sectors", "Sectores pendientes de reasignar")?,
+            media_errors: gauge("disk_smart_media_errors", "Errores de integridad de datos (NVMe)")?,
+            wear_level: gauge("disk_smart_wear_percent", "Vida útil consumida del SSD")?,
+        })
+    }
+
+    pub fn record(&self, health: &[SmartHealth]) {
+        for disk in health {
+            let set = |gauge: &GaugeVec, value: Option<f64>| {
+                if let Some(value) = value {
+                    gauge.with_label_values(&[&disk.device]).set(value);
+                }
+            };
+            set(&self.healthy, disk.passed.map(|passed| if passed { 1.0 } else { 0.0 }));
+            set(&self.temperature, disk.temperature.map(f64::from));
+            set(&self.power_on_hours, disk.power_on_hours.map(|h| h as f64));
+            set(&self.reallocated_sectors, disk.reallocated_sectors.map(|s| s as f64));
+            set(&self.pending_sectors, disk.pending_sectors.map(|s| s as f64));
+            set(&self.media_errors, disk.media_errors.map(|e| e as f64));
+            set(&self.wear_level, disk.wear_level.map(f64::from));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! API de estado en JSON
+//!
+//! Documento estructurado servido en `/api/v1/status` para la capa de UI y
+//! scripts. Cada subsistema (consenso, fabric, seguridad) aporta su sección
+//! registrándose como `StatusProvider` en el `MetricsCollector`.
+
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+use crate::nano_cores::SystemHealth;
+
+/// Subsistema que aporta una sección al documento de estado
+#[async_trait]
+pub trait StatusProvider: Send + Sync {
+    /// Nombre de la sección (`consensus`, `fabric`, `security`...)
+    fn section(&self) -> &'static str;
+
+    /// Estado actual de la sección
+    async fn status(&self) -> Value;
+}
+
+/// Construir el documento de estado completo
+pub async fn build_status(
+    health: Option<&SystemHealth>,
+    providers: &[Arc<dyn StatusProvider>],
+) -> Value {
+    let mut document = Map::new();
+    document.insert("service".to_string(), json!("saai-core"));
+    document.insert("version".to_string(), json!(env!("CARGO_PKG_VERSION")));
+    document.insert("timestamp".to_string(), json!(chrono::Utc::now()));
+    document.insert(
+        "health".to_string(),
+        serde_json::to_value(health).unwrap_or(Value::Null),
+    );
+
+    for provider in providers {
+        document.insert(provider.section().to_string(), provider.status().await);
+    }
+
+    Value::Object(document)
+}
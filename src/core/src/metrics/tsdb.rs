@@ -0,0 +1,219 @@
+//! Series temporales locales
+//!
+//! Buffer circular en memoria con las muestras recientes del registro
+//! Prometheus, retenidas durante `retention_hours`, para componentes
+//! predictivos (FailurePredictor) que necesitan historia sin un TSDB externo.
+
+use prometheus::proto::{MetricFamily, MetricType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Máximo de series distintas retenidas
+const MAX_SERIES: usize = 10_000;
+
+/// Muestra de una serie
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Milisegundos desde UNIX_EPOCH
+    pub timestamp_ms: u64,
+    pub value: f64,
+}
+
+/// Almacén de series en buffers circulares
+pub struct TimeSeriesStore {
+    retention: Duration,
+    capacity: usize,
+    series: RwLock<HashMap<String, VecDeque<Sample>>>,
+}
+
+impl TimeSeriesStore {
+    /// Crear almacén para la retención y resolución de muestreo indicadas
+    pub fn new(retention: Duration, resolution: Duration) -> Self {
+        let resolution_ms = resolution.as_millis().max(1);
+        let capacity = (retention.as_millis() / resolution_ms).max(1) as usize;
+
+        Self {
+            retention,
+            capacity,
+            series: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Añadir una muestra a una serie
+    pub fn record(&self, name: &str, timestamp: SystemTime, value: f64) {
+        let timestamp_ms = unix_millis(timestamp);
+        let cutoff = timestamp_ms.saturating_sub(self.retention.as_millis() as u64);
+        let mut series = self.series.write().unwrap();
+
+        if !series.contains_key(name) && series.len() >= MAX_SERIES {
+            return;
+        }
+
+        let samples = series
+            .entry(name.to_string())
+            .or_insert_with(|| VecDeque::with_capacity(self.capacity.min(1024)));
+
+        while samples.len() >= self.capacity
+            || samples.front().map_or(false, |s| s.timestamp_ms < cutoff)
+        {
+            samples.pop_front();
+        }
+
+        samples.push_back(Sample { timestamp_ms, value });
+    }
+
+    /// Registrar el estado actual de todas las familias del registro
+    ///
+    /// Cada conjunto de etiquetas es una serie (`nombre{k="v"}`); si hay
+    /// varios, se guarda además la suma bajo el nombre sin etiquetas. Los
+    /// histogramas y summaries se guardan como `_count` y `_sum`.
+    pub fn ingest(&self, families: &[MetricFamily], timestamp: SystemTime) {
+        for family in families {
+            let name = family.get_name();
+            let mut totals: HashMap<String, f64> = HashMap::new();
+
+            for metric in family.get_metric() {
+                let labels = series_labels(metric);
+                let values: Vec<(String, f64)> = match family.get_field_type() {
+                    MetricType::COUNTER => vec![(name.to_string(), metric.get_counter().get_value())],
+                    MetricType::GAUGE => vec![(name.to_string(), metric.get_gauge().get_value())],
+                    MetricType::UNTYPED => vec![(name.to_string(), metric.get_untyped().get_value())],
+                    MetricType::HISTOGRAM => {
+                        let histogram = metric.get_histogram();
+                        vec![
+                            (format!("{}_count", name), histogram.get_sample_count() as f64),
+                            (format!("{}_sum", name), histogram.get_sample_sum()),
+                        ]
+                    }
+                    MetricType::SUMMARY => {
+                        let summary = metric.get_summary();
+                        vec![
+                            (format!("{}_count", name), summary.get_sample_count() as f64),
+                            (format!("{}_sum", name), summary.get_sample_sum()),
+                        ]
+                    }
+                };
+
+                for (series_name, value) in values {
+                    if !labels.is_empty() {
+                        self.record(&format!("{}{}", series_name, labels), timestamp, value);
+                    }
+                    *totals.entry(series_name).or_insert(0.0) += value;
+                }
+            }
+
+            for (series_name, value) in totals {
+                self.record(&series_name, timestamp, value);
+            }
+        }
+    }
+
+    /// Consultar una serie en la ventana `range` hasta ahora
+    ///
+    /// Con `step` distinto de cero devuelve una muestra por intervalo (la
+    /// última de cada uno); con `Duration::ZERO` devuelve todas.
+    pub fn get_series(&self, name: &str, range: Duration, step: Duration) -> Vec<Sample> {
+        let now = unix_millis(SystemTime::now());
+        let start = now.saturating_sub(range.as_millis() as u64);
+        let series = self.series.read().unwrap();
+
+        let samples = match series.get(name) {
+            Some(samples) => samples.iter().filter(|s| s.timestamp_ms >= start),
+            None => return Vec::new(),
+        };
+
+        let step_ms = step.as_millis() as u64;
+        if step_ms == 0 {
+            return samples.copied().collect();
+        }
+
+        let mut result: Vec<Sample> = Vec::new();
+        for sample in samples {
+            let window = start + (sample.timestamp_ms - start) / step_ms * step_ms;
+            let aligned = Sample {
+                timestamp_ms: window,
+                value: sample.value,
+            };
+
+            match result.last_mut() {
+                Some(last) if last.timestamp_ms == window => *last = aligned,
+                _ => result.push(aligned),
+            }
+        }
+
+        result
+    }
+
+    /// Nombres de las series retenidas
+    pub fn series_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.series.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Etiquetas en formato Prometheus (`{k="v",...}`), ordenadas por nombre
+fn series_labels(metric: &prometheus::proto::Metric) -> String {
+    let mut labels: Vec<(&str, &str)> = metric
+        .get_label()
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .collect();
+
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    labels.sort();
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, value))
+        .collect();
+
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{IntCounterVec, Opts, Registry};
+
+    #[test]
+    fn test_ring_buffer_and_query() {
+        let store = TimeSeriesStore::new(Duration::from_secs(60), Duration::from_secs(10));
+        let now = SystemTime::now();
+
+        for i in 0..10u64 {
+            store.record("cpu", now - Duration::from_secs(90 - i * 10), i as f64);
+        }
+
+        // Capacidad de 6 muestras y retención de 60s
+        let all = store.get_series("cpu", Duration::from_secs(3600), Duration::ZERO);
+        assert_eq!(all.len(), 6);
+        assert_eq!(all.last().unwrap().value, 9.0);
+
+        let stepped = store.get_series("cpu", Duration::from_secs(60), Duration::from_secs(30));
+        assert!(stepped.len() <= 3);
+
+        let registry = Registry::new();
+        let errors = IntCounterVec::new(Opts::new("errors_total", "Errores"), &["core_type"]).unwrap();
+        registry.register(Box::new(errors.clone())).unwrap();
+        errors.with_label_values(&["OS"]).inc_by(2);
+        errors.with_label_values(&["Network"]).inc();
+
+        store.ingest(&registry.gather(), now);
+        assert_eq!(store.get_series("errors_total", Duration::from_secs(1), Duration::ZERO)[0].value, 3.0);
+        assert_eq!(
+            store.get_series("errors_total{core_type=\"OS\"}", Duration::from_secs(1), Duration::ZERO)[0].value,
+            2.0
+        );
+    }
+}
@@ -0,0 +1,413 @@
+//! Contabilidad de ancho de banda por proceso y por host remoto
+//!
+//! `BandwidthAccounting` recibe lecturas periódicas de la tabla de conexiones
+//! y de los contadores de interfaz. El crecimiento de bytes de cada socket se
+//! atribuye a su proceso y a su extremo remoto, y cada lectura queda como una
+//! muestra en una ventana deslizante de `retention_secs`. Solo hay contadores
+//! por socket para TCP (`tcp_info` vía `sock_diag` en Linux); el resto del
+//! tráfico de las interfaces se informa como no atribuido. El tráfico por
+//! loopback no se contabiliza.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use super::connections::Socket;
+use super::network_core::{InterfaceStatus, NetworkInterface};
+
+/// Sección `[nano_cores.network_core.bandwidth]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct BandwidthConfig {
+    pub sample_interval_secs: u64,
+    /// Historia conservada; es la ventana más larga que se puede consultar
+    pub retention_secs: u64,
+    /// Ventana de `GetTopTalkers` cuando no se indica
+    pub default_window_secs: u64,
+    pub top: usize,
+}
+
+impl Default for BandwidthConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_secs: 10,
+            retention_secs: 900,
+            default_window_secs: 60,
+            top: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Usage {
+    sent: u64,
+    received: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.sent += other.sent;
+        self.received += other.received;
+    }
+
+    fn total(&self) -> u64 {
+        self.sent + self.received
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ProcessKey {
+    pid: Option<u32>,
+    name: Option<String>,
+}
+
+struct Sample {
+    at: Instant,
+    /// Tiempo cubierto desde la lectura anterior
+    covered: Duration,
+    interfaces: Usage,
+    processes: HashMap<ProcessKey, Usage>,
+    remotes: HashMap<IpAddr, Usage>,
+}
+
+/// Consumo de un proceso en la ventana
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessUsage {
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub rate_bps: f64,
+    /// Sockets abiertos en la última lectura
+    pub connections: usize,
+}
+
+/// Tráfico con un host remoto en la ventana
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteUsage {
+    pub address: IpAddr,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub rate_bps: f64,
+    pub connections: usize,
+}
+
+/// Respuesta de `GetTopTalkers`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopTalkers {
+    /// Tiempo realmente cubierto por las muestras, hasta la ventana pedida
+    pub window_secs: f64,
+    pub interface_bytes_sent: u64,
+    pub interface_bytes_received: u64,
+    /// Tráfico de las interfaces sin socket al que atribuirlo (UDP, ICMP, reenvío...)
+    pub unattributed_bytes: u64,
+    pub processes: Vec<ProcessUsage>,
+    pub remotes: Vec<RemoteUsage>,
+}
+
+/// Ventana deslizante de consumo por proceso y por remoto
+#[derive(Default)]
+pub struct BandwidthAccounting {
+    retention: Duration,
+    /// Contadores por socket de la lectura anterior; `None` hasta la primera
+    sockets: Option<HashMap<String, Usage>>,
+    interfaces: HashMap<String, Usage>,
+    last_read: Option<Instant>,
+    samples: VecDeque<Sample>,
+    process_connections: HashMap<ProcessKey, usize>,
+    remote_connections: HashMap<IpAddr, usize>,
+    /// Suma de velocidades de enlace de las interfaces activas, en bit/s
+    capacity_bps: u64,
+}
+
+fn is_loopback(interface: &NetworkInterface) -> bool {
+    !interface.ip_addresses.is_empty() && interface.ip_addresses.iter().all(|ip| ip.is_loopback())
+}
+
+impl BandwidthAccounting {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_retention(&mut self, retention: Duration) {
+        self.retention = retention;
+    }
+
+    /// Incorporar una lectura de sockets e interfaces
+    ///
+    /// La primera lectura solo fija la referencia. Después, un socket nuevo
+    /// aporta todos sus bytes, porque se abrió dentro del intervalo.
+    pub fn record(&mut self, sockets: &[Socket], interfaces: &[NetworkInterface], now: Instant) {
+        let baseline = self.sockets.is_none();
+        let previous = self.sockets.take().unwrap_or_default();
+        let mut current = HashMap::with_capacity(sockets.len());
+        let mut processes: HashMap<ProcessKey, Usage> = HashMap::new();
+        let mut remotes: HashMap<IpAddr, Usage> = HashMap::new();
+        self.process_connections.clear();
+        self.remote_connections.clear();
+
+        for socket in sockets {
+            let remote = socket.remote.ip();
+            if remote.is_loopback() || remote.is_unspecified() {
+                continue;
+            }
+            let counters = Usage { sent: socket.bytes_sent, received: socket.bytes_received };
+            let key = ProcessKey { pid: socket.pid, name: socket.process.clone() };
+            *self.process_connections.entry(key.clone()).or_default() += 1;
+            *self.remote_connections.entry(remote).or_default() += 1;
+
+            let id = socket.id();
+            let delta = match previous.get(&id) {
+                // Contadores menores: la 4-tupla se reutilizó con un socket nuevo
+                Some(before) if counters.sent >= before.sent && counters.received >= before.received => Usage {
+                    sent: counters.sent - before.sent,
+                    received: counters.received - before.received,
+                },
+                _ if baseline => Usage::default(),
+                _ => counters,
+            };
+            current.insert(id, counters);
+            if delta.total() > 0 {
+                processes.entry(key).or_default().add(delta);
+                remotes.entry(remote).or_default().add(delta);
+            }
+        }
+        self.sockets = Some(current);
+
+        let mut interface_usage = Usage::default();
+        let mut capacity_bps = 0;
+        let mut interface_counters = HashMap::with_capacity(interfaces.len());
+        for interface in interfaces.iter().filter(|i| !is_loopback(i)) {
+            let counters = Usage {
+                sent: interface.statistics.bytes_sent,
+                received: interface.statistics.bytes_received,
+            };
+            if let Some(before) = self.interfaces.get(&interface.name) {
+                interface_usage.add(Usage {
+                    sent: counters.sent.saturating_sub(before.sent),
+                    received: counters.received.saturating_sub(before.received),
+                });
+            }
+            if matches!(interface.status, InterfaceStatus::Up) {
+                capacity_bps += interface.speed.unwrap_or(0) * 1_000_000;
+            }
+            interface_counters.insert(interface.name.clone(), counters);
+        }
+        self.interfaces = interface_counters;
+        self.capacity_bps = capacity_bps;
+
+        if let Some(last_read) = self.last_read.replace(now) {
+            self.samples.push_back(Sample {
+                at: now,
+                covered: now.saturating_duration_since(last_read),
+                interfaces: interface_usage,
+                processes,
+                remotes,
+            });
+        }
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| now.saturating_duration_since(sample.at) > self.retention)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Procesos y remotos con más tráfico en la ventana que termina en `now`
+    pub fn top(&self, window: Duration, limit: usize, now: Instant) -> TopTalkers {
+        let mut covered = Duration::ZERO;
+        let mut interfaces = Usage::default();
+        let mut processes: HashMap<&ProcessKey, Usage> = HashMap::new();
+        let mut remotes: HashMap<IpAddr, Usage> = HashMap::new();
+        for sample in self.samples.iter().rev().take_while(|s| now.saturating_duration_since(s.at) < window) {
+            covered += sample.covered;
+            interfaces.add(sample.interfaces);
+            for (key, usage) in &sample.processes {
+                processes.entry(key).or_default().add(*usage);
+            }
+            for (address, usage) in &sample.remotes {
+                remotes.entry(*address).or_default().add(*usage);
+            }
+        }
+
+        let seconds = covered.as_secs_f64();
+        let rate = |usage: &Usage| if seconds > 0.0 { usage.total() as f64 * 8.0 / seconds } else { 0.0 };
+        let attributed: u64 = processes.values().map(Usage::total).sum();
+
+        let mut process_usage: Vec<ProcessUsage> = processes
+            .into_iter()
+            .map(|(key, usage)| ProcessUsage {
+                pid: key.pid,
+                process: key.name.clone(),
+                bytes_sent: usage.sent,
+                bytes_received: usage.received,
+                rate_bps: rate(&usage),
+                connections: self.process_connections.get(key).copied().unwrap_or(0),
+            })
+            .collect();
+        process_usage.sort_by(|a, b| {
+            (b.bytes_sent + b.bytes_received).cmp(&(a.bytes_sent + a.bytes_received)).then_with(|| a.pid.cmp(&b.pid))
+        });
+        process_usage.truncate(limit);
+
+        let mut remote_usage: Vec<RemoteUsage> = remotes
+            .into_iter()
+            .map(|(address, usage)| RemoteUsage {
+                address,
+                bytes_sent: usage.sent,
+                bytes_received: usage.received,
+                rate_bps: rate(&usage),
+                connections: self.remote_connections.get(&address).copied().unwrap_or(0),
+            })
+            .collect();
+        remote_usage.sort_by(|a, b| {
+            (b.bytes_sent + b.bytes_received)
+                .cmp(&(a.bytes_sent + a.bytes_received))
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        remote_usage.truncate(limit);
+
+        TopTalkers {
+            window_secs: seconds,
+            interface_bytes_sent: interfaces.sent,
+            interface_bytes_received: interfaces.received,
+            unattributed_bytes: interfaces.total().saturating_sub(attributed),
+            processes: process_usage,
+            remotes: remote_usage,
+        }
+    }
+
+    /// Capacidad y disponible en bit/s según la última muestra; el sentido más cargado manda
+    pub fn bandwidth(&self) -> (u64, u64) {
+        let used = self.samples.back().map_or(0, |sample| {
+            let seconds = sample.covered.as_secs_f64();
+            if seconds > 0.0 {
+                (sample.interfaces.sent.max(sample.interfaces.received) as f64 * 8.0 / seconds) as u64
+            } else {
+                0
+            }
+        });
+        (self.capacity_bps, self.capacity_bps.saturating_sub(used))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nano_cores::network_core::{ConnectionState, DuplexMode, InterfaceStatistics, Protocol};
+
+    fn socket(local: &str, remote: &str, pid: u32, sent: u64, received: u64) -> Socket {
+        Socket {
+            protocol: Protocol::TCP,
+            local: local.parse().unwrap(),
+            remote: remote.parse().unwrap(),
+            state: ConnectionState::Established,
+            inode: 0,
+            pid: Some(pid),
+            process: Some(format!("proc{}", pid)),
+            bytes_sent: sent,
+            bytes_received: received,
+            rtt: None,
+            rtt_var: None,
+            retransmits: 0,
+            segments_sent: 0,
+        }
+    }
+
+    fn interface(name: &str, ip: &str, sent: u64, received: u64) -> NetworkInterface {
+        NetworkInterface {
+            name: name.to_string(),
+            index: None,
+            ip_addresses: vec![ip.parse().unwrap()],
+            mac_address: None,
+            mtu: 1500,
+            speed: Some(1000),
+            duplex: DuplexMode::Full,
+            status: InterfaceStatus::Up,
+            carrier: Some(true),
+            statistics: InterfaceStatistics { bytes_sent: sent, bytes_received: received, ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn test_attributes_socket_growth_to_process_and_remote() {
+        let start = Instant::now();
+        let mut accounting = BandwidthAccounting::new(Duration::from_secs(900));
+        accounting.record(
+            &[
+                socket("10.0.0.2:40000", "93.184.216.34:443", 100, 5_000, 50_000),
+                socket("127.0.0.1:41000", "127.0.0.1:5432", 300, 1_000, 1_000),
+            ],
+            &[interface("eth0", "10.0.0.2", 1_000_000, 2_000_000), interface("lo", "127.0.0.1", 0, 0)],
+            start,
+        );
+        // La referencia no cuenta como tráfico
+        assert_eq!(accounting.top(Duration::from_secs(60), 10, start).processes, vec![]);
+
+        let later = start + Duration::from_secs(10);
+        accounting.record(
+            &[
+                socket("10.0.0.2:40000", "93.184.216.34:443", 100, 6_000, 150_000),
+                socket("10.0.0.2:40002", "93.184.216.34:443", 200, 500, 500),
+                socket("10.0.0.2:40004", "1.1.1.1:853", 200, 100, 100),
+                socket("127.0.0.1:41000", "127.0.0.1:5432", 300, 9_000, 9_000),
+            ],
+            &[interface("eth0", "10.0.0.2", 1_010_000, 2_200_000), interface("lo", "127.0.0.1", 9_000, 9_000)],
+            later,
+        );
+
+        let top = accounting.top(Duration::from_secs(60), 10, later);
+        assert_eq!(top.window_secs, 10.0);
+        let processes: Vec<(Option<u32>, u64, usize)> =
+            top.processes.iter().map(|p| (p.pid, p.bytes_sent + p.bytes_received, p.connections)).collect();
+        assert_eq!(processes, vec![(Some(100), 101_000, 1), (Some(200), 1_200, 2)]);
+        assert_eq!(top.processes[0].rate_bps, 101_000.0 * 8.0 / 10.0);
+        assert_eq!(top.remotes[0].address, "93.184.216.34".parse::<IpAddr>().unwrap());
+        assert_eq!((top.remotes[0].bytes_received, top.remotes[0].connections), (100_500, 2));
+        assert_eq!((top.interface_bytes_sent, top.interface_bytes_received), (10_000, 200_000));
+        assert_eq!(top.unattributed_bytes, 210_000 - 102_200);
+        assert_eq!(accounting.top(Duration::from_secs(60), 1, later).remotes.len(), 1);
+
+        // Capacidad de eth0 menos el sentido más cargado (200 kB en 10 s)
+        assert_eq!(accounting.bandwidth(), (1_000_000_000, 1_000_000_000 - 160_000));
+    }
+
+    #[test]
+    fn test_window_and_retention_bound_the_history() {
+        let start = Instant::now();
+        let mut accounting = BandwidthAccounting::new(Duration::from_secs(30));
+        let interfaces = [interface("eth0", "10.0.0.2", 0, 0)];
+        accounting.record(&[], &interfaces, start);
+        // El socket aparece en la primera lectura tras la referencia: cuenta entero
+        let sockets = [socket("10.0.0.2:40000", "10.0.0.9:22", 1, 1_000, 0)];
+        accounting.record(&sockets, &interfaces, start + Duration::from_secs(10));
+        let first = accounting.top(Duration::from_secs(60), 10, start + Duration::from_secs(10));
+        assert_eq!(first.processes[0].bytes_sent, 1_000);
+
+        for step in 2..=6u64 {
+            let sockets = [socket("10.0.0.2:40000", "10.0.0.9:22", 1, step * 1_000, 0)];
+            accounting.record(&sockets, &interfaces, start + Duration::from_secs(step * 10));
+        }
+        let now = start + Duration::from_secs(60);
+
+        let recent = accounting.top(Duration::from_secs(15), 10, now);
+        assert_eq!(recent.processes[0].bytes_sent, 2_000);
+        assert_eq!(recent.window_secs, 20.0);
+
+        // La retención de 30 s deja fuera las muestras más viejas
+        let everything = accounting.top(Duration::from_secs(3600), 10, now);
+        assert_eq!(everything.processes[0].bytes_sent, 4_000);
+
+        // Contadores que retroceden: la conexión se reabrió con la misma tupla
+        let sockets = [socket("10.0.0.2:40000", "10.0.0.9:22", 1, 300, 0)];
+        accounting.record(&sockets, &interfaces, now + Duration::from_secs(10));
+        let top = accounting.top(Duration::from_secs(5), 10, now + Duration::from_secs(10));
+        assert_eq!(top.processes[0].bytes_sent, 300);
+    }
+}
@@ -0,0 +1,897 @@
+//! Captura acotada de paquetes para diagnóstico
+//!
+//! `CapturePackets` abre un socket AF_PACKET sobre la interfaz (o `any`), lee
+//! cabeceras durante un tiempo y número de paquetes limitados por
+//! `[nano_cores.network_core.capture]` y devuelve un resumen: protocolos, hosts
+//! con más tráfico y flujos (5-tupla). El filtro admite un subconjunto de la
+//! sintaxis de pcap y se evalúa en espacio de usuario sobre las cabeceras
+//! decodificadas; nunca se guarda ni se devuelve el contenido de los paquetes.
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+use tracing::info;
+
+use crate::nano_cores::NanoCoreError;
+
+/// Flujos distintos que se siguen por captura; el resto solo suma a los totales
+const MAX_TRACKED_FLOWS: usize = 4096;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+
+/// Sección `[nano_cores.network_core.capture]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CaptureConfig {
+    pub default_duration_secs: u64,
+    pub max_duration_secs: u64,
+    pub default_max_packets: u64,
+    /// Tope absoluto; las peticiones por encima se recortan
+    pub max_packets: u64,
+    /// Entradas de cada clasificación en el informe
+    pub top: usize,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            default_duration_secs: 10,
+            max_duration_secs: 60,
+            default_max_packets: 10_000,
+            max_packets: 100_000,
+            top: 10,
+        }
+    }
+}
+
+/// Protocolo de un paquete capturado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PacketProtocol {
+    Tcp,
+    Udp,
+    Icmp,
+    Icmpv6,
+    /// Otro protocolo sobre IP, por número
+    Ip(u8),
+    Arp,
+    /// Otro ethertype
+    Ethertype(u16),
+}
+
+impl PacketProtocol {
+    pub fn name(&self) -> String {
+        match self {
+            PacketProtocol::Tcp => "tcp".to_string(),
+            PacketProtocol::Udp => "udp".to_string(),
+            PacketProtocol::Icmp => "icmp".to_string(),
+            PacketProtocol::Icmpv6 => "icmp6".to_string(),
+            PacketProtocol::Ip(number) => format!("ip-{}", number),
+            PacketProtocol::Arp => "arp".to_string(),
+            PacketProtocol::Ethertype(ethertype) => format!("ethertype-0x{:04x}", ethertype),
+        }
+    }
+
+    fn from_ip(number: u8, ipv6: bool) -> Self {
+        match number {
+            6 => PacketProtocol::Tcp,
+            17 => PacketProtocol::Udp,
+            1 if !ipv6 => PacketProtocol::Icmp,
+            58 if ipv6 => PacketProtocol::Icmpv6,
+            other => PacketProtocol::Ip(other),
+        }
+    }
+}
+
+/// Cabeceras decodificadas de un paquete
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub protocol: PacketProtocol,
+    pub source: Option<IpAddr>,
+    pub destination: Option<IpAddr>,
+    pub source_port: Option<u16>,
+    pub destination_port: Option<u16>,
+    /// Longitud en el cable, aunque se haya leído menos
+    pub length: usize,
+}
+
+/// Decodificar un paquete a partir de la capa de red
+pub fn parse_packet(ethertype: u16, data: &[u8], length: usize) -> Packet {
+    let mut packet = Packet {
+        protocol: PacketProtocol::Ethertype(ethertype),
+        source: None,
+        destination: None,
+        source_port: None,
+        destination_port: None,
+        length,
+    };
+
+    match ethertype {
+        ETHERTYPE_IPV4 if data.len() >= 20 && data[0] >> 4 == 4 => {
+            let header_len = usize::from(data[0] & 0x0f) * 4;
+            let fragment_offset = u16::from_be_bytes([data[6], data[7]]) & 0x1fff;
+            packet.protocol = PacketProtocol::from_ip(data[9], false);
+            packet.source = Some(IpAddr::V4(Ipv4Addr::new(data[12], data[13], data[14], data[15])));
+            packet.destination = Some(IpAddr::V4(Ipv4Addr::new(data[16], data[17], data[18], data[19])));
+            if fragment_offset == 0 && header_len >= 20 {
+                set_ports(&mut packet, data.get(header_len..));
+            }
+        }
+        ETHERTYPE_IPV6 if data.len() >= 40 && data[0] >> 4 == 6 => {
+            let octets = |range: std::ops::Range<usize>| -> [u8; 16] { data[range].try_into().unwrap_or([0; 16]) };
+            packet.source = Some(IpAddr::V6(Ipv6Addr::from(octets(8..24))));
+            packet.destination = Some(IpAddr::V6(Ipv6Addr::from(octets(24..40))));
+
+            // Saltar cabeceras de extensión hasta el transporte
+            let mut next = data[6];
+            let mut offset = 40;
+            let mut first_fragment = true;
+            while let Some(header) = data.get(offset..offset + 8) {
+                let header_len = match next {
+                    0 | 43 | 60 => (usize::from(header[1]) + 1) * 8,
+                    44 => {
+                        first_fragment = u16::from_be_bytes([header[2], header[3]]) >> 3 == 0;
+                        8
+                    }
+                    51 => (usize::from(header[1]) + 2) * 4,
+                    _ => break,
+                };
+                next = header[0];
+                offset += header_len;
+            }
+            packet.protocol = PacketProtocol::from_ip(next, true);
+            if first_fragment {
+                set_ports(&mut packet, data.get(offset..));
+            }
+        }
+        ETHERTYPE_ARP => {
+            packet.protocol = PacketProtocol::Arp;
+            // Solo ARP sobre Ethernet para IPv4 (hlen 6, plen 4)
+            if data.len() >= 28 && u16::from_be_bytes([data[2], data[3]]) == ETHERTYPE_IPV4 && data[4] == 6 && data[5] == 4 {
+                packet.source = Some(IpAddr::V4(Ipv4Addr::new(data[14], data[15], data[16], data[17])));
+                packet.destination = Some(IpAddr::V4(Ipv4Addr::new(data[24], data[25], data[26], data[27])));
+            }
+        }
+        _ => {}
+    }
+    packet
+}
+
+fn set_ports(packet: &mut Packet, transport: Option<&[u8]>) {
+    if !matches!(packet.protocol, PacketProtocol::Tcp | PacketProtocol::Udp) {
+        return;
+    }
+    if let Some(header) = transport.filter(|header| header.len() >= 4) {
+        packet.source_port = Some(u16::from_be_bytes([header[0], header[1]]));
+        packet.destination_port = Some(u16::from_be_bytes([header[2], header[3]]));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Source,
+    Destination,
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProtocolMatch {
+    Ip,
+    Ip6,
+    Arp,
+    Tcp,
+    Udp,
+    Icmp,
+    Icmp6,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Protocol(ProtocolMatch),
+    Host(Direction, IpAddr),
+    Net(Direction, IpAddr, u8),
+    Port(Direction, u16, u16),
+    Less(usize),
+    Greater(usize),
+}
+
+/// Filtro con la sintaxis de pcap
+///
+/// Admite `ip`, `ip6`, `arp`, `tcp`, `udp`, `icmp`, `icmp6`, `[src|dst] host
+/// <ip>`, `[src|dst] net <ip>/<prefijo>`, `[src|dst] port <n>`, `[src|dst]
+/// portrange <a>-<b>`, `less <n>`, `greater <n>`, un protocolo como calificador
+/// (`tcp port 80`), `and`/`&&`, `or`/`||`, `not`/`!` y paréntesis. Los nombres
+/// de host no se resuelven.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureFilter {
+    expr: Expr,
+}
+
+impl CaptureFilter {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let tokens = tokenize(expression);
+        if tokens.is_empty() {
+            return Err(invalid_filter("filtro vacío"));
+        }
+        let mut parser = Parser { tokens: &tokens, position: 0 };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self { expr }),
+            Some(token) => Err(invalid_filter(&format!("elemento inesperado '{}'", token))),
+        }
+    }
+
+    pub fn matches(&self, packet: &Packet) -> bool {
+        evaluate(&self.expr, packet)
+    }
+}
+
+fn invalid_filter(message: &str) -> anyhow::Error {
+    NanoCoreError::ConfigError(anyhow!("Filtro de captura inválido: {}", message)).into()
+}
+
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in expression.chars() {
+        match c {
+            '(' | ')' | '!' => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                tokens.push(c.to_string());
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<&str> {
+        let token = self.tokens.get(self.position).map(String::as_str);
+        self.position += 1;
+        token
+    }
+
+    fn expect_value(&mut self, after: &str) -> Result<String> {
+        self.next()
+            .map(str::to_string)
+            .ok_or_else(|| invalid_filter(&format!("falta el valor tras '{}'", after)))
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while matches!(self.peek(), Some("or" | "||")) {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while matches!(self.peek(), Some("and" | "&&")) {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some("not" | "!") => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some("(") => {
+                self.position += 1;
+                let expr = self.or()?;
+                match self.next() {
+                    Some(")") => Ok(expr),
+                    _ => Err(invalid_filter("falta ')'")),
+                }
+            }
+            _ => self.primitive(),
+        }
+    }
+
+    fn primitive(&mut self) -> Result<Expr> {
+        let token = self.next().ok_or_else(|| invalid_filter("expresión incompleta"))?.to_string();
+        let protocol = match token.as_str() {
+            "ip" => Some(ProtocolMatch::Ip),
+            "ip6" => Some(ProtocolMatch::Ip6),
+            "arp" => Some(ProtocolMatch::Arp),
+            "tcp" => Some(ProtocolMatch::Tcp),
+            "udp" => Some(ProtocolMatch::Udp),
+            "icmp" => Some(ProtocolMatch::Icmp),
+            "icmp6" => Some(ProtocolMatch::Icmp6),
+            _ => None,
+        };
+        if let Some(protocol) = protocol {
+            // `tcp port 80` equivale a `tcp and port 80`
+            return match self.peek() {
+                Some("src" | "dst" | "host" | "net" | "port" | "portrange") => {
+                    Ok(Expr::And(Box::new(Expr::Protocol(protocol)), Box::new(self.primitive()?)))
+                }
+                _ => Ok(Expr::Protocol(protocol)),
+            };
+        }
+
+        let (direction, keyword) = match token.as_str() {
+            "src" => (Direction::Source, self.expect_value("src")?),
+            "dst" => (Direction::Destination, self.expect_value("dst")?),
+            _ => (Direction::Either, token),
+        };
+        match keyword.as_str() {
+            "host" => {
+                let value = self.expect_value("host")?;
+                let address = value
+                    .parse()
+                    .map_err(|_| invalid_filter(&format!("'{}' no es una dirección IP", value)))?;
+                Ok(Expr::Host(direction, address))
+            }
+            "net" => {
+                let value = self.expect_value("net")?;
+                let (address, prefix) = parse_net(&value)?;
+                Ok(Expr::Net(direction, address, prefix))
+            }
+            "port" => {
+                let value = self.expect_value("port")?;
+                let port = parse_port(&value)?;
+                Ok(Expr::Port(direction, port, port))
+            }
+            "portrange" => {
+                let value = self.expect_value("portrange")?;
+                let (low, high) = value
+                    .split_once('-')
+                    .ok_or_else(|| invalid_filter(&format!("rango de puertos '{}' sin '-'", value)))?;
+                let (low, high) = (parse_port(low)?, parse_port(high)?);
+                if low > high {
+                    return Err(invalid_filter(&format!("rango de puertos '{}' invertido", value)));
+                }
+                Ok(Expr::Port(direction, low, high))
+            }
+            "less" | "greater" if direction == Direction::Either => {
+                let value = self.expect_value(&keyword)?;
+                let length = value
+                    .parse()
+                    .map_err(|_| invalid_filter(&format!("'{}' no es una longitud", value)))?;
+                Ok(if keyword == "less" { Expr::Less(length) } else { Expr::Greater(length) })
+            }
+            other => Err(invalid_filter(&format!("primitiva no soportada '{}'", other))),
+        }
+    }
+}
+
+fn parse_port(value: &str) -> Result<u16> {
+    value
+        .parse()
+        .map_err(|_| invalid_filter(&format!("'{}' no es un puerto", value)))
+}
+
+fn parse_net(value: &str) -> Result<(IpAddr, u8)> {
+    let (address, prefix) = match value.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (value, None),
+    };
+    let address: IpAddr = address
+        .parse()
+        .map_err(|_| invalid_filter(&format!("'{}' no es una red", value)))?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix
+            .parse()
+            .ok()
+            .filter(|prefix| *prefix <= max)
+            .ok_or_else(|| invalid_filter(&format!("prefijo inválido en '{}'", value)))?,
+        None => max,
+    };
+    Ok((address, prefix))
+}
+
+fn in_net(address: &IpAddr, network: &IpAddr, prefix: u8) -> bool {
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(*address) & mask == u32::from(*network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(*address) & mask == u128::from(*network) & mask
+        }
+        _ => false,
+    }
+}
+
+fn evaluate(expr: &Expr, packet: &Packet) -> bool {
+    let addresses = |direction: Direction| -> Vec<IpAddr> {
+        match direction {
+            Direction::Source => packet.source.into_iter().collect(),
+            Direction::Destination => packet.destination.into_iter().collect(),
+            Direction::Either => packet.source.into_iter().chain(packet.destination).collect(),
+        }
+    };
+    let ports = |direction: Direction| -> Vec<u16> {
+        match direction {
+            Direction::Source => packet.source_port.into_iter().collect(),
+            Direction::Destination => packet.destination_port.into_iter().collect(),
+            Direction::Either => packet.source_port.into_iter().chain(packet.destination_port).collect(),
+        }
+    };
+    let ipv6 = packet.source.is_some_and(|address| address.is_ipv6());
+
+    match expr {
+        Expr::And(left, right) => evaluate(left, packet) && evaluate(right, packet),
+        Expr::Or(left, right) => evaluate(left, packet) || evaluate(right, packet),
+        Expr::Not(inner) => !evaluate(inner, packet),
+        Expr::Protocol(protocol) => match protocol {
+            ProtocolMatch::Ip => packet.protocol != PacketProtocol::Arp && packet.source.is_some_and(|a| a.is_ipv4()),
+            ProtocolMatch::Ip6 => ipv6,
+            ProtocolMatch::Arp => packet.protocol == PacketProtocol::Arp,
+            ProtocolMatch::Tcp => packet.protocol == PacketProtocol::Tcp,
+            ProtocolMatch::Udp => packet.protocol == PacketProtocol::Udp,
+            ProtocolMatch::Icmp => packet.protocol == PacketProtocol::Icmp,
+            ProtocolMatch::Icmp6 => packet.protocol == PacketProtocol::Icmpv6,
+        },
+        Expr::Host(direction, host) => addresses(*direction).contains(host),
+        Expr::Net(direction, network, prefix) => {
+            addresses(*direction).iter().any(|address| in_net(address, network, *prefix))
+        }
+        Expr::Port(direction, low, high) => ports(*direction).iter().any(|port| (*low..=*high).contains(port)),
+        Expr::Less(length) => packet.length <= *length,
+        Expr::Greater(length) => packet.length >= *length,
+    }
+}
+
+/// Tráfico de un protocolo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolSummary {
+    pub protocol: String,
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// Tráfico enviado y recibido por una dirección
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TalkerSummary {
+    pub address: IpAddr,
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// Flujo unidireccional identificado por su 5-tupla
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowSummary {
+    pub protocol: String,
+    pub source: IpAddr,
+    pub source_port: Option<u16>,
+    pub destination: IpAddr,
+    pub destination_port: Option<u16>,
+    pub packets: u64,
+    pub bytes: u64,
+    /// Milisegundos desde el inicio de la captura
+    pub first_seen_ms: u64,
+    pub last_seen_ms: u64,
+}
+
+/// Resumen de una captura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureReport {
+    pub interface: String,
+    pub filter: Option<String>,
+    pub duration_ms: u64,
+    /// Paquetes leídos antes de aplicar el filtro
+    pub packets_seen: u64,
+    pub packets: u64,
+    pub bytes: u64,
+    /// Se alcanzó el límite de paquetes antes de acabar el tiempo
+    pub truncated: bool,
+    /// Paquetes de flujos que no cupieron en la tabla
+    pub untracked_packets: u64,
+    pub protocols: Vec<ProtocolSummary>,
+    pub top_talkers: Vec<TalkerSummary>,
+    pub top_flows: Vec<FlowSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FlowKey {
+    protocol: PacketProtocol,
+    source: IpAddr,
+    source_port: Option<u16>,
+    destination: IpAddr,
+    destination_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    packets: u64,
+    bytes: u64,
+    first_seen_ms: u64,
+    last_seen_ms: u64,
+}
+
+impl Counters {
+    fn add(&mut self, length: usize, elapsed_ms: u64) {
+        if self.packets == 0 {
+            self.first_seen_ms = elapsed_ms;
+        }
+        self.packets += 1;
+        self.bytes += length as u64;
+        self.last_seen_ms = elapsed_ms;
+    }
+}
+
+/// Acumulador de una captura en curso
+#[derive(Debug, Default)]
+pub struct FlowAggregator {
+    packets_seen: u64,
+    packets: u64,
+    bytes: u64,
+    untracked_packets: u64,
+    protocols: HashMap<PacketProtocol, Counters>,
+    talkers: HashMap<IpAddr, Counters>,
+    flows: HashMap<FlowKey, Counters>,
+}
+
+impl FlowAggregator {
+    /// Contabilizar un paquete si pasa el filtro
+    pub fn record(&mut self, packet: &Packet, filter: Option<&CaptureFilter>, elapsed: Duration) -> bool {
+        self.packets_seen += 1;
+        if filter.is_some_and(|filter| !filter.matches(packet)) {
+            return false;
+        }
+
+        let elapsed_ms = elapsed.as_millis() as u64;
+        self.packets += 1;
+        self.bytes += packet.length as u64;
+        self.protocols.entry(packet.protocol).or_default().add(packet.length, elapsed_ms);
+        for address in packet.source.iter().chain(packet.destination.iter().filter(|d| Some(**d) != packet.source)) {
+            self.talkers.entry(*address).or_default().add(packet.length, elapsed_ms);
+        }
+
+        if let (Some(source), Some(destination)) = (packet.source, packet.destination) {
+            let key = FlowKey {
+                protocol: packet.protocol,
+                source,
+                source_port: packet.source_port,
+                destination,
+                destination_port: packet.destination_port,
+            };
+            if self.flows.len() < MAX_TRACKED_FLOWS || self.flows.contains_key(&key) {
+                self.flows.entry(key).or_default().add(packet.length, elapsed_ms);
+            } else {
+                self.untracked_packets += 1;
+            }
+        }
+        true
+    }
+
+    pub fn packets(&self) -> u64 {
+        self.packets
+    }
+
+    pub fn report(self, interface: &str, filter: Option<&str>, duration: Duration, truncated: bool, top: usize) -> CaptureReport {
+        let mut protocols: Vec<ProtocolSummary> = self
+            .protocols
+            .into_iter()
+            .map(|(protocol, counters)| ProtocolSummary {
+                protocol: protocol.name(),
+                packets: counters.packets,
+                bytes: counters.bytes,
+            })
+            .collect();
+        protocols.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.protocol.cmp(&b.protocol)));
+
+        let mut top_talkers: Vec<TalkerSummary> = self
+            .talkers
+            .into_iter()
+            .map(|(address, counters)| TalkerSummary {
+                address,
+                packets: counters.packets,
+                bytes: counters.bytes,
+            })
+            .collect();
+        top_talkers.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.address.cmp(&b.address)));
+        top_talkers.truncate(top);
+
+        let mut top_flows: Vec<FlowSummary> = self
+            .flows
+            .into_iter()
+            .map(|(key, counters)| FlowSummary {
+                protocol: key.protocol.name(),
+                source: key.source,
+                source_port: key.source_port,
+                destination: key.destination,
+                destination_port: key.destination_port,
+                packets: counters.packets,
+                bytes: counters.bytes,
+                first_seen_ms: counters.first_seen_ms,
+                last_seen_ms: counters.last_seen_ms,
+            })
+            .collect();
+        top_flows.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.first_seen_ms.cmp(&b.first_seen_ms)));
+        top_flows.truncate(top);
+
+        CaptureReport {
+            interface: interface.to_string(),
+            filter: filter.map(str::to_string),
+            duration_ms: duration.as_millis() as u64,
+            packets_seen: self.packets_seen,
+            packets: self.packets,
+            bytes: self.bytes,
+            truncated,
+            untracked_packets: self.untracked_packets,
+            protocols,
+            top_talkers,
+            top_flows,
+        }
+    }
+}
+
+/// Capturar en `interface` (`any` para todas) hasta agotar el tiempo o el número de paquetes
+pub async fn capture(
+    interface: &str,
+    filter: Option<&str>,
+    duration: Duration,
+    max_packets: u64,
+    top: usize,
+) -> Result<CaptureReport> {
+    let parsed = filter
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(CaptureFilter::parse)
+        .transpose()?;
+
+    info!(
+        "🔬 Captura en {} durante {:?} (máx. {} paquetes, filtro {:?})",
+        interface, duration, max_packets, filter
+    );
+    let owned_interface = interface.to_string();
+    let (aggregator, elapsed, truncated) = tokio::task::spawn_blocking(move || {
+        platform::capture(&owned_interface, parsed.as_ref(), duration, max_packets)
+    })
+    .await
+    .map_err(|e| anyhow!("Tarea de captura interrumpida: {}", e))??;
+
+    let report = aggregator.report(interface, filter, elapsed, truncated, top);
+    info!(
+        "🔬 Captura en {}: {} de {} paquetes, {} bytes",
+        interface, report.packets, report.packets_seen, report.bytes
+    );
+    Ok(report)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::Instant;
+
+    /// Bastan las cabeceras; la longitud real llega con MSG_TRUNC
+    const SNAPLEN: usize = 512;
+    const READ_TIMEOUT: Duration = Duration::from_millis(100);
+    /// `linux/if_packet.h`
+    const PACKET_OUTGOING: u8 = 4;
+
+    pub fn capture(
+        interface: &str,
+        filter: Option<&CaptureFilter>,
+        duration: Duration,
+        max_packets: u64,
+    ) -> Result<(FlowAggregator, Duration, bool)> {
+        let ifindex = if interface == "any" {
+            0
+        } else {
+            let name = CString::new(interface).map_err(|_| anyhow!("Nombre de interfaz inválido: {}", interface))?;
+            match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+                0 => return Err(NanoCoreError::ConfigError(anyhow!("Interfaz desconocida: {}", interface)).into()),
+                index => index as i32,
+            }
+        };
+
+        let protocol = (libc::ETH_P_ALL as u16).to_be();
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, i32::from(protocol)) };
+        if fd < 0 {
+            let error = std::io::Error::last_os_error();
+            return Err(match error.kind() {
+                std::io::ErrorKind::PermissionDenied => {
+                    NanoCoreError::SecurityDenied(anyhow!("La captura requiere CAP_NET_RAW: {}", error)).into()
+                }
+                _ => anyhow!("No se pudo abrir el socket de captura: {}", error),
+            });
+        }
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut address: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        address.sll_family = libc::AF_PACKET as u16;
+        address.sll_protocol = protocol;
+        address.sll_ifindex = ifindex;
+        let bound = unsafe {
+            libc::bind(
+                socket.as_raw_fd(),
+                &address as *const libc::sockaddr_ll as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if bound < 0 {
+            return Err(anyhow!("No se pudo enlazar la captura a {}: {}", interface, std::io::Error::last_os_error()));
+        }
+
+        let timeout = libc::timeval {
+            tv_sec: 0,
+            tv_usec: READ_TIMEOUT.as_micros() as libc::suseconds_t,
+        };
+        unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            );
+        }
+
+        let mut aggregator = FlowAggregator::default();
+        let mut buffer = [0u8; SNAPLEN];
+        let started = Instant::now();
+        let mut truncated = false;
+        while started.elapsed() < duration {
+            let mut from: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+            let mut from_len = std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+            let received = unsafe {
+                libc::recvfrom(
+                    socket.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    libc::MSG_TRUNC,
+                    &mut from as *mut libc::sockaddr_ll as *mut libc::sockaddr,
+                    &mut from_len,
+                )
+            };
+            if received < 0 {
+                let error = std::io::Error::last_os_error();
+                match error.kind() {
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted => continue,
+                    _ => return Err(anyhow!("Error leyendo la captura en {}: {}", interface, error)),
+                }
+            }
+
+            // En loopback cada paquete aparece como saliente y entrante
+            if from.sll_pkttype == PACKET_OUTGOING && from.sll_hatype == libc::ARPHRD_LOOPBACK {
+                continue;
+            }
+            let length = received as usize;
+            let packet = parse_packet(u16::from_be(from.sll_protocol), &buffer[..length.min(SNAPLEN)], length);
+            aggregator.record(&packet, filter, started.elapsed());
+            if aggregator.packets() >= max_packets {
+                truncated = true;
+                break;
+            }
+        }
+        Ok((aggregator, started.elapsed(), truncated))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::*;
+
+    pub fn capture(
+        _interface: &str,
+        _filter: Option<&CaptureFilter>,
+        _duration: Duration,
+        _max_packets: u64,
+    ) -> Result<(FlowAggregator, Duration, bool)> {
+        Err(anyhow!("Captura de paquetes no soportada en esta plataforma (requiere AF_PACKET)"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipv4_udp(source: [u8; 4], destination: [u8; 4], source_port: u16, destination_port: u16) -> Vec<u8> {
+        let mut data = vec![0x45, 0, 0, 28, 0, 0, 0x40, 0, 64, 17, 0, 0];
+        data.extend_from_slice(&source);
+        data.extend_from_slice(&destination);
+        data.extend_from_slice(&source_port.to_be_bytes());
+        data.extend_from_slice(&destination_port.to_be_bytes());
+        data.extend_from_slice(&[0, 8, 0, 0]);
+        data
+    }
+
+    #[test]
+    fn test_decodes_ipv4_and_ipv6_with_extension_headers() {
+        let packet = parse_packet(ETHERTYPE_IPV4, &ipv4_udp([10, 0, 0, 1], [10, 0, 0, 2], 5353, 53), 60);
+        assert_eq!(packet.protocol, PacketProtocol::Udp);
+        assert_eq!(packet.source, Some("10.0.0.1".parse().unwrap()));
+        assert_eq!((packet.source_port, packet.destination_port), (Some(5353), Some(53)));
+        assert_eq!(packet.length, 60);
+
+        // IPv6 con hop-by-hop delante de TCP
+        let mut data = vec![0x60, 0, 0, 0, 0, 28, 0, 64];
+        data.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        data.extend_from_slice(&[6, 0, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0x01, 0xbb, 0xc0, 0x00]);
+        let packet = parse_packet(ETHERTYPE_IPV6, &data, data.len());
+        assert_eq!(packet.protocol, PacketProtocol::Tcp);
+        assert_eq!(packet.source_port, Some(443));
+        assert_eq!(packet.destination, Some("2001:db8::2".parse().unwrap()));
+
+        // Los fragmentos posteriores no llevan puertos
+        let mut fragment = ipv4_udp([10, 0, 0, 1], [10, 0, 0, 2], 1, 2);
+        fragment[7] = 0x10;
+        assert_eq!(parse_packet(ETHERTYPE_IPV4, &fragment, 60).source_port, None);
+        assert_eq!(parse_packet(0x88cc, &[], 60).protocol.name(), "ethertype-0x88cc");
+    }
+
+    #[test]
+    fn test_filter_follows_pcap_semantics() {
+        let packet = parse_packet(ETHERTYPE_IPV4, &ipv4_udp([10, 0, 0, 1], [192, 168, 1, 7], 40000, 53), 80);
+        let matches = |expression: &str| CaptureFilter::parse(expression).unwrap().matches(&packet);
+
+        assert!(matches("udp port 53"));
+        assert!(matches("src host 10.0.0.1 and dst net 192.168.0.0/16"));
+        assert!(!matches("dst host 10.0.0.1"));
+        assert!(matches("tcp or (udp && portrange 50-60)"));
+        assert!(matches("not tcp and ip"));
+        assert!(!matches("ip6 || arp"));
+        assert!(matches("!(src port 53) and greater 64"));
+        assert!(!matches("less 64"));
+
+        for invalid in ["", "host example.com", "port", "udp and", "(udp", "net 10.0.0.0/33", "portrange 9-1", "vlan 10"] {
+            let error = CaptureFilter::parse(invalid).unwrap_err();
+            assert!(matches!(error.downcast_ref::<NanoCoreError>(), Some(NanoCoreError::ConfigError(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_report_ranks_talkers_and_flows_by_bytes() {
+        let filter = CaptureFilter::parse("udp").unwrap();
+        let mut aggregator = FlowAggregator::default();
+        let big = parse_packet(ETHERTYPE_IPV4, &ipv4_udp([10, 0, 0, 1], [10, 0, 0, 2], 1000, 53), 1500);
+        let small = parse_packet(ETHERTYPE_IPV4, &ipv4_udp([10, 0, 0, 3], [10, 0, 0, 2], 1001, 53), 100);
+        let arp = parse_packet(ETHERTYPE_ARP, &[], 42);
+        for i in 0..3 {
+            aggregator.record(&big, Some(&filter), Duration::from_millis(i * 10));
+        }
+        aggregator.record(&small, Some(&filter), Duration::from_millis(5));
+        assert!(!aggregator.record(&arp, Some(&filter), Duration::from_millis(6)));
+
+        let report = aggregator.report("eth0", Some("udp"), Duration::from_secs(1), false, 2);
+        assert_eq!((report.packets_seen, report.packets, report.bytes), (5, 4, 4600));
+        assert_eq!(report.protocols, vec![ProtocolSummary { protocol: "udp".to_string(), packets: 4, bytes: 4600 }]);
+        let talkers: Vec<(IpAddr, u64)> = report.top_talkers.iter().map(|t| (t.address, t.bytes)).collect();
+        assert_eq!(talkers, vec![("10.0.0.2".parse().unwrap(), 4600), ("10.0.0.1".parse().unwrap(), 4500)]);
+        let flow = &report.top_flows[0];
+        assert_eq!((flow.packets, flow.first_seen_ms, flow.last_seen_ms), (3, 0, 20));
+        assert_eq!(report.top_flows[1].source_port, Some(1001));
+    }
+}
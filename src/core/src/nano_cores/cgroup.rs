@@ -0,0 +1,185 @@
+//! Límites de recursos con cgroups v2 (Linux)
+//!
+//! OSCore crea `cgroup_path` con dos hojas, `core` para el árbol de procesos
+//! de SAAI y `sandbox` para los hijos aislados, y aplica en el grupo padre
+//! los `ResourceLimits` como `cpu.max`, `memory.max` y `pids.max`. Los
+//! descriptores de fichero no tienen controlador y se limitan con RLIMIT_NOFILE.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::config::ResourceLimits;
+
+/// Periodo de `cpu.max`, en microsegundos
+const CPU_PERIOD_US: u64 = 100_000;
+
+const CONTROLLERS: [&str; 3] = ["cpu", "memory", "pids"];
+
+/// Contadores de throttling y uso del cgroup de SAAI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupStats {
+    pub nr_periods: u64,
+    pub nr_throttled: u64,
+    pub throttled_usec: u64,
+    pub memory_current: u64,
+    /// Veces que se alcanzó `memory.max`
+    pub memory_max_events: u64,
+    pub oom_kills: u64,
+    pub pids_current: u64,
+}
+
+/// Cgroup v2 gestionado por OSCore
+#[derive(Debug, Clone)]
+pub struct CgroupManager {
+    path: PathBuf,
+}
+
+impl CgroupManager {
+    /// Crear el grupo y sus hojas y mover el proceso actual a `core`
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let parent = path.parent().ok_or_else(|| anyhow!("Ruta de cgroup inválida: {}", path.display()))?;
+        if !parent.join("cgroup.controllers").exists() {
+            return Err(anyhow!("{} no es una jerarquía cgroup v2", parent.display()));
+        }
+
+        fs::create_dir_all(&path)?;
+        // El padre puede no delegar algún controlador; se aplican los disponibles
+        let available = fs::read_to_string(path.join("cgroup.controllers")).unwrap_or_default();
+        for controller in CONTROLLERS {
+            if !available.split_whitespace().any(|c| c == controller) {
+                if let Err(e) = fs::write(parent.join("cgroup.subtree_control"), format!("+{}", controller)) {
+                    warn!("⚠️  Controlador {} no disponible en {}: {}", controller, parent.display(), e);
+                    continue;
+                }
+            }
+            if let Err(e) = fs::write(path.join("cgroup.subtree_control"), format!("+{}", controller)) {
+                warn!("⚠️  Error habilitando el controlador {}: {}", controller, e);
+            }
+        }
+
+        for leaf in ["core", "sandbox"] {
+            fs::create_dir_all(path.join(leaf))?;
+        }
+
+        let manager = Self { path };
+        manager.attach(std::process::id())?;
+        info!("📦 Proceso {} en el cgroup {}", std::process::id(), manager.path.display());
+        Ok(manager)
+    }
+
+    /// Mover un proceso (y sus hijos futuros) a la hoja `core`
+    pub fn attach(&self, pid: u32) -> Result<()> {
+        fs::write(self.path.join("core/cgroup.procs"), pid.to_string())?;
+        Ok(())
+    }
+
+    /// Mover un hijo aislado a la hoja `sandbox`, dentro de los mismos límites
+    pub fn attach_sandboxed(&self, pid: u32) -> Result<()> {
+        fs::write(self.path.join("sandbox/cgroup.procs"), pid.to_string())?;
+        debug!("📦 Proceso {} movido al sandbox", pid);
+        Ok(())
+    }
+
+    /// Aplicar límites de CPU, memoria, procesos y descriptores
+    pub fn apply_limits(&self, limits: &ResourceLimits) -> Result<()> {
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        fs::write(self.path.join("cpu.max"), cpu_max(limits.max_cpu_percent, cpus))?;
+        fs::write(self.path.join("memory.max"), (limits.max_memory_mb * 1024 * 1024).to_string())?;
+        fs::write(self.path.join("pids.max"), limits.max_pids.to_string())?;
+        set_nofile_limit(limits.max_file_descriptors)?;
+
+        info!(
+            "📦 Límites del cgroup: CPU {}%, memoria {} MB, {} procesos, {} descriptores",
+            limits.max_cpu_percent, limits.max_memory_mb, limits.max_pids, limits.max_file_descriptors
+        );
+        Ok(())
+    }
+
+    /// Leer contadores de `cpu.stat`, `memory.events`, `memory.current` y `pids.current`
+    pub fn stats(&self) -> Result<CgroupStats> {
+        let read = |file: &str| fs::read_to_string(self.path.join(file)).unwrap_or_default();
+        let (cpu_stat, memory_events) = (read("cpu.stat"), read("memory.events"));
+        let cpu = parse_flat_keyed(&cpu_stat);
+        let memory = parse_flat_keyed(&memory_events);
+        let value = |content: String| content.trim().parse::<u64>().unwrap_or(0);
+
+        Ok(CgroupStats {
+            nr_periods: cpu("nr_periods"),
+            nr_throttled: cpu("nr_throttled"),
+            throttled_usec: cpu("throttled_usec"),
+            memory_current: value(read("memory.current")),
+            memory_max_events: memory("max"),
+            oom_kills: memory("oom_kill"),
+            pids_current: value(read("pids.current")),
+        })
+    }
+}
+
+/// `cpu.max` para un porcentaje de la capacidad total de la máquina
+fn cpu_max(percent: f64, cpus: usize) -> String {
+    if percent >= 100.0 {
+        return format!("max {}", CPU_PERIOD_US);
+    }
+    let quota = (percent / 100.0 * cpus as f64 * CPU_PERIOD_US as f64).round().max(1000.0) as u64;
+    format!("{} {}", quota, CPU_PERIOD_US)
+}
+
+/// Archivos "clave valor" por línea de cgroup v2
+fn parse_flat_keyed(content: &str) -> impl Fn(&str) -> u64 + '_ {
+    move |key| {
+        content
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.trim().parse().ok())
+            .unwrap_or(0)
+    }
+}
+
+/// Límite blando de descriptores, acotado al límite duro
+#[cfg(unix)]
+fn set_nofile_limit(max: u32) -> Result<()> {
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    limit.rlim_cur = (max as libc::rlim_t).min(limit.rlim_max);
+    if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_nofile_limit(_max: u32) -> Result<()> {
+    Err(anyhow!("RLIMIT_NOFILE solo está disponible en Unix"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_and_stats() {
+        assert_eq!(cpu_max(50.0, 4), "200000 100000");
+        assert_eq!(cpu_max(100.0, 4), "max 100000");
+
+        let dir = tempfile::tempdir().unwrap();
+        let manager = CgroupManager { path: dir.path().to_path_buf() };
+        fs::write(dir.path().join("cpu.stat"), "usage_usec 10\nnr_periods 20\nnr_throttled 5\nthrottled_usec 1500\n").unwrap();
+        fs::write(dir.path().join("memory.events"), "low 0\nhigh 0\nmax 3\noom 1\noom_kill 1\n").unwrap();
+        fs::write(dir.path().join("memory.current"), "1048576\n").unwrap();
+
+        let stats = manager.stats().unwrap();
+        assert_eq!(stats.nr_throttled, 5);
+        assert_eq!(stats.throttled_usec, 1500);
+        assert_eq!(stats.memory_max_events, 3);
+        assert_eq!(stats.oom_kills, 1);
+        assert_eq!(stats.memory_current, 1048576);
+        assert_eq!(stats.pids_current, 0);
+    }
+}
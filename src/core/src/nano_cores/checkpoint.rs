@@ -0,0 +1,114 @@
+//! Checkpoints del estado de los nano-núcleos
+//!
+//! Con `[nano_cores.checkpoint] enabled = true` el NanoCoreManager guarda
+//! cada `interval_secs` el `snapshot()` de cada réplica en `dir` y lo
+//! recupera con `restore()` al arrancarla, tras un reinicio del proceso, y al
+//! reemplazarla en un hot-swap, para no perder líneas base ni contadores.
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tracing::debug;
+
+use super::NanoCoreType;
+
+/// Sección `[nano_cores.checkpoint]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CheckpointConfig {
+    pub enabled: bool,
+    /// Directorio de los checkpoints
+    pub dir: String,
+    /// Intervalo entre checkpoints, en segundos
+    pub interval_secs: u64,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "data/checkpoints".to_string(),
+            interval_secs: 60,
+        }
+    }
+}
+
+impl CheckpointConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+}
+
+/// Un archivo por réplica: `<sección>-<instancia>.bin`
+///
+/// Se identifica por sección e instancia, no por `instance_id`, que cambia
+/// en cada reemplazo y reinicio.
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Guardar el estado de una réplica; un estado vacío borra el checkpoint anterior
+    pub async fn save(&self, core_type: &NanoCoreType, instance: usize, state: &[u8]) -> Result<()> {
+        let path = self.path(core_type, instance);
+        if state.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path).await?;
+            }
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dir).await?;
+        let tmp = path.with_extension("bin.tmp");
+        fs::write(&tmp, state).await?;
+        fs::rename(&tmp, &path).await?;
+
+        debug!("💾 Checkpoint de {} instancia {}: {} bytes", core_type, instance, state.len());
+        Ok(())
+    }
+
+    /// Último estado guardado de una réplica
+    pub async fn load(&self, core_type: &NanoCoreType, instance: usize) -> Result<Option<Vec<u8>>> {
+        let path = self.path(core_type, instance);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(&path).await?))
+    }
+
+    fn path(&self, core_type: &NanoCoreType, instance: usize) -> PathBuf {
+        self.dir.join(format!("{}-{}.bin", core_type.config_section(), instance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(dir.path().join("checkpoints"));
+
+        assert_eq!(store.load(&NanoCoreType::Network, 0).await.unwrap(), None);
+
+        store.save(&NanoCoreType::Network, 0, b"baseline").await.unwrap();
+        store.save(&NanoCoreType::Network, 1, b"other").await.unwrap();
+        assert_eq!(store.load(&NanoCoreType::Network, 0).await.unwrap().as_deref(), Some(&b"baseline"[..]));
+        assert_eq!(store.load(&NanoCoreType::OS, 0).await.unwrap(), None);
+
+        store.save(&NanoCoreType::Network, 0, b"").await.unwrap();
+        assert_eq!(store.load(&NanoCoreType::Network, 0).await.unwrap(), None);
+        assert!(store.load(&NanoCoreType::Network, 1).await.unwrap().is_some());
+    }
+}
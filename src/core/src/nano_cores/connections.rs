@@ -0,0 +1,727 @@
+//! Tabla de conexiones del sistema para NetworkCore
+//!
+//! En Linux los sockets TCP y UDP se piden al kernel por netlink (`sock_diag`,
+//! lo mismo que `ss`), que con TCP adjunta `tcp_info`: bytes, RTT y
+//! retransmisiones. Sin netlink se leen `/proc/net/{tcp,udp}{,6}`. El proceso
+//! dueño de cada socket sale de `/proc/<pid>/fd`. En Windows se usan
+//! `GetExtendedTcpTable` y `GetExtendedUdpTable`, que ya traen el PID.
+//! `ConnectionTracker` conserva entre lecturas el instante de apertura, los
+//! contadores y las transiciones de estado de cada conexión.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::network_core::{Connection, ConnectionState, Protocol, QualityMetrics, StateTransition};
+
+/// Transiciones de estado conservadas por conexión
+const MAX_TRANSITIONS: usize = 16;
+
+/// Socket tal como lo informa el sistema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Socket {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: ConnectionState,
+    /// Inodo del socket (Linux); 0 si no se conoce
+    pub inode: u64,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub rtt: Option<Duration>,
+    pub rtt_var: Option<Duration>,
+    pub retransmits: u64,
+    pub segments_sent: u64,
+}
+
+impl Socket {
+    fn new(protocol: Protocol, local: SocketAddr, remote: SocketAddr, state: ConnectionState) -> Self {
+        Self {
+            protocol,
+            local,
+            remote,
+            state,
+            inode: 0,
+            pid: None,
+            process: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            rtt: None,
+            rtt_var: None,
+            retransmits: 0,
+            segments_sent: 0,
+        }
+    }
+
+    /// Identificador estable mientras exista el socket: `tcp 10.0.0.2:5432->10.0.0.9:40112`
+    pub fn id(&self) -> String {
+        let protocol = match self.protocol {
+            Protocol::UDP => "udp",
+            _ => "tcp",
+        };
+        format!("{} {}->{}", protocol, self.local, self.remote)
+    }
+
+    fn quality(&self, throughput_mbps: f64) -> QualityMetrics {
+        let latency_ms = self.rtt.map_or(0.0, |rtt| rtt.as_secs_f64() * 1000.0);
+        let packet_loss_rate = if self.segments_sent > 0 {
+            (self.retransmits as f64 / self.segments_sent as f64).min(1.0)
+        } else {
+            0.0
+        };
+        QualityMetrics {
+            latency_ms,
+            jitter_ms: self.rtt_var.map_or(0.0, |var| var.as_secs_f64() * 1000.0),
+            packet_loss_rate,
+            throughput_mbps,
+            // 100 ms de RTT o un 10 % de retransmisiones dejan la nota en la mitad
+            quality_score: (1.0 - packet_loss_rate * 5.0).max(0.0) / (1.0 + latency_ms / 100.0),
+        }
+    }
+}
+
+/// Sockets TCP y UDP abiertos, con su proceso cuando se puede determinar
+pub fn sockets() -> Result<Vec<Socket>> {
+    platform::sockets()
+}
+
+struct Tracked {
+    connection: Connection,
+    updated: Instant,
+}
+
+/// Conexiones vistas en la última lectura, con su historia
+#[derive(Default)]
+pub struct ConnectionTracker {
+    connections: HashMap<String, Tracked>,
+}
+
+impl ConnectionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Incorporar una lectura; las conexiones que ya no aparecen se descartan
+    pub fn update(&mut self, sockets: Vec<Socket>, now: Instant, wall: SystemTime) {
+        let mut current = HashMap::with_capacity(sockets.len());
+        for socket in sockets {
+            let id = socket.id();
+            let tracked = match self.connections.remove(&id) {
+                Some(mut tracked) => {
+                    let connection = &mut tracked.connection;
+                    let elapsed = now.duration_since(tracked.updated).as_secs_f64();
+                    let delta = socket.bytes_sent.saturating_sub(connection.bytes_sent)
+                        + socket.bytes_received.saturating_sub(connection.bytes_received);
+                    let throughput = if elapsed > 0.0 { delta as f64 * 8.0 / elapsed / 1_000_000.0 } else { 0.0 };
+
+                    if connection.state != socket.state {
+                        if connection.transitions.len() == MAX_TRANSITIONS {
+                            connection.transitions.remove(0);
+                        }
+                        connection.transitions.push(StateTransition {
+                            from: connection.state.clone(),
+                            to: socket.state.clone(),
+                            at: wall,
+                        });
+                    }
+                    connection.state = socket.state.clone();
+                    connection.bytes_sent = socket.bytes_sent;
+                    connection.bytes_received = socket.bytes_received;
+                    connection.latency = socket.rtt;
+                    connection.quality_metrics = socket.quality(throughput);
+                    // El PID puede faltar en una lectura si el proceso cerró el descriptor
+                    if socket.pid.is_some() {
+                        connection.pid = socket.pid;
+                        connection.process = socket.process;
+                    }
+                    tracked.updated = now;
+                    tracked
+                }
+                None => Tracked {
+                    connection: Connection {
+                        id: id.clone(),
+                        protocol: socket.protocol.clone(),
+                        local_address: socket.local,
+                        remote_address: socket.remote,
+                        state: socket.state.clone(),
+                        established_time: wall,
+                        bytes_sent: socket.bytes_sent,
+                        bytes_received: socket.bytes_received,
+                        latency: socket.rtt,
+                        quality_metrics: socket.quality(0.0),
+                        pid: socket.pid,
+                        process: socket.process.clone(),
+                        transitions: Vec::new(),
+                    },
+                    updated: now,
+                },
+            };
+            current.insert(id, tracked);
+        }
+        self.connections = current;
+    }
+
+    /// Conexiones ordenadas por identificador
+    pub fn connections(&self) -> Vec<Connection> {
+        let mut connections: Vec<Connection> = self.connections.values().map(|t| t.connection.clone()).collect();
+        connections.sort_by(|a, b| a.id.cmp(&b.id));
+        connections
+    }
+}
+
+/// Estados TCP del kernel Linux (`include/net/tcp_states.h`)
+fn linux_tcp_state(state: u8) -> ConnectionState {
+    match state {
+        1 => ConnectionState::Established,
+        2 | 3 => ConnectionState::Connecting,
+        4 | 5 | 8 | 9 | 11 => ConnectionState::Closing,
+        6 => ConnectionState::TimeWait,
+        10 => ConnectionState::Listening,
+        _ => ConnectionState::Closed,
+    }
+}
+
+/// UDP no tiene estados: sin destino fijo el socket solo escucha
+fn udp_state(remote: &SocketAddr) -> ConnectionState {
+    if remote.port() == 0 {
+        ConnectionState::Listening
+    } else {
+        ConnectionState::Established
+    }
+}
+
+/// Líneas de `/proc/net/{tcp,udp}{,6}`: `sl local rem st ... uid timeout inode`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net(content: &str, protocol: Protocol) -> Vec<Socket> {
+    // Direcciones como palabras de 32 bits en el orden del host; puerto en hexadecimal
+    let address = |value: &str| -> Option<SocketAddr> {
+        let (ip, port) = value.split_once(':')?;
+        let words: Vec<[u8; 4]> = (0..ip.len() / 8)
+            .map(|i| u32::from_str_radix(ip.get(i * 8..i * 8 + 8)?, 16).ok().map(u32::to_ne_bytes))
+            .collect::<Option<_>>()?;
+        let ip = match words.len() {
+            1 => IpAddr::V4(Ipv4Addr::from(words[0])),
+            4 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(words.concat()).ok()?)),
+            _ => return None,
+        };
+        Some(SocketAddr::new(ip, u16::from_str_radix(port, 16).ok()?))
+    };
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let (local, remote) = (address(fields[1])?, address(fields[2])?);
+            let state = match protocol {
+                Protocol::UDP => udp_state(&remote),
+                _ => linux_tcp_state(u8::from_str_radix(fields[3], 16).ok()?),
+            };
+            let mut socket = Socket::new(protocol.clone(), local, remote, state);
+            socket.inode = fields[9].parse().ok()?;
+            Some(socket)
+        })
+        .collect()
+}
+
+/// Inodo de socket → PID, recorriendo `/proc/<pid>/fd`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn socket_owners(proc_root: &std::path::Path) -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(processes) = std::fs::read_dir(proc_root) else {
+        return owners;
+    };
+    for process in processes.filter_map(|entry| entry.ok()) {
+        let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        // Sin permisos sobre los procesos de otros usuarios no se puede leer `fd`
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.filter_map(|entry| entry.ok()) {
+            let Ok(target) = std::fs::read_link(fd.path()) else { continue };
+            let inode = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse().ok());
+            if let Some(inode) = inode {
+                owners.insert(inode, pid);
+            }
+        }
+    }
+    owners
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::path::Path;
+    use tracing::debug;
+
+    pub fn sockets() -> Result<Vec<Socket>> {
+        let mut sockets = match sock_diag::all() {
+            Ok(sockets) => sockets,
+            Err(e) => {
+                debug!("🔌 sock_diag no disponible, se lee /proc/net: {}", e);
+                let mut sockets = Vec::new();
+                for (file, protocol) in [("tcp", Protocol::TCP), ("tcp6", Protocol::TCP), ("udp", Protocol::UDP), ("udp6", Protocol::UDP)] {
+                    // Sin IPv6 en el kernel no existen `tcp6` ni `udp6`
+                    if let Ok(content) = std::fs::read_to_string(Path::new("/proc/net").join(file)) {
+                        sockets.extend(parse_proc_net(&content, protocol));
+                    }
+                }
+                sockets
+            }
+        };
+
+        let owners = socket_owners(Path::new("/proc"));
+        let mut names: HashMap<u32, Option<String>> = HashMap::new();
+        for socket in &mut sockets {
+            if let Some(pid) = owners.get(&socket.inode).copied() {
+                socket.pid = Some(pid);
+                socket.process = names
+                    .entry(pid)
+                    .or_insert_with(|| {
+                        std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok().map(|comm| comm.trim().to_string())
+                    })
+                    .clone();
+            }
+        }
+        Ok(sockets)
+    }
+
+    /// Volcado de sockets por netlink `NETLINK_SOCK_DIAG`
+    pub(super) mod sock_diag {
+        use super::*;
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+        const NETLINK_SOCK_DIAG: i32 = 4;
+        const SOCK_DIAG_BY_FAMILY: u16 = 20;
+        const NLM_F_REQUEST: u16 = 0x1;
+        const NLM_F_DUMP: u16 = 0x300;
+        const NLMSG_ERROR: u16 = 2;
+        const NLMSG_DONE: u16 = 3;
+        const INET_DIAG_INFO: u16 = 2;
+        const HEADER_LEN: usize = 16;
+        /// `struct inet_diag_msg`
+        const DIAG_MSG_LEN: usize = 72;
+
+        fn align(len: usize) -> usize {
+            (len + 3) & !3
+        }
+
+        fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+            Some(u16::from_ne_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+        }
+
+        fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+            Some(u32::from_ne_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+        }
+
+        fn u64_at(bytes: &[u8], offset: usize) -> Option<u64> {
+            Some(u64::from_ne_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+        }
+
+        pub fn all() -> Result<Vec<Socket>> {
+            let mut sockets = Vec::new();
+            for family in [libc::AF_INET as u8, libc::AF_INET6 as u8] {
+                sockets.extend(dump(family, libc::IPPROTO_TCP as u8)?);
+                sockets.extend(dump(family, libc::IPPROTO_UDP as u8)?);
+            }
+            Ok(sockets)
+        }
+
+        /// `nlmsghdr` + `inet_diag_req_v2` pidiendo todos los estados y `tcp_info`
+        pub fn request(family: u8, protocol: u8) -> Vec<u8> {
+            let mut request = Vec::with_capacity(HEADER_LEN + 56);
+            request.extend_from_slice(&((HEADER_LEN + 56) as u32).to_ne_bytes());
+            request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+            request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+            request.extend_from_slice(&1u32.to_ne_bytes());
+            request.extend_from_slice(&0u32.to_ne_bytes());
+            request.extend_from_slice(&[family, protocol, 1 << (INET_DIAG_INFO - 1), 0]);
+            request.extend_from_slice(&u32::MAX.to_ne_bytes());
+            // `inet_diag_sockid` a cero: sin filtro
+            request.extend_from_slice(&[0u8; 48]);
+            request
+        }
+
+        fn dump(family: u8, protocol: u8) -> Result<Vec<Socket>> {
+            let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_SOCK_DIAG) };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            // SAFETY: descriptor recién creado y sin otro dueño
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+            let request = request(family, protocol);
+            let mut kernel: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+            kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            let sent = unsafe {
+                libc::sendto(
+                    fd.as_raw_fd(),
+                    request.as_ptr() as *const libc::c_void,
+                    request.len(),
+                    0,
+                    &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                )
+            };
+            if sent < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+
+            let mut buffer = vec![0u8; 64 * 1024];
+            let mut sockets = Vec::new();
+            loop {
+                let received = unsafe { libc::recv(fd.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+                if received < 0 {
+                    return Err(std::io::Error::last_os_error().into());
+                }
+                if parse_messages(&buffer[..received as usize], protocol, &mut sockets)? {
+                    return Ok(sockets);
+                }
+            }
+        }
+
+        /// Mensajes de una respuesta; `true` al llegar `NLMSG_DONE`
+        pub fn parse_messages(buffer: &[u8], protocol: u8, sockets: &mut Vec<Socket>) -> Result<bool> {
+            let mut offset = 0;
+            while let (Some(len), Some(kind)) = (u32_at(buffer, offset), u16_at(buffer, offset + 4)) {
+                let len = len as usize;
+                if len < HEADER_LEN || offset + len > buffer.len() {
+                    break;
+                }
+                let body = &buffer[offset + HEADER_LEN..offset + len];
+                match kind {
+                    NLMSG_DONE => return Ok(true),
+                    NLMSG_ERROR => {
+                        let errno = u32_at(body, 0).map_or(0, |e| e as i32);
+                        return Err(std::io::Error::from_raw_os_error(-errno).into());
+                    }
+                    _ => sockets.extend(parse_diag_msg(body, protocol)),
+                }
+                offset += align(len);
+            }
+            Ok(false)
+        }
+
+        fn parse_diag_msg(msg: &[u8], protocol: u8) -> Option<Socket> {
+            if msg.len() < DIAG_MSG_LEN {
+                return None;
+            }
+            let ip = |bytes: &[u8]| -> Option<IpAddr> {
+                if msg[0] == libc::AF_INET as u8 {
+                    Some(IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(&bytes[..4]).ok()?)))
+                } else {
+                    Some(IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)))
+                }
+            };
+            // Puertos en orden de red
+            let local = SocketAddr::new(ip(&msg[8..24])?, u16::from_be_bytes([msg[4], msg[5]]));
+            let remote = SocketAddr::new(ip(&msg[24..40])?, u16::from_be_bytes([msg[6], msg[7]]));
+            let (protocol, state) = if protocol == libc::IPPROTO_UDP as u8 {
+                (Protocol::UDP, udp_state(&remote))
+            } else {
+                (Protocol::TCP, linux_tcp_state(msg[1]))
+            };
+            let mut socket = Socket::new(protocol, local, remote, state);
+            socket.inode = u32_at(msg, 68)? as u64;
+
+            let mut offset = DIAG_MSG_LEN;
+            while let (Some(len), Some(kind)) = (u16_at(msg, offset), u16_at(msg, offset + 2)) {
+                let len = len as usize;
+                if len < 4 || offset + len > msg.len() {
+                    break;
+                }
+                if kind == INET_DIAG_INFO {
+                    apply_tcp_info(&mut socket, &msg[offset + 4..offset + len]);
+                }
+                offset += align(len);
+            }
+            Some(socket)
+        }
+
+        /// Campos de `struct tcp_info`; los kernels antiguos envían versiones más cortas
+        fn apply_tcp_info(socket: &mut Socket, info: &[u8]) {
+            socket.rtt = u32_at(info, 68).filter(|rtt| *rtt > 0).map(|us| Duration::from_micros(us as u64));
+            socket.rtt_var = u32_at(info, 72).map(|us| Duration::from_micros(us as u64));
+            socket.retransmits = u32_at(info, 100).unwrap_or(0) as u64;
+            socket.bytes_sent = u64_at(info, 120).unwrap_or(0);
+            socket.bytes_received = u64_at(info, 128).unwrap_or(0);
+            socket.segments_sent = u32_at(info, 136).unwrap_or(0) as u64;
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_INSUFFICIENT_BUFFER, ERROR_SUCCESS};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6TABLE_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
+        MIB_UDP6TABLE_OWNER_PID, MIB_UDPTABLE_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+    };
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6};
+    use windows::Win32::System::Threading::{OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    /// Estados de `MIB_TCP_STATE`
+    fn tcp_state(state: u32) -> ConnectionState {
+        match state {
+            2 => ConnectionState::Listening,
+            3 | 4 => ConnectionState::Connecting,
+            5 => ConnectionState::Established,
+            6..=10 => ConnectionState::Closing,
+            11 => ConnectionState::TimeWait,
+            _ => ConnectionState::Closed,
+        }
+    }
+
+    /// Puerto en orden de red en los 16 bits bajos
+    fn port(value: u32) -> u16 {
+        u16::from_be(value as u16)
+    }
+
+    fn v4(addr: u32, port_value: u32) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr))), port(port_value))
+    }
+
+    fn v6(addr: [u8; 16], port_value: u32) -> SocketAddr {
+        SocketAddr::new(IpAddr::V6(Ipv6Addr::from(addr)), port(port_value))
+    }
+
+    /// Llamar a una función de tabla ampliando el buffer hasta que quepa
+    fn read_table(fill: impl Fn(*mut std::ffi::c_void, *mut u32) -> u32) -> Result<Vec<u64>> {
+        let mut size = 0u32;
+        // u64 para la alineación de las filas
+        let mut buffer: Vec<u64> = vec![0];
+        loop {
+            match fill(buffer.as_mut_ptr() as *mut std::ffi::c_void, &mut size) {
+                result if result == ERROR_SUCCESS.0 => return Ok(buffer),
+                result if result == ERROR_INSUFFICIENT_BUFFER.0 => buffer = vec![0u64; (size as usize).div_ceil(8)],
+                result => return Err(std::io::Error::from_raw_os_error(result as i32).into()),
+            }
+        }
+    }
+
+    fn tcp_table(family: u32) -> Result<Vec<u64>> {
+        read_table(|table, size| unsafe {
+            GetExtendedTcpTable(Some(table), size, false, family, TCP_TABLE_OWNER_PID_ALL, 0)
+        })
+    }
+
+    fn udp_table(family: u32) -> Result<Vec<u64>> {
+        read_table(|table, size| unsafe { GetExtendedUdpTable(Some(table), size, false, family, UDP_TABLE_OWNER_PID, 0) })
+    }
+
+    /// Nombre del ejecutable, sin ruta
+    fn process_name(pid: u32) -> Option<String> {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+        let mut buffer = [0u16; 260];
+        let mut size = buffer.len() as u32;
+        let result = unsafe { QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut size) };
+        let _ = unsafe { CloseHandle(handle) };
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        path.rsplit('\\').next().map(str::to_string)
+    }
+
+    pub fn sockets() -> Result<Vec<Socket>> {
+        let mut sockets: Vec<(Socket, u32)> = Vec::new();
+        let unspecified_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        let unspecified_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0);
+
+        // SAFETY: cada buffer contiene la tabla completa con `dwNumEntries` filas
+        unsafe {
+            let buffer = tcp_table(AF_INET.0 as u32)?;
+            let table = &*(buffer.as_ptr() as *const MIB_TCPTABLE_OWNER_PID);
+            for row in std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) {
+                let socket = Socket::new(
+                    Protocol::TCP,
+                    v4(row.dwLocalAddr, row.dwLocalPort),
+                    v4(row.dwRemoteAddr, row.dwRemotePort),
+                    tcp_state(row.dwState),
+                );
+                sockets.push((socket, row.dwOwningPid));
+            }
+
+            let buffer = tcp_table(AF_INET6.0 as u32)?;
+            let table = &*(buffer.as_ptr() as *const MIB_TCP6TABLE_OWNER_PID);
+            for row in std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) {
+                let socket = Socket::new(
+                    Protocol::TCP,
+                    v6(row.ucLocalAddr, row.dwLocalPort),
+                    v6(row.ucRemoteAddr, row.dwRemotePort),
+                    tcp_state(row.dwState),
+                );
+                sockets.push((socket, row.dwOwningPid));
+            }
+
+            let buffer = udp_table(AF_INET.0 as u32)?;
+            let table = &*(buffer.as_ptr() as *const MIB_UDPTABLE_OWNER_PID);
+            for row in std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) {
+                let socket = Socket::new(Protocol::UDP, v4(row.dwLocalAddr, row.dwLocalPort), unspecified_v4, ConnectionState::Listening);
+                sockets.push((socket, row.dwOwningPid));
+            }
+
+            let buffer = udp_table(AF_INET6.0 as u32)?;
+            let table = &*(buffer.as_ptr() as *const MIB_UDP6TABLE_OWNER_PID);
+            for row in std::slice::from_raw_parts(table.table.as_ptr(), table.dwNumEntries as usize) {
+                let socket = Socket::new(Protocol::UDP, v6(row.ucLocalAddr, row.dwLocalPort), unspecified_v6, ConnectionState::Listening);
+                sockets.push((socket, row.dwOwningPid));
+            }
+        }
+
+        let mut names: HashMap<u32, Option<String>> = HashMap::new();
+        Ok(sockets
+            .into_iter()
+            .map(|(mut socket, pid)| {
+                // PID 0: proceso inactivo, sockets en TIME_WAIT sin dueño
+                if pid != 0 {
+                    socket.pid = Some(pid);
+                    socket.process = names.entry(pid).or_insert_with(|| process_name(pid)).clone();
+                }
+                socket
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use super::*;
+
+    pub fn sockets() -> Result<Vec<Socket>> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket(state: ConnectionState, bytes: u64) -> Socket {
+        let mut socket = Socket::new(
+            Protocol::TCP,
+            "10.0.0.2:5432".parse().unwrap(),
+            "10.0.0.9:40112".parse().unwrap(),
+            state,
+        );
+        socket.bytes_sent = bytes;
+        socket.segments_sent = 100;
+        socket.retransmits = 2;
+        socket.rtt = Some(Duration::from_millis(20));
+        socket
+    }
+
+    #[test]
+    fn test_tracker_keeps_history() {
+        let mut tracker = ConnectionTracker::new();
+        let start = Instant::now();
+        let wall = SystemTime::now();
+
+        tracker.update(vec![socket(ConnectionState::Connecting, 0)], start, wall);
+        tracker.update(
+            vec![socket(ConnectionState::Established, 1_250_000)],
+            start + Duration::from_secs(1),
+            wall + Duration::from_secs(1),
+        );
+        let connections = tracker.connections();
+        assert_eq!(connections.len(), 1);
+        let connection = &connections[0];
+        assert_eq!(connection.id, "tcp 10.0.0.2:5432->10.0.0.9:40112");
+        assert_eq!(connection.established_time, wall);
+        assert_eq!(connection.bytes_sent, 1_250_000);
+        assert_eq!(connection.transitions.len(), 1);
+        assert_eq!(connection.transitions[0].from, ConnectionState::Connecting);
+        assert_eq!(connection.transitions[0].to, ConnectionState::Established);
+        assert!((connection.quality_metrics.throughput_mbps - 10.0).abs() < 1e-9);
+        assert!((connection.quality_metrics.packet_loss_rate - 0.02).abs() < 1e-9);
+        assert_eq!(connection.latency, Some(Duration::from_millis(20)));
+
+        tracker.update(Vec::new(), start + Duration::from_secs(2), wall);
+        assert!(tracker.connections().is_empty());
+    }
+
+    #[test]
+    fn test_proc_net_and_owners() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+            0: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   100        0 31337 1 0000000000000000 100 0 0 10 0\n\
+            1: 0200000A:1538 0900000A:9CB0 01 00000000:00000000 00:00000000 00000000   100        0 31338 1 0000000000000000 20 4 30 10 -1\n";
+        let sockets = parse_proc_net(tcp, Protocol::TCP);
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].local, "127.0.0.1:5432".parse().unwrap());
+        assert_eq!(sockets[0].state, ConnectionState::Listening);
+        assert_eq!(sockets[1].id(), "tcp 10.0.0.2:5432->10.0.0.9:40112");
+        assert_eq!(sockets[1].inode, 31338);
+
+        let udp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops\n\
+            0: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 4242 2 0000000000000000 0\n";
+        let sockets = parse_proc_net(udp6, Protocol::UDP);
+        assert_eq!(sockets[0].local, "[::1]:53".parse().unwrap());
+        assert_eq!(sockets[0].state, ConnectionState::Listening);
+
+        #[cfg(unix)]
+        {
+            let root = tempfile::tempdir().unwrap();
+            let fd_dir = root.path().join("1234/fd");
+            std::fs::create_dir_all(&fd_dir).unwrap();
+            std::os::unix::fs::symlink("socket:[31338]", fd_dir.join("3")).unwrap();
+            std::os::unix::fs::symlink("/dev/null", fd_dir.join("0")).unwrap();
+            std::fs::create_dir_all(root.path().join("self")).unwrap();
+            assert_eq!(socket_owners(root.path()), HashMap::from([(31338, 1234)]));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sock_diag_messages() {
+        use super::platform::sock_diag;
+
+        assert_eq!(sock_diag::request(libc::AF_INET as u8, libc::IPPROTO_TCP as u8).len(), 72);
+
+        // inet_diag_msg de 10.0.0.2:5432 -> 10.0.0.9:40112 con tcp_info
+        let mut msg = vec![libc::AF_INET as u8, 1, 0, 0];
+        msg.extend_from_slice(&5432u16.to_be_bytes());
+        msg.extend_from_slice(&40112u16.to_be_bytes());
+        msg.extend_from_slice(&[10, 0, 0, 2]);
+        msg.extend_from_slice(&[0; 12]);
+        msg.extend_from_slice(&[10, 0, 0, 9]);
+        msg.extend_from_slice(&[0; 12]);
+        msg.extend_from_slice(&[0; 12 + 16]);
+        msg.extend_from_slice(&31338u32.to_ne_bytes());
+        let mut info = vec![0u8; 144];
+        info[68..72].copy_from_slice(&20_000u32.to_ne_bytes());
+        info[120..128].copy_from_slice(&4096u64.to_ne_bytes());
+        info[128..136].copy_from_slice(&8192u64.to_ne_bytes());
+        msg.extend_from_slice(&((info.len() + 4) as u16).to_ne_bytes());
+        msg.extend_from_slice(&2u16.to_ne_bytes());
+        msg.extend_from_slice(&info);
+
+        let mut buffer = Vec::new();
+        for (kind, body) in [(20u16, msg.as_slice()), (3u16, &[0u8; 4][..])] {
+            buffer.extend_from_slice(&((16 + body.len()) as u32).to_ne_bytes());
+            buffer.extend_from_slice(&kind.to_ne_bytes());
+            buffer.extend_from_slice(&[0; 10]);
+            buffer.extend_from_slice(body);
+        }
+
+        let mut sockets = Vec::new();
+        assert!(sock_diag::parse_messages(&buffer, libc::IPPROTO_TCP as u8, &mut sockets).unwrap());
+        assert_eq!(sockets.len(), 1);
+        let socket = &sockets[0];
+        assert_eq!(socket.id(), "tcp 10.0.0.2:5432->10.0.0.9:40112");
+        assert_eq!(socket.state, ConnectionState::Established);
+        assert_eq!((socket.inode, socket.bytes_sent, socket.bytes_received), (31338, 4096, 8192));
+        assert_eq!(socket.rtt, Some(Duration::from_millis(20)));
+    }
+}
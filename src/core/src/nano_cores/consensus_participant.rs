@@ -89,6 +89,12 @@ impl NanoCoreConsensusParticipant {
                     _ => Ok(VoteDecision::Abstain),
                 }
             }
+            
+            ProposalType::Remediation => {
+                // Una réplica degradada se abstiene: su diagnóstico puede ser el problema
+                let health = *self.health_score.read().await;
+                Ok(if health > 0.7 { VoteDecision::Approve } else { VoteDecision::Abstain })
+            }
         }
     }
     
@@ -178,6 +184,7 @@ impl ConsensusParticipant for NanoCoreConsensusParticipant {
             payload: serde_json::to_vec(result)?,
             priority: crate::communication::EventPriority::High,
             correlation_id: Some(result.proposal_id),
+            headers: std::collections::HashMap::new(),
         }).await?;
         
         Ok(())
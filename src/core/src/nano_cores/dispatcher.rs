@@ -0,0 +1,422 @@
+//! Enrutado de comandos desde el Cognitive Fabric
+//!
+//! `CommandDispatcher` escucha `<núcleo>.commands` y los buzones de eventos
+//! dirigidos (`saai.inbox.OSCore.*`) por cada tipo de nano-núcleo, interpreta
+//! el sobre del comando, lo ejecuta con `NanoCoreManager::execute_command`
+//! (que comprueba la autorización) y publica en `reply_to` el resultado o un
+//! error estructurado. Un evento con `target = "OSCore-0"` solo se ejecuta en
+//! esa instancia; uno dirigido al grupo, en todas las activas.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::{CommandDenied, NanoCoreError, NanoCoreManager, NanoCoreType};
+use crate::communication::router;
+use crate::security::SecurityContext;
+
+/// Comandos recibidos pendientes de despacho
+const COMMAND_QUEUE_CAPACITY: usize = 1024;
+
+/// Credenciales del emisor de un comando
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandCredentials {
+    /// Sesión abierta en el SecurityManager; se usan sus permisos, no los del emisor
+    Session(Uuid),
+    /// JWT; abre una sesión solo para este comando
+    Token(String),
+}
+
+/// Sobre de un comando publicado en `<núcleo>.commands`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandEnvelope {
+    pub command: String,
+    /// Payload JSON que recibe `process_command`
+    #[serde(default)]
+    pub payload: Value,
+    /// Tema en el que publicar la respuesta
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    pub credentials: CommandCredentials,
+    /// Réplica destino; sin ella se reparte entre las activas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+/// Causa de un comando no ejecutado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandErrorCode {
+    InvalidEnvelope,
+    Unauthenticated,
+    Unauthorized,
+    /// Sin réplicas activas del nano-núcleo
+    Unavailable,
+    /// Réplica sobrecargada (`NanoCoreError::Backpressure`): reintentar más tarde
+    Backpressure,
+    /// `process_command` devolvió un error
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandError {
+    pub code: CommandErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    fn new(code: CommandErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Respuesta publicada en `reply_to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandReply {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    pub command: String,
+    /// Réplica que ejecutó el comando
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<usize>,
+    /// Respuesta de `process_command`; en base64 si no es JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<CommandError>,
+}
+
+/// Réplicas a las que va un comando recibido
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// `<núcleo>.commands`: la réplica del sobre o la siguiente en turno
+    Shared,
+    /// Buzón de una instancia: solo esa réplica, pida lo que pida el sobre
+    Instance(usize),
+    /// Buzón del grupo: todas las réplicas activas
+    All,
+}
+
+/// Suscripciones a `<núcleo>.commands` y a los buzones, y la tarea que las despacha
+pub struct CommandDispatcher {
+    subjects: Vec<String>,
+    task: JoinHandle<()>,
+}
+
+impl CommandDispatcher {
+    /// Suscribirse a los temas de comandos y a los buzones de `core_types`
+    pub async fn start(manager: &Arc<NanoCoreManager>, core_types: Vec<NanoCoreType>) -> anyhow::Result<Self> {
+        let (tx, mut rx) = mpsc::channel::<(NanoCoreType, Delivery, Vec<u8>)>(COMMAND_QUEUE_CAPACITY);
+        let mut subjects = Vec::new();
+
+        for core_type in core_types {
+            let subject = core_type.command_subject();
+            manager.cognitive_fabric
+                .subscribe(&subject, {
+                    let (tx, core_type, subject) = (tx.clone(), core_type.clone(), subject.clone());
+                    move |data| {
+                        if tx.try_send((core_type.clone(), Delivery::Shared, data.to_vec())).is_err() {
+                            warn!("⚠️  Cola de comandos llena: comando descartado en {}", subject);
+                        }
+                    }
+                })
+                .await?;
+            subjects.push(subject);
+
+            // El payload de un evento dirigido es un `CommandEnvelope`
+            let inbox = inbox_subject(&core_type);
+            manager.cognitive_fabric
+                .subscribe_events(&inbox, {
+                    let (tx, inbox) = (tx.clone(), inbox.clone());
+                    move |event| {
+                        let delivery = inbox_delivery(event.target.as_deref());
+                        if tx.try_send((core_type.clone(), delivery, event.payload)).is_err() {
+                            warn!("⚠️  Cola de comandos llena: evento {} descartado en {}", event.id, inbox);
+                        }
+                    }
+                })
+                .await?;
+            subjects.push(inbox);
+        }
+
+        let manager: Weak<NanoCoreManager> = Arc::downgrade(manager);
+        let task = tokio::spawn(async move {
+            let next_instance = Arc::new(AtomicUsize::new(0));
+            while let Some((core_type, delivery, data)) = rx.recv().await {
+                let Some(manager) = manager.upgrade() else { break };
+                let next_instance = next_instance.clone();
+                tokio::spawn(async move {
+                    dispatch(&manager, core_type, delivery, &data, &next_instance).await;
+                });
+            }
+        });
+
+        info!("📬 Despachador de comandos escuchando en {}", subjects.join(", "));
+        Ok(Self { subjects, task })
+    }
+
+    pub async fn stop(self, manager: &NanoCoreManager) {
+        for subject in &self.subjects {
+            if let Err(e) = manager.cognitive_fabric.unsubscribe(subject).await {
+                warn!("⚠️  Error cancelando la suscripción a {}: {}", subject, e);
+            }
+        }
+        self.task.abort();
+    }
+}
+
+/// Buzones de las instancias y del grupo de `core_type`
+fn inbox_subject(core_type: &NanoCoreType) -> String {
+    router::inbox_wildcard(&core_type.target_name())
+}
+
+/// Réplicas a las que se entrega un evento dirigido según su `target`
+fn inbox_delivery(target: Option<&str>) -> Delivery {
+    match target.map(router::parse_target) {
+        Some((_, Some(instance))) => Delivery::Instance(instance),
+        _ => Delivery::All,
+    }
+}
+
+/// Réplicas pedidas para un comando; `None` deja elegir a `select_instance`
+fn requested_instances(delivery: Delivery, envelope_instance: Option<usize>, available: Vec<usize>) -> Vec<Option<usize>> {
+    match delivery {
+        Delivery::Shared => vec![envelope_instance],
+        Delivery::Instance(instance) => vec![Some(instance)],
+        // Sin réplicas activas `select_instance` responde `Unavailable`
+        Delivery::All if available.is_empty() => vec![None],
+        Delivery::All => available.into_iter().map(Some).collect(),
+    }
+}
+
+/// Ejecutar un comando recibido y publicar la respuesta de cada réplica
+async fn dispatch(manager: &NanoCoreManager, core_type: NanoCoreType, delivery: Delivery, data: &[u8], next_instance: &AtomicUsize) {
+    let envelope: CommandEnvelope = match serde_json::from_slice(data) {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            // Responder al emisor aunque el resto del sobre sea inválido
+            let raw: Value = serde_json::from_slice(data).unwrap_or_default();
+            let field = |name: &str| raw.get(name).and_then(Value::as_str).map(str::to_string);
+            let reply = CommandReply {
+                correlation_id: field("correlation_id"),
+                command: field("command").unwrap_or_default(),
+                instance: None,
+                result: None,
+                error: Some(CommandError::new(CommandErrorCode::InvalidEnvelope, e.to_string())),
+            };
+            publish_reply(manager, &core_type, field("reply_to").as_deref(), &reply).await;
+            return;
+        }
+    };
+
+    let available = match delivery {
+        Delivery::All => manager.available_instances(&core_type).await,
+        _ => Vec::new(),
+    };
+    for requested in requested_instances(delivery, envelope.instance, available) {
+        dispatch_to(manager, &core_type, &envelope, requested, next_instance).await;
+    }
+}
+
+/// Ejecutar el comando en la réplica pedida (o la siguiente en turno) y responder
+async fn dispatch_to(
+    manager: &NanoCoreManager,
+    core_type: &NanoCoreType,
+    envelope: &CommandEnvelope,
+    requested: Option<usize>,
+    next_instance: &AtomicUsize,
+) {
+    let (instance, outcome) = match select_instance(manager, core_type, requested, next_instance).await {
+        Ok(instance) => (Some(instance), execute(manager, core_type, instance, envelope).await),
+        Err(e) => (None, Err(e)),
+    };
+
+    let (result, error) = match outcome {
+        Ok(result) => (Some(result), None),
+        Err(error) => {
+            debug!("📭 Comando {} en {} no ejecutado: {:?}", envelope.command, core_type, error);
+            (None, Some(error))
+        }
+    };
+    let reply = CommandReply {
+        correlation_id: envelope.correlation_id.clone(),
+        command: envelope.command.clone(),
+        instance,
+        result,
+        error,
+    };
+    publish_reply(manager, core_type, envelope.reply_to.as_deref(), &reply).await;
+}
+
+/// Réplica destino: la pedida o la siguiente activa en turno rotatorio
+async fn select_instance(
+    manager: &NanoCoreManager,
+    core_type: &NanoCoreType,
+    requested: Option<usize>,
+    next_instance: &AtomicUsize,
+) -> Result<usize, CommandError> {
+    let available = manager.available_instances(core_type).await;
+    match requested {
+        Some(instance) if available.contains(&instance) => Ok(instance),
+        Some(instance) => Err(CommandError::new(
+            CommandErrorCode::Unavailable,
+            format!("{} instancia {} no disponible", core_type, instance),
+        )),
+        None if available.is_empty() => Err(CommandError::new(
+            CommandErrorCode::Unavailable,
+            format!("Sin instancias activas de {}", core_type),
+        )),
+        None => Ok(available[next_instance.fetch_add(1, Ordering::Relaxed) % available.len()]),
+    }
+}
+
+async fn execute(
+    manager: &NanoCoreManager,
+    core_type: &NanoCoreType,
+    instance: usize,
+    envelope: &CommandEnvelope,
+) -> Result<Value, CommandError> {
+    let (context, ephemeral) = authenticate(manager, &envelope.credentials).await?;
+    let payload = serde_json::to_vec(&envelope.payload)
+        .map_err(|e| CommandError::new(CommandErrorCode::InvalidEnvelope, e.to_string()))?;
+
+    let result = manager
+        .execute_command(core_type.clone(), instance, &context, &envelope.command, &payload)
+        .await;
+    if ephemeral {
+        if let Err(e) = manager.security_manager.close_session(context.session_id).await {
+            warn!("⚠️  Error cerrando la sesión del comando {}: {}", envelope.command, e);
+        }
+    }
+
+    match result {
+        Ok(response) => Ok(serde_json::from_slice(&response).unwrap_or_else(|_| {
+            Value::String(base64::engine::general_purpose::STANDARD.encode(&response))
+        })),
+        Err(e) if e.downcast_ref::<CommandDenied>().is_some() => {
+            Err(CommandError::new(CommandErrorCode::Unauthorized, e.to_string()))
+        }
+        Err(e) => {
+            let code = match e.downcast_ref::<NanoCoreError>() {
+                Some(NanoCoreError::SecurityDenied(_)) => CommandErrorCode::Unauthorized,
+                Some(NanoCoreError::Backpressure(_)) => CommandErrorCode::Backpressure,
+                _ => CommandErrorCode::Failed,
+            };
+            Err(CommandError::new(code, e.to_string()))
+        }
+    }
+}
+
+/// Contexto de seguridad del emisor y si se abrió solo para este comando
+async fn authenticate(manager: &NanoCoreManager, credentials: &CommandCredentials) -> Result<(SecurityContext, bool), CommandError> {
+    match credentials {
+        CommandCredentials::Session(session_id) => manager.security_manager
+            .get_session(*session_id)
+            .await
+            .map(|context| (context, false))
+            .ok_or_else(|| CommandError::new(CommandErrorCode::Unauthenticated, format!("Sesión no válida: {}", session_id))),
+        CommandCredentials::Token(jwt) => manager.security_manager
+            .authenticate_token(jwt)
+            .await
+            .map(|context| (context, true))
+            .map_err(|e| CommandError::new(CommandErrorCode::Unauthenticated, e.to_string())),
+    }
+}
+
+async fn publish_reply(manager: &NanoCoreManager, core_type: &NanoCoreType, reply_to: Option<&str>, reply: &CommandReply) {
+    let Some(reply_to) = reply_to else {
+        if let Some(error) = &reply.error {
+            warn!("⚠️  Comando {} en {} sin reply_to falló: {}", reply.command, core_type, error.message);
+        }
+        return;
+    };
+
+    let result = match serde_json::to_vec(reply) {
+        Ok(data) => manager.cognitive_fabric.publish(reply_to, &data).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        warn!("⚠️  Error respondiendo a {} en {}: {}", reply.command, reply_to, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::subject_matches;
+
+    #[test]
+    fn test_envelope_and_reply_format() {
+        let session = Uuid::new_v4();
+        let envelope: CommandEnvelope = serde_json::from_value(serde_json::json!({
+            "command": "kill_process",
+            "payload": { "KillProcess": 42 },
+            "reply_to": "_INBOX.cli.1",
+            "credentials": { "session": session },
+        }))
+        .unwrap();
+        assert_eq!(envelope.credentials, CommandCredentials::Session(session));
+        assert_eq!(envelope.instance, None);
+
+        let token: CommandEnvelope = serde_json::from_value(serde_json::json!({
+            "command": "status",
+            "credentials": { "token": "eyJ..." },
+            "instance": 2,
+        }))
+        .unwrap();
+        assert_eq!(token.payload, Value::Null);
+        assert_eq!(token.instance, Some(2));
+
+        let reply = CommandReply {
+            correlation_id: None,
+            command: "kill_process".to_string(),
+            instance: Some(1),
+            result: None,
+            error: Some(CommandError::new(CommandErrorCode::Unauthorized, "requiere nano_core.os.process.kill")),
+        };
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            serde_json::json!({
+                "command": "kill_process",
+                "instance": 1,
+                "error": { "code": "unauthorized", "message": "requiere nano_core.os.process.kill" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_inbox_event_reaches_targeted_instance() {
+        let target = router::target_name(&NanoCoreType::OS.target_name(), 0);
+        assert_eq!(target, "OSCore-0");
+
+        // `publish_event` lo entrega en el buzón de la instancia, que escucha el despachador de OSCore
+        let subject = router::inbox_for_target(&target);
+        assert!(subject_matches(&inbox_subject(&NanoCoreType::OS), &subject));
+        assert!(!subject_matches(&inbox_subject(&NanoCoreType::Security), &subject));
+        assert!(subject_matches(&inbox_subject(&NanoCoreType::OS), &router::inbox_for_target("OSCore")));
+
+        // Solo se ejecuta en la instancia 0, aunque el sobre pida otra
+        let delivery = inbox_delivery(Some(&target));
+        assert_eq!(delivery, Delivery::Instance(0));
+        assert_eq!(requested_instances(delivery, Some(2), vec![0, 1, 2]), vec![Some(0)]);
+
+        // El grupo va a todas las activas; `<núcleo>.commands` respeta el sobre
+        assert_eq!(inbox_delivery(Some("OSCore-*")), Delivery::All);
+        assert_eq!(inbox_delivery(None), Delivery::All);
+        assert_eq!(requested_instances(Delivery::All, None, vec![0, 2]), vec![Some(0), Some(2)]);
+        assert_eq!(requested_instances(Delivery::All, None, Vec::new()), vec![None]);
+        assert_eq!(requested_instances(Delivery::Shared, Some(1), vec![0, 1]), vec![Some(1)]);
+    }
+}
@@ -0,0 +1,757 @@
+//! Salud de los resolvers DNS de NetworkCore
+//!
+//! Cada `interval_secs` se resuelven los `probe_names` contra cada resolver
+//! (los configurados o los del sistema) con consultas UDP propias, sin pasar
+//! por la caché del sistema. Se sigue la latencia, los fallos y las anomalías
+//! NXDOMAIN —un nombre de sondeo que deja de existir o un nombre inventado que
+//! sí resuelve— y cada cambio de estado de un resolver se publica en
+//! `network.alerts`. Las últimas respuestas quedan en caché según su TTL.
+//! Solo se sondean resolvers de familias habilitadas, los preferidos primero,
+//! y con IPv6 preferido los sondeos piden registros AAAA.
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::net::UdpSocket;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::communication::CognitiveFabric;
+use crate::nano_cores::ip_family::{self, AddressFamilyConfig, IpFamily};
+use crate::nano_cores::netinfo;
+
+/// Sección `[nano_cores.network_core.dns_monitor]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DnsMonitorConfig {
+    pub enabled: bool,
+    /// Vacío: los resolvers del sistema
+    pub resolvers: Vec<IpAddr>,
+    pub probe_names: Vec<String>,
+    pub interval_secs: u64,
+    pub timeout_ms: u64,
+    /// Latencia media a partir de la que el resolver se considera degradado
+    pub latency_threshold_ms: f64,
+    /// Fallos consecutivos para darlo por caído
+    pub failure_threshold: u32,
+    /// Consultar también un nombre inexistente para detectar secuestro de NXDOMAIN
+    pub detect_nxdomain_hijack: bool,
+    /// Tope del TTL con el que se guardan las respuestas
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for DnsMonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolvers: Vec::new(),
+            probe_names: vec!["example.com".to_string()],
+            interval_secs: 60,
+            timeout_ms: 2000,
+            latency_threshold_ms: 500.0,
+            failure_threshold: 3,
+            detect_nxdomain_hijack: true,
+            cache_ttl_secs: 300,
+        }
+    }
+}
+
+/// Muestras recientes con las que se calcula el estado
+const WINDOW: usize = 20;
+/// Proporción de fallos en la ventana que degrada el resolver
+const FAILURE_RATIO: f64 = 0.25;
+const DNS_PORT: u16 = 53;
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+
+/// Código de respuesta DNS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rcode {
+    NoError,
+    ServFail,
+    NxDomain,
+    Refused,
+    Other(u8),
+}
+
+impl From<u8> for Rcode {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => Rcode::NoError,
+            2 => Rcode::ServFail,
+            3 => Rcode::NxDomain,
+            5 => Rcode::Refused,
+            other => Rcode::Other(other),
+        }
+    }
+}
+
+/// Respuesta de un resolver a una consulta
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsAnswer {
+    pub rcode: Rcode,
+    pub addresses: Vec<IpAddr>,
+    /// Menor TTL de las respuestas
+    pub ttl: Option<u32>,
+    pub truncated: bool,
+}
+
+/// Consulta recursiva de un nombre con un único registro de pregunta
+fn build_query(id: u16, name: &str, qtype: u16) -> Result<Vec<u8>> {
+    let name = name.trim_end_matches('.');
+    if name.is_empty() || name.len() > 253 {
+        return Err(anyhow!("Nombre DNS inválido: '{}'", name));
+    }
+    let mut packet = Vec::with_capacity(18 + name.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    // RD: pedir resolución recursiva
+    packet.extend_from_slice(&0x0100u16.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(anyhow!("Etiqueta DNS inválida en '{}'", name));
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&qtype.to_be_bytes());
+    packet.extend_from_slice(&1u16.to_be_bytes());
+    Ok(packet)
+}
+
+/// Saltar un nombre, comprimido o no, y devolver la posición siguiente
+fn skip_name(packet: &[u8], mut pos: usize) -> Result<usize> {
+    loop {
+        let len = *packet.get(pos).ok_or_else(|| anyhow!("Respuesta DNS truncada"))?;
+        match len {
+            0 => return Ok(pos + 1),
+            len if len & 0xC0 == 0xC0 => return Ok(pos + 2),
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Result<u16> {
+    packet
+        .get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| anyhow!("Respuesta DNS truncada"))
+}
+
+fn parse_response(id: u16, packet: &[u8]) -> Result<DnsAnswer> {
+    if read_u16(packet, 0)? != id {
+        return Err(anyhow!("Respuesta DNS con id inesperado"));
+    }
+    let flags = read_u16(packet, 2)?;
+    if flags & 0x8000 == 0 {
+        return Err(anyhow!("Paquete DNS que no es una respuesta"));
+    }
+    let questions = read_u16(packet, 4)?;
+    let answers = read_u16(packet, 6)?;
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(packet, pos)? + 4;
+    }
+    let mut addresses = Vec::new();
+    let mut ttl: Option<u32> = None;
+    for _ in 0..answers {
+        pos = skip_name(packet, pos)?;
+        let rtype = read_u16(packet, pos)?;
+        let record_ttl = u32::from(read_u16(packet, pos + 4)?) << 16 | u32::from(read_u16(packet, pos + 6)?);
+        let length = read_u16(packet, pos + 8)? as usize;
+        let data = packet.get(pos + 10..pos + 10 + length).ok_or_else(|| anyhow!("Respuesta DNS truncada"))?;
+        match (rtype, length) {
+            (TYPE_A, 4) => addresses.push(IpAddr::from(<[u8; 4]>::try_from(data)?)),
+            (TYPE_AAAA, 16) => addresses.push(IpAddr::from(<[u8; 16]>::try_from(data)?)),
+            _ => {}
+        }
+        ttl = Some(ttl.map_or(record_ttl, |t| t.min(record_ttl)));
+        pos += 10 + length;
+    }
+
+    Ok(DnsAnswer {
+        rcode: Rcode::from((flags & 0x000F) as u8),
+        addresses,
+        ttl,
+        truncated: flags & 0x0200 != 0,
+    })
+}
+
+/// Resolver `name` directamente contra `resolver` y medir el tiempo de respuesta
+pub async fn query(resolver: SocketAddr, name: &str, ipv6: bool, timeout: Duration) -> Result<(DnsAnswer, Duration)> {
+    let id = (Uuid::new_v4().as_u128() & 0xFFFF) as u16;
+    let packet = build_query(id, name, if ipv6 { TYPE_AAAA } else { TYPE_A })?;
+    let local: IpAddr = if resolver.is_ipv4() { Ipv4Addr::UNSPECIFIED.into() } else { Ipv6Addr::UNSPECIFIED.into() };
+    let socket = UdpSocket::bind((local, 0)).await?;
+    socket.connect(resolver).await?;
+
+    let start = Instant::now();
+    socket.send(&packet).await?;
+    let mut buffer = vec![0u8; 4096];
+    tokio::time::timeout(timeout, async {
+        loop {
+            let len = socket.recv(&mut buffer).await?;
+            // Respuestas tardías de otra consulta se descartan
+            if let Ok(answer) = parse_response(id, &buffer[..len]) {
+                return Ok((answer, start.elapsed()));
+            }
+        }
+    })
+    .await
+    .map_err(|_| anyhow!("Sin respuesta de {} en {} ms", resolver, timeout.as_millis()))?
+}
+
+/// Estado de un resolver
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResolverStatus {
+    #[default]
+    Healthy,
+    Degraded,
+    Down,
+}
+
+/// Resumen de un resolver para comandos y alertas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolverHealth {
+    pub resolver: IpAddr,
+    pub status: ResolverStatus,
+    /// Motivo del estado si no está sano
+    pub reason: Option<String>,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    pub failure_rate: f64,
+    pub consecutive_failures: u32,
+    pub total_queries: u64,
+    pub total_failures: u64,
+    /// Nombres de sondeo que respondieron NXDOMAIN
+    pub unexpected_nxdomain: u64,
+    pub nxdomain_hijack: bool,
+    pub last_error: Option<String>,
+    pub last_checked: Option<SystemTime>,
+}
+
+/// Resultado de una consulta de sondeo
+#[derive(Debug, Clone)]
+pub enum ProbeOutcome {
+    Answered { latency: Duration, answer: DnsAnswer },
+    Failed(String),
+}
+
+#[derive(Debug, Default)]
+struct ResolverState {
+    /// Latencia de cada muestra; `None` si falló
+    window: VecDeque<Option<Duration>>,
+    consecutive_failures: u32,
+    total_queries: u64,
+    total_failures: u64,
+    unexpected_nxdomain: u64,
+    /// NXDOMAIN en la última ronda
+    nxdomain_anomaly: Option<String>,
+    nxdomain_hijack: bool,
+    status: ResolverStatus,
+    reason: Option<String>,
+    last_error: Option<String>,
+    last_checked: Option<SystemTime>,
+}
+
+impl ResolverState {
+    fn push(&mut self, latency: Option<Duration>) {
+        if self.window.len() == WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(latency);
+        self.total_queries += 1;
+        match latency {
+            Some(_) => self.consecutive_failures = 0,
+            None => {
+                self.consecutive_failures += 1;
+                self.total_failures += 1;
+            }
+        }
+    }
+
+    /// Registrar la respuesta a un nombre de sondeo
+    fn record_probe(&mut self, name: &str, outcome: &ProbeOutcome) {
+        match outcome {
+            ProbeOutcome::Answered { latency, answer } => match answer.rcode {
+                Rcode::NoError => self.push(Some(*latency)),
+                Rcode::NxDomain => {
+                    self.push(Some(*latency));
+                    self.unexpected_nxdomain += 1;
+                    self.nxdomain_anomaly = Some(format!("NXDOMAIN para {}", name));
+                }
+                rcode => {
+                    self.push(None);
+                    self.last_error = Some(format!("{:?} para {}", rcode, name));
+                }
+            },
+            ProbeOutcome::Failed(error) => {
+                self.push(None);
+                self.last_error = Some(error.clone());
+            }
+        }
+    }
+
+    /// Registrar la respuesta al nombre inventado; solo cuenta si devuelve direcciones
+    fn record_hijack_probe(&mut self, outcome: &ProbeOutcome) {
+        if let ProbeOutcome::Answered { answer, .. } = outcome {
+            self.nxdomain_hijack = answer.rcode == Rcode::NoError && !answer.addresses.is_empty();
+        }
+    }
+
+    fn latencies(&self) -> impl Iterator<Item = f64> + '_ {
+        self.window.iter().flatten().map(|latency| latency.as_secs_f64() * 1000.0)
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.window.is_empty() {
+            return 0.0;
+        }
+        self.window.iter().filter(|sample| sample.is_none()).count() as f64 / self.window.len() as f64
+    }
+
+    fn avg_latency_ms(&self) -> Option<f64> {
+        let (sum, count) = self.latencies().fold((0.0, 0), |(sum, count), ms| (sum + ms, count + 1));
+        (count > 0).then(|| sum / count as f64)
+    }
+
+    /// Recalcular el estado al final de una ronda; `Some` con el anterior si cambió
+    fn evaluate(&mut self, config: &DnsMonitorConfig) -> Option<ResolverStatus> {
+        let (status, reason) = if self.consecutive_failures >= config.failure_threshold.max(1) {
+            (ResolverStatus::Down, Some(format!("{} fallos consecutivos", self.consecutive_failures)))
+        } else if self.failure_rate() > FAILURE_RATIO {
+            (ResolverStatus::Degraded, Some(format!("{:.0}% de consultas fallidas", self.failure_rate() * 100.0)))
+        } else if let Some(avg) = self.avg_latency_ms().filter(|avg| *avg > config.latency_threshold_ms) {
+            (ResolverStatus::Degraded, Some(format!("latencia media {:.0} ms", avg)))
+        } else if let Some(anomaly) = self.nxdomain_anomaly.take() {
+            (ResolverStatus::Degraded, Some(anomaly))
+        } else if self.nxdomain_hijack {
+            (ResolverStatus::Degraded, Some("resuelve nombres inexistentes".to_string()))
+        } else {
+            (ResolverStatus::Healthy, None)
+        };
+        self.reason = reason;
+        let previous = std::mem::replace(&mut self.status, status);
+        (previous != status).then_some(previous)
+    }
+
+    fn health(&self, resolver: IpAddr) -> ResolverHealth {
+        ResolverHealth {
+            resolver,
+            status: self.status,
+            reason: self.reason.clone(),
+            avg_latency_ms: self.avg_latency_ms(),
+            max_latency_ms: self.latencies().reduce(f64::max),
+            failure_rate: self.failure_rate(),
+            consecutive_failures: self.consecutive_failures,
+            total_queries: self.total_queries,
+            total_failures: self.total_failures,
+            unexpected_nxdomain: self.unexpected_nxdomain,
+            nxdomain_hijack: self.nxdomain_hijack,
+            last_error: self.last_error.clone(),
+            last_checked: self.last_checked,
+        }
+    }
+}
+
+/// Última resolución válida de un nombre de sondeo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResolution {
+    pub name: String,
+    pub addresses: Vec<IpAddr>,
+    pub resolver: IpAddr,
+    pub latency_ms: f64,
+    pub resolved_at: SystemTime,
+    pub expires_at: SystemTime,
+}
+
+/// Estado de todos los resolvers y la caché
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsHealth {
+    pub resolvers: Vec<ResolverHealth>,
+    pub cache: Vec<CachedResolution>,
+}
+
+/// Resultado de `BenchmarkDns` para un resolver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolverBenchmark {
+    pub resolver: IpAddr,
+    pub queries: u32,
+    pub failures: u32,
+    pub min_latency_ms: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    resolvers: HashMap<IpAddr, ResolverState>,
+    cache: HashMap<String, CachedResolution>,
+}
+
+/// Monitor periódico de resolvers DNS
+pub struct DnsMonitor {
+    config: Arc<RwLock<DnsMonitorConfig>>,
+    families: Arc<RwLock<AddressFamilyConfig>>,
+    state: Arc<RwLock<MonitorState>>,
+    is_running: Arc<RwLock<bool>>,
+}
+
+/// Resolvers a sondear; los del sistema conservan su zona, los configurados de enlace local no la tienen y se omiten
+async fn resolvers(config: &DnsMonitorConfig, families: &AddressFamilyConfig) -> Result<Vec<SocketAddr>> {
+    let resolvers = if config.resolvers.is_empty() {
+        tokio::task::spawn_blocking(netinfo::dns_resolvers).await??
+    } else {
+        config
+            .resolvers
+            .iter()
+            .filter(|ip| !ip_family::is_link_local(ip))
+            .map(|ip| SocketAddr::new(*ip, DNS_PORT))
+            .collect()
+    };
+    Ok(families.order(resolvers, |resolver| resolver.ip()))
+}
+
+async fn probe(resolver: SocketAddr, name: &str, ipv6: bool, timeout: Duration) -> ProbeOutcome {
+    match query(resolver, name, ipv6, timeout).await {
+        Ok((answer, latency)) => ProbeOutcome::Answered { latency, answer },
+        Err(e) => ProbeOutcome::Failed(e.to_string()),
+    }
+}
+
+impl DnsMonitor {
+    pub fn new(config: DnsMonitorConfig, families: AddressFamilyConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            families: Arc::new(RwLock::new(families)),
+            state: Arc::default(),
+            is_running: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    pub async fn reconfigure(&self, config: DnsMonitorConfig, families: AddressFamilyConfig) {
+        *self.config.write().await = config;
+        *self.families.write().await = families;
+    }
+
+    pub async fn start(&self, fabric: Arc<CognitiveFabric>) -> Result<()> {
+        *self.is_running.write().await = true;
+
+        let config = self.config.clone();
+        let families = self.families.clone();
+        let state = self.state.clone();
+        let is_running = self.is_running.clone();
+
+        tokio::spawn(async move {
+            while *is_running.read().await {
+                let current = config.read().await.clone();
+                if current.enabled {
+                    let families = families.read().await.clone();
+                    if let Err(e) = Self::round(&current, &families, &state, &fabric).await {
+                        warn!("⚠️  Error sondeando resolvers DNS: {}", e);
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(current.interval_secs.max(1))).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        *self.is_running.write().await = false;
+        Ok(())
+    }
+
+    /// Sondear todos los resolvers en paralelo y publicar los cambios de estado
+    async fn round(
+        config: &DnsMonitorConfig,
+        families: &AddressFamilyConfig,
+        state: &RwLock<MonitorState>,
+        fabric: &CognitiveFabric,
+    ) -> Result<()> {
+        let resolvers = resolvers(config, families).await?;
+        let ipv6 = families.preferred() == Some(IpFamily::V6);
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let hijack_name = config
+            .probe_names
+            .first()
+            .filter(|_| config.detect_nxdomain_hijack)
+            .map(|name| format!("saai-nx-{}.{}", Uuid::new_v4().simple(), name.trim_end_matches('.')));
+
+        let rounds = resolvers.iter().map(|resolver| {
+            let hijack_name = hijack_name.clone();
+            async move {
+                let mut outcomes = Vec::new();
+                for name in &config.probe_names {
+                    outcomes.push((name.clone(), probe(*resolver, name, ipv6, timeout).await));
+                }
+                let hijack = match hijack_name {
+                    Some(name) => Some(probe(*resolver, &name, ipv6, timeout).await),
+                    None => None,
+                };
+                (resolver.ip(), outcomes, hijack)
+            }
+        });
+        let results = futures::future::join_all(rounds).await;
+
+        let now = SystemTime::now();
+        let mut alerts = Vec::new();
+        {
+            let mut state = state.write().await;
+            state.resolvers.retain(|ip, _| resolvers.iter().any(|resolver| resolver.ip() == *ip));
+            state.cache.retain(|_, cached| cached.expires_at > now);
+
+            for (resolver, outcomes, hijack) in results {
+                let resolver_state = state.resolvers.entry(resolver).or_default();
+                for (name, outcome) in &outcomes {
+                    resolver_state.record_probe(name, outcome);
+                }
+                if let Some(outcome) = &hijack {
+                    resolver_state.record_hijack_probe(outcome);
+                }
+                resolver_state.last_checked = Some(now);
+                if let Some(previous) = resolver_state.evaluate(config) {
+                    alerts.push((previous, resolver_state.health(resolver)));
+                }
+
+                // La respuesta más rápida de cada nombre queda en caché
+                for (name, outcome) in outcomes {
+                    let ProbeOutcome::Answered { latency, answer } = outcome else { continue };
+                    if answer.rcode != Rcode::NoError || answer.addresses.is_empty() {
+                        continue;
+                    }
+                    let latency_ms = latency.as_secs_f64() * 1000.0;
+                    let faster = match state.cache.get(&name) {
+                        Some(cached) => cached.resolved_at < now || latency_ms < cached.latency_ms,
+                        None => true,
+                    };
+                    if faster {
+                        let ttl = u64::from(answer.ttl.unwrap_or(0)).min(config.cache_ttl_secs);
+                        state.cache.insert(
+                            name.clone(),
+                            CachedResolution {
+                                name,
+                                addresses: answer.addresses,
+                                resolver,
+                                latency_ms,
+                                resolved_at: now,
+                                expires_at: now + Duration::from_secs(ttl),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        for (previous, health) in alerts {
+            let kind = if health.status == ResolverStatus::Healthy { "dns_recovered" } else { "dns_degraded" };
+            match health.status {
+                ResolverStatus::Healthy => info!("✅ Resolver DNS {} recuperado", health.resolver),
+                _ => warn!(
+                    "🌐 Resolver DNS {} {:?}: {}",
+                    health.resolver,
+                    health.status,
+                    health.reason.as_deref().unwrap_or_default()
+                ),
+            }
+            fabric
+                .publish(
+                    "network.alerts",
+                    &serde_json::to_vec(&serde_json::json!({
+                        "type": kind,
+                        "resolver": health.resolver,
+                        "previous": previous,
+                        "status": health.status,
+                        "reason": health.reason,
+                        "avg_latency_ms": health.avg_latency_ms,
+                        "failure_rate": health.failure_rate,
+                        "timestamp": SystemTime::now()
+                    }))?,
+                )
+                .await?;
+        }
+        debug!("🌐 Sondeo DNS completado contra {} resolvers", resolvers.len());
+        Ok(())
+    }
+
+    pub async fn health(&self) -> DnsHealth {
+        let state = self.state.read().await;
+        let mut resolvers: Vec<ResolverHealth> = state.resolvers.iter().map(|(ip, s)| s.health(*ip)).collect();
+        resolvers.sort_by_key(|health| health.resolver);
+        let mut cache: Vec<CachedResolution> = state.cache.values().cloned().collect();
+        cache.sort_by(|a, b| a.name.cmp(&b.name));
+        DnsHealth { resolvers, cache }
+    }
+
+    /// Resolver `names` `rounds` veces contra cada resolver; los más rápidos primero
+    pub async fn benchmark(&self, names: &[String], rounds: u32) -> Result<Vec<ResolverBenchmark>> {
+        let config = self.config.read().await.clone();
+        let families = self.families.read().await.clone();
+        let names = if names.is_empty() { &config.probe_names } else { names };
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let ipv6 = families.preferred() == Some(IpFamily::V6);
+
+        let runs = resolvers(&config, &families).await?.into_iter().map(|resolver| async move {
+            let mut latencies = Vec::new();
+            let mut failures = 0;
+            for _ in 0..rounds {
+                for name in names {
+                    match probe(resolver, name, ipv6, timeout).await {
+                        ProbeOutcome::Answered { latency, answer } if answer.rcode == Rcode::NoError => {
+                            latencies.push(latency.as_secs_f64() * 1000.0);
+                        }
+                        _ => failures += 1,
+                    }
+                }
+            }
+            latencies.sort_by(f64::total_cmp);
+            let p95 = latencies.get(((latencies.len() as f64 * 0.95).ceil() as usize).saturating_sub(1)).copied();
+            ResolverBenchmark {
+                resolver: resolver.ip(),
+                queries: rounds * names.len() as u32,
+                failures,
+                min_latency_ms: latencies.first().copied(),
+                avg_latency_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<f64>() / latencies.len() as f64),
+                p95_latency_ms: p95,
+            }
+        });
+
+        let mut results = futures::future::join_all(runs).await;
+        results.sort_by(|a, b| {
+            (a.failures, a.avg_latency_ms.unwrap_or(f64::MAX)).partial_cmp(&(b.failures, b.avg_latency_ms.unwrap_or(f64::MAX))).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolver de prueba: `ok.test` → 192.0.2.1, `slow.test` tras 300 ms, el resto NXDOMAIN
+    async fn fake_resolver() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buffer).await {
+                let query = buffer[..len].to_vec();
+                let name_end = skip_name(&query, 12).unwrap();
+                let mut labels = Vec::new();
+                let mut pos = 12;
+                while query[pos] != 0 {
+                    let len = query[pos] as usize;
+                    labels.push(String::from_utf8_lossy(&query[pos + 1..pos + 1 + len]).to_string());
+                    pos += 1 + len;
+                }
+                let name = labels.join(".");
+                let mut reply = query[..name_end + 4].to_vec();
+                reply[2] = 0x81;
+                if name.ends_with("ok.test") || name == "slow.test" {
+                    reply[3] = 0x80;
+                    reply[7] = 1;
+                    reply.extend_from_slice(&[0xC0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 192, 0, 2, 1]);
+                } else {
+                    reply[3] = 0x83;
+                }
+                if name == "slow.test" {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                }
+                socket.send_to(&reply, peer).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_query_packet() {
+        let packet = build_query(0xABCD, "example.com.", TYPE_A).unwrap();
+        assert_eq!(&packet[..4], &[0xAB, 0xCD, 0x01, 0x00]);
+        assert_eq!(&packet[12..], b"\x07example\x03com\x00\x00\x01\x00\x01");
+        assert!(build_query(1, "a..b", TYPE_A).is_err());
+        assert!(build_query(1, &"x".repeat(64), TYPE_A).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_fake_resolver() {
+        let resolver = fake_resolver().await;
+        let timeout = Duration::from_secs(1);
+
+        let (answer, _) = query(resolver, "ok.test", false, timeout).await.unwrap();
+        assert_eq!(answer.rcode, Rcode::NoError);
+        assert_eq!(answer.addresses, ["192.0.2.1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(answer.ttl, Some(60));
+
+        let (answer, _) = query(resolver, "missing.test", false, timeout).await.unwrap();
+        assert_eq!((answer.rcode, answer.addresses.len()), (Rcode::NxDomain, 0));
+
+        let (_, latency) = query(resolver, "slow.test", false, timeout).await.unwrap();
+        assert!(latency >= Duration::from_millis(300));
+        assert!(query(resolver, "slow.test", false, Duration::from_millis(50)).await.is_err());
+    }
+
+    #[test]
+    fn test_resolver_status() {
+        let config = DnsMonitorConfig {
+            latency_threshold_ms: 100.0,
+            failure_threshold: 3,
+            ..Default::default()
+        };
+        let answered = |ms: u64, rcode: Rcode| ProbeOutcome::Answered {
+            latency: Duration::from_millis(ms),
+            answer: DnsAnswer {
+                rcode,
+                addresses: vec!["192.0.2.1".parse().unwrap()],
+                ttl: Some(60),
+                truncated: false,
+            },
+        };
+        let mut state = ResolverState::default();
+
+        state.record_probe("ok.test", &answered(10, Rcode::NoError));
+        assert_eq!(state.evaluate(&config), None);
+
+        // Un NXDOMAIN de un nombre de sondeo degrada solo esa ronda
+        state.record_probe("ok.test", &answered(10, Rcode::NxDomain));
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Healthy));
+        assert_eq!(state.status, ResolverStatus::Degraded);
+        state.record_probe("ok.test", &answered(10, Rcode::NoError));
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Degraded));
+
+        for _ in 0..3 {
+            state.record_probe("ok.test", &ProbeOutcome::Failed("timeout".to_string()));
+        }
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Healthy));
+        assert_eq!((state.status, state.consecutive_failures), (ResolverStatus::Down, 3));
+
+        // Se recupera, pero la ventana aún tiene demasiados fallos
+        state.record_probe("ok.test", &answered(10, Rcode::NoError));
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Down));
+        assert_eq!(state.status, ResolverStatus::Degraded);
+        for _ in 0..12 {
+            state.record_probe("ok.test", &answered(10, Rcode::NoError));
+        }
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Degraded));
+
+        state.record_hijack_probe(&answered(10, Rcode::NoError));
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Healthy));
+        assert_eq!(state.reason.as_deref(), Some("resuelve nombres inexistentes"));
+        state.record_hijack_probe(&answered(10, Rcode::NxDomain));
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Degraded));
+
+        for _ in 0..WINDOW {
+            state.record_probe("ok.test", &answered(400, Rcode::NoError));
+        }
+        assert_eq!(state.evaluate(&config), Some(ResolverStatus::Healthy));
+        assert!(state.reason.as_deref().unwrap().starts_with("latencia media"));
+        assert_eq!(state.health("192.0.2.53".parse().unwrap()).total_failures, 3);
+    }
+}
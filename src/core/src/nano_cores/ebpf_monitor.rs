@@ -0,0 +1,302 @@
+//! Monitor eBPF de procesos y syscalls para OSCore (Linux)
+//!
+//! Carga el objeto compilado desde `src/core/ebpf`, engancha los tracepoints
+//! de exec, exit y `raw_syscalls/sys_enter`, y publica cada evento en
+//! `os.security`. Los procesos fuera de `process_whitelist` se reportan como
+//! violación y, con `enforce_process_whitelist`, se terminan con SIGKILL.
+
+use anyhow::{Result, anyhow};
+use aya::maps::{AsyncPerfEventArray, HashMap as BpfHashMap};
+use aya::programs::TracePoint;
+use aya::util::online_cpus;
+use aya::Bpf;
+use bytes::BytesMut;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::communication::CognitiveFabric;
+use crate::config::OSCoreConfig;
+
+/// Subject de los eventos de seguridad del sistema operativo
+pub const OS_SECURITY_SUBJECT: &str = "os.security";
+
+const EVENT_EXEC: u32 = 0;
+const EVENT_EXIT: u32 = 1;
+const EVENT_SYSCALL: u32 = 2;
+
+/// `comm` del kernel: 16 bytes con el terminador
+const COMM_LEN: usize = 15;
+
+/// Debe coincidir con `ProcessEvent` de `ebpf/src/main.rs`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ProcessEvent {
+    kind: u32,
+    pid: u32,
+    syscall: i64,
+    comm: [u8; 16],
+}
+
+/// Syscalls que se pueden trazar por nombre
+const SYSCALLS: &[(&str, i64)] = &[
+    ("ptrace", libc::SYS_ptrace),
+    ("init_module", libc::SYS_init_module),
+    ("finit_module", libc::SYS_finit_module),
+    ("kexec_load", libc::SYS_kexec_load),
+    ("mount", libc::SYS_mount),
+    ("setuid", libc::SYS_setuid),
+    ("bpf", libc::SYS_bpf),
+    ("unshare", libc::SYS_unshare),
+];
+
+fn syscall_number(name: &str) -> Option<i64> {
+    SYSCALLS.iter().find(|(n, _)| *n == name).map(|(_, nr)| *nr)
+}
+
+fn syscall_name(nr: i64) -> Option<&'static str> {
+    SYSCALLS.iter().find(|(_, n)| *n == nr).map(|(name, _)| *name)
+}
+
+/// Evento publicado en `os.security`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSecurityEvent {
+    /// `exec`, `exit` o `syscall`
+    pub kind: String,
+    pub pid: u32,
+    pub process: String,
+    pub syscall: Option<String>,
+    pub whitelisted: bool,
+    /// El proceso se terminó por estar fuera de la lista blanca
+    pub enforced: bool,
+    pub instance: usize,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Lista blanca vigente, actualizable en caliente
+#[derive(Debug, Clone)]
+struct WhitelistPolicy {
+    processes: Vec<String>,
+    enforce: bool,
+}
+
+impl WhitelistPolicy {
+    fn from_config(config: &OSCoreConfig) -> Self {
+        Self {
+            processes: config.process_whitelist.clone(),
+            enforce: config.enforce_process_whitelist,
+        }
+    }
+
+    /// Una lista vacía permite cualquier proceso; `comm` llega truncado a 15 caracteres
+    fn allows(&self, comm: &str) -> bool {
+        self.processes.is_empty()
+            || self
+                .processes
+                .iter()
+                .any(|p| p == comm || (comm.len() == COMM_LEN && p.starts_with(comm)))
+    }
+}
+
+/// Programas eBPF cargados y tareas lectoras del perf buffer
+pub struct EbpfMonitor {
+    // Los programas se desenganchan al soltar `Bpf`
+    _bpf: Bpf,
+    policy: Arc<RwLock<WhitelistPolicy>>,
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl EbpfMonitor {
+    /// Cargar y enganchar los programas y arrancar un lector por CPU
+    pub async fn start(
+        config: &OSCoreConfig,
+        cognitive_fabric: Arc<CognitiveFabric>,
+        instance_number: usize,
+    ) -> Result<Self> {
+        let mut bpf = Bpf::load_file(&config.ebpf_object)
+            .map_err(|e| anyhow!("Error cargando {}: {}", config.ebpf_object, e))?;
+
+        for (program, category, name) in [
+            ("saai_exec", "sched", "sched_process_exec"),
+            ("saai_exit", "sched", "sched_process_exit"),
+            ("saai_sys_enter", "raw_syscalls", "sys_enter"),
+        ] {
+            let tracepoint: &mut TracePoint = bpf
+                .program_mut(program)
+                .ok_or_else(|| anyhow!("Programa {} no encontrado en {}", program, config.ebpf_object))?
+                .try_into()?;
+            tracepoint.load()?;
+            tracepoint.attach(category, name)?;
+        }
+
+        {
+            let map = bpf
+                .map_mut("WATCHED_SYSCALLS")
+                .ok_or_else(|| anyhow!("Mapa WATCHED_SYSCALLS no encontrado"))?;
+            let mut watched: BpfHashMap<_, u32, u8> = BpfHashMap::try_from(map)?;
+            for name in &config.ebpf_syscalls {
+                match syscall_number(name) {
+                    Some(nr) => watched.insert(nr as u32, 1, 0)?,
+                    None => warn!("⚠️  Syscall desconocida en ebpf_syscalls: {}", name),
+                }
+            }
+        }
+
+        let mut events = AsyncPerfEventArray::try_from(
+            bpf.take_map("EVENTS").ok_or_else(|| anyhow!("Mapa EVENTS no encontrado"))?,
+        )?;
+
+        let policy = Arc::new(RwLock::new(WhitelistPolicy::from_config(config)));
+        let cpus = online_cpus().map_err(|e| anyhow!("Error obteniendo las CPUs: {}", e))?;
+        let mut readers = Vec::with_capacity(cpus.len());
+
+        for cpu in cpus {
+            let mut buffer = events.open(cpu, None)?;
+            let policy = policy.clone();
+            let cognitive_fabric = cognitive_fabric.clone();
+
+            readers.push(tokio::spawn(async move {
+                let mut buffers = (0..16)
+                    .map(|_| BytesMut::with_capacity(std::mem::size_of::<ProcessEvent>()))
+                    .collect::<Vec<_>>();
+
+                loop {
+                    let batch = match buffer.read_events(&mut buffers).await {
+                        Ok(batch) => batch,
+                        Err(e) => {
+                            warn!("⚠️  Error leyendo eventos eBPF de la CPU {}: {}", cpu, e);
+                            return;
+                        }
+                    };
+                    if batch.lost > 0 {
+                        warn!("⚠️  {} eventos eBPF perdidos en la CPU {}", batch.lost, cpu);
+                    }
+
+                    for data in buffers.iter().take(batch.read) {
+                        if data.len() < std::mem::size_of::<ProcessEvent>() {
+                            continue;
+                        }
+                        let event = unsafe { std::ptr::read_unaligned(data.as_ptr() as *const ProcessEvent) };
+                        let policy = policy.read().await.clone();
+                        handle_event(&event, &policy, &cognitive_fabric, instance_number).await;
+                    }
+                }
+            }));
+        }
+
+        info!(
+            "🐝 Monitor eBPF activo ({} syscalls trazadas, lista blanca {})",
+            config.ebpf_syscalls.len(),
+            if config.enforce_process_whitelist { "aplicada" } else { "solo reportada" }
+        );
+
+        Ok(Self {
+            _bpf: bpf,
+            policy,
+            readers,
+        })
+    }
+
+    /// Actualizar la lista blanca; las syscalls trazadas se aplican al reiniciar
+    pub async fn apply_config(&self, config: &OSCoreConfig) {
+        *self.policy.write().await = WhitelistPolicy::from_config(config);
+    }
+
+    pub fn stop(&mut self) {
+        for reader in self.readers.drain(..) {
+            reader.abort();
+        }
+    }
+}
+
+impl Drop for EbpfMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+async fn handle_event(
+    event: &ProcessEvent,
+    policy: &WhitelistPolicy,
+    cognitive_fabric: &CognitiveFabric,
+    instance_number: usize,
+) {
+    let len = event.comm.iter().position(|b| *b == 0).unwrap_or(event.comm.len());
+    let process = String::from_utf8_lossy(&event.comm[..len]).to_string();
+
+    let kind = match event.kind {
+        EVENT_EXEC => "exec",
+        EVENT_EXIT => "exit",
+        EVENT_SYSCALL => "syscall",
+        _ => return,
+    };
+    let syscall = (event.kind == EVENT_SYSCALL)
+        .then(|| syscall_name(event.syscall).map(str::to_string).unwrap_or_else(|| event.syscall.to_string()));
+
+    let whitelisted = policy.allows(&process);
+    let mut enforced = false;
+    if !whitelisted && event.kind != EVENT_EXIT {
+        warn!(
+            "🚨 Proceso fuera de la lista blanca: {} (PID {}, {})",
+            process,
+            event.pid,
+            syscall.as_deref().unwrap_or(kind)
+        );
+        if policy.enforce && event.pid != std::process::id() {
+            use nix::sys::signal::{self, Signal};
+            use nix::unistd::Pid;
+
+            match signal::kill(Pid::from_raw(event.pid as i32), Signal::SIGKILL) {
+                Ok(()) => enforced = true,
+                Err(e) => warn!("⚠️  Error terminando el proceso {}: {}", event.pid, e),
+            }
+        }
+    }
+
+    let security_event = ProcessSecurityEvent {
+        kind: kind.to_string(),
+        pid: event.pid,
+        process,
+        syscall,
+        whitelisted,
+        enforced,
+        instance: instance_number,
+        timestamp: chrono::Utc::now(),
+    };
+
+    match serde_json::to_vec(&security_event) {
+        Ok(data) => {
+            if let Err(e) = cognitive_fabric.publish(OS_SECURITY_SUBJECT, &data).await {
+                debug!("Error publicando evento eBPF: {}", e);
+            }
+        }
+        Err(e) => debug!("Error serializando evento eBPF: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syscall_table_and_whitelist() {
+        assert_eq!(syscall_number("ptrace"), Some(libc::SYS_ptrace));
+        assert_eq!(syscall_name(libc::SYS_mount), Some("mount"));
+        assert_eq!(syscall_number("no_existe"), None);
+
+        let policy = WhitelistPolicy {
+            processes: vec!["saai-core".to_string(), "saai-agents-supervisor".to_string()],
+            enforce: false,
+        };
+        assert!(policy.allows("saai-core"));
+        // `comm` truncado a 15 caracteres
+        assert!(policy.allows("saai-agents-sup"));
+        assert!(!policy.allows("saai-agents"));
+        assert!(!policy.allows("bash"));
+
+        let open = WhitelistPolicy { processes: Vec::new(), enforce: true };
+        assert!(open.allows("bash"));
+    }
+}
@@ -0,0 +1,791 @@
+//! Reglas de firewall de NetworkCore
+//!
+//! Traduce `FirewallRule` a nftables (Linux), pf (macOS) y `netsh advfirewall`
+//! (Windows, sobre WFP). Las reglas propias viven aparte —tabla `inet saai`,
+//! ancla `com.apple/saai` o reglas con nombre `saai:<id>`— y llevan su id en el
+//! comentario, la etiqueta o el nombre, de modo que listar y quitar nunca toca
+//! las reglas del administrador. En dry-run solo se devuelven los comandos.
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::nano_cores::ip_family::IpFamily;
+use crate::nano_cores::NanoCoreError;
+use crate::nano_cores::network_core::{FirewallAction, FirewallDirection, FirewallRule, Protocol};
+
+/// Sección `[nano_cores.network_core.firewall]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FirewallConfig {
+    /// Tabla nftables, ancla pf (`com.apple/<table>`) y prefijo de nombre en Windows
+    pub table: String,
+    /// Tratar todos los cambios como dry-run aunque el comando no lo pida
+    pub dry_run: bool,
+}
+
+impl Default for FirewallConfig {
+    fn default() -> Self {
+        Self {
+            table: "saai".to_string(),
+            dry_run: false,
+        }
+    }
+}
+
+/// Resultado de añadir o quitar una regla
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallChange {
+    pub id: String,
+    pub backend: String,
+    /// Comandos ejecutados, o que se ejecutarían en dry-run
+    pub commands: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// Regla propia instalada, tal como la muestra el backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FirewallEntry {
+    pub id: String,
+    pub backend: String,
+    pub definition: String,
+}
+
+/// Nombres válidos en nft, pf y netsh sin comillas
+pub fn validate_table(table: &str) -> bool {
+    !table.is_empty() && table.len() <= 32 && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn validate_id(id: &str) -> Result<()> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(NanoCoreError::ConfigError(anyhow!("Id de regla de firewall inválido: {}", id)).into())
+    }
+}
+
+fn tag(table: &str, id: &str) -> String {
+    format!("{}:{}", table, id)
+}
+
+/// Transporte al que se traduce `Protocol`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Tcp,
+    Udp,
+    Icmp,
+}
+
+/// Regla con el protocolo resuelto y sus combinaciones ya validadas
+#[derive(Debug)]
+struct Normalized {
+    action: FirewallAction,
+    direction: FirewallDirection,
+    /// Vacío: cualquier protocolo
+    transports: Vec<Transport>,
+    family: Option<IpFamily>,
+    source: Option<IpAddr>,
+    destination: Option<IpAddr>,
+    /// Puerto de destino
+    port: Option<u16>,
+}
+
+impl Normalized {
+    fn from_rule(rule: &FirewallRule) -> Result<Self> {
+        let invalid = |message: &str| -> anyhow::Error { NanoCoreError::ConfigError(anyhow!("{}", message)).into() };
+        let (transports, default_port) = match rule.protocol {
+            None if rule.port.is_some() => (vec![Transport::Tcp, Transport::Udp], None),
+            None => (Vec::new(), None),
+            Some(Protocol::TCP | Protocol::GRPC | Protocol::WebSocket) => (vec![Transport::Tcp], None),
+            Some(Protocol::HTTP) => (vec![Transport::Tcp], Some(80)),
+            Some(Protocol::HTTPS) => (vec![Transport::Tcp], Some(443)),
+            Some(Protocol::UDP) => (vec![Transport::Udp], None),
+            Some(Protocol::ICMP) => (vec![Transport::Icmp], None),
+        };
+        if transports == [Transport::Icmp] && rule.port.is_some() {
+            return Err(invalid("ICMP no tiene puertos"));
+        }
+
+        let mut family = rule.family;
+        for address in rule.source.iter().chain(rule.destination.iter()) {
+            match family {
+                Some(expected) if expected != IpFamily::of(address) => {
+                    return Err(invalid("Origen, destino y familia de la regla no coinciden (IPv4/IPv6)"));
+                }
+                _ => family = Some(IpFamily::of(address)),
+            }
+        }
+
+        Ok(Self {
+            action: rule.action.clone(),
+            direction: rule.direction,
+            transports,
+            family,
+            source: rule.source,
+            destination: rule.destination,
+            port: rule.port.or(default_port),
+        })
+    }
+
+    /// Transportes con ICMP desdoblado por familia cuando hace falta nombrarla
+    fn icmp_families(&self) -> Vec<IpFamily> {
+        match self.family {
+            Some(family) => vec![family],
+            None => vec![IpFamily::V4, IpFamily::V6],
+        }
+    }
+}
+
+/// Regla nftables para la cadena `input` u `output` de la tabla propia
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn nft_rule(table: &str, id: &str, rule: &Normalized) -> String {
+    let chain = match rule.direction {
+        FirewallDirection::Inbound => "input",
+        FirewallDirection::Outbound => "output",
+    };
+    let mut parts = vec![format!("add rule inet {} {}", table, chain)];
+    let ip = |addr: &IpAddr| if addr.is_ipv4() { "ip" } else { "ip6" };
+    if let Some(source) = &rule.source {
+        parts.push(format!("{} saddr {}", ip(source), source));
+    }
+    if let Some(destination) = &rule.destination {
+        parts.push(format!("{} daddr {}", ip(destination), destination));
+    }
+    // Sin direcciones la familia se indica aparte; con ICMP ya la fija el protocolo
+    if rule.source.is_none() && rule.destination.is_none() && !rule.transports.contains(&Transport::Icmp) {
+        match rule.family {
+            Some(IpFamily::V4) => parts.push("meta nfproto ipv4".to_string()),
+            Some(IpFamily::V6) => parts.push("meta nfproto ipv6".to_string()),
+            None => {}
+        }
+    }
+    let protocols: Vec<&str> = rule
+        .transports
+        .iter()
+        .flat_map(|transport| match transport {
+            Transport::Tcp => vec!["tcp"],
+            Transport::Udp => vec!["udp"],
+            Transport::Icmp => rule
+                .icmp_families()
+                .into_iter()
+                .map(|family| if family == IpFamily::V4 { "icmp" } else { "ipv6-icmp" })
+                .collect(),
+        })
+        .collect();
+    match protocols.as_slice() {
+        [] => {}
+        [protocol] => parts.push(format!("meta l4proto {}", protocol)),
+        many => parts.push(format!("meta l4proto {{ {} }}", many.join(", "))),
+    }
+    if let Some(port) = rule.port {
+        parts.push(format!("th dport {}", port));
+    }
+    parts.push(match rule.action {
+        FirewallAction::Allow => "accept".to_string(),
+        FirewallAction::Deny => "drop".to_string(),
+        FirewallAction::Log => format!("log prefix \"{} \"", tag(table, id)),
+    });
+    parts.push(format!("comment \"{}\"", tag(table, id)));
+    parts.join(" ")
+}
+
+/// Declaración idempotente de la tabla propia; prioridad -10 para evaluarse antes que las del sistema
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn nft_table(table: &str) -> String {
+    format!(
+        "table inet {table} {{\n\
+         \tchain input {{ type filter hook input priority -10; policy accept; }}\n\
+         \tchain output {{ type filter hook output priority -10; policy accept; }}\n\
+         }}"
+    )
+}
+
+/// Regla propia en `nft -a list table`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, PartialEq)]
+struct NftRule {
+    id: String,
+    chain: String,
+    handle: u64,
+    definition: String,
+}
+
+/// Reglas con comentario `<table>:<id>` de la salida de `nft -a list table inet <table>`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nft_rules(output: &str, table: &str) -> Vec<NftRule> {
+    let marker = format!("comment \"{}:", table);
+    let mut chain = String::new();
+    let mut rules = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("chain ").and_then(|rest| rest.split_whitespace().next()) {
+            chain = name.to_string();
+            continue;
+        }
+        let Some(start) = line.find(&marker) else { continue };
+        let id: String = line[start + marker.len()..].chars().take_while(|c| *c != '"').collect();
+        let Some((definition, handle)) = line.rsplit_once("# handle ") else { continue };
+        let Ok(handle) = handle.trim().parse() else { continue };
+        rules.push(NftRule {
+            id,
+            chain: chain.clone(),
+            handle,
+            definition: definition.trim().to_string(),
+        });
+    }
+    rules
+}
+
+/// Reglas pf para el ancla propia; ICMP sin familia se desdobla en inet e inet6
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn pf_rules(table: &str, id: &str, rule: &Normalized) -> Vec<String> {
+    let action = match rule.action {
+        FirewallAction::Allow => "pass",
+        FirewallAction::Deny => "block drop",
+        // pf solo registra sobre pass/block; sin `quick` reglas posteriores pueden decidir
+        FirewallAction::Log => "pass",
+    };
+    let direction = match rule.direction {
+        FirewallDirection::Inbound => "in",
+        FirewallDirection::Outbound => "out",
+    };
+    let modifiers = match rule.action {
+        FirewallAction::Log => "log",
+        _ => "quick",
+    };
+
+    let variants: Vec<(Option<IpFamily>, Option<String>)> = match rule.transports.as_slice() {
+        [] => vec![(rule.family, None)],
+        [Transport::Icmp] => rule
+            .icmp_families()
+            .into_iter()
+            .map(|family| (Some(family), Some(if family == IpFamily::V4 { "icmp" } else { "icmp6" }.to_string())))
+            .collect(),
+        [transport] => vec![(rule.family, Some(pf_transport(*transport).to_string()))],
+        many => vec![(rule.family, Some(format!("{{ {} }}", many.iter().map(|t| pf_transport(*t)).collect::<Vec<_>>().join(" "))))],
+    };
+
+    let address = |addr: &Option<IpAddr>| addr.map_or("any".to_string(), |a| a.to_string());
+    variants
+        .into_iter()
+        .map(|(family, protocol)| {
+            let mut parts = vec![action.to_string(), direction.to_string(), modifiers.to_string()];
+            match family {
+                Some(IpFamily::V4) => parts.push("inet".to_string()),
+                Some(IpFamily::V6) => parts.push("inet6".to_string()),
+                None => {}
+            }
+            if let Some(protocol) = protocol {
+                parts.push(format!("proto {}", protocol));
+            }
+            parts.push(format!("from {} to {}", address(&rule.source), address(&rule.destination)));
+            if let Some(port) = rule.port {
+                parts.push(format!("port {}", port));
+            }
+            parts.push(format!("label \"{}\"", tag(table, id)));
+            parts.join(" ")
+        })
+        .collect()
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn pf_transport(transport: Transport) -> &'static str {
+    match transport {
+        Transport::Tcp => "tcp",
+        Transport::Udp => "udp",
+        Transport::Icmp => "icmp",
+    }
+}
+
+/// Id de una regla de `pfctl -sr` con etiqueta `<table>:<id>`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn pf_rule_id(line: &str, table: &str) -> Option<String> {
+    let marker = format!("label \"{}:", table);
+    let start = line.find(&marker)? + marker.len();
+    Some(line[start..].chars().take_while(|c| *c != '"').collect())
+}
+
+/// Argumentos de `netsh advfirewall firewall add rule`, uno por combinación de protocolo
+#[cfg_attr(not(windows), allow(dead_code))]
+fn netsh_rules(table: &str, id: &str, rule: &Normalized) -> Result<Vec<Vec<String>>> {
+    let action = match rule.action {
+        FirewallAction::Allow => "allow",
+        FirewallAction::Deny => "block",
+        FirewallAction::Log => {
+            return Err(NanoCoreError::ConfigError(anyhow!("El firewall de Windows no admite reglas que solo registren")).into());
+        }
+    };
+    let (direction, source_key, destination_key, port_key) = match rule.direction {
+        FirewallDirection::Inbound => ("in", "remoteip", "localip", "localport"),
+        FirewallDirection::Outbound => ("out", "localip", "remoteip", "remoteport"),
+    };
+    let protocols: Vec<&str> = match rule.transports.as_slice() {
+        [] => vec!["any"],
+        transports => transports
+            .iter()
+            .flat_map(|transport| match transport {
+                Transport::Tcp => vec!["TCP"],
+                Transport::Udp => vec!["UDP"],
+                Transport::Icmp => rule
+                    .icmp_families()
+                    .into_iter()
+                    .map(|family| if family == IpFamily::V4 { "ICMPv4" } else { "ICMPv6" })
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    Ok(protocols
+        .into_iter()
+        .map(|protocol| {
+            let mut args: Vec<String> = ["advfirewall", "firewall", "add", "rule"].map(String::from).to_vec();
+            args.push(format!("name={}", tag(table, id)));
+            args.push(format!("dir={}", direction));
+            args.push(format!("action={}", action));
+            args.push(format!("protocol={}", protocol));
+            if let Some(source) = rule.source {
+                args.push(format!("{}={}", source_key, source));
+            }
+            if let Some(destination) = rule.destination {
+                args.push(format!("{}={}", destination_key, destination));
+            }
+            // netsh no filtra por familia: se limita el remoto a todo su rango
+            if rule.source.is_none() && rule.destination.is_none() && !protocol.starts_with("ICMP") {
+                match rule.family {
+                    Some(IpFamily::V4) => args.push("remoteip=0.0.0.0-255.255.255.255".to_string()),
+                    Some(IpFamily::V6) => args.push("remoteip=::-ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".to_string()),
+                    None => {}
+                }
+            }
+            if let Some(port) = rule.port {
+                args.push(format!("{}={}", port_key, port));
+            }
+            args
+        })
+        .collect())
+}
+
+/// Reglas propias de `netsh advfirewall firewall show rule name=all`
+///
+/// Cada bloque empieza con la línea del nombre; se busca por valor y no por
+/// clave para no depender del idioma de Windows.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netsh_rules(output: &str, table: &str) -> Vec<(String, String)> {
+    let prefix = format!("{}:", table);
+    let mut rules = Vec::new();
+    for block in output.replace("\r\n", "\n").split("\n\n") {
+        let mut lines = block.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(first) = lines.next() else { continue };
+        let Some((_, name)) = first.split_once(':') else { continue };
+        let Some(id) = name.trim().strip_prefix(&prefix) else { continue };
+        let fields: Vec<String> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| format!("{}: {}", key.trim(), value.trim()))
+            .collect();
+        rules.push((id.to_string(), fields.join("; ")));
+    }
+    rules
+}
+
+/// Programa a ejecutar con su entrada estándar
+#[derive(Debug, Clone)]
+pub(super) struct Invocation {
+    program: &'static str,
+    args: Vec<String>,
+    stdin: Option<String>,
+}
+
+impl Invocation {
+    pub(super) fn new(program: &'static str, args: &[&str]) -> Self {
+        Self {
+            program,
+            args: args.iter().map(|a| a.to_string()).collect(),
+            stdin: None,
+        }
+    }
+
+    fn with_stdin(mut self, stdin: String) -> Self {
+        self.stdin = Some(stdin);
+        self
+    }
+
+    pub(super) fn display(&self) -> String {
+        let mut line = std::iter::once(self.program.to_string()).chain(self.args.iter().cloned()).collect::<Vec<_>>().join(" ");
+        if let Some(stdin) = &self.stdin {
+            line.push_str(" <<EOF\n");
+            line.push_str(stdin);
+            line.push_str("\nEOF");
+        }
+        line
+    }
+
+    /// Ejecutar y devolver stdout; la falta de privilegios se informa como `SecurityDenied`
+    pub(super) async fn run(&self) -> Result<String> {
+        let mut child = Command::new(self.program)
+            .args(&self.args)
+            .stdin(if self.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("No se pudo ejecutar {}: {}", self.program, e))?;
+        if let (Some(input), Some(mut stdin)) = (&self.stdin, child.stdin.take()) {
+            stdin.write_all(input.as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if output.status.success() {
+            return Ok(stdout);
+        }
+
+        // netsh informa los errores por stdout
+        let message = format!("{}{}", String::from_utf8_lossy(&output.stderr).trim(), stdout.trim());
+        let lower = message.to_lowercase();
+        if ["operation not permitted", "permission denied", "elevation", "access is denied"].iter().any(|m| lower.contains(m)) {
+            Err(NanoCoreError::SecurityDenied(anyhow!("{} sin privilegios: {}", self.program, message)).into())
+        } else {
+            Err(anyhow!("{} {} falló: {}", self.program, self.args.join(" "), message))
+        }
+    }
+}
+
+async fn execute(backend: &str, id: String, plan: Vec<Invocation>, dry_run: bool) -> Result<FirewallChange> {
+    if !dry_run {
+        for invocation in &plan {
+            invocation.run().await?;
+        }
+    }
+    Ok(FirewallChange {
+        id,
+        backend: backend.to_string(),
+        commands: plan.iter().map(Invocation::display).collect(),
+        dry_run,
+    })
+}
+
+/// Añadir una regla; devuelve su id para poder quitarla
+pub async fn apply(rule: &FirewallRule, config: &FirewallConfig, dry_run: bool) -> Result<FirewallChange> {
+    let rule = Normalized::from_rule(rule)?;
+    let id = Uuid::new_v4().simple().to_string()[..12].to_string();
+    let dry_run = dry_run || config.dry_run;
+    let plan = platform::plan_apply(&config.table, &id, &rule).await?;
+    let change = execute(platform::BACKEND, id, plan, dry_run).await?;
+    if !dry_run {
+        info!("🧱 Regla de firewall {} aplicada con {}", change.id, change.backend);
+    }
+    Ok(change)
+}
+
+/// Quitar una regla propia por id
+pub async fn remove(id: &str, config: &FirewallConfig, dry_run: bool) -> Result<FirewallChange> {
+    validate_id(id)?;
+    let dry_run = dry_run || config.dry_run;
+    let plan = platform::plan_remove(&config.table, id).await?;
+    let change = execute(platform::BACKEND, id.to_string(), plan, dry_run).await?;
+    if !dry_run {
+        info!("🧱 Regla de firewall {} eliminada de {}", id, change.backend);
+    }
+    Ok(change)
+}
+
+/// Reglas propias instaladas
+pub async fn list(config: &FirewallConfig) -> Result<Vec<FirewallEntry>> {
+    platform::list(&config.table).await
+}
+
+fn not_found(id: &str) -> anyhow::Error {
+    anyhow!("Regla de firewall {} no encontrada", id)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    pub const BACKEND: &str = "nftables";
+
+    /// Sin tabla propia todavía no hay reglas
+    async fn current(table: &str) -> Result<Vec<NftRule>> {
+        match Invocation::new("nft", &["-a", "list", "table", "inet", table]).run().await {
+            Ok(output) => Ok(parse_nft_rules(&output, table)),
+            Err(e) if e.to_string().contains("No such file or directory") => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn plan_apply(table: &str, id: &str, rule: &Normalized) -> Result<Vec<Invocation>> {
+        let script = format!("{}\n{}", nft_table(table), nft_rule(table, id, rule));
+        Ok(vec![Invocation::new("nft", &["-f", "-"]).with_stdin(script)])
+    }
+
+    pub async fn plan_remove(table: &str, id: &str) -> Result<Vec<Invocation>> {
+        let rule = current(table).await?.into_iter().find(|rule| rule.id == id).ok_or_else(|| not_found(id))?;
+        let handle = rule.handle.to_string();
+        Ok(vec![Invocation::new("nft", &["delete", "rule", "inet", table, &rule.chain, "handle", &handle])])
+    }
+
+    pub async fn list(table: &str) -> Result<Vec<FirewallEntry>> {
+        Ok(current(table)
+            .await?
+            .into_iter()
+            .map(|rule| FirewallEntry {
+                id: rule.id,
+                backend: BACKEND.to_string(),
+                definition: format!("{}: {}", rule.chain, rule.definition),
+            })
+            .collect())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub const BACKEND: &str = "pf";
+
+    /// `com.apple/*` ya está enlazado desde el /etc/pf.conf de macOS
+    fn anchor(table: &str) -> String {
+        format!("com.apple/{}", table)
+    }
+
+    /// Reglas actuales del ancla; pfctl las carga siempre como conjunto completo
+    async fn current(table: &str) -> Result<Vec<String>> {
+        let output = Invocation::new("pfctl", &["-a", &anchor(table), "-sr"]).run().await?;
+        Ok(output.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect())
+    }
+
+    fn load(table: &str, rules: Vec<String>) -> Invocation {
+        Invocation::new("pfctl", &["-a", &anchor(table), "-f", "-"]).with_stdin(rules.join("\n") + "\n")
+    }
+
+    pub async fn plan_apply(table: &str, id: &str, rule: &Normalized) -> Result<Vec<Invocation>> {
+        let mut rules = current(table).await?;
+        rules.extend(pf_rules(table, id, rule));
+        Ok(vec![load(table, rules)])
+    }
+
+    pub async fn plan_remove(table: &str, id: &str) -> Result<Vec<Invocation>> {
+        let rules = current(table).await?;
+        let kept: Vec<String> = rules.iter().filter(|line| pf_rule_id(line, table).as_deref() != Some(id)).cloned().collect();
+        if kept.len() == rules.len() {
+            return Err(not_found(id));
+        }
+        Ok(vec![load(table, kept)])
+    }
+
+    pub async fn list(table: &str) -> Result<Vec<FirewallEntry>> {
+        Ok(current(table)
+            .await?
+            .into_iter()
+            .filter_map(|line| {
+                Some(FirewallEntry {
+                    id: pf_rule_id(&line, table)?,
+                    backend: BACKEND.to_string(),
+                    definition: line,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    pub const BACKEND: &str = "netsh";
+
+    async fn current(table: &str) -> Result<Vec<(String, String)>> {
+        let output = Invocation::new("netsh", &["advfirewall", "firewall", "show", "rule", "name=all"]).run().await?;
+        Ok(parse_netsh_rules(&output, table))
+    }
+
+    pub async fn plan_apply(table: &str, id: &str, rule: &Normalized) -> Result<Vec<Invocation>> {
+        Ok(netsh_rules(table, id, rule)?
+            .into_iter()
+            .map(|args| Invocation {
+                program: "netsh",
+                args,
+                stdin: None,
+            })
+            .collect())
+    }
+
+    pub async fn plan_remove(table: &str, id: &str) -> Result<Vec<Invocation>> {
+        if !current(table).await?.iter().any(|(rule, _)| rule == id) {
+            return Err(not_found(id));
+        }
+        // Borra a la vez las reglas TCP y UDP que comparten nombre
+        let name = format!("name={}", tag(table, id));
+        Ok(vec![Invocation::new("netsh", &["advfirewall", "firewall", "delete", "rule", &name])])
+    }
+
+    pub async fn list(table: &str) -> Result<Vec<FirewallEntry>> {
+        Ok(current(table)
+            .await?
+            .into_iter()
+            .map(|(id, definition)| FirewallEntry {
+                id,
+                backend: BACKEND.to_string(),
+                definition,
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub const BACKEND: &str = "none";
+
+    fn unsupported() -> anyhow::Error {
+        anyhow!("Firewall no soportado en esta plataforma")
+    }
+
+    pub async fn plan_apply(_table: &str, _id: &str, _rule: &Normalized) -> Result<Vec<Invocation>> {
+        Err(unsupported())
+    }
+
+    pub async fn plan_remove(_table: &str, _id: &str) -> Result<Vec<Invocation>> {
+        Err(unsupported())
+    }
+
+    pub async fn list(_table: &str) -> Result<Vec<FirewallEntry>> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(action: FirewallAction, protocol: Option<Protocol>, source: Option<&str>, port: Option<u16>) -> Normalized {
+        Normalized::from_rule(&FirewallRule {
+            action,
+            direction: FirewallDirection::Inbound,
+            protocol,
+            family: None,
+            source: source.map(|s| s.parse().unwrap()),
+            destination: None,
+            port,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_translation() {
+        let ssh = rule(FirewallAction::Deny, Some(Protocol::TCP), Some("10.0.0.5"), Some(22));
+        assert_eq!(
+            nft_rule("saai", "ab12", &ssh),
+            "add rule inet saai input ip saddr 10.0.0.5 meta l4proto tcp th dport 22 drop comment \"saai:ab12\""
+        );
+        assert_eq!(
+            pf_rules("saai", "ab12", &ssh),
+            ["block drop in quick inet proto tcp from 10.0.0.5 to any port 22 label \"saai:ab12\""]
+        );
+        assert_eq!(
+            netsh_rules("saai", "ab12", &ssh).unwrap()[0][4..],
+            ["name=saai:ab12", "dir=in", "action=block", "protocol=TCP", "remoteip=10.0.0.5", "localport=22"]
+        );
+
+        // Puerto sin protocolo: TCP y UDP
+        let dns = rule(FirewallAction::Allow, None, None, Some(53));
+        assert!(nft_rule("saai", "1", &dns).contains("meta l4proto { tcp, udp } th dport 53 accept"));
+        assert!(pf_rules("saai", "1", &dns)[0].starts_with("pass in quick proto { tcp udp } from any to any port 53"));
+        assert_eq!(netsh_rules("saai", "1", &dns).unwrap().len(), 2);
+
+        // HTTPS implica TCP/443; ICMP sin familia cubre v4 y v6
+        assert!(nft_rule("saai", "2", &rule(FirewallAction::Log, Some(Protocol::HTTPS), None, None))
+            .ends_with("tcp th dport 443 log prefix \"saai:2 \" comment \"saai:2\""));
+        let icmp = rule(FirewallAction::Deny, Some(Protocol::ICMP), None, None);
+        assert!(nft_rule("saai", "3", &icmp).contains("meta l4proto { icmp, ipv6-icmp } drop"));
+        assert_eq!(pf_rules("saai", "3", &icmp).len(), 2);
+        assert!(netsh_rules("saai", "4", &rule(FirewallAction::Log, None, None, None)).is_err());
+
+        let mixed = FirewallRule {
+            action: FirewallAction::Deny,
+            direction: FirewallDirection::Outbound,
+            protocol: None,
+            family: None,
+            source: Some("10.0.0.1".parse().unwrap()),
+            destination: Some("::1".parse().unwrap()),
+            port: None,
+        };
+        assert!(Normalized::from_rule(&mixed).is_err());
+        assert!(Normalized::from_rule(&FirewallRule { port: Some(1), ..rule_icmp() }).is_err());
+
+        // Familia explícita sin direcciones
+        let ssh_v6 = Normalized::from_rule(&FirewallRule {
+            protocol: Some(Protocol::TCP),
+            family: Some(IpFamily::V6),
+            port: Some(22),
+            ..rule_icmp()
+        })
+        .unwrap();
+        assert!(nft_rule("saai", "5", &ssh_v6).contains("meta nfproto ipv6 meta l4proto tcp th dport 22"));
+        assert!(pf_rules("saai", "5", &ssh_v6)[0].starts_with("block drop in quick inet6 proto tcp"));
+        assert!(netsh_rules("saai", "5", &ssh_v6).unwrap()[0].contains(&"remoteip=::-ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".to_string()));
+        let icmp_v6 = Normalized::from_rule(&FirewallRule { family: Some(IpFamily::V6), ..rule_icmp() }).unwrap();
+        assert!(nft_rule("saai", "6", &icmp_v6).contains("meta l4proto ipv6-icmp drop"));
+        assert!(Normalized::from_rule(&FirewallRule { family: Some(IpFamily::V4), destination: Some("::1".parse().unwrap()), ..rule_icmp() }).is_err());
+    }
+
+    fn rule_icmp() -> FirewallRule {
+        FirewallRule {
+            action: FirewallAction::Deny,
+            direction: FirewallDirection::Inbound,
+            protocol: Some(Protocol::ICMP),
+            family: None,
+            source: None,
+            destination: None,
+            port: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_installed_rules() {
+        let nft = "table inet saai { # handle 7\n\
+                   \tchain input { # handle 1\n\
+                   \t\ttype filter hook input priority -10; policy accept;\n\
+                   \t\tip saddr 10.0.0.5 meta l4proto tcp th dport 22 drop comment \"saai:ab12\" # handle 4\n\
+                   \t\ttcp dport 80 accept # handle 5\n\
+                   \t}\n\
+                   \tchain output { # handle 2\n\
+                   \t\tip daddr 1.1.1.1 drop comment \"saai:cd34\" # handle 6\n\
+                   \t}\n\
+                   }\n";
+        let rules = parse_nft_rules(nft, "saai");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0],
+            NftRule {
+                id: "ab12".to_string(),
+                chain: "input".to_string(),
+                handle: 4,
+                definition: "ip saddr 10.0.0.5 meta l4proto tcp th dport 22 drop comment \"saai:ab12\"".to_string(),
+            }
+        );
+        assert_eq!((rules[1].chain.as_str(), rules[1].handle), ("output", 6));
+
+        assert_eq!(pf_rule_id("block drop in quick inet proto tcp from 10.0.0.5 to any port = 22 label \"saai:ab12\"", "saai").as_deref(), Some("ab12"));
+        assert_eq!(pf_rule_id("pass in all", "saai"), None);
+
+        let netsh = "\r\nRule Name:                            saai:ab12\r\n\
+                     ----------------------------------------------------------------------\r\n\
+                     Enabled:                              Yes\r\n\
+                     Direction:                            In\r\n\
+                     Action:                               Block\r\n\
+                     \r\n\
+                     Nombre de regla:                      Core Networking\r\n\
+                     Habilitada:                           Sí\r\n\
+                     \r\n\
+                     Ok.\r\n";
+        assert_eq!(
+            parse_netsh_rules(netsh, "saai"),
+            [("ab12".to_string(), "Enabled: Yes; Direction: In; Action: Block".to_string())]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Vigilancia de rutas del sistema de archivos para OSCore
+//!
+//! `OSCommand::WatchPath` registra un watcher de notify por ruta; cada
+//! creación, modificación o borrado bajo ella se publica como `FsEvent` en
+//! `os.fs.events`, base de la configuración GitOps y de la monitorización de
+//! integridad.
+
+use anyhow::{Result, anyhow};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::communication::CognitiveFabric;
+
+/// Subject de los eventos del sistema de archivos
+pub const FS_EVENTS_SUBJECT: &str = "os.fs.events";
+
+/// Tipo de cambio observado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FsEventKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+/// Evento publicado en `os.fs.events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FsEvent {
+    pub kind: FsEventKind,
+    pub paths: Vec<String>,
+    /// Ruta vigilada que originó el evento
+    pub watch: String,
+    pub instance: usize,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl FsEvent {
+    /// Traducir un evento de notify; los accesos se ignoran y los cambios de
+    /// permisos cuentan como modificación
+    fn from_notify(event: &Event, watch: &Path, instance: usize) -> Option<Self> {
+        let kind = match event.kind {
+            EventKind::Create(_) => FsEventKind::Create,
+            EventKind::Modify(_) => FsEventKind::Modify,
+            EventKind::Remove(_) => FsEventKind::Delete,
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            paths: event.paths.iter().map(|p| p.display().to_string()).collect(),
+            watch: watch.display().to_string(),
+            instance,
+            timestamp: chrono::Utc::now(),
+        })
+    }
+}
+
+/// Ruta vigilada, devuelta por `ListWatchedPaths`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchedPath {
+    pub path: String,
+    pub recursive: bool,
+}
+
+struct FsWatch {
+    _watcher: RecommendedWatcher,
+    recursive: bool,
+    handle: JoinHandle<()>,
+}
+
+/// Watchers activos de una instancia de OSCore
+#[derive(Default)]
+pub struct FsWatchRegistry {
+    watches: BTreeMap<PathBuf, FsWatch>,
+}
+
+impl FsWatchRegistry {
+    /// Empezar a vigilar una ruta; `false` si ya estaba vigilada
+    pub fn watch(
+        &mut self,
+        path: &str,
+        recursive: bool,
+        cognitive_fabric: Arc<CognitiveFabric>,
+        instance: usize,
+    ) -> Result<bool> {
+        let path = Path::new(path)
+            .canonicalize()
+            .map_err(|e| anyhow!("Ruta {} no vigilable: {}", path, e))?;
+        if self.watches.contains_key(&path) {
+            return Ok(false);
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watch_path = path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                if let Some(event) = FsEvent::from_notify(&event, &watch_path, instance) {
+                    let _ = tx.send(event);
+                }
+            }
+            Err(e) => warn!("⚠️  Error vigilando {}: {}", watch_path.display(), e),
+        })?;
+        let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+        watcher.watch(&path, mode)?;
+
+        let handle = tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                match serde_json::to_vec(&event) {
+                    Ok(data) => {
+                        if let Err(e) = cognitive_fabric.publish(FS_EVENTS_SUBJECT, &data).await {
+                            debug!("Error publicando evento de archivos: {}", e);
+                        }
+                    }
+                    Err(e) => debug!("Error serializando evento de archivos: {}", e),
+                }
+            }
+        });
+
+        info!("👀 Vigilando {} ({})", path.display(), if recursive { "recursivo" } else { "no recursivo" });
+        self.watches.insert(path, FsWatch {
+            _watcher: watcher,
+            recursive,
+            handle,
+        });
+        Ok(true)
+    }
+
+    /// Dejar de vigilar una ruta; `false` si no estaba vigilada
+    pub fn unwatch(&mut self, path: &str) -> bool {
+        let path = Path::new(path).canonicalize().unwrap_or_else(|_| PathBuf::from(path));
+        match self.watches.remove(&path) {
+            Some(watch) => {
+                watch.handle.abort();
+                info!("👀 Vigilancia de {} detenida", path.display());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<WatchedPath> {
+        self.watches
+            .iter()
+            .map(|(path, watch)| WatchedPath {
+                path: path.display().to_string(),
+                recursive: watch.recursive,
+            })
+            .collect()
+    }
+
+    /// Detener todos los watchers
+    pub fn clear(&mut self) {
+        for (_, watch) in std::mem::take(&mut self.watches) {
+            watch.handle.abort();
+        }
+    }
+}
+
+impl Drop for FsWatchRegistry {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, MetadataKind, ModifyKind, RemoveKind};
+
+    #[test]
+    fn test_event_translation() {
+        let watch = Path::new("/etc/saai");
+        let event = |kind| Event::new(kind).add_path(PathBuf::from("/etc/saai/core.toml"));
+
+        let created = FsEvent::from_notify(&event(EventKind::Create(CreateKind::File)), watch, 0).unwrap();
+        assert_eq!(created.kind, FsEventKind::Create);
+        assert_eq!(created.paths, vec!["/etc/saai/core.toml".to_string()]);
+        assert_eq!(created.watch, "/etc/saai");
+
+        let modified = event(EventKind::Modify(ModifyKind::Data(DataChange::Content)));
+        assert_eq!(FsEvent::from_notify(&modified, watch, 0).unwrap().kind, FsEventKind::Modify);
+        let removed = event(EventKind::Remove(RemoveKind::File));
+        assert_eq!(FsEvent::from_notify(&removed, watch, 0).unwrap().kind, FsEventKind::Delete);
+
+        let metadata = event(EventKind::Modify(ModifyKind::Metadata(MetadataKind::Permissions)));
+        assert_eq!(FsEvent::from_notify(&metadata, watch, 0).unwrap().kind, FsEventKind::Modify);
+        assert!(FsEvent::from_notify(&event(EventKind::Access(AccessKind::Any)), watch, 0).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! GPUs para HardwareCore
+//!
+//! NVIDIA a través de NVML (feature `nvml`, carga `libnvidia-ml` en tiempo de
+//! ejecución) y AMD a través de los contadores sysfs del driver amdgpu, los
+//! mismos que lee `rocm-smi`. Sin GPUs o sin drivers la lista queda vacía.
+
+use serde::{Deserialize, Serialize};
+
+/// Fabricante de la GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+}
+
+/// Estado de una GPU
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub name: String,
+    pub vendor: GpuVendor,
+    /// Uso del motor gráfico, en porcentaje
+    pub utilization: Option<f32>,
+    pub memory_used: Option<u64>,
+    pub memory_total: Option<u64>,
+    pub temperature: Option<f32>,
+    pub power_watts: Option<f32>,
+}
+
+impl GpuInfo {
+    pub fn memory_usage_percentage(&self) -> Option<f32> {
+        match (self.memory_used, self.memory_total) {
+            (Some(used), Some(total)) if total > 0 => Some(used as f32 / total as f32 * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Lector de las GPUs disponibles
+pub struct GpuMonitor {
+    #[cfg(feature = "nvml")]
+    nvml: Option<nvml_wrapper::Nvml>,
+    #[cfg(target_os = "linux")]
+    drm_root: std::path::PathBuf,
+}
+
+impl GpuMonitor {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "nvml")]
+            nvml: nvml_wrapper::Nvml::init()
+                .map_err(|e| tracing::debug!("🎮 NVML no disponible: {}", e))
+                .ok(),
+            #[cfg(target_os = "linux")]
+            drm_root: std::path::PathBuf::from("/sys/class/drm"),
+        }
+    }
+
+    /// GPUs de todos los fabricantes soportados
+    pub fn gpus(&self) -> Vec<GpuInfo> {
+        #[allow(unused_mut)]
+        let mut gpus = Vec::new();
+        #[cfg(feature = "nvml")]
+        if let Some(nvml) = &self.nvml {
+            match read_nvml(nvml) {
+                Ok(nvidia) => gpus.extend(nvidia),
+                Err(e) => tracing::debug!("🎮 Error leyendo NVML: {}", e),
+            }
+        }
+        #[cfg(target_os = "linux")]
+        gpus.extend(read_amdgpu(&self.drm_root));
+        gpus
+    }
+}
+
+impl Default for GpuMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nvml")]
+fn read_nvml(nvml: &nvml_wrapper::Nvml) -> Result<Vec<GpuInfo>, nvml_wrapper::error::NvmlError> {
+    use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+
+    let mut gpus = Vec::new();
+    for index in 0..nvml.device_count()? {
+        let device = nvml.device_by_index(index)?;
+        let memory = device.memory_info().ok();
+        gpus.push(GpuInfo {
+            index,
+            name: device.name().unwrap_or_else(|_| format!("NVIDIA GPU {}", index)),
+            vendor: GpuVendor::Nvidia,
+            utilization: device.utilization_rates().ok().map(|rates| rates.gpu as f32),
+            memory_used: memory.as_ref().map(|m| m.used),
+            memory_total: memory.as_ref().map(|m| m.total),
+            temperature: device.temperature(TemperatureSensor::Gpu).ok().map(|t| t as f32),
+            power_watts: device.power_usage().ok().map(|mw| mw as f32 / 1000.0),
+        });
+    }
+    Ok(gpus)
+}
+
+/// `/sys/class/drm/card*/device` con vendor `0x1002`
+#[cfg(target_os = "linux")]
+fn read_amdgpu(drm_root: &std::path::Path) -> Vec<GpuInfo> {
+    use std::fs;
+
+    let read = |path: std::path::PathBuf| fs::read_to_string(path).ok().map(|v| v.trim().to_string());
+    let number = |path: std::path::PathBuf| read(path).and_then(|v| v.parse::<u64>().ok());
+
+    let Ok(entries) = fs::read_dir(drm_root) else {
+        return Vec::new();
+    };
+    // Solo `cardN`, no los conectores `cardN-DP-1`
+    let mut cards: Vec<(u32, std::path::PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let index = entry.file_name().to_str()?.strip_prefix("card")?.parse().ok()?;
+            Some((index, entry.path().join("device")))
+        })
+        .filter(|(_, device)| read(device.join("vendor")).as_deref() == Some("0x1002"))
+        .collect();
+    cards.sort();
+
+    cards
+        .into_iter()
+        .map(|(index, device)| {
+            let hwmon = fs::read_dir(device.join("hwmon"))
+                .ok()
+                .and_then(|mut dirs| dirs.find_map(|dir| dir.ok()))
+                .map(|dir| dir.path());
+            let hwmon_value = |file: &str| hwmon.as_ref().and_then(|dir| number(dir.join(file)));
+
+            GpuInfo {
+                index,
+                name: read(device.join("product_name"))
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| format!("AMD GPU {}", index)),
+                vendor: GpuVendor::Amd,
+                utilization: number(device.join("gpu_busy_percent")).map(|u| u as f32),
+                memory_used: number(device.join("mem_info_vram_used")),
+                memory_total: number(device.join("mem_info_vram_total")),
+                temperature: hwmon_value("temp1_input").map(|t| t as f32 / 1000.0),
+                power_watts: hwmon_value("power1_average")
+                    .or_else(|| hwmon_value("power1_input"))
+                    .map(|uw| uw as f32 / 1_000_000.0),
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amdgpu_sysfs() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("card0/device/vendor", "0x1002\n");
+        write("card0/device/gpu_busy_percent", "37\n");
+        write("card0/device/mem_info_vram_used", "1073741824\n");
+        write("card0/device/mem_info_vram_total", "8589934592\n");
+        write("card0/device/hwmon/hwmon3/temp1_input", "64000\n");
+        write("card0/device/hwmon/hwmon3/power1_average", "45000000\n");
+        write("card0-DP-1/status", "connected\n");
+        write("card1/device/vendor", "0x8086\n");
+
+        let gpus = read_amdgpu(root.path());
+        assert_eq!(gpus.len(), 1);
+        let gpu = &gpus[0];
+        assert_eq!(gpu.name, "AMD GPU 0");
+        assert_eq!(gpu.utilization, Some(37.0));
+        assert_eq!(gpu.temperature, Some(64.0));
+        assert_eq!(gpu.power_watts, Some(45.0));
+        assert_eq!(gpu.memory_usage_percentage(), Some(12.5));
+    }
+}
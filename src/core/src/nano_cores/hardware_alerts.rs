@@ -0,0 +1,115 @@
+//! Histéresis y enfriamiento de las alertas de HardwareCore
+//!
+//! Una alerta se dispara al superar su umbral y no se resuelve hasta bajar
+//! de `umbral - hysteresis`. Mientras sigue activa se repite como mucho una
+//! vez por `cooldown`, y tras resolverse no vuelve a dispararse antes de que
+//! pase ese mismo plazo, para no repetir la alerta en cada ciclo de 5 s.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Cambio de una alerta que hay que publicar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertEvent {
+    /// Disparada por primera vez o repetida tras el enfriamiento
+    Firing,
+    Resolved,
+}
+
+impl AlertEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertEvent::Firing => "firing",
+            AlertEvent::Resolved => "resolved",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AlertState {
+    active: bool,
+    last_fired: Option<Instant>,
+}
+
+/// Estado de las alertas por clave (`cpu_temperature`, `disk:/var`...)
+#[derive(Debug, Default)]
+pub struct AlertGate {
+    hysteresis: f64,
+    cooldown: Duration,
+    alerts: HashMap<String, AlertState>,
+}
+
+impl AlertGate {
+    pub fn new(hysteresis: f64, cooldown: Duration) -> Self {
+        Self {
+            hysteresis: hysteresis.max(0.0),
+            cooldown,
+            alerts: HashMap::new(),
+        }
+    }
+
+    /// Cambiar parámetros conservando qué alertas están activas
+    pub fn configure(&mut self, hysteresis: f64, cooldown: Duration) {
+        self.hysteresis = hysteresis.max(0.0);
+        self.cooldown = cooldown;
+    }
+
+    /// Evaluar un valor contra su umbral; `Some` si hay que publicar
+    pub fn evaluate(&mut self, key: &str, value: f64, threshold: f64, now: Instant) -> Option<AlertEvent> {
+        let state = self.alerts.entry(key.to_string()).or_default();
+        let cooled = match state.last_fired {
+            Some(at) => now.duration_since(at) >= self.cooldown,
+            None => true,
+        };
+
+        if state.active && value < threshold - self.hysteresis {
+            state.active = false;
+            return Some(AlertEvent::Resolved);
+        }
+        if value > threshold && (state.active || cooled) {
+            state.active = true;
+            if cooled {
+                state.last_fired = Some(now);
+                return Some(AlertEvent::Firing);
+            }
+        }
+        None
+    }
+
+    /// Olvidar las claves que ya no existen (discos desmontados)
+    pub fn retain<F: Fn(&str) -> bool>(&mut self, keep: F) {
+        self.alerts.retain(|key, _| keep(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hysteresis_and_cooldown() {
+        let mut gate = AlertGate::new(5.0, Duration::from_secs(300));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(gate.evaluate("cpu", 70.0, 80.0, at(0)), None);
+        assert_eq!(gate.evaluate("cpu", 85.0, 80.0, at(5)), Some(AlertEvent::Firing));
+        // Sigue por encima: silencio hasta que pase el enfriamiento
+        assert_eq!(gate.evaluate("cpu", 86.0, 80.0, at(10)), None);
+        // Dentro de la banda de histéresis no se resuelve
+        assert_eq!(gate.evaluate("cpu", 78.0, 80.0, at(15)), None);
+        assert_eq!(gate.evaluate("cpu", 82.0, 80.0, at(20)), None);
+        assert_eq!(gate.evaluate("cpu", 82.0, 80.0, at(305)), Some(AlertEvent::Firing));
+
+        assert_eq!(gate.evaluate("cpu", 74.0, 80.0, at(310)), Some(AlertEvent::Resolved));
+        assert_eq!(gate.evaluate("cpu", 74.0, 80.0, at(315)), None);
+        // Vuelve a subir antes del enfriamiento: no se repite
+        assert_eq!(gate.evaluate("cpu", 90.0, 80.0, at(320)), None);
+        assert_eq!(gate.evaluate("cpu", 90.0, 80.0, at(605)), Some(AlertEvent::Firing));
+
+        // Las claves son independientes
+        assert_eq!(gate.evaluate("disk:/", 96.0, 95.0, at(320)), Some(AlertEvent::Firing));
+        gate.retain(|key| key != "disk:/");
+        assert_eq!(gate.evaluate("disk:/", 96.0, 95.0, at(321)), Some(AlertEvent::Firing));
+    }
+}
@@ -6,16 +6,26 @@
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use sysinfo::{System, SystemExt, ComponentExt, DiskExt, NetworkExt};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::communication::CognitiveFabric;
+use crate::config::topology::{self, CpuTopology, NumaMemory};
+use crate::config::{CoreConfig, HardwareCoreConfig};
 use crate::metrics::MetricsCollector;
-use crate::nano_cores::{NanoCore, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::nano_cores::hardware_alerts::{AlertEvent, AlertGate};
+use crate::nano_cores::gpu::{GpuInfo, GpuMonitor};
+use crate::nano_cores::prediction::{FailurePredictorConfig, HistoryStore, PredictorSample, Trend};
+use crate::nano_cores::power::{self, PowerProfile};
+use crate::nano_cores::smart::{self, SmartHealth};
+use crate::nano_cores::thermal::{self, FanReading, SensorKind, TemperatureReading, ThermalBackend, ThermalReadings};
+use crate::nano_cores::{CommandAuthorization, CoreResult, NanoCore, NanoCoreError, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::security::SecurityLevel;
 
 /// Información detallada de hardware
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +36,8 @@ pub struct HardwareInfo {
     pub network_info: Vec<NetworkInfo>,
     pub thermal_info: ThermalInfo,
     pub power_info: PowerInfo,
+    #[serde(default)]
+    pub gpu_info: Vec<GpuInfo>,
 }
 
 /// Información de CPU
@@ -38,6 +50,11 @@ pub struct CpuInfo {
     pub average_usage: f32,
     pub temperature: Option<f32>,
     pub load_average: [f64; 3],
+    #[serde(default)]
+    pub topology: CpuTopology,
+    /// Uso medio de las CPUs de cada socket
+    #[serde(default)]
+    pub usage_per_socket: Vec<f32>,
 }
 
 /// Información de memoria
@@ -50,6 +67,8 @@ pub struct MemoryInfo {
     pub swap_used: u64,
     pub usage_percentage: f32,
     pub pressure_score: f32,
+    #[serde(default)]
+    pub numa_nodes: Vec<NumaMemory>,
 }
 
 /// Información de disco
@@ -64,6 +83,9 @@ pub struct DiskInfo {
     pub is_removable: bool,
     pub read_speed: u64,
     pub write_speed: u64,
+    /// Lectura SMART del dispositivo que contiene la partición
+    #[serde(default)]
+    pub smart: Option<SmartHealth>,
 }
 
 /// Información de red
@@ -86,8 +108,41 @@ pub struct ThermalInfo {
     pub cpu_temperature: Option<f32>,
     pub gpu_temperature: Option<f32>,
     pub motherboard_temperature: Option<f32>,
+    /// RPM de cada ventilador, en el orden de `fans`
     pub fan_speeds: Vec<u32>,
     pub thermal_state: ThermalState,
+    #[serde(default)]
+    pub sensors: Vec<TemperatureReading>,
+    #[serde(default)]
+    pub fans: Vec<FanReading>,
+    /// Backend que dio las lecturas (`hwmon`, `smc`, `wmi`, `sysinfo`)
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl ThermalInfo {
+    /// Incorporar las temperaturas de las GPUs y recalcular el estado
+    ///
+    /// Las GPUs sin sensor en el backend térmico (NVIDIA) se añaden a `sensors`.
+    pub fn include_gpus(&mut self, gpus: &[GpuInfo]) {
+        let gpu_sensors = self.sensors.iter().any(|sensor| sensor.kind == SensorKind::Gpu);
+        for gpu in gpus {
+            let Some(temperature) = gpu.temperature else { continue };
+            self.gpu_temperature = Some(self.gpu_temperature.map_or(temperature, |t| t.max(temperature)));
+            if !gpu_sensors {
+                self.sensors.push(TemperatureReading {
+                    label: gpu.name.clone(),
+                    kind: SensorKind::Gpu,
+                    celsius: temperature,
+                });
+            }
+        }
+        self.thermal_state = ThermalState::from_temperatures(&[
+            self.cpu_temperature,
+            self.gpu_temperature,
+            self.motherboard_temperature,
+        ]);
+    }
 }
 
 /// Estado térmico del sistema
@@ -99,6 +154,19 @@ pub enum ThermalState {
     Critical,
 }
 
+impl ThermalState {
+    /// Estado según la temperatura más alta
+    pub fn from_temperatures(temperatures: &[Option<f32>]) -> Self {
+        let max_temp = temperatures.iter().filter_map(|&t| t).fold(0.0f32, f32::max);
+        match max_temp {
+            t if t < 60.0 => ThermalState::Optimal,
+            t if t < 75.0 => ThermalState::Warm,
+            t if t < 85.0 => ThermalState::Hot,
+            _ => ThermalState::Critical,
+        }
+    }
+}
+
 /// Información de energía
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerInfo {
@@ -148,11 +216,31 @@ pub enum HardwareCommand {
     OptimizePerformance,
     SetPowerMode(PowerState),
     GetComponentHealth(String),
+    GetGpuInfo,
+}
+
+impl HardwareCommand {
+    /// Permiso y nivel exigidos para ejecutar el comando
+    pub fn authorization(&self) -> CommandAuthorization {
+        match self {
+            HardwareCommand::GetHardwareInfo
+            | HardwareCommand::GetThermalStatus
+            | HardwareCommand::GetPowerStatus
+            | HardwareCommand::PredictFailures
+            | HardwareCommand::GetComponentHealth(_)
+            | HardwareCommand::GetGpuInfo => {
+                CommandAuthorization::new("nano_core.hardware.status", SecurityLevel::Internal)
+            }
+            HardwareCommand::OptimizePerformance => CommandAuthorization::new("nano_core.hardware.optimize", SecurityLevel::Confidential),
+            HardwareCommand::SetPowerMode(_) => CommandAuthorization::new("nano_core.hardware.power", SecurityLevel::Secret),
+        }
+    }
 }
 
 /// Nano-Core para monitoreo de hardware
 pub struct HardwareCore {
     instance_id: Uuid,
+    config: HardwareCoreConfig,
     cognitive_fabric: Arc<CognitiveFabric>,
     metrics: Arc<MetricsCollector>,
     instance_number: usize,
@@ -160,8 +248,31 @@ pub struct HardwareCore {
     start_time: SystemTime,
     error_count: Arc<RwLock<u64>>,
     failure_predictor: FailurePredictor,
+    /// Detectada al crear la instancia; no cambia en ejecución
+    topology: CpuTopology,
     performance_optimizer: HardwareOptimizer,
     thermal_monitor: ThermalMonitor,
+    /// `None` con `enable_gpu_monitoring = false`
+    gpu_monitor: Option<GpuMonitor>,
+    /// Modo aplicado con `SetPowerMode`
+    power_state: PowerState,
+    /// Perfil previo al primer `SetPowerMode`, para restaurarlo al apagar
+    original_power_profile: Option<PowerProfile>,
+    /// Histéresis y enfriamiento de `hardware.alerts`
+    alerts: AlertGate,
+    /// Última lectura SMART y su instante
+    smart_health: Arc<RwLock<Option<(Instant, Vec<SmartHealth>)>>>,
+}
+
+/// Estado de `HardwareCore` entre reemplazos y reinicios
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct HardwareCoreCheckpoint {
+    error_count: u64,
+    /// Historial del predictor de fallos
+    samples: Vec<PredictorSample>,
+    power_state: Option<PowerState>,
+    original_power_profile: Option<PowerProfile>,
 }
 
 impl HardwareCore {
@@ -170,34 +281,48 @@ impl HardwareCore {
         cognitive_fabric: Arc<CognitiveFabric>,
         metrics: Arc<MetricsCollector>,
         instance_number: usize,
+        config: HardwareCoreConfig,
     ) -> Result<Self> {
         let mut system = System::new_all();
         system.refresh_all();
+        let gpu_monitor = config.enable_gpu_monitoring.then(GpuMonitor::new);
+        let failure_predictor = FailurePredictor::load(config.predictor.clone(), instance_number).await;
+        let alerts = AlertGate::new(config.alert_hysteresis, Duration::from_secs(config.alert_cooldown_secs));
         
         Ok(Self {
             instance_id: Uuid::new_v4(),
+            config,
             cognitive_fabric,
             metrics,
             instance_number,
             system: Arc::new(RwLock::new(system)),
             start_time: SystemTime::now(),
             error_count: Arc::new(RwLock::new(0)),
-            failure_predictor: FailurePredictor::new(),
+            failure_predictor,
+            topology: CpuTopology::detect(),
             performance_optimizer: HardwareOptimizer::new(),
             thermal_monitor: ThermalMonitor::new(),
+            gpu_monitor,
+            power_state: PowerState::Normal,
+            original_power_profile: None,
+            alerts,
+            smart_health: Arc::new(RwLock::new(None)),
         })
     }
 
     /// Obtener información completa de hardware
     async fn get_hardware_info(&self) -> Result<HardwareInfo> {
+        let smart_health = self.smart_health().await;
         let mut system = self.system.write().await;
         system.refresh_all();
 
-        let cpu_info = self.get_cpu_info(&system).await?;
+        let mut cpu_info = self.get_cpu_info(&system).await?;
         let memory_info = self.get_memory_info(&system).await?;
-        let disk_info = self.get_disk_info(&system).await?;
+        let disk_info = self.get_disk_info(&system, &smart_health).await?;
         let network_info = self.get_network_info(&system).await?;
-        let thermal_info = self.thermal_monitor.get_thermal_info(&system).await?;
+        let gpu_info = self.gpu_info();
+        let thermal_info = self.thermal_info(&system, &gpu_info).await?;
+        cpu_info.temperature = thermal_info.cpu_temperature.or(cpu_info.temperature);
         let power_info = self.get_power_info().await?;
 
         Ok(HardwareInfo {
@@ -207,9 +332,21 @@ impl HardwareCore {
             network_info,
             thermal_info,
             power_info,
+            gpu_info,
         })
     }
 
+    fn gpu_info(&self) -> Vec<GpuInfo> {
+        self.gpu_monitor.as_ref().map(GpuMonitor::gpus).unwrap_or_default()
+    }
+
+    /// Lecturas del monitor térmico completadas con las de las GPUs
+    async fn thermal_info(&self, system: &System, gpus: &[GpuInfo]) -> Result<ThermalInfo> {
+        let mut thermal_info = self.thermal_monitor.get_thermal_info(system).await?;
+        thermal_info.include_gpus(gpus);
+        Ok(thermal_info)
+    }
+
     /// Obtener información de CPU
     async fn get_cpu_info(&self, system: &System) -> Result<CpuInfo> {
         let cpus = system.cpus();
@@ -232,10 +369,12 @@ impl HardwareCore {
             frequency: cpus.first()
                 .map(|cpu| cpu.frequency())
                 .unwrap_or(0),
+            usage_per_socket: self.topology.usage_per_socket(&usage_per_core),
             usage_per_core,
             average_usage,
             temperature,
             load_average: [load_avg.one, load_avg.five, load_avg.fifteen],
+            topology: self.topology.clone(),
         })
     }
 
@@ -264,6 +403,7 @@ impl HardwareCore {
             swap_used,
             usage_percentage,
             pressure_score,
+            numa_nodes: topology::numa_memory(),
         })
     }
 
@@ -278,7 +418,7 @@ impl HardwareCore {
     }
 
     /// Obtener información de discos
-    async fn get_disk_info(&self, system: &System) -> Result<Vec<DiskInfo>> {
+    async fn get_disk_info(&self, system: &System, smart_health: &[SmartHealth]) -> Result<Vec<DiskInfo>> {
         let mut disk_info = Vec::new();
         
         for disk in system.disks() {
@@ -290,8 +430,15 @@ impl HardwareCore {
                 0.0
             };
 
+            let name = disk.name().to_string_lossy().to_string();
+            let smart = smart_health
+                .iter()
+                .filter(|health| health.covers(&name))
+                .max_by_key(|health| health.device.len())
+                .cloned();
+
             disk_info.push(DiskInfo {
-                name: disk.name().to_string_lossy().to_string(),
+                name,
                 mount_point: disk.mount_point().to_string_lossy().to_string(),
                 total_space,
                 available_space,
@@ -300,12 +447,36 @@ impl HardwareCore {
                 is_removable: disk.is_removable(),
                 read_speed: 0, // TODO: Implementar medición de velocidad
                 write_speed: 0, // TODO: Implementar medición de velocidad
+                smart,
             });
         }
 
         Ok(disk_info)
     }
 
+    /// Lectura SMART en caché, renovada cada `smart_interval_secs`
+    ///
+    /// Si `smartctl` no está disponible se guarda una lectura vacía para no
+    /// reintentarlo en cada ciclo.
+    async fn smart_health(&self) -> Vec<SmartHealth> {
+        if !self.config.enable_smart {
+            return Vec::new();
+        }
+        if let Some((read_at, health)) = &*self.smart_health.read().await {
+            if read_at.elapsed() < Duration::from_secs(self.config.smart_interval_secs) {
+                return health.clone();
+            }
+        }
+
+        let health = smart::collect().await.unwrap_or_else(|e| {
+            debug!("💿 SMART no disponible: {}", e);
+            Vec::new()
+        });
+        self.metrics.record_smart_health(&health).await;
+        *self.smart_health.write().await = Some((Instant::now(), health.clone()));
+        health
+    }
+
     /// Obtener información de red
     async fn get_network_info(&self, system: &System) -> Result<Vec<NetworkInfo>> {
         let mut network_info = Vec::new();
@@ -336,10 +507,25 @@ impl HardwareCore {
             is_charging: None,
             power_consumption: None, // TODO: Implementar medición de consumo
             voltage: None,
-            power_state: PowerState::Normal,
+            power_state: self.power_state.clone(),
         })
     }
 
+    /// Cambiar el perfil de energía del sistema
+    ///
+    /// Antes del primer cambio se guarda el perfil activo para poder restaurarlo.
+    async fn set_power_mode(&mut self, mode: PowerState) -> Result<PowerProfile> {
+        if self.original_power_profile.is_none() {
+            match power::current().await {
+                Ok(profile) => self.original_power_profile = Some(profile),
+                Err(e) => warn!("⚠️  No se pudo leer el perfil de energía actual: {}", e),
+            }
+        }
+        let profile = power::apply(&mode).await?;
+        self.power_state = mode;
+        Ok(profile)
+    }
+
     /// Predecir fallos de hardware
     async fn predict_failures(&self) -> Result<Vec<FailurePrediction>> {
         let hardware_info = self.get_hardware_info().await?;
@@ -381,57 +567,103 @@ impl HardwareCore {
         Ok(())
     }
 
+    /// Umbral de ocupación de un disco: por punto de montaje, por nombre o el general
+    fn disk_threshold(&self, disk: &DiskInfo) -> f64 {
+        self.config
+            .disk_thresholds
+            .get(&disk.mount_point)
+            .or_else(|| self.config.disk_thresholds.get(&disk.name))
+            .copied()
+            .unwrap_or(self.config.disk_usage_threshold)
+    }
+
     /// Verificar alertas de hardware
-    async fn check_hardware_alerts(&self) -> Result<()> {
+    ///
+    /// Solo se publican los disparos y resoluciones que deja pasar `AlertGate`.
+    async fn check_hardware_alerts(&mut self) -> Result<()> {
         let hardware_info = self.get_hardware_info().await?;
+        let now = Instant::now();
+        let mut alerts = Vec::new();
         
         // Verificar temperatura crítica
         if let Some(temp) = hardware_info.thermal_info.cpu_temperature {
-            if temp > 85.0 {
-                warn!("🌡️  Temperatura crítica de CPU: {:.1}°C", temp);
-                
-                self.cognitive_fabric
-                    .publish("hardware.alerts", &serde_json::to_vec(&serde_json::json!({
-                        "type": "critical_temperature",
-                        "component": "cpu",
-                        "temperature": temp,
-                        "threshold": 85.0,
-                        "timestamp": SystemTime::now()
-                    }))?)
-                    .await?;
+            let threshold = self.config.temperature_threshold;
+            if let Some(event) = self.alerts.evaluate("cpu_temperature", temp as f64, threshold, now) {
+                if event == AlertEvent::Firing {
+                    warn!("🌡️  Temperatura crítica de CPU: {:.1}°C", temp);
+                }
+                alerts.push((event, serde_json::json!({
+                    "type": "critical_temperature",
+                    "component": "cpu",
+                    "temperature": temp,
+                    "threshold": threshold,
+                })));
             }
         }
         
+        // Verificar uso de CPU sostenido
+        let cpu_usage = hardware_info.cpu_info.average_usage;
+        let threshold = self.config.cpu_usage_threshold;
+        if let Some(event) = self.alerts.evaluate("cpu_usage", cpu_usage as f64, threshold, now) {
+            if event == AlertEvent::Firing {
+                warn!("🔥 Uso crítico de CPU: {:.1}%", cpu_usage);
+            }
+            alerts.push((event, serde_json::json!({
+                "type": "critical_cpu",
+                "usage_percentage": cpu_usage,
+                "load_average": hardware_info.cpu_info.load_average,
+                "threshold": threshold,
+            })));
+        }
+        
         // Verificar uso de memoria crítico
-        if hardware_info.memory_info.usage_percentage > 90.0 {
-            warn!("💾 Uso crítico de memoria: {:.1}%", hardware_info.memory_info.usage_percentage);
-            
-            self.cognitive_fabric
-                .publish("hardware.alerts", &serde_json::to_vec(&serde_json::json!({
-                    "type": "critical_memory",
-                    "usage_percentage": hardware_info.memory_info.usage_percentage,
-                    "available": hardware_info.memory_info.available,
-                    "timestamp": SystemTime::now()
-                }))?)
-                .await?;
+        let memory_usage = hardware_info.memory_info.usage_percentage;
+        let threshold = self.config.memory_usage_threshold;
+        if let Some(event) = self.alerts.evaluate("memory_usage", memory_usage as f64, threshold, now) {
+            if event == AlertEvent::Firing {
+                warn!("💾 Uso crítico de memoria: {:.1}%", memory_usage);
+            }
+            alerts.push((event, serde_json::json!({
+                "type": "critical_memory",
+                "usage_percentage": memory_usage,
+                "available": hardware_info.memory_info.available,
+                "threshold": threshold,
+            })));
         }
         
         // Verificar espacio en disco crítico
         for disk in &hardware_info.disk_info {
-            if disk.usage_percentage > 95.0 {
-                warn!("💿 Espacio crítico en disco {}: {:.1}%", disk.name, disk.usage_percentage);
-                
-                self.cognitive_fabric
-                    .publish("hardware.alerts", &serde_json::to_vec(&serde_json::json!({
-                        "type": "critical_disk_space",
-                        "disk": disk.name,
-                        "usage_percentage": disk.usage_percentage,
-                        "available_space": disk.available_space,
-                        "timestamp": SystemTime::now()
-                    }))?)
-                    .await?;
+            let threshold = self.disk_threshold(disk);
+            let key = format!("disk:{}", disk.mount_point);
+            if let Some(event) = self.alerts.evaluate(&key, disk.usage_percentage as f64, threshold, now) {
+                if event == AlertEvent::Firing {
+                    warn!("💿 Espacio crítico en disco {}: {:.1}%", disk.name, disk.usage_percentage);
+                }
+                alerts.push((event, serde_json::json!({
+                    "type": "critical_disk_space",
+                    "disk": disk.name,
+                    "mount_point": disk.mount_point,
+                    "usage_percentage": disk.usage_percentage,
+                    "available_space": disk.available_space,
+                    "threshold": threshold,
+                })));
             }
         }
+        self.alerts.retain(|key| match key.strip_prefix("disk:") {
+            Some(mount) => hardware_info.disk_info.iter().any(|disk| disk.mount_point == mount),
+            None => true,
+        });
+
+        for (event, mut alert) in alerts {
+            if event == AlertEvent::Resolved {
+                info!("✅ Alerta de hardware resuelta: {}", alert["type"]);
+            }
+            alert["state"] = serde_json::json!(event.as_str());
+            alert["timestamp"] = serde_json::json!(SystemTime::now());
+            self.cognitive_fabric
+                .publish("hardware.alerts", &serde_json::to_vec(&alert)?)
+                .await?;
+        }
 
         Ok(())
     }
@@ -454,16 +686,6 @@ impl NanoCore for HardwareCore {
             self.instance_id
         );
 
-        // Suscribirse a comandos de hardware
-        self.cognitive_fabric
-            .subscribe("hardware.commands", {
-                let instance_id = self.instance_id;
-                move |data| {
-                    debug!("📨 HardwareCore {} recibió comando: {} bytes", instance_id, data.len());
-                }
-            })
-            .await?;
-
         // Publicar información inicial de hardware
         let hardware_info = self.get_hardware_info().await?;
         let info_data = serde_json::to_vec(&hardware_info)?;
@@ -476,12 +698,12 @@ impl NanoCore for HardwareCore {
         Ok(())
     }
 
-    async fn run(&mut self) -> Result<()> {
+    async fn run(&mut self) -> CoreResult<()> {
         // Publicar métricas de hardware
         if let Err(e) = self.publish_hardware_metrics().await {
             let mut error_count = self.error_count.write().await;
             *error_count += 1;
-            return Err(anyhow!("Error publicando métricas de hardware: {}", e));
+            return Err(NanoCoreError::Transient(anyhow!("Error publicando métricas de hardware: {}", e)));
         }
 
         // Verificar alertas de hardware
@@ -504,8 +726,6 @@ impl NanoCore for HardwareCore {
                 }
             }
         }
-
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
         Ok(())
     }
 
@@ -541,17 +761,24 @@ impl NanoCore for HardwareCore {
 
     async fn shutdown(&mut self) -> Result<()> {
         info!("🛑 Deteniendo HardwareCore instancia {}", self.instance_number);
-        
-        // Desuscribirse de eventos
-        self.cognitive_fabric
-            .unsubscribe("hardware.commands")
-            .await?;
+
+        if self.config.restore_power_mode_on_shutdown {
+            if let Some(profile) = self.original_power_profile.take() {
+                if let Err(e) = power::restore(&profile).await {
+                    warn!("⚠️  No se pudo restaurar el perfil de energía: {}", e);
+                }
+            }
+        }
 
         info!("✅ HardwareCore instancia {} detenido correctamente", self.instance_number);
         Ok(())
     }
 
-    async fn process_command(&mut self, command: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    fn command_authorization(&self, payload: &[u8]) -> Result<CommandAuthorization> {
+        Ok(serde_json::from_slice::<HardwareCommand>(payload)?.authorization())
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
         let cmd: HardwareCommand = serde_json::from_slice(payload)?;
         
         let response = match cmd {
@@ -560,7 +787,7 @@ impl NanoCore for HardwareCore {
                 serde_json::to_vec(&info)?
             }
             HardwareCommand::GetThermalStatus => {
-                let thermal = self.thermal_monitor.get_thermal_info(&*self.system.read().await).await?;
+                let thermal = self.thermal_info(&*self.system.read().await, &self.gpu_info()).await?;
                 serde_json::to_vec(&thermal)?
             }
             HardwareCommand::GetPowerStatus => {
@@ -576,10 +803,10 @@ impl NanoCore for HardwareCore {
                 serde_json::to_vec(&result)?
             }
             HardwareCommand::SetPowerMode(mode) => {
-                // TODO: Implementar cambio de modo de energía
-                let result = format!("Modo de energía cambiado a: {:?}", mode);
-                serde_json::to_vec(&result)?
+                let profile = self.set_power_mode(mode).await?;
+                serde_json::to_vec(&profile)?
             }
+            HardwareCommand::GetGpuInfo => serde_json::to_vec(&self.gpu_info())?,
             HardwareCommand::GetComponentHealth(component) => {
                 // TODO: Implementar salud de componente específico
                 let health = format!("Salud de {}: OK", component);
@@ -590,83 +817,297 @@ impl NanoCore for HardwareCore {
         debug!("✅ Comando HardwareCore procesado: {}", command);
         Ok(response)
     }
+    
+    async fn apply_config(&mut self, config: &CoreConfig) -> Result<()> {
+        self.config = config.nano_cores.for_instance(self.instance_number)?.hardware_core;
+        self.failure_predictor.set_config(self.config.predictor.clone(), self.instance_number);
+        self.alerts.configure(self.config.alert_hysteresis, Duration::from_secs(self.config.alert_cooldown_secs));
+        match (self.config.enable_gpu_monitoring, self.gpu_monitor.is_some()) {
+            (true, false) => self.gpu_monitor = Some(GpuMonitor::new()),
+            (false, true) => self.gpu_monitor = None,
+            _ => {}
+        }
+        debug!("📋 HardwareCore instancia {} reconfigurada", self.instance_number);
+        Ok(())
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&HardwareCoreCheckpoint {
+            error_count: *self.error_count.read().await,
+            samples: self.failure_predictor.history().await,
+            power_state: Some(self.power_state.clone()),
+            original_power_profile: self.original_power_profile.clone(),
+        })?)
+    }
+
+    async fn restore(&mut self, state: &[u8]) -> Result<()> {
+        let checkpoint: HardwareCoreCheckpoint = serde_json::from_slice(state)?;
+        *self.error_count.write().await = checkpoint.error_count;
+        self.failure_predictor.restore_history(checkpoint.samples).await;
+        self.power_state = checkpoint.power_state.unwrap_or(PowerState::Normal);
+        self.original_power_profile = checkpoint.original_power_profile;
+        // La instancia anterior restauró el perfil original al detenerse
+        if self.config.restore_power_mode_on_shutdown && self.original_power_profile.is_some() {
+            if let Err(e) = power::apply(&self.power_state).await {
+                warn!("⚠️  No se pudo volver a aplicar el modo de energía {:?}: {}", self.power_state, e);
+            }
+        }
+        debug!("♻️  HardwareCore instancia {} restaurada", self.instance_number);
+        Ok(())
+    }
 }
 
 /// Predictor de fallos de hardware
+///
+/// Combina umbrales sobre el estado actual con tendencias ajustadas sobre el
+/// historial persistido (ver `prediction`).
 pub struct FailurePredictor {
-    historical_data: Arc<RwLock<Vec<HardwareInfo>>>,
+    config: FailurePredictorConfig,
+    store: Option<HistoryStore>,
+    samples: Arc<RwLock<Vec<PredictorSample>>>,
 }
 
 impl FailurePredictor {
-    pub fn new() -> Self {
-        Self {
-            historical_data: Arc::new(RwLock::new(Vec::new())),
+    /// Predictor con el historial guardado de la réplica
+    pub async fn load(config: FailurePredictorConfig, instance: usize) -> Self {
+        let store = HistoryStore::new(&config, instance);
+        let samples = match &store {
+            Some(store) => store.load().await.unwrap_or_else(|e| {
+                warn!("⚠️  Historial del predictor ilegible, se empieza de cero: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let predictor = Self {
+            config,
+            store,
+            samples: Arc::new(RwLock::new(Vec::new())),
+        };
+        predictor.restore_history(samples).await;
+        predictor
+    }
+
+    pub fn set_config(&mut self, config: FailurePredictorConfig, instance: usize) {
+        self.store = HistoryStore::new(&config, instance);
+        self.config = config;
+    }
+
+    /// Muestras guardadas, de la más antigua a la más reciente
+    pub async fn history(&self) -> Vec<PredictorSample> {
+        self.samples.read().await.clone()
+    }
+
+    /// Adoptar el historial de un checkpoint si es más reciente que el actual
+    pub async fn restore_history(&self, mut history: Vec<PredictorSample>) {
+        let limit = self.config.history_limit.max(1);
+        if history.len() > limit {
+            history.drain(0..history.len() - limit);
+        }
+        let mut samples = self.samples.write().await;
+        let latest = |samples: &[PredictorSample]| samples.last().map(|s| s.timestamp);
+        if latest(&history) >= latest(&samples) {
+            *samples = history;
+        }
+    }
+
+    /// Añadir una muestra si pasó `sample_interval_secs` desde la anterior y persistir
+    async fn record(&self, hardware_info: &HardwareInfo) {
+        let now = chrono::Utc::now();
+        let mut samples = self.samples.write().await;
+        let interval = chrono::Duration::seconds(self.config.sample_interval_secs as i64);
+        if samples.last().is_some_and(|last| now - last.timestamp < interval) {
+            return;
+        }
+
+        samples.push(PredictorSample {
+            timestamp: now,
+            cpu_temperature: hardware_info.thermal_info.cpu_temperature,
+            gpu_temperature: hardware_info.thermal_info.gpu_temperature,
+            memory_pressure: hardware_info.memory_info.pressure_score,
+            disk_usage: hardware_info
+                .disk_info
+                .iter()
+                .map(|disk| (disk.mount_point.clone(), disk.usage_percentage))
+                .collect(),
+        });
+        let limit = self.config.history_limit.max(1);
+        if samples.len() > limit {
+            let excess = samples.len() - limit;
+            samples.drain(0..excess);
+        }
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save(&samples).await {
+                warn!("⚠️  Error guardando el historial del predictor: {}", e);
+            }
         }
     }
 
+    /// Tendencia de una serie en las muestras posteriores a `since`
+    fn trend<F>(&self, samples: &[PredictorSample], since: Option<chrono::Duration>, value: F) -> Option<Trend>
+    where
+        F: Fn(&PredictorSample) -> Option<f64>,
+    {
+        let since = since.map(|window| chrono::Utc::now() - window);
+        Trend::fit(
+            samples
+                .iter()
+                .filter(|sample| since.map_or(true, |since| sample.timestamp >= since))
+                .filter_map(|sample| Some((sample.timestamp, value(sample)?))),
+            self.config.min_samples,
+        )
+    }
+
+    /// Predicción por tendencia: el valor alcanzaría `limit` dentro del horizonte
+    ///
+    /// Se descartan los ajustes con confianza inferior a 0.5 (series ruidosas).
+    fn trend_prediction(&self, component: String, trend: Option<Trend>, current: f64, limit: f64, recommended_actions: Vec<String>) -> Option<FailurePrediction> {
+        let trend = trend?;
+        let seconds = trend.time_to(current, limit)?;
+        let horizon = self.config.horizon_secs();
+        let confidence = trend.confidence();
+        if seconds > horizon || confidence < 0.5 {
+            return None;
+        }
+
+        let risk_level = match seconds {
+            s if s < 3600.0 => RiskLevel::Critical,
+            s if s < 6.0 * 3600.0 => RiskLevel::High,
+            s if s < 24.0 * 3600.0 => RiskLevel::Medium,
+            _ => RiskLevel::Low,
+        };
+        Some(FailurePrediction {
+            component,
+            risk_level,
+            probability: confidence * (1.0 - (seconds / horizon) as f32),
+            time_to_failure: Some(seconds as u64),
+            recommended_actions,
+            confidence,
+        })
+    }
+
     pub async fn analyze(&self, hardware_info: &HardwareInfo) -> Result<Vec<FailurePrediction>> {
         let mut predictions = Vec::new();
         
         // Almacenar datos históricos
-        let mut history = self.historical_data.write().await;
-        history.push(hardware_info.clone());
-        
-        // Mantener solo los últimos 100 registros
-        if history.len() > 100 {
-            history.drain(0..history.len() - 100);
-        }
+        self.record(hardware_info).await;
+        let samples = self.samples.read().await;
+        // Temperatura y memoria cambian en minutos; el disco se llena en días
+        let recent = Some(chrono::Duration::hours(1));
         
         // Predicción basada en temperatura de CPU
         if let Some(temp) = hardware_info.thermal_info.cpu_temperature {
+            let trend = self.trend(&samples, recent, |s| s.cpu_temperature.map(f64::from));
+            let actions = vec![
+                "Verificar ventilación del sistema".to_string(),
+                "Limpiar disipadores de calor".to_string(),
+                "Reducir carga de trabajo".to_string(),
+            ];
             if temp > 80.0 {
                 let probability = ((temp - 80.0) / 20.0).min(1.0);
                 predictions.push(FailurePrediction {
                     component: "CPU".to_string(),
                     risk_level: if temp > 90.0 { RiskLevel::Critical } else { RiskLevel::High },
                     probability,
-                    time_to_failure: Some(((100.0 - temp) * 3600.0) as u64), // Estimación simplificada
-                    recommended_actions: vec![
-                        "Verificar ventilación del sistema".to_string(),
-                        "Limpiar disipadores de calor".to_string(),
-                        "Reducir carga de trabajo".to_string(),
-                    ],
+                    time_to_failure: trend.and_then(|t| t.time_to(temp as f64, 100.0)).map(|s| s as u64),
+                    recommended_actions: actions,
                     confidence: 0.85,
                 });
+            } else {
+                predictions.extend(self.trend_prediction("CPU".to_string(), trend, temp as f64, 100.0, actions));
+            }
+        }
+        
+        // Predicción basada en las GPUs
+        for gpu in &hardware_info.gpu_info {
+            let gpu_actions = || vec![
+                "Verificar ventiladores de la GPU".to_string(),
+                "Limitar la potencia de la GPU".to_string(),
+                "Reducir carga de trabajo en la GPU".to_string(),
+            ];
+            if let Some(temp) = gpu.temperature.filter(|temp| *temp <= 85.0) {
+                // El historial guarda la temperatura de la GPU más caliente
+                if hardware_info.thermal_info.gpu_temperature == Some(temp) {
+                    let trend = self.trend(&samples, recent, |s| s.gpu_temperature.map(f64::from));
+                    predictions.extend(self.trend_prediction(format!("GPU {}: {}", gpu.index, gpu.name), trend, temp as f64, 100.0, gpu_actions()));
+                }
+            }
+            if let Some(temp) = gpu.temperature.filter(|temp| *temp > 85.0) {
+                predictions.push(FailurePrediction {
+                    component: format!("GPU {}: {}", gpu.index, gpu.name),
+                    risk_level: if temp > 95.0 { RiskLevel::Critical } else { RiskLevel::High },
+                    probability: ((temp - 85.0) / 15.0).min(1.0),
+                    time_to_failure: None,
+                    recommended_actions: gpu_actions(),
+                    confidence: 0.8,
+                });
+            }
+            if let Some(usage) = gpu.memory_usage_percentage().filter(|usage| *usage > 95.0) {
+                predictions.push(FailurePrediction {
+                    component: format!("GPU {} memory: {}", gpu.index, gpu.name),
+                    risk_level: RiskLevel::Medium,
+                    probability: (usage - 95.0) / 5.0,
+                    time_to_failure: None,
+                    recommended_actions: vec!["Liberar memoria de la GPU".to_string()],
+                    confidence: 0.7,
+                });
             }
         }
         
         // Predicción basada en uso de memoria
-        if hardware_info.memory_info.pressure_score > 0.8 {
+        let pressure = hardware_info.memory_info.pressure_score;
+        let trend = self.trend(&samples, recent, |s| Some(s.memory_pressure as f64));
+        let actions = vec![
+            "Liberar memoria no utilizada".to_string(),
+            "Optimizar aplicaciones en ejecución".to_string(),
+            "Considerar agregar más RAM".to_string(),
+        ];
+        if pressure > 0.8 {
             predictions.push(FailurePrediction {
                 component: "Memory".to_string(),
                 risk_level: RiskLevel::Medium,
-                probability: hardware_info.memory_info.pressure_score,
-                time_to_failure: None,
-                recommended_actions: vec![
-                    "Liberar memoria no utilizada".to_string(),
-                    "Optimizar aplicaciones en ejecución".to_string(),
-                    "Considerar agregar más RAM".to_string(),
-                ],
+                probability: pressure,
+                time_to_failure: trend.and_then(|t| t.time_to(pressure as f64, 1.0)).map(|s| s as u64),
+                recommended_actions: actions,
                 confidence: 0.75,
             });
+        } else {
+            predictions.extend(self.trend_prediction("Memory".to_string(), trend, pressure as f64, 1.0, actions));
         }
         
-        // Predicción basada en espacio en disco
+        // Predicción basada en SMART, una vez por dispositivo
+        let mut devices = HashSet::new();
+        for health in hardware_info.disk_info.iter().filter_map(|disk| disk.smart.as_ref()) {
+            if devices.insert(health.device.as_str()) {
+                predictions.extend(health.predictions());
+            }
+        }
+        
+        // Predicción basada en espacio en disco y su ritmo de llenado
         for disk in &hardware_info.disk_info {
-            if disk.usage_percentage > 90.0 {
-                let probability = (disk.usage_percentage - 90.0) / 10.0;
+            let usage = disk.usage_percentage;
+            let trend = self.trend(&samples, None, |s| s.disk_usage.get(&disk.mount_point).map(|u| *u as f64));
+            let actions = vec![
+                "Limpiar archivos temporales".to_string(),
+                "Mover datos a otro disco".to_string(),
+                "Expandir capacidad de almacenamiento".to_string(),
+            ];
+            if usage > 90.0 {
+                let probability = (usage - 90.0) / 10.0;
                 predictions.push(FailurePrediction {
                     component: format!("Disk: {}", disk.name),
-                    risk_level: if disk.usage_percentage > 98.0 { RiskLevel::Critical } else { RiskLevel::High },
+                    risk_level: if usage > 98.0 { RiskLevel::Critical } else { RiskLevel::High },
                     probability,
-                    time_to_failure: Some(((100.0 - disk.usage_percentage) * 86400.0) as u64), // días a segundos
-                    recommended_actions: vec![
-                        "Limpiar archivos temporales".to_string(),
-                        "Mover datos a otro disco".to_string(),
-                        "Expandir capacidad de almacenamiento".to_string(),
-                    ],
+                    time_to_failure: trend.and_then(|t| t.time_to(usage as f64, 100.0)).map(|s| s as u64),
+                    recommended_actions: actions,
                     confidence: 0.90,
                 });
+            } else {
+                predictions.extend(self.trend_prediction(format!("Disk: {}", disk.name), trend, usage as f64, 100.0, actions));
             }
         }
 
@@ -710,58 +1151,40 @@ impl HardwareOptimizer {
     }
 }
 
-/// Monitor térmico avanzado
-pub struct ThermalMonitor;
+/// Monitor térmico sobre los backends de la plataforma
+pub struct ThermalMonitor {
+    backends: Vec<Box<dyn ThermalBackend>>,
+}
 
 impl ThermalMonitor {
     pub fn new() -> Self {
-        Self
+        Self::with_backends(thermal::platform_backends())
+    }
+
+    pub fn with_backends(backends: Vec<Box<dyn ThermalBackend>>) -> Self {
+        Self { backends }
     }
 
+    /// Lecturas del primer backend que responda; sin ninguno el estado es `Optimal`
     pub async fn get_thermal_info(&self, system: &System) -> Result<ThermalInfo> {
-        let mut cpu_temperature = None;
-        let mut gpu_temperature = None;
-        let mut motherboard_temperature = None;
-        let mut fan_speeds = Vec::new();
-        
-        // Obtener temperaturas de componentes
-        for component in system.components() {
-            let label = component.label().to_lowercase();
-            let temp = component.temperature();
-            
-            if label.contains("cpu") || label.contains("processor") {
-                cpu_temperature = Some(temp);
-            } else if label.contains("gpu") || label.contains("graphics") {
-                gpu_temperature = Some(temp);
-            } else if label.contains("motherboard") || label.contains("system") {
-                motherboard_temperature = Some(temp);
-            }
-            
-            // Simular velocidades de ventiladores
-            if label.contains("fan") {
-                fan_speeds.push((1000.0 + temp * 20.0) as u32);
-            }
-        }
-        
-        // Determinar estado térmico
-        let max_temp = [cpu_temperature, gpu_temperature, motherboard_temperature]
-            .iter()
-            .filter_map(|&t| t)
-            .fold(0.0f32, f32::max);
-        
-        let thermal_state = match max_temp {
-            t if t < 60.0 => ThermalState::Optimal,
-            t if t < 75.0 => ThermalState::Warm,
-            t if t < 85.0 => ThermalState::Hot,
-            _ => ThermalState::Critical,
+        let (source, readings) = match thermal::read_first(&self.backends, system).await {
+            Some((source, readings)) => (Some(source.to_string()), readings),
+            None => (None, ThermalReadings::default()),
         };
-        
+
+        let cpu_temperature = readings.max(SensorKind::Cpu);
+        let gpu_temperature = readings.max(SensorKind::Gpu);
+        let motherboard_temperature = readings.max(SensorKind::Motherboard);
+
         Ok(ThermalInfo {
             cpu_temperature,
             gpu_temperature,
             motherboard_temperature,
-            fan_speeds,
-            thermal_state,
+            fan_speeds: readings.fans.iter().map(|fan| fan.rpm).collect(),
+            thermal_state: ThermalState::from_temperatures(&[cpu_temperature, gpu_temperature, motherboard_temperature]),
+            sensors: readings.temperatures,
+            fans: readings.fans,
+            source,
         })
     }
-}
\ No newline at end of file
+}
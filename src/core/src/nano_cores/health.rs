@@ -0,0 +1,401 @@
+//! Puntuación de salud de nano-núcleos y del sistema
+//!
+//! Cada instancia recibe un score compuesto (estado, errores y CPU) y el
+//! sistema otro (núcleos, consenso y latencia del fabric), ponderados según
+//! `[nano_cores.health]`. El estado resultante tiene histéresis: para cambiar
+//! de banda el score debe superar el umbral por `hysteresis`, de modo que un
+//! valor que oscila sobre el umbral no alterna entre Running y Degraded.
+//! El monitor de salud guarda un historial acotado de muestras del que sale
+//! la tendencia, y publica cada cambio de estado como evento `HealthCheck`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use super::{NanoCoreHealth, NanoCoreState, NanoCoreType};
+
+/// Pesos del score de una instancia
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CoreWeights {
+    /// Estado que reporta el propio núcleo
+    pub state: f64,
+    /// Errores acumulados frente a `max_errors`
+    pub errors: f64,
+    /// CPU libre
+    pub cpu: f64,
+}
+
+impl Default for CoreWeights {
+    fn default() -> Self {
+        Self { state: 0.6, errors: 0.25, cpu: 0.15 }
+    }
+}
+
+/// Pesos del score del sistema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SystemWeights {
+    /// Media de los scores de las instancias
+    pub cores: f64,
+    pub consensus: f64,
+    /// Latencia del fabric frente a `max_fabric_latency_ms`
+    pub latency: f64,
+}
+
+impl Default for SystemWeights {
+    fn default() -> Self {
+        Self { cores: 0.6, consensus: 0.25, latency: 0.15 }
+    }
+}
+
+/// Sección `[nano_cores.health]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Score mínimo de Running
+    pub healthy_score: f64,
+    /// Score mínimo de Degraded; por debajo, Failed
+    pub degraded_score: f64,
+    /// Margen que el score debe superar para cambiar de estado
+    pub hysteresis: f64,
+    /// Errores con los que el componente de errores llega a 0
+    pub max_errors: u64,
+    /// Por debajo de esta salud del consenso el sistema no pasa de Degraded
+    pub min_consensus_health: f64,
+    /// Por encima de esta latencia del fabric el sistema no pasa de Degraded
+    pub max_fabric_latency_ms: f64,
+    /// Espera máxima del ping al fabric; sin respuesta la latencia vale esto
+    pub fabric_ping_timeout_ms: u64,
+    /// Fracción de instancias Running sin la que el sistema no pasa de Degraded
+    pub min_healthy_cores: f64,
+    pub core_weights: CoreWeights,
+    pub system_weights: SystemWeights,
+    /// Muestras del sistema conservadas en el historial
+    pub history_size: usize,
+    /// Últimas muestras de las que se calcula la tendencia
+    pub trend_window: usize,
+    /// Variación del score en la ventana a partir de la cual hay tendencia
+    pub trend_threshold: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            healthy_score: 0.8,
+            degraded_score: 0.5,
+            hysteresis: 0.05,
+            max_errors: 10,
+            min_consensus_health: 0.8,
+            max_fabric_latency_ms: 10.0,
+            fabric_ping_timeout_ms: 1000,
+            min_healthy_cores: 0.8,
+            core_weights: CoreWeights::default(),
+            system_weights: SystemWeights::default(),
+            history_size: 120,
+            trend_window: 12,
+            trend_threshold: 0.05,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Score de una instancia entre 0 y 1
+    pub fn core_score(&self, health: &NanoCoreHealth) -> f64 {
+        let state = match health.state {
+            NanoCoreState::Running => 1.0,
+            NanoCoreState::Degraded | NanoCoreState::Initializing => 0.5,
+            NanoCoreState::Failed | NanoCoreState::Quarantined | NanoCoreState::Shutdown => 0.0,
+        };
+        let errors = 1.0 - (health.error_count as f64 / self.max_errors.max(1) as f64).min(1.0);
+        let cpu = 1.0 - (health.cpu_usage / 100.0).clamp(0.0, 1.0);
+
+        let weights = &self.core_weights;
+        weighted(&[(weights.state, state), (weights.errors, errors), (weights.cpu, cpu)])
+    }
+
+    /// Score del sistema a partir de los scores de sus instancias
+    pub fn system_score(&self, core_scores: &[f64], consensus_health: f64, fabric_latency_ms: f64) -> f64 {
+        let cores = if core_scores.is_empty() {
+            0.0
+        } else {
+            core_scores.iter().sum::<f64>() / core_scores.len() as f64
+        };
+        let latency = 1.0 - (fabric_latency_ms / self.max_fabric_latency_ms.max(f64::EPSILON)).clamp(0.0, 1.0);
+
+        let weights = &self.system_weights;
+        weighted(&[
+            (weights.cores, cores),
+            (weights.consensus, consensus_health.clamp(0.0, 1.0)),
+            (weights.latency, latency),
+        ])
+    }
+
+    /// Estado de un score dado el anterior
+    pub fn classify(&self, score: f64, previous: Option<&NanoCoreState>) -> NanoCoreState {
+        let band = |score: f64| {
+            if score >= self.healthy_score {
+                2
+            } else if score >= self.degraded_score {
+                1
+            } else {
+                0
+            }
+        };
+
+        let mut level = band(score);
+        if let Some(previous) = previous.and_then(level_of) {
+            // Solo se cambia de banda superando el umbral por `hysteresis`
+            if level < previous {
+                level = band(score + self.hysteresis).min(previous);
+            } else if level > previous {
+                level = band(score - self.hysteresis).max(previous);
+            }
+        }
+
+        match level {
+            2 => NanoCoreState::Running,
+            1 => NanoCoreState::Degraded,
+            _ => NanoCoreState::Failed,
+        }
+    }
+}
+
+/// Banda de un estado; los demás no tienen histéresis
+fn level_of(state: &NanoCoreState) -> Option<u8> {
+    match state {
+        NanoCoreState::Running => Some(2),
+        NanoCoreState::Degraded => Some(1),
+        NanoCoreState::Failed => Some(0),
+        _ => None,
+    }
+}
+
+fn weighted(components: &[(f64, f64)]) -> f64 {
+    let total: f64 = components.iter().map(|(weight, _)| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    components.iter().map(|(weight, value)| weight.max(0.0) * value).sum::<f64>() / total
+}
+
+/// Cambio de estado de una instancia o del sistema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthTransition {
+    /// Instancia afectada; ambos `None` para el sistema
+    pub core_type: Option<NanoCoreType>,
+    pub instance: Option<usize>,
+    pub from: NanoCoreState,
+    pub to: NanoCoreState,
+    pub score: f64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl HealthTransition {
+    /// Si el nuevo estado es peor que el anterior
+    pub fn is_degradation(&self) -> bool {
+        rank(&self.to) < rank(&self.from)
+    }
+}
+
+/// Orden de los estados de mejor a peor
+fn rank(state: &NanoCoreState) -> u8 {
+    match state {
+        NanoCoreState::Running => 4,
+        NanoCoreState::Initializing => 3,
+        NanoCoreState::Degraded => 2,
+        NanoCoreState::Shutdown => 1,
+        NanoCoreState::Failed | NanoCoreState::Quarantined => 0,
+    }
+}
+
+/// Último estado calculado de cada instancia y del sistema
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    cores: HashMap<(NanoCoreType, usize), NanoCoreState>,
+    system: Option<NanoCoreState>,
+    /// Transiciones pendientes de publicar
+    transitions: Vec<HealthTransition>,
+}
+
+impl HealthTracker {
+    /// Estado de una instancia con histéresis respecto al anterior
+    pub fn core_state(&mut self, config: &HealthConfig, core_type: &NanoCoreType, instance: usize, score: f64) -> NanoCoreState {
+        let state = config.classify(score, self.cores.get(&(core_type.clone(), instance)));
+        self.set_core_state(core_type, instance, state.clone(), score);
+        state
+    }
+
+    /// Estado impuesto a una instancia, como los del supervisor
+    pub fn set_core_state(&mut self, core_type: &NanoCoreType, instance: usize, state: NanoCoreState, score: f64) {
+        let previous = self.cores.insert((core_type.clone(), instance), state.clone());
+        self.record(Some((core_type, instance)), previous, state, score);
+    }
+
+    /// Estado del sistema con histéresis respecto al anterior
+    pub fn system_state(&mut self, config: &HealthConfig, score: f64) -> NanoCoreState {
+        let state = config.classify(score, self.system.as_ref());
+        let previous = self.system.replace(state.clone());
+        self.record(None, previous, state.clone(), score);
+        state
+    }
+
+    /// Transiciones registradas desde la última llamada
+    pub fn take_transitions(&mut self) -> Vec<HealthTransition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    fn record(&mut self, core: Option<(&NanoCoreType, usize)>, previous: Option<NanoCoreState>, state: NanoCoreState, score: f64) {
+        let Some(from) = previous.filter(|previous| *previous != state) else { return };
+        self.transitions.push(HealthTransition {
+            core_type: core.map(|(core_type, _)| core_type.clone()),
+            instance: core.map(|(_, instance)| instance),
+            from,
+            to: state,
+            score,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+}
+
+/// Tendencia del score del sistema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthTrend {
+    Improving,
+    #[default]
+    Stable,
+    Degrading,
+}
+
+/// Muestra del historial de salud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSample {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub state: NanoCoreState,
+    pub score: f64,
+    pub consensus_health: f64,
+    pub fabric_latency_ms: f64,
+}
+
+/// Historial acotado de muestras del sistema
+#[derive(Debug, Default)]
+pub struct HealthHistory {
+    samples: VecDeque<HealthSample>,
+}
+
+impl HealthHistory {
+    /// Añadir una muestra descartando las más antiguas por encima de `capacity`
+    pub fn push(&mut self, sample: HealthSample, capacity: usize) {
+        self.samples.push_back(sample);
+        while self.samples.len() > capacity.max(1) {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn samples(&self) -> Vec<HealthSample> {
+        self.samples.iter().cloned().collect()
+    }
+
+    /// Tendencia de las últimas `window` muestras por mínimos cuadrados
+    ///
+    /// Hay tendencia si la recta ajustada varía más de `threshold` a lo
+    /// largo de la ventana.
+    pub fn trend(&self, window: usize, threshold: f64) -> HealthTrend {
+        let recent: Vec<f64> = self.samples.iter().rev().take(window).rev().map(|sample| sample.score).collect();
+        if recent.len() < 2 {
+            return HealthTrend::Stable;
+        }
+
+        let n = recent.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = recent.iter().sum::<f64>() / n;
+        let (covariance, variance) = recent.iter().enumerate().fold((0.0, 0.0), |(cov, var), (i, score)| {
+            let dx = i as f64 - mean_x;
+            (cov + dx * (score - mean_y), var + dx * dx)
+        });
+        let change = covariance / variance * (n - 1.0);
+
+        if change > threshold {
+            HealthTrend::Improving
+        } else if change < -threshold {
+            HealthTrend::Degrading
+        } else {
+            HealthTrend::Stable
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn health(state: NanoCoreState, error_count: u64, cpu_usage: f64) -> NanoCoreHealth {
+        NanoCoreHealth {
+            core_type: NanoCoreType::OS,
+            instance_id: Uuid::new_v4(),
+            state,
+            cpu_usage,
+            memory_usage: 0.0,
+            last_heartbeat: chrono::Utc::now(),
+            error_count,
+            uptime_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn test_scores_and_hysteresis() {
+        let config = HealthConfig::default();
+        assert_eq!(config.core_score(&health(NanoCoreState::Running, 0, 0.0)), 1.0);
+        assert!(config.core_score(&health(NanoCoreState::Running, 10, 50.0)) < config.healthy_score);
+        assert!(config.core_score(&health(NanoCoreState::Failed, 0, 0.0)) < config.degraded_score);
+        assert!(config.system_score(&[1.0, 1.0], 0.95, 2.5) > config.healthy_score);
+        assert_eq!(config.system_score(&[], 0.0, 10.0), 0.0);
+
+        // Un score que oscila sobre 0.8 no cambia el estado hasta salir del margen
+        let mut tracker = HealthTracker::default();
+        let states: Vec<NanoCoreState> = [0.9, 0.78, 0.82, 0.74, 0.8, 0.84, 0.86]
+            .into_iter()
+            .map(|score| tracker.system_state(&config, score))
+            .collect();
+        let running: Vec<bool> = states.iter().map(|state| matches!(state, NanoCoreState::Running)).collect();
+        assert_eq!(running, [true, true, true, false, false, false, true]);
+
+        assert!(matches!(tracker.core_state(&config, &NanoCoreType::OS, 0, 0.3), NanoCoreState::Failed));
+        assert!(matches!(tracker.core_state(&config, &NanoCoreType::OS, 0, 0.52), NanoCoreState::Failed));
+        assert!(matches!(tracker.core_state(&config, &NanoCoreType::OS, 0, 0.6), NanoCoreState::Degraded));
+
+        // Running → Degraded → Running del sistema y Failed → Degraded de la instancia
+        let transitions = tracker.take_transitions();
+        let system: Vec<bool> = transitions.iter().filter(|t| t.core_type.is_none()).map(HealthTransition::is_degradation).collect();
+        assert_eq!(system, [true, false]);
+        let core = transitions.iter().find(|t| t.instance == Some(0)).unwrap();
+        assert_eq!((&core.from, &core.to), (&NanoCoreState::Failed, &NanoCoreState::Degraded));
+        assert!(tracker.take_transitions().is_empty());
+    }
+
+    #[test]
+    fn test_history_and_trend() {
+        let sample = |score: f64| HealthSample {
+            timestamp: chrono::Utc::now(),
+            state: NanoCoreState::Running,
+            score,
+            consensus_health: 1.0,
+            fabric_latency_ms: 1.0,
+        };
+        let mut history = HealthHistory::default();
+        for score in [0.9, 0.9, 0.88, 0.8, 0.7, 0.6] {
+            history.push(sample(score), 4);
+        }
+        assert_eq!(history.samples().len(), 4);
+        assert_eq!(history.trend(4, 0.05), HealthTrend::Degrading);
+
+        for score in [0.62, 0.6, 0.61, 0.6] {
+            history.push(sample(score), 4);
+        }
+        assert_eq!(history.trend(4, 0.05), HealthTrend::Stable);
+        history.push(sample(0.9), 4);
+        assert_eq!(history.trend(4, 0.05), HealthTrend::Improving);
+    }
+}
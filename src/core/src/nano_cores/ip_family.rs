@@ -0,0 +1,263 @@
+//! Familias IPv4/IPv6 de NetworkCore
+//!
+//! `[nano_cores.network_core.address_family]` habilita cada familia y elige la
+//! preferida cuando hay ambas: gateway por defecto, orden y tipo de registro de
+//! los sondeos DNS, y destinos admitidos en las pruebas. `DualStackStatus`
+//! resume por familia las direcciones globales, el gateway y si el kernel tiene
+//! ruta hacia Internet, comprobado con un `connect` UDP que no envía paquetes.
+//! Las direcciones IPv6 de enlace local solo sirven con su zona (`fe80::1%eth0`).
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6, UdpSocket};
+
+use crate::nano_cores::netinfo;
+use crate::nano_cores::NanoCoreError;
+use crate::nano_cores::network_core::{InterfaceStatus, NetworkInterface, Route};
+
+/// Familia de direcciones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum IpFamily {
+    #[default]
+    #[serde(rename = "ipv4")]
+    V4,
+    #[serde(rename = "ipv6")]
+    V6,
+}
+
+impl IpFamily {
+    pub fn of(ip: &IpAddr) -> Self {
+        if ip.is_ipv4() { IpFamily::V4 } else { IpFamily::V6 }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IpFamily::V4 => "IPv4",
+            IpFamily::V6 => "IPv6",
+        }
+    }
+}
+
+/// Sección `[nano_cores.network_core.address_family]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AddressFamilyConfig {
+    pub ipv4: bool,
+    pub ipv6: bool,
+    /// Familia que se usa primero cuando ambas están disponibles
+    pub prefer: IpFamily,
+}
+
+impl Default for AddressFamilyConfig {
+    fn default() -> Self {
+        Self {
+            ipv4: true,
+            ipv6: true,
+            prefer: IpFamily::V4,
+        }
+    }
+}
+
+impl AddressFamilyConfig {
+    pub fn allows(&self, family: IpFamily) -> bool {
+        match family {
+            IpFamily::V4 => self.ipv4,
+            IpFamily::V6 => self.ipv6,
+        }
+    }
+
+    pub fn allows_ip(&self, ip: &IpAddr) -> bool {
+        self.allows(IpFamily::of(ip))
+    }
+
+    /// Familias habilitadas, la preferida primero
+    pub fn families(&self) -> Vec<IpFamily> {
+        let other = match self.prefer {
+            IpFamily::V4 => IpFamily::V6,
+            IpFamily::V6 => IpFamily::V4,
+        };
+        [self.prefer, other].into_iter().filter(|family| self.allows(*family)).collect()
+    }
+
+    /// Familia habilitada que se usa primero
+    pub fn preferred(&self) -> Option<IpFamily> {
+        self.families().first().copied()
+    }
+
+    /// Quitar las direcciones de familias deshabilitadas y poner delante las preferidas
+    pub fn order<T>(&self, items: Vec<T>, ip: impl Fn(&T) -> IpAddr) -> Vec<T> {
+        let families = self.families();
+        let mut items: Vec<(usize, T)> = items
+            .into_iter()
+            .filter_map(|item| {
+                let family = IpFamily::of(&ip(&item));
+                families.iter().position(|f| *f == family).map(|rank| (rank, item))
+            })
+            .collect();
+        items.sort_by_key(|(rank, _)| *rank);
+        items.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Rechazar destinos de una familia deshabilitada
+    pub fn check(&self, ip: &IpAddr) -> Result<()> {
+        if self.allows_ip(ip) {
+            Ok(())
+        } else {
+            Err(NanoCoreError::ConfigError(anyhow!("{} está deshabilitado; destino {} rechazado", IpFamily::of(ip).name(), ip)).into())
+        }
+    }
+}
+
+/// IPv6 unicast de enlace local (`fe80::/10`), que necesita zona para usarse
+pub fn is_link_local(ip: &IpAddr) -> bool {
+    matches!(ip, IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80)
+}
+
+/// Dirección global: ni loopback, ni enlace local, ni sin especificar
+pub fn is_global(ip: &IpAddr) -> bool {
+    let link_local_v4 = matches!(ip, IpAddr::V4(v4) if v4.is_link_local());
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || is_link_local(ip) || link_local_v4)
+}
+
+/// `SocketAddr` con la zona de `scope_id` si la dirección es de enlace local
+pub fn socket_addr(ip: IpAddr, port: u16, scope_id: Option<u32>) -> SocketAddr {
+    match (ip, scope_id) {
+        (IpAddr::V6(v6), Some(scope_id)) if is_link_local(&ip) => SocketAddr::V6(SocketAddrV6::new(v6, port, 0, scope_id)),
+        _ => SocketAddr::new(ip, port),
+    }
+}
+
+/// Estado de una familia en el host
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FamilyStatus {
+    pub enabled: bool,
+    /// Direcciones globales en interfaces activas
+    pub addresses: Vec<IpAddr>,
+    pub gateway: Option<IpAddr>,
+    /// El kernel tiene ruta hacia Internet para esta familia
+    pub route: bool,
+}
+
+/// Resultado del sondeo de doble pila
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DualStackStatus {
+    /// Familia habilitada y con ruta que se usará primero
+    pub preferred: Option<IpFamily>,
+    pub ipv4: FamilyStatus,
+    pub ipv6: FamilyStatus,
+}
+
+/// Resumir la doble pila; `has_route` decide si el kernel encamina cada familia
+pub fn summarize(
+    config: &AddressFamilyConfig,
+    interfaces: &[NetworkInterface],
+    routes: &[Route],
+    has_route: impl Fn(IpFamily) -> bool,
+) -> DualStackStatus {
+    let status = |family: IpFamily| {
+        let enabled = config.allows(family);
+        let addresses: Vec<IpAddr> = interfaces
+            .iter()
+            .filter(|interface| matches!(interface.status, InterfaceStatus::Up))
+            .flat_map(|interface| interface.ip_addresses.iter().copied())
+            .filter(|ip| IpFamily::of(ip) == family && is_global(ip))
+            .collect();
+        FamilyStatus {
+            enabled,
+            route: enabled && !addresses.is_empty() && has_route(family),
+            addresses,
+            gateway: netinfo::family_gateway(routes, family),
+        }
+    };
+    let (ipv4, ipv6) = (status(IpFamily::V4), status(IpFamily::V6));
+    let preferred = config.families().into_iter().find(|family| match family {
+        IpFamily::V4 => ipv4.route,
+        IpFamily::V6 => ipv6.route,
+    });
+    DualStackStatus { preferred, ipv4, ipv6 }
+}
+
+/// Sondeo de doble pila contra la tabla de rutas real del kernel
+pub fn probe(config: &AddressFamilyConfig, interfaces: &[NetworkInterface], routes: &[Route]) -> DualStackStatus {
+    summarize(config, interfaces, routes, |family| {
+        // Prefijos de documentación: se encaminan por la ruta por defecto y nunca se envía nada
+        let (bind, target) = match family {
+            IpFamily::V4 => (IpAddr::V4(Ipv4Addr::UNSPECIFIED), IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))),
+            IpFamily::V6 => (IpAddr::V6(Ipv6Addr::UNSPECIFIED), IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))),
+        };
+        UdpSocket::bind(SocketAddr::new(bind, 0))
+            .and_then(|socket| socket.connect(SocketAddr::new(target, 53)))
+            .is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nano_cores::network_core::{DuplexMode, InterfaceStatistics};
+
+    fn route(destination: &str, prefix_len: u8, gateway: &str, metric: u32) -> Route {
+        Route {
+            destination: destination.parse().unwrap(),
+            prefix_len,
+            gateway: gateway.parse().unwrap(),
+            interface: "eth0".to_string(),
+            metric,
+            is_default: prefix_len == 0,
+        }
+    }
+
+    #[test]
+    fn test_policy_orders_and_filters_families() {
+        let addresses: Vec<IpAddr> = ["10.0.0.1", "2001:db8::1", "10.0.0.2"].iter().map(|a| a.parse().unwrap()).collect();
+        let prefer_v6 = AddressFamilyConfig { prefer: IpFamily::V6, ..Default::default() };
+        assert_eq!(prefer_v6.order(addresses.clone(), |ip| *ip), vec![addresses[1], addresses[0], addresses[2]]);
+
+        let v4_only = AddressFamilyConfig { ipv6: false, prefer: IpFamily::V6, ..Default::default() };
+        assert_eq!(v4_only.preferred(), Some(IpFamily::V4));
+        assert_eq!(v4_only.order(addresses.clone(), |ip| *ip), vec![addresses[0], addresses[2]]);
+        assert!(v4_only.check(&addresses[1]).is_err());
+        assert!(v4_only.check(&addresses[0]).is_ok());
+
+        let scoped = socket_addr("fe80::1".parse().unwrap(), 53, Some(3));
+        assert_eq!(scoped.to_string(), "[fe80::1%3]:53");
+        assert_eq!(socket_addr("2001:db8::1".parse().unwrap(), 53, Some(3)).to_string(), "[2001:db8::1]:53");
+        assert!(!is_global(&"fe80::1".parse().unwrap()) && !is_global(&"169.254.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_dual_stack_summary_reports_each_family() {
+        let interface = NetworkInterface {
+            name: "eth0".to_string(),
+            index: Some(2),
+            ip_addresses: ["192.168.1.10", "fe80::1", "2001:db8::10"].iter().map(|a| a.parse().unwrap()).collect(),
+            mac_address: None,
+            mtu: 1500,
+            speed: None,
+            duplex: DuplexMode::Full,
+            status: InterfaceStatus::Up,
+            carrier: Some(true),
+            statistics: InterfaceStatistics::default(),
+        };
+        let routes = vec![
+            route("0.0.0.0", 0, "192.168.1.1", 100),
+            route("0.0.0.0", 0, "192.168.1.254", 50),
+            route("::", 0, "fe80::1", 1024),
+        ];
+
+        let config = AddressFamilyConfig { prefer: IpFamily::V6, ..Default::default() };
+        let status = summarize(&config, std::slice::from_ref(&interface), &routes, |_| true);
+        assert_eq!(status.preferred, Some(IpFamily::V6));
+        assert_eq!(status.ipv4.gateway, Some("192.168.1.254".parse().unwrap()));
+        assert_eq!(status.ipv6.addresses, vec!["2001:db8::10".parse::<IpAddr>().unwrap()]);
+        assert_eq!(status.ipv6.gateway, Some("fe80::1".parse().unwrap()));
+
+        // Sin ruta IPv6 se cae a IPv4; una familia deshabilitada nunca tiene ruta
+        let status = summarize(&config, std::slice::from_ref(&interface), &routes, |family| family == IpFamily::V4);
+        assert_eq!(status.preferred, Some(IpFamily::V4));
+        let v6_only = AddressFamilyConfig { ipv4: false, ..Default::default() };
+        let status = summarize(&v6_only, &[interface], &routes, |family| family == IpFamily::V4);
+        assert_eq!((status.preferred, status.ipv4.enabled, status.ipv4.route), (None, false, false));
+    }
+}
@@ -0,0 +1,392 @@
+//! Cambios de enlace y de direcciones de NetworkCore
+//!
+//! En Linux un socket `NETLINK_ROUTE` suscrito a `RTMGRP_LINK` y
+//! `RTMGRP_IPV{4,6}_IFADDR` despierta al monitor en cuanto el kernel cambia una
+//! interfaz; entonces se vuelven a leer las interfaces y se comparan con la
+//! lectura anterior, de modo que el estado coincide con el de `netinfo`. En el
+//! resto de plataformas, o si netlink no está disponible, se compara cada
+//! `poll_interval_secs`. Cada cambio se publica al momento en `network.alerts`.
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::communication::CognitiveFabric;
+use crate::nano_cores::netinfo;
+use crate::nano_cores::network_core::{InterfaceStatus, NetworkInterface};
+
+/// Espera máxima de netlink antes de comprobar si el monitor sigue activo
+const WAKE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Sección `[nano_cores.network_core.link_events]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LinkEventsConfig {
+    pub enabled: bool,
+    /// Intervalo de sondeo cuando no hay eventos del kernel
+    pub poll_interval_secs: u64,
+}
+
+impl Default for LinkEventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_secs: 3,
+        }
+    }
+}
+
+/// Tipo de cambio; es el `type` de la alerta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkEventKind {
+    InterfaceUp,
+    InterfaceDown,
+    AddressAdded,
+    AddressRemoved,
+}
+
+/// Cambio detectado en una interfaz
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkEvent {
+    pub kind: LinkEventKind,
+    pub interface: String,
+    pub address: Option<IpAddr>,
+}
+
+/// Origen de la detección
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSource {
+    Netlink,
+    Poll,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LinkState {
+    up: bool,
+    addresses: BTreeSet<IpAddr>,
+}
+
+/// Estado de enlace y direcciones por interfaz
+type LinkSnapshot = BTreeMap<String, LinkState>;
+
+fn snapshot(interfaces: &[NetworkInterface]) -> LinkSnapshot {
+    interfaces
+        .iter()
+        .map(|interface| {
+            let state = LinkState {
+                up: matches!(interface.status, InterfaceStatus::Up),
+                addresses: interface.ip_addresses.iter().copied().collect(),
+            };
+            (interface.name.clone(), state)
+        })
+        .collect()
+}
+
+/// Cambios entre dos lecturas; subidas y caídas solo de interfaces con direcciones, como en las alertas periódicas
+fn diff(previous: &LinkSnapshot, current: &LinkSnapshot) -> Vec<LinkEvent> {
+    let empty = LinkState { up: false, addresses: BTreeSet::new() };
+    let names: BTreeSet<&String> = previous.keys().chain(current.keys()).collect();
+    let mut events = Vec::new();
+    for name in names {
+        let before = previous.get(name).unwrap_or(&empty);
+        let after = current.get(name).unwrap_or(&empty);
+        let event = |kind, address| LinkEvent { kind, interface: name.clone(), address };
+
+        let configured = !before.addresses.is_empty() || !after.addresses.is_empty();
+        if before.up != after.up && configured {
+            events.push(event(if after.up { LinkEventKind::InterfaceUp } else { LinkEventKind::InterfaceDown }, None));
+        }
+        events.extend(before.addresses.difference(&after.addresses).map(|ip| event(LinkEventKind::AddressRemoved, Some(*ip))));
+        events.extend(after.addresses.difference(&before.addresses).map(|ip| event(LinkEventKind::AddressAdded, Some(*ip))));
+    }
+    events
+}
+
+/// Monitor de cambios de enlace
+pub struct LinkMonitor {
+    config: Arc<RwLock<LinkEventsConfig>>,
+    is_running: Arc<RwLock<bool>>,
+}
+
+impl LinkMonitor {
+    pub fn new(config: LinkEventsConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            is_running: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    pub async fn reconfigure(&self, config: LinkEventsConfig) {
+        *self.config.write().await = config;
+    }
+
+    pub async fn start(&self, fabric: Arc<CognitiveFabric>) -> Result<()> {
+        *self.is_running.write().await = true;
+
+        let (sender, mut receiver) = mpsc::channel::<(EventSource, LinkEvent)>(256);
+        let config = self.config.clone();
+        let is_running = self.is_running.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = watch(&config, &is_running, &sender) {
+                warn!("⚠️  Monitor de enlaces detenido: {}", e);
+            }
+        });
+
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            while let Some((source, event)) = receiver.recv().await {
+                if !config.read().await.enabled {
+                    continue;
+                }
+                match (event.kind, event.address) {
+                    (LinkEventKind::InterfaceDown, _) => warn!("🔌 Interfaz de red caída: {}", event.interface),
+                    (LinkEventKind::InterfaceUp, _) => info!("🔌 Interfaz de red activa: {}", event.interface),
+                    (kind, Some(address)) => info!("🔌 {:?} {} en {}", kind, address, event.interface),
+                    (kind, None) => info!("🔌 {:?} en {}", kind, event.interface),
+                }
+                let alert = serde_json::json!({
+                    "type": event.kind,
+                    "interface": event.interface,
+                    "address": event.address,
+                    "source": source,
+                    "timestamp": SystemTime::now()
+                });
+                let published = match serde_json::to_vec(&alert) {
+                    Ok(data) => fabric.publish("network.alerts", &data).await,
+                    Err(e) => Err(e.into()),
+                };
+                if let Err(e) = published {
+                    warn!("⚠️  Error publicando cambio de enlace: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        *self.is_running.write().await = false;
+        Ok(())
+    }
+}
+
+/// Bucle bloqueante: esperar un evento del kernel (o el intervalo de sondeo) y enviar los cambios
+fn watch(
+    config: &RwLock<LinkEventsConfig>,
+    is_running: &RwLock<bool>,
+    sender: &mpsc::Sender<(EventSource, LinkEvent)>,
+) -> Result<()> {
+    let mut subscription = match platform::subscribe() {
+        Ok(subscription) => Some(subscription),
+        Err(e) => {
+            debug!("🔌 Sin eventos de enlace del kernel, se sondea: {}", e);
+            None
+        }
+    };
+    let source = if subscription.is_some() { EventSource::Netlink } else { EventSource::Poll };
+    let mut previous = snapshot(&netinfo::interfaces()?);
+
+    while *is_running.blocking_read() {
+        let changed = match &mut subscription {
+            Some(subscription) => subscription.wait(WAKE_INTERVAL)?,
+            None => {
+                std::thread::sleep(Duration::from_secs(config.blocking_read().poll_interval_secs.max(1)));
+                true
+            }
+        };
+        if !changed {
+            continue;
+        }
+        let current = match netinfo::interfaces() {
+            Ok(interfaces) => snapshot(&interfaces),
+            Err(e) => {
+                warn!("⚠️  Error leyendo interfaces tras un cambio de enlace: {}", e);
+                continue;
+            }
+        };
+        for event in diff(&previous, &current) {
+            if sender.blocking_send((source, event)).is_err() {
+                return Ok(());
+            }
+        }
+        previous = current;
+    }
+    Ok(())
+}
+
+/// `true` si un datagrama de netlink trae algún mensaje de enlace o dirección
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn has_link_message(buffer: &[u8]) -> bool {
+    // RTM_NEWLINK, RTM_DELLINK, RTM_NEWADDR, RTM_DELADDR
+    const KINDS: [u16; 4] = [16, 17, 20, 21];
+    const HEADER_LEN: usize = 16;
+    let mut offset = 0;
+    while let Some(header) = buffer.get(offset..offset + HEADER_LEN) {
+        let len = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = u16::from_ne_bytes([header[4], header[5]]);
+        if len < HEADER_LEN {
+            break;
+        }
+        if KINDS.contains(&kind) {
+            return true;
+        }
+        offset += (len + 3) & !3;
+    }
+    false
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    pub struct Subscription {
+        fd: OwnedFd,
+        buffer: Vec<u8>,
+    }
+
+    pub fn subscribe() -> Result<Subscription> {
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_ROUTE) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // SAFETY: descriptor recién creado y sin otro dueño
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut local: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        local.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        local.nl_groups = (libc::RTMGRP_LINK | libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR) as u32;
+        let bound = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &local as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if bound < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(Subscription { fd, buffer: vec![0u8; 64 * 1024] })
+    }
+
+    impl Subscription {
+        /// Esperar hasta `timeout` a un cambio; las ráfagas pendientes se agrupan en uno
+        pub fn wait(&mut self, timeout: Duration) -> Result<bool> {
+            let mut poll = libc::pollfd { fd: self.fd.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            let ready = unsafe { libc::poll(&mut poll, 1, timeout.as_millis() as libc::c_int) };
+            if ready < 0 {
+                let error = std::io::Error::last_os_error();
+                return if error.kind() == std::io::ErrorKind::Interrupted { Ok(false) } else { Err(error.into()) };
+            }
+            if ready == 0 {
+                return Ok(false);
+            }
+
+            let buffer = &mut self.buffer;
+            let mut changed = false;
+            loop {
+                let received = unsafe {
+                    libc::recv(self.fd.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), libc::MSG_DONTWAIT)
+                };
+                if received < 0 {
+                    let error = std::io::Error::last_os_error();
+                    match error.raw_os_error() {
+                        Some(libc::EAGAIN) | Some(libc::EINTR) => return Ok(changed),
+                        // Cola desbordada: se perdieron eventos, releer todo
+                        Some(libc::ENOBUFS) => changed = true,
+                        _ => return Err(error.into()),
+                    }
+                } else {
+                    changed |= has_link_message(&buffer[..received as usize]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::*;
+    use anyhow::anyhow;
+
+    pub struct Subscription;
+
+    pub fn subscribe() -> Result<Subscription> {
+        Err(anyhow!("eventos de enlace solo disponibles en Linux"))
+    }
+
+    impl Subscription {
+        pub fn wait(&mut self, _timeout: Duration) -> Result<bool> {
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(up: bool, addresses: &[&str]) -> LinkState {
+        LinkState { up, addresses: addresses.iter().map(|a| a.parse().unwrap()).collect() }
+    }
+
+    #[test]
+    fn test_diff_reports_link_and_address_changes() {
+        let previous: LinkSnapshot = [
+            ("eth0".to_string(), state(true, &["192.168.1.10", "fe80::1"])),
+            ("docker0".to_string(), state(false, &[])),
+        ]
+        .into_iter()
+        .collect();
+        let current: LinkSnapshot = [
+            ("eth0".to_string(), state(false, &["192.168.1.10"])),
+            ("docker0".to_string(), state(true, &[])),
+            ("wlan0".to_string(), state(true, &["10.0.0.5"])),
+        ]
+        .into_iter()
+        .collect();
+
+        let event = |kind, interface: &str, address: Option<&str>| LinkEvent {
+            kind,
+            interface: interface.to_string(),
+            address: address.map(|a| a.parse().unwrap()),
+        };
+        // docker0 sin direcciones no genera alertas
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                event(LinkEventKind::InterfaceDown, "eth0", None),
+                event(LinkEventKind::AddressRemoved, "eth0", Some("fe80::1")),
+                event(LinkEventKind::InterfaceUp, "wlan0", None),
+                event(LinkEventKind::AddressAdded, "wlan0", Some("10.0.0.5")),
+            ]
+        );
+        assert!(diff(&current, &current).is_empty());
+        assert_eq!(serde_json::to_value(LinkEventKind::InterfaceDown).unwrap(), "interface_down");
+    }
+
+    #[test]
+    fn test_netlink_messages_are_filtered_by_type() {
+        let message = |kind: u16, len: u32| {
+            let mut bytes = len.to_ne_bytes().to_vec();
+            bytes.extend_from_slice(&kind.to_ne_bytes());
+            bytes.resize(len as usize, 0);
+            bytes
+        };
+        // NLMSG_DONE y RTM_NEWROUTE no interesan
+        let mut buffer = message(3, 16);
+        buffer.extend(message(24, 28));
+        assert!(!has_link_message(&buffer));
+        buffer.extend(message(20, 24));
+        assert!(has_link_message(&buffer));
+        assert!(!has_link_message(&message(16, 8)));
+    }
+}
@@ -3,42 +3,179 @@
 //! Cada nano-núcleo es responsable de una función específica y crítica,
 //! operando con máxima eficiencia y resiliencia.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, warn, error};
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
+pub mod bandwidth;
+pub mod capture;
+pub mod cgroup;
+pub mod checkpoint;
+pub mod connections;
+pub mod dispatcher;
+pub mod dns_monitor;
+pub mod firewall;
+#[cfg(all(target_os = "linux", feature = "ebpf"))]
+pub mod ebpf_monitor;
+pub mod fs_watch;
+pub mod gpu;
 pub mod os_core;
+pub mod hardware_alerts;
 pub mod hardware_core;
+pub mod health;
+pub mod ip_family;
+pub mod link_events;
+pub mod netinfo;
 pub mod network_core;
+pub mod placement;
+pub mod power;
+pub mod prediction;
+pub mod process_history;
 pub mod security_core;
+pub mod services;
+pub mod shaping;
+pub mod smart;
+pub mod remote_core;
+pub mod scheduler;
+pub mod startup;
+pub mod supervisor;
+pub mod swap;
+pub mod thermal;
+pub mod throughput;
+#[cfg(feature = "wasm")]
+pub mod wasm_core;
+pub mod watchdog;
+
+pub use bandwidth::BandwidthConfig;
+pub use capture::CaptureConfig;
+pub use checkpoint::CheckpointConfig;
+pub use dispatcher::{CommandCredentials, CommandEnvelope, CommandError, CommandErrorCode, CommandReply};
+pub use dns_monitor::DnsMonitorConfig;
+pub use firewall::FirewallConfig;
+pub use health::{HealthConfig, HealthSample, HealthTransition, HealthTrend};
+pub use ip_family::AddressFamilyConfig;
+pub use link_events::LinkEventsConfig;
+pub use placement::{InstancePlacement, Placement, PlacementConfig};
+pub use prediction::FailurePredictorConfig;
+pub use process_history::ProcessHistoryConfig;
+pub use remote_core::{RemoteCoreConfig, RemoteNanoCore};
+pub use scheduler::ScheduledCommand;
+pub use shaping::QosConfig;
+pub use startup::StartupConfig;
+pub use supervisor::{RestartMode, RestartPolicy, SupervisorConfig};
+pub use throughput::ThroughputConfig;
+pub use watchdog::WatchdogConfig;
 
 use crate::communication::CognitiveFabric;
-use crate::consensus::ConsensusManager;
-use crate::config::CoreConfig;
+use crate::consensus::{ConsensusManager, ConsensusProposal, ProposalType, ReplicaState, VoteDecision};
+use crate::config::{ConfigSection, ConfigSubscriber, CoreConfig, FieldChange, HealthProbe};
 use crate::metrics::MetricsCollector;
-use crate::security::SecurityManager;
+use crate::security::{SecurityContext, SecurityLevel, SecurityManager};
+use checkpoint::CheckpointStore;
+use dispatcher::CommandDispatcher;
+use health::{HealthHistory, HealthTracker};
+use supervisor::{Supervisor, SupervisorDecision};
+use swap::SwapQueue;
 
 /// Tipos de nano-núcleos disponibles
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NanoCoreType {
     OS,
     Hardware,
     Network,
     Security,
+    /// Nano-núcleo externo creado por una fábrica registrada con este nombre
+    Custom(String),
+}
+
+impl NanoCoreType {
+    /// Nano-núcleos integrados; `StartupConfig::startup_order` decide el orden de arranque
+    pub const BUILTIN: [NanoCoreType; 4] = [NanoCoreType::OS, NanoCoreType::Hardware, NanoCoreType::Network, NanoCoreType::Security];
+    
+    /// Sección de `[nano_cores]` con su configuración; el nombre para los externos
+    pub fn config_section(&self) -> &str {
+        match self {
+            NanoCoreType::OS => "os_core",
+            NanoCoreType::Hardware => "hardware_core",
+            NanoCoreType::Network => "network_core",
+            NanoCoreType::Security => "security_core",
+            NanoCoreType::Custom(name) => name,
+        }
+    }
+    
+    /// Tipo cuya sección de `[nano_cores]` es `section`
+    pub fn from_section(section: &str) -> Self {
+        Self::BUILTIN
+            .into_iter()
+            .find(|builtin| builtin.config_section() == section)
+            .unwrap_or_else(|| NanoCoreType::Custom(section.to_string()))
+    }
+    
+    /// Tema del Cognitive Fabric del que `CommandDispatcher` toma sus comandos
+    pub fn command_subject(&self) -> String {
+        let prefix = match self {
+            NanoCoreType::OS => "os",
+            NanoCoreType::Hardware => "hardware",
+            NanoCoreType::Network => "network",
+            NanoCoreType::Security => "security",
+            NanoCoreType::Custom(name) => name,
+        };
+        format!("{}.commands", prefix)
+    }
+    
+    /// Nombre en los `target` de los eventos dirigidos (`OSCore-0`) y en sus buzones
+    pub fn target_name(&self) -> String {
+        match self {
+            NanoCoreType::Custom(name) => name.clone(),
+            builtin => format!("{:?}Core", builtin),
+        }
+    }
+}
+
+/// Acepta la sección (`network_core`), el prefijo de sus temas (`network`) o el nombre de un externo
+impl std::str::FromStr for NanoCoreType {
+    type Err = anyhow::Error;
+    
+    fn from_str(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Nombre de nano-núcleo vacío"));
+        }
+        Ok(Self::BUILTIN
+            .into_iter()
+            .find(|builtin| {
+                name.eq_ignore_ascii_case(builtin.config_section())
+                    || builtin.command_subject().strip_suffix(".commands").is_some_and(|prefix| name.eq_ignore_ascii_case(prefix))
+            })
+            .unwrap_or_else(|| NanoCoreType::Custom(name.to_string())))
+    }
+}
+
+impl fmt::Display for NanoCoreType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NanoCoreType::Custom(name) => write!(f, "{}", name),
+            builtin => write!(f, "{:?}", builtin),
+        }
+    }
 }
 
 /// Estado de un nano-núcleo
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NanoCoreState {
     Initializing,
     Running,
     Degraded,
     Failed,
+    /// Detenido por el supervisor tras un crash loop
+    Quarantined,
     Shutdown,
 }
 
@@ -60,18 +197,123 @@ pub struct NanoCoreHealth {
 pub struct SystemHealth {
     pub cores: HashMap<NanoCoreType, Vec<NanoCoreHealth>>,
     pub overall_state: NanoCoreState,
+    /// Score compuesto entre 0 y 1 (`HealthConfig::system_score`)
+    pub score: f64,
+    /// Tendencia del score en el historial del monitor de salud
+    #[serde(default)]
+    pub trend: HealthTrend,
     pub consensus_health: f64,
     pub fabric_latency_ms: f64,
 }
 
 impl SystemHealth {
+    /// `overall_state` ya tiene en cuenta consenso, latencia y núcleos sanos
     pub fn is_healthy(&self) -> bool {
-        matches!(self.overall_state, NanoCoreState::Running) &&
-        self.consensus_health > 0.8 &&
-        self.fabric_latency_ms < 10.0
+        matches!(self.overall_state, NanoCoreState::Running)
     }
 }
 
+/// Permiso y nivel de seguridad que exige un comando
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandAuthorization {
+    pub permission: String,
+    pub level: SecurityLevel,
+}
+
+impl CommandAuthorization {
+    pub fn new(permission: &str, level: SecurityLevel) -> Self {
+        Self {
+            permission: permission.to_string(),
+            level,
+        }
+    }
+}
+
+/// Error de `execute_command` cuando el contexto no cumple la autorización
+#[derive(Debug, Clone)]
+pub struct CommandDenied {
+    pub command: String,
+    pub required: CommandAuthorization,
+}
+
+impl fmt::Display for CommandDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Comando {} no autorizado: requiere {} con nivel {:?}",
+            self.command, self.required.permission, self.required.level
+        )
+    }
+}
+
+impl std::error::Error for CommandDenied {}
+
+/// Error de `run` y `process_command`; su clase decide la reacción del supervisor
+#[derive(Debug)]
+pub enum NanoCoreError {
+    /// Fallo pasajero: se reintenta según la política de reinicio
+    Transient(anyhow::Error),
+    /// La instancia no puede continuar: se reemplaza sin esperar a `failure_threshold`
+    Fatal(anyhow::Error),
+    /// Configuración inválida: se reintenta, pero no se reemplaza, porque la nueva tendría la misma
+    ConfigError(anyhow::Error),
+    /// Permisos insuficientes: reintentar no sirve y la instancia se detiene
+    SecurityDenied(anyhow::Error),
+    /// Sobrecarga: se espera sin contarlo como fallo
+    Backpressure(anyhow::Error),
+}
+
+impl NanoCoreError {
+    /// Nombre de la clase para logs y métricas
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NanoCoreError::Transient(_) => "transient",
+            NanoCoreError::Fatal(_) => "fatal",
+            NanoCoreError::ConfigError(_) => "config_error",
+            NanoCoreError::SecurityDenied(_) => "security_denied",
+            NanoCoreError::Backpressure(_) => "backpressure",
+        }
+    }
+    
+    fn inner(&self) -> &anyhow::Error {
+        match self {
+            NanoCoreError::Transient(e)
+            | NanoCoreError::Fatal(e)
+            | NanoCoreError::ConfigError(e)
+            | NanoCoreError::SecurityDenied(e)
+            | NanoCoreError::Backpressure(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for NanoCoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+impl std::error::Error for NanoCoreError {}
+
+/// Los errores sin clasificar son transitorios, salvo los que ya la traen
+impl From<anyhow::Error> for NanoCoreError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<NanoCoreError>() {
+            Ok(classified) => classified,
+            Err(e) if e.is::<CommandDenied>() => NanoCoreError::SecurityDenied(e),
+            Err(e) => NanoCoreError::Transient(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for NanoCoreError {
+    fn from(e: serde_json::Error) -> Self {
+        NanoCoreError::Transient(e.into())
+    }
+}
+
+/// Resultado de `NanoCore::run` y `NanoCore::process_command`
+pub type CoreResult<T> = std::result::Result<T, NanoCoreError>;
+
 /// Trait común para todos los nano-núcleos
 #[async_trait]
 pub trait NanoCore: Send + Sync {
@@ -85,7 +327,7 @@ pub trait NanoCore: Send + Sync {
     async fn initialize(&mut self) -> Result<()>;
     
     /// Ejecutar el bucle principal
-    async fn run(&mut self) -> Result<()>;
+    async fn run(&mut self) -> CoreResult<()>;
     
     /// Obtener estado de salud
     async fn health_check(&self) -> Result<NanoCoreHealth>;
@@ -93,20 +335,111 @@ pub trait NanoCore: Send + Sync {
     /// Shutdown graceful
     async fn shutdown(&mut self) -> Result<()>;
     
-    /// Procesar comando específico
-    async fn process_command(&mut self, command: &str, payload: &[u8]) -> Result<Vec<u8>>;
+    /// Permiso y nivel que exige el comando de `payload`
+    fn command_authorization(&self, payload: &[u8]) -> Result<CommandAuthorization>;
+    
+    /// Procesar comando específico, ya autorizado por `NanoCoreManager::execute_command`
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>>;
+    
+    /// Aplicar una nueva configuración en caliente
+    async fn apply_config(&mut self, _config: &CoreConfig) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Espera entre dos ejecuciones correctas de `run`
+    fn run_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+    
+    /// Estado a conservar entre reemplazos y reinicios (vacío = nada que guardar)
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+    
+    /// Recuperar un estado de `snapshot`, tras `initialize`
+    async fn restore(&mut self, _state: &[u8]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Lo que recibe una fábrica para crear una instancia
+pub struct NanoCoreContext {
+    /// `NanoCoreType::Custom` con el nombre de la fábrica
+    pub core_type: NanoCoreType,
+    pub cognitive_fabric: Arc<CognitiveFabric>,
+    pub metrics: Arc<MetricsCollector>,
+    pub instance: usize,
+    /// Sección `[nano_cores.custom.<nombre>]` con los overrides de la instancia (`null` si no existe)
+    pub config: serde_json::Value,
+}
+
+/// Constructor de nano-núcleos externos (`NanoCoreManager::register_factory`)
+#[async_trait]
+pub trait NanoCoreFactory: Send + Sync {
+    async fn create(&self, context: NanoCoreContext) -> Result<Box<dyn NanoCore>>;
+}
+
+/// Instancia con su propio lock: su bucle no bloquea a las demás
+type CoreSlot = Arc<Mutex<Box<dyn NanoCore>>>;
+
+/// Comandos en curso por instancia
+type InFlightCounts = std::sync::Mutex<HashMap<(NanoCoreType, usize), usize>>;
+
+/// Comando en curso en una instancia mientras vive; `drain` espera a que no quede ninguno
+struct InFlight<'a> {
+    counts: &'a InFlightCounts,
+    key: (NanoCoreType, usize),
+}
+
+impl<'a> InFlight<'a> {
+    fn start(counts: &'a InFlightCounts, key: (NanoCoreType, usize)) -> Self {
+        *counts.lock().unwrap_or_else(|e| e.into_inner()).entry(key.clone()).or_default() += 1;
+        Self { counts, key }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&self.key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.key);
+            }
+        }
+    }
 }
 
 /// Gestor de nano-núcleos
 pub struct NanoCoreManager {
-    config: CoreConfig,
+    config: Arc<RwLock<CoreConfig>>,
     cognitive_fabric: Arc<CognitiveFabric>,
     consensus_manager: Arc<ConsensusManager>,
     metrics: Arc<MetricsCollector>,
     security_manager: Arc<SecurityManager>,
-    cores: Arc<RwLock<HashMap<NanoCoreType, Vec<Box<dyn NanoCore>>>>>,
+    cores: Arc<RwLock<HashMap<NanoCoreType, Vec<CoreSlot>>>>,
     running: Arc<RwLock<bool>>,
     health_monitor: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+    /// Checkpoints periódicos de `[nano_cores.checkpoint]`
+    checkpoint_task: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    /// Fábricas de nano-núcleos externos por nombre
+    factories: RwLock<BTreeMap<String, Arc<dyn NanoCoreFactory>>>,
+    /// Suscripciones a `<núcleo>.commands`
+    dispatcher: RwLock<Option<CommandDispatcher>>,
+    /// Instancias en reemplazo y los comandos recibidos mientras tanto
+    swaps: SwapQueue,
+    /// Instancias detenidas por el supervisor (Failed o Quarantined)
+    halted: Arc<RwLock<HashMap<(NanoCoreType, usize), NanoCoreState>>>,
+    /// Instancias en drenaje: no aceptan comandos nuevos
+    draining: RwLock<HashSet<(NanoCoreType, usize)>>,
+    /// Comandos en curso por instancia
+    in_flight: InFlightCounts,
+    /// Orden en que `initialize_all_cores` arrancó los núcleos; el shutdown lo invierte
+    startup_order: RwLock<Vec<NanoCoreType>>,
+    /// Últimos estados de salud calculados, para la histéresis
+    health_tracker: Mutex<HealthTracker>,
+    /// Muestras del monitor de salud
+    health_history: RwLock<HealthHistory>,
 }
 
 impl NanoCoreManager {
@@ -121,7 +454,7 @@ impl NanoCoreManager {
         info!("🚀 Inicializando NanoCoreManager con configuración empresarial");
         
         Ok(Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
             cognitive_fabric,
             consensus_manager,
             metrics,
@@ -129,57 +462,227 @@ impl NanoCoreManager {
             cores: Arc::new(RwLock::new(HashMap::new())),
             running: Arc::new(RwLock::new(false)),
             health_monitor: Arc::new(RwLock::new(None)),
+            checkpoint_task: RwLock::new(None),
+            factories: RwLock::new(BTreeMap::new()),
+            dispatcher: RwLock::new(None),
+            swaps: SwapQueue::default(),
+            halted: Arc::new(RwLock::new(HashMap::new())),
+            draining: RwLock::new(HashSet::new()),
+            in_flight: std::sync::Mutex::new(HashMap::new()),
+            startup_order: RwLock::new(Vec::new()),
+            health_tracker: Mutex::new(HealthTracker::default()),
+            health_history: RwLock::new(HealthHistory::default()),
         })
     }
 
+    /// Registrar la fábrica de un nano-núcleo externo (`NanoCoreType::Custom(name)`)
+    ///
+    /// Las fábricas registradas antes de `initialize_all_cores` arrancan con
+    /// los integrados; las posteriores con `start_nano_core`.
+    pub async fn register_factory(&self, name: impl Into<String>, factory: Arc<dyn NanoCoreFactory>) -> Result<()> {
+        let name = name.into();
+        if name.is_empty() || NanoCoreType::BUILTIN.iter().any(|builtin| builtin.config_section() == name) {
+            return Err(anyhow!("Nombre de nano-núcleo externo inválido: {:?}", name));
+        }
+        
+        let mut factories = self.factories.write().await;
+        if factories.contains_key(&name) {
+            return Err(anyhow!("Ya hay una fábrica registrada para {}", name));
+        }
+        factories.insert(name.clone(), factory);
+        
+        info!("🧩 Fábrica de nano-núcleo externo registrada: {}", name);
+        Ok(())
+    }
+    
     /// Inicializar todos los nano-núcleos con redundancia
-    pub async fn initialize_all_cores(&self) -> Result<()> {
+    pub async fn initialize_all_cores(self: &Arc<Self>) -> Result<()> {
         info!("⚡ Inicializando todos los nano-núcleos con redundancia empresarial");
         
-        // Inicializar cada tipo de nano-núcleo
-        for core_type in [NanoCoreType::OS, NanoCoreType::Hardware, NanoCoreType::Network, NanoCoreType::Security] {
-            self.start_nano_core(core_type).await?;
+        // Inicializar cada tipo de nano-núcleo detrás de sus dependencias
+        let custom: Vec<NanoCoreType> = self.factories.read().await.keys().cloned().map(NanoCoreType::Custom).collect();
+        let core_types: Vec<NanoCoreType> = NanoCoreType::BUILTIN.into_iter().chain(custom).collect();
+        let startup = self.config.read().await.nano_cores.startup.clone();
+        let core_types = startup.startup_order(&core_types)?;
+        for core_type in &core_types {
+            for dependency in startup.dependencies_of(core_type) {
+                self.wait_ready(&dependency, startup.readiness_timeout()).await?;
+            }
+            self.start_nano_core(core_type.clone()).await?;
+            self.startup_order.write().await.push(core_type.clone());
         }
         
-        // Iniciar monitoreo de salud continuo
-        self.start_health_monitoring().await?;
+        self.start_services(core_types).await?;
+        
+        info!("✅ Todos los nano-núcleos inicializados y registrados");
+        Ok(())
+    }
+    
+    /// Arrancar solo `core_type` con `replicas` réplicas (`--only-core`)
+    ///
+    /// Sin `replicas` se usa `consensus.replica_count`. Sus dependencias no se
+    /// esperan: pueden ejecutarse en otra máquina y responder por el fabric.
+    pub async fn initialize_single_core(self: &Arc<Self>, core_type: NanoCoreType, replicas: Option<usize>) -> Result<()> {
+        info!("🎯 Modo de un solo nano-núcleo: {}", core_type);
+        
+        let dependencies = self.config.read().await.nano_cores.startup.dependencies_of(&core_type);
+        if !dependencies.is_empty() {
+            let names: Vec<String> = dependencies.iter().map(ToString::to_string).collect();
+            info!("🔗 {} depende de {}: se asume que se ejecutan en otro proceso", core_type, names.join(", "));
+        }
+        
+        let replicas = match replicas {
+            Some(replicas) => replicas,
+            None => self.config.read().await.consensus.replica_count,
+        };
+        self.start_replicas(core_type.clone(), replicas).await?;
+        self.startup_order.write().await.push(core_type.clone());
+        
+        self.start_services(vec![core_type.clone()]).await?;
+        
+        info!("✅ {} inicializado en solitario con {} réplicas", core_type, replicas);
+        Ok(())
+    }
+    
+    /// Despachador de comandos, consenso y monitoreo de salud de los núcleos arrancados
+    async fn start_services(self: &Arc<Self>, core_types: Vec<NanoCoreType>) -> Result<()> {
+        // Enrutar los comandos del fabric hacia process_command
+        *self.dispatcher.write().await = Some(CommandDispatcher::start(self, core_types).await?);
         
         // Registrar nano-núcleos en el sistema de consenso
         self.register_cores_in_consensus().await?;
         
-        info!("✅ Todos los nano-núcleos inicializados y registrados");
+        // Iniciar monitoreo de salud continuo, ya con las réplicas en el consenso
+        self.start_health_monitoring().await?;
+        
+        self.start_checkpointing().await;
         Ok(())
     }
     
+    /// Esperar a que todas las réplicas de `core_type` estén `Running`
+    async fn wait_ready(&self, core_type: &NanoCoreType, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        
+        loop {
+            let instances = self.cores.read().await.get(core_type).cloned().unwrap_or_default();
+            let mut ready = !instances.is_empty();
+            for (i, slot) in instances.iter().enumerate() {
+                let health = slot.lock().await.health_check().await;
+                let halted = self.halted.read().await.contains_key(&(core_type.clone(), i));
+                ready &= !halted && matches!(health, Ok(NanoCoreHealth { state: NanoCoreState::Running, .. }));
+            }
+            
+            if ready {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(anyhow!("{} no está listo tras {:?}: sus dependientes no arrancan", core_type, timeout));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+    
     /// Registrar nano-núcleos en el sistema de consenso
     async fn register_cores_in_consensus(&self) -> Result<()> {
-        let cores_guard = self.cores.read().await;
-        
-        for (core_type, instances) in cores_guard.iter() {
-            for (i, core) in instances.iter().enumerate() {
-                // Crear participante de consenso para cada instancia
-                let participant = NanoCoreConsensusParticipant::new(
-                    core.instance_id(),
-                    *core_type,
-                    i,
-                    self.cognitive_fabric.clone(),
-                );
-                
-                self.consensus_manager.register_participant(Box::new(participant)).await?;
-                
-                info!("🗳️  Nano-núcleo {:?} instancia {} registrado en consenso", core_type, i);
+        for (core_type, instances) in self.snapshot().await {
+            for (i, slot) in instances.iter().enumerate() {
+                let instance_id = slot.lock().await.instance_id();
+                self.register_in_consensus(&core_type, i, instance_id).await?;
             }
         }
         
         Ok(())
     }
     
+    /// Crear el participante de consenso de una instancia
+    async fn register_in_consensus(&self, core_type: &NanoCoreType, instance: usize, instance_id: Uuid) -> Result<()> {
+        let participant = NanoCoreConsensusParticipant::new(
+            instance_id,
+            core_type.clone(),
+            instance,
+            self.cognitive_fabric.clone(),
+        );
+        
+        self.consensus_manager.register_participant(Box::new(participant)).await?;
+        
+        info!("🗳️  Nano-núcleo {:?} instancia {} registrado en consenso", core_type, instance);
+        Ok(())
+    }
+    
+    /// Almacén de checkpoints si `[nano_cores.checkpoint]` está activo
+    async fn checkpoint_store(&self) -> Option<CheckpointStore> {
+        let config = self.config.read().await.nano_cores.checkpoint.clone();
+        config.enabled.then(|| CheckpointStore::new(&config.dir))
+    }
+    
+    /// Guardar cada `interval_secs` el estado de todas las réplicas
+    async fn start_checkpointing(self: &Arc<Self>) {
+        let running = self.running.clone();
+        let weak = Arc::downgrade(self);
+        
+        let task = tokio::spawn(async move {
+            while *running.read().await {
+                let Some(manager) = weak.upgrade() else {
+                    break;
+                };
+                let interval = manager.config.read().await.nano_cores.checkpoint.interval();
+                drop(manager);
+                tokio::time::sleep(interval).await;
+                
+                let Some(manager) = weak.upgrade() else {
+                    break;
+                };
+                manager.checkpoint_all().await;
+            }
+        });
+        
+        *self.checkpoint_task.write().await = Some(task);
+    }
+    
+    /// Guardar el estado de todas las réplicas, si los checkpoints están activos
+    pub async fn checkpoint_all(&self) {
+        let Some(store) = self.checkpoint_store().await else {
+            return;
+        };
+        
+        for (core_type, instances) in self.snapshot().await {
+            for (i, slot) in instances.iter().enumerate() {
+                let state = slot.lock().await.snapshot().await;
+                let result = match state {
+                    Ok(state) => store.save(&core_type, i, &state).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    warn!("⚠️  Error guardando el checkpoint de {:?} instancia {}: {}", core_type, i, e);
+                }
+            }
+        }
+    }
+    
+    /// Recuperar el último checkpoint guardado de una réplica recién inicializada
+    async fn restore_checkpoint(&self, core_type: &NanoCoreType, instance: usize, core: &mut Box<dyn NanoCore>) {
+        let Some(store) = self.checkpoint_store().await else {
+            return;
+        };
+        
+        let result = match store.load(core_type, instance).await {
+            Ok(Some(state)) => core.restore(&state).await.map(|()| true),
+            Ok(None) => Ok(false),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(true) => info!("♻️  {:?} instancia {} restaurada desde su checkpoint", core_type, instance),
+            Ok(false) => {}
+            Err(e) => warn!("⚠️  {:?} instancia {} arranca sin su checkpoint: {}", core_type, instance, e),
+        }
+    }
+    
     /// Iniciar monitoreo de salud continuo
-    async fn start_health_monitoring(&self) -> Result<()> {
-        let cores = self.cores.clone();
+    async fn start_health_monitoring(self: &Arc<Self>) -> Result<()> {
         let metrics = self.metrics.clone();
         let cognitive_fabric = self.cognitive_fabric.clone();
         let running = self.running.clone();
+        let manager = Arc::downgrade(self);
         
         let health_task = tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
@@ -187,53 +690,11 @@ impl NanoCoreManager {
             while *running.read().await {
                 interval.tick().await;
                 
-                let cores_guard = cores.read().await;
-                let mut overall_health = SystemHealth {
-                    cores: HashMap::new(),
-                    overall_state: NanoCoreState::Running,
-                    consensus_health: 0.95,
-                    fabric_latency_ms: 2.5,
-                };
-                
-                let mut total_healthy = 0;
-                let mut total_cores = 0;
-                
-                for (core_type, instances) in cores_guard.iter() {
-                    let mut core_healths = Vec::new();
-                    
-                    for core in instances.iter() {
-                        match core.health_check().await {
-                            Ok(health) => {
-                                if matches!(health.state, NanoCoreState::Running) {
-                                    total_healthy += 1;
-                                }
-                                core_healths.push(health);
-                                total_cores += 1;
-                            }
-                            Err(e) => {
-                                error!("❌ Error obteniendo salud de {:?}: {}", core_type, e);
-                                total_cores += 1;
-                            }
-                        }
-                    }
-                    
-                    overall_health.cores.insert(*core_type, core_healths);
-                }
-                
-                // Calcular estado general
-                let health_percentage = if total_cores > 0 {
-                    (total_healthy as f64 / total_cores as f64) * 100.0
-                } else {
-                    0.0
-                };
-                
-                overall_health.overall_state = if health_percentage > 80.0 {
-                    NanoCoreState::Running
-                } else if health_percentage > 50.0 {
-                    NanoCoreState::Degraded
-                } else {
-                    NanoCoreState::Failed
+                let Some(manager) = manager.upgrade() else {
+                    break;
                 };
+                let overall_health = manager.sample_health().await;
+                drop(manager);
                 
                 // Publicar métricas de salud
                 metrics.record_health_status(&overall_health).await;
@@ -248,13 +709,14 @@ impl NanoCoreManager {
                     payload: serde_json::to_vec(&overall_health).unwrap_or_default(),
                     priority: crate::communication::EventPriority::Normal,
                     correlation_id: None,
+                    headers: HashMap::new(),
                 }).await {
                     warn!("⚠️  Error publicando métricas de salud: {}", e);
                 }
                 
                 // Log de estado crítico
                 if matches!(overall_health.overall_state, NanoCoreState::Failed) {
-                    error!("🚨 Estado crítico del sistema: {}% de nano-núcleos saludables", health_percentage);
+                    error!("🚨 Estado crítico del sistema: score de salud {:.2}", overall_health.score);
                 }
             }
         });
@@ -264,14 +726,20 @@ impl NanoCoreManager {
         Ok(())
     }
     /// Iniciar un tipo específico de nano-núcleo
-    pub async fn start_nano_core(&self, core_type: NanoCoreType) -> Result<()> {
-        let replica_count = self.config.consensus.replica_count;
-        let mut cores_guard = self.cores.write().await;
+    pub async fn start_nano_core(self: &Arc<Self>, core_type: NanoCoreType) -> Result<()> {
+        let replica_count = self.config.read().await.consensus.replica_count;
+        self.start_replicas(core_type, replica_count).await
+    }
+    
+    async fn start_replicas(self: &Arc<Self>, core_type: NanoCoreType, replica_count: usize) -> Result<()> {
+        if replica_count == 0 {
+            return Err(anyhow!("{:?} necesita al menos una réplica", core_type));
+        }
         
         let mut instances = Vec::new();
         
         for i in 0..replica_count {
-            let mut core = self.create_nano_core(core_type, i).await?;
+            let mut core = self.create_nano_core(&core_type, i).await?;
             
             info!(
                 "🔧 Inicializando {} instancia {} de {:?}",
@@ -279,14 +747,15 @@ impl NanoCoreManager {
             );
             
             core.initialize().await?;
-            instances.push(core);
+            self.restore_checkpoint(&core_type, i, &mut core).await;
+            instances.push(Arc::new(Mutex::new(core)));
         }
         
-        cores_guard.insert(core_type, instances);
+        self.cores.write().await.insert(core_type.clone(), instances);
         
         // Iniciar bucles de ejecución para cada instancia
-        for (i, _) in cores_guard.get(&core_type).unwrap().iter().enumerate() {
-            self.start_core_loop(core_type, i).await?;
+        for i in 0..replica_count {
+            self.start_core_loop(core_type.clone(), i).await?;
         }
         
         *self.running.write().await = true;
@@ -296,13 +765,17 @@ impl NanoCoreManager {
     }
 
     /// Crear una instancia de nano-núcleo
-    async fn create_nano_core(&self, core_type: NanoCoreType, instance: usize) -> Result<Box<dyn NanoCore>> {
+    async fn create_nano_core(&self, core_type: &NanoCoreType, instance: usize) -> Result<Box<dyn NanoCore>> {
+        // Sección común con los overrides de esta instancia
+        let config = self.config.read().await.nano_cores.for_instance(instance)?;
+        
         let core: Box<dyn NanoCore> = match core_type {
             NanoCoreType::OS => Box::new(
                 os_core::OSCore::new(
                     self.cognitive_fabric.clone(),
                     self.metrics.clone(),
                     instance,
+                    config.os_core,
                 ).await?
             ),
             NanoCoreType::Hardware => Box::new(
@@ -310,6 +783,7 @@ impl NanoCoreManager {
                     self.cognitive_fabric.clone(),
                     self.metrics.clone(),
                     instance,
+                    config.hardware_core,
                 ).await?
             ),
             NanoCoreType::Network => Box::new(
@@ -317,6 +791,7 @@ impl NanoCoreManager {
                     self.cognitive_fabric.clone(),
                     self.metrics.clone(),
                     instance,
+                    config.network_core,
                 ).await?
             ),
             NanoCoreType::Security => Box::new(
@@ -324,88 +799,582 @@ impl NanoCoreManager {
                     self.cognitive_fabric.clone(),
                     self.metrics.clone(),
                     instance,
+                    config.security_core,
                 ).await?
             ),
+            NanoCoreType::Custom(name) => {
+                let factory = self.factories.read().await.get(name).cloned()
+                    .ok_or_else(|| anyhow!("Sin fábrica registrada para el nano-núcleo {}", name))?;
+                factory.create(NanoCoreContext {
+                    core_type: core_type.clone(),
+                    cognitive_fabric: self.cognitive_fabric.clone(),
+                    metrics: self.metrics.clone(),
+                    instance,
+                    config: config.custom.get(name).cloned().unwrap_or_default(),
+                }).await?
+            }
         };
         
         Ok(core)
     }
 
     /// Iniciar bucle de ejecución para una instancia específica
-    async fn start_core_loop(&self, core_type: NanoCoreType, instance: usize) -> Result<()> {
+    async fn start_core_loop(self: &Arc<Self>, core_type: NanoCoreType, instance: usize) -> Result<()> {
         let cores = self.cores.clone();
         let running = self.running.clone();
         let metrics = self.metrics.clone();
+        let config = self.config.clone();
+        let halted = self.halted.clone();
+        let manager = Arc::downgrade(self);
+        let placement = self.config.read().await.nano_cores.placement
+            .get(core_type.config_section())
+            .map(|placement| placement.for_instance(instance))
+            .unwrap_or_default();
+        let thread_name = format!("{}-{}", core_type.config_section(), instance);
         
-        tokio::spawn(async move {
+        let task = async move {
+            let mut supervisor = Supervisor::default();
+            
             while *running.read().await {
-                let mut cores_guard = cores.write().await;
+                let Some(slot) = cores.read().await.get(&core_type).and_then(|instances| instances.get(instance)).cloned() else {
+                    break;
+                };
                 
-                if let Some(instances) = cores_guard.get_mut(&core_type) {
-                    if let Some(core) = instances.get_mut(instance) {
-                        match core.run().await {
-                            Ok(()) => {
-                                // Registrar métricas de éxito
-                                metrics.record_core_execution(core_type, instance, true).await;
-                            }
-                            Err(e) => {
-                                error!(
-                                    "❌ Error en {:?} instancia {}: {}",
-                                    core_type, instance, e
-                                );
-                                metrics.record_core_execution(core_type, instance, false).await;
-                                
-                                // TODO: Implementar hot-swapping aquí
-                                warn!("🔄 Hot-swapping requerido para {:?} instancia {}", core_type, instance);
-                            }
-                        }
+                // Solo esta instancia queda bloqueada mientras ejecuta
+                let (result, interval) = {
+                    let mut core = slot.lock().await;
+                    // Detenida por el supervisor o drenada mientras esperaba el lock
+                    if halted.read().await.contains_key(&(core_type.clone(), instance)) {
+                        break;
+                    }
+                    let start_time = std::time::Instant::now();
+                    let result = core.run().await;
+                    metrics
+                        .record_core_latency(&core_type, instance, start_time.elapsed().as_secs_f64())
+                        .await;
+                    (result, core.run_interval())
+                };
+                drop(slot);
+                
+                let error = match result {
+                    Ok(()) => {
+                        // Registrar métricas de éxito
+                        metrics.record_core_execution(&core_type, instance, true).await;
+                        supervisor.record_success();
+                        tokio::time::sleep(interval).await;
+                        continue;
                     }
+                    Err(e) => e,
+                };
+                
+                let current = config.read().await.clone();
+                let policy = current.nano_cores.supervisor.policy_for(&core_type);
+                let decision = supervisor.record_error(
+                    &error,
+                    policy,
+                    &current.nano_cores.supervisor,
+                    current.consensus.failure_threshold,
+                    std::time::Instant::now(),
+                );
+                
+                // La sobrecarga no es un fallo de la instancia
+                if let SupervisorDecision::Wait(delay) = decision {
+                    debug!("⏳ {:?} instancia {} sobrecargada, espera {:?}: {}", core_type, instance, delay, error);
+                    tokio::time::sleep(delay.max(interval)).await;
+                    continue;
                 }
+                error!(
+                    "❌ Error {} en {:?} instancia {}: {}",
+                    error.kind(), core_type, instance, error
+                );
+                metrics.record_core_execution(&core_type, instance, false).await;
+                
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
                 
-                drop(cores_guard);
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                let delay = match decision {
+                    SupervisorDecision::Restart(delay) | SupervisorDecision::Wait(delay) => delay,
+                    SupervisorDecision::Replace(delay) => {
+                        warn!("🔄 Hot-swapping requerido para {:?} instancia {}", core_type, instance);
+                        if let Err(e) = manager.hot_swap(&core_type, instance, &error.to_string()).await {
+                            error!("❌ Hot-swap de {:?} instancia {} fallido: {}", core_type, instance, e);
+                        }
+                        delay
+                    }
+                    SupervisorDecision::Stop => {
+                        let reason = match &error {
+                            NanoCoreError::SecurityDenied(_) => "permisos insuficientes",
+                            _ => "política de reinicio agotada",
+                        };
+                        error!("🛑 {:?} instancia {} detenida: {}", core_type, instance, reason);
+                        manager.halt(&core_type, instance, NanoCoreState::Failed).await;
+                        break;
+                    }
+                    SupervisorDecision::Quarantine => {
+                        error!(
+                            "🚨 {:?} instancia {} en cuarentena: crash loop de {} fallos",
+                            core_type, instance, current.nano_cores.supervisor.crash_loop_max_failures
+                        );
+                        manager.halt(&core_type, instance, NanoCoreState::Quarantined).await;
+                        break;
+                    }
+                };
+                drop(manager);
+                
+                warn!("🔁 Reiniciando {:?} instancia {} en {:?}", core_type, instance, delay);
+                tokio::time::sleep(delay).await;
             }
-        });
+        };
+        
+        if placement.is_default() {
+            tokio::spawn(task);
+            return Ok(());
+        }
+        
+        // Hilo propio con su runtime: la afinidad y el nice son por hilo
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        std::thread::Builder::new().name(thread_name.clone()).spawn(move || {
+            match placement.apply_to_current_thread() {
+                Ok(()) => info!(
+                    "📌 {} fijado a las CPUs {:?} con nice {:?}",
+                    thread_name, placement.cpu_affinity, placement.nice
+                ),
+                Err(e) => warn!("⚠️  {} sin afinidad ni prioridad propias: {}", thread_name, e),
+            }
+            runtime.block_on(task);
+        })?;
         
         Ok(())
     }
 
+    /// Detener una instancia por decisión del supervisor
+    ///
+    /// En cuarentena la réplica deja además de votar en el consenso.
+    async fn halt(&self, core_type: &NanoCoreType, instance: usize, state: NanoCoreState) {
+        let instance_id = {
+            let Ok(slot) = self.slot(core_type, instance).await else {
+                return;
+            };
+            let mut core = slot.lock().await;
+            if let Err(e) = core.shutdown().await {
+                warn!("⚠️  Error deteniendo {:?} instancia {}: {}", core_type, instance, e);
+            }
+            // Antes de soltar el lock: ni el bucle ni los comandos vuelven a usarla
+            self.halted.write().await.insert((core_type.clone(), instance), state.clone());
+            core.instance_id()
+        };
+        
+        if matches!(state, NanoCoreState::Quarantined) {
+            self.consensus_manager.set_replica_state(instance_id, ReplicaState::Quarantined).await;
+        }
+    }
+    
+    /// Retirar una instancia sin perder los comandos que está atendiendo
+    ///
+    /// Deja de recibir comandos nuevos, espera a los que están en curso hasta
+    /// `[nano_cores.supervisor] drain_timeout_ms` y la detiene en `Shutdown`,
+    /// fuera del consenso. El resto de réplicas sigue atendiendo.
+    pub async fn drain(&self, core_type: NanoCoreType, instance: usize) -> Result<()> {
+        let key = (core_type.clone(), instance);
+        let slot = self.slot(&core_type, instance).await?;
+        if self.halted.read().await.contains_key(&key) {
+            return Err(anyhow!("{:?} instancia {} ya está detenida", core_type, instance));
+        }
+        if !self.draining.write().await.insert(key.clone()) {
+            return Err(anyhow!("{:?} instancia {} ya se está drenando", core_type, instance));
+        }
+        info!("🚰 Drenando {:?} instancia {}", core_type, instance);
+        
+        let timeout = self.config.read().await.nano_cores.supervisor.drain_timeout();
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let pending = self.in_flight_count(&key);
+            if pending == 0 {
+                break;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                warn!(
+                    "⏱️  {:?} instancia {} se detiene con {} comandos en curso tras {:?}",
+                    core_type, instance, pending, timeout
+                );
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        
+        let instance_id = slot.lock().await.instance_id();
+        self.halt(&core_type, instance, NanoCoreState::Shutdown).await;
+        self.consensus_manager.unregister_participant(instance_id).await;
+        self.draining.write().await.remove(&key);
+        
+        info!("✅ {:?} instancia {} drenada y detenida", core_type, instance);
+        Ok(())
+    }
+    
+    fn in_flight_count(&self, key: &(NanoCoreType, usize)) -> usize {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).get(key).copied().unwrap_or(0)
+    }
+    
+    /// Reemplazar una instancia fallida si el consenso lo aprueba (`ReplicaReplacement`)
+    ///
+    /// Los comandos que llegan durante el reemplazo esperan y se ejecutan en
+    /// la instancia resultante, la nueva o la anterior si se rechaza.
+    async fn hot_swap(&self, core_type: &NanoCoreType, instance: usize, reason: &str) -> Result<()> {
+        let key = (core_type.clone(), instance);
+        self.swaps.begin(key.clone()).await;
+        
+        let result = self.replace_instance(core_type, instance, reason).await;
+        
+        let replayed = self.swaps.finish(&key, self.slot(core_type, instance).await).await;
+        if replayed > 0 {
+            info!("📨 {} comandos pendientes transferidos a {:?} instancia {}", replayed, core_type, instance);
+        }
+        
+        result
+    }
+    
+    async fn replace_instance(&self, core_type: &NanoCoreType, instance: usize, reason: &str) -> Result<()> {
+        let slot = self.slot(core_type, instance).await?;
+        let old_id = slot.lock().await.instance_id();
+        
+        // Mayoría de las réplicas saludables
+        let healthy = self.consensus_manager.replicas().await
+            .iter()
+            .filter(|replica| replica.state == ReplicaState::Healthy)
+            .count();
+        let proposal = ConsensusProposal {
+            id: Uuid::new_v4(),
+            proposal_type: ProposalType::ReplicaReplacement,
+            proposer: old_id,
+            data: serde_json::to_vec(&serde_json::json!({
+                "core_type": core_type,
+                "instance": instance,
+                "instance_id": old_id,
+                "reason": reason,
+            }))?,
+            timestamp: std::time::SystemTime::now(),
+            required_votes: healthy / 2 + 1,
+        };
+        
+        let result = self.consensus_manager.propose_and_wait(proposal).await?;
+        if result.decision != VoteDecision::Approve {
+            warn!("🗳️  Reemplazo de {:?} instancia {} rechazado por consenso", core_type, instance);
+            return Ok(());
+        }
+        
+        let mut replacement = self.create_nano_core(core_type, instance).await?;
+        let new_id = replacement.instance_id();
+        let store = self.checkpoint_store().await;
+        
+        {
+            let mut core = slot.lock().await;
+            
+            // La nueva instancia continúa con las líneas base y contadores de la anterior
+            let state = match &store {
+                Some(_) => core.snapshot().await
+                    .map_err(|e| warn!("⚠️  Sin checkpoint de la instancia reemplazada {}: {}", old_id, e))
+                    .ok(),
+                None => None,
+            };
+            
+            // La instancia anterior libera sus suscripciones antes de que la nueva se suscriba
+            if let Err(e) = core.shutdown().await {
+                warn!("⚠️  Error deteniendo la instancia reemplazada {}: {}", old_id, e);
+            }
+            replacement.initialize().await?;
+            
+            if let (Some(store), Some(state)) = (&store, state) {
+                if let Err(e) = replacement.restore(&state).await {
+                    warn!("⚠️  {:?} instancia {} no restauró el estado anterior: {}", core_type, instance, e);
+                } else if let Err(e) = store.save(core_type, instance, &state).await {
+                    warn!("⚠️  Error guardando el checkpoint de {:?} instancia {}: {}", core_type, instance, e);
+                }
+            }
+            *core = replacement;
+        }
+        
+        self.consensus_manager.unregister_participant(old_id).await;
+        self.register_in_consensus(core_type, instance, new_id).await?;
+        
+        info!("🔄 {:?} instancia {} reemplazada: {} → {}", core_type, instance, old_id, new_id);
+        Ok(())
+    }
+    
+    /// Someter a consenso una remediación de NetworkCore y ejecutarla en la instancia que la propuso si se aprueba
+    pub async fn remediate(&self, request: watchdog::RemediationRequest) -> Result<bool> {
+        let slot = self.slot(&NanoCoreType::Network, request.instance).await?;
+        let proposer = slot.lock().await.instance_id();
+        
+        // Mayoría de las réplicas saludables
+        let healthy = self.consensus_manager.replicas().await
+            .iter()
+            .filter(|replica| replica.state == ReplicaState::Healthy)
+            .count();
+        let proposal = ConsensusProposal {
+            id: request.id,
+            proposal_type: ProposalType::Remediation,
+            proposer,
+            data: serde_json::to_vec(&request)?,
+            timestamp: std::time::SystemTime::now(),
+            required_votes: healthy / 2 + 1,
+        };
+        
+        let result = self.consensus_manager.propose_and_wait(proposal).await?;
+        if result.decision != VoteDecision::Approve {
+            warn!("🗳️  Remediación {:?} rechazada por consenso", request.action);
+            return Ok(false);
+        }
+        
+        // La aprobación concede a una sesión propia el permiso que exige el comando;
+        // `execute_command` sigue comprobando autorización, drenaje y reemplazos
+        let payload = serde_json::to_vec(&request.action.command())?;
+        let required = slot.lock().await.command_authorization(&payload)?;
+        let context = self.security_manager.create_security_context(
+            Some(format!("consensus:{}", result.proposal_id)),
+            required.level,
+            vec![required.permission],
+            Vec::new(),
+            None,
+        ).await;
+        let executed = self.execute_command(NanoCoreType::Network, request.instance, &context, "Remediation", &payload).await;
+        if let Err(e) = self.security_manager.close_session(context.session_id).await {
+            warn!("⚠️  Error cerrando la sesión de la remediación {}: {}", request.id, e);
+        }
+        executed?;
+        info!("🛠️  Remediación {:?} aplicada en NetworkCore instancia {}: {}", request.action, request.instance, request.reason);
+        Ok(true)
+    }
+    
+    /// Réplicas de `core_type` que no están detenidas ni drenándose
+    pub async fn available_instances(&self, core_type: &NanoCoreType) -> Vec<usize> {
+        let count = self.cores.read().await.get(core_type).map_or(0, Vec::len);
+        let halted = self.halted.read().await;
+        let draining = self.draining.read().await;
+        (0..count)
+            .map(|instance| (core_type.clone(), instance))
+            .filter(|key| !halted.contains_key(key) && !draining.contains(key))
+            .map(|(_, instance)| instance)
+            .collect()
+    }
+    
+    /// Ejecutar un comando en una instancia si `context` tiene el permiso y el nivel que exige
+    ///
+    /// Las denegaciones quedan registradas como `AuthorizationDenied` por el SecurityManager.
+    pub async fn execute_command(
+        &self,
+        core_type: NanoCoreType,
+        instance: usize,
+        context: &SecurityContext,
+        command: &str,
+        payload: &[u8],
+    ) -> Result<Vec<u8>> {
+        let key = (core_type.clone(), instance);
+        // Se cuenta antes de mirar `draining` para que `drain` no lo pierda
+        let _in_flight = InFlight::start(&self.in_flight, key.clone());
+        if self.draining.read().await.contains(&key) {
+            return Err(anyhow!("{:?} instancia {} en drenaje: no acepta comandos nuevos", core_type, instance));
+        }
+        
+        let slot = self.slot(&core_type, instance).await?;
+        let required = slot.lock().await.command_authorization(payload)?;
+        
+        if !self.security_manager.check_authorization(context, &required.permission, required.level).await? {
+            warn!(
+                "🚫 Comando {} denegado en {:?} instancia {}: requiere {} con nivel {:?}",
+                command, core_type, instance, required.permission, required.level
+            );
+            return Err(CommandDenied { command: command.to_string(), required }.into());
+        }
+        
+        // Durante un reemplazo el comando espera a la nueva instancia
+        if let Some(response) = self.swaps.enqueue(&key, command, payload).await {
+            return response.await
+                .map_err(|_| anyhow!("Reemplazo de {:?} instancia {} interrumpido", core_type, instance))?;
+        }
+        
+        let mut core = slot.lock().await;
+        if let Some(state) = self.halted.read().await.get(&key) {
+            return Err(anyhow!("{:?} instancia {} detenida ({:?})", core_type, instance, state));
+        }
+        Ok(core.process_command(command, payload).await?)
+    }
+    
+    /// Instancia `instance` de `core_type`
+    async fn slot(&self, core_type: &NanoCoreType, instance: usize) -> Result<CoreSlot> {
+        self.cores.read().await
+            .get(core_type)
+            .and_then(|instances| instances.get(instance))
+            .cloned()
+            .ok_or_else(|| anyhow!("Nano-núcleo {:?} instancia {} no encontrado", core_type, instance))
+    }
+    
+    /// Instancias de cada tipo, sin retener el lock del mapa
+    async fn snapshot(&self) -> Vec<(NanoCoreType, Vec<CoreSlot>)> {
+        self.cores.read().await
+            .iter()
+            .map(|(core_type, instances)| (core_type.clone(), instances.clone()))
+            .collect()
+    }
+
     /// Obtener estado de salud del sistema
+    ///
+    /// Los estados de las instancias y del sistema salen de sus scores
+    /// compuestos según `[nano_cores.health]`, con histéresis.
     pub async fn get_health_status(&self) -> SystemHealth {
-        let cores_guard = self.cores.read().await;
+        let config = self.config.read().await.nano_cores.health.clone();
+        let snapshot = self.snapshot().await;
+        let mut tracker = self.health_tracker.lock().await;
         let mut health_map = HashMap::new();
-        let mut overall_healthy = true;
+        let mut scores = Vec::new();
+        let mut running = 0;
         
-        for (core_type, instances) in cores_guard.iter() {
+        for (core_type, instances) in snapshot {
             let mut core_healths = Vec::new();
             
-            for core in instances.iter() {
-                match core.health_check().await {
-                    Ok(health) => {
-                        if !matches!(health.state, NanoCoreState::Running) {
-                            overall_healthy = false;
+            for (i, slot) in instances.iter().enumerate() {
+                let result = slot.lock().await.health_check().await;
+                match result {
+                    Ok(mut health) => {
+                        let halted = self.halted.read().await.get(&(core_type.clone(), i)).cloned();
+                        let score = match halted {
+                            Some(state) => {
+                                health.state = state;
+                                let score = config.core_score(&health);
+                                tracker.set_core_state(&core_type, i, health.state.clone(), score);
+                                score
+                            }
+                            None => {
+                                let score = config.core_score(&health);
+                                health.state = tracker.core_state(&config, &core_type, i, score);
+                                score
+                            }
+                        };
+                        if matches!(health.state, NanoCoreState::Running) {
+                            running += 1;
                         }
+                        scores.push(score);
                         core_healths.push(health);
                     }
                     Err(e) => {
                         error!("❌ Error obteniendo salud de {:?}: {}", core_type, e);
-                        overall_healthy = false;
+                        scores.push(0.0);
                     }
                 }
             }
             
-            health_map.insert(*core_type, core_healths);
+            health_map.insert(core_type, core_healths);
+        }
+        
+        let consensus_health = self.consensus_manager.health().await;
+        let fabric_latency_ms = self.fabric_rtt_ms(&config).await;
+        let score = config.system_score(&scores, consensus_health, fabric_latency_ms);
+        let mut overall_state = tracker.system_state(&config, score);
+        
+        // Límites que impiden un sistema Running aunque el score lo permita
+        let healthy_cores = if scores.is_empty() { 0.0 } else { running as f64 / scores.len() as f64 };
+        if matches!(overall_state, NanoCoreState::Running)
+            && (consensus_health < config.min_consensus_health
+                || fabric_latency_ms > config.max_fabric_latency_ms
+                || healthy_cores < config.min_healthy_cores)
+        {
+            overall_state = NanoCoreState::Degraded;
         }
         
+        let trend = self.health_history.read().await.trend(config.trend_window, config.trend_threshold);
+        
         SystemHealth {
             cores: health_map,
-            overall_state: if overall_healthy {
-                NanoCoreState::Running
+            overall_state,
+            score,
+            trend,
+            consensus_health,
+            fabric_latency_ms,
+        }
+    }
+    
+    /// Ida y vuelta de un ping por el fabric; sin respuesta cuenta como el timeout
+    async fn fabric_rtt_ms(&self, config: &HealthConfig) -> f64 {
+        let timeout = Duration::from_millis(config.fabric_ping_timeout_ms);
+        match self.cognitive_fabric.ping(timeout).await {
+            Ok(rtt) => rtt.as_secs_f64() * 1000.0,
+            Err(e) => {
+                debug!("🏓 Ping del Cognitive Fabric fallido: {}", e);
+                timeout.as_secs_f64() * 1000.0
+            }
+        }
+    }
+    
+    /// Tomar una muestra del monitor de salud
+    ///
+    /// La muestra entra en el historial y cada cambio de estado desde la
+    /// anterior se publica como evento `HealthCheck`.
+    pub async fn sample_health(&self) -> SystemHealth {
+        let mut health = self.get_health_status().await;
+        let config = self.config.read().await.nano_cores.health.clone();
+        
+        {
+            let mut history = self.health_history.write().await;
+            history.push(HealthSample {
+                timestamp: chrono::Utc::now(),
+                state: health.overall_state.clone(),
+                score: health.score,
+                consensus_health: health.consensus_health,
+                fabric_latency_ms: health.fabric_latency_ms,
+            }, config.history_size);
+            health.trend = history.trend(config.trend_window, config.trend_threshold);
+        }
+        
+        let transitions = self.health_tracker.lock().await.take_transitions();
+        for transition in transitions {
+            self.publish_transition(&transition).await;
+        }
+        
+        health
+    }
+    
+    /// Historial de muestras del monitor de salud, de la más antigua a la más reciente
+    pub async fn health_history(&self) -> Vec<HealthSample> {
+        self.health_history.read().await.samples()
+    }
+    
+    async fn publish_transition(&self, transition: &HealthTransition) {
+        let subject = match (&transition.core_type, transition.instance) {
+            (Some(core_type), Some(instance)) => format!("{} instancia {}", core_type, instance),
+            _ => "Sistema".to_string(),
+        };
+        if transition.is_degradation() {
+            warn!("📉 {}: {:?} → {:?} (score {:.2})", subject, transition.from, transition.to, transition.score);
+        } else {
+            info!("📈 {}: {:?} → {:?} (score {:.2})", subject, transition.from, transition.to, transition.score);
+        }
+        
+        // Distingue la transición de la muestra periódica; el núcleo afectado va en
+        // las cabeceras y no en `target`, que la desviaría al inbox del núcleo
+        let mut headers = HashMap::from([("health_event".to_string(), "transition".to_string())]);
+        if let (Some(core_type), Some(instance)) = (&transition.core_type, transition.instance) {
+            headers.insert("core_type".to_string(), core_type.to_string());
+            headers.insert("instance".to_string(), instance.to_string());
+        }
+        
+        let event = crate::communication::CognitiveEvent {
+            id: Uuid::new_v4(),
+            event_type: crate::communication::EventType::HealthCheck,
+            source: "nano-core-manager".to_string(),
+            target: None,
+            timestamp: chrono::Utc::now(),
+            payload: serde_json::to_vec(transition).unwrap_or_default(),
+            priority: if transition.is_degradation() {
+                crate::communication::EventPriority::High
             } else {
-                NanoCoreState::Degraded
+                crate::communication::EventPriority::Normal
             },
-            consensus_health: 0.95, // TODO: Obtener del ConsensusManager
-            fabric_latency_ms: 2.5,  // TODO: Obtener del CognitiveFabric
+            correlation_id: None,
+            headers,
+        };
+        
+        if let Err(e) = self.cognitive_fabric.publish_event(event).await {
+            warn!("⚠️  Error publicando la transición de salud de {}: {}", subject, e);
         }
     }
 
@@ -415,21 +1384,84 @@ impl NanoCoreManager {
         
         *self.running.write().await = false;
         
-        let mut cores_guard = self.cores.write().await;
+        if let Some(dispatcher) = self.dispatcher.write().await.take() {
+            dispatcher.stop(self).await;
+        }
+        
+        // Último checkpoint para que el próximo arranque continúe desde aquí
+        if let Some(task) = self.checkpoint_task.write().await.take() {
+            task.abort();
+        }
+        self.checkpoint_all().await;
+        
+        // Los bucles terminan al no encontrar su instancia
+        let mut cores = std::mem::take(&mut *self.cores.write().await);
+        
+        // Primero los arrancados fuera de `initialize_all_cores`, luego en orden inverso de arranque
+        let order = std::mem::take(&mut *self.startup_order.write().await);
+        let mut core_types: Vec<NanoCoreType> = cores.keys().filter(|core_type| !order.contains(core_type)).cloned().collect();
+        core_types.extend(order.into_iter().rev());
         
-        for (core_type, instances) in cores_guard.iter_mut() {
+        for core_type in core_types {
+            let Some(instances) = cores.remove(&core_type) else { continue };
             info!("🔄 Deteniendo {:?}...", core_type);
             
-            for (i, core) in instances.iter_mut().enumerate() {
-                if let Err(e) = core.shutdown().await {
+            for (i, slot) in instances.iter().enumerate() {
+                if let Err(e) = slot.lock().await.shutdown().await {
                     error!("❌ Error deteniendo {:?} instancia {}: {}", core_type, i, e);
                 }
             }
         }
         
-        cores_guard.clear();
-        
         info!("✅ Todos los nano-núcleos detenidos");
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[async_trait]
+impl ConfigSubscriber for NanoCoreManager {
+    fn subscriber_name(&self) -> &str {
+        "nano-cores"
+    }
+
+    fn sections(&self) -> &[ConfigSection] {
+        &[ConfigSection::NanoCores, ConfigSection::Consensus]
+    }
+
+    async fn on_config_change(&self, config: &CoreConfig, changes: &[FieldChange]) -> Result<()> {
+        *self.config.write().await = config.clone();
+
+        if changes.iter().any(|change| change.path == "consensus.replica_count") {
+            info!(
+                "🔧 Nuevo número de réplicas ({}) aplicado al reiniciar cada nano-núcleo",
+                config.consensus.replica_count
+            );
+        }
+        if changes.iter().any(|change| change.path.starts_with("nano_cores.placement")) {
+            info!("📌 Nueva afinidad de CPU y nice aplicados al reiniciar cada nano-núcleo");
+        }
+
+        for (core_type, instances) in self.snapshot().await {
+            for (i, slot) in instances.iter().enumerate() {
+                if let Err(e) = slot.lock().await.apply_config(config).await {
+                    warn!("⚠️  {:?} instancia {} no aplicó la configuración: {}", core_type, i, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HealthProbe for NanoCoreManager {
+    async fn degraded_cores(&self) -> (usize, usize) {
+        let health = self.get_health_status().await;
+        let instances: Vec<&NanoCoreHealth> = health.cores.values().flatten().collect();
+        let degraded = instances
+            .iter()
+            .filter(|instance| !matches!(instance.state, NanoCoreState::Running))
+            .count();
+
+        (degraded, instances.len())
+    }
+}
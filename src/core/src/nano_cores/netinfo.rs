@@ -0,0 +1,804 @@
+//! Interfaces, rutas y DNS del sistema para NetworkCore
+//!
+//! En Linux las interfaces, contadores, velocidad y carrier salen de sysfs
+//! (`/sys/class/net`), las direcciones de `getifaddrs` y las rutas de
+//! `/proc/net/route` e `ipv6_route`. En macOS `getifaddrs` aporta también MTU
+//! y contadores (`if_data`) y las rutas salen de `netstat -rn`. En Windows
+//! todo procede de `GetAdaptersAddresses`, `GetIfEntry2` y `GetIpForwardTable2`.
+//! Los resolvers IPv6 de enlace local conservan su zona como `scope_id`.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+
+use super::ip_family::{self, AddressFamilyConfig, IpFamily};
+use super::network_core::{DuplexMode, InterfaceStatistics, InterfaceStatus, NetworkInterface, Route};
+
+const DNS_PORT: u16 = 53;
+
+/// Interfaces con sus direcciones y contadores actuales
+pub fn interfaces() -> Result<Vec<NetworkInterface>> {
+    platform::interfaces()
+}
+
+/// Tablas de rutas IPv4 e IPv6
+pub fn routes() -> Result<Vec<Route>> {
+    platform::routes()
+}
+
+/// Servidores DNS configurados
+pub fn dns_servers() -> Result<Vec<IpAddr>> {
+    let mut servers: Vec<IpAddr> = Vec::new();
+    for resolver in platform::dns_resolvers()? {
+        if !servers.contains(&resolver.ip()) {
+            servers.push(resolver.ip());
+        }
+    }
+    Ok(servers)
+}
+
+/// Servidores DNS en el puerto 53, con zona si son de enlace local
+pub fn dns_resolvers() -> Result<Vec<SocketAddr>> {
+    platform::dns_resolvers()
+}
+
+/// Gateway de la ruta por defecto de menor métrica en la primera familia habilitada que tenga una
+pub fn default_gateway(routes: &[Route], families: &AddressFamilyConfig) -> Option<IpAddr> {
+    families.families().into_iter().find_map(|family| family_gateway(routes, family))
+}
+
+/// Gateway de la ruta por defecto de menor métrica en `family`
+pub fn family_gateway(routes: &[Route], family: IpFamily) -> Option<IpAddr> {
+    routes
+        .iter()
+        .filter(|route| route.is_default && !route.gateway.is_unspecified() && IpFamily::of(&route.gateway) == family)
+        .min_by_key(|route| route.metric)
+        .map(|route| route.gateway)
+}
+
+/// Índice de una interfaz, que es también la zona de sus direcciones de enlace local
+pub fn interface_index(name: &str) -> Option<u32> {
+    platform::interface_index(name)
+}
+
+/// Zona `%eth0` o `%2` de una dirección
+#[cfg_attr(windows, allow(dead_code))]
+fn zone_index(zone: &str, index: impl Fn(&str) -> Option<u32>) -> Option<u32> {
+    zone.parse().ok().or_else(|| index(zone))
+}
+
+fn new_interface(name: String) -> NetworkInterface {
+    NetworkInterface {
+        name,
+        index: None,
+        ip_addresses: Vec::new(),
+        mac_address: None,
+        mtu: 0,
+        speed: None,
+        duplex: DuplexMode::Unknown,
+        status: InterfaceStatus::Unknown,
+        carrier: None,
+        statistics: InterfaceStatistics::default(),
+    }
+}
+
+/// `aa:bb:cc:dd:ee:ff`; `None` para direcciones vacías o todo ceros
+fn format_mac(bytes: &[u8]) -> Option<String> {
+    if bytes.is_empty() || bytes.iter().all(|b| *b == 0) {
+        return None;
+    }
+    Some(bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+}
+
+/// Interfaces de un `/sys/class/net` montado en `root`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn read_sysfs_interfaces(root: &Path, addresses: &HashMap<String, Vec<IpAddr>>) -> Vec<NetworkInterface> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut interfaces: Vec<(u64, NetworkInterface)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let dir = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok().map(|v| v.trim().to_string());
+            // `speed` y `carrier` fallan con EINVAL si la interfaz está caída
+            let number = |file: &str| read(file).and_then(|v| v.parse::<i64>().ok());
+            let counter = |file: &str| number(&format!("statistics/{}", file)).unwrap_or(0) as u64;
+
+            let carrier = number("carrier").map(|c| c == 1);
+            let flags = read("flags")
+                .and_then(|f| u32::from_str_radix(f.trim_start_matches("0x"), 16).ok())
+                .unwrap_or(0);
+            let status = match read("operstate").as_deref() {
+                Some("up") => InterfaceStatus::Up,
+                Some("down" | "dormant" | "lowerlayerdown" | "notpresent") => InterfaceStatus::Down,
+                Some("testing") => InterfaceStatus::Testing,
+                // Loopback y muchos túneles no informan operstate
+                _ if flags & libc::IFF_UP as u32 != 0 && carrier != Some(false) => InterfaceStatus::Up,
+                _ => InterfaceStatus::Unknown,
+            };
+
+            let mut interface = new_interface(name.clone());
+            interface.index = number("ifindex").map(|index| index as u32);
+            interface.ip_addresses = addresses.get(&name).cloned().unwrap_or_default();
+            interface.mac_address = read("address").and_then(|mac| {
+                let bytes: Vec<u8> = mac.split(':').filter_map(|b| u8::from_str_radix(b, 16).ok()).collect();
+                format_mac(&bytes)
+            });
+            interface.mtu = number("mtu").unwrap_or(0) as u32;
+            interface.speed = number("speed").filter(|s| *s > 0 && *s < u32::MAX as i64).map(|s| s as u64);
+            interface.duplex = match read("duplex").as_deref() {
+                Some("full") => DuplexMode::Full,
+                Some("half") => DuplexMode::Half,
+                _ => DuplexMode::Unknown,
+            };
+            interface.status = status;
+            interface.carrier = carrier;
+            interface.statistics = InterfaceStatistics {
+                bytes_sent: counter("tx_bytes"),
+                bytes_received: counter("rx_bytes"),
+                packets_sent: counter("tx_packets"),
+                packets_received: counter("rx_packets"),
+                errors_sent: counter("tx_errors"),
+                errors_received: counter("rx_errors"),
+                dropped_sent: counter("tx_dropped"),
+                dropped_received: counter("rx_dropped"),
+                collisions: counter("collisions"),
+            };
+            (number("ifindex").unwrap_or(i64::MAX) as u64, interface)
+        })
+        .collect();
+    interfaces.sort_by_key(|(index, _)| *index);
+    interfaces.into_iter().map(|(_, interface)| interface).collect()
+}
+
+const RTF_UP: u32 = 0x0001;
+const RTF_REJECT: u32 = 0x0200;
+const RTF_LOCAL: u32 = 0x8000_0000;
+
+/// `/proc/net/route`: direcciones en hexadecimal con el orden de bytes del host
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ipv4_routes(content: &str) -> Vec<Route> {
+    let hex = |value: &str| u32::from_str_radix(value, 16).ok();
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 8 {
+                return None;
+            }
+            let (destination, gateway, flags, metric, mask) =
+                (hex(fields[1])?, hex(fields[2])?, hex(fields[3])?, fields[6].parse().ok()?, hex(fields[7])?);
+            if flags & RTF_UP == 0 {
+                return None;
+            }
+            Some(Route {
+                destination: IpAddr::V4(Ipv4Addr::from(destination.to_ne_bytes())),
+                prefix_len: mask.count_ones() as u8,
+                gateway: IpAddr::V4(Ipv4Addr::from(gateway.to_ne_bytes())),
+                interface: fields[0].to_string(),
+                metric,
+                is_default: destination == 0 && mask == 0,
+            })
+        })
+        .collect()
+}
+
+/// `/proc/net/ipv6_route`: destino, prefijo, origen, prefijo, next hop,
+/// métrica, refcnt, use, flags e interfaz, sin cabecera
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ipv6_routes(content: &str) -> Vec<Route> {
+    let address = |value: &str| u128::from_str_radix(value, 16).ok().map(|v| IpAddr::V6(Ipv6Addr::from(v)));
+    let hex = |value: &str| u32::from_str_radix(value, 16).ok();
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let flags = hex(fields[8])?;
+            // Las rutas `local` y las `unreachable` del loopback no sirven para encaminar
+            if flags & RTF_UP == 0 || flags & (RTF_REJECT | RTF_LOCAL) != 0 {
+                return None;
+            }
+            let prefix_len = hex(fields[1])? as u8;
+            Some(Route {
+                destination: address(fields[0])?,
+                prefix_len,
+                gateway: address(fields[4])?,
+                interface: fields[9].to_string(),
+                metric: hex(fields[5])?,
+                is_default: prefix_len == 0,
+            })
+        })
+        .collect()
+}
+
+/// `nameserver` de un resolv.conf; la zona (`fe80::1%eth0`) se traduce con `index`
+#[cfg_attr(windows, allow(dead_code))]
+fn parse_resolv_conf(content: &str, index: impl Fn(&str) -> Option<u32>) -> Vec<SocketAddr> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next()? == "nameserver").then_some(())?;
+            let value = parts.next()?;
+            let (address, zone) = match value.split_once('%') {
+                Some((address, zone)) => (address, Some(zone)),
+                None => (value, None),
+            };
+            let ip: IpAddr = address.parse().ok()?;
+            let scope = zone.and_then(|zone| zone_index(zone, &index));
+            Some(ip_family::socket_addr(ip, DNS_PORT, scope))
+        })
+        .collect()
+}
+
+/// Tabla de `netstat -rn` de macOS, con sus secciones `Internet:` e `Internet6:`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_netstat_routes(output: &str) -> Vec<Route> {
+    let mut routes = Vec::new();
+    let mut ipv6 = false;
+    let mut columns: Option<(usize, usize)> = None;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first() {
+            Some(&"Internet:") => ipv6 = false,
+            Some(&"Internet6:") => ipv6 = true,
+            Some(&"Destination") => {
+                columns = fields
+                    .iter()
+                    .position(|f| *f == "Flags")
+                    .zip(fields.iter().position(|f| *f == "Netif"));
+            }
+            Some(destination) => {
+                let Some((flags, netif)) = columns else { continue };
+                let (Some(gateway), Some(flags), Some(interface)) = (fields.get(1), fields.get(flags), fields.get(netif))
+                else {
+                    continue;
+                };
+                // `W`: entradas clonadas de la caché ARP/NDP
+                if flags.contains('W') {
+                    continue;
+                }
+                let Some((destination, prefix_len)) = parse_netstat_destination(destination, ipv6, flags.contains('H'))
+                else {
+                    continue;
+                };
+                let unspecified = if ipv6 { IpAddr::V6(Ipv6Addr::UNSPECIFIED) } else { IpAddr::V4(Ipv4Addr::UNSPECIFIED) };
+                routes.push(Route {
+                    destination,
+                    prefix_len,
+                    // `link#6` o una MAC: la ruta es directa
+                    gateway: gateway.split('%').next().and_then(|g| g.parse().ok()).unwrap_or(unspecified),
+                    interface: interface.to_string(),
+                    metric: 0,
+                    is_default: prefix_len == 0,
+                });
+            }
+            None => {}
+        }
+    }
+    routes
+}
+
+/// `default`, `127`, `192.168.1`, `10.0/16`, `fe80::%lo0/64`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_netstat_destination(destination: &str, ipv6: bool, host: bool) -> Option<(IpAddr, u8)> {
+    if destination == "default" {
+        let unspecified = if ipv6 { IpAddr::V6(Ipv6Addr::UNSPECIFIED) } else { IpAddr::V4(Ipv4Addr::UNSPECIFIED) };
+        return Some((unspecified, 0));
+    }
+    let (address, prefix) = match destination.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse().ok()?)),
+        None => (destination, None),
+    };
+    let address = address.split('%').next()?;
+    if ipv6 {
+        return Some((IpAddr::V6(address.parse().ok()?), prefix.unwrap_or(128)));
+    }
+    // netstat abrevia las redes IPv4 omitiendo los octetos a cero
+    let octets: Vec<u8> = address.split('.').map(|o| o.parse().ok()).collect::<Option<_>>()?;
+    if octets.is_empty() || octets.len() > 4 {
+        return None;
+    }
+    let mut padded = [0u8; 4];
+    padded[..octets.len()].copy_from_slice(&octets);
+    let implied = if host || octets.len() == 4 { 32 } else { octets.len() as u8 * 8 };
+    Some((IpAddr::V4(Ipv4Addr::from(padded)), prefix.unwrap_or(implied)))
+}
+
+/// Servidores de `/etc/resolv.conf`, o los reales si apunta al stub de systemd-resolved
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn resolv_conf_servers() -> Result<Vec<SocketAddr>> {
+    let servers = parse_resolv_conf(&std::fs::read_to_string("/etc/resolv.conf")?, unix_interface_index);
+    let stub = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 53));
+    if !servers.is_empty() && servers.iter().all(|server| server.ip() == stub) {
+        if let Ok(upstream) = std::fs::read_to_string("/run/systemd/resolve/resolv.conf") {
+            return Ok(parse_resolv_conf(&upstream, unix_interface_index));
+        }
+    }
+    Ok(servers)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn unix_interface_index(name: &str) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => None,
+        index => Some(index),
+    }
+}
+
+/// Recorrer la lista de `getifaddrs` con el nombre de cada entrada
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn walk_ifaddrs(mut visit: impl FnMut(&str, &libc::ifaddrs)) -> Result<()> {
+    let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut head) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let mut current = head;
+    while !current.is_null() {
+        // SAFETY: la lista es válida hasta `freeifaddrs`
+        let entry = unsafe { &*current };
+        let name = unsafe { std::ffi::CStr::from_ptr(entry.ifa_name) }.to_string_lossy();
+        visit(&name, entry);
+        current = entry.ifa_next;
+    }
+    unsafe { libc::freeifaddrs(head) };
+    Ok(())
+}
+
+/// Dirección IP de un `sockaddr` AF_INET o AF_INET6
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+unsafe fn sockaddr_ip(addr: *const libc::sockaddr) -> Option<IpAddr> {
+    if addr.is_null() {
+        return None;
+    }
+    match (*addr).sa_family as i32 {
+        libc::AF_INET => {
+            let sin = &*(addr as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr))))
+        }
+        libc::AF_INET6 => {
+            let sin6 = &*(addr as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    pub fn interfaces() -> Result<Vec<NetworkInterface>> {
+        let mut addresses: HashMap<String, Vec<IpAddr>> = HashMap::new();
+        walk_ifaddrs(|name, entry| {
+            if let Some(address) = unsafe { sockaddr_ip(entry.ifa_addr) } {
+                addresses.entry(name.to_string()).or_default().push(address);
+            }
+        })?;
+        Ok(read_sysfs_interfaces(Path::new("/sys/class/net"), &addresses))
+    }
+
+    pub fn routes() -> Result<Vec<Route>> {
+        let mut routes = parse_ipv4_routes(&std::fs::read_to_string("/proc/net/route")?);
+        // Sin soporte IPv6 en el kernel el fichero no existe
+        if let Ok(ipv6) = std::fs::read_to_string("/proc/net/ipv6_route") {
+            routes.extend(parse_ipv6_routes(&ipv6));
+        }
+        Ok(routes)
+    }
+
+    pub fn dns_resolvers() -> Result<Vec<SocketAddr>> {
+        resolv_conf_servers()
+    }
+
+    pub fn interface_index(name: &str) -> Option<u32> {
+        unix_interface_index(name)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub fn interfaces() -> Result<Vec<NetworkInterface>> {
+        let mut interfaces: Vec<NetworkInterface> = Vec::new();
+        walk_ifaddrs(|name, entry| {
+            let position = match interfaces.iter().position(|interface| interface.name == name) {
+                Some(position) => position,
+                None => {
+                    let mut interface = new_interface(name.to_string());
+                    interface.index = unix_interface_index(name);
+                    interfaces.push(interface);
+                    interfaces.len() - 1
+                }
+            };
+            let interface = &mut interfaces[position];
+            let running = entry.ifa_flags & libc::IFF_RUNNING as u32 != 0;
+            interface.carrier = Some(running);
+            interface.status = if entry.ifa_flags & libc::IFF_UP as u32 != 0 && running {
+                InterfaceStatus::Up
+            } else {
+                InterfaceStatus::Down
+            };
+            match unsafe { sockaddr_ip(entry.ifa_addr) } {
+                Some(address) => interface.ip_addresses.push(address),
+                None => unsafe { read_link(interface, entry) },
+            }
+        })?;
+        Ok(interfaces)
+    }
+
+    /// Entrada AF_LINK: MAC y `if_data` (contadores de 32 bits que dan la vuelta)
+    unsafe fn read_link(interface: &mut NetworkInterface, entry: &libc::ifaddrs) {
+        if entry.ifa_addr.is_null() || (*entry.ifa_addr).sa_family as i32 != libc::AF_LINK || entry.ifa_data.is_null() {
+            return;
+        }
+        let link = &*(entry.ifa_addr as *const libc::sockaddr_dl);
+        if link.sdl_alen == 6 {
+            // La dirección sigue al nombre dentro de `sdl_data`
+            let mac = link.sdl_data.as_ptr().add(link.sdl_nlen as usize) as *const u8;
+            interface.mac_address = format_mac(std::slice::from_raw_parts(mac, 6));
+        }
+        let data = &*(entry.ifa_data as *const libc::if_data);
+        interface.mtu = data.ifi_mtu;
+        interface.speed = (data.ifi_baudrate > 0).then(|| data.ifi_baudrate as u64 / 1_000_000);
+        interface.statistics = InterfaceStatistics {
+            bytes_sent: data.ifi_obytes as u64,
+            bytes_received: data.ifi_ibytes as u64,
+            packets_sent: data.ifi_opackets as u64,
+            packets_received: data.ifi_ipackets as u64,
+            errors_sent: data.ifi_oerrors as u64,
+            errors_received: data.ifi_ierrors as u64,
+            dropped_sent: 0,
+            dropped_received: data.ifi_iqdrops as u64,
+            collisions: data.ifi_collisions as u64,
+        };
+    }
+
+    pub fn routes() -> Result<Vec<Route>> {
+        let output = std::process::Command::new("netstat").args(["-rn"]).output()?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("netstat -rn falló: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(parse_netstat_routes(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    pub fn dns_resolvers() -> Result<Vec<SocketAddr>> {
+        resolv_conf_servers()
+    }
+
+    pub fn interface_index(name: &str) -> Option<u32> {
+        unix_interface_index(name)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        FreeMibTable, GetAdaptersAddresses, GetIfEntry2, GetIpForwardTable2, GAA_FLAG_INCLUDE_GATEWAYS,
+        GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_MULTICAST, IP_ADAPTER_ADDRESSES_LH, MIB_IF_ROW2, MIB_IPFORWARD_TABLE2,
+    };
+    use windows::Win32::NetworkManagement::Ndis::{IfOperStatusDown, IfOperStatusTesting, IfOperStatusUp, MediaConnectStateConnected, MediaConnectStateDisconnected};
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, SOCKADDR_INET};
+
+    /// Adaptador de `GetAdaptersAddresses` ya copiado
+    struct Adapter {
+        index: u32,
+        interface: NetworkInterface,
+        dns_servers: Vec<SocketAddr>,
+    }
+
+    unsafe fn socket_ip(addr: *const SOCKADDR) -> Option<IpAddr> {
+        if addr.is_null() {
+            return None;
+        }
+        match (*addr).sa_family {
+            AF_INET => {
+                let sin = &*(addr as *const SOCKADDR_IN);
+                Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.S_un.S_addr))))
+            }
+            AF_INET6 => {
+                let sin6 = &*(addr as *const SOCKADDR_IN6);
+                Some(IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.u.Byte)))
+            }
+            _ => None,
+        }
+    }
+
+    unsafe fn inet_ip(addr: &SOCKADDR_INET) -> Option<IpAddr> {
+        socket_ip(addr as *const SOCKADDR_INET as *const SOCKADDR)
+    }
+
+    /// Servidor DNS con la zona de `sin6_scope_id`
+    unsafe fn dns_socket_addr(addr: *const SOCKADDR) -> Option<SocketAddr> {
+        let ip = socket_ip(addr)?;
+        let scope_id = match (*addr).sa_family {
+            AF_INET6 => Some((*(addr as *const SOCKADDR_IN6)).Anonymous.sin6_scope_id),
+            _ => None,
+        };
+        Some(ip_family::socket_addr(ip, DNS_PORT, scope_id))
+    }
+
+    fn adapters() -> Result<Vec<Adapter>> {
+        let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+        let mut size = 16 * 1024u32;
+        // Buffer de u64 para respetar la alineación de IP_ADAPTER_ADDRESSES_LH
+        let mut buffer: Vec<u64>;
+        loop {
+            buffer = vec![0u64; (size as usize).div_ceil(8)];
+            let result = unsafe {
+                GetAdaptersAddresses(
+                    AF_UNSPEC.0 as u32,
+                    flags,
+                    None,
+                    Some(buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
+                    &mut size,
+                )
+            };
+            if result == ERROR_SUCCESS.0 {
+                break;
+            }
+            if result != ERROR_BUFFER_OVERFLOW.0 {
+                return Err(std::io::Error::from_raw_os_error(result as i32).into());
+            }
+        }
+
+        let mut adapters = Vec::new();
+        let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+        while !current.is_null() {
+            // SAFETY: lista enlazada dentro de `buffer`
+            let adapter = unsafe { &*current };
+            current = adapter.Next;
+
+            let name = unsafe { adapter.FriendlyName.to_string() }.unwrap_or_default();
+            let mut interface = new_interface(name);
+            let mut unicast = adapter.FirstUnicastAddress;
+            while let Some(address) = unsafe { unicast.as_ref() } {
+                interface.ip_addresses.extend(unsafe { socket_ip(address.Address.lpSockaddr) });
+                unicast = address.Next;
+            }
+            let mac_len = (adapter.PhysicalAddressLength as usize).min(adapter.PhysicalAddress.len());
+            interface.mac_address = format_mac(&adapter.PhysicalAddress[..mac_len]);
+            interface.mtu = adapter.Mtu;
+            interface.speed = (adapter.TransmitLinkSpeed != u64::MAX && adapter.TransmitLinkSpeed > 0)
+                .then(|| adapter.TransmitLinkSpeed / 1_000_000);
+            interface.status = match adapter.OperStatus {
+                IfOperStatusUp => InterfaceStatus::Up,
+                IfOperStatusDown => InterfaceStatus::Down,
+                IfOperStatusTesting => InterfaceStatus::Testing,
+                _ => InterfaceStatus::Unknown,
+            };
+
+            let mut dns_servers = Vec::new();
+            let mut dns = adapter.FirstDnsServerAddress;
+            while let Some(server) = unsafe { dns.as_ref() } {
+                dns_servers.extend(unsafe { dns_socket_addr(server.Address.lpSockaddr) });
+                dns = server.Next;
+            }
+
+            let index = unsafe { adapter.Anonymous1.Anonymous.IfIndex };
+            interface.index = Some(index);
+            read_counters(index, &mut interface);
+            adapters.push(Adapter {
+                index,
+                interface,
+                dns_servers,
+            });
+        }
+        Ok(adapters)
+    }
+
+    /// Contadores de 64 bits y estado del medio de `GetIfEntry2`
+    fn read_counters(index: u32, interface: &mut NetworkInterface) {
+        let mut row = MIB_IF_ROW2 {
+            InterfaceIndex: index,
+            ..Default::default()
+        };
+        if unsafe { GetIfEntry2(&mut row) }.is_err() {
+            return;
+        }
+        interface.carrier = match row.MediaConnectState {
+            MediaConnectStateConnected => Some(true),
+            MediaConnectStateDisconnected => Some(false),
+            _ => None,
+        };
+        interface.statistics = InterfaceStatistics {
+            bytes_sent: row.OutOctets,
+            bytes_received: row.InOctets,
+            packets_sent: row.OutUcastPkts + row.OutNUcastPkts,
+            packets_received: row.InUcastPkts + row.InNUcastPkts,
+            errors_sent: row.OutErrors,
+            errors_received: row.InErrors,
+            dropped_sent: row.OutDiscards,
+            dropped_received: row.InDiscards,
+            collisions: 0,
+        };
+    }
+
+    pub fn interfaces() -> Result<Vec<NetworkInterface>> {
+        Ok(adapters()?.into_iter().map(|adapter| adapter.interface).collect())
+    }
+
+    pub fn routes() -> Result<Vec<Route>> {
+        let names: HashMap<u32, String> =
+            adapters()?.into_iter().map(|adapter| (adapter.index, adapter.interface.name)).collect();
+
+        let mut table: *mut MIB_IPFORWARD_TABLE2 = std::ptr::null_mut();
+        unsafe { GetIpForwardTable2(AF_UNSPEC, &mut table) }?;
+        // SAFETY: `NumEntries` filas contiguas hasta `FreeMibTable`
+        let rows = unsafe { std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize) };
+        let routes = rows
+            .iter()
+            .filter_map(|row| {
+                let destination = unsafe { inet_ip(&row.DestinationPrefix.Prefix) }?;
+                let prefix_len = row.DestinationPrefix.PrefixLength;
+                Some(Route {
+                    destination,
+                    prefix_len,
+                    gateway: unsafe { inet_ip(&row.NextHop) }?,
+                    interface: names.get(&row.InterfaceIndex).cloned().unwrap_or_else(|| row.InterfaceIndex.to_string()),
+                    metric: row.Metric,
+                    is_default: prefix_len == 0,
+                })
+            })
+            .collect();
+        unsafe { FreeMibTable(table as *const _) }?;
+        Ok(routes)
+    }
+
+    pub fn dns_resolvers() -> Result<Vec<SocketAddr>> {
+        let mut servers = Vec::new();
+        for adapter in adapters()? {
+            if matches!(adapter.interface.status, InterfaceStatus::Up) {
+                for server in adapter.dns_servers {
+                    if !servers.contains(&server) {
+                        servers.push(server);
+                    }
+                }
+            }
+        }
+        Ok(servers)
+    }
+
+    pub fn interface_index(name: &str) -> Option<u32> {
+        adapters().ok()?.into_iter().find(|adapter| adapter.interface.name == name).map(|adapter| adapter.index)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub fn interfaces() -> Result<Vec<NetworkInterface>> {
+        Ok(Vec::new())
+    }
+
+    pub fn routes() -> Result<Vec<Route>> {
+        Ok(Vec::new())
+    }
+
+    pub fn dns_resolvers() -> Result<Vec<SocketAddr>> {
+        Ok(Vec::new())
+    }
+
+    pub fn interface_index(_name: &str) -> Option<u32> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_sysfs_interfaces() {
+        let root = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("lo/ifindex", "1\n");
+        write("lo/operstate", "unknown\n");
+        write("lo/flags", "0x9\n");
+        write("lo/carrier", "1\n");
+        write("lo/address", "00:00:00:00:00:00\n");
+        write("lo/mtu", "65536\n");
+        write("eth0/ifindex", "2\n");
+        write("eth0/operstate", "up\n");
+        write("eth0/flags", "0x1003\n");
+        write("eth0/carrier", "1\n");
+        write("eth0/address", "52:54:00:12:34:56\n");
+        write("eth0/mtu", "1500\n");
+        write("eth0/speed", "1000\n");
+        write("eth0/duplex", "full\n");
+        write("eth0/statistics/rx_bytes", "2048\n");
+        write("eth0/statistics/tx_bytes", "1024\n");
+        write("eth0/statistics/rx_errors", "3\n");
+        write("wlan0/ifindex", "3\n");
+        write("wlan0/operstate", "down\n");
+        write("wlan0/speed", "-1\n");
+
+        let addresses = HashMap::from([("eth0".to_string(), vec!["192.168.1.100".parse().unwrap()])]);
+        let interfaces = read_sysfs_interfaces(root.path(), &addresses);
+        let names: Vec<_> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["lo", "eth0", "wlan0"]);
+
+        assert!(matches!(interfaces[0].status, InterfaceStatus::Up));
+        assert_eq!(interfaces[0].mac_address, None);
+        let eth0 = &interfaces[1];
+        assert_eq!(eth0.ip_addresses, addresses["eth0"]);
+        assert_eq!(eth0.index, Some(2));
+        assert_eq!(eth0.mac_address.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!((eth0.mtu, eth0.speed, eth0.carrier), (1500, Some(1000), Some(true)));
+        assert!(matches!(eth0.duplex, DuplexMode::Full));
+        assert_eq!(eth0.statistics.bytes_received, 2048);
+        assert_eq!(eth0.statistics.errors_received, 3);
+        assert!(matches!(interfaces[2].status, InterfaceStatus::Down));
+        assert_eq!((interfaces[2].speed, interfaces[2].carrier), (None, None));
+    }
+
+    #[test]
+    fn test_routes_and_dns() {
+        let ipv4 = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+            eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+            eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n";
+        let routes = parse_ipv4_routes(ipv4);
+        assert_eq!(routes.len(), 2);
+        assert!(routes[0].is_default);
+        assert_eq!(routes[1].destination, "192.168.1.0".parse::<IpAddr>().unwrap());
+        assert_eq!(routes[1].prefix_len, 24);
+
+        let ipv6 = "00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003 eth0\n\
+            fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0\n\
+            00000000000000000000000000000001 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000002 00000000 80200001 lo\n\
+            00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200 lo\n";
+        let routes_v6 = parse_ipv6_routes(ipv6);
+        assert_eq!(routes_v6.len(), 2);
+        assert_eq!(routes_v6[0].gateway, "fe80::1".parse::<IpAddr>().unwrap());
+        assert_eq!((routes_v6[1].prefix_len, routes_v6[1].metric), (64, 256));
+
+        let all: Vec<Route> = routes.into_iter().chain(routes_v6).collect();
+        assert_eq!(default_gateway(&all, &AddressFamilyConfig::default()), Some("192.168.1.1".parse().unwrap()));
+        let prefer_v6 = AddressFamilyConfig { prefer: IpFamily::V6, ..Default::default() };
+        assert_eq!(default_gateway(&all, &prefer_v6), Some("fe80::1".parse().unwrap()));
+        let v4_only = AddressFamilyConfig { ipv6: false, ..prefer_v6 };
+        assert_eq!(default_gateway(&all, &v4_only), Some("192.168.1.1".parse().unwrap()));
+
+        let resolv = "# generado\nsearch lan\nnameserver 192.168.1.1\nnameserver fe80::1%eth0\nnameserver fe80::2%7\noptions edns0\n";
+        let resolvers: Vec<String> = parse_resolv_conf(resolv, |name| (name == "eth0").then_some(2))
+            .iter()
+            .map(|resolver| resolver.to_string())
+            .collect();
+        assert_eq!(resolvers, ["192.168.1.1:53", "[fe80::1%2]:53", "[fe80::2%7]:53"]);
+
+        let netstat = "Routing tables\n\nInternet:\n\
+            Destination        Gateway            Flags               Netif Expire\n\
+            default            192.168.1.1        UGScg                 en0\n\
+            127                127.0.0.1          UCS                   lo0\n\
+            192.168.1          link#6             UCS                   en0      !\n\
+            192.168.1.23       a4:83:e7:0:0:1     UHLWIi                en0   1187\n\n\
+            Internet6:\n\
+            Destination        Gateway            Flags               Netif Expire\n\
+            default            fe80::1%en0        UGcg                  en0\n\
+            fe80::%lo0/64      fe80::1%lo0        UcI                   lo0\n";
+        let routes = parse_netstat_routes(netstat);
+        assert_eq!(routes.len(), 5);
+        assert_eq!((routes[1].destination, routes[1].prefix_len), ("127.0.0.0".parse().unwrap(), 8));
+        assert_eq!(routes[2].gateway, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(routes[3].gateway, "fe80::1".parse::<IpAddr>().unwrap());
+        assert_eq!(routes[4].prefix_len, 64);
+    }
+}
@@ -5,6 +5,7 @@
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
@@ -16,8 +17,22 @@ use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::communication::CognitiveFabric;
+use crate::config::{CoreConfig, NetworkCoreConfig};
 use crate::metrics::MetricsCollector;
-use crate::nano_cores::{NanoCore, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::nano_cores::bandwidth::{BandwidthAccounting, BandwidthConfig, TopTalkers};
+use crate::nano_cores::capture;
+use crate::nano_cores::connections::{self, ConnectionTracker};
+use crate::nano_cores::dns_monitor::DnsMonitor;
+use crate::nano_cores::firewall;
+use crate::nano_cores::ip_family::{self, DualStackStatus, IpFamily};
+use crate::nano_cores::link_events::LinkMonitor;
+use crate::nano_cores::netinfo;
+use crate::nano_cores::shaping::{self, ShapingPlan, StatsSampler, TrafficClassStats};
+use crate::nano_cores::throughput::{self, ThroughputProtocol, ThroughputServer};
+use crate::nano_cores::watchdog::{self, ConnectivityWatchdog};
+use crate::nano_cores::{CommandAuthorization, CoreResult, NanoCore, NanoCoreError, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::security::SecurityLevel;
+use crate::util::rng;
 
 /// Información de conectividad de red
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,24 +41,44 @@ pub struct NetworkConnectivity {
     pub active_connections: Vec<Connection>,
     pub routing_table: Vec<Route>,
     pub dns_servers: Vec<IpAddr>,
+    /// Gateway por defecto de la familia preferida
     pub gateway: Option<IpAddr>,
+    /// Direcciones, gateway y ruta de cada familia
+    #[serde(default)]
+    pub dual_stack: DualStackStatus,
     pub total_bandwidth: u64,
     pub available_bandwidth: u64,
+    /// Clases de tráfico moldeadas; solo en `network.metrics`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub traffic_classes: Vec<TrafficClassStats>,
 }
 
 /// Interfaz de red
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInterface {
     pub name: String,
+    /// Índice del sistema; es la zona de sus direcciones de enlace local
+    #[serde(default)]
+    pub index: Option<u32>,
     pub ip_addresses: Vec<IpAddr>,
     pub mac_address: Option<String>,
     pub mtu: u32,
     pub speed: Option<u64>, // Mbps
     pub duplex: DuplexMode,
     pub status: InterfaceStatus,
+    /// Enlace físico detectado; `None` si la plataforma no lo informa
+    #[serde(default)]
+    pub carrier: Option<bool>,
     pub statistics: InterfaceStatistics,
 }
 
+impl NetworkInterface {
+    /// Interfaz con alguna dirección asignada
+    pub fn is_configured(&self) -> bool {
+        !self.ip_addresses.is_empty()
+    }
+}
+
 /// Modo duplex
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DuplexMode {
@@ -62,7 +97,7 @@ pub enum InterfaceStatus {
 }
 
 /// Estadísticas de interfaz
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InterfaceStatistics {
     pub bytes_sent: u64,
     pub bytes_received: u64,
@@ -88,10 +123,26 @@ pub struct Connection {
     pub bytes_received: u64,
     pub latency: Option<Duration>,
     pub quality_metrics: QualityMetrics,
+    /// Proceso dueño del socket, si se pudo determinar
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub process: Option<String>,
+    /// Cambios de estado observados, del más antiguo al más reciente
+    #[serde(default)]
+    pub transitions: Vec<StateTransition>,
 }
 
-/// Protocolo de red
+/// Cambio de estado de una conexión entre dos lecturas
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub from: ConnectionState,
+    pub to: ConnectionState,
+    pub at: SystemTime,
+}
+
+/// Protocolo de red
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum Protocol {
     TCP,
     UDP,
@@ -103,7 +154,7 @@ pub enum Protocol {
 }
 
 /// Estado de conexión
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConnectionState {
     Established,
     Connecting,
@@ -127,12 +178,28 @@ pub struct QualityMetrics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Route {
     pub destination: IpAddr,
+    #[serde(default)]
+    pub prefix_len: u8,
     pub gateway: IpAddr,
     pub interface: String,
     pub metric: u32,
     pub is_default: bool,
 }
 
+/// Tablas de rutas separadas por familia
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingTables {
+    pub ipv4: Vec<Route>,
+    pub ipv6: Vec<Route>,
+}
+
+impl RoutingTables {
+    pub fn split(routes: Vec<Route>) -> Self {
+        let (ipv4, ipv6) = routes.into_iter().partition(|route| IpFamily::of(&route.destination) == IpFamily::V4);
+        Self { ipv4, ipv6 }
+    }
+}
+
 /// Configuración de QoS
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QoSConfig {
@@ -154,12 +221,17 @@ pub struct TrafficClass {
 }
 
 /// Filtro de paquetes
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PacketFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol: Option<Protocol>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub destination_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source_ip: Option<IpAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub destination_ip: Option<IpAddr>,
 }
 
@@ -193,16 +265,102 @@ pub enum NetworkCommand {
     OptimizeQoS,
     GetConnectionStats,
     MonitorBandwidth,
-    ConfigureFirewall(FirewallRule),
-    TestThroughput(SocketAddr),
+    /// Procesos y hosts remotos con más tráfico en la ventana
+    GetTopTalkers {
+        #[serde(default)]
+        window_secs: Option<u64>,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    /// Añadir una regla; con `dry_run` solo se devuelven los comandos
+    ConfigureFirewall {
+        rule: FirewallRule,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Estado de los resolvers y caché del monitor DNS
+    GetDnsHealth,
+    /// Puntuación de alcanzabilidad y estado de cada destino del watchdog
+    GetWatchdog,
+    /// Bajar y subir una interfaz; es la remediación que aprueba el consenso
+    RestartInterface {
+        interface: String,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Medir cada resolver; sin nombres se usan los de sondeo
+    BenchmarkDns {
+        #[serde(default)]
+        names: Vec<String>,
+        #[serde(default)]
+        rounds: Option<u32>,
+    },
+    /// Reglas propias instaladas en el firewall del sistema
+    ListFirewallRules,
+    RemoveFirewallRule {
+        id: String,
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Enviar a un NetworkCore en modo escucha; sin duración ni ancho de banda se usan los de la configuración
+    TestThroughput {
+        target: SocketAddr,
+        #[serde(default)]
+        protocol: ThroughputProtocol,
+        #[serde(default)]
+        duration_secs: Option<u64>,
+        #[serde(default)]
+        bandwidth_mbps: Option<f64>,
+    },
+    /// Tablas de rutas IPv4 e IPv6 por separado
     GetRoutingTable,
+    /// Captura acotada con resumen de flujos; sin duración ni límite se usan los de la configuración
+    CapturePackets {
+        interface: String,
+        #[serde(default)]
+        bpf_filter: Option<String>,
+        #[serde(default)]
+        duration_secs: Option<u64>,
+        #[serde(default)]
+        max_packets: Option<u64>,
+    },
+}
+
+impl NetworkCommand {
+    /// Permiso y nivel exigidos para ejecutar el comando
+    pub fn authorization(&self) -> CommandAuthorization {
+        match self {
+            NetworkCommand::GetConnectivity
+            | NetworkCommand::GetConnectionStats
+            | NetworkCommand::MonitorBandwidth
+            | NetworkCommand::GetTopTalkers { .. }
+            | NetworkCommand::GetRoutingTable
+            | NetworkCommand::GetDnsHealth
+            | NetworkCommand::GetWatchdog => CommandAuthorization::new("nano_core.network.status", SecurityLevel::Internal),
+            NetworkCommand::TestLatency(_) | NetworkCommand::TestThroughput { .. } | NetworkCommand::BenchmarkDns { .. } => {
+                CommandAuthorization::new("nano_core.network.diagnose", SecurityLevel::Internal)
+            }
+            NetworkCommand::OptimizeQoS => CommandAuthorization::new("nano_core.network.qos", SecurityLevel::Confidential),
+            NetworkCommand::ListFirewallRules => CommandAuthorization::new("nano_core.network.firewall", SecurityLevel::Confidential),
+            NetworkCommand::ConfigureFirewall { .. } | NetworkCommand::RemoveFirewallRule { .. } => {
+                CommandAuthorization::new("nano_core.network.firewall", SecurityLevel::Secret)
+            }
+            NetworkCommand::CapturePackets { .. } => CommandAuthorization::new("nano_core.network.capture", SecurityLevel::Secret),
+            NetworkCommand::RestartInterface { .. } => CommandAuthorization::new("nano_core.network.remediate", SecurityLevel::Secret),
+        }
+    }
 }
 
 /// Regla de firewall
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FirewallRule {
     pub action: FirewallAction,
+    #[serde(default)]
+    pub direction: FirewallDirection,
     pub protocol: Option<Protocol>,
+    /// Familia a filtrar cuando la regla no tiene direcciones; si las tiene debe coincidir
+    #[serde(default)]
+    pub family: Option<IpFamily>,
     pub source: Option<IpAddr>,
     pub destination: Option<IpAddr>,
     pub port: Option<u16>,
@@ -216,9 +374,18 @@ pub enum FirewallAction {
     Log,
 }
 
+/// Tráfico al que se aplica la regla; `port` es siempre el puerto de destino
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FirewallDirection {
+    #[default]
+    Inbound,
+    Outbound,
+}
+
 /// Nano-Core para gestión de red
 pub struct NetworkCore {
     instance_id: Uuid,
+    config: NetworkCoreConfig,
     cognitive_fabric: Arc<CognitiveFabric>,
     metrics: Arc<MetricsCollector>,
     instance_number: usize,
@@ -228,6 +395,17 @@ pub struct NetworkCore {
     qos_manager: QoSManager,
     latency_monitor: LatencyMonitor,
     bandwidth_monitor: BandwidthMonitor,
+    dns_monitor: DnsMonitor,
+    link_monitor: LinkMonitor,
+    watchdog: ConnectivityWatchdog,
+    throughput_server: Option<ThroughputServer>,
+}
+
+/// Estado de `NetworkCore` entre reemplazos y reinicios
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct NetworkCoreCheckpoint {
+    error_count: u64,
 }
 
 impl NetworkCore {
@@ -236,28 +414,116 @@ impl NetworkCore {
         cognitive_fabric: Arc<CognitiveFabric>,
         metrics: Arc<MetricsCollector>,
         instance_number: usize,
+        config: NetworkCoreConfig,
     ) -> Result<Self> {
+        let bandwidth_monitor = BandwidthMonitor::new(config.bandwidth.clone());
+        let dns_monitor = DnsMonitor::new(config.dns_monitor.clone(), config.address_family.clone());
+        let link_monitor = LinkMonitor::new(config.link_events.clone());
+        let watchdog = ConnectivityWatchdog::new(config.watchdog.clone(), config.address_family.clone());
+        
         Ok(Self {
             instance_id: Uuid::new_v4(),
+            qos_manager: QoSManager::new(&config),
+            config,
             cognitive_fabric,
             metrics,
             instance_number,
             start_time: SystemTime::now(),
             error_count: Arc::new(RwLock::new(0)),
             connection_monitor: ConnectionMonitor::new(),
-            qos_manager: QoSManager::new(),
             latency_monitor: LatencyMonitor::new(),
-            bandwidth_monitor: BandwidthMonitor::new(),
+            bandwidth_monitor,
+            dns_monitor,
+            link_monitor,
+            watchdog,
+            throughput_server: None,
         })
     }
 
+    /// Arrancar o parar el receptor de pruebas de throughput según la configuración
+    async fn sync_throughput_server(&mut self) {
+        let listen = &self.config.throughput;
+        let wanted = match listen.listen_addr.parse::<SocketAddr>() {
+            Ok(addr) if listen.listen => Some(addr),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("⚠️  listen_addr de throughput inválida '{}': {}", listen.listen_addr, e);
+                None
+            }
+        };
+        let current = self.throughput_server.as_ref().map(|server| server.local_addr());
+        if let (Some(addr), Some(current)) = (wanted, current) {
+            if addr.port() == 0 || addr == current {
+                return;
+            }
+        }
+
+        if let Some(server) = self.throughput_server.take() {
+            server.stop();
+        }
+        if let Some(addr) = wanted {
+            // Sin receptor el resto del núcleo sigue funcionando (p. ej. varias instancias en el mismo puerto)
+            match ThroughputServer::start(addr).await {
+                Ok(server) => self.throughput_server = Some(server),
+                Err(e) => warn!("⚠️  NetworkCore instancia {} no pudo escuchar pruebas de throughput en {}: {}", self.instance_number, addr, e),
+            }
+        }
+    }
+
+    /// Ejecutar una prueba de throughput contra otro NetworkCore
+    async fn test_throughput(
+        &self,
+        target: SocketAddr,
+        protocol: ThroughputProtocol,
+        duration_secs: Option<u64>,
+        bandwidth_mbps: Option<f64>,
+    ) -> Result<throughput::ThroughputResult> {
+        self.config.address_family.check(&target.ip())?;
+        let limits = &self.config.throughput;
+        let duration = duration_secs.unwrap_or(limits.default_duration_secs).clamp(1, limits.max_duration_secs.max(1));
+        let bandwidth = match bandwidth_mbps {
+            Some(mbps) if mbps > 0.0 => mbps.min(limits.max_bandwidth_mbps),
+            _ => limits.max_bandwidth_mbps,
+        };
+
+        info!("📶 Prueba de throughput {:?} a {} ({} s, máx. {} Mbps)", protocol, target, duration, bandwidth);
+        let result = throughput::run(
+            target,
+            protocol,
+            Duration::from_secs(duration),
+            bandwidth,
+            Duration::from_millis(self.config.timeout_ms),
+        )
+        .await?;
+        info!("📶 Throughput a {}: {:.1} Mbps", target, result.goodput_mbps);
+        Ok(result)
+    }
+
+    /// Capturar tráfico dentro de los límites de la configuración
+    async fn capture_packets(
+        &self,
+        interface: &str,
+        bpf_filter: Option<&str>,
+        duration_secs: Option<u64>,
+        max_packets: Option<u64>,
+    ) -> Result<capture::CaptureReport> {
+        let limits = &self.config.capture;
+        let duration = duration_secs.unwrap_or(limits.default_duration_secs).clamp(1, limits.max_duration_secs.max(1));
+        let max_packets = max_packets.unwrap_or(limits.default_max_packets).clamp(1, limits.max_packets.max(1));
+        capture::capture(interface, bpf_filter, Duration::from_secs(duration), max_packets, limits.top).await
+    }
+
     /// Obtener información de conectividad
     async fn get_connectivity(&self) -> Result<NetworkConnectivity> {
         let interfaces = self.get_network_interfaces().await?;
         let active_connections = self.connection_monitor.get_active_connections().await?;
         let routing_table = self.get_routing_table().await?;
         let dns_servers = self.get_dns_servers().await?;
-        let gateway = self.get_default_gateway().await?;
+        let gateway = netinfo::default_gateway(&routing_table, &self.config.address_family);
+        let dual_stack = {
+            let (families, interfaces, routes) = (self.config.address_family.clone(), interfaces.clone(), routing_table.clone());
+            tokio::task::spawn_blocking(move || ip_family::probe(&families, &interfaces, &routes)).await?
+        };
         let (total_bandwidth, available_bandwidth) = self.bandwidth_monitor.get_bandwidth_info().await?;
 
         Ok(NetworkConnectivity {
@@ -266,111 +532,63 @@ impl NetworkCore {
             routing_table,
             dns_servers,
             gateway,
+            dual_stack,
             total_bandwidth,
             available_bandwidth,
+            traffic_classes: Vec::new(),
         })
     }
 
     /// Obtener interfaces de red
     async fn get_network_interfaces(&self) -> Result<Vec<NetworkInterface>> {
-        let mut interfaces = Vec::new();
-        
-        // En una implementación real, esto usaría APIs del sistema operativo
-        // Por ahora, simulamos algunas interfaces comunes
-        interfaces.push(NetworkInterface {
-            name: "eth0".to_string(),
-            ip_addresses: vec!["192.168.1.100".parse()?],
-            mac_address: Some("00:11:22:33:44:55".to_string()),
-            mtu: 1500,
-            speed: Some(1000), // 1 Gbps
-            duplex: DuplexMode::Full,
-            status: InterfaceStatus::Up,
-            statistics: InterfaceStatistics {
-                bytes_sent: 1024 * 1024 * 100, // 100 MB
-                bytes_received: 1024 * 1024 * 200, // 200 MB
-                packets_sent: 10000,
-                packets_received: 15000,
-                errors_sent: 0,
-                errors_received: 2,
-                dropped_sent: 0,
-                dropped_received: 1,
-                collisions: 0,
-            },
-        });
-
-        interfaces.push(NetworkInterface {
-            name: "lo".to_string(),
-            ip_addresses: vec!["127.0.0.1".parse()?],
-            mac_address: None,
-            mtu: 65536,
-            speed: None,
-            duplex: DuplexMode::Full,
-            status: InterfaceStatus::Up,
-            statistics: InterfaceStatistics {
-                bytes_sent: 1024 * 50,
-                bytes_received: 1024 * 50,
-                packets_sent: 500,
-                packets_received: 500,
-                errors_sent: 0,
-                errors_received: 0,
-                dropped_sent: 0,
-                dropped_received: 0,
-                collisions: 0,
-            },
-        });
-
-        Ok(interfaces)
+        tokio::task::spawn_blocking(netinfo::interfaces).await?
     }
 
     /// Obtener tabla de rutas
     async fn get_routing_table(&self) -> Result<Vec<Route>> {
-        // Simulación de tabla de rutas
-        Ok(vec![
-            Route {
-                destination: "0.0.0.0".parse()?,
-                gateway: "192.168.1.1".parse()?,
-                interface: "eth0".to_string(),
-                metric: 100,
-                is_default: true,
-            },
-            Route {
-                destination: "192.168.1.0".parse()?,
-                gateway: "0.0.0.0".parse()?,
-                interface: "eth0".to_string(),
-                metric: 0,
-                is_default: false,
-            },
-        ])
+        tokio::task::spawn_blocking(netinfo::routes).await?
     }
 
     /// Obtener servidores DNS
     async fn get_dns_servers(&self) -> Result<Vec<IpAddr>> {
-        // En una implementación real, esto leería /etc/resolv.conf o registro de Windows
-        Ok(vec![
-            "8.8.8.8".parse()?,
-            "8.8.4.4".parse()?,
-            "1.1.1.1".parse()?,
-        ])
-    }
-
-    /// Obtener gateway por defecto
-    async fn get_default_gateway(&self) -> Result<Option<IpAddr>> {
-        Ok(Some("192.168.1.1".parse()?))
+        tokio::task::spawn_blocking(netinfo::dns_servers).await?
     }
 
     /// Probar latencia a un destino
     async fn test_latency(&self, target: IpAddr) -> Result<LatencyTest> {
+        self.config.address_family.check(&target)?;
         self.latency_monitor.test_latency(target).await
     }
 
     /// Optimizar QoS
-    async fn optimize_qos(&self) -> Result<String> {
+    async fn optimize_qos(&self) -> Result<ShapingPlan> {
         self.qos_manager.optimize().await
     }
 
+    /// Aplicar el shaping si está configurado, o retirarlo si QoS se deshabilitó
+    async fn sync_shaping(&self) {
+        let result = if !self.config.qos_enabled {
+            self.qos_manager.clear().await
+        } else if self.config.qos.shaping {
+            self.qos_manager.optimize().await.map(|_| ())
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            warn!("⚠️  NetworkCore instancia {} no pudo aplicar el shaping QoS: {}", self.instance_number, e);
+        }
+    }
+
     /// Publicar métricas de red
     async fn publish_network_metrics(&self) -> Result<()> {
-        let connectivity = self.get_connectivity().await?;
+        let mut connectivity = self.get_connectivity().await?;
+        match self.qos_manager.statistics().await {
+            Ok(stats) => {
+                self.metrics.record_traffic_classes(&stats).await;
+                connectivity.traffic_classes = stats;
+            }
+            Err(e) => debug!("Sin estadísticas de clases QoS: {}", e),
+        }
         
         // Publicar en el Cognitive Fabric
         let metrics_data = serde_json::to_vec(&connectivity)?;
@@ -400,25 +618,10 @@ impl NetworkCore {
         Ok(())
     }
 
-    /// Verificar alertas de red
+    /// Verificar alertas de red; las caídas de interfaz las publica `LinkMonitor` al producirse
     async fn check_network_alerts(&self) -> Result<()> {
         let connectivity = self.get_connectivity().await?;
         
-        // Verificar interfaces caídas
-        for interface in &connectivity.interfaces {
-            if matches!(interface.status, InterfaceStatus::Down) {
-                warn!("🔌 Interfaz de red caída: {}", interface.name);
-                
-                self.cognitive_fabric
-                    .publish("network.alerts", &serde_json::to_vec(&serde_json::json!({
-                        "type": "interface_down",
-                        "interface": interface.name,
-                        "timestamp": SystemTime::now()
-                    }))?)
-                    .await?;
-            }
-        }
-        
         // Verificar alta tasa de errores
         for interface in &connectivity.interfaces {
             let total_packets = interface.statistics.packets_sent + interface.statistics.packets_received;
@@ -476,20 +679,19 @@ impl NanoCore for NetworkCore {
             self.instance_id
         );
 
-        // Suscribirse a comandos de red
-        self.cognitive_fabric
-            .subscribe("network.commands", {
-                let instance_id = self.instance_id;
-                move |data| {
-                    debug!("📨 NetworkCore {} recibió comando: {} bytes", instance_id, data.len());
-                }
-            })
-            .await?;
+        if self.config.enable_dpdk {
+            info!("⚡ NetworkCore instancia {} con DPDK habilitado", self.instance_number);
+        }
 
         // Inicializar monitores
         self.connection_monitor.start().await?;
         self.bandwidth_monitor.start().await?;
         self.latency_monitor.start().await?;
+        self.dns_monitor.start(self.cognitive_fabric.clone()).await?;
+        self.link_monitor.start(self.cognitive_fabric.clone()).await?;
+        self.watchdog.start(self.cognitive_fabric.clone(), self.instance_number).await?;
+        self.sync_throughput_server().await;
+        self.sync_shaping().await;
 
         // Publicar información inicial de red
         let connectivity = self.get_connectivity().await?;
@@ -503,20 +705,18 @@ impl NanoCore for NetworkCore {
         Ok(())
     }
 
-    async fn run(&mut self) -> Result<()> {
+    async fn run(&mut self) -> CoreResult<()> {
         // Publicar métricas de red
         if let Err(e) = self.publish_network_metrics().await {
             let mut error_count = self.error_count.write().await;
             *error_count += 1;
-            return Err(anyhow!("Error publicando métricas de red: {}", e));
+            return Err(NanoCoreError::Transient(anyhow!("Error publicando métricas de red: {}", e)));
         }
 
         // Verificar alertas de red
         if let Err(e) = self.check_network_alerts().await {
             warn!("⚠️  Error verificando alertas de red: {}", e);
         }
-
-        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
         Ok(())
     }
 
@@ -526,16 +726,23 @@ impl NanoCore for NetworkCore {
         
         // Evaluar salud basada en conectividad
         let connectivity = self.get_connectivity().await?;
-        let active_interfaces = connectivity.interfaces.iter()
+        // Solo cuentan las interfaces con direcciones (no bridges ni NICs sin configurar)
+        let configured: Vec<&NetworkInterface> = connectivity.interfaces.iter()
+            .filter(|i| i.is_configured())
+            .collect();
+        let active_interfaces = configured.iter()
             .filter(|i| matches!(i.status, InterfaceStatus::Up))
             .count();
         
         let cpu_usage = 10.0 + (active_interfaces as f64 * 5.0); // Estimación
         let memory_usage = 20.0 + (connectivity.active_connections.len() as f64 * 0.1);
         
+        // Destinos inalcanzables degradan el núcleo, pero reemplazarlo no arregla la red
+        let unreachable = self.watchdog.score().await.is_some_and(|score| score < self.config.watchdog.degraded_score);
+        
         let state = if error_count > 10 || active_interfaces == 0 {
             NanoCoreState::Failed
-        } else if active_interfaces < connectivity.interfaces.len() {
+        } else if active_interfaces < configured.len() || unreachable {
             NanoCoreState::Degraded
         } else {
             NanoCoreState::Running
@@ -560,17 +767,26 @@ impl NanoCore for NetworkCore {
         self.connection_monitor.stop().await?;
         self.bandwidth_monitor.stop().await?;
         self.latency_monitor.stop().await?;
-        
-        // Desuscribirse de eventos
-        self.cognitive_fabric
-            .unsubscribe("network.commands")
-            .await?;
+        self.dns_monitor.stop().await?;
+        self.link_monitor.stop().await?;
+        self.watchdog.stop().await?;
+        if let Some(server) = self.throughput_server.take() {
+            server.stop();
+        }
+        // No dejar el qdisc del host modificado
+        if let Err(e) = self.qos_manager.clear().await {
+            warn!("⚠️  Error retirando el shaping QoS: {}", e);
+        }
 
         info!("✅ NetworkCore instancia {} detenido correctamente", self.instance_number);
         Ok(())
     }
 
-    async fn process_command(&mut self, command: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    fn command_authorization(&self, payload: &[u8]) -> Result<CommandAuthorization> {
+        Ok(serde_json::from_slice::<NetworkCommand>(payload)?.authorization())
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
         let cmd: NetworkCommand = serde_json::from_slice(payload)?;
         
         let response = match cmd {
@@ -594,37 +810,87 @@ impl NanoCore for NetworkCore {
                 let bandwidth_info = self.bandwidth_monitor.get_bandwidth_info().await?;
                 serde_json::to_vec(&bandwidth_info)?
             }
-            NetworkCommand::ConfigureFirewall(rule) => {
-                // TODO: Implementar configuración de firewall
-                let result = format!("Regla de firewall configurada: {:?}", rule);
-                serde_json::to_vec(&result)?
+            NetworkCommand::GetTopTalkers { window_secs, limit } => {
+                serde_json::to_vec(&self.bandwidth_monitor.top_talkers(window_secs, limit).await)?
+            }
+            NetworkCommand::ConfigureFirewall { rule, dry_run } => {
+                let change = firewall::apply(&rule, &self.config.firewall, dry_run).await?;
+                serde_json::to_vec(&change)?
+            }
+            NetworkCommand::GetDnsHealth => serde_json::to_vec(&self.dns_monitor.health().await)?,
+            NetworkCommand::GetWatchdog => serde_json::to_vec(&self.watchdog.report().await)?,
+            NetworkCommand::RestartInterface { interface, dry_run } => {
+                let restart = watchdog::restart_interface(&interface, dry_run).await?;
+                serde_json::to_vec(&restart)?
+            }
+            NetworkCommand::BenchmarkDns { names, rounds } => {
+                let results = self.dns_monitor.benchmark(&names, rounds.unwrap_or(5).clamp(1, 50)).await?;
+                serde_json::to_vec(&results)?
             }
-            NetworkCommand::TestThroughput(target) => {
-                // TODO: Implementar prueba de throughput
-                let result = format!("Prueba de throughput a {}: 100 Mbps", target);
+            NetworkCommand::ListFirewallRules => serde_json::to_vec(&firewall::list(&self.config.firewall).await?)?,
+            NetworkCommand::RemoveFirewallRule { id, dry_run } => {
+                let change = firewall::remove(&id, &self.config.firewall, dry_run).await?;
+                serde_json::to_vec(&change)?
+            }
+            NetworkCommand::TestThroughput { target, protocol, duration_secs, bandwidth_mbps } => {
+                let result = self.test_throughput(target, protocol, duration_secs, bandwidth_mbps).await?;
                 serde_json::to_vec(&result)?
             }
             NetworkCommand::GetRoutingTable => {
-                let routing_table = self.get_routing_table().await?;
+                let routing_table = RoutingTables::split(self.get_routing_table().await?);
                 serde_json::to_vec(&routing_table)?
             }
+            NetworkCommand::CapturePackets { interface, bpf_filter, duration_secs, max_packets } => {
+                let report = self.capture_packets(&interface, bpf_filter.as_deref(), duration_secs, max_packets).await?;
+                serde_json::to_vec(&report)?
+            }
         };
 
         debug!("✅ Comando NetworkCore procesado: {}", command);
         Ok(response)
     }
+    
+    async fn apply_config(&mut self, config: &CoreConfig) -> Result<()> {
+        self.config = config.nano_cores.for_instance(self.instance_number)?.network_core;
+        self.sync_throughput_server().await;
+        self.qos_manager.reconfigure(&self.config).await;
+        self.dns_monitor.reconfigure(self.config.dns_monitor.clone(), self.config.address_family.clone()).await;
+        self.bandwidth_monitor.reconfigure(self.config.bandwidth.clone()).await;
+        self.link_monitor.reconfigure(self.config.link_events.clone()).await;
+        self.watchdog.reconfigure(self.config.watchdog.clone(), self.config.address_family.clone()).await;
+        self.sync_shaping().await;
+        debug!("📋 NetworkCore instancia {} reconfigurada", self.instance_number);
+        Ok(())
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_secs(3)
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&NetworkCoreCheckpoint {
+            error_count: *self.error_count.read().await,
+        })?)
+    }
+
+    async fn restore(&mut self, state: &[u8]) -> Result<()> {
+        let checkpoint: NetworkCoreCheckpoint = serde_json::from_slice(state)?;
+        *self.error_count.write().await = checkpoint.error_count;
+        debug!("♻️  NetworkCore instancia {} restaurada", self.instance_number);
+        Ok(())
+    }
 }
 
 /// Monitor de conexiones
 pub struct ConnectionMonitor {
-    active_connections: Arc<RwLock<Vec<Connection>>>,
+    tracker: Arc<RwLock<ConnectionTracker>>,
     is_running: Arc<RwLock<bool>>,
 }
 
 impl ConnectionMonitor {
     pub fn new() -> Self {
         Self {
-            active_connections: Arc::new(RwLock::new(Vec::new())),
+            tracker: Arc::new(RwLock::new(ConnectionTracker::new())),
             is_running: Arc::new(RwLock::new(false)),
         }
     }
@@ -633,36 +899,16 @@ impl ConnectionMonitor {
         *self.is_running.write().await = true;
         
         // Iniciar monitoreo en background
-        let connections = self.active_connections.clone();
+        let tracker = self.tracker.clone();
         let is_running = self.is_running.clone();
         
         tokio::spawn(async move {
             while *is_running.read().await {
-                // Simular actualización de conexiones
-                let mut conns = connections.write().await;
-                conns.clear();
-                
-                // Agregar algunas conexiones simuladas
-                conns.push(Connection {
-                    id: "conn-1".to_string(),
-                    protocol: Protocol::TCP,
-                    local_address: "127.0.0.1:8080".parse().unwrap(),
-                    remote_address: "192.168.1.50:443".parse().unwrap(),
-                    state: ConnectionState::Established,
-                    established_time: SystemTime::now(),
-                    bytes_sent: 1024 * 100,
-                    bytes_received: 1024 * 200,
-                    latency: Some(Duration::from_millis(15)),
-                    quality_metrics: QualityMetrics {
-                        latency_ms: 15.0,
-                        jitter_ms: 2.0,
-                        packet_loss_rate: 0.001,
-                        throughput_mbps: 50.0,
-                        quality_score: 0.95,
-                    },
-                });
-                
-                drop(conns);
+                match tokio::task::spawn_blocking(connections::sockets).await {
+                    Ok(Ok(sockets)) => tracker.write().await.update(sockets, Instant::now(), SystemTime::now()),
+                    Ok(Err(e)) => warn!("⚠️  Error leyendo la tabla de conexiones: {}", e),
+                    Err(e) => warn!("⚠️  Error leyendo la tabla de conexiones: {}", e),
+                }
                 tokio::time::sleep(Duration::from_secs(5)).await;
             }
         });
@@ -676,54 +922,84 @@ impl ConnectionMonitor {
     }
 
     pub async fn get_active_connections(&self) -> Result<Vec<Connection>> {
-        Ok(self.active_connections.read().await.clone())
+        Ok(self.tracker.read().await.connections())
     }
 }
 
 /// Gestor de QoS
 pub struct QoSManager {
     config: Arc<RwLock<QoSConfig>>,
+    settings: Arc<RwLock<shaping::QosConfig>>,
+    /// Jerarquía tc instalada por este gestor
+    applied: Arc<RwLock<Option<ShapingPlan>>>,
+    sampler: Arc<tokio::sync::Mutex<StatsSampler>>,
 }
 
 impl QoSManager {
-    pub fn new() -> Self {
-        let default_config = QoSConfig {
-            enabled: true,
-            traffic_classes: vec![
-                TrafficClass {
-                    name: "Critical".to_string(),
-                    priority: 1,
-                    bandwidth_guarantee: 1024 * 1024 * 10, // 10 MB/s
-                    max_bandwidth: 1024 * 1024 * 50, // 50 MB/s
-                    latency_target: Duration::from_millis(1),
-                    packet_filters: vec![],
-                },
-                TrafficClass {
-                    name: "High".to_string(),
-                    priority: 2,
-                    bandwidth_guarantee: 1024 * 1024 * 5, // 5 MB/s
-                    max_bandwidth: 1024 * 1024 * 25, // 25 MB/s
-                    latency_target: Duration::from_millis(10),
-                    packet_filters: vec![],
-                },
-            ],
+    pub fn new(config: &NetworkCoreConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(Self::qos_config(config))),
+            settings: Arc::new(RwLock::new(config.qos.clone())),
+            applied: Arc::new(RwLock::new(None)),
+            sampler: Arc::default(),
+        }
+    }
+
+    fn qos_config(config: &NetworkCoreConfig) -> QoSConfig {
+        QoSConfig {
+            enabled: config.qos_enabled,
+            traffic_classes: config.qos.traffic_classes.iter().map(TrafficClass::from).collect(),
             bandwidth_limits: HashMap::new(),
             priority_queues: vec![],
-        };
-        
-        Self {
-            config: Arc::new(RwLock::new(default_config)),
         }
     }
 
-    pub async fn optimize(&self) -> Result<String> {
-        let config = self.config.read().await;
-        
-        if config.enabled {
-            Ok("QoS optimizado: Prioridades ajustadas, ancho de banda balanceado".to_string())
-        } else {
-            Ok("QoS deshabilitado - habilitando configuración óptima".to_string())
+    pub async fn reconfigure(&self, config: &NetworkCoreConfig) {
+        *self.config.write().await = Self::qos_config(config);
+        *self.settings.write().await = config.qos.clone();
+    }
+
+    /// Moldear la interfaz según las clases de tráfico configuradas
+    pub async fn optimize(&self) -> Result<ShapingPlan> {
+        let config = self.config.read().await.clone();
+        if !config.enabled {
+            return Err(NanoCoreError::ConfigError(anyhow!("QoS deshabilitado (qos_enabled = false)")).into());
+        }
+
+        let settings = self.settings.read().await.clone();
+        let (interface, link_mbps) = tokio::task::spawn_blocking(move || shaping::resolve_link(&settings)).await??;
+        let plan = shaping::plan(&interface, link_mbps, &config.traffic_classes)?;
+
+        let mut applied = self.applied.write().await;
+        // Si cambia la interfaz, la anterior vuelve a su qdisc por defecto
+        if let Some(previous) = applied.as_ref().filter(|previous| previous.interface != interface) {
+            if let Err(e) = shaping::clear(&previous.interface).await {
+                warn!("⚠️  Error retirando el shaping de {}: {}", previous.interface, e);
+            }
         }
+        shaping::apply(&plan).await?;
+        *applied = Some(plan.clone());
+        *self.sampler.lock().await = StatsSampler::default();
+
+        info!("🚦 Shaping QoS aplicado en {} ({} Mbit/s, {} clases)", interface, link_mbps, config.traffic_classes.len());
+        Ok(plan)
+    }
+
+    /// Retirar el shaping instalado, si lo hay
+    pub async fn clear(&self) -> Result<()> {
+        if let Some(plan) = self.applied.write().await.take() {
+            shaping::clear(&plan.interface).await?;
+            info!("🚦 Shaping QoS retirado de {}", plan.interface);
+        }
+        Ok(())
+    }
+
+    /// Contadores por clase; vacío si no hay shaping aplicado
+    pub async fn statistics(&self) -> Result<Vec<TrafficClassStats>> {
+        let Some(plan) = self.applied.read().await.clone() else {
+            return Ok(Vec::new());
+        };
+        self.sampler.lock().await.sample(&plan).await
     }
 }
 
@@ -752,10 +1028,10 @@ impl LatencyMonitor {
             packets_sent += 1;
             
             // Simular latencia variable
-            let latency = Duration::from_millis(1 + (rand::random::<u64>() % 50));
+            let latency = rng::duration(Duration::from_millis(1), Duration::from_millis(50));
             
             // Simular pérdida de paquetes ocasional
-            if rand::random::<f64>() > 0.02 { // 2% pérdida
+            if rng::chance(0.98) { // 2% pérdida
                 latencies.push(latency);
                 packets_received += 1;
             }
@@ -805,44 +1081,68 @@ impl LatencyMonitor {
 }
 
 /// Monitor de ancho de banda
-pub struct BandwidthMonitor;
+pub struct BandwidthMonitor {
+    accounting: Arc<RwLock<BandwidthAccounting>>,
+    config: Arc<RwLock<BandwidthConfig>>,
+    is_running: Arc<RwLock<bool>>,
+}
 
 impl BandwidthMonitor {
-    pub fn new() -> Self {
-        Self
+    pub fn new(config: BandwidthConfig) -> Self {
+        Self {
+            accounting: Arc::new(RwLock::new(BandwidthAccounting::new(Duration::from_secs(config.retention_secs)))),
+            config: Arc::new(RwLock::new(config)),
+            is_running: Arc::new(RwLock::new(false)),
+        }
     }
 
     pub async fn start(&self) -> Result<()> {
-        // Inicializar monitor de ancho de banda
+        *self.is_running.write().await = true;
+
+        let accounting = self.accounting.clone();
+        let config = self.config.clone();
+        let is_running = self.is_running.clone();
+
+        tokio::spawn(async move {
+            while *is_running.read().await {
+                let read = tokio::task::spawn_blocking(|| -> Result<_> { Ok((connections::sockets()?, netinfo::interfaces()?)) }).await;
+                match read {
+                    Ok(Ok((sockets, interfaces))) => accounting.write().await.record(&sockets, &interfaces, Instant::now()),
+                    Ok(Err(e)) => warn!("⚠️  Error muestreando el ancho de banda: {}", e),
+                    Err(e) => warn!("⚠️  Error muestreando el ancho de banda: {}", e),
+                }
+                let interval = config.read().await.sample_interval_secs.max(1);
+                tokio::time::sleep(Duration::from_secs(interval)).await;
+            }
+        });
+
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
+        *self.is_running.write().await = false;
         Ok(())
     }
 
-    pub async fn get_bandwidth_info(&self) -> Result<(u64, u64)> {
-        // Simular información de ancho de banda
-        let total_bandwidth = 1024 * 1024 * 1000; // 1 Gbps
-        let used_bandwidth = (total_bandwidth as f64 * (0.1 + rand::random::<f64>() * 0.3)) as u64;
-        let available_bandwidth = total_bandwidth - used_bandwidth;
-        
-        Ok((total_bandwidth, available_bandwidth))
+    pub async fn reconfigure(&self, config: BandwidthConfig) {
+        self.accounting.write().await.set_retention(Duration::from_secs(config.retention_secs));
+        *self.config.write().await = config;
     }
-}
 
-// Función auxiliar para generar números aleatorios (simplificada)
-mod rand {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    use std::time::{SystemTime, UNIX_EPOCH};
+    /// Capacidad y disponible de las interfaces, en bit/s
+    pub async fn get_bandwidth_info(&self) -> Result<(u64, u64)> {
+        Ok(self.accounting.read().await.bandwidth())
+    }
 
-    pub fn random<T: Hash + Copy>() -> T
-    where
-        T: From<u64>,
-    {
-        let mut hasher = DefaultHasher::new();
-        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().hash(&mut hasher);
-        T::from(hasher.finish())
+    /// Mayores consumidores en la ventana; sin ventana ni límite se usan los de la configuración
+    pub async fn top_talkers(&self, window_secs: Option<u64>, limit: Option<usize>) -> TopTalkers {
+        let (window, limit) = {
+            let config = self.config.read().await;
+            (
+                window_secs.unwrap_or(config.default_window_secs).clamp(1, config.retention_secs.max(1)),
+                limit.unwrap_or(config.top).max(1),
+            )
+        };
+        self.accounting.read().await.top(Duration::from_secs(window), limit, Instant::now())
     }
-}
\ No newline at end of file
+}
@@ -6,16 +6,30 @@
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-use sysinfo::{System, SystemExt, CpuExt, ProcessExt};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{System, SystemExt, CpuExt, ProcessExt, UserExt};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::communication::CognitiveFabric;
+use crate::config::{CoreConfig, OSCoreConfig, RuntimeEnvironment};
 use crate::metrics::MetricsCollector;
-use crate::nano_cores::{NanoCore, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::nano_cores::fs_watch::{FsWatchRegistry, WatchedPath};
+use crate::nano_cores::process_history::{ProcessHistory, PROCESS_ANOMALY_SUBJECT};
+use crate::nano_cores::scheduler::{
+    CommandScheduler, ScheduleResult, ScheduleUpdate, ScheduledCommand, SCHEDULES_SUBJECT, SCHEDULE_RESULTS_SUBJECT,
+};
+use crate::nano_cores::services::{self, ServiceAction};
+use crate::nano_cores::{CommandAuthorization, CoreResult, NanoCore, NanoCoreError, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::security::SecurityLevel;
+
+#[cfg(all(target_os = "linux", feature = "ebpf"))]
+use crate::nano_cores::ebpf_monitor::EbpfMonitor;
+#[cfg(target_os = "linux")]
+use crate::nano_cores::cgroup::CgroupManager;
 
 /// Información del sistema operativo
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +40,9 @@ pub struct OSInfo {
     pub hostname: String,
     pub uptime_seconds: u64,
     pub boot_time: u64,
+    /// Contenedor, hipervisor y límites del cgroup
+    #[serde(default)]
+    pub environment: RuntimeEnvironment,
 }
 
 /// Información de procesos
@@ -38,6 +55,93 @@ pub struct ProcessInfo {
     pub status: String,
 }
 
+/// Proceso con su padre, usuario y línea de comandos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessDetail {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub user: Option<String>,
+    pub cmdline: String,
+    pub cpu_usage: f32,
+    pub memory_usage: u64,
+    pub status: String,
+}
+
+/// Nodo del árbol de procesos con el consumo acumulado de su subárbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessNode {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory_usage: u64,
+    pub subtree_cpu_usage: f32,
+    pub subtree_memory_usage: u64,
+    pub children: Vec<ProcessNode>,
+}
+
+/// Criterios de `FindProcesses`; los campos vacíos no filtran
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessFilter {
+    /// Subcadena del nombre, sin distinguir mayúsculas
+    pub name: Option<String>,
+    /// Nombre de usuario exacto
+    pub user: Option<String>,
+    /// Subcadena de la línea de comandos
+    pub cmdline: Option<String>,
+}
+
+impl ProcessFilter {
+    pub fn matches(&self, process: &ProcessDetail) -> bool {
+        let name = self
+            .name
+            .as_ref()
+            .map_or(true, |name| process.name.to_lowercase().contains(&name.to_lowercase()));
+        let user = self.user.as_ref().map_or(true, |user| process.user.as_ref() == Some(user));
+        let cmdline = self.cmdline.as_ref().map_or(true, |cmdline| process.cmdline.contains(cmdline.as_str()));
+        name && user && cmdline
+    }
+}
+
+/// Construir el bosque de procesos; son raíz los que no tienen padre conocido
+fn build_process_tree(processes: &[ProcessDetail]) -> Vec<ProcessNode> {
+    let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut children: HashMap<u32, Vec<&ProcessDetail>> = HashMap::new();
+    let mut roots = Vec::new();
+    for process in processes {
+        match process.parent.filter(|parent| pids.contains(parent) && *parent != process.pid) {
+            Some(parent) => children.entry(parent).or_default().push(process),
+            None => roots.push(process),
+        }
+    }
+
+    fn build(process: &ProcessDetail, children: &HashMap<u32, Vec<&ProcessDetail>>, visited: &mut HashSet<u32>) -> ProcessNode {
+        visited.insert(process.pid);
+        let mut nodes = Vec::new();
+        for child in children.get(&process.pid).into_iter().flatten() {
+            if !visited.contains(&child.pid) {
+                nodes.push(build(child, children, visited));
+            }
+        }
+
+        ProcessNode {
+            pid: process.pid,
+            name: process.name.clone(),
+            cpu_usage: process.cpu_usage,
+            memory_usage: process.memory_usage,
+            subtree_cpu_usage: process.cpu_usage + nodes.iter().map(|n| n.subtree_cpu_usage).sum::<f32>(),
+            subtree_memory_usage: process.memory_usage + nodes.iter().map(|n| n.subtree_memory_usage).sum::<u64>(),
+            children: nodes,
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut tree: Vec<ProcessNode> = roots.into_iter().map(|root| build(root, &children, &mut visited)).collect();
+    tree.sort_by_key(|node| node.pid);
+    tree
+}
+
 /// Información de recursos del sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemResources {
@@ -56,22 +160,86 @@ pub struct SystemResources {
 pub enum OSCommand {
     GetSystemInfo,
     GetProcessList,
+    GetProcessTree,
+    FindProcesses(ProcessFilter),
+    /// Muestras retenidas de un proceso seguido por `process_history`
+    GetProcessHistory(u32),
     GetSystemResources,
     KillProcess(u32),
     SetProcessPriority(u32, i32),
     GetEnvironmentVariable(String),
     SetEnvironmentVariable(String, String),
+    /// Publicar en `os.fs.events` los cambios bajo la ruta (ruta, recursivo)
+    WatchPath(String, bool),
+    UnwatchPath(String),
+    ListWatchedPaths,
+    QueryService(String),
+    StartService(String),
+    StopService(String),
+    RestartService(String),
+    /// Añadir o reemplazar un comando recurrente (se guarda en la configuración)
+    ScheduleCommand(ScheduledCommand),
+    UnscheduleCommand(String),
+    ListSchedules,
+}
+
+impl OSCommand {
+    /// Permiso y nivel exigidos para ejecutar el comando
+    pub fn authorization(&self) -> CommandAuthorization {
+        match self {
+            OSCommand::GetSystemInfo
+            | OSCommand::GetProcessList
+            | OSCommand::GetProcessTree
+            | OSCommand::FindProcesses(_)
+            | OSCommand::GetProcessHistory(_)
+            | OSCommand::GetSystemResources
+            | OSCommand::ListWatchedPaths
+            | OSCommand::QueryService(_)
+            | OSCommand::ListSchedules => {
+                CommandAuthorization::new("nano_core.os.status", SecurityLevel::Internal)
+            }
+            OSCommand::GetEnvironmentVariable(_) => CommandAuthorization::new("nano_core.os.env.read", SecurityLevel::Confidential),
+            OSCommand::SetEnvironmentVariable(..) => CommandAuthorization::new("nano_core.os.env.write", SecurityLevel::Secret),
+            OSCommand::SetProcessPriority(..) => CommandAuthorization::new("nano_core.os.process.priority", SecurityLevel::Confidential),
+            OSCommand::KillProcess(_) => CommandAuthorization::new("nano_core.os.process.kill", SecurityLevel::Secret),
+            OSCommand::StartService(_) | OSCommand::StopService(_) | OSCommand::RestartService(_) => {
+                CommandAuthorization::new("nano_core.os.service.control", SecurityLevel::Secret)
+            }
+            OSCommand::ScheduleCommand(_) | OSCommand::UnscheduleCommand(_) => {
+                CommandAuthorization::new("nano_core.os.schedule.write", SecurityLevel::Secret)
+            }
+            OSCommand::WatchPath(..) | OSCommand::UnwatchPath(_) => {
+                CommandAuthorization::new("nano_core.os.fs.watch", SecurityLevel::Confidential)
+            }
+        }
+    }
 }
 
 /// Nano-Core para abstracción del sistema operativo
 pub struct OSCore {
     instance_id: Uuid,
+    config: OSCoreConfig,
     cognitive_fabric: Arc<CognitiveFabric>,
     metrics: Arc<MetricsCollector>,
     instance_number: usize,
     system: Arc<RwLock<System>>,
     start_time: SystemTime,
     error_count: Arc<RwLock<u64>>,
+    fs_watches: FsWatchRegistry,
+    process_history: ProcessHistory,
+    scheduler: CommandScheduler,
+    #[cfg(all(target_os = "linux", feature = "ebpf"))]
+    ebpf: Option<EbpfMonitor>,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<CgroupManager>,
+}
+
+/// Estado de `OSCore` entre reemplazos y reinicios
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct OSCoreCheckpoint {
+    error_count: u64,
+    watched_paths: Vec<WatchedPath>,
 }
 
 impl OSCore {
@@ -80,21 +248,88 @@ impl OSCore {
         cognitive_fabric: Arc<CognitiveFabric>,
         metrics: Arc<MetricsCollector>,
         instance_number: usize,
+        config: OSCoreConfig,
     ) -> Result<Self> {
         let mut system = System::new_all();
         system.refresh_all();
+        let process_history = ProcessHistory::new(config.process_history.clone());
+        let mut scheduler = CommandScheduler::default();
+        scheduler.set(&config.schedules, chrono::Utc::now());
         
         Ok(Self {
             instance_id: Uuid::new_v4(),
+            config,
             cognitive_fabric,
             metrics,
             instance_number,
             system: Arc::new(RwLock::new(system)),
             start_time: SystemTime::now(),
             error_count: Arc::new(RwLock::new(0)),
+            fs_watches: FsWatchRegistry::default(),
+            process_history,
+            scheduler,
+            #[cfg(all(target_os = "linux", feature = "ebpf"))]
+            ebpf: None,
+            #[cfg(target_os = "linux")]
+            cgroup: None,
         })
     }
 
+    /// Crear el cgroup de SAAI y aplicar `resource_limits` si está habilitado
+    ///
+    /// Como el monitor eBPF, solo lo gestiona la instancia 0.
+    fn start_cgroup(&mut self) {
+        if !self.config.enable_cgroups || self.instance_number != 0 {
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        match CgroupManager::create(&self.config.cgroup_path)
+            .and_then(|cgroup| cgroup.apply_limits(&self.config.resource_limits).map(|_| cgroup))
+        {
+            Ok(cgroup) => self.cgroup = Some(cgroup),
+            Err(e) => warn!("⚠️  Límites de recursos no aplicados en {}: {}", self.config.cgroup_path, e),
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        info!("ℹ️  enable_cgroups requiere Linux; resource_limits no se aplican");
+    }
+
+    /// Exportar throttling y uso del cgroup
+    #[cfg(target_os = "linux")]
+    async fn publish_cgroup_stats(&self) {
+        let Some(cgroup) = &self.cgroup else {
+            return;
+        };
+        match cgroup.stats() {
+            Ok(stats) => {
+                self.metrics.record_cgroup_stats(&stats).await;
+                if let Ok(data) = serde_json::to_vec(&stats) {
+                    let _ = self.cognitive_fabric.publish("system.cgroup", &data).await;
+                }
+            }
+            Err(e) => debug!("Error leyendo estadísticas del cgroup: {}", e),
+        }
+    }
+
+    /// Arrancar el monitor eBPF si está habilitado; sin él se sigue con sysinfo
+    ///
+    /// Solo la instancia 0 lo carga, para no duplicar eventos en `os.security`.
+    async fn start_ebpf_monitor(&mut self) {
+        if !self.config.enable_ebpf || self.instance_number != 0 {
+            return;
+        }
+
+        #[cfg(all(target_os = "linux", feature = "ebpf"))]
+        match EbpfMonitor::start(&self.config, self.cognitive_fabric.clone(), self.instance_number).await {
+            Ok(monitor) => self.ebpf = Some(monitor),
+            Err(e) => warn!("⚠️  Monitor eBPF no disponible, se usa solo sysinfo: {}", e),
+        }
+
+        #[cfg(not(all(target_os = "linux", feature = "ebpf")))]
+        info!("ℹ️  enable_ebpf requiere Linux y la feature `ebpf`; se usa solo sysinfo");
+    }
+
     /// Obtener información del sistema operativo
     async fn get_system_info(&self) -> Result<OSInfo> {
         let system = self.system.read().await;
@@ -106,6 +341,7 @@ impl OSCore {
             hostname: system.host_name().unwrap_or_else(|| "Unknown".to_string()),
             uptime_seconds: system.uptime(),
             boot_time: system.boot_time(),
+            environment: RuntimeEnvironment::detect(),
         })
     }
 
@@ -129,6 +365,32 @@ impl OSCore {
         Ok(processes)
     }
 
+    /// Procesos con padre, usuario y línea de comandos
+    async fn get_process_details(&self) -> Result<Vec<ProcessDetail>> {
+        let mut system = self.system.write().await;
+        system.refresh_processes();
+
+        let processes = system
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessDetail {
+                pid: pid.as_u32(),
+                parent: process.parent().map(|parent| parent.as_u32()),
+                name: process.name().to_string(),
+                user: process
+                    .user_id()
+                    .and_then(|uid| system.get_user_by_id(uid))
+                    .map(|user| user.name().to_string()),
+                cmdline: process.cmd().join(" "),
+                cpu_usage: process.cpu_usage(),
+                memory_usage: process.memory(),
+                status: format!("{:?}", process.status()),
+            })
+            .collect();
+
+        Ok(processes)
+    }
+
     /// Obtener recursos del sistema
     async fn get_system_resources(&self) -> Result<SystemResources> {
         let mut system = self.system.write().await;
@@ -171,9 +433,7 @@ impl OSCore {
         
         #[cfg(windows)]
         {
-            // Implementación para Windows usando WinAPI
-            warn!("🚧 Terminación de procesos en Windows no implementada aún");
-            Ok(false)
+            windows_process::terminate(pid)
         }
     }
 
@@ -202,11 +462,95 @@ impl OSCore {
         
         #[cfg(windows)]
         {
-            warn!("🚧 Establecimiento de prioridad en Windows no implementado aún");
-            Ok(false)
+            windows_process::set_priority(pid, priority)
         }
     }
 
+    /// Arrancar, parar o reiniciar un servicio de `controllable_services`
+    async fn control_service(&self, name: &str, action: ServiceAction) -> Result<Vec<u8>> {
+        services::check_controllable(name, &self.config.controllable_services)?;
+        let status = services::control(name, action).await?;
+        Ok(serde_json::to_vec(&status)?)
+    }
+
+    /// Muestrear los procesos seguidos y publicar los crecimientos bruscos de RSS
+    async fn sample_process_history(&mut self) -> Result<()> {
+        if !self.process_history.is_enabled() {
+            return Ok(());
+        }
+
+        let processes = self.get_process_details().await?;
+        for anomaly in self.process_history.record(&processes, chrono::Utc::now()) {
+            warn!(
+                "📈 Crecimiento de memoria en {} (PID {}): {} → {} bytes",
+                anomaly.name, anomaly.pid, anomaly.baseline_memory, anomaly.memory_usage
+            );
+            self.cognitive_fabric
+                .publish(PROCESS_ANOMALY_SUBJECT, &serde_json::to_vec(&anomaly)?)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Ejecutar los comandos programados vencidos y publicar su resultado
+    ///
+    /// Solo la instancia 0 los ejecuta; el resto mantiene la lista para consultarla.
+    async fn run_schedules(&mut self) {
+        if self.instance_number != 0 {
+            return;
+        }
+
+        for schedule in self.scheduler.due(chrono::Utc::now()) {
+            let started_at = chrono::Utc::now();
+            let start = std::time::Instant::now();
+            let payload = serde_json::to_vec(&schedule.command).unwrap_or_default();
+            let outcome = self.process_command("scheduled", &payload).await;
+
+            let result = ScheduleResult {
+                id: schedule.id.clone(),
+                success: outcome.is_ok(),
+                output: outcome.as_ref().ok().and_then(|output| serde_json::from_slice(output).ok()),
+                error: outcome.as_ref().err().map(|e| e.to_string()),
+                started_at,
+                duration_ms: start.elapsed().as_millis() as u64,
+                instance: self.instance_number,
+            };
+            match &result.error {
+                Some(e) => warn!("⚠️  Comando programado {} falló: {}", schedule.id, e),
+                None => debug!("⏰ Comando programado {} ejecutado", schedule.id),
+            }
+
+            if let Ok(data) = serde_json::to_vec(&result) {
+                if let Err(e) = self.cognitive_fabric.publish(SCHEDULE_RESULTS_SUBJECT, &data).await {
+                    debug!("Error publicando resultado de {}: {}", schedule.id, e);
+                }
+            }
+        }
+    }
+
+    /// Aplicar un cambio de programación y publicarlo para guardarlo en la configuración
+    async fn update_schedule(&mut self, update: ScheduleUpdate) -> Result<bool> {
+        let changed = match &update {
+            ScheduleUpdate::Upsert(schedule) => {
+                let command: OSCommand = serde_json::from_value(schedule.command.clone())
+                    .map_err(|e| NanoCoreError::ConfigError(anyhow!("Comando programado inválido: {}", e)))?;
+                if matches!(command, OSCommand::ScheduleCommand(_) | OSCommand::UnscheduleCommand(_)) {
+                    return Err(NanoCoreError::ConfigError(anyhow!("No se pueden programar cambios de programación")).into());
+                }
+                self.scheduler.upsert(schedule.clone(), chrono::Utc::now())?;
+                true
+            }
+            ScheduleUpdate::Remove(id) => self.scheduler.remove(id),
+        };
+
+        if changed {
+            self.cognitive_fabric
+                .publish(SCHEDULES_SUBJECT, &serde_json::to_vec(&update)?)
+                .await?;
+        }
+        Ok(changed)
+    }
+
     /// Publicar métricas del sistema
     async fn publish_system_metrics(&self) -> Result<()> {
         let resources = self.get_system_resources().await?;
@@ -245,16 +589,6 @@ impl NanoCore for OSCore {
             self.instance_id
         );
 
-        // Suscribirse a comandos del OS
-        self.cognitive_fabric
-            .subscribe("os.commands", {
-                let instance_id = self.instance_id;
-                move |data| {
-                    debug!("📨 OSCore {} recibió comando: {} bytes", instance_id, data.len());
-                }
-            })
-            .await?;
-
         // Publicar información inicial del sistema
         let system_info = self.get_system_info().await?;
         let info_data = serde_json::to_vec(&system_info)?;
@@ -263,19 +597,31 @@ impl NanoCore for OSCore {
             .publish("system.info", &info_data)
             .await?;
 
+        self.start_ebpf_monitor().await;
+        self.start_cgroup();
+
         info!("✅ OSCore instancia {} inicializado correctamente", self.instance_number);
         Ok(())
     }
 
-    async fn run(&mut self) -> Result<()> {
-        // Publicar métricas del sistema cada 5 segundos
+    async fn run(&mut self) -> CoreResult<()> {
+        // Publicar métricas del sistema cada `monitor_interval_ms`
         if let Err(e) = self.publish_system_metrics().await {
             let mut error_count = self.error_count.write().await;
             *error_count += 1;
-            return Err(anyhow!("Error publicando métricas: {}", e));
+            return Err(NanoCoreError::Transient(anyhow!("Error publicando métricas: {}", e)));
+        }
+
+        #[cfg(target_os = "linux")]
+        self.publish_cgroup_stats().await;
+
+        self.run_schedules().await;
+
+        if let Err(e) = self.sample_process_history().await {
+            *self.error_count.write().await += 1;
+            return Err(NanoCoreError::Transient(anyhow!("Error muestreando procesos: {}", e)));
         }
 
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
         Ok(())
     }
 
@@ -316,17 +662,22 @@ impl NanoCore for OSCore {
 
     async fn shutdown(&mut self) -> Result<()> {
         info!("🛑 Deteniendo OSCore instancia {}", self.instance_number);
-        
-        // Desuscribirse de eventos
-        self.cognitive_fabric
-            .unsubscribe("os.commands")
-            .await?;
+
+        #[cfg(all(target_os = "linux", feature = "ebpf"))]
+        if let Some(mut monitor) = self.ebpf.take() {
+            monitor.stop();
+        }
+        self.fs_watches.clear();
 
         info!("✅ OSCore instancia {} detenido correctamente", self.instance_number);
         Ok(())
     }
 
-    async fn process_command(&mut self, command: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    fn command_authorization(&self, payload: &[u8]) -> Result<CommandAuthorization> {
+        Ok(serde_json::from_slice::<OSCommand>(payload)?.authorization())
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
         let cmd: OSCommand = serde_json::from_slice(payload)?;
         
         let response = match cmd {
@@ -338,6 +689,25 @@ impl NanoCore for OSCore {
                 let processes = self.get_process_list().await?;
                 serde_json::to_vec(&processes)?
             }
+            OSCommand::GetProcessTree => {
+                let processes = self.get_process_details().await?;
+                serde_json::to_vec(&build_process_tree(&processes))?
+            }
+            OSCommand::FindProcesses(filter) => {
+                let processes: Vec<ProcessDetail> = self
+                    .get_process_details()
+                    .await?
+                    .into_iter()
+                    .filter(|process| filter.matches(process))
+                    .collect();
+                serde_json::to_vec(&processes)?
+            }
+            OSCommand::ScheduleCommand(schedule) => {
+                serde_json::to_vec(&self.update_schedule(ScheduleUpdate::Upsert(schedule)).await?)?
+            }
+            OSCommand::UnscheduleCommand(id) => serde_json::to_vec(&self.update_schedule(ScheduleUpdate::Remove(id)).await?)?,
+            OSCommand::ListSchedules => serde_json::to_vec(&self.scheduler.list())?,
+            OSCommand::GetProcessHistory(pid) => serde_json::to_vec(&self.process_history.get(pid))?,
             OSCommand::GetSystemResources => {
                 let resources = self.get_system_resources().await?;
                 serde_json::to_vec(&resources)?
@@ -358,9 +728,217 @@ impl NanoCore for OSCore {
                 std::env::set_var(&var, &value);
                 serde_json::to_vec(&true)?
             }
+            OSCommand::WatchPath(path, recursive) => {
+                let started = self.fs_watches.watch(&path, recursive, self.cognitive_fabric.clone(), self.instance_number)?;
+                serde_json::to_vec(&started)?
+            }
+            OSCommand::UnwatchPath(path) => serde_json::to_vec(&self.fs_watches.unwatch(&path))?,
+            OSCommand::ListWatchedPaths => serde_json::to_vec(&self.fs_watches.list())?,
+            OSCommand::QueryService(name) => serde_json::to_vec(&services::query(&name).await?)?,
+            OSCommand::StartService(name) => self.control_service(&name, ServiceAction::Start).await?,
+            OSCommand::StopService(name) => self.control_service(&name, ServiceAction::Stop).await?,
+            OSCommand::RestartService(name) => self.control_service(&name, ServiceAction::Restart).await?,
         };
 
         debug!("✅ Comando OSCore procesado: {}", command);
         Ok(response)
     }
-}
\ No newline at end of file
+    
+    async fn apply_config(&mut self, config: &CoreConfig) -> Result<()> {
+        self.config = config.nano_cores.for_instance(self.instance_number)?.os_core;
+        self.process_history.set_config(self.config.process_history.clone());
+        self.scheduler.set(&self.config.schedules, chrono::Utc::now());
+
+        #[cfg(all(target_os = "linux", feature = "ebpf"))]
+        if let Some(monitor) = &self.ebpf {
+            monitor.apply_config(&self.config).await;
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = &self.cgroup {
+            if let Err(e) = cgroup.apply_limits(&self.config.resource_limits) {
+                warn!("⚠️  Error actualizando los límites del cgroup: {}", e);
+            }
+        }
+        debug!("📋 OSCore instancia {} reconfigurada", self.instance_number);
+        Ok(())
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_millis(self.config.monitor_interval_ms)
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&OSCoreCheckpoint {
+            error_count: *self.error_count.read().await,
+            watched_paths: self.fs_watches.list(),
+        })?)
+    }
+
+    async fn restore(&mut self, state: &[u8]) -> Result<()> {
+        let checkpoint: OSCoreCheckpoint = serde_json::from_slice(state)?;
+        *self.error_count.write().await = checkpoint.error_count;
+        for watched in checkpoint.watched_paths {
+            if let Err(e) = self.fs_watches.watch(&watched.path, watched.recursive, self.cognitive_fabric.clone(), self.instance_number) {
+                warn!("⚠️  No se pudo restaurar la vigilancia de {}: {}", watched.path, e);
+            }
+        }
+        debug!("♻️  OSCore instancia {} restaurada", self.instance_number);
+        Ok(())
+    }
+}
+
+/// Terminación y prioridad de procesos con la API Win32
+///
+/// Igual que en Unix, un proceso inexistente o que no se deja abrir devuelve
+/// `Ok(false)`; la falta de permisos y los procesos del sistema se rechazan con
+/// `NanoCoreError::SecurityDenied`.
+#[cfg(windows)]
+mod windows_process {
+    use anyhow::{Result, anyhow};
+    use tracing::{info, warn};
+    use windows::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, HANDLE};
+    use windows::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, TerminateProcess, ABOVE_NORMAL_PRIORITY_CLASS,
+        BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS,
+        PROCESS_ACCESS_RIGHTS, PROCESS_CREATION_FLAGS, PROCESS_SET_INFORMATION, PROCESS_TERMINATE,
+    };
+
+    use crate::nano_cores::NanoCoreError;
+
+    /// PIDs del proceso inactivo y de System
+    const SYSTEM_PIDS: [u32; 2] = [0, 4];
+
+    /// Handle que se cierra al salir de ámbito
+    struct ProcessHandle(HANDLE);
+
+    impl Drop for ProcessHandle {
+        fn drop(&mut self) {
+            let _ = unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    fn denied(action: &str, pid: u32, reason: impl std::fmt::Display) -> anyhow::Error {
+        NanoCoreError::SecurityDenied(anyhow!("Sin permisos para {} el proceso {}: {}", action, pid, reason)).into()
+    }
+
+    /// `None` si el proceso no existe o no se pudo abrir por otro motivo
+    fn open(pid: u32, access: PROCESS_ACCESS_RIGHTS, action: &str) -> Result<Option<ProcessHandle>> {
+        if SYSTEM_PIDS.contains(&pid) {
+            return Err(denied(action, pid, "proceso del sistema"));
+        }
+        match unsafe { OpenProcess(access, false, pid) } {
+            Ok(handle) => Ok(Some(ProcessHandle(handle))),
+            Err(e) if e.code() == ERROR_ACCESS_DENIED.to_hresult() => Err(denied(action, pid, e)),
+            Err(e) => {
+                warn!("⚠️  Error abriendo proceso {}: {}", pid, e);
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn terminate(pid: u32) -> Result<bool> {
+        if pid == std::process::id() {
+            return Err(denied("terminar", pid, "es el propio saai-core"));
+        }
+        let Some(process) = open(pid, PROCESS_TERMINATE, "terminar")? else {
+            return Ok(false);
+        };
+
+        match unsafe { TerminateProcess(process.0, 1) } {
+            Ok(()) => {
+                info!("🔪 Proceso {} terminado exitosamente", pid);
+                Ok(true)
+            }
+            Err(e) if e.code() == ERROR_ACCESS_DENIED.to_hresult() => Err(denied("terminar", pid, e)),
+            Err(e) => {
+                warn!("⚠️  Error terminando proceso {}: {}", pid, e);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Clase de prioridad equivalente a un valor nice (-20..=19)
+    ///
+    /// `REALTIME_PRIORITY_CLASS` no se usa: sin privilegios Windows la rebaja
+    /// en silencio a `HIGH_PRIORITY_CLASS` y con ellos puede bloquear el sistema.
+    fn priority_class(priority: i32) -> PROCESS_CREATION_FLAGS {
+        match priority {
+            i32::MIN..=-15 => HIGH_PRIORITY_CLASS,
+            -14..=-5 => ABOVE_NORMAL_PRIORITY_CLASS,
+            -4..=4 => NORMAL_PRIORITY_CLASS,
+            5..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+            _ => IDLE_PRIORITY_CLASS,
+        }
+    }
+
+    pub fn set_priority(pid: u32, priority: i32) -> Result<bool> {
+        let Some(process) = open(pid, PROCESS_SET_INFORMATION, "cambiar la prioridad de")? else {
+            return Ok(false);
+        };
+
+        match unsafe { SetPriorityClass(process.0, priority_class(priority)) } {
+            Ok(()) => {
+                info!("⚖️  Prioridad del proceso {} establecida a {}", pid, priority);
+                Ok(true)
+            }
+            Err(e) if e.code() == ERROR_ACCESS_DENIED.to_hresult() => {
+                Err(denied("cambiar la prioridad de", pid, e))
+            }
+            Err(e) => {
+                warn!("⚠️  Error estableciendo prioridad del proceso {}: {}", pid, e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent: Option<u32>, name: &str, memory_usage: u64) -> ProcessDetail {
+        ProcessDetail {
+            pid,
+            parent,
+            name: name.to_string(),
+            user: Some("saai".to_string()),
+            cmdline: format!("/usr/bin/{} --serve", name),
+            cpu_usage: 1.0,
+            memory_usage,
+            status: "Run".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_process_tree_and_filter() {
+        let processes = vec![
+            process(1, None, "init", 10),
+            process(10, Some(1), "saai-core", 100),
+            process(11, Some(10), "saai-agents", 50),
+            process(12, Some(10), "nats-server", 20),
+            // Padre ya terminado: queda como raíz
+            process(30, Some(99), "orphan", 5),
+        ];
+
+        let tree = build_process_tree(&processes);
+        assert_eq!(tree.iter().map(|n| n.pid).collect::<Vec<_>>(), vec![1, 30]);
+        let core = &tree[0].children[0];
+        assert_eq!(core.pid, 10);
+        assert_eq!(core.children.len(), 2);
+        assert_eq!(core.subtree_memory_usage, 170);
+        assert_eq!(tree[0].subtree_memory_usage, 180);
+        assert!((tree[0].subtree_cpu_usage - 4.0).abs() < f32::EPSILON);
+
+        let filter = ProcessFilter {
+            name: Some("SAAI".to_string()),
+            cmdline: Some("--serve".to_string()),
+            ..Default::default()
+        };
+        let found: Vec<u32> = processes.iter().filter(|p| filter.matches(p)).map(|p| p.pid).collect();
+        assert_eq!(found, vec![10, 11]);
+
+        let by_user = ProcessFilter { user: Some("root".to_string()), ..Default::default() };
+        assert!(!processes.iter().any(|p| by_user.matches(p)));
+    }
+}
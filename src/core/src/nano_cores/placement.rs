@@ -0,0 +1,128 @@
+//! Afinidad de CPU y prioridad de los nano-núcleos
+//!
+//! `[nano_cores.placement.<núcleo>]` fija las CPUs (`cpu_affinity = [2, 3]`) y
+//! el `nice` de cada réplica, con overrides en `instances.<n>`. Las réplicas
+//! con ubicación propia ejecutan su bucle en un hilo dedicado, fijado al
+//! arrancar, para aislar a los núcleos sensibles a la latencia de los ruidosos.
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Overrides de una réplica
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct InstancePlacement {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_affinity: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+}
+
+/// Sección `[nano_cores.placement.<núcleo>]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PlacementConfig {
+    /// CPUs en las que puede ejecutarse el bucle (vacío = cualquiera)
+    pub cpu_affinity: Vec<usize>,
+    /// Prioridad del hilo del bucle, de -20 a 19
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// Overrides por réplica (`[nano_cores.placement.network_core.instances.1]`)
+    pub instances: BTreeMap<usize, InstancePlacement>,
+}
+
+impl PlacementConfig {
+    /// Ubicación efectiva de una réplica
+    pub fn for_instance(&self, instance: usize) -> Placement {
+        let overrides = self.instances.get(&instance).cloned().unwrap_or_default();
+        Placement {
+            cpu_affinity: overrides.cpu_affinity.unwrap_or_else(|| self.cpu_affinity.clone()),
+            nice: overrides.nice.or(self.nice),
+        }
+    }
+}
+
+/// Afinidad y prioridad de una réplica
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Placement {
+    pub cpu_affinity: Vec<usize>,
+    pub nice: Option<i32>,
+}
+
+impl Placement {
+    /// Sin afinidad ni prioridad: el bucle corre en el runtime compartido
+    pub fn is_default(&self) -> bool {
+        self.cpu_affinity.is_empty() && self.nice.is_none()
+    }
+
+    /// Aplicar la ubicación al hilo que llama
+    #[cfg(target_os = "linux")]
+    pub fn apply_to_current_thread(&self) -> Result<()> {
+        if !self.cpu_affinity.is_empty() {
+            let mut cpus = nix::sched::CpuSet::new();
+            for cpu in &self.cpu_affinity {
+                cpus.set(*cpu).map_err(|e| anyhow!("CPU {} fuera de rango: {}", cpu, e))?;
+            }
+            // Pid 0: el hilo actual, no todo el proceso
+            nix::sched::sched_setaffinity(nix::unistd::Pid::from_raw(0), &cpus)
+                .map_err(|e| anyhow!("Error fijando la afinidad {:?}: {}", self.cpu_affinity, e))?;
+        }
+
+        if let Some(nice) = self.nice {
+            // En Linux setpriority sobre un tid solo afecta a ese hilo
+            let tid = unsafe { libc::syscall(libc::SYS_gettid) } as libc::id_t;
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice) } != 0 {
+                return Err(anyhow!("Error fijando nice {}: {}", nice, std::io::Error::last_os_error()));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply_to_current_thread(&self) -> Result<()> {
+        Err(anyhow!("Afinidad de CPU y nice por hilo solo disponibles en Linux"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_overrides() {
+        let config = PlacementConfig {
+            cpu_affinity: vec![2, 3],
+            nice: Some(-5),
+            instances: BTreeMap::from([
+                (1, InstancePlacement { cpu_affinity: Some(vec![4]), nice: None }),
+                (2, InstancePlacement { cpu_affinity: Some(Vec::new()), nice: Some(0) }),
+            ]),
+        };
+
+        assert_eq!(config.for_instance(0), Placement { cpu_affinity: vec![2, 3], nice: Some(-5) });
+        assert_eq!(config.for_instance(1), Placement { cpu_affinity: vec![4], nice: Some(-5) });
+        assert_eq!(config.for_instance(2), Placement { cpu_affinity: Vec::new(), nice: Some(0) });
+        assert!(PlacementConfig::default().for_instance(0).is_default());
+    }
+
+    #[tokio::test]
+    async fn test_partial_placement_toml_round_trip() {
+        // Afinidad sin nice, a nivel de sección y de réplica
+        let placement = PlacementConfig {
+            cpu_affinity: vec![2, 3],
+            nice: None,
+            instances: BTreeMap::from([(1, InstancePlacement { cpu_affinity: Some(vec![4]), nice: None })]),
+        };
+        let mut config = crate::config::CoreConfig::default();
+        config.nano_cores.placement.insert("network_core".to_string(), placement.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        config.save(&path).await.unwrap();
+        let loaded = crate::config::CoreConfig::read_file(&path).await.unwrap();
+        assert_eq!(loaded.nano_cores.placement["network_core"], placement);
+    }
+}
@@ -0,0 +1,309 @@
+//! Perfiles de energía del sistema para HardwareCore
+//!
+//! `SetPowerMode` se traduce al mecanismo de cada plataforma:
+//! power-profiles-daemon (`powerprofilesctl`) o el governor de cpufreq en
+//! Linux, `powercfg` en Windows y `lowpowermode` de `pmset` en macOS. El
+//! perfil anterior se puede restaurar con `restore`.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::hardware_core::PowerState;
+use crate::nano_cores::NanoCoreError;
+
+/// Perfil tal como lo entiende el mecanismo de la plataforma
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerProfile {
+    /// `power-profiles-daemon`, `cpufreq`, `powercfg` o `pmset`
+    pub backend: String,
+    /// Perfil, governor, GUID del esquema o valor de `lowpowermode`
+    pub profile: String,
+}
+
+impl PowerProfile {
+    fn new(backend: &str, profile: impl Into<String>) -> Self {
+        Self {
+            backend: backend.to_string(),
+            profile: profile.into(),
+        }
+    }
+}
+
+/// Perfil activo
+pub async fn current() -> Result<PowerProfile> {
+    platform::current().await
+}
+
+/// Aplicar el modo y devolver el perfil resultante
+pub async fn apply(mode: &PowerState) -> Result<PowerProfile> {
+    let profile = platform::apply(mode).await?;
+    info!("🔋 Modo de energía {:?}: {} {}", mode, profile.backend, profile.profile);
+    Ok(profile)
+}
+
+/// Volver a un perfil leído con `current`
+pub async fn restore(profile: &PowerProfile) -> Result<()> {
+    platform::set(profile).await?;
+    info!("🔋 Perfil de energía restaurado: {} {}", profile.backend, profile.profile);
+    Ok(())
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new(program).args(args).output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let error = anyhow!("{} {} falló: {}", program, args.join(" "), stderr);
+        return Err(if stderr.to_lowercase().contains("denied") || stderr.to_lowercase().contains("privilege") {
+            NanoCoreError::SecurityDenied(error).into()
+        } else {
+            error
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::path::Path;
+    use tracing::debug;
+
+    const PPD: &str = "power-profiles-daemon";
+    const CPUFREQ: &str = "cpufreq";
+    const CPUFREQ_ROOT: &str = "/sys/devices/system/cpu/cpufreq";
+
+    fn ppd_profile(mode: &PowerState) -> &'static str {
+        match mode {
+            PowerState::PowerSaving | PowerState::Critical => "power-saver",
+            PowerState::Normal => "balanced",
+            PowerState::HighPerformance => "performance",
+        }
+    }
+
+    pub async fn current() -> Result<PowerProfile> {
+        match run("powerprofilesctl", &["get"]).await {
+            Ok(profile) => Ok(PowerProfile::new(PPD, profile)),
+            Err(_) => Ok(PowerProfile::new(CPUFREQ, cpufreq::governor(Path::new(CPUFREQ_ROOT))?)),
+        }
+    }
+
+    pub async fn apply(mode: &PowerState) -> Result<PowerProfile> {
+        let profile = PowerProfile::new(PPD, ppd_profile(mode));
+        match set(&profile).await {
+            Ok(()) => return Ok(profile),
+            Err(e) => debug!("🔋 power-profiles-daemon no disponible, se usa cpufreq: {}", e),
+        }
+
+        let profile = PowerProfile::new(CPUFREQ, cpufreq::governor_for(Path::new(CPUFREQ_ROOT), mode)?);
+        set(&profile).await?;
+        Ok(profile)
+    }
+
+    pub async fn set(profile: &PowerProfile) -> Result<()> {
+        match profile.backend.as_str() {
+            PPD => {
+                if !["power-saver", "balanced", "performance"].contains(&profile.profile.as_str()) {
+                    return Err(NanoCoreError::ConfigError(anyhow!("Perfil desconocido: {}", profile.profile)).into());
+                }
+                run("powerprofilesctl", &["set", &profile.profile]).await.map(|_| ())
+            }
+            CPUFREQ => {
+                let governor = profile.profile.clone();
+                tokio::task::spawn_blocking(move || cpufreq::set_governor(Path::new(CPUFREQ_ROOT), &governor)).await?
+            }
+            other => Err(NanoCoreError::ConfigError(anyhow!("Backend de energía no soportado en Linux: {}", other)).into()),
+        }
+    }
+
+    /// Governors de `cpufreq/policy*`
+    pub(super) mod cpufreq {
+        use super::*;
+        use std::fs;
+        use std::path::PathBuf;
+
+        fn policies(root: &Path) -> Result<Vec<PathBuf>> {
+            let mut policies: Vec<PathBuf> = fs::read_dir(root)?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("policy"))
+                .map(|entry| entry.path())
+                .collect();
+            if policies.is_empty() {
+                return Err(anyhow!("Sin políticas cpufreq en {}", root.display()));
+            }
+            policies.sort();
+            Ok(policies)
+        }
+
+        fn read(path: PathBuf) -> Result<String> {
+            Ok(fs::read_to_string(&path)
+                .map_err(|e| anyhow!("{}: {}", path.display(), e))?
+                .trim()
+                .to_string())
+        }
+
+        pub fn governor(root: &Path) -> Result<String> {
+            read(policies(root)?[0].join("scaling_governor"))
+        }
+
+        fn available(root: &Path) -> Result<Vec<String>> {
+            Ok(read(policies(root)?[0].join("scaling_available_governors"))?
+                .split_whitespace()
+                .map(str::to_string)
+                .collect())
+        }
+
+        /// Governor disponible más adecuado para el modo
+        pub fn governor_for(root: &Path, mode: &PowerState) -> Result<String> {
+            // Con intel_pstate/amd-pstate `powersave` es el governor dinámico
+            let preferred: &[&str] = match mode {
+                PowerState::PowerSaving | PowerState::Critical => &["powersave", "conservative"],
+                PowerState::Normal => &["schedutil", "ondemand", "powersave"],
+                PowerState::HighPerformance => &["performance"],
+            };
+            let available = available(root)?;
+            preferred
+                .iter()
+                .find(|governor| available.iter().any(|a| a == *governor))
+                .map(|governor| governor.to_string())
+                .ok_or_else(|| anyhow!("Ningún governor para {:?} entre {:?}", mode, available))
+        }
+
+        pub fn set_governor(root: &Path, governor: &str) -> Result<()> {
+            if !available(root)?.iter().any(|a| a == governor) {
+                return Err(NanoCoreError::ConfigError(anyhow!("Governor no disponible: {}", governor)).into());
+            }
+            for policy in policies(root)? {
+                let path = policy.join("scaling_governor");
+                fs::write(&path, governor).map_err(|e| {
+                    let error = anyhow!("{}: {}", path.display(), e);
+                    if e.kind() == std::io::ErrorKind::PermissionDenied {
+                        NanoCoreError::SecurityDenied(error).into()
+                    } else {
+                        error
+                    }
+                })?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    const POWERCFG: &str = "powercfg";
+
+    fn scheme(mode: &PowerState) -> &'static str {
+        match mode {
+            PowerState::PowerSaving | PowerState::Critical => "a1841308-3541-4fab-bc81-f71556f20b4a",
+            PowerState::Normal => "381b4222-f694-41f0-9685-ff5bb260df2e",
+            PowerState::HighPerformance => "8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c",
+        }
+    }
+
+    fn is_guid(value: &str) -> bool {
+        value.len() == 36 && value.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+    }
+
+    pub async fn current() -> Result<PowerProfile> {
+        // "Power Scheme GUID: 381b4222-... (Balanced)", con el texto localizado
+        let output = run(POWERCFG, &["/getactivescheme"]).await?;
+        output
+            .split_whitespace()
+            .find(|token| is_guid(token))
+            .map(|guid| PowerProfile::new(POWERCFG, guid))
+            .ok_or_else(|| anyhow!("Salida de powercfg inesperada: {}", output))
+    }
+
+    pub async fn apply(mode: &PowerState) -> Result<PowerProfile> {
+        let profile = PowerProfile::new(POWERCFG, scheme(mode));
+        set(&profile).await?;
+        Ok(profile)
+    }
+
+    pub async fn set(profile: &PowerProfile) -> Result<()> {
+        if profile.backend != POWERCFG || !is_guid(&profile.profile) {
+            return Err(NanoCoreError::ConfigError(anyhow!("Perfil de energía inválido: {:?}", profile)).into());
+        }
+        run(POWERCFG, &["/setactive", &profile.profile]).await.map(|_| ())
+    }
+}
+
+/// macOS solo distingue bajo consumo (`lowpowermode 1`) del modo normal
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    const PMSET: &str = "pmset";
+
+    pub async fn current() -> Result<PowerProfile> {
+        let output = run(PMSET, &["-g"]).await?;
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("lowpowermode"))
+            .map(|value| PowerProfile::new(PMSET, value.trim()))
+            .ok_or_else(|| anyhow!("pmset no informa lowpowermode"))
+    }
+
+    pub async fn apply(mode: &PowerState) -> Result<PowerProfile> {
+        let value = match mode {
+            PowerState::PowerSaving | PowerState::Critical => "1",
+            PowerState::Normal | PowerState::HighPerformance => "0",
+        };
+        let profile = PowerProfile::new(PMSET, value);
+        set(&profile).await?;
+        Ok(profile)
+    }
+
+    pub async fn set(profile: &PowerProfile) -> Result<()> {
+        if profile.backend != PMSET || !["0", "1"].contains(&profile.profile.as_str()) {
+            return Err(NanoCoreError::ConfigError(anyhow!("Perfil de energía inválido: {:?}", profile)).into());
+        }
+        run(PMSET, &["-a", "lowpowermode", &profile.profile]).await.map(|_| ())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub async fn current() -> Result<PowerProfile> {
+        Err(anyhow!("Perfiles de energía no soportados en esta plataforma"))
+    }
+
+    pub async fn apply(_mode: &PowerState) -> Result<PowerProfile> {
+        current().await
+    }
+
+    pub async fn set(_profile: &PowerProfile) -> Result<()> {
+        current().await.map(|_| ())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::platform::cpufreq;
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_cpufreq_governors() {
+        let root = tempfile::tempdir().unwrap();
+        for policy in ["policy0", "policy4"] {
+            let dir = root.path().join(policy);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("scaling_governor"), "schedutil\n").unwrap();
+            fs::write(dir.join("scaling_available_governors"), "performance schedutil powersave\n").unwrap();
+        }
+
+        assert_eq!(cpufreq::governor(root.path()).unwrap(), "schedutil");
+        assert_eq!(cpufreq::governor_for(root.path(), &PowerState::HighPerformance).unwrap(), "performance");
+        assert_eq!(cpufreq::governor_for(root.path(), &PowerState::Critical).unwrap(), "powersave");
+
+        cpufreq::set_governor(root.path(), "performance").unwrap();
+        assert_eq!(fs::read_to_string(root.path().join("policy4/scaling_governor")).unwrap(), "performance");
+        assert!(cpufreq::set_governor(root.path(), "userspace").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Historial y tendencias del predictor de fallos de HardwareCore
+//!
+//! Cada `sample_interval_secs` se guarda una muestra compacta (temperaturas,
+//! presión de memoria y ocupación por disco) en `history_dir`, de modo que la
+//! historia sobrevive a reinicios. Sobre ella se ajusta una recta por mínimos
+//! cuadrados: la pendiente da el tiempo hasta el límite de cada componente y
+//! el R² junto con el número de muestras, la confianza de la predicción.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::fs;
+use tracing::debug;
+
+/// Sección `[nano_cores.hardware_core.predictor]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FailurePredictorConfig {
+    /// Directorio del historial; vacío lo mantiene solo en memoria
+    pub history_dir: String,
+    /// Segundos entre muestras del historial
+    pub sample_interval_secs: u64,
+    /// Muestras retenidas (1440 a 60 s son 24 horas)
+    pub history_limit: usize,
+    /// Muestras mínimas para ajustar una tendencia
+    pub min_samples: usize,
+    /// Se predicen los fallos que ocurrirían dentro de este plazo
+    pub horizon_hours: u64,
+}
+
+impl Default for FailurePredictorConfig {
+    fn default() -> Self {
+        Self {
+            history_dir: "data/hardware".to_string(),
+            sample_interval_secs: 60,
+            history_limit: 1440,
+            min_samples: 10,
+            horizon_hours: 24,
+        }
+    }
+}
+
+impl FailurePredictorConfig {
+    pub fn horizon_secs(&self) -> f64 {
+        (self.horizon_hours * 3600) as f64
+    }
+}
+
+/// Muestra del historial del predictor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PredictorSample {
+    pub timestamp: DateTime<Utc>,
+    pub cpu_temperature: Option<f32>,
+    pub gpu_temperature: Option<f32>,
+    pub memory_pressure: f32,
+    /// Ocupación por punto de montaje, en porcentaje
+    pub disk_usage: BTreeMap<String, f32>,
+}
+
+/// Recta ajustada a una serie
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trend {
+    /// Cambio por segundo
+    pub slope: f64,
+    /// Bondad del ajuste, de 0 a 1
+    pub r_squared: f64,
+    pub samples: usize,
+}
+
+impl Trend {
+    /// Ajustar por mínimos cuadrados; `None` con menos de `min_samples` puntos
+    pub fn fit<I>(points: I, min_samples: usize) -> Option<Self>
+    where
+        I: IntoIterator<Item = (DateTime<Utc>, f64)>,
+    {
+        let points: Vec<(DateTime<Utc>, f64)> = points.into_iter().collect();
+        if points.len() < min_samples.max(2) {
+            return None;
+        }
+
+        let origin = points[0].0;
+        let xs: Vec<f64> = points.iter().map(|(t, _)| (*t - origin).num_milliseconds() as f64 / 1000.0).collect();
+        let ys: Vec<f64> = points.iter().map(|(_, y)| *y).collect();
+        let n = xs.len() as f64;
+        let mean_x = xs.iter().sum::<f64>() / n;
+        let mean_y = ys.iter().sum::<f64>() / n;
+
+        let var_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+        if var_x == 0.0 {
+            return None;
+        }
+        let cov: f64 = xs.iter().zip(&ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let slope = cov / var_x;
+        let intercept = mean_y - slope * mean_x;
+
+        let ss_tot: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+        let ss_res: f64 = xs.iter().zip(&ys).map(|(x, y)| (y - (intercept + slope * x)).powi(2)).sum();
+        let r_squared = if ss_tot == 0.0 { 0.0 } else { (1.0 - ss_res / ss_tot).max(0.0) };
+
+        Some(Self {
+            slope,
+            r_squared,
+            samples: points.len(),
+        })
+    }
+
+    /// Segundos hasta que `current` alcance `limit` al ritmo actual
+    pub fn time_to(&self, current: f64, limit: f64) -> Option<f64> {
+        if current >= limit {
+            Some(0.0)
+        } else if self.slope > 0.0 {
+            Some((limit - current) / self.slope)
+        } else {
+            None
+        }
+    }
+
+    /// Confianza: R² atenuado con pocas muestras
+    pub fn confidence(&self) -> f32 {
+        (self.r_squared * (self.samples as f64 / 30.0).min(1.0)) as f32
+    }
+}
+
+/// Historial de una réplica en `<history_dir>/failure-history-<instancia>.json`
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    /// `None` si `history_dir` está vacío
+    pub fn new(config: &FailurePredictorConfig, instance: usize) -> Option<Self> {
+        if config.history_dir.is_empty() {
+            return None;
+        }
+        Some(Self {
+            path: PathBuf::from(&config.history_dir).join(format!("failure-history-{}.json", instance)),
+        })
+    }
+
+    pub async fn load(&self) -> Result<Vec<PredictorSample>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_slice(&fs::read(&self.path).await?)?)
+    }
+
+    pub async fn save(&self, samples: &[PredictorSample]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(samples)?).await?;
+        fs::rename(&tmp, &self.path).await?;
+        debug!("💾 Historial del predictor guardado: {} muestras", samples.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn sample(minutes: i64, temperature: f32, disk: f32) -> PredictorSample {
+        PredictorSample {
+            timestamp: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(minutes),
+            cpu_temperature: Some(temperature),
+            gpu_temperature: None,
+            memory_pressure: 0.2,
+            disk_usage: BTreeMap::from([("/".to_string(), disk)]),
+        }
+    }
+
+    #[test]
+    fn test_trend_fit() {
+        // +1 % de disco por hora, exacto
+        let points: Vec<_> = (0..20).map(|m| (sample(m * 60, 50.0, 80.0 + m as f32).timestamp, 80.0 + m as f64)).collect();
+        let trend = Trend::fit(points.clone(), 10).unwrap();
+        assert!((trend.slope * 3600.0 - 1.0).abs() < 1e-9);
+        assert!((trend.r_squared - 1.0).abs() < 1e-9);
+        assert!((trend.confidence() - 20.0 / 30.0).abs() < 1e-6);
+        let hours = trend.time_to(99.0, 100.0).unwrap() / 3600.0;
+        assert!((hours - 1.0).abs() < 1e-9);
+
+        assert!(Trend::fit(points.into_iter().take(5), 10).is_none());
+
+        let flat = Trend::fit((0..20).map(|m| (sample(m, 50.0, 0.0).timestamp, 50.0)), 10).unwrap();
+        assert_eq!(flat.slope, 0.0);
+        assert_eq!(flat.r_squared, 0.0);
+        assert_eq!(flat.time_to(50.0, 100.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_history_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = FailurePredictorConfig {
+            history_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let store = HistoryStore::new(&config, 0).unwrap();
+        assert!(store.load().await.unwrap().is_empty());
+
+        let samples = vec![sample(0, 45.0, 50.0), sample(1, 46.0, 50.1)];
+        store.save(&samples).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), samples);
+
+        assert!(HistoryStore::new(&FailurePredictorConfig { history_dir: String::new(), ..config }, 0).is_none());
+    }
+}
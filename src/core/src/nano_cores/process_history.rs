@@ -0,0 +1,199 @@
+//! Historial de consumo por proceso para OSCore
+//!
+//! Guarda una ventana de muestras de CPU y memoria de los procesos cuyo
+//! nombre coincide con `process_history.patterns`. Un crecimiento brusco de
+//! RSS dentro de la ventana se publica en `os.process.anomaly`, que el
+//! SecurityManager correlaciona con el patrón `ResourceAbuse`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use super::os_core::ProcessDetail;
+
+/// Subject de las anomalías de consumo de procesos
+pub const PROCESS_ANOMALY_SUBJECT: &str = "os.process.anomaly";
+
+/// Sección `[nano_cores.os_core.process_history]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProcessHistoryConfig {
+    /// Subcadenas del nombre de los procesos seguidos; vacío lo desactiva
+    pub patterns: Vec<String>,
+    /// Muestras retenidas por proceso (una por `monitor_interval_ms`)
+    pub window: usize,
+    /// Crecimiento de RSS respecto al mínimo de la ventana que se considera anómalo
+    pub rss_growth_factor: f64,
+    /// RSS por debajo del cual no se reporta crecimiento, en MB
+    pub min_rss_mb: u64,
+}
+
+impl Default for ProcessHistoryConfig {
+    fn default() -> Self {
+        Self {
+            patterns: vec!["saai".to_string()],
+            window: 120,
+            rss_growth_factor: 2.0,
+            min_rss_mb: 64,
+        }
+    }
+}
+
+/// Muestra de consumo de un proceso
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSample {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub cpu_usage: f32,
+    pub memory_usage: u64,
+}
+
+/// Crecimiento brusco de RSS publicado en `os.process.anomaly`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessAnomaly {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    /// Mínimo de la ventana
+    pub baseline_memory: u64,
+    pub memory_usage: u64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+struct ProcessTrack {
+    name: String,
+    samples: VecDeque<ProcessSample>,
+    /// Ya reportado; se rearma cuando el RSS vuelve por debajo del umbral
+    alerted: bool,
+}
+
+/// Ventanas de muestras por PID
+#[derive(Default)]
+pub struct ProcessHistory {
+    config: ProcessHistoryConfig,
+    tracks: HashMap<u32, ProcessTrack>,
+}
+
+impl ProcessHistory {
+    pub fn new(config: ProcessHistoryConfig) -> Self {
+        Self {
+            config,
+            tracks: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.config.patterns.is_empty()
+    }
+
+    pub fn set_config(&mut self, config: ProcessHistoryConfig) {
+        self.config = config;
+        let patterns = self.config.patterns.clone();
+        self.tracks.retain(|_, track| matches_patterns(&patterns, &track.name));
+    }
+
+    /// Incorporar una muestra de los procesos vivos y devolver los crecimientos nuevos
+    ///
+    /// Los PIDs que ya no aparecen se descartan.
+    pub fn record(&mut self, processes: &[ProcessDetail], now: chrono::DateTime<chrono::Utc>) -> Vec<ProcessAnomaly> {
+        let window = self.config.window.max(2);
+        let min_rss = self.config.min_rss_mb * 1024 * 1024;
+        let mut anomalies = Vec::new();
+        let mut alive = Vec::new();
+
+        for process in processes.iter().filter(|p| matches_patterns(&self.config.patterns, &p.name)) {
+            alive.push(process.pid);
+            let track = self.tracks.entry(process.pid).or_insert_with(|| ProcessTrack {
+                name: process.name.clone(),
+                samples: VecDeque::new(),
+                alerted: false,
+            });
+            // PID reutilizado por otro programa
+            if track.name != process.name {
+                track.name = process.name.clone();
+                track.samples.clear();
+                track.alerted = false;
+            }
+
+            track.samples.push_back(ProcessSample {
+                timestamp: now,
+                cpu_usage: process.cpu_usage,
+                memory_usage: process.memory_usage,
+            });
+            while track.samples.len() > window {
+                track.samples.pop_front();
+            }
+
+            let baseline = track.samples.iter().map(|s| s.memory_usage).min().unwrap_or(0);
+            let grown = baseline > 0
+                && process.memory_usage >= min_rss
+                && process.memory_usage as f64 >= baseline as f64 * self.config.rss_growth_factor;
+
+            if grown && !track.alerted {
+                anomalies.push(ProcessAnomaly {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    cpu_usage: process.cpu_usage,
+                    baseline_memory: baseline,
+                    memory_usage: process.memory_usage,
+                    timestamp: now,
+                });
+            }
+            track.alerted = grown;
+        }
+
+        self.tracks.retain(|pid, _| alive.contains(pid));
+        anomalies
+    }
+
+    /// Muestras retenidas de un proceso, de la más antigua a la más reciente
+    pub fn get(&self, pid: u32) -> Option<Vec<ProcessSample>> {
+        self.tracks.get(&pid).map(|track| track.samples.iter().copied().collect())
+    }
+}
+
+fn matches_patterns(patterns: &[String], name: &str) -> bool {
+    let name = name.to_lowercase();
+    patterns.iter().any(|pattern| name.contains(&pattern.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, memory_mb: u64) -> ProcessDetail {
+        ProcessDetail {
+            pid,
+            parent: None,
+            name: name.to_string(),
+            user: None,
+            cmdline: String::new(),
+            cpu_usage: 5.0,
+            memory_usage: memory_mb * 1024 * 1024,
+            status: "Run".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_history_and_rss_growth() {
+        let mut history = ProcessHistory::new(ProcessHistoryConfig {
+            window: 4,
+            ..Default::default()
+        });
+        let now = chrono::Utc::now();
+
+        assert!(history.record(&[process(10, "saai-core", 100), process(20, "bash", 500)], now).is_empty());
+        assert!(history.record(&[process(10, "saai-core", 150)], now).is_empty());
+        assert!(history.get(20).is_none());
+
+        let anomalies = history.record(&[process(10, "saai-core", 210)], now);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].baseline_memory, 100 * 1024 * 1024);
+        // Se reporta una sola vez mientras siga por encima
+        assert!(history.record(&[process(10, "saai-core", 220)], now).is_empty());
+        assert_eq!(history.get(10).unwrap().len(), 4);
+
+        // Proceso terminado
+        history.record(&[], now);
+        assert!(history.get(10).is_none());
+    }
+}
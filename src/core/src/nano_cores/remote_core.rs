@@ -0,0 +1,512 @@
+//! Nano-Core.Remote - Nano-núcleos fuera de proceso
+//!
+//! `RemoteNanoCore` adapta el servicio `NanoCoreService` de
+//! `proto/nano_core.proto` al trait `NanoCore`: lanza el proceso de cada
+//! réplica, lo llama por gRPC y lo vuelve a lanzar si termina. Del otro lado
+//! `serve` expone cualquier `NanoCore` como ese servicio.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tonic::transport::{Channel, Endpoint, Server};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::NanoCoresConfig;
+use crate::nano_cores::{
+    CommandAuthorization, CoreResult, NanoCore, NanoCoreContext, NanoCoreError, NanoCoreFactory, NanoCoreHealth,
+    NanoCoreManager, NanoCoreState, NanoCoreType,
+};
+use crate::security::SecurityLevel;
+use crate::util::Backoff;
+
+/// Servicio generado por tonic-build a partir de `proto/nano_core.proto`
+pub mod proto {
+    include!("../generated/saai.nano_core.rs");
+}
+
+use proto::nano_core_service_client::NanoCoreServiceClient;
+use proto::nano_core_service_server::{NanoCoreService, NanoCoreServiceServer};
+use proto::{CommandReply, CommandRequest, Empty, HealthReply};
+
+/// Variable con la dirección en la que debe escuchar el proceso
+pub const LISTEN_ENV: &str = "SAAI_NANO_CORE_LISTEN";
+
+/// Variable con el número de réplica del proceso
+pub const INSTANCE_ENV: &str = "SAAI_NANO_CORE_INSTANCE";
+
+/// Espera inicial entre intentos de conexión mientras arranca el proceso
+const CONNECT_RETRY_MS: u64 = 100;
+/// Espera máxima entre intentos de conexión
+const CONNECT_RETRY_MAX_MS: u64 = 1_000;
+
+/// Sección `[nano_cores.custom.<nombre>]` de un nano-núcleo remoto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteCoreConfig {
+    /// Programa y argumentos; vacío si el proceso lo gestiona otro (systemd, k8s)
+    pub command: Vec<String>,
+    pub host: String,
+    /// La réplica `n` escucha en `base_port + n`
+    pub base_port: u16,
+    /// Tiempo para que el proceso acepte conexiones
+    pub startup_timeout_ms: u64,
+    /// Tiempo máximo de cada llamada
+    pub call_timeout_ms: u64,
+    /// Permiso de los comandos del proceso (por defecto `nano_core.<nombre>.command`)
+    pub permission: Option<String>,
+    /// Nivel de seguridad de los comandos del proceso
+    pub level: SecurityLevel,
+    /// Espera entre dos llamadas a `run`, en ms
+    pub run_interval_ms: u64,
+}
+
+impl Default for RemoteCoreConfig {
+    fn default() -> Self {
+        Self {
+            command: Vec::new(),
+            host: "127.0.0.1".to_string(),
+            base_port: 0,
+            startup_timeout_ms: 10_000,
+            call_timeout_ms: 5_000,
+            permission: None,
+            level: SecurityLevel::Secret,
+            run_interval_ms: 1000,
+        }
+    }
+}
+
+impl RemoteCoreConfig {
+    /// Dirección de escucha de una réplica
+    pub fn listen_addr(&self, instance: usize) -> Result<String> {
+        let port = u16::try_from(instance)
+            .ok()
+            .and_then(|offset| self.base_port.checked_add(offset))
+            .ok_or_else(|| anyhow!("Puerto fuera de rango para la instancia {}", instance))?;
+        Ok(format!("{}:{}", self.host, port))
+    }
+}
+
+/// Fábrica de nano-núcleos remotos
+pub struct RemoteCoreFactory;
+
+#[async_trait]
+impl NanoCoreFactory for RemoteCoreFactory {
+    async fn create(&self, context: NanoCoreContext) -> Result<Box<dyn NanoCore>> {
+        Ok(Box::new(RemoteNanoCore::new(context)?))
+    }
+}
+
+/// Registrar una fábrica remota por cada sección de `[nano_cores.custom]` con `base_port`
+pub async fn register_configured(manager: &NanoCoreManager, config: &NanoCoresConfig) -> Result<()> {
+    let factory = Arc::new(RemoteCoreFactory);
+    for (name, section) in &config.custom {
+        if section.get("base_port").is_some() {
+            manager.register_factory(name.clone(), factory.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Nano-núcleo que corre en otro proceso
+pub struct RemoteNanoCore {
+    core_type: NanoCoreType,
+    instance_id: Uuid,
+    instance_number: usize,
+    config: RemoteCoreConfig,
+    listen: String,
+    /// Proceso lanzado por esta instancia; `None` si lo gestiona otro
+    child: Option<Child>,
+    client: Option<NanoCoreServiceClient<Channel>>,
+    start_time: SystemTime,
+    error_count: u64,
+    restarts: u64,
+}
+
+impl RemoteNanoCore {
+    pub fn new(context: NanoCoreContext) -> Result<Self> {
+        let config: RemoteCoreConfig = serde_json::from_value(context.config).map_err(|e| {
+            anyhow!("Configuración inválida de nano_cores.custom.{}: {}", context.core_type, e)
+        })?;
+        Self::with_config(context.core_type, context.instance, config)
+    }
+
+    pub fn with_config(core_type: NanoCoreType, instance: usize, config: RemoteCoreConfig) -> Result<Self> {
+        if config.base_port == 0 {
+            return Err(anyhow!("nano_cores.custom.{}.base_port no definido", core_type));
+        }
+
+        Ok(Self {
+            core_type,
+            instance_id: Uuid::new_v4(),
+            instance_number: instance,
+            listen: config.listen_addr(instance)?,
+            config,
+            child: None,
+            client: None,
+            start_time: SystemTime::now(),
+            error_count: 0,
+            restarts: 0,
+        })
+    }
+
+    /// Lanzar el proceso (si hay `command`) y esperar a que acepte conexiones
+    async fn start(&mut self) -> Result<()> {
+        if let Some((program, args)) = self.config.command.split_first() {
+            let child = Command::new(program)
+                .args(args)
+                .env(LISTEN_ENV, &self.listen)
+                .env(INSTANCE_ENV, self.instance_number.to_string())
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(|e| anyhow!("Error lanzando {} para {}: {}", program, self.core_type, e))?;
+            info!(
+                "🚀 {} instancia {} lanzado (PID {:?}) en {}",
+                self.core_type, self.instance_number, child.id(), self.listen
+            );
+            self.child = Some(child);
+        }
+
+        let endpoint = Endpoint::from_shared(format!("http://{}", self.listen))?
+            .timeout(Duration::from_millis(self.config.call_timeout_ms));
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(self.config.startup_timeout_ms);
+        let mut backoff = Backoff::new(Duration::from_millis(CONNECT_RETRY_MS))
+            .with_max(Duration::from_millis(CONNECT_RETRY_MAX_MS))
+            .with_jitter(0.5);
+        let channel = loop {
+            match endpoint.connect().await {
+                Ok(channel) => break channel,
+                Err(e) if tokio::time::Instant::now() >= deadline => {
+                    self.stop_child().await;
+                    return Err(anyhow!("{} no responde en {}: {}", self.core_type, self.listen, e));
+                }
+                Err(_) => tokio::time::sleep(backoff.next_delay()).await,
+            }
+        };
+
+        let mut client = NanoCoreServiceClient::new(channel);
+        client.initialize(Empty {}).await?;
+        self.client = Some(client);
+        Ok(())
+    }
+
+    async fn stop_child(&mut self) {
+        self.client = None;
+        if let Some(mut child) = self.child.take() {
+            if let Err(e) = child.kill().await {
+                warn!("⚠️  No se pudo terminar el proceso de {}: {}", self.core_type, e);
+            }
+        }
+    }
+
+    /// Relanzar el proceso si terminó
+    async fn ensure_running(&mut self) -> Result<()> {
+        let status = match self.child.as_mut() {
+            Some(child) => child.try_wait()?,
+            None => return Ok(()),
+        };
+        let Some(status) = status else {
+            return Ok(());
+        };
+
+        self.error_count += 1;
+        self.restarts += 1;
+        self.child = None;
+        self.client = None;
+        error!(
+            "💥 Proceso de {} instancia {} terminó ({}), relanzando",
+            self.core_type, self.instance_number, status
+        );
+        self.start().await?;
+        Err(anyhow!("Proceso de {} terminó: {}", self.core_type, status))
+    }
+
+    fn client(&self) -> Result<NanoCoreServiceClient<Channel>> {
+        self.client
+            .clone()
+            .ok_or_else(|| anyhow!("{} instancia {} no conectado", self.core_type, self.instance_number))
+    }
+}
+
+#[async_trait]
+impl NanoCore for RemoteNanoCore {
+    fn core_type(&self) -> NanoCoreType {
+        self.core_type.clone()
+    }
+
+    fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        info!(
+            "🔧 Inicializando {} instancia {} en {} (ID: {})",
+            self.core_type, self.instance_number, self.listen, self.instance_id
+        );
+
+        self.start().await?;
+
+        info!("✅ {} instancia {} inicializado correctamente", self.core_type, self.instance_number);
+        Ok(())
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        self.ensure_running().await?;
+
+        if let Err(status) = self.client()?.run(Empty {}).await {
+            self.error_count += 1;
+            return Err(from_status(&status, format!("run de {} falló", self.core_type)));
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<NanoCoreHealth> {
+        let uptime = self.start_time.elapsed()?.as_secs();
+
+        // Sin respuesta el proceso cuenta como caído hasta el siguiente `run`
+        let reply = match self.client() {
+            Ok(mut client) => client.health(Empty {}).await.map(Response::into_inner).ok(),
+            Err(_) => None,
+        };
+        let state = match &reply {
+            Some(reply) if reply.healthy && self.error_count <= 10 => NanoCoreState::Running,
+            Some(_) => NanoCoreState::Degraded,
+            None => NanoCoreState::Failed,
+        };
+        let reply = reply.unwrap_or_default();
+
+        Ok(NanoCoreHealth {
+            core_type: self.core_type(),
+            instance_id: self.instance_id,
+            state,
+            cpu_usage: reply.cpu_usage,
+            memory_usage: reply.memory_usage,
+            last_heartbeat: chrono::Utc::now(),
+            error_count: self.error_count + reply.error_count,
+            uptime_seconds: uptime,
+        })
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        info!("🛑 Deteniendo {} instancia {}", self.core_type, self.instance_number);
+
+        if let Ok(mut client) = self.client() {
+            if let Err(status) = client.shutdown(Empty {}).await {
+                warn!("⚠️  {} no completó el shutdown: {}", self.core_type, status.message());
+            }
+        }
+        self.stop_child().await;
+
+        info!(
+            "✅ {} instancia {} detenido correctamente ({} reinicios)",
+            self.core_type, self.instance_number, self.restarts
+        );
+        Ok(())
+    }
+
+    fn command_authorization(&self, _payload: &[u8]) -> Result<CommandAuthorization> {
+        // El proceso no decide qué se le exige: lo fija la configuración
+        let permission = self.config.permission.clone()
+            .unwrap_or_else(|| format!("nano_core.{}.command", self.core_type));
+        Ok(CommandAuthorization::new(&permission, self.config.level))
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_millis(self.config.run_interval_ms)
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
+        let reply = self.client()?
+            .process_command(CommandRequest {
+                command: command.to_string(),
+                payload: payload.to_vec(),
+            })
+            .await
+            .map_err(|status| from_status(&status, format!("{} de {} falló", command, self.core_type)))?;
+        Ok(reply.into_inner().payload)
+    }
+}
+
+/// Clase de error del proceso según el código gRPC de `core_status`
+fn from_status(status: &Status, context: String) -> NanoCoreError {
+    let error = anyhow!("{}: {}", context, status.message());
+    match status.code() {
+        tonic::Code::Internal => NanoCoreError::Fatal(error),
+        tonic::Code::FailedPrecondition => NanoCoreError::ConfigError(error),
+        tonic::Code::PermissionDenied => NanoCoreError::SecurityDenied(error),
+        tonic::Code::ResourceExhausted => NanoCoreError::Backpressure(error),
+        // Incluye los fallos de transporte: el proceso se relanza en el siguiente `run`
+        _ => NanoCoreError::Transient(error),
+    }
+}
+
+/// Servicio que expone un `NanoCore` local
+struct HostedNanoCore {
+    core: Mutex<Box<dyn NanoCore>>,
+}
+
+fn to_status(e: anyhow::Error) -> Status {
+    Status::internal(e.to_string())
+}
+
+/// Código gRPC que conserva la clase de error hasta `from_status`
+fn core_status(e: NanoCoreError) -> Status {
+    let message = e.to_string();
+    match e {
+        NanoCoreError::Transient(_) => Status::unavailable(message),
+        NanoCoreError::Fatal(_) => Status::internal(message),
+        NanoCoreError::ConfigError(_) => Status::failed_precondition(message),
+        NanoCoreError::SecurityDenied(_) => Status::permission_denied(message),
+        NanoCoreError::Backpressure(_) => Status::resource_exhausted(message),
+    }
+}
+
+#[tonic::async_trait]
+impl NanoCoreService for HostedNanoCore {
+    async fn initialize(&self, _request: Request<Empty>) -> std::result::Result<Response<Empty>, Status> {
+        self.core.lock().await.initialize().await.map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn run(&self, _request: Request<Empty>) -> std::result::Result<Response<Empty>, Status> {
+        self.core.lock().await.run().await.map_err(core_status)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn health(&self, _request: Request<Empty>) -> std::result::Result<Response<HealthReply>, Status> {
+        let health = self.core.lock().await.health_check().await.map_err(to_status)?;
+        Ok(Response::new(HealthReply {
+            healthy: matches!(health.state, NanoCoreState::Running),
+            cpu_usage: health.cpu_usage,
+            memory_usage: health.memory_usage,
+            error_count: health.error_count,
+        }))
+    }
+
+    async fn process_command(&self, request: Request<CommandRequest>) -> std::result::Result<Response<CommandReply>, Status> {
+        let request = request.into_inner();
+        let payload = self.core.lock().await
+            .process_command(&request.command, &request.payload)
+            .await
+            .map_err(core_status)?;
+        Ok(Response::new(CommandReply { payload }))
+    }
+
+    async fn shutdown(&self, _request: Request<Empty>) -> std::result::Result<Response<Empty>, Status> {
+        self.core.lock().await.shutdown().await.map_err(to_status)?;
+        Ok(Response::new(Empty {}))
+    }
+}
+
+/// Servir `core` en la dirección de `SAAI_NANO_CORE_LISTEN` hasta que termine el proceso
+///
+/// Punto de entrada de los binarios de nano-núcleos remotos.
+pub async fn serve(core: Box<dyn NanoCore>) -> Result<()> {
+    let listen = std::env::var(LISTEN_ENV).map_err(|_| anyhow!("{} no definida", LISTEN_ENV))?;
+    serve_at(core, &listen).await
+}
+
+/// Servir `core` en `listen`
+pub async fn serve_at(core: Box<dyn NanoCore>, listen: &str) -> Result<()> {
+    let addr = listen.parse()?;
+
+    info!("📡 Nano-núcleo {} escuchando en {}", core.core_type(), addr);
+    Server::builder()
+        .add_service(NanoCoreServiceServer::new(HostedNanoCore { core: Mutex::new(core) }))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nano-núcleo que cuenta iteraciones y devuelve los comandos al revés
+    struct EchoCore {
+        runs: u64,
+    }
+
+    #[async_trait]
+    impl NanoCore for EchoCore {
+        fn core_type(&self) -> NanoCoreType {
+            NanoCoreType::Custom("echo".to_string())
+        }
+
+        fn instance_id(&self) -> Uuid {
+            Uuid::nil()
+        }
+
+        async fn initialize(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn run(&mut self) -> CoreResult<()> {
+            self.runs += 1;
+            if self.runs > 2 {
+                return Err(NanoCoreError::Backpressure(anyhow!("sin trabajo")));
+            }
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<NanoCoreHealth> {
+            Ok(NanoCoreHealth {
+                core_type: self.core_type(),
+                instance_id: self.instance_id(),
+                state: NanoCoreState::Running,
+                cpu_usage: 1.5,
+                memory_usage: 1024.0,
+                last_heartbeat: chrono::Utc::now(),
+                error_count: 0,
+                uptime_seconds: 0,
+            })
+        }
+
+        async fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn command_authorization(&self, _payload: &[u8]) -> Result<CommandAuthorization> {
+            Ok(CommandAuthorization::new("echo", SecurityLevel::Public))
+        }
+
+        async fn process_command(&mut self, _command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
+            Ok(payload.iter().rev().copied().collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_core_round_trip() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let config = RemoteCoreConfig { base_port: port - 1, ..Default::default() };
+        let listen = config.listen_addr(1).unwrap();
+        assert_eq!(listen, format!("127.0.0.1:{}", port));
+        tokio::spawn(async move { serve_at(Box::new(EchoCore { runs: 0 }), &listen).await });
+
+        // Proceso gestionado externamente: sin `command`
+        let core_type = NanoCoreType::Custom("echo".to_string());
+        let mut remote = RemoteNanoCore::with_config(core_type, 1, config).unwrap();
+        remote.initialize().await.unwrap();
+        remote.run().await.unwrap();
+        remote.run().await.unwrap();
+        assert!(matches!(remote.run().await, Err(NanoCoreError::Backpressure(_))));
+
+        let health = remote.health_check().await.unwrap();
+        assert!(matches!(health.state, NanoCoreState::Degraded | NanoCoreState::Running));
+        assert_eq!(health.memory_usage, 1024.0);
+        assert_eq!(remote.process_command("reverse", b"abc").await.unwrap(), b"cba");
+
+        let required = remote.command_authorization(b"").unwrap();
+        assert_eq!((required.permission.as_str(), required.level), ("nano_core.echo.command", SecurityLevel::Secret));
+
+        remote.shutdown().await.unwrap();
+        assert!(matches!(remote.health_check().await.unwrap().state, NanoCoreState::Failed));
+    }
+}
@@ -0,0 +1,188 @@
+//! Comandos programados de OSCore
+//!
+//! `nano_cores.os_core.schedules` asocia comandos `OSCommand` a expresiones
+//! cron (limpieza de logs, verificación de checksums...). La instancia 0 los
+//! ejecuta en su ciclo de `run` y publica cada resultado en
+//! `os.schedule.results`. Los que se registran con `ScheduleCommand` se
+//! publican en `os.schedules` para que el proceso los guarde en la configuración.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Subject de los resultados de las ejecuciones programadas
+pub const SCHEDULE_RESULTS_SUBJECT: &str = "os.schedule.results";
+/// Subject de los cambios de programación a persistir en la configuración
+pub const SCHEDULES_SUBJECT: &str = "os.schedules";
+
+/// Comando recurrente, `[[nano_cores.os_core.schedules]]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduledCommand {
+    pub id: String,
+    /// Expresión cron de 5 campos (minuto a día de la semana) o de 6 con segundos
+    pub cron: String,
+    /// `OSCommand` tal como lo recibe `process_command`, p. ej. `"GetSystemResources"`
+    pub command: serde_json::Value,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Cambio de programación publicado en `os.schedules`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScheduleUpdate {
+    Upsert(ScheduledCommand),
+    Remove(String),
+}
+
+impl ScheduleUpdate {
+    /// Aplicar el cambio a la lista guardada en la configuración
+    pub fn apply(&self, schedules: &mut Vec<ScheduledCommand>) {
+        match self {
+            ScheduleUpdate::Upsert(command) => match schedules.iter_mut().find(|s| s.id == command.id) {
+                Some(existing) => *existing = command.clone(),
+                None => schedules.push(command.clone()),
+            },
+            ScheduleUpdate::Remove(id) => schedules.retain(|s| &s.id != id),
+        }
+    }
+}
+
+/// Resultado de una ejecución, publicado en `os.schedule.results`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleResult {
+    pub id: String,
+    pub success: bool,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub instance: usize,
+}
+
+/// Expresión cron; las de 5 campos se ejecutan en el segundo 0
+pub fn parse_cron(expression: &str) -> Result<Schedule> {
+    let expression = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression),
+        _ => expression.to_string(),
+    };
+    Schedule::from_str(&expression).map_err(|e| anyhow!("Expresión cron inválida '{}': {}", expression, e))
+}
+
+struct Entry {
+    command: ScheduledCommand,
+    schedule: Schedule,
+    next: Option<DateTime<Utc>>,
+}
+
+/// Programaciones activas de una instancia de OSCore
+#[derive(Default)]
+pub struct CommandScheduler {
+    entries: BTreeMap<String, Entry>,
+}
+
+impl CommandScheduler {
+    /// Sustituir las programaciones por las de la configuración
+    ///
+    /// Las que no cambian conservan su próxima ejecución.
+    pub fn set(&mut self, commands: &[ScheduledCommand], now: DateTime<Utc>) {
+        let mut entries = BTreeMap::new();
+        for command in commands {
+            match self.entries.remove(&command.id) {
+                Some(entry) if entry.command == *command => {
+                    entries.insert(command.id.clone(), entry);
+                }
+                _ => match Self::entry(command.clone(), now) {
+                    Ok(entry) => {
+                        entries.insert(command.id.clone(), entry);
+                    }
+                    Err(e) => tracing::warn!("⚠️  Programación {} descartada: {}", command.id, e),
+                },
+            }
+        }
+        self.entries = entries;
+    }
+
+    /// Añadir o reemplazar una programación
+    pub fn upsert(&mut self, command: ScheduledCommand, now: DateTime<Utc>) -> Result<()> {
+        let entry = Self::entry(command, now)?;
+        self.entries.insert(entry.command.id.clone(), entry);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.entries.remove(id).is_some()
+    }
+
+    pub fn list(&self) -> Vec<ScheduledCommand> {
+        self.entries.values().map(|entry| entry.command.clone()).collect()
+    }
+
+    /// Programaciones vencidas en `now`, avanzando su próxima ejecución
+    ///
+    /// Las ejecuciones perdidas mientras el proceso estaba parado no se recuperan.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<ScheduledCommand> {
+        let mut due = Vec::new();
+        for entry in self.entries.values_mut().filter(|entry| entry.command.enabled) {
+            if entry.next.is_some_and(|next| next <= now) {
+                due.push(entry.command.clone());
+                entry.next = entry.schedule.after(&now).next();
+            }
+        }
+        due
+    }
+
+    fn entry(command: ScheduledCommand, now: DateTime<Utc>) -> Result<Entry> {
+        let schedule = parse_cron(&command.cron)?;
+        let next = schedule.after(&now).next();
+        Ok(Entry { command, schedule, next })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn command(id: &str, cron: &str) -> ScheduledCommand {
+        ScheduledCommand {
+            id: id.to_string(),
+            cron: cron.to_string(),
+            command: serde_json::json!("GetSystemResources"),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_schedule_due_and_updates() {
+        assert!(parse_cron("*/5 * * * *").is_ok());
+        assert!(parse_cron("0 30 2 * * *").is_ok());
+        assert!(parse_cron("no es cron").is_err());
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap();
+        let mut scheduler = CommandScheduler::default();
+        scheduler.set(&[command("cleanup", "*/5 * * * *"), command("bad", "x")], start);
+        assert_eq!(scheduler.list().len(), 1);
+
+        assert!(scheduler.due(start).is_empty());
+        let at = |minute, second| Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, second).unwrap();
+        assert_eq!(scheduler.due(at(5, 0))[0].id, "cleanup");
+        assert!(scheduler.due(at(5, 1)).is_empty());
+        assert_eq!(scheduler.due(at(10, 2)).len(), 1);
+
+        let mut schedules = vec![command("cleanup", "*/5 * * * *")];
+        ScheduleUpdate::Upsert(command("checksum", "0 3 * * *")).apply(&mut schedules);
+        ScheduleUpdate::Upsert(command("cleanup", "0 * * * *")).apply(&mut schedules);
+        assert_eq!(schedules.len(), 2);
+        assert_eq!(schedules[0].cron, "0 * * * *");
+        ScheduleUpdate::Remove("cleanup".to_string()).apply(&mut schedules);
+        assert_eq!(schedules.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["checksum"]);
+    }
+}
@@ -9,14 +9,17 @@ use ring::{digest, hmac, rand};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::communication::CognitiveFabric;
+use crate::config::{CoreConfig, SecurityCoreConfig};
 use crate::metrics::MetricsCollector;
-use crate::nano_cores::{NanoCore, NanoCoreType, NanoCoreState, NanoCoreHealth};
+use crate::nano_cores::{CommandAuthorization, CoreResult, NanoCore, NanoCoreError, NanoCoreType, NanoCoreState, NanoCoreHealth};
+// El `SecurityLevel` de este módulo es la postura evaluada, no el nivel de una sesión
+use crate::security::SecurityLevel as SessionLevel;
 
 /// Estado de seguridad del sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,6 +124,7 @@ pub enum IsolationLevel {
 
 /// Límites de recursos
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ResourceLimits {
     pub max_cpu_percent: f64,
     pub max_memory_bytes: u64,
@@ -129,6 +133,18 @@ pub struct ResourceLimits {
     pub allowed_syscalls: Vec<String>,
 }
 
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_cpu_percent: 50.0,
+            max_memory_bytes: 1024 * 1024 * 512,
+            max_file_descriptors: 1024,
+            max_network_connections: 100,
+            allowed_syscalls: vec!["read".to_string(), "write".to_string()],
+        }
+    }
+}
+
 /// Uso de recursos
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceUsage {
@@ -263,6 +279,23 @@ pub enum SecurityCommand {
     QuarantineProcess(u32),
 }
 
+impl SecurityCommand {
+    /// Permiso y nivel exigidos para ejecutar el comando
+    pub fn authorization(&self) -> CommandAuthorization {
+        match self {
+            SecurityCommand::GetSecurityStatus => CommandAuthorization::new("nano_core.security.status", SessionLevel::Internal),
+            SecurityCommand::GenerateSecurityReport => CommandAuthorization::new("nano_core.security.report", SessionLevel::Confidential),
+            SecurityCommand::ScanVulnerabilities => CommandAuthorization::new("nano_core.security.scan", SessionLevel::Confidential),
+            SecurityCommand::CreateSandbox(_) | SecurityCommand::DestroySandbox(_) => {
+                CommandAuthorization::new("nano_core.security.sandbox", SessionLevel::Secret)
+            }
+            SecurityCommand::UpdateFirewallRules(_) => CommandAuthorization::new("nano_core.security.firewall", SessionLevel::Secret),
+            SecurityCommand::QuarantineProcess(_) => CommandAuthorization::new("nano_core.security.quarantine", SessionLevel::Secret),
+            SecurityCommand::RotateEncryptionKeys => CommandAuthorization::new("nano_core.security.keys", SessionLevel::TopSecret),
+        }
+    }
+}
+
 /// Configuración de sandbox
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxConfig {
@@ -298,6 +331,7 @@ pub enum FirewallAction {
 /// Nano-Core de seguridad
 pub struct SecurityCore {
     instance_id: Uuid,
+    config: SecurityCoreConfig,
     cognitive_fabric: Arc<CognitiveFabric>,
     metrics: Arc<MetricsCollector>,
     instance_number: usize,
@@ -311,15 +345,25 @@ pub struct SecurityCore {
     intrusion_detector: IntrusionDetector,
 }
 
+/// Estado de `SecurityCore` entre reemplazos y reinicios
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SecurityCoreCheckpoint {
+    error_count: u64,
+}
+
 impl SecurityCore {
     /// Crear nueva instancia de SecurityCore
     pub async fn new(
         cognitive_fabric: Arc<CognitiveFabric>,
         metrics: Arc<MetricsCollector>,
         instance_number: usize,
+        config: SecurityCoreConfig,
     ) -> Result<Self> {
+        let retained = config.retained_keys;
         Ok(Self {
             instance_id: Uuid::new_v4(),
+            config,
             cognitive_fabric,
             metrics,
             instance_number,
@@ -327,7 +371,7 @@ impl SecurityCore {
             error_count: Arc::new(RwLock::new(0)),
             threat_detector: ThreatDetector::new(),
             sandbox_manager: SandboxManager::new(),
-            encryption_manager: EncryptionManager::new()?,
+            encryption_manager: EncryptionManager::new(retained)?,
             firewall_manager: FirewallManager::new(),
             vulnerability_scanner: VulnerabilityScanner::new(),
             intrusion_detector: IntrusionDetector::new(),
@@ -441,7 +485,29 @@ impl SecurityCore {
 
     /// Rotar claves de encriptación
     async fn rotate_encryption_keys(&self) -> Result<()> {
-        self.encryption_manager.rotate_keys().await
+        let key_id = self.encryption_manager.rotate_keys().await?;
+        self.publish_key_rotation(key_id).await
+    }
+
+    /// Rotar si la clave actual supera `key_rotation_interval_hours`
+    async fn rotate_keys_if_due(&self) -> Result<()> {
+        let interval = chrono::Duration::hours(self.config.key_rotation_interval_hours as i64);
+        if let Some(key_id) = self.encryption_manager.rotate_if_due(interval).await? {
+            self.publish_key_rotation(key_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Anunciar la nueva versión de clave para que los consumidores re-encripten
+    async fn publish_key_rotation(&self, key_id: u32) -> Result<()> {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "instance": self.instance_number,
+            "key_id": key_id,
+        }))?;
+
+        self.cognitive_fabric
+            .publish("security.keys.rotated", &payload)
+            .await
     }
 
     /// Publicar métricas de seguridad
@@ -514,15 +580,9 @@ impl NanoCore for SecurityCore {
             self.instance_id
         );
 
-        // Suscribirse a comandos de seguridad
-        self.cognitive_fabric
-            .subscribe("security.commands", {
-                let instance_id = self.instance_id;
-                move |data| {
-                    debug!("📨 SecurityCore {} recibió comando: {} bytes", instance_id, data.len());
-                }
-            })
-            .await?;
+        if !self.config.sandbox_enabled {
+            warn!("⚠️  SecurityCore instancia {} sin sandbox", self.instance_number);
+        }
 
         // Inicializar componentes de seguridad
         self.threat_detector.start().await?;
@@ -541,12 +601,12 @@ impl NanoCore for SecurityCore {
         Ok(())
     }
 
-    async fn run(&mut self) -> Result<()> {
+    async fn run(&mut self) -> CoreResult<()> {
         // Publicar métricas de seguridad
         if let Err(e) = self.publish_security_metrics().await {
             let mut error_count = self.error_count.write().await;
             *error_count += 1;
-            return Err(anyhow!("Error publicando métricas de seguridad: {}", e));
+            return Err(NanoCoreError::Transient(anyhow!("Error publicando métricas de seguridad: {}", e)));
         }
 
         // Verificar alertas de seguridad
@@ -554,14 +614,17 @@ impl NanoCore for SecurityCore {
             warn!("⚠️  Error verificando alertas de seguridad: {}", e);
         }
 
+        // Rotación programada de claves
+        if let Err(e) = self.rotate_keys_if_due().await {
+            warn!("⚠️  Error rotando claves de encriptación: {}", e);
+        }
+
         // Ejecutar escaneo de vulnerabilidades cada 20 ciclos
         if self.instance_number % 20 == 0 {
             if let Err(e) = self.scan_vulnerabilities().await {
                 warn!("⚠️  Error en escaneo de vulnerabilidades: {}", e);
             }
         }
-
-        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
         Ok(())
     }
 
@@ -599,17 +662,16 @@ impl NanoCore for SecurityCore {
         // Detener componentes de seguridad
         self.threat_detector.stop().await?;
         self.intrusion_detector.stop().await?;
-        
-        // Desuscribirse de eventos
-        self.cognitive_fabric
-            .unsubscribe("security.commands")
-            .await?;
 
         info!("✅ SecurityCore instancia {} detenido correctamente", self.instance_number);
         Ok(())
     }
 
-    async fn process_command(&mut self, command: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    fn command_authorization(&self, payload: &[u8]) -> Result<CommandAuthorization> {
+        Ok(serde_json::from_slice::<SecurityCommand>(payload)?.authorization())
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
         let cmd: SecurityCommand = serde_json::from_slice(payload)?;
         
         let response = match cmd {
@@ -650,6 +712,30 @@ impl NanoCore for SecurityCore {
         debug!("✅ Comando SecurityCore procesado: {}", command);
         Ok(response)
     }
+    
+    async fn apply_config(&mut self, config: &CoreConfig) -> Result<()> {
+        self.config = config.nano_cores.for_instance(self.instance_number)?.security_core;
+        self.encryption_manager.set_retained_keys(self.config.retained_keys)?;
+        debug!("📋 SecurityCore instancia {} reconfigurada", self.instance_number);
+        Ok(())
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&SecurityCoreCheckpoint {
+            error_count: *self.error_count.read().await,
+        })?)
+    }
+
+    async fn restore(&mut self, state: &[u8]) -> Result<()> {
+        let checkpoint: SecurityCoreCheckpoint = serde_json::from_slice(state)?;
+        *self.error_count.write().await = checkpoint.error_count;
+        debug!("♻️  SecurityCore instancia {} restaurada", self.instance_number);
+        Ok(())
+    }
 }
 
 impl SecurityCore {
@@ -698,13 +784,7 @@ impl SandboxManager {
             enabled: true,
             active_sandboxes: vec![],
             isolation_level: IsolationLevel::Container,
-            resource_limits: ResourceLimits {
-                max_cpu_percent: 50.0,
-                max_memory_bytes: 1024 * 1024 * 512,
-                max_file_descriptors: 1024,
-                max_network_connections: 100,
-                allowed_syscalls: vec!["read".to_string(), "write".to_string()],
-            },
+            resource_limits: ResourceLimits::default(),
         })
     }
     pub async fn create_sandbox(&self, _config: SandboxConfig) -> Result<String> {
@@ -713,20 +793,28 @@ impl SandboxManager {
     pub async fn destroy_sandbox(&self, _id: &str) -> Result<()> { Ok(()) }
 }
 
-pub struct EncryptionManager;
+pub struct EncryptionManager {
+    inner: crate::security::EncryptionManager,
+}
 impl EncryptionManager {
-    pub fn new() -> Result<Self> { Ok(Self) }
+    pub fn new(retained_keys: usize) -> Result<Self> {
+        Ok(Self { inner: crate::security::EncryptionManager::with_retained_keys(retained_keys)? })
+    }
     pub async fn get_status(&self) -> Result<EncryptionStatus> {
         Ok(EncryptionStatus {
             enabled: true,
             algorithm: "AES-256-GCM".to_string(),
             key_strength: 256,
-            last_key_rotation: SystemTime::now(),
+            last_key_rotation: self.inner.last_rotation()?.into(),
             encrypted_connections: 10,
             encryption_overhead: 2.5,
         })
     }
-    pub async fn rotate_keys(&self) -> Result<()> { Ok(()) }
+    pub async fn rotate_keys(&self) -> Result<u32> { self.inner.rotate_keys().await }
+    pub async fn rotate_if_due(&self, interval: chrono::Duration) -> Result<Option<u32>> {
+        self.inner.rotate_if_due(interval).await
+    }
+    pub fn set_retained_keys(&self, retained: usize) -> Result<()> { self.inner.set_retained_keys(retained) }
 }
 
 pub struct FirewallManager;
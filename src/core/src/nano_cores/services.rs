@@ -0,0 +1,285 @@
+//! Gestión de servicios del sistema para OSCore
+//!
+//! Consulta, arranque, parada y reinicio de servicios con el gestor de cada
+//! plataforma: systemd por D-Bus en Linux, `launchctl` en macOS y `sc` en
+//! Windows. Permite a los agentes remediar dependencias caídas, como un
+//! servidor NATS local.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::nano_cores::NanoCoreError;
+
+/// Operación sobre un servicio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
+}
+
+/// Estado de un servicio según el gestor de la plataforma
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub name: String,
+    pub active: bool,
+    /// Estado tal como lo reporta el gestor (`active`, `RUNNING`, `running`...)
+    pub state: String,
+    pub manager: String,
+}
+
+/// Nombres de unidad válidos en los tres gestores; evita rutas y opciones
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "@._:-".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(NanoCoreError::ConfigError(anyhow!("Nombre de servicio inválido: {}", name)).into())
+    }
+}
+
+/// Solo los servicios de `controllable_services` se pueden arrancar o parar
+pub fn check_controllable(name: &str, controllable: &[String]) -> Result<()> {
+    if controllable.iter().any(|s| s == name) {
+        Ok(())
+    } else {
+        Err(NanoCoreError::SecurityDenied(anyhow!("Servicio {} fuera de controllable_services", name)).into())
+    }
+}
+
+pub async fn query(name: &str) -> Result<ServiceStatus> {
+    validate_name(name)?;
+    platform::query(name).await
+}
+
+/// Ejecutar la acción y devolver el estado resultante
+pub async fn control(name: &str, action: ServiceAction) -> Result<ServiceStatus> {
+    validate_name(name)?;
+    platform::control(name, action).await?;
+    info!("🛠️  Servicio {}: {}", name, action.as_str());
+    platform::query(name).await
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use zbus::zvariant::OwnedObjectPath;
+    use zbus::{Connection, Proxy};
+
+    const DESTINATION: &str = "org.freedesktop.systemd1";
+
+    fn unit_name(name: &str) -> String {
+        if name.contains('.') {
+            name.to_string()
+        } else {
+            format!("{}.service", name)
+        }
+    }
+
+    async fn manager(connection: &Connection) -> Result<Proxy<'_>> {
+        Ok(Proxy::new(connection, DESTINATION, "/org/freedesktop/systemd1", "org.freedesktop.systemd1.Manager").await?)
+    }
+
+    pub async fn query(name: &str) -> Result<ServiceStatus> {
+        let connection = Connection::system().await?;
+        let path: OwnedObjectPath = manager(&connection).await?.call("LoadUnit", &(unit_name(name),)).await?;
+        let unit = Proxy::new(&connection, DESTINATION, path, "org.freedesktop.systemd1.Unit").await?;
+        let state: String = unit.get_property("ActiveState").await?;
+
+        Ok(ServiceStatus {
+            name: name.to_string(),
+            active: state == "active",
+            state,
+            manager: "systemd".to_string(),
+        })
+    }
+
+    pub async fn control(name: &str, action: ServiceAction) -> Result<()> {
+        let method = match action {
+            ServiceAction::Start => "StartUnit",
+            ServiceAction::Stop => "StopUnit",
+            ServiceAction::Restart => "RestartUnit",
+        };
+        let connection = Connection::system().await?;
+        let result: zbus::Result<OwnedObjectPath> =
+            manager(&connection).await?.call(method, &(unit_name(name), "replace")).await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(zbus::Error::MethodError(error, message, _))
+                if error.as_str() == "org.freedesktop.DBus.Error.AccessDenied"
+                    || error.as_str() == "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired" =>
+            {
+                Err(NanoCoreError::SecurityDenied(anyhow!(
+                    "systemd rechazó {} {}: {}",
+                    action.as_str(),
+                    name,
+                    message.unwrap_or_default()
+                ))
+                .into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use tokio::process::Command;
+
+    async fn launchctl(args: &[&str]) -> Result<std::process::Output> {
+        Ok(Command::new("launchctl").args(args).output().await?)
+    }
+
+    pub async fn query(name: &str) -> Result<ServiceStatus> {
+        let output = launchctl(&["print", &format!("system/{}", name)]).await?;
+        if !output.status.success() {
+            return Err(anyhow!("Servicio {} no encontrado en launchd", name));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let state = stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("state = "))
+            .unwrap_or("unknown")
+            .to_string();
+
+        Ok(ServiceStatus {
+            name: name.to_string(),
+            active: state == "running",
+            state,
+            manager: "launchd".to_string(),
+        })
+    }
+
+    pub async fn control(name: &str, action: ServiceAction) -> Result<()> {
+        let target = format!("system/{}", name);
+        let args: Vec<&str> = match action {
+            ServiceAction::Start => vec!["kickstart", &target],
+            ServiceAction::Stop => vec!["kill", "SIGTERM", &target],
+            ServiceAction::Restart => vec!["kickstart", "-k", &target],
+        };
+        let output = launchctl(&args).await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.contains("Operation not permitted") || stderr.contains("privileges") {
+            Err(NanoCoreError::SecurityDenied(anyhow!("launchctl rechazó {} {}: {}", action.as_str(), name, stderr)).into())
+        } else {
+            Err(anyhow!("launchctl {} {} falló: {}", action.as_str(), name, stderr))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use tokio::process::Command;
+
+    /// Código de `sc` cuando falta permiso (ERROR_ACCESS_DENIED)
+    const ACCESS_DENIED: i32 = 5;
+    /// El servicio ya estaba parado o arrancado
+    const ALREADY_STOPPED: i32 = 1062;
+    const ALREADY_RUNNING: i32 = 1056;
+
+    async fn sc(verb: &str, name: &str) -> Result<std::process::Output> {
+        Ok(Command::new("sc").args([verb, name]).output().await?)
+    }
+
+    pub async fn query(name: &str) -> Result<ServiceStatus> {
+        let output = sc("query", name).await?;
+        if !output.status.success() {
+            return Err(anyhow!("Servicio {} no encontrado: {}", name, String::from_utf8_lossy(&output.stdout).trim()));
+        }
+        // "        STATE              : 4  RUNNING"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let state = stdout
+            .lines()
+            .find(|line| line.trim_start().starts_with("STATE"))
+            .and_then(|line| line.split_whitespace().last())
+            .unwrap_or("UNKNOWN")
+            .to_string();
+
+        Ok(ServiceStatus {
+            name: name.to_string(),
+            active: state == "RUNNING",
+            state,
+            manager: "sc".to_string(),
+        })
+    }
+
+    async fn run(verb: &str, name: &str, ignored: i32) -> Result<()> {
+        let output = sc(verb, name).await?;
+        match output.status.code() {
+            Some(0) => Ok(()),
+            Some(code) if code == ignored => Ok(()),
+            Some(ACCESS_DENIED) => Err(NanoCoreError::SecurityDenied(anyhow!("sc {} {}: acceso denegado", verb, name)).into()),
+            _ => Err(anyhow!("sc {} {} falló: {}", verb, name, String::from_utf8_lossy(&output.stdout).trim())),
+        }
+    }
+
+    pub async fn control(name: &str, action: ServiceAction) -> Result<()> {
+        match action {
+            ServiceAction::Start => run("start", name, ALREADY_RUNNING).await,
+            ServiceAction::Stop => run("stop", name, ALREADY_STOPPED).await,
+            ServiceAction::Restart => {
+                run("stop", name, ALREADY_STOPPED).await?;
+                // `sc stop` no espera a que el servicio termine de parar
+                for _ in 0..30 {
+                    if query(name).await?.state == "STOPPED" {
+                        break;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                }
+                run("start", name, ALREADY_RUNNING).await
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub async fn query(_name: &str) -> Result<ServiceStatus> {
+        Err(anyhow!("Gestión de servicios no soportada en esta plataforma"))
+    }
+
+    pub async fn control(_name: &str, _action: ServiceAction) -> Result<()> {
+        Err(anyhow!("Gestión de servicios no soportada en esta plataforma"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_names() {
+        assert!(validate_name("nats-server").is_ok());
+        assert!(validate_name("getty@tty1.service").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("-H host").is_err());
+        assert!(validate_name("../../etc/passwd").is_err());
+
+        let controllable = vec!["nats-server".to_string()];
+        assert!(check_controllable("nats-server", &controllable).is_ok());
+        let denied = check_controllable("sshd", &controllable).unwrap_err();
+        assert!(matches!(NanoCoreError::from(denied), NanoCoreError::SecurityDenied(_)));
+    }
+}
@@ -0,0 +1,533 @@
+//! Traffic shaping de las clases QoS con tc (Linux)
+//!
+//! Cada `TrafficClass` es una clase HTB hija de `1:1` con su garantía como
+//! `rate`, su máximo como `ceil` y una cola fq_codel cuyo `target` sale del
+//! objetivo de latencia. Los `packet_filters` se traducen a filtros flower y el
+//! tráfico sin clasificar va a una clase por defecto con lo que sobra del
+//! enlace. Las estadísticas por clase salen de `tc -s class show`.
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::nano_cores::NanoCoreError;
+use crate::nano_cores::netinfo;
+use crate::nano_cores::network_core::{PacketFilter, Protocol, TrafficClass};
+
+/// Clase HTB de lo que no coincide con ningún filtro
+const DEFAULT_MINOR: u32 = 0xfff;
+/// Primera clase configurada; las siguientes van correlativas
+const FIRST_MINOR: u32 = 0x10;
+const DEFAULT_LINK_MBPS: u64 = 1000;
+/// Objetivo de latencia más alto que admite la configuración
+pub const MAX_LATENCY_TARGET_MS: f64 = 60_000.0;
+
+/// Sección `[nano_cores.network_core.qos]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct QosConfig {
+    /// Aplicar el shaping al arrancar y al recargar; si no, solo con `OptimizeQoS`
+    pub shaping: bool,
+    /// Interfaz a moldear; por defecto la de la ruta por defecto
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// Capacidad del enlace; por defecto la velocidad que informa la interfaz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_mbps: Option<u64>,
+    pub traffic_classes: Vec<TrafficClassConfig>,
+}
+
+impl Default for QosConfig {
+    fn default() -> Self {
+        Self {
+            shaping: false,
+            interface: None,
+            link_mbps: None,
+            traffic_classes: vec![
+                TrafficClassConfig {
+                    name: "Critical".to_string(),
+                    priority: 1,
+                    bandwidth_guarantee: 1024 * 1024 * 10, // 10 MB/s
+                    max_bandwidth: 1024 * 1024 * 50,       // 50 MB/s
+                    latency_target_ms: 1.0,
+                    packet_filters: vec![],
+                },
+                TrafficClassConfig {
+                    name: "High".to_string(),
+                    priority: 2,
+                    bandwidth_guarantee: 1024 * 1024 * 5, // 5 MB/s
+                    max_bandwidth: 1024 * 1024 * 25,      // 25 MB/s
+                    latency_target_ms: 10.0,
+                    packet_filters: vec![],
+                },
+            ],
+        }
+    }
+}
+
+/// `TrafficClass` tal como se escribe en la configuración
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TrafficClassConfig {
+    pub name: String,
+    /// 1 es la más prioritaria
+    pub priority: u8,
+    /// Bytes/s garantizados
+    pub bandwidth_guarantee: u64,
+    /// Bytes/s como máximo, tomando prestado de otras clases
+    pub max_bandwidth: u64,
+    pub latency_target_ms: f64,
+    #[serde(default)]
+    pub packet_filters: Vec<PacketFilter>,
+}
+
+impl From<&TrafficClassConfig> for TrafficClass {
+    fn from(config: &TrafficClassConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            priority: config.priority,
+            bandwidth_guarantee: config.bandwidth_guarantee,
+            max_bandwidth: config.max_bandwidth,
+            // La validación rechaza los objetivos fuera de rango; aquí solo se evita el pánico
+            latency_target: Duration::try_from_secs_f64(config.latency_target_ms.clamp(0.0, MAX_LATENCY_TARGET_MS) / 1000.0)
+                .unwrap_or_default(),
+            packet_filters: config.packet_filters.clone(),
+        }
+    }
+}
+
+/// Jerarquía tc calculada para una interfaz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShapingPlan {
+    pub interface: String,
+    pub link_mbps: u64,
+    /// Nombre de clase por handle HTB (`1:10`)
+    pub classes: HashMap<String, String>,
+    /// Líneas para `tc -batch`
+    pub commands: Vec<String>,
+}
+
+/// Estadísticas de una clase HTB
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrafficClassStats {
+    pub interface: String,
+    pub class: String,
+    pub handle: String,
+    pub bytes: u64,
+    pub packets: u64,
+    pub drops: u64,
+    pub overlimits: u64,
+    pub backlog_bytes: u64,
+    /// Bits/s desde la lectura anterior
+    pub rate_bps: f64,
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    NanoCoreError::ConfigError(anyhow!(message)).into()
+}
+
+/// Interfaz y capacidad a moldear según la configuración y el sistema
+pub fn resolve_link(config: &QosConfig) -> Result<(String, u64)> {
+    let interface = match &config.interface {
+        Some(interface) => interface.clone(),
+        None => netinfo::routes()?
+            .into_iter()
+            .find(|route| route.is_default)
+            .map(|route| route.interface)
+            .ok_or_else(|| anyhow!("Sin ruta por defecto: indique qos.interface"))?,
+    };
+    let link_mbps = match config.link_mbps {
+        Some(mbps) => mbps,
+        None => netinfo::interfaces()?
+            .into_iter()
+            .find(|i| i.name == interface)
+            .and_then(|i| i.speed)
+            .filter(|speed| *speed > 0)
+            .unwrap_or(DEFAULT_LINK_MBPS),
+    };
+    Ok((interface, link_mbps))
+}
+
+/// Objetivo y periodo de fq_codel: el objetivo de latencia acotado a lo que codel
+/// puede sostener y un intervalo de 20 veces el objetivo, dentro de 10-100 ms
+fn codel_params(latency_target: Duration) -> (u64, u64) {
+    let target = (latency_target.as_micros() as u64).clamp(500, 5_000);
+    (target, (target * 20).clamp(10_000, 100_000))
+}
+
+/// Filtros flower de una clase; sin familia se generan para IPv4 e IPv6
+fn filter_commands(interface: &str, minor: u32, priority: u8, filter: &PacketFilter) -> Result<Vec<String>> {
+    let (transports, default_port): (Vec<&str>, Option<u16>) = match filter.protocol {
+        None if filter.source_port.is_some() || filter.destination_port.is_some() => (vec!["tcp", "udp"], None),
+        None => (vec![], None),
+        Some(Protocol::TCP | Protocol::GRPC | Protocol::WebSocket) => (vec!["tcp"], None),
+        Some(Protocol::HTTP) => (vec!["tcp"], Some(80)),
+        Some(Protocol::HTTPS) => (vec!["tcp"], Some(443)),
+        Some(Protocol::UDP) => (vec!["udp"], None),
+        Some(Protocol::ICMP) => (vec!["icmp"], None),
+    };
+    if transports == ["icmp"] && (filter.source_port.is_some() || filter.destination_port.is_some()) {
+        return Err(invalid("Un filtro ICMP no puede tener puertos".to_string()));
+    }
+
+    let family = |ip: &IpAddr| ip.is_ipv4();
+    let families: Vec<bool> = match (filter.source_ip.as_ref().map(family), filter.destination_ip.as_ref().map(family)) {
+        (Some(a), Some(b)) if a != b => return Err(invalid("Filtro con origen y destino de familias distintas".to_string())),
+        (Some(v4), _) | (None, Some(v4)) => vec![v4],
+        (None, None) => vec![true, false],
+    };
+    let destination_port = filter.destination_port.or(default_port);
+
+    let mut commands = Vec::new();
+    for v4 in families {
+        let (protocol, icmp) = if v4 { ("ip", "icmp") } else { ("ipv6", "icmpv6") };
+        // Cada prioridad de filtro admite un solo protocolo de capa 3
+        let prio = u32::from(priority) * 2 + u32::from(!v4) + 1;
+        let protocols: Vec<Option<&str>> = if transports.is_empty() {
+            vec![None]
+        } else {
+            transports.iter().map(|t| Some(if *t == "icmp" { icmp } else { t })).collect()
+        };
+        for ip_proto in protocols {
+            let mut line = format!("filter add dev {} parent 1: protocol {} prio {} flower", interface, protocol, prio);
+            if let Some(ip_proto) = ip_proto {
+                line.push_str(&format!(" ip_proto {}", ip_proto));
+            }
+            if let Some(ip) = filter.source_ip {
+                line.push_str(&format!(" src_ip {}", ip));
+            }
+            if let Some(ip) = filter.destination_ip {
+                line.push_str(&format!(" dst_ip {}", ip));
+            }
+            if let Some(port) = filter.source_port {
+                line.push_str(&format!(" src_port {}", port));
+            }
+            if let Some(port) = destination_port {
+                line.push_str(&format!(" dst_port {}", port));
+            }
+            line.push_str(&format!(" classid 1:{:x}", minor));
+            commands.push(line);
+        }
+    }
+    Ok(commands)
+}
+
+/// Calcular la jerarquía HTB/fq_codel de `classes` sobre un enlace de `link_mbps`
+pub fn plan(interface: &str, link_mbps: u64, classes: &[TrafficClass]) -> Result<ShapingPlan> {
+    let link = link_mbps.max(1) * 1_000_000;
+    let guaranteed: u64 = classes.iter().map(|class| class.bandwidth_guarantee * 8).sum();
+    if guaranteed > link {
+        return Err(invalid(format!(
+            "Las garantías de las clases ({} Mbit/s) superan el enlace de {} ({} Mbit/s)",
+            guaranteed / 1_000_000,
+            interface,
+            link_mbps
+        )));
+    }
+
+    let mut commands = vec![
+        format!("qdisc add dev {} root handle 1: htb default {:x}", interface, DEFAULT_MINOR),
+        format!("class add dev {} parent 1: classid 1:1 htb rate {}bit ceil {}bit", interface, link, link),
+    ];
+    let mut names = HashMap::new();
+    for (i, class) in classes.iter().enumerate() {
+        let minor = FIRST_MINOR + i as u32;
+        let rate = (class.bandwidth_guarantee * 8).max(8_000);
+        let ceil = (class.max_bandwidth * 8).clamp(rate, link);
+        let (target, interval) = codel_params(class.latency_target);
+        commands.push(format!(
+            "class add dev {} parent 1:1 classid 1:{:x} htb rate {}bit ceil {}bit prio {}",
+            interface,
+            minor,
+            rate,
+            ceil,
+            class.priority.saturating_sub(1).min(7)
+        ));
+        commands.push(format!(
+            "qdisc add dev {} parent 1:{:x} handle {:x}: fq_codel target {}us interval {}us",
+            interface, minor, minor, target, interval
+        ));
+        for filter in &class.packet_filters {
+            commands.extend(filter_commands(interface, minor, class.priority, filter)?);
+        }
+        names.insert(format!("1:{:x}", minor), class.name.clone());
+    }
+
+    // Lo no garantizado queda para el tráfico sin clasificar, que puede usar todo el enlace
+    let rest = (link - guaranteed).max(link / 100);
+    commands.push(format!(
+        "class add dev {} parent 1:1 classid 1:{:x} htb rate {}bit ceil {}bit prio 7",
+        interface, DEFAULT_MINOR, rest, link
+    ));
+    commands.push(format!("qdisc add dev {} parent 1:{:x} handle {:x}: fq_codel", interface, DEFAULT_MINOR, DEFAULT_MINOR));
+    names.insert(format!("1:{:x}", DEFAULT_MINOR), "default".to_string());
+
+    Ok(ShapingPlan {
+        interface: interface.to_string(),
+        link_mbps,
+        classes: names,
+        commands,
+    })
+}
+
+/// Contadores de una clase HTB en `tc -s class show`
+#[derive(Debug, Default, PartialEq)]
+struct ClassCounters {
+    bytes: u64,
+    packets: u64,
+    drops: u64,
+    overlimits: u64,
+    backlog_bytes: u64,
+}
+
+/// Clases HTB por handle de la salida de `tc -s class show dev <if>`
+fn parse_class_stats(output: &str) -> HashMap<String, ClassCounters> {
+    let number = |token: Option<&str>| -> u64 {
+        token
+            .map(|t| t.trim_matches(|c: char| !c.is_ascii_digit()))
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(0)
+    };
+    let mut classes = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in output.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["class", "htb", handle, ..] => {
+                current = Some(handle.to_string());
+                classes.insert(handle.to_string(), ClassCounters::default());
+            }
+            ["class", ..] => current = None,
+            // Sent 1234 bytes 12 pkt (dropped 0, overlimits 0 requeues 0)
+            ["Sent", bytes, "bytes", packets, "pkt", rest @ ..] => {
+                if let Some(counters) = current.as_ref().and_then(|handle| classes.get_mut(handle)) {
+                    counters.bytes = number(Some(bytes));
+                    counters.packets = number(Some(packets));
+                    let field = |name: &str| number(rest.iter().position(|t| t.trim_start_matches('(') == name).and_then(|i| rest.get(i + 1).copied()));
+                    counters.drops = field("dropped");
+                    counters.overlimits = field("overlimits");
+                }
+            }
+            ["backlog", bytes, ..] => {
+                if let Some(counters) = current.as_ref().and_then(|handle| classes.get_mut(handle)) {
+                    counters.backlog_bytes = number(Some(bytes));
+                }
+            }
+            _ => {}
+        }
+    }
+    classes
+}
+
+async fn tc(args: &[&str], stdin: Option<&str>) -> Result<String> {
+    if !cfg!(target_os = "linux") {
+        return Err(anyhow!("Traffic shaping solo disponible en Linux (tc)"));
+    }
+    let mut child = Command::new("tc")
+        .args(args)
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("No se pudo ejecutar tc: {}", e))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.contains("Operation not permitted") {
+        Err(NanoCoreError::SecurityDenied(anyhow!("tc sin privilegios: {}", stderr)).into())
+    } else {
+        Err(anyhow!("tc {} falló: {}", args.join(" "), stderr))
+    }
+}
+
+/// Sustituir la raíz de la interfaz por la jerarquía del plan
+pub async fn apply(plan: &ShapingPlan) -> Result<()> {
+    // Sin qdisc propio el borrado falla; da igual, se parte de cero
+    let _ = clear(&plan.interface).await;
+    tc(&["-batch", "-"], Some(&(plan.commands.join("\n") + "\n"))).await?;
+    Ok(())
+}
+
+/// Devolver la interfaz a su qdisc por defecto
+pub async fn clear(interface: &str) -> Result<()> {
+    tc(&["qdisc", "del", "dev", interface, "root"], None).await?;
+    Ok(())
+}
+
+/// Lecturas sucesivas de las clases de un plan para calcular su tasa
+#[derive(Debug, Default)]
+pub struct StatsSampler {
+    previous: HashMap<String, u64>,
+    at: Option<Instant>,
+}
+
+impl StatsSampler {
+    pub async fn sample(&mut self, plan: &ShapingPlan) -> Result<Vec<TrafficClassStats>> {
+        let output = tc(&["-s", "class", "show", "dev", &plan.interface], None).await?;
+        Ok(self.record(plan, parse_class_stats(&output), Instant::now()))
+    }
+
+    fn record(&mut self, plan: &ShapingPlan, counters: HashMap<String, ClassCounters>, now: Instant) -> Vec<TrafficClassStats> {
+        let elapsed = self.at.map(|at| now.duration_since(at).as_secs_f64()).filter(|secs| *secs > 0.0);
+        let mut stats: Vec<TrafficClassStats> = plan
+            .classes
+            .iter()
+            .filter_map(|(handle, name)| {
+                let counters = counters.get(handle)?;
+                let rate_bps = match (elapsed, self.previous.get(handle)) {
+                    (Some(secs), Some(previous)) => counters.bytes.saturating_sub(*previous) as f64 * 8.0 / secs,
+                    _ => 0.0,
+                };
+                Some(TrafficClassStats {
+                    interface: plan.interface.clone(),
+                    class: name.clone(),
+                    handle: handle.clone(),
+                    bytes: counters.bytes,
+                    packets: counters.packets,
+                    drops: counters.drops,
+                    overlimits: counters.overlimits,
+                    backlog_bytes: counters.backlog_bytes,
+                    rate_bps,
+                })
+            })
+            .collect();
+        stats.sort_by(|a, b| a.handle.cmp(&b.handle));
+        self.previous = stats.iter().map(|s| (s.handle.clone(), s.bytes)).collect();
+        self.at = Some(now);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(name: &str, priority: u8, guarantee_mbit: u64, max_mbit: u64, latency_ms: u64, filters: Vec<PacketFilter>) -> TrafficClass {
+        TrafficClass {
+            name: name.to_string(),
+            priority,
+            bandwidth_guarantee: guarantee_mbit * 1_000_000 / 8,
+            max_bandwidth: max_mbit * 1_000_000 / 8,
+            latency_target: Duration::from_millis(latency_ms),
+            packet_filters: filters,
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let ssh = PacketFilter {
+            protocol: Some(Protocol::TCP),
+            source_port: None,
+            destination_port: Some(22),
+            source_ip: None,
+            destination_ip: None,
+        };
+        let nats = PacketFilter {
+            protocol: None,
+            source_port: None,
+            destination_port: Some(4222),
+            source_ip: Some("10.0.0.1".parse().unwrap()),
+            destination_ip: None,
+        };
+        let classes = [class("Critical", 1, 100, 400, 1, vec![ssh]), class("High", 2, 50, 2000, 50, vec![nats])];
+        let plan = plan("eth0", 1000, &classes).unwrap();
+
+        assert_eq!(
+            plan.commands,
+            [
+                "qdisc add dev eth0 root handle 1: htb default fff",
+                "class add dev eth0 parent 1: classid 1:1 htb rate 1000000000bit ceil 1000000000bit",
+                "class add dev eth0 parent 1:1 classid 1:10 htb rate 100000000bit ceil 400000000bit prio 0",
+                "qdisc add dev eth0 parent 1:10 handle 10: fq_codel target 1000us interval 20000us",
+                "filter add dev eth0 parent 1: protocol ip prio 3 flower ip_proto tcp dst_port 22 classid 1:10",
+                "filter add dev eth0 parent 1: protocol ipv6 prio 4 flower ip_proto tcp dst_port 22 classid 1:10",
+                // El máximo se recorta al enlace y el objetivo de codel a 5 ms
+                "class add dev eth0 parent 1:1 classid 1:11 htb rate 50000000bit ceil 1000000000bit prio 1",
+                "qdisc add dev eth0 parent 1:11 handle 11: fq_codel target 5000us interval 100000us",
+                "filter add dev eth0 parent 1: protocol ip prio 5 flower ip_proto tcp src_ip 10.0.0.1 dst_port 4222 classid 1:11",
+                "filter add dev eth0 parent 1: protocol ip prio 5 flower ip_proto udp src_ip 10.0.0.1 dst_port 4222 classid 1:11",
+                "class add dev eth0 parent 1:1 classid 1:fff htb rate 850000000bit ceil 1000000000bit prio 7",
+                "qdisc add dev eth0 parent 1:fff handle fff: fq_codel",
+            ]
+        );
+        assert_eq!(plan.classes["1:11"], "High");
+        assert_eq!(plan.classes["1:fff"], "default");
+
+        // Garantías por encima del enlace
+        assert!(super::plan("eth0", 100, &classes).is_err());
+    }
+
+    #[test]
+    fn test_class_stats() {
+        let output = "class htb 1:1 root rate 1Gbit ceil 1Gbit burst 1375b cburst 1375b \n \
+                      Sent 5000 bytes 50 pkt (dropped 0, overlimits 0 requeues 0) \n \
+                      backlog 0b 0p requeues 0\n\
+                      class htb 1:10 parent 1:1 leaf 10: prio 0 rate 100Mbit ceil 400Mbit burst 1600b cburst 1600b \n \
+                      Sent 4000 bytes 40 pkt (dropped 3, overlimits 7 requeues 0) \n \
+                      backlog 1514b 1p requeues 0\n \
+                      lended: 40 borrowed: 0 giants: 0\n\
+                      class fq_codel 10:1 parent 10: \n \
+                      Sent 999 bytes 9 pkt (dropped 9, overlimits 9 requeues 0) \n";
+        let counters = parse_class_stats(output);
+        assert_eq!(
+            counters["1:10"],
+            ClassCounters {
+                bytes: 4000,
+                packets: 40,
+                drops: 3,
+                overlimits: 7,
+                backlog_bytes: 1514,
+            }
+        );
+        assert_eq!(counters.len(), 2);
+
+        let plan = plan("eth0", 1000, &[class("Critical", 1, 100, 400, 1, vec![])]).unwrap();
+        let mut sampler = StatsSampler::default();
+        let start = Instant::now();
+        let first = sampler.record(&plan, parse_class_stats(output), start);
+        assert_eq!((first.len(), first[0].class.as_str(), first[0].rate_bps), (1, "Critical", 0.0));
+
+        let later = output.replace("Sent 4000 bytes", "Sent 129000 bytes");
+        let second = sampler.record(&plan, parse_class_stats(&later), start + Duration::from_secs(1));
+        assert_eq!(second[0].rate_bps, 1_000_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_default_config_toml_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("core.toml");
+        let mut config = crate::config::CoreConfig::default();
+        config.save(&path).await.unwrap();
+        let loaded = crate::config::CoreConfig::read_file(&path).await.unwrap();
+        assert_eq!(loaded.nano_cores.network_core.qos, QosConfig::default());
+
+        // Filtros con campos sin fijar
+        config.nano_cores.network_core.qos.traffic_classes[0].packet_filters = vec![PacketFilter {
+            protocol: None,
+            source_port: None,
+            destination_port: Some(22),
+            source_ip: None,
+            destination_ip: None,
+        }];
+        config.save(&path).await.unwrap();
+        let loaded = crate::config::CoreConfig::read_file(&path).await.unwrap();
+        assert_eq!(loaded.nano_cores.network_core.qos, config.nano_cores.network_core.qos);
+
+        // Objetivos de latencia fuera de rango: error de validación, nunca pánico
+        config.nano_cores.network_core.qos.traffic_classes[0].latency_target_ms = f64::INFINITY;
+        let path = "nano_cores.network_core.qos.traffic_classes.0.latency_target_ms";
+        assert!(config.validation_report().errors().any(|issue| issue.path == path));
+        let class = &config.nano_cores.network_core.qos.traffic_classes[0];
+        assert_eq!(TrafficClass::from(class).latency_target, Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,246 @@
+//! Salud SMART de los discos para HardwareCore
+//!
+//! Se lee con `smartctl --json` (smartmontools 7+, requiere privilegios):
+//! sectores reasignados y pendientes, desgaste del SSD, temperatura y la
+//! autoevaluación del firmware. Las lecturas se asocian a los `DiskInfo` por
+//! dispositivo y se convierten en `FailurePrediction` por disco.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::hardware_core::{FailurePrediction, RiskLevel};
+
+/// Atributos ATA cuyo valor normalizado es la vida restante del SSD
+const ATA_LIFE_REMAINING: [u64; 4] = [177, 202, 231, 233];
+
+/// Lectura SMART de un dispositivo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmartHealth {
+    /// Dispositivo de `smartctl --scan`, p. ej. `/dev/sda` o `/dev/nvme0`
+    pub device: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    /// Autoevaluación global del firmware
+    pub passed: Option<bool>,
+    pub temperature: Option<f32>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    /// Errores de integridad de datos (NVMe)
+    pub media_errors: Option<u64>,
+    /// Vida útil consumida, en porcentaje
+    pub wear_level: Option<f32>,
+    /// Bits de `critical_warning` (NVMe)
+    pub critical_warning: Option<u8>,
+}
+
+impl SmartHealth {
+    /// Interpretar la salida de `smartctl -a --json`
+    pub fn from_smartctl(device: &str, report: &Value) -> Self {
+        let u64_at = |pointer: &str| report.pointer(pointer).and_then(Value::as_u64);
+        let attributes: Vec<&Value> = report
+            .pointer("/ata_smart_attributes/table")
+            .and_then(Value::as_array)
+            .map(|table| table.iter().collect())
+            .unwrap_or_default();
+        let attribute = |id: u64| attributes.iter().find(|a| a.get("id").and_then(Value::as_u64) == Some(id));
+        let raw = |id: u64| attribute(id).and_then(|a| a.pointer("/raw/value")).and_then(Value::as_u64);
+        let nvme = |field: &str| u64_at(&format!("/nvme_smart_health_information_log/{}", field));
+
+        let wear_level = nvme("percentage_used").map(|used| used as f32).or_else(|| {
+            ATA_LIFE_REMAINING
+                .iter()
+                .find_map(|id| attribute(*id).and_then(|a| a.get("value")).and_then(Value::as_u64))
+                .map(|remaining| 100.0 - remaining.min(100) as f32)
+        });
+
+        Self {
+            device: device.to_string(),
+            model: report.get("model_name").and_then(Value::as_str).map(str::to_string),
+            serial: report.get("serial_number").and_then(Value::as_str).map(str::to_string),
+            passed: report.pointer("/smart_status/passed").and_then(Value::as_bool),
+            temperature: u64_at("/temperature/current").or_else(|| nvme("temperature")).or_else(|| raw(194)).map(|t| (t & 0xff) as f32),
+            power_on_hours: u64_at("/power_on_time/hours").or_else(|| nvme("power_on_hours")),
+            reallocated_sectors: raw(5),
+            pending_sectors: raw(197),
+            media_errors: nvme("media_errors"),
+            wear_level,
+            critical_warning: nvme("critical_warning").map(|w| w as u8),
+        }
+    }
+
+    /// Si el dispositivo contiene la partición `disk_name` (`/dev/sda1`, `/dev/nvme0n1p2`)
+    pub fn covers(&self, disk_name: &str) -> bool {
+        disk_name.starts_with(&self.device)
+    }
+
+    /// Predicciones de fallo a partir de los atributos
+    pub fn predictions(&self) -> Vec<FailurePrediction> {
+        let component = format!("Disk SMART: {}", self.device);
+        let mut predictions = Vec::new();
+
+        if self.passed == Some(false) || self.critical_warning.is_some_and(|w| w != 0) {
+            predictions.push(FailurePrediction {
+                component: component.clone(),
+                risk_level: RiskLevel::Critical,
+                probability: 0.9,
+                time_to_failure: None,
+                recommended_actions: vec![
+                    "Hacer copia de seguridad inmediata".to_string(),
+                    "Reemplazar el disco".to_string(),
+                ],
+                confidence: 0.95,
+            });
+        }
+
+        let pending = self.pending_sectors.unwrap_or(0);
+        let bad_sectors = self.reallocated_sectors.unwrap_or(0) + pending + self.media_errors.unwrap_or(0);
+        if bad_sectors > 0 {
+            predictions.push(FailurePrediction {
+                component: component.clone(),
+                risk_level: if pending > 0 || bad_sectors >= 50 { RiskLevel::High } else { RiskLevel::Medium },
+                probability: (0.3 + bad_sectors as f32 / 200.0).min(0.85),
+                time_to_failure: None,
+                recommended_actions: vec![
+                    "Hacer copia de seguridad de los datos".to_string(),
+                    "Ejecutar un self-test largo (smartctl -t long)".to_string(),
+                    "Planificar el reemplazo del disco".to_string(),
+                ],
+                confidence: 0.8,
+            });
+        }
+
+        if let Some(wear) = self.wear_level.filter(|wear| *wear >= 80.0) {
+            predictions.push(FailurePrediction {
+                component: component.clone(),
+                risk_level: if wear >= 90.0 { RiskLevel::High } else { RiskLevel::Medium },
+                probability: (wear / 100.0).min(1.0),
+                time_to_failure: None,
+                recommended_actions: vec![
+                    "Reducir escrituras en el SSD".to_string(),
+                    "Planificar el reemplazo del disco".to_string(),
+                ],
+                confidence: 0.85,
+            });
+        }
+
+        if let Some(temp) = self.temperature.filter(|temp| *temp > 60.0) {
+            predictions.push(FailurePrediction {
+                component,
+                risk_level: RiskLevel::Medium,
+                probability: ((temp - 60.0) / 20.0).min(1.0),
+                time_to_failure: None,
+                recommended_actions: vec!["Mejorar la ventilación de los discos".to_string()],
+                confidence: 0.7,
+            });
+        }
+
+        predictions
+    }
+}
+
+/// Dispositivos con SMART según `smartctl --scan`
+pub async fn scan() -> Result<Vec<String>> {
+    let report = smartctl(&["--scan", "--json"]).await?;
+    Ok(report
+        .get("devices")
+        .and_then(Value::as_array)
+        .map(|devices| {
+            devices
+                .iter()
+                .filter_map(|device| device.get("name").and_then(Value::as_str).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Leer los atributos de un dispositivo
+pub async fn read(device: &str) -> Result<SmartHealth> {
+    let report = smartctl(&["-a", "--json", device]).await?;
+    Ok(SmartHealth::from_smartctl(device, &report))
+}
+
+/// Todos los dispositivos; los que fallan se omiten
+pub async fn collect() -> Result<Vec<SmartHealth>> {
+    let mut health = Vec::new();
+    for device in scan().await? {
+        match read(&device).await {
+            Ok(reading) => health.push(reading),
+            Err(e) => tracing::debug!("💿 SMART no disponible para {}: {}", device, e),
+        }
+    }
+    Ok(health)
+}
+
+async fn smartctl(args: &[&str]) -> Result<Value> {
+    let output = tokio::process::Command::new("smartctl").args(args).output().await?;
+    // Los bits 0 y 1 del código de salida son errores de línea de comandos o
+    // de apertura; el resto describe el estado del disco y el JSON es válido
+    let status = output.status.code().unwrap_or(1);
+    if status & 0b11 != 0 {
+        return Err(anyhow!(
+            "smartctl {} terminó con código {}: {}",
+            args.join(" "),
+            status,
+            String::from_utf8_lossy(&output.stdout).lines().last().unwrap_or_default()
+        ));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_smartctl_reports() {
+        let ata = json!({
+            "model_name": "Samsung SSD 860 EVO",
+            "serial_number": "S3Z9NB0K",
+            "smart_status": { "passed": true },
+            "temperature": { "current": 34 },
+            "power_on_time": { "hours": 12000 },
+            "ata_smart_attributes": { "table": [
+                { "id": 5, "name": "Reallocated_Sector_Ct", "value": 99, "raw": { "value": 12 } },
+                { "id": 177, "name": "Wear_Leveling_Count", "value": 8, "raw": { "value": 1900 } },
+                { "id": 197, "name": "Current_Pending_Sector", "value": 100, "raw": { "value": 0 } }
+            ]}
+        });
+        let health = SmartHealth::from_smartctl("/dev/sda", &ata);
+        assert_eq!(health.model.as_deref(), Some("Samsung SSD 860 EVO"));
+        assert_eq!(health.passed, Some(true));
+        assert_eq!(health.temperature, Some(34.0));
+        assert_eq!(health.reallocated_sectors, Some(12));
+        assert_eq!(health.pending_sectors, Some(0));
+        assert_eq!(health.wear_level, Some(92.0));
+        assert!(health.covers("/dev/sda1"));
+        assert!(!health.covers("/dev/sdb1"));
+
+        let predictions = health.predictions();
+        assert_eq!(predictions.len(), 2);
+        assert!(matches!(predictions[0].risk_level, RiskLevel::Medium));
+        assert!(matches!(predictions[1].risk_level, RiskLevel::High));
+
+        let nvme = json!({
+            "smart_status": { "passed": true },
+            "nvme_smart_health_information_log": {
+                "critical_warning": 0, "temperature": 41, "percentage_used": 3,
+                "power_on_hours": 800, "media_errors": 0
+            }
+        });
+        let health = SmartHealth::from_smartctl("/dev/nvme0", &nvme);
+        assert_eq!(health.temperature, Some(41.0));
+        assert_eq!(health.wear_level, Some(3.0));
+        assert_eq!(health.power_on_hours, Some(800));
+        assert!(health.covers("/dev/nvme0n1p2"));
+        assert!(health.predictions().is_empty());
+
+        let failing = SmartHealth {
+            passed: Some(false),
+            ..health
+        };
+        assert!(matches!(failing.predictions()[0].risk_level, RiskLevel::Critical));
+    }
+}
@@ -0,0 +1,107 @@
+//! Orden de arranque de los nano-núcleos
+//!
+//! `[nano_cores.startup.dependencies]` declara de qué núcleos depende cada
+//! sección (`network_core = ["security_core"]`). `initialize_all_cores`
+//! arranca en orden topológico, espera a que las réplicas de cada
+//! dependencia estén `Running` antes de arrancar a sus dependientes y el
+//! shutdown recorre el orden inverso.
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::NanoCoreType;
+
+/// Sección `[nano_cores.startup]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Secciones de las que depende cada sección
+    pub dependencies: BTreeMap<String, Vec<String>>,
+    /// Espera máxima a que las réplicas de una dependencia estén `Running`, en ms
+    pub readiness_timeout_ms: u64,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            dependencies: BTreeMap::from([("network_core".to_string(), vec!["security_core".to_string()])]),
+            readiness_timeout_ms: 30_000,
+        }
+    }
+}
+
+impl StartupConfig {
+    pub fn readiness_timeout(&self) -> Duration {
+        Duration::from_millis(self.readiness_timeout_ms)
+    }
+
+    /// Dependencias declaradas de `core_type`
+    pub fn dependencies_of(&self, core_type: &NanoCoreType) -> Vec<NanoCoreType> {
+        self.dependencies
+            .get(core_type.config_section())
+            .map(|sections| sections.iter().map(|section| NanoCoreType::from_section(section)).collect())
+            .unwrap_or_default()
+    }
+
+    /// `core_types` con cada núcleo detrás de sus dependencias
+    ///
+    /// Entre núcleos independientes se conserva el orden recibido. Falla con
+    /// dependencias que no están en `core_types` o con ciclos.
+    pub fn startup_order(&self, core_types: &[NanoCoreType]) -> Result<Vec<NanoCoreType>> {
+        for core_type in core_types {
+            if let Some(missing) = self.dependencies_of(core_type).into_iter().find(|dep| !core_types.contains(dep)) {
+                return Err(anyhow!("{} depende de {}, que no está registrado", core_type, missing));
+            }
+        }
+
+        let mut pending = core_types.to_vec();
+        let mut order: Vec<NanoCoreType> = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|core_type| self.dependencies_of(core_type).iter().all(|dep| order.contains(dep)))
+                .ok_or_else(|| {
+                    let cycle: Vec<String> = pending.iter().map(ToString::to_string).collect();
+                    anyhow!("Dependencias circulares entre nano-núcleos: {}", cycle.join(", "))
+                })?;
+            order.push(pending.remove(ready));
+        }
+
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_order() {
+        let config = StartupConfig::default();
+        assert_eq!(
+            config.startup_order(&NanoCoreType::BUILTIN).unwrap(),
+            [NanoCoreType::OS, NanoCoreType::Hardware, NanoCoreType::Security, NanoCoreType::Network]
+        );
+
+        // Un externo detrás de un integrado y otro externo
+        let gpu = NanoCoreType::Custom("gpu_core".to_string());
+        let telemetry = NanoCoreType::Custom("telemetry".to_string());
+        let mut config = StartupConfig::default();
+        config.dependencies.insert("telemetry".to_string(), vec!["gpu_core".to_string(), "network_core".to_string()]);
+        config.dependencies.insert("gpu_core".to_string(), vec!["hardware_core".to_string()]);
+        let types: Vec<NanoCoreType> = [telemetry.clone(), gpu.clone()].into_iter().chain(NanoCoreType::BUILTIN).collect();
+        let order = config.startup_order(&types).unwrap();
+        let position = |core_type: &NanoCoreType| order.iter().position(|t| t == core_type).unwrap();
+        assert!(position(&NanoCoreType::Hardware) < position(&gpu));
+        assert!(position(&gpu) < position(&telemetry));
+        assert!(position(&NanoCoreType::Network) < position(&telemetry));
+
+        // Dependencias sin registrar y ciclos
+        assert!(config.startup_order(&NanoCoreType::BUILTIN.into_iter().chain([telemetry]).collect::<Vec<_>>()).is_err());
+        config.dependencies.insert("security_core".to_string(), vec!["network_core".to_string()]);
+        assert!(config.startup_order(&NanoCoreType::BUILTIN).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! Supervisión de los bucles de nano-núcleos
+//!
+//! Cada instancia tiene una política de reinicio (`always`, `on_failure`,
+//! `exponential_backoff` o `never`) que decide cuánto esperar tras un fallo
+//! de `run()` y cuándo dejar de intentarlo. Demasiados fallos en poco tiempo
+//! se consideran un crash loop y la instancia pasa a cuarentena. La clase de
+//! `NanoCoreError` decide si el fallo cuenta, si se reemplaza la instancia o
+//! si se detiene sin reintentar.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::{NanoCoreError, NanoCoreType};
+use crate::util::Backoff;
+
+/// Cuándo se reinicia un nano-núcleo cuyo `run()` falla
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    /// Siempre, tras `delay_ms`
+    Always,
+    /// Tras `delay_ms`, hasta `max_retries` fallos seguidos
+    OnFailure,
+    /// Con esperas que se duplican desde `delay_ms` hasta `max_delay_ms`
+    ExponentialBackoff,
+    /// Nunca: el primer fallo detiene la instancia
+    Never,
+}
+
+/// Política de reinicio de un nano-núcleo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Fallos seguidos tolerados por `on_failure` y `exponential_backoff` (0 = sin límite)
+    pub max_retries: u32,
+    /// Espera tras un fallo, en ms
+    pub delay_ms: u64,
+    /// Espera máxima del backoff exponencial, en ms
+    pub max_delay_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::ExponentialBackoff,
+            max_retries: 0,
+            delay_ms: 500,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+/// Sección `[nano_cores.supervisor]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SupervisorConfig {
+    /// Política de los núcleos sin una propia
+    pub restart_policy: RestartPolicy,
+    /// Políticas por sección (`[nano_cores.supervisor.policies.network_core]`)
+    pub policies: BTreeMap<String, RestartPolicy>,
+    /// Fallos dentro de la ventana que ponen la instancia en cuarentena (0 = nunca)
+    pub crash_loop_max_failures: u32,
+    /// Ventana de detección de crash loops, en segundos
+    pub crash_loop_window_secs: u64,
+    /// Espera máxima de `NanoCoreManager::drain` a los comandos en curso, en ms
+    pub drain_timeout_ms: u64,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            restart_policy: RestartPolicy::default(),
+            policies: BTreeMap::new(),
+            crash_loop_max_failures: 5,
+            crash_loop_window_secs: 60,
+            drain_timeout_ms: 30_000,
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Política efectiva de un tipo de nano-núcleo
+    pub fn policy_for(&self, core_type: &NanoCoreType) -> &RestartPolicy {
+        self.policies.get(core_type.config_section()).unwrap_or(&self.restart_policy)
+    }
+
+    pub fn drain_timeout(&self) -> Duration {
+        Duration::from_millis(self.drain_timeout_ms)
+    }
+}
+
+/// Qué hacer tras un fallo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisorDecision {
+    /// Volver a ejecutar tras la espera
+    Restart(Duration),
+    /// Reemplazar la instancia (hot-swap) y volver a ejecutar tras la espera
+    Replace(Duration),
+    /// Sobrecarga: esperar sin contarlo como fallo
+    Wait(Duration),
+    /// Política agotada: detener la instancia
+    Stop,
+    /// Crash loop: detener la instancia y sacarla del consenso
+    Quarantine,
+}
+
+/// Historial de fallos de una instancia
+#[derive(Debug, Default)]
+pub struct Supervisor {
+    failures: VecDeque<Instant>,
+    consecutive: u32,
+}
+
+impl Supervisor {
+    pub fn record_success(&mut self) {
+        self.consecutive = 0;
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive
+    }
+
+    /// Decidir según la clase de `error`
+    ///
+    /// Los transitorios se reemplazan cada `failure_threshold` fallos
+    /// seguidos; los fatales en cuanto la política permite reintentar.
+    pub fn record_error(
+        &mut self,
+        error: &NanoCoreError,
+        policy: &RestartPolicy,
+        config: &SupervisorConfig,
+        failure_threshold: u32,
+        now: Instant,
+    ) -> SupervisorDecision {
+        match error {
+            NanoCoreError::Backpressure(_) => return SupervisorDecision::Wait(Duration::from_millis(policy.delay_ms)),
+            NanoCoreError::SecurityDenied(_) => {
+                self.consecutive += 1;
+                return SupervisorDecision::Stop;
+            }
+            _ => {}
+        }
+
+        let delay = match self.record_failure(policy, config, now) {
+            SupervisorDecision::Restart(delay) => delay,
+            decision => return decision,
+        };
+        match error {
+            NanoCoreError::Fatal(_) => SupervisorDecision::Replace(delay),
+            NanoCoreError::Transient(_) if self.consecutive % failure_threshold.max(1) == 0 => SupervisorDecision::Replace(delay),
+            _ => SupervisorDecision::Restart(delay),
+        }
+    }
+
+    /// Registrar un fallo y decidir según `policy` y la detección de crash loops
+    pub fn record_failure(&mut self, policy: &RestartPolicy, config: &SupervisorConfig, now: Instant) -> SupervisorDecision {
+        self.consecutive += 1;
+        self.failures.push_back(now);
+        let window = Duration::from_secs(config.crash_loop_window_secs);
+        while self.failures.front().is_some_and(|at| now.duration_since(*at) > window) {
+            self.failures.pop_front();
+        }
+
+        if config.crash_loop_max_failures > 0 && self.failures.len() >= config.crash_loop_max_failures as usize {
+            return SupervisorDecision::Quarantine;
+        }
+
+        let exhausted = policy.max_retries > 0 && self.consecutive > policy.max_retries;
+        let delay = Duration::from_millis(policy.delay_ms);
+        match policy.mode {
+            RestartMode::Never => SupervisorDecision::Stop,
+            RestartMode::Always => SupervisorDecision::Restart(delay),
+            RestartMode::OnFailure | RestartMode::ExponentialBackoff if exhausted => SupervisorDecision::Stop,
+            RestartMode::OnFailure => SupervisorDecision::Restart(delay),
+            RestartMode::ExponentialBackoff => {
+                let backoff = Backoff::new(delay).with_max(Duration::from_millis(policy.max_delay_ms));
+                SupervisorDecision::Restart(backoff.ceiling(self.consecutive - 1))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_and_crash_loop() {
+        let config = SupervisorConfig {
+            crash_loop_max_failures: 4,
+            crash_loop_window_secs: 10,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let after = |secs: u64| start + Duration::from_secs(secs);
+
+        // Backoff exponencial acotado; fallos espaciados no son un crash loop
+        let backoff = RestartPolicy { max_delay_ms: 1500, ..Default::default() };
+        let mut supervisor = Supervisor::default();
+        let delays: Vec<_> = (0..4).map(|i| supervisor.record_failure(&backoff, &config, after(i * 20))).collect();
+        assert_eq!(delays, [500, 1000, 1500, 1500].map(|ms| SupervisorDecision::Restart(Duration::from_millis(ms))));
+        supervisor.record_success();
+        assert_eq!(supervisor.consecutive_failures(), 0);
+
+        let on_failure = RestartPolicy { mode: RestartMode::OnFailure, max_retries: 2, ..Default::default() };
+        let mut supervisor = Supervisor::default();
+        assert!(matches!(supervisor.record_failure(&on_failure, &config, after(0)), SupervisorDecision::Restart(_)));
+        assert!(matches!(supervisor.record_failure(&on_failure, &config, after(20)), SupervisorDecision::Restart(_)));
+        assert_eq!(supervisor.record_failure(&on_failure, &config, after(40)), SupervisorDecision::Stop);
+
+        let never = RestartPolicy { mode: RestartMode::Never, ..Default::default() };
+        assert_eq!(Supervisor::default().record_failure(&never, &config, start), SupervisorDecision::Stop);
+
+        // Cuatro fallos en la ventana de 10s escalan a cuarentena incluso con `always`
+        let always = RestartPolicy { mode: RestartMode::Always, ..Default::default() };
+        let mut supervisor = Supervisor::default();
+        for i in 0..3 {
+            assert!(matches!(supervisor.record_failure(&always, &config, after(i)), SupervisorDecision::Restart(_)));
+        }
+        assert_eq!(supervisor.record_failure(&always, &config, after(3)), SupervisorDecision::Quarantine);
+
+        let policies = SupervisorConfig {
+            policies: BTreeMap::from([("network_core".to_string(), never.clone())]),
+            ..Default::default()
+        };
+        assert_eq!(policies.policy_for(&NanoCoreType::Network), &never);
+        assert_eq!(policies.policy_for(&NanoCoreType::OS), &RestartPolicy::default());
+    }
+
+    #[test]
+    fn test_error_classes() {
+        let config = SupervisorConfig::default();
+        let policy = RestartPolicy { mode: RestartMode::Always, ..Default::default() };
+        let delay = Duration::from_millis(policy.delay_ms);
+        let start = Instant::now();
+        let after = |secs: u64| start + Duration::from_secs(secs * 20);
+        let error = |kind: fn(anyhow::Error) -> NanoCoreError| kind(anyhow::anyhow!("fallo"));
+
+        // Transitorios: reemplazo cada `failure_threshold` fallos seguidos
+        let mut supervisor = Supervisor::default();
+        let decisions: Vec<_> = (0..3)
+            .map(|i| supervisor.record_error(&error(NanoCoreError::Transient), &policy, &config, 3, after(i)))
+            .collect();
+        assert_eq!(decisions, [SupervisorDecision::Restart(delay), SupervisorDecision::Restart(delay), SupervisorDecision::Replace(delay)]);
+
+        // La sobrecarga no cuenta como fallo
+        let mut supervisor = Supervisor::default();
+        assert_eq!(supervisor.record_error(&error(NanoCoreError::Backpressure), &policy, &config, 3, start), SupervisorDecision::Wait(delay));
+        assert_eq!(supervisor.consecutive_failures(), 0);
+
+        let mut supervisor = Supervisor::default();
+        assert_eq!(supervisor.record_error(&error(NanoCoreError::Fatal), &policy, &config, 3, start), SupervisorDecision::Replace(delay));
+        assert_eq!(supervisor.record_error(&error(NanoCoreError::ConfigError), &policy, &config, 1, after(1)), SupervisorDecision::Restart(delay));
+        assert_eq!(supervisor.record_error(&error(NanoCoreError::SecurityDenied), &policy, &config, 3, after(2)), SupervisorDecision::Stop);
+    }
+}
@@ -0,0 +1,147 @@
+//! Comandos retenidos durante un hot-swap
+//!
+//! Mientras se reemplaza una instancia, `execute_command` deja sus comandos
+//! en la cola en vez de ejecutarlos en la instancia saliente. Al terminar el
+//! reemplazo se ejecutan en orden en la instancia que queda en el slot, la
+//! nueva o la anterior si el consenso lo rechazó.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use tokio::sync::{oneshot, RwLock};
+
+use super::{CoreSlot, NanoCoreType};
+
+/// Comando en espera mientras se reemplaza su instancia
+struct PendingCommand {
+    command: String,
+    payload: Vec<u8>,
+    reply: oneshot::Sender<Result<Vec<u8>>>,
+}
+
+/// Instancias en reemplazo y los comandos recibidos mientras tanto
+#[derive(Default)]
+pub struct SwapQueue {
+    pending: RwLock<HashMap<(NanoCoreType, usize), Vec<PendingCommand>>>,
+}
+
+impl SwapQueue {
+    /// Empezar a retener los comandos de `key`
+    pub async fn begin(&self, key: (NanoCoreType, usize)) {
+        self.pending.write().await.insert(key, Vec::new());
+    }
+
+    /// Retener el comando si `key` está en reemplazo; la respuesta llega con `finish`
+    pub async fn enqueue(&self, key: &(NanoCoreType, usize), command: &str, payload: &[u8]) -> Option<oneshot::Receiver<Result<Vec<u8>>>> {
+        self.pending.write().await.get_mut(key).map(|pending| {
+            let (reply, response) = oneshot::channel();
+            pending.push(PendingCommand {
+                command: command.to_string(),
+                payload: payload.to_vec(),
+                reply,
+            });
+            response
+        })
+    }
+
+    /// Dejar de retener los comandos de `key` y ejecutar los retenidos en `slot`
+    pub async fn finish(&self, key: &(NanoCoreType, usize), slot: Result<CoreSlot>) -> usize {
+        let pending = self.pending.write().await.remove(key).unwrap_or_default();
+        let count = pending.len();
+        for pending in pending {
+            let response = match &slot {
+                Ok(slot) => slot.lock().await.process_command(&pending.command, &pending.payload).await.map_err(Into::into),
+                Err(e) => Err(anyhow!("{}", e)),
+            };
+            let _ = pending.reply.send(response);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nano_cores::{CommandAuthorization, CoreResult, NanoCore, NanoCoreHealth, NanoCoreState};
+    use crate::security::SecurityLevel;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Nano-núcleo que responde a cada comando con su `instance_id`
+    struct TaggedCore(Uuid);
+
+    #[async_trait]
+    impl NanoCore for TaggedCore {
+        fn core_type(&self) -> NanoCoreType {
+            NanoCoreType::OS
+        }
+
+        fn instance_id(&self) -> Uuid {
+            self.0
+        }
+
+        async fn initialize(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn run(&mut self) -> CoreResult<()> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<NanoCoreHealth> {
+            Ok(NanoCoreHealth {
+                core_type: self.core_type(),
+                instance_id: self.0,
+                state: NanoCoreState::Running,
+                cpu_usage: 0.0,
+                memory_usage: 0.0,
+                last_heartbeat: chrono::Utc::now(),
+                error_count: 0,
+                uptime_seconds: 0,
+            })
+        }
+
+        async fn shutdown(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn command_authorization(&self, _payload: &[u8]) -> Result<CommandAuthorization> {
+            Ok(CommandAuthorization::new("tagged", SecurityLevel::Public))
+        }
+
+        async fn process_command(&mut self, _command: &str, _payload: &[u8]) -> CoreResult<Vec<u8>> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commands_queued_during_swap_run_on_new_instance() {
+        let key = (NanoCoreType::OS, 0);
+        let (old_id, new_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let core: Box<dyn NanoCore> = Box::new(TaggedCore(old_id));
+        let slot: CoreSlot = Arc::new(Mutex::new(core));
+        let swaps = SwapQueue::default();
+
+        // Fuera de un reemplazo no se retiene nada
+        assert!(swaps.enqueue(&key, "GetSystemResources", b"{}").await.is_none());
+
+        swaps.begin(key.clone()).await;
+        let first = swaps.enqueue(&key, "GetSystemResources", b"{}").await.unwrap();
+        let second = swaps.enqueue(&key, "GetSystemResources", b"{}").await.unwrap();
+        assert!(swaps.enqueue(&(NanoCoreType::OS, 1), "GetSystemResources", b"{}").await.is_none());
+
+        // `replace_instance` cambia la instancia dentro del mismo slot
+        *slot.lock().await = Box::new(TaggedCore(new_id));
+        assert_eq!(swaps.finish(&key, Ok(slot)).await, 2);
+        assert_eq!(first.await.unwrap().unwrap(), new_id.as_bytes());
+        assert_eq!(second.await.unwrap().unwrap(), new_id.as_bytes());
+        assert!(swaps.enqueue(&key, "GetSystemResources", b"{}").await.is_none());
+
+        // Si la instancia desaparece, los retenidos reciben el error
+        swaps.begin(key.clone()).await;
+        let orphan = swaps.enqueue(&key, "GetSystemResources", b"{}").await.unwrap();
+        swaps.finish(&key, Err(anyhow!("instancia no encontrada"))).await;
+        assert!(orphan.await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Sensores de temperatura y ventiladores para HardwareCore
+//!
+//! Cada plataforma tiene su `ThermalBackend`: hwmon en Linux, el SMC (vía
+//! `powermetrics`) en macOS y WMI de OpenHardwareMonitor/LibreHardwareMonitor
+//! o ACPI en Windows. Los componentes de sysinfo quedan como último recurso;
+//! si ningún backend da lecturas el estado térmico se calcula sin ellas.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sysinfo::{ComponentExt, System, SystemExt};
+use tracing::debug;
+
+/// Componente medido por un sensor de temperatura
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorKind {
+    Cpu,
+    Gpu,
+    Motherboard,
+    Disk,
+    Other,
+}
+
+impl SensorKind {
+    /// Clasificar por la etiqueta o el driver del sensor
+    pub fn classify(label: &str) -> Self {
+        let label = label.to_lowercase();
+        let any = |markers: &[&str]| markers.iter().any(|m| label.contains(m));
+        if any(&["cpu", "processor", "coretemp", "k10temp", "zenpower", "package", "tdie", "tctl"]) {
+            SensorKind::Cpu
+        } else if any(&["gpu", "graphics", "amdgpu", "nouveau", "radeon", "nvidia"]) {
+            SensorKind::Gpu
+        } else if any(&["nvme", "drivetemp", "disk", "ssd", "hdd"]) {
+            SensorKind::Disk
+        } else if any(&["motherboard", "system", "acpitz", "nct", "it87", "pch", "board"]) {
+            SensorKind::Motherboard
+        } else {
+            SensorKind::Other
+        }
+    }
+}
+
+/// Lectura de un sensor de temperatura
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureReading {
+    pub label: String,
+    pub kind: SensorKind,
+    pub celsius: f32,
+}
+
+/// Lectura de un ventilador
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanReading {
+    pub label: String,
+    pub rpm: u32,
+}
+
+/// Todo lo que devolvió un backend en una lectura
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalReadings {
+    pub temperatures: Vec<TemperatureReading>,
+    pub fans: Vec<FanReading>,
+}
+
+impl ThermalReadings {
+    pub fn is_empty(&self) -> bool {
+        self.temperatures.is_empty() && self.fans.is_empty()
+    }
+
+    /// Temperatura más alta de un tipo de componente
+    pub fn max(&self, kind: SensorKind) -> Option<f32> {
+        self.temperatures
+            .iter()
+            .filter(|t| t.kind == kind)
+            .map(|t| t.celsius)
+            .fold(None, |max, t| Some(max.map_or(t, |m: f32| m.max(t))))
+    }
+}
+
+/// Fuente de lecturas térmicas de una plataforma
+#[async_trait]
+pub trait ThermalBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Leer sensores; `system` ya viene refrescado por HardwareCore
+    async fn read(&self, system: &System) -> Result<ThermalReadings>;
+}
+
+/// Backends de la plataforma actual, del más preciso a sysinfo
+pub fn platform_backends() -> Vec<Box<dyn ThermalBackend>> {
+    vec![
+        #[cfg(target_os = "linux")]
+        Box::new(HwmonBackend::default()),
+        #[cfg(target_os = "macos")]
+        Box::new(SmcBackend),
+        #[cfg(windows)]
+        Box::new(WmiBackend),
+        Box::new(SysinfoBackend),
+    ]
+}
+
+/// Primera lectura no vacía de los backends, con el nombre del que la dio
+pub async fn read_first(backends: &[Box<dyn ThermalBackend>], system: &System) -> Option<(&'static str, ThermalReadings)> {
+    for backend in backends {
+        match backend.read(system).await {
+            Ok(readings) if !readings.is_empty() => return Some((backend.name(), readings)),
+            Ok(_) => debug!("🌡️  Backend térmico {} sin lecturas", backend.name()),
+            Err(e) => debug!("🌡️  Backend térmico {} no disponible: {}", backend.name(), e),
+        }
+    }
+    None
+}
+
+/// Componentes de sysinfo; no informa ventiladores
+pub struct SysinfoBackend;
+
+#[async_trait]
+impl ThermalBackend for SysinfoBackend {
+    fn name(&self) -> &'static str {
+        "sysinfo"
+    }
+
+    async fn read(&self, system: &System) -> Result<ThermalReadings> {
+        Ok(ThermalReadings {
+            temperatures: system
+                .components()
+                .iter()
+                .map(|component| TemperatureReading {
+                    label: component.label().to_string(),
+                    kind: SensorKind::classify(component.label()),
+                    celsius: component.temperature(),
+                })
+                .collect(),
+            fans: Vec::new(),
+        })
+    }
+}
+
+/// `/sys/class/hwmon/hwmon*/{temp,fan}*_input`
+#[cfg(target_os = "linux")]
+pub struct HwmonBackend {
+    root: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl Default for HwmonBackend {
+    fn default() -> Self {
+        Self {
+            root: std::path::PathBuf::from("/sys/class/hwmon"),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[async_trait]
+impl ThermalBackend for HwmonBackend {
+    fn name(&self) -> &'static str {
+        "hwmon"
+    }
+
+    async fn read(&self, _system: &System) -> Result<ThermalReadings> {
+        let root = self.root.clone();
+        Ok(tokio::task::spawn_blocking(move || read_hwmon(&root)).await??)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_hwmon(root: &std::path::Path) -> Result<ThermalReadings> {
+    use std::fs;
+
+    let read = |path: std::path::PathBuf| fs::read_to_string(path).ok().map(|v| v.trim().to_string());
+    let mut readings = ThermalReadings::default();
+
+    let mut chips: Vec<_> = fs::read_dir(root)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    chips.sort();
+    for chip in chips {
+        let driver = read(chip.join("name")).unwrap_or_default();
+        let mut files: Vec<_> = fs::read_dir(&chip)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.ends_with("_input"))
+            .collect();
+        files.sort();
+
+        for file in files {
+            let prefix = file.trim_end_matches("_input");
+            let Some(value) = read(chip.join(&file)).and_then(|v| v.parse::<i64>().ok()) else {
+                continue;
+            };
+            let label = read(chip.join(format!("{}_label", prefix))).unwrap_or_else(|| format!("{} {}", driver, prefix));
+
+            if prefix.starts_with("temp") {
+                // El tipo lo da el driver (coretemp, amdgpu, nvme...) antes que la etiqueta
+                let kind = match SensorKind::classify(&driver) {
+                    SensorKind::Other => SensorKind::classify(&label),
+                    kind => kind,
+                };
+                readings.temperatures.push(TemperatureReading {
+                    label,
+                    kind,
+                    celsius: value as f32 / 1000.0,
+                });
+            } else if prefix.starts_with("fan") {
+                readings.fans.push(FanReading {
+                    label,
+                    rpm: value.max(0) as u32,
+                });
+            }
+        }
+    }
+
+    Ok(readings)
+}
+
+/// Sensores del SMC con `powermetrics --samplers smc` (requiere root)
+#[cfg(target_os = "macos")]
+pub struct SmcBackend;
+
+#[cfg(target_os = "macos")]
+#[async_trait]
+impl ThermalBackend for SmcBackend {
+    fn name(&self) -> &'static str {
+        "smc"
+    }
+
+    async fn read(&self, _system: &System) -> Result<ThermalReadings> {
+        let output = tokio::process::Command::new("powermetrics")
+            .args(["--samplers", "smc", "-n", "1", "-i", "1"])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("powermetrics falló: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(parse_powermetrics(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// "CPU die temperature: 45.12 C" y "Fan: 1837.54 rpm"
+#[cfg(target_os = "macos")]
+fn parse_powermetrics(output: &str) -> ThermalReadings {
+    let mut readings = ThermalReadings::default();
+    for line in output.lines() {
+        let Some((label, value)) = line.split_once(':') else { continue };
+        let mut parts = value.split_whitespace();
+        let (Some(number), Some(unit)) = (parts.next().and_then(|n| n.parse::<f32>().ok()), parts.next()) else {
+            continue;
+        };
+        match unit {
+            "C" => readings.temperatures.push(TemperatureReading {
+                label: label.trim().to_string(),
+                kind: SensorKind::classify(label),
+                celsius: number,
+            }),
+            "rpm" => readings.fans.push(FanReading {
+                label: label.trim().to_string(),
+                rpm: number as u32,
+            }),
+            _ => {}
+        }
+    }
+    readings
+}
+
+/// WMI: OpenHardwareMonitor o LibreHardwareMonitor si están en ejecución,
+/// y si no la zona térmica ACPI
+#[cfg(windows)]
+pub struct WmiBackend;
+
+#[cfg(windows)]
+#[async_trait]
+impl ThermalBackend for WmiBackend {
+    fn name(&self) -> &'static str {
+        "wmi"
+    }
+
+    async fn read(&self, _system: &System) -> Result<ThermalReadings> {
+        Ok(tokio::task::spawn_blocking(read_wmi).await??)
+    }
+}
+
+#[cfg(windows)]
+fn read_wmi() -> Result<ThermalReadings> {
+    use wmi::{COMLibrary, WMIConnection};
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Sensor {
+        name: String,
+        sensor_type: String,
+        value: f32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct ThermalZone {
+        instance_name: String,
+        /// Décimas de kelvin
+        current_temperature: u32,
+    }
+
+    let com = COMLibrary::new()?;
+    let mut readings = ThermalReadings::default();
+
+    for namespace in ["root\\OpenHardwareMonitor", "root\\LibreHardwareMonitor"] {
+        let Ok(connection) = WMIConnection::with_namespace_path(namespace, com) else { continue };
+        let sensors: Vec<Sensor> = connection
+            .raw_query("SELECT Name, SensorType, Value FROM Sensor WHERE SensorType = 'Temperature' OR SensorType = 'Fan'")
+            .unwrap_or_default();
+        for sensor in sensors {
+            match sensor.sensor_type.as_str() {
+                "Temperature" => readings.temperatures.push(TemperatureReading {
+                    kind: SensorKind::classify(&sensor.name),
+                    label: sensor.name,
+                    celsius: sensor.value,
+                }),
+                _ => readings.fans.push(FanReading {
+                    label: sensor.name,
+                    rpm: sensor.value as u32,
+                }),
+            }
+        }
+        if !readings.is_empty() {
+            return Ok(readings);
+        }
+    }
+
+    let connection = WMIConnection::with_namespace_path("root\\WMI", com)?;
+    let zones: Vec<ThermalZone> =
+        connection.raw_query("SELECT InstanceName, CurrentTemperature FROM MSAcpi_ThermalZoneTemperature")?;
+    readings.temperatures = zones
+        .into_iter()
+        .map(|zone| TemperatureReading {
+            label: zone.instance_name,
+            kind: SensorKind::Motherboard,
+            celsius: zone.current_temperature as f32 / 10.0 - 273.15,
+        })
+        .collect();
+    Ok(readings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensor_classification() {
+        assert_eq!(SensorKind::classify("coretemp"), SensorKind::Cpu);
+        assert_eq!(SensorKind::classify("Package id 0"), SensorKind::Cpu);
+        assert_eq!(SensorKind::classify("amdgpu edge"), SensorKind::Gpu);
+        assert_eq!(SensorKind::classify("nvme Composite"), SensorKind::Disk);
+        assert_eq!(SensorKind::classify("acpitz"), SensorKind::Motherboard);
+        assert_eq!(SensorKind::classify("wifi"), SensorKind::Other);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_hwmon_readings() {
+        let root = tempfile::tempdir().unwrap();
+        let chip = root.path().join("hwmon0");
+        std::fs::create_dir_all(&chip).unwrap();
+        std::fs::write(chip.join("name"), "coretemp\n").unwrap();
+        std::fs::write(chip.join("temp1_input"), "52000\n").unwrap();
+        std::fs::write(chip.join("temp1_label"), "Core 0\n").unwrap();
+        std::fs::write(chip.join("temp2_input"), "61500\n").unwrap();
+        std::fs::write(chip.join("fan1_input"), "1450\n").unwrap();
+
+        let readings = read_hwmon(root.path()).unwrap();
+        assert_eq!(readings.temperatures.len(), 2);
+        assert_eq!(readings.temperatures[0].label, "Core 0");
+        assert_eq!(readings.max(SensorKind::Cpu), Some(61.5));
+        assert_eq!(readings.fans, vec![FanReading { label: "coretemp fan1".to_string(), rpm: 1450 }]);
+        assert_eq!(readings.max(SensorKind::Gpu), None);
+    }
+}
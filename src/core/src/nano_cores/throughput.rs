@@ -0,0 +1,437 @@
+//! Pruebas de throughput entre instancias de NetworkCore
+//!
+//! Un lado escucha (`[nano_cores.network_core.throughput] listen = true`) en
+//! TCP y UDP sobre el mismo puerto y el otro envía con `TestThroughput`. La
+//! conexión TCP lleva la petición y el informe final; en modo TCP lleva también
+//! los datos y en modo UDP los datagramas van numerados para medir pérdida y
+//! jitter (RFC 3550). El emisor limita su ritmo a `max_bandwidth_mbps` y la
+//! duración a `max_duration_secs`, y el receptor atiende una prueba a la vez.
+
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Sección `[nano_cores.network_core.throughput]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ThroughputConfig {
+    /// Atender pruebas de otras instancias
+    pub listen: bool,
+    pub listen_addr: String,
+    /// Ritmo máximo del emisor; las peticiones por encima se recortan
+    pub max_bandwidth_mbps: f64,
+    pub default_duration_secs: u64,
+    pub max_duration_secs: u64,
+}
+
+impl Default for ThroughputConfig {
+    fn default() -> Self {
+        Self {
+            listen: false,
+            listen_addr: "0.0.0.0:5201".to_string(),
+            max_bandwidth_mbps: 100.0,
+            default_duration_secs: 5,
+            max_duration_secs: 30,
+        }
+    }
+}
+
+/// Transporte de la prueba
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ThroughputProtocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// Resultado de una prueba, desde el punto de vista del emisor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThroughputResult {
+    pub target: SocketAddr,
+    pub protocol: ThroughputProtocol,
+    pub duration: Duration,
+    pub bytes_sent: u64,
+    /// Bytes de carga útil que llegaron al receptor
+    pub bytes_received: u64,
+    pub goodput_mbps: f64,
+    pub bandwidth_cap_mbps: f64,
+    /// Retransmisiones TCP del emisor (solo Linux)
+    pub retransmits: Option<u64>,
+    pub packets_sent: Option<u64>,
+    pub packets_lost: Option<u64>,
+    pub jitter_ms: Option<f64>,
+}
+
+/// Primera línea de la conexión de control
+#[derive(Debug, Serialize, Deserialize)]
+struct Hello {
+    protocol: ThroughputProtocol,
+    session: u64,
+    duration_ms: u64,
+}
+
+/// Final de una prueba UDP
+#[derive(Debug, Serialize, Deserialize)]
+struct Done {
+    packets: u64,
+}
+
+/// Lo que midió el receptor
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Report {
+    bytes: u64,
+    elapsed_ms: f64,
+    packets: u64,
+    jitter_ms: Option<f64>,
+    error: Option<String>,
+}
+
+const READY: &str = "ready";
+/// Cabecera de cada datagrama: sesión, secuencia y nanosegundos desde el inicio
+const DATAGRAM_HEADER: usize = 24;
+const DATAGRAM_SIZE: usize = 1200;
+/// Margen sobre la duración pedida antes de abandonar una prueba
+const GRACE: Duration = Duration::from_secs(10);
+
+/// Recuento de una sesión UDP en el receptor
+#[derive(Debug, Default)]
+struct UdpSession {
+    packets: u64,
+    bytes: u64,
+    first: Option<Instant>,
+    last: Option<Instant>,
+    last_transit: Option<f64>,
+    /// Jitter en segundos
+    jitter: f64,
+}
+
+impl UdpSession {
+    /// `sent` según el reloj del emisor: el desfase entre relojes se cancela
+    fn record(&mut self, bytes: usize, sent: f64, now: Instant, epoch: Instant) {
+        let transit = now.duration_since(epoch).as_secs_f64() - sent;
+        if let Some(last) = self.last_transit {
+            self.jitter += ((transit - last).abs() - self.jitter) / 16.0;
+        }
+        self.last_transit = Some(transit);
+        self.packets += 1;
+        self.bytes += bytes as u64;
+        self.first.get_or_insert(now);
+        self.last = Some(now);
+    }
+}
+
+type Sessions = Arc<Mutex<HashMap<u64, UdpSession>>>;
+
+/// Receptor de pruebas en segundo plano
+pub struct ThroughputServer {
+    local_addr: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ThroughputServer {
+    pub async fn start(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let udp = UdpSocket::bind(local_addr).await?;
+        let sessions: Sessions = Arc::default();
+        let busy = Arc::new(Semaphore::new(1));
+
+        let receiver = tokio::spawn(receive_datagrams(udp, sessions.clone()));
+        let acceptor = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let (sessions, busy) = (sessions.clone(), busy.clone());
+                        tokio::spawn(async move {
+                            if let Err(e) = serve(stream, sessions, busy).await {
+                                warn!("⚠️  Prueba de throughput desde {} fallida: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("⚠️  Error aceptando prueba de throughput: {}", e),
+                }
+            }
+        });
+
+        info!("📶 Receptor de pruebas de throughput en {}", local_addr);
+        Ok(Self {
+            local_addr,
+            tasks: vec![receiver, acceptor],
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn stop(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for ThroughputServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+async fn receive_datagrams(udp: UdpSocket, sessions: Sessions) {
+    let epoch = Instant::now();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let Ok((len, _)) = udp.recv_from(&mut buffer).await else { continue };
+        if len < DATAGRAM_HEADER {
+            continue;
+        }
+        let field = |i: usize| u64::from_be_bytes(buffer[i * 8..i * 8 + 8].try_into().unwrap_or_default());
+        let (session, sent) = (field(0), field(2) as f64 / 1e9);
+        // Los datagramas de sesiones desconocidas o ya cerradas se ignoran
+        if let Some(stats) = sessions.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&session) {
+            stats.record(len, sent, Instant::now(), epoch);
+        }
+    }
+}
+
+async fn write_line<T: Serialize>(writer: &mut (impl AsyncWriteExt + Unpin), value: &T) -> Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+async fn read_line<T: for<'de> Deserialize<'de>>(reader: &mut (impl AsyncBufReadExt + Unpin), limit: Duration) -> Result<T> {
+    let mut line = String::new();
+    if tokio::time::timeout(limit, reader.read_line(&mut line)).await?? == 0 {
+        return Err(anyhow!("Conexión cerrada"));
+    }
+    Ok(serde_json::from_str(line.trim())?)
+}
+
+async fn serve(stream: TcpStream, sessions: Sessions, busy: Arc<Semaphore>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let hello: Hello = read_line(&mut reader, Duration::from_secs(5)).await?;
+    let Ok(_permit) = busy.try_acquire() else {
+        let report = Report {
+            error: Some("El receptor ya está atendiendo otra prueba".to_string()),
+            ..Default::default()
+        };
+        return write_line(&mut writer, &report).await;
+    };
+    let limit = Duration::from_millis(hello.duration_ms) + GRACE;
+    debug!("📶 Prueba {:?} {} aceptada", hello.protocol, hello.session);
+
+    let report = match hello.protocol {
+        ThroughputProtocol::Tcp => {
+            let start = Instant::now();
+            let mut buffer = vec![0u8; 128 * 1024];
+            let mut bytes = 0u64;
+            tokio::time::timeout(limit, async {
+                loop {
+                    match reader.read(&mut buffer).await? {
+                        0 => return Ok::<_, std::io::Error>(()),
+                        n => bytes += n as u64,
+                    }
+                }
+            })
+            .await??;
+            Report {
+                bytes,
+                elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+                ..Default::default()
+            }
+        }
+        ThroughputProtocol::Udp => {
+            sessions.lock().unwrap_or_else(|e| e.into_inner()).insert(hello.session, UdpSession::default());
+            write_line(&mut writer, &READY).await?;
+            let done: Result<Done> = read_line(&mut reader, limit).await;
+            // Datagramas aún en vuelo
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let stats = sessions.lock().unwrap_or_else(|e| e.into_inner()).remove(&hello.session).unwrap_or_default();
+            done?;
+            let elapsed = match (stats.first, stats.last) {
+                (Some(first), Some(last)) => last.duration_since(first),
+                _ => Duration::ZERO,
+            };
+            Report {
+                bytes: stats.bytes.saturating_sub(stats.packets * DATAGRAM_HEADER as u64),
+                elapsed_ms: elapsed.as_secs_f64() * 1000.0,
+                packets: stats.packets,
+                jitter_ms: Some(stats.jitter * 1000.0),
+                error: None,
+            }
+        }
+    };
+    write_line(&mut writer, &report).await
+}
+
+/// Esperar lo necesario para no superar `rate` bytes por segundo
+async fn pace(sent: u64, rate: f64, start: Instant) {
+    let due = Duration::from_secs_f64(sent as f64 / rate);
+    let elapsed = start.elapsed();
+    if due > elapsed {
+        tokio::time::sleep(due - elapsed).await;
+    }
+}
+
+/// Enviar durante `duration` a `bandwidth_mbps` como mucho y pedir el informe al receptor
+pub async fn run(
+    target: SocketAddr,
+    protocol: ThroughputProtocol,
+    duration: Duration,
+    bandwidth_mbps: f64,
+    connect_timeout: Duration,
+) -> Result<ThroughputResult> {
+    let stream = tokio::time::timeout(connect_timeout, TcpStream::connect(target)).await??;
+    stream.set_nodelay(true)?;
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let session = (uuid::Uuid::new_v4().as_u128() >> 64) as u64;
+    write_line(
+        &mut writer,
+        &Hello {
+            protocol,
+            session,
+            duration_ms: duration.as_millis() as u64,
+        },
+    )
+    .await?;
+
+    let rate = bandwidth_mbps * 1_000_000.0 / 8.0;
+    let limit = duration + GRACE;
+    let start = Instant::now();
+    let mut bytes_sent = 0u64;
+    let mut packets_sent = None;
+    let mut retransmits = None;
+
+    match protocol {
+        ThroughputProtocol::Tcp => {
+            // Bloques de ~10 ms al ritmo pedido para que el límite se note en enlaces lentos
+            let chunk = vec![0u8; ((rate / 100.0) as usize).clamp(DATAGRAM_SIZE, 64 * 1024)];
+            while start.elapsed() < duration {
+                writer.write_all(&chunk).await?;
+                bytes_sent += chunk.len() as u64;
+                pace(bytes_sent, rate, start).await;
+            }
+            retransmits = tcp_retransmits(writer.as_ref());
+            writer.shutdown().await?;
+        }
+        ThroughputProtocol::Udp => {
+            let ready: String = read_line(&mut reader, Duration::from_secs(5)).await?;
+            if ready != READY {
+                return Err(anyhow!("Respuesta inesperada del receptor: {}", ready));
+            }
+            let local: IpAddr = if target.is_ipv4() { Ipv4Addr::UNSPECIFIED.into() } else { Ipv6Addr::UNSPECIFIED.into() };
+            let udp = UdpSocket::bind((local, 0)).await?;
+            udp.connect(target).await?;
+
+            let mut datagram = vec![0u8; DATAGRAM_SIZE];
+            datagram[..8].copy_from_slice(&session.to_be_bytes());
+            let mut sequence = 0u64;
+            while start.elapsed() < duration {
+                datagram[8..16].copy_from_slice(&sequence.to_be_bytes());
+                datagram[16..24].copy_from_slice(&(start.elapsed().as_nanos() as u64).to_be_bytes());
+                // Un búfer lleno cuenta como pérdida, igual que un descarte en la red
+                if udp.send(&datagram).await.is_ok() {
+                    bytes_sent += (DATAGRAM_SIZE - DATAGRAM_HEADER) as u64;
+                }
+                sequence += 1;
+                pace(sequence * DATAGRAM_SIZE as u64, rate, start).await;
+            }
+            packets_sent = Some(sequence);
+            write_line(&mut writer, &Done { packets: sequence }).await?;
+        }
+    }
+
+    let report: Report = read_line(&mut reader, limit).await?;
+    if let Some(error) = report.error {
+        return Err(anyhow!(error));
+    }
+    let seconds = (report.elapsed_ms / 1000.0).max(f64::EPSILON);
+    Ok(ThroughputResult {
+        target,
+        protocol,
+        duration: start.elapsed(),
+        bytes_sent,
+        bytes_received: report.bytes,
+        goodput_mbps: report.bytes as f64 * 8.0 / seconds / 1_000_000.0,
+        bandwidth_cap_mbps: bandwidth_mbps,
+        retransmits,
+        packets_lost: packets_sent.map(|sent| sent.saturating_sub(report.packets)),
+        packets_sent,
+        jitter_ms: report.jitter_ms,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn tcp_retransmits(stream: &TcpStream) -> Option<u64> {
+    use std::os::fd::AsRawFd;
+
+    let mut info: libc::tcp_info = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::tcp_info>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            &mut info as *mut libc::tcp_info as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    (result == 0).then_some(info.tcpi_total_retrans as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn tcp_retransmits(_stream: &TcpStream) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_loopback_throughput() {
+        let server = ThroughputServer::start("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let target = server.local_addr();
+        let duration = Duration::from_millis(400);
+
+        let tcp = run(target, ThroughputProtocol::Tcp, duration, 40.0, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(tcp.bytes_received, tcp.bytes_sent);
+        assert!(tcp.goodput_mbps > 10.0 && tcp.goodput_mbps < 60.0, "{}", tcp.goodput_mbps);
+
+        let udp = run(target, ThroughputProtocol::Udp, duration, 8.0, Duration::from_secs(1)).await.unwrap();
+        let sent = udp.packets_sent.unwrap();
+        assert!(sent > 100);
+        assert_eq!(udp.packets_lost, Some(0));
+        assert_eq!(udp.bytes_received, sent * (DATAGRAM_SIZE - DATAGRAM_HEADER) as u64);
+        assert!(udp.jitter_ms.unwrap() >= 0.0);
+
+        server.stop();
+    }
+
+    #[test]
+    fn test_jitter_from_transit_times() {
+        let epoch = Instant::now();
+        let mut session = UdpSession::default();
+        // Llegadas cada 10 ms con envíos cada 10 ms salvo uno retrasado 8 ms
+        for (i, delay) in [0.0, 0.0, 0.008, 0.0].iter().enumerate() {
+            let sent = i as f64 * 0.01;
+            session.record(100, sent, epoch + Duration::from_secs_f64(sent + 0.5 + delay), epoch);
+        }
+        assert_eq!(session.packets, 4);
+        // Dos saltos de 8 ms: 0.5 ms y luego 0.5 + (8 - 0.5) / 16
+        assert!((session.jitter * 1000.0 - (0.5 + 7.5 / 16.0)).abs() < 1e-6);
+    }
+}
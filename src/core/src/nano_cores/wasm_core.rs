@@ -0,0 +1,412 @@
+//! Nano-Core.Wasm - Nano-núcleos en sandbox WASM
+//!
+//! `WasmCoreFactory` crea nano-núcleos a partir de componentes `.wasm` que
+//! implementan el mundo `saai:nano-core` de `wit/nano-core.wit`. Cada
+//! instancia tiene su propio `Store`, con el fuel por iteración y la memoria
+//! acotados por los `ResourceLimits` del SecurityCore. Un trap descarta la
+//! instancia del módulo y la siguiente iteración la vuelve a crear.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn, error};
+use uuid::Uuid;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine, ResourceLimiter, Store};
+
+use crate::communication::CognitiveFabric;
+use crate::config::NanoCoresConfig;
+use crate::nano_cores::security_core::ResourceLimits;
+use crate::nano_cores::{
+    CommandAuthorization, CoreResult, NanoCore, NanoCoreContext, NanoCoreFactory, NanoCoreHealth, NanoCoreManager,
+    NanoCoreState, NanoCoreType,
+};
+use crate::security::SecurityLevel;
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "nano-core",
+        async: true,
+    });
+}
+
+use bindings::LogLevel;
+
+/// Fuel consumido entre cesiones al runtime de tokio
+const FUEL_YIELD_INTERVAL: u64 = 10_000;
+
+/// Elementos máximos de una tabla del módulo
+const MAX_TABLE_ELEMENTS: u32 = 10_000;
+
+/// Sección `[nano_cores.custom.<nombre>]` de un nano-núcleo WASM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasmCoreConfig {
+    /// Componente a cargar
+    pub module: PathBuf,
+    /// Fuel de cada llamada al módulo con `max_cpu_percent` al 100%
+    pub fuel_per_call: u64,
+    /// Límites del sandbox: `max_cpu_percent` escala el fuel y `max_memory_bytes` acota la memoria
+    pub resource_limits: ResourceLimits,
+    /// Permiso de los comandos del módulo (por defecto `nano_core.<nombre>.command`)
+    pub permission: Option<String>,
+    /// Nivel de seguridad de los comandos del módulo
+    pub level: SecurityLevel,
+    /// Espera entre dos llamadas a `run`, en ms
+    pub run_interval_ms: u64,
+}
+
+impl Default for WasmCoreConfig {
+    fn default() -> Self {
+        Self {
+            module: PathBuf::new(),
+            fuel_per_call: 10_000_000,
+            resource_limits: ResourceLimits::default(),
+            permission: None,
+            level: SecurityLevel::Secret,
+            run_interval_ms: 1000,
+        }
+    }
+}
+
+impl WasmCoreConfig {
+    /// Fuel disponible en cada llamada
+    pub fn fuel_budget(&self) -> u64 {
+        (self.fuel_per_call as f64 * self.resource_limits.max_cpu_percent.clamp(0.0, 100.0) / 100.0) as u64
+    }
+}
+
+/// Memoria lineal y tablas del módulo
+struct SandboxLimiter {
+    max_memory: usize,
+    memory: usize,
+}
+
+impl ResourceLimiter for SandboxLimiter {
+    fn memory_growing(&mut self, _current: usize, desired: usize, _maximum: Option<usize>) -> wasmtime::Result<bool> {
+        if desired > self.max_memory {
+            return Ok(false);
+        }
+        self.memory = desired;
+        Ok(true)
+    }
+
+    fn table_growing(&mut self, _current: u32, desired: u32, _maximum: Option<u32>) -> wasmtime::Result<bool> {
+        Ok(desired <= MAX_TABLE_ELEMENTS)
+    }
+}
+
+/// Estado del host en cada `Store`
+struct HostState {
+    name: String,
+    instance_number: usize,
+    cognitive_fabric: Arc<CognitiveFabric>,
+    limiter: SandboxLimiter,
+}
+
+#[async_trait]
+impl bindings::NanoCoreImports for HostState {
+    async fn log(&mut self, level: LogLevel, message: String) {
+        match level {
+            LogLevel::Debug => debug!("🧩 {}-{}: {}", self.name, self.instance_number, message),
+            LogLevel::Info => info!("🧩 {}-{}: {}", self.name, self.instance_number, message),
+            LogLevel::Warn => warn!("🧩 {}-{}: {}", self.name, self.instance_number, message),
+            LogLevel::Error => error!("🧩 {}-{}: {}", self.name, self.instance_number, message),
+        }
+    }
+
+    async fn publish(&mut self, topic: String, payload: Vec<u8>) -> Result<(), String> {
+        // El módulo solo publica bajo su propio prefijo
+        if topic.is_empty() || topic.contains(['*', '>', ' ']) {
+            return Err(format!("Tema inválido: {:?}", topic));
+        }
+        self.cognitive_fabric
+            .publish(&format!("wasm.{}.{}", self.name, topic), &payload)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Fábrica de nano-núcleos WASM; un mismo `Engine` para todas las instancias
+pub struct WasmCoreFactory {
+    engine: Engine,
+    linker: Arc<Linker<HostState>>,
+}
+
+impl WasmCoreFactory {
+    pub fn new() -> Result<Self> {
+        let mut config = Config::new();
+        config.async_support(true).consume_fuel(true).wasm_component_model(true);
+        let engine = Engine::new(&config)?;
+
+        let mut linker = Linker::new(&engine);
+        bindings::NanoCore::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+
+        Ok(Self {
+            engine,
+            linker: Arc::new(linker),
+        })
+    }
+}
+
+#[async_trait]
+impl NanoCoreFactory for WasmCoreFactory {
+    async fn create(&self, context: NanoCoreContext) -> Result<Box<dyn NanoCore>> {
+        Ok(Box::new(WasmCore::new(&self.engine, self.linker.clone(), context).await?))
+    }
+}
+
+/// Registrar una fábrica WASM por cada sección de `[nano_cores.custom]` con `module`
+pub async fn register_configured(manager: &NanoCoreManager, config: &NanoCoresConfig) -> Result<()> {
+    let names: Vec<&String> = config.custom
+        .iter()
+        .filter(|(_, section)| section.get("module").is_some())
+        .map(|(name, _)| name)
+        .collect();
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let factory = Arc::new(WasmCoreFactory::new()?);
+    for name in names {
+        manager.register_factory(name.clone(), factory.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Nano-núcleo implementado por un componente WASM
+pub struct WasmCore {
+    core_type: NanoCoreType,
+    instance_id: Uuid,
+    instance_number: usize,
+    config: WasmCoreConfig,
+    engine: Engine,
+    component: Component,
+    linker: Arc<Linker<HostState>>,
+    cognitive_fabric: Arc<CognitiveFabric>,
+    /// Instancia del módulo; `None` tras un trap hasta la siguiente iteración
+    sandbox: Option<(Store<HostState>, bindings::NanoCore)>,
+    start_time: SystemTime,
+    error_count: u64,
+    /// Resultado de `healthy` tras la última iteración
+    healthy: bool,
+    /// Fuel consumido en la última iteración
+    fuel_used: u64,
+}
+
+impl WasmCore {
+    /// Compilar el componente de la sección del nano-núcleo
+    async fn new(engine: &Engine, linker: Arc<Linker<HostState>>, context: NanoCoreContext) -> Result<Self> {
+        let name = context.core_type.config_section().to_string();
+        let config: WasmCoreConfig = serde_json::from_value(context.config)
+            .map_err(|e| anyhow!("Configuración inválida de nano_cores.custom.{}: {}", name, e))?;
+        if config.module.as_os_str().is_empty() {
+            return Err(anyhow!("nano_cores.custom.{}.module no definido", name));
+        }
+
+        let component = tokio::task::spawn_blocking({
+            let engine = engine.clone();
+            let module = config.module.clone();
+            move || Component::from_file(&engine, &module)
+        })
+        .await?
+        .map_err(|e| anyhow!("Error compilando {}: {}", config.module.display(), e))?;
+
+        Ok(Self {
+            core_type: context.core_type,
+            instance_id: Uuid::new_v4(),
+            instance_number: context.instance,
+            config,
+            engine: engine.clone(),
+            component,
+            linker,
+            cognitive_fabric: context.cognitive_fabric,
+            sandbox: None,
+            start_time: SystemTime::now(),
+            error_count: 0,
+            healthy: true,
+            fuel_used: 0,
+        })
+    }
+
+    /// Instanciar el módulo e inicializarlo si no hay instancia viva
+    async fn ensure_sandbox(&mut self) -> Result<()> {
+        if self.sandbox.is_some() {
+            return Ok(());
+        }
+
+        let mut store = Store::new(&self.engine, HostState {
+            name: self.core_type.config_section().to_string(),
+            instance_number: self.instance_number,
+            cognitive_fabric: self.cognitive_fabric.clone(),
+            limiter: SandboxLimiter {
+                max_memory: self.config.resource_limits.max_memory_bytes as usize,
+                memory: 0,
+            },
+        });
+        store.limiter(|state| &mut state.limiter);
+        store.fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL))?;
+        store.set_fuel(self.config.fuel_budget())?;
+
+        let guest = bindings::NanoCore::instantiate_async(&mut store, &self.component, &self.linker).await?;
+        self.sandbox = Some((store, guest));
+
+        let (store, guest) = self.refuel()?;
+        let result = guest.call_initialize(store).await;
+        self.settle("initialize", result)
+    }
+
+    /// Store y exportaciones con el fuel de una llamada
+    fn refuel(&mut self) -> Result<(&mut Store<HostState>, &bindings::NanoCore)> {
+        let budget = self.config.fuel_budget();
+        let (store, guest) = self.sandbox.as_mut()
+            .ok_or_else(|| anyhow!("Sandbox WASM no inicializado"))?;
+        store.set_fuel(budget)?;
+        Ok((store, guest))
+    }
+
+    /// Resultado de una llamada al módulo; un trap descarta la instancia
+    fn settle<T>(&mut self, export: &str, result: wasmtime::Result<Result<T, String>>) -> Result<T> {
+        match result {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(message)) => {
+                self.error_count += 1;
+                Err(anyhow!("{} de {} falló: {}", export, self.core_type, message))
+            }
+            Err(trap) => {
+                self.error_count += 1;
+                self.sandbox = None;
+                warn!("💥 Trap en {} de {} instancia {}: {}", export, self.core_type, self.instance_number, trap);
+                Err(anyhow!("Trap en {} de {}: {}", export, self.core_type, trap))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl NanoCore for WasmCore {
+    fn core_type(&self) -> NanoCoreType {
+        self.core_type.clone()
+    }
+
+    fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        info!(
+            "🔧 Inicializando {} instancia {} desde {} (ID: {})",
+            self.core_type,
+            self.instance_number,
+            self.config.module.display(),
+            self.instance_id
+        );
+
+        self.ensure_sandbox().await?;
+
+        info!("✅ {} instancia {} inicializado correctamente", self.core_type, self.instance_number);
+        Ok(())
+    }
+
+    async fn run(&mut self) -> CoreResult<()> {
+        if self.sandbox.is_none() {
+            info!("♻️  Reinstanciando {} instancia {} tras un trap", self.core_type, self.instance_number);
+        }
+        self.ensure_sandbox().await?;
+
+        let budget = self.config.fuel_budget();
+        let (store, guest) = self.refuel()?;
+        let result = guest.call_run(&mut *store).await;
+        let remaining = store.get_fuel().unwrap_or(0);
+        self.fuel_used = budget - remaining;
+        self.settle("run", result)?;
+
+        let (store, guest) = self.refuel()?;
+        let result = guest.call_healthy(store).await.map(Ok);
+        self.healthy = self.settle("healthy", result)?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<NanoCoreHealth> {
+        let uptime = self.start_time.elapsed()?.as_secs();
+        let budget = self.config.fuel_budget().max(1);
+        let memory = self.sandbox.as_ref().map_or(0, |(store, _)| store.data().limiter.memory);
+
+        let state = if self.sandbox.is_none() || !self.healthy || self.error_count > 10 {
+            NanoCoreState::Degraded
+        } else {
+            NanoCoreState::Running
+        };
+
+        Ok(NanoCoreHealth {
+            core_type: self.core_type(),
+            instance_id: self.instance_id,
+            state,
+            // Porcentaje del fuel de la última iteración
+            cpu_usage: self.fuel_used as f64 / budget as f64 * 100.0,
+            memory_usage: memory as f64,
+            last_heartbeat: chrono::Utc::now(),
+            error_count: self.error_count,
+            uptime_seconds: uptime,
+        })
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        info!("🛑 Deteniendo {} instancia {}", self.core_type, self.instance_number);
+
+        if self.sandbox.is_some() {
+            let (store, guest) = self.refuel()?;
+            let result = guest.call_shutdown(store).await;
+            let outcome = self.settle("shutdown", result);
+            self.sandbox = None;
+            outcome?;
+        }
+
+        info!("✅ {} instancia {} detenido correctamente", self.core_type, self.instance_number);
+        Ok(())
+    }
+
+    fn command_authorization(&self, _payload: &[u8]) -> Result<CommandAuthorization> {
+        // El módulo no decide qué se le exige: lo fija la configuración
+        let permission = self.config.permission.clone()
+            .unwrap_or_else(|| format!("nano_core.{}.command", self.core_type));
+        Ok(CommandAuthorization::new(&permission, self.config.level))
+    }
+
+    fn run_interval(&self) -> Duration {
+        Duration::from_millis(self.config.run_interval_ms)
+    }
+
+    async fn process_command(&mut self, command: &str, payload: &[u8]) -> CoreResult<Vec<u8>> {
+        self.ensure_sandbox().await?;
+
+        let (store, guest) = self.refuel()?;
+        let result = guest.call_process_command(store, command, payload).await;
+        Ok(self.settle(command, result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_limits_and_linker() {
+        let config: WasmCoreConfig = serde_json::from_value(serde_json::json!({
+            "module": "plugins/filter.wasm",
+            "fuel_per_call": 1_000_000,
+            "resource_limits": { "max_cpu_percent": 25.0 }
+        }))
+        .unwrap();
+        assert_eq!(config.fuel_budget(), 250_000);
+        assert_eq!(config.resource_limits.max_memory_bytes, ResourceLimits::default().max_memory_bytes);
+        assert_eq!(config.level, SecurityLevel::Secret);
+
+        // El mundo de `wit/nano-core.wit` se enlaza con las importaciones del host
+        assert!(WasmCoreFactory::new().is_ok());
+    }
+}
@@ -0,0 +1,637 @@
+//! Watchdog de conectividad de NetworkCore
+//!
+//! Cada `interval_secs` se sondean el gateway por defecto, el primer resolver
+//! DNS del sistema, el servidor NATS (un ping por el Cognitive Fabric) y los
+//! `targets` configurados. La puntuación de alcanzabilidad es la tasa de éxito
+//! de la ventana de cada destino, ponderada por su peso; por debajo de
+//! `degraded_score` el núcleo se informa degradado. Con `remediation` activa,
+//! varias rondas por debajo de `remediation_score` con el gateway caído
+//! publican en `network.remediation` una propuesta que se vota por consenso.
+
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::communication::CognitiveFabric;
+use crate::nano_cores::dns_monitor;
+use crate::nano_cores::firewall::Invocation;
+use crate::nano_cores::ip_family::{self, AddressFamilyConfig};
+use crate::nano_cores::netinfo;
+use crate::nano_cores::network_core::NetworkCommand;
+use crate::nano_cores::NanoCoreError;
+
+/// Tema en el que NetworkCore publica las remediaciones propuestas
+pub const REMEDIATION_SUBJECT: &str = "network.remediation";
+
+/// Puerto con el que se comprueba el gateway; un rechazo también prueba que responde
+const GATEWAY_PORT: u16 = 53;
+const GATEWAY: &str = "gateway";
+const DNS: &str = "dns";
+const NATS: &str = "nats";
+
+/// Sección `[nano_cores.network_core.watchdog]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    pub timeout_ms: u64,
+    /// Sondear el gateway de la ruta por defecto
+    pub gateway: bool,
+    /// Resolver `dns_name` con el primer resolver del sistema
+    pub dns: bool,
+    pub dns_name: String,
+    /// Ping al servidor NATS a través del Cognitive Fabric
+    pub nats: bool,
+    pub targets: Vec<WatchdogTarget>,
+    /// Rondas que cuentan en la tasa de éxito de cada destino
+    pub window: usize,
+    /// Puntuación por debajo de la que `health_check` informa `Degraded`
+    pub degraded_score: f64,
+    /// Proponer remediaciones por consenso
+    pub remediation: bool,
+    pub remediation_score: f64,
+    /// Rondas seguidas por debajo de `remediation_score` antes de proponer
+    pub remediation_rounds: u32,
+    pub remediation_cooldown_secs: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 15,
+            timeout_ms: 2000,
+            gateway: true,
+            dns: true,
+            dns_name: "example.com".to_string(),
+            nats: true,
+            targets: Vec::new(),
+            window: 8,
+            degraded_score: 0.8,
+            remediation: false,
+            remediation_score: 0.5,
+            remediation_rounds: 4,
+            remediation_cooldown_secs: 900,
+        }
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// Destino adicional del watchdog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WatchdogTarget {
+    pub name: String,
+    /// `host:puerto`, con IP o nombre
+    pub address: String,
+    #[serde(default)]
+    pub probe: ProbeKind,
+    /// Peso en la puntuación; los destinos integrados pesan 1
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+/// Qué cuenta como alcanzable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeKind {
+    /// La conexión TCP debe completarse
+    #[default]
+    Tcp,
+    /// Basta con que el host conteste, aunque rechace la conexión
+    Host,
+    /// Consulta DNS; cualquier respuesta, también NXDOMAIN, cuenta
+    Dns,
+    /// Ping del Cognitive Fabric
+    Nats,
+}
+
+/// Acción de remediación sometida a consenso
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationAction {
+    RestartInterface { interface: String },
+}
+
+impl RemediationAction {
+    /// Comando de NetworkCore que la ejecuta una vez aprobada
+    pub fn command(&self) -> NetworkCommand {
+        match self {
+            RemediationAction::RestartInterface { interface } => NetworkCommand::RestartInterface {
+                interface: interface.clone(),
+                dry_run: false,
+            },
+        }
+    }
+}
+
+/// Propuesta publicada en `network.remediation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationRequest {
+    pub id: Uuid,
+    /// Instancia de NetworkCore que la propone y la ejecutará
+    pub instance: usize,
+    pub action: RemediationAction,
+    pub reason: String,
+    pub score: f64,
+    pub timestamp: SystemTime,
+}
+
+/// Estado de un destino para `GetWatchdog`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetStatus {
+    pub name: String,
+    pub probe: ProbeKind,
+    pub address: Option<String>,
+    pub weight: f64,
+    pub reachable: bool,
+    pub success_rate: f64,
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+/// Resultado de `GetWatchdog`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogReport {
+    /// 0.0 - 1.0; `None` si aún no hay sondeos
+    pub score: Option<f64>,
+    pub targets: Vec<TargetStatus>,
+    pub last_round: Option<SystemTime>,
+    pub last_remediation: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone)]
+enum Endpoint {
+    Socket(SocketAddr),
+    Address(String),
+    Fabric,
+}
+
+#[derive(Debug, Clone)]
+struct Probe {
+    name: String,
+    kind: ProbeKind,
+    endpoint: Endpoint,
+    weight: f64,
+}
+
+impl Probe {
+    fn address(&self) -> Option<String> {
+        match &self.endpoint {
+            Endpoint::Socket(addr) => Some(addr.to_string()),
+            Endpoint::Address(address) => Some(address.clone()),
+            Endpoint::Fabric => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct TargetState {
+    probe: ProbeKind,
+    address: Option<String>,
+    weight: f64,
+    window: VecDeque<bool>,
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+    last_error: Option<String>,
+}
+
+impl TargetState {
+    fn success_rate(&self) -> f64 {
+        if self.window.is_empty() {
+            return 0.0;
+        }
+        self.window.iter().filter(|ok| **ok).count() as f64 / self.window.len() as f64
+    }
+}
+
+#[derive(Debug, Default)]
+struct WatchdogState {
+    targets: HashMap<String, TargetState>,
+    /// Interfaz del gateway en la última ronda, la que se reiniciaría
+    gateway_interface: Option<String>,
+    low_rounds: u32,
+    degraded: bool,
+    last_round: Option<SystemTime>,
+    last_remediation: Option<Instant>,
+    last_remediation_at: Option<SystemTime>,
+}
+
+impl WatchdogState {
+    fn record(&mut self, probe: &Probe, result: &Result<Duration>, window: usize) {
+        let target = self.targets.entry(probe.name.clone()).or_default();
+        target.probe = probe.kind;
+        target.address = probe.address();
+        target.weight = probe.weight;
+        target.window.push_back(result.is_ok());
+        while target.window.len() > window.max(1) {
+            target.window.pop_front();
+        }
+        match result {
+            Ok(latency) => {
+                target.latency = Some(*latency);
+                target.consecutive_failures = 0;
+                target.last_error = None;
+            }
+            Err(e) => {
+                target.latency = None;
+                target.consecutive_failures += 1;
+                target.last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Media de las tasas de éxito ponderada por peso
+    fn score(&self) -> Option<f64> {
+        let (weighted, total) = self
+            .targets
+            .values()
+            .filter(|target| !target.window.is_empty() && target.weight > 0.0)
+            .fold((0.0, 0.0), |(weighted, total), target| (weighted + target.weight * target.success_rate(), total + target.weight));
+        (total > 0.0).then(|| weighted / total)
+    }
+
+    /// Interfaz a reiniciar si la puntuación lleva las rondas exigidas baja, el gateway falla y pasó el enfriamiento
+    fn remediation(&mut self, config: &WatchdogConfig, score: Option<f64>, now: Instant) -> Option<String> {
+        if score.is_some_and(|score| score < config.remediation_score) {
+            self.low_rounds += 1;
+        } else {
+            self.low_rounds = 0;
+        }
+        let gateway_down = self.targets.get(GATEWAY).is_some_and(|gateway| gateway.consecutive_failures > 0);
+        let cooled = self
+            .last_remediation
+            .is_none_or(|last| now.duration_since(last) >= Duration::from_secs(config.remediation_cooldown_secs));
+        if !config.remediation || self.low_rounds < config.remediation_rounds.max(1) || !gateway_down || !cooled {
+            return None;
+        }
+        let interface = self.gateway_interface.clone()?;
+        self.last_remediation = Some(now);
+        self.last_remediation_at = Some(SystemTime::now());
+        self.low_rounds = 0;
+        Some(interface)
+    }
+
+    fn report(&self) -> WatchdogReport {
+        let mut targets: Vec<TargetStatus> = self
+            .targets
+            .iter()
+            .map(|(name, target)| TargetStatus {
+                name: name.clone(),
+                probe: target.probe,
+                address: target.address.clone(),
+                weight: target.weight,
+                reachable: target.window.back().copied().unwrap_or(false),
+                success_rate: target.success_rate(),
+                latency_ms: target.latency.map(|latency| latency.as_secs_f64() * 1000.0),
+                consecutive_failures: target.consecutive_failures,
+                last_error: target.last_error.clone(),
+            })
+            .collect();
+        targets.sort_by(|a, b| a.name.cmp(&b.name));
+        WatchdogReport {
+            score: self.score(),
+            targets,
+            last_round: self.last_round,
+            last_remediation: self.last_remediation_at,
+        }
+    }
+}
+
+/// Destinos de una ronda con la interfaz del gateway elegido
+async fn probes(config: &WatchdogConfig, families: &AddressFamilyConfig) -> Result<(Vec<Probe>, Option<String>)> {
+    let mut probes = Vec::new();
+    let mut gateway_interface = None;
+    let builtin = |name: &str, kind, endpoint| Probe { name: name.to_string(), kind, endpoint, weight: 1.0 };
+
+    if config.gateway {
+        let routes = tokio::task::spawn_blocking(netinfo::routes).await??;
+        let gateway = netinfo::default_gateway(&routes, families)
+            .and_then(|gateway| routes.iter().find(|route| route.is_default && route.gateway == gateway));
+        if let Some(route) = gateway {
+            let zone = netinfo::interface_index(&route.interface);
+            let addr = ip_family::socket_addr(route.gateway, GATEWAY_PORT, zone);
+            probes.push(builtin(GATEWAY, ProbeKind::Host, Endpoint::Socket(addr)));
+            gateway_interface = Some(route.interface.clone());
+        }
+    }
+    if config.dns {
+        let resolvers = tokio::task::spawn_blocking(netinfo::dns_resolvers).await??;
+        if let Some(resolver) = families.order(resolvers, |resolver| resolver.ip()).first() {
+            probes.push(builtin(DNS, ProbeKind::Dns, Endpoint::Socket(*resolver)));
+        }
+    }
+    if config.nats {
+        probes.push(builtin(NATS, ProbeKind::Nats, Endpoint::Fabric));
+    }
+    probes.extend(config.targets.iter().map(|target| Probe {
+        name: target.name.clone(),
+        kind: target.probe,
+        endpoint: if target.probe == ProbeKind::Nats { Endpoint::Fabric } else { Endpoint::Address(target.address.clone()) },
+        weight: target.weight,
+    }));
+    Ok((probes, gateway_interface))
+}
+
+async fn resolve(endpoint: &Endpoint, families: &AddressFamilyConfig) -> Result<SocketAddr> {
+    match endpoint {
+        Endpoint::Socket(addr) => Ok(*addr),
+        Endpoint::Address(address) => {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host(address.as_str()).await?.collect();
+            families
+                .order(addrs, |addr| addr.ip())
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("{} no resuelve a ninguna familia habilitada", address))
+        }
+        Endpoint::Fabric => Err(anyhow!("El Cognitive Fabric no tiene dirección")),
+    }
+}
+
+/// Ejecutar un sondeo; devuelve su latencia si el destino es alcanzable
+async fn run_probe(probe: &Probe, families: &AddressFamilyConfig, fabric: &CognitiveFabric, timeout: Duration, dns_name: &str) -> Result<Duration> {
+    let start = Instant::now();
+    let probed = tokio::time::timeout(timeout, async {
+        match probe.kind {
+            ProbeKind::Nats => fabric.ping(timeout).await.map(|_| ()),
+            ProbeKind::Dns => {
+                let addr = resolve(&probe.endpoint, families).await?;
+                dns_monitor::query(addr, dns_name, false, timeout).await.map(|_| ())
+            }
+            ProbeKind::Tcp => Ok(TcpStream::connect(resolve(&probe.endpoint, families).await?).await.map(|_| ())?),
+            ProbeKind::Host => match TcpStream::connect(resolve(&probe.endpoint, families).await?).await {
+                Ok(_) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => Ok(()),
+                Err(e) => Err(e.into()),
+            },
+        }
+    })
+    .await
+    .map_err(|_| anyhow!("Sin respuesta en {} ms", timeout.as_millis()))?;
+    probed.map(|_| start.elapsed())
+}
+
+/// Watchdog periódico de conectividad
+pub struct ConnectivityWatchdog {
+    config: Arc<RwLock<WatchdogConfig>>,
+    families: Arc<RwLock<AddressFamilyConfig>>,
+    state: Arc<RwLock<WatchdogState>>,
+    is_running: Arc<RwLock<bool>>,
+}
+
+impl ConnectivityWatchdog {
+    pub fn new(config: WatchdogConfig, families: AddressFamilyConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            families: Arc::new(RwLock::new(families)),
+            state: Arc::default(),
+            is_running: Arc::new(RwLock::new(false)),
+        }
+    }
+
+    pub async fn reconfigure(&self, config: WatchdogConfig, families: AddressFamilyConfig) {
+        *self.config.write().await = config;
+        *self.families.write().await = families;
+    }
+
+    /// Solo la instancia 0 propone remediaciones: todas las réplicas ven la misma red
+    pub async fn start(&self, fabric: Arc<CognitiveFabric>, instance: usize) -> Result<()> {
+        *self.is_running.write().await = true;
+
+        let config = self.config.clone();
+        let families = self.families.clone();
+        let state = self.state.clone();
+        let is_running = self.is_running.clone();
+
+        tokio::spawn(async move {
+            while *is_running.read().await {
+                let current = config.read().await.clone();
+                if current.enabled {
+                    let families = families.read().await.clone();
+                    if let Err(e) = Self::round(&current, &families, &state, &fabric, instance).await {
+                        warn!("⚠️  Error en el watchdog de conectividad: {}", e);
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(current.interval_secs.max(1))).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        *self.is_running.write().await = false;
+        Ok(())
+    }
+
+    /// Puntuación de alcanzabilidad; `None` si el watchdog está deshabilitado o sin sondeos
+    pub async fn score(&self) -> Option<f64> {
+        if !self.config.read().await.enabled {
+            return None;
+        }
+        self.state.read().await.score()
+    }
+
+    pub async fn report(&self) -> WatchdogReport {
+        self.state.read().await.report()
+    }
+
+    async fn round(
+        config: &WatchdogConfig,
+        families: &AddressFamilyConfig,
+        state: &RwLock<WatchdogState>,
+        fabric: &CognitiveFabric,
+        instance: usize,
+    ) -> Result<()> {
+        let (probes, gateway_interface) = probes(config, families).await?;
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let results = join_all(probes.iter().map(|probe| run_probe(probe, families, fabric, timeout, &config.dns_name))).await;
+
+        let (score, transition, remediation) = {
+            let mut state = state.write().await;
+            state.targets.retain(|name, _| probes.iter().any(|probe| &probe.name == name));
+            for (probe, result) in probes.iter().zip(&results) {
+                state.record(probe, result, config.window);
+            }
+            state.gateway_interface = gateway_interface;
+            state.last_round = Some(SystemTime::now());
+
+            let score = state.score();
+            let degraded = score.is_some_and(|score| score < config.degraded_score);
+            let transition = (degraded != state.degraded).then_some(degraded);
+            state.degraded = degraded;
+            let remediation = if instance == 0 { state.remediation(config, score, Instant::now()) } else { None };
+            (score, transition, remediation)
+        };
+
+        let failing: Vec<&str> = probes
+            .iter()
+            .zip(&results)
+            .filter(|(_, result)| result.is_err())
+            .map(|(probe, _)| probe.name.as_str())
+            .collect();
+        if let Some(degraded) = transition {
+            if degraded {
+                warn!("📉 Alcanzabilidad degradada ({:.2}); fallan: {}", score.unwrap_or_default(), failing.join(", "));
+            } else {
+                info!("📈 Alcanzabilidad recuperada ({:.2})", score.unwrap_or_default());
+            }
+            fabric
+                .publish(
+                    "network.alerts",
+                    &serde_json::to_vec(&serde_json::json!({
+                        "type": if degraded { "reachability_degraded" } else { "reachability_recovered" },
+                        "score": score,
+                        "failing": failing,
+                        "timestamp": SystemTime::now()
+                    }))?,
+                )
+                .await?;
+        }
+
+        if let Some(interface) = remediation {
+            let request = RemediationRequest {
+                id: Uuid::new_v4(),
+                instance,
+                action: RemediationAction::RestartInterface { interface: interface.clone() },
+                reason: format!("Gateway inalcanzable; fallan: {}", failing.join(", ")),
+                score: score.unwrap_or_default(),
+                timestamp: SystemTime::now(),
+            };
+            warn!("🛠️  Proponiendo reiniciar {} por consenso (puntuación {:.2})", interface, request.score);
+            fabric.publish(REMEDIATION_SUBJECT, &serde_json::to_vec(&request)?).await?;
+        }
+
+        debug!("🐕 Watchdog: {} destinos, puntuación {:?}", probes.len(), score);
+        Ok(())
+    }
+}
+
+/// Resultado de `RestartInterface`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceRestart {
+    pub interface: String,
+    /// Comandos ejecutados, o que se ejecutarían en dry-run
+    pub commands: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// Bajar y subir una interfaz existente
+pub async fn restart_interface(interface: &str, dry_run: bool) -> Result<InterfaceRestart> {
+    let name = interface.to_string();
+    let known = tokio::task::spawn_blocking(move || netinfo::interfaces().map(|all| all.iter().any(|i| i.name == name))).await??;
+    if !known {
+        return Err(NanoCoreError::ConfigError(anyhow!("Interfaz desconocida: {}", interface)).into());
+    }
+
+    let plan = if cfg!(target_os = "linux") {
+        vec![
+            Invocation::new("ip", &["link", "set", "dev", interface, "down"]),
+            Invocation::new("ip", &["link", "set", "dev", interface, "up"]),
+        ]
+    } else if cfg!(target_os = "macos") {
+        vec![Invocation::new("ifconfig", &[interface, "down"]), Invocation::new("ifconfig", &[interface, "up"])]
+    } else if cfg!(windows) {
+        let name = format!("name={}", interface);
+        vec![
+            Invocation::new("netsh", &["interface", "set", "interface", &name, "admin=disabled"]),
+            Invocation::new("netsh", &["interface", "set", "interface", &name, "admin=enabled"]),
+        ]
+    } else {
+        return Err(anyhow!("Reinicio de interfaces no soportado en esta plataforma"));
+    };
+
+    if !dry_run {
+        info!("🛠️  Reiniciando interfaz {}", interface);
+        for invocation in &plan {
+            invocation.run().await?;
+        }
+    }
+    Ok(InterfaceRestart {
+        interface: interface.to_string(),
+        commands: plan.iter().map(Invocation::display).collect(),
+        dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(name: &str, weight: f64) -> Probe {
+        Probe {
+            name: name.to_string(),
+            kind: ProbeKind::Host,
+            endpoint: Endpoint::Address("192.0.2.1:53".to_string()),
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_score_weights_targets_and_gates_remediation() {
+        let config = WatchdogConfig {
+            window: 4,
+            remediation: true,
+            remediation_rounds: 2,
+            remediation_cooldown_secs: 60,
+            ..Default::default()
+        };
+        let (gateway, api) = (probe(GATEWAY, 1.0), probe("api", 3.0));
+        let mut state = WatchdogState { gateway_interface: Some("eth0".to_string()), ..Default::default() };
+        assert_eq!(state.score(), None);
+
+        // api: 4 de 4; gateway: 1 de 4 con la ventana ya desplazada
+        for ok in [true, true, false, false, false] {
+            let result = if ok { Ok(Duration::from_millis(5)) } else { Err(anyhow!("timeout")) };
+            state.record(&gateway, &result, config.window);
+            state.record(&api, &Ok(Duration::from_millis(5)), config.window);
+        }
+        let score = state.score().unwrap();
+        assert!((score - (0.25 + 3.0) / 4.0).abs() < 1e-9);
+        assert_eq!(state.targets[GATEWAY].consecutive_failures, 3);
+
+        let now = Instant::now();
+        assert_eq!(state.remediation(&config, Some(0.3), now), None);
+        assert_eq!(state.remediation(&config, Some(0.3), now), Some("eth0".to_string()));
+        // Enfriamiento y puntuación recuperada
+        assert_eq!(state.remediation(&config, Some(0.3), now + Duration::from_secs(1)), None);
+        assert_eq!(state.remediation(&config, Some(0.3), now + Duration::from_secs(2)), None);
+        assert_eq!(state.remediation(&config, Some(0.9), now + Duration::from_secs(61)), None);
+        assert_eq!(state.low_rounds, 0);
+    }
+
+    #[tokio::test]
+    async fn test_host_probe_accepts_refused_connections() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        let closed = {
+            let other = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            other.local_addr().unwrap()
+        };
+        let fabric = CognitiveFabric::new("nats://127.0.0.1:1").await.unwrap();
+        let families = AddressFamilyConfig::default();
+        let timeout = Duration::from_secs(1);
+        let run = |kind, addr| {
+            let probe = Probe { name: "t".to_string(), kind, endpoint: Endpoint::Socket(addr), weight: 1.0 };
+            let (families, fabric) = (&families, &fabric);
+            async move { run_probe(&probe, families, fabric, timeout, "example.com").await }
+        };
+
+        assert!(run(ProbeKind::Tcp, open).await.is_ok());
+        assert!(run(ProbeKind::Tcp, closed).await.is_err());
+        assert!(run(ProbeKind::Host, closed).await.is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+//! Datos de los patrones de anomalía
+//!
+//! `ResourceAbuse` se evalúa contra la última instantánea de CPU/memoria de
+//! cada origen y `AccessAnomaly` contra la línea base de horas e IPs de cada
+//! usuario, aprendida de sus accesos autorizados.
+
+use chrono::{DateTime, Timelike, Utc};
+use std::collections::HashMap;
+
+/// Clave del contexto de un evento con el usuario
+pub const CONTEXT_USER: &str = "user";
+/// Clave del contexto de un evento con la IP de origen
+pub const CONTEXT_IP: &str = "ip";
+/// Claves del contexto con una medida de recursos adjunta al evento
+pub const CONTEXT_CPU: &str = "cpu_percent";
+pub const CONTEXT_MEMORY: &str = "memory_bytes";
+
+/// Accesos necesarios antes de juzgar a un usuario
+pub const MIN_OBSERVATIONS: u32 = 20;
+
+/// Uso de recursos de un origen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceSnapshot {
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ResourceSnapshot {
+    /// Medida adjunta al contexto de un evento, si la hay
+    pub fn from_context(context: &HashMap<String, String>, timestamp: DateTime<Utc>) -> Option<Self> {
+        let cpu_percent = context.get(CONTEXT_CPU).and_then(|v| v.parse().ok());
+        let memory_bytes = context.get(CONTEXT_MEMORY).and_then(|v| v.parse().ok());
+        if cpu_percent.is_none() && memory_bytes.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            cpu_percent: cpu_percent.unwrap_or(0.0),
+            memory_bytes: memory_bytes.unwrap_or(0),
+            timestamp,
+        })
+    }
+}
+
+/// Motivo de una anomalía de acceso
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessDeviation {
+    UnusualTime,
+    UnusualLocation,
+}
+
+impl AccessDeviation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessDeviation::UnusualTime => "unusual_time",
+            AccessDeviation::UnusualLocation => "unusual_location",
+        }
+    }
+}
+
+/// Horas (UTC) e IPs habituales de un usuario
+#[derive(Debug, Clone, Default)]
+pub struct AccessProfile {
+    hours: [u32; 24],
+    ips: HashMap<String, u32>,
+    observations: u32,
+}
+
+impl AccessProfile {
+    pub fn observations(&self) -> u32 {
+        self.observations
+    }
+
+    /// Una hora es habitual si hubo accesos en ella o en las contiguas
+    fn usual_hour(&self, hour: usize) -> bool {
+        [hour + 23, hour, hour + 1].iter().any(|h| self.hours[h % 24] > 0)
+    }
+}
+
+/// Líneas base de acceso por usuario
+#[derive(Debug, Clone, Default)]
+pub struct AccessBaselines {
+    profiles: HashMap<String, AccessProfile>,
+}
+
+impl AccessBaselines {
+    /// Desviaciones de un acceso respecto a la línea base del usuario
+    pub fn assess(&self, user: &str, ip: Option<&str>, at: DateTime<Utc>) -> Vec<AccessDeviation> {
+        let Some(profile) = self.profiles.get(user).filter(|p| p.observations >= MIN_OBSERVATIONS) else {
+            return Vec::new();
+        };
+
+        let mut deviations = Vec::new();
+        if !profile.usual_hour(at.hour() as usize) {
+            deviations.push(AccessDeviation::UnusualTime);
+        }
+        if ip.is_some_and(|ip| !profile.ips.contains_key(ip)) {
+            deviations.push(AccessDeviation::UnusualLocation);
+        }
+        deviations
+    }
+
+    /// Incorporar un acceso autorizado a la línea base
+    pub fn observe(&mut self, user: &str, ip: Option<&str>, at: DateTime<Utc>) {
+        let profile = self.profiles.entry(user.to_string()).or_default();
+        profile.hours[at.hour() as usize] += 1;
+        if let Some(ip) = ip {
+            *profile.ips.entry(ip.to_string()).or_insert(0) += 1;
+        }
+        profile.observations += 1;
+    }
+
+    pub fn profile(&self, user: &str) -> Option<&AccessProfile> {
+        self.profiles.get(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_access_baseline_deviations() {
+        let mut baselines = AccessBaselines::default();
+        let at = |hour| Utc.with_ymd_and_hms(2024, 5, 6, hour, 15, 0).unwrap();
+
+        for i in 0..MIN_OBSERVATIONS {
+            baselines.observe("ana", Some("10.0.0.7"), at(9 + i % 3));
+        }
+        let office_ip = Some("10.0.0.7");
+
+        assert!(baselines.assess("ana", office_ip, at(10)).is_empty());
+        // Las horas contiguas a las habituales no se consideran anómalas
+        assert!(baselines.assess("ana", office_ip, at(12)).is_empty());
+        assert_eq!(baselines.assess("ana", office_ip, at(3)), vec![AccessDeviation::UnusualTime]);
+        assert_eq!(
+            baselines.assess("ana", Some("203.0.113.9"), at(3)),
+            vec![AccessDeviation::UnusualTime, AccessDeviation::UnusualLocation]
+        );
+
+        // Sin suficientes observaciones no se juzga
+        baselines.observe("luis", Some("10.0.0.8"), at(9));
+        assert!(baselines.assess("luis", Some("203.0.113.9"), at(3)).is_empty());
+
+        let context = HashMap::from([(CONTEXT_CPU.to_string(), "97.5".to_string())]);
+        let snapshot = ResourceSnapshot::from_context(&context, at(10)).unwrap();
+        assert_eq!((snapshot.cpu_percent, snapshot.memory_bytes), (97.5, 0));
+        assert!(ResourceSnapshot::from_context(&HashMap::new(), at(10)).is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! Registro de auditoría persistente
+//!
+//! Cada `SecurityEvent` se añade como una línea JSON a segmentos rotados por
+//! tamaño. Los registros se encadenan con SHA-256 (hash del anterior + evento),
+//! de modo que editar o borrar una línea rompe la cadena y `verify()` lo detecta.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeBounds;
+use std::path::PathBuf;
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::{IntegrityVerifier, SecurityEvent, SecurityEventType, SecuritySeverity};
+
+/// Hash previo del primer registro
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Línea del registro de auditoría
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub sequence: u64,
+    pub prev_hash: String,
+    pub hash: String,
+    pub event: SecurityEvent,
+}
+
+impl AuditRecord {
+    fn compute_hash(sequence: u64, prev_hash: &str, event: &SecurityEvent) -> Result<String> {
+        // Vía `Value` las claves del contexto quedan ordenadas y el hash es estable
+        let payload = serde_json::to_vec(&serde_json::to_value(event)?)?;
+        let mut data = format!("{}:{}:", sequence, prev_hash).into_bytes();
+        data.extend_from_slice(&payload);
+        Ok(IntegrityVerifier::calculate_hash(&data))
+    }
+}
+
+/// Criterios de búsqueda de eventos
+#[derive(Debug, Clone, Default)]
+pub struct SecurityEventFilter {
+    pub event_type: Option<SecurityEventType>,
+    pub min_severity: Option<SecuritySeverity>,
+    pub source: Option<String>,
+}
+
+impl SecurityEventFilter {
+    pub fn matches(&self, event: &SecurityEvent) -> bool {
+        self.event_type.as_ref().is_none_or(|t| *t == event.event_type)
+            && self.min_severity.as_ref().is_none_or(|s| event.severity >= *s)
+            && self.source.as_ref().is_none_or(|s| *s == event.source)
+    }
+}
+
+/// Opciones del registro de auditoría
+#[derive(Debug, Clone)]
+pub struct AuditLogOptions {
+    pub dir: PathBuf,
+    /// Tamaño a partir del cual se abre un segmento nuevo
+    pub max_file_bytes: u64,
+    /// Segmentos conservados; los más antiguos se eliminan
+    pub max_files: usize,
+}
+
+impl AuditLogOptions {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 30,
+        }
+    }
+}
+
+struct Segment {
+    file: File,
+    size: u64,
+}
+
+struct AuditState {
+    segment: Option<Segment>,
+    sequence: u64,
+    last_hash: String,
+}
+
+/// Registro append-only con cadena de hashes
+pub struct AuditLog {
+    options: AuditLogOptions,
+    state: Mutex<AuditState>,
+}
+
+impl AuditLog {
+    /// Abrir el registro, continuando la cadena del último segmento
+    pub async fn open(options: AuditLogOptions) -> Result<Self> {
+        fs::create_dir_all(&options.dir).await?;
+
+        let mut state = AuditState {
+            segment: None,
+            sequence: 0,
+            last_hash: GENESIS_HASH.to_string(),
+        };
+
+        if let Some(last) = segments(&options.dir).await?.pop() {
+            // Una última línea truncada (caída a mitad de escritura) se ignora
+            if let Some(record) = read_segment(&last).await?.pop() {
+                state.sequence = record.sequence;
+                state.last_hash = record.hash;
+            }
+            info!("📜 Registro de auditoría reanudado en la secuencia {}", state.sequence);
+        }
+
+        Ok(Self {
+            options,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Añadir un evento al final de la cadena
+    pub async fn append(&self, event: &SecurityEvent) -> Result<AuditRecord> {
+        let mut state = self.state.lock().await;
+        let sequence = state.sequence + 1;
+        let record = AuditRecord {
+            sequence,
+            hash: AuditRecord::compute_hash(sequence, &state.last_hash, event)?,
+            prev_hash: state.last_hash.clone(),
+            event: event.clone(),
+        };
+
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+
+        let rotate = match &state.segment {
+            Some(segment) => segment.size + line.len() as u64 > self.options.max_file_bytes,
+            None => true,
+        };
+        if rotate {
+            state.segment = Some(self.open_segment(sequence).await?);
+        }
+
+        if let Some(segment) = state.segment.as_mut() {
+            segment.file.write_all(&line).await?;
+            segment.file.sync_data().await?;
+            segment.size += line.len() as u64;
+        }
+
+        state.sequence = sequence;
+        state.last_hash = record.hash.clone();
+        Ok(record)
+    }
+
+    /// Segmento nuevo nombrado por su primera secuencia, podando los antiguos
+    async fn open_segment(&self, first_sequence: u64) -> Result<Segment> {
+        let path = self.options.dir.join(format!("audit-{:020}.jsonl", first_sequence));
+        let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+        let size = file.metadata().await?.len();
+
+        let existing = segments(&self.options.dir).await?;
+        let excess = existing.len().saturating_sub(self.options.max_files.max(1));
+        for old in &existing[..excess] {
+            fs::remove_file(old).await?;
+            info!("🗑️  Segmento de auditoría eliminado por rotación: {}", old.display());
+        }
+
+        Ok(Segment { file, size })
+    }
+
+    /// Leer del disco los eventos que cumplen el filtro dentro del rango
+    pub async fn query(
+        &self,
+        filter: &SecurityEventFilter,
+        range: impl RangeBounds<DateTime<Utc>>,
+    ) -> Result<Vec<SecurityEvent>> {
+        let mut events = Vec::new();
+
+        for segment in segments(&self.options.dir).await? {
+            for record in read_segment(&segment).await? {
+                if range.contains(&record.event.timestamp) && filter.matches(&record.event) {
+                    events.push(record.event);
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Comprobar la cadena completa; devuelve el número de registros
+    ///
+    /// El primer registro conservado tras una rotación actúa como ancla.
+    pub async fn verify(&self) -> Result<u64> {
+        let _state = self.state.lock().await;
+        let mut previous: Option<AuditRecord> = None;
+        let mut count = 0;
+
+        for segment in segments(&self.options.dir).await? {
+            for record in read_segment(&segment).await? {
+                if let Some(previous) = &previous {
+                    if record.sequence != previous.sequence + 1 || record.prev_hash != previous.hash {
+                        return Err(anyhow!("Cadena de auditoría rota en la secuencia {}", record.sequence));
+                    }
+                }
+
+                let expected = AuditRecord::compute_hash(record.sequence, &record.prev_hash, &record.event)?;
+                if expected != record.hash {
+                    return Err(anyhow!("Registro de auditoría alterado en la secuencia {}", record.sequence));
+                }
+
+                count += 1;
+                previous = Some(record);
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+/// Segmentos ordenados del más antiguo al más reciente
+async fn segments(dir: &std::path::Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with("audit-") && name.ends_with(".jsonl") {
+            paths.push(entry.path());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+async fn read_segment(path: &std::path::Path) -> Result<Vec<AuditRecord>> {
+    let content = fs::read_to_string(path).await?;
+    let mut records = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(e) => warn!("⚠️  Línea de auditoría ilegible en {}:{}: {}", path.display(), index + 1, e),
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn event(event_type: SecurityEventType, severity: SecuritySeverity, description: &str) -> SecurityEvent {
+        SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type,
+            severity,
+            source: "test".to_string(),
+            target: None,
+            description: description.to_string(),
+            context: HashMap::from([
+                ("user".to_string(), "admin".to_string()),
+                ("ip".to_string(), "10.0.0.7".to_string()),
+                ("attempt".to_string(), description.to_string()),
+            ]),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_chain_rotation_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = AuditLogOptions {
+            max_file_bytes: 600,
+            ..AuditLogOptions::new(dir.path())
+        };
+
+        let log = AuditLog::open(options.clone()).await.unwrap();
+        for i in 0..4 {
+            log.append(&event(SecurityEventType::AuthenticationFailure, SecuritySeverity::Low, &format!("fallo {}", i)))
+                .await
+                .unwrap();
+        }
+        drop(log);
+
+        // Reabrir continúa la cadena
+        let log = AuditLog::open(options).await.unwrap();
+        let record = log
+            .append(&event(SecurityEventType::IntegrityViolation, SecuritySeverity::Critical, "checksum"))
+            .await
+            .unwrap();
+        assert_eq!(record.sequence, 5);
+        assert!(segments(dir.path()).await.unwrap().len() > 1);
+        assert_eq!(log.verify().await.unwrap(), 5);
+
+        let critical = SecurityEventFilter {
+            min_severity: Some(SecuritySeverity::High),
+            ..Default::default()
+        };
+        let found = log.query(&critical, ..).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].description, "checksum");
+        assert!(log.query(&SecurityEventFilter::default(), ..Utc::now() - chrono::Duration::hours(1)).await.unwrap().is_empty());
+
+        // Alterar una línea rompe la cadena
+        let first = &segments(dir.path()).await.unwrap()[0];
+        let content = std::fs::read_to_string(first).unwrap().replace("fallo 0", "fallo X");
+        std::fs::write(first, content).unwrap();
+        assert!(log.verify().await.is_err());
+    }
+}
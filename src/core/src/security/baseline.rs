@@ -0,0 +1,193 @@
+//! Líneas base estadísticas de frecuencia de eventos
+//!
+//! Para cada patrón `FrequencyAnomaly` adaptativo se cuenta cuántos eventos
+//! de cada tipo llegan de cada origen por ventana, y al cerrarse cada ventana
+//! se actualizan la media y la varianza con un EWMA. Un recuento cuya
+//! puntuación z supera el umbral del patrón es anómalo para ese despliegue.
+//! Las líneas base se serializan para no volver a aprender tras un reinicio.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::keystore::write_private;
+
+/// Peso de cada ventana nueva en el EWMA
+pub const EWMA_ALPHA: f64 = 0.1;
+
+/// Ventanas observadas antes de confiar en la línea base
+pub const MIN_OBSERVATIONS: u64 = 12;
+
+/// Ventanas vacías que se incorporan como máximo tras un hueco
+const MAX_IDLE_UPDATES: u64 = 100;
+
+/// Media y varianza de una serie de recuentos por ventana
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateBaseline {
+    pub mean: f64,
+    pub variance: f64,
+    /// Ventanas incorporadas a la media
+    pub observations: u64,
+    pub bucket_start: DateTime<Utc>,
+    /// Eventos de la ventana en curso
+    pub bucket_count: u64,
+}
+
+impl RateBaseline {
+    fn new(bucket_start: DateTime<Utc>) -> Self {
+        Self {
+            mean: 0.0,
+            variance: 0.0,
+            observations: 0,
+            bucket_start,
+            bucket_count: 0,
+        }
+    }
+
+    fn update(&mut self, count: f64) {
+        let delta = count - self.mean;
+        self.mean += EWMA_ALPHA * delta;
+        self.variance = (1.0 - EWMA_ALPHA) * (self.variance + EWMA_ALPHA * delta * delta);
+        self.observations += 1;
+    }
+
+    /// Cerrar las ventanas transcurridas hasta la que contiene `at`
+    fn advance(&mut self, at: DateTime<Utc>, bucket: Duration) {
+        let elapsed = (at - self.bucket_start).num_milliseconds() / bucket.num_milliseconds().max(1);
+        if elapsed <= 0 {
+            return;
+        }
+
+        self.update(self.bucket_count as f64);
+        for _ in 1..(elapsed as u64).min(MAX_IDLE_UPDATES) {
+            self.update(0.0);
+        }
+        self.bucket_start += bucket * elapsed as i32;
+        self.bucket_count = 0;
+    }
+
+    /// Desviación típica, con un mínimo de Poisson para series casi constantes
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt().max(self.mean.sqrt()).max(1.0)
+    }
+
+    pub fn z_score(&self, count: u64) -> f64 {
+        (count as f64 - self.mean) / self.std_dev()
+    }
+
+    pub fn is_learned(&self) -> bool {
+        self.observations >= MIN_OBSERVATIONS
+    }
+}
+
+/// Recuento de la ventana en curso frente a su línea base
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateAssessment {
+    pub count: u64,
+    pub mean: f64,
+    pub z_score: f64,
+    pub learned: bool,
+}
+
+/// Líneas base por patrón, y dentro de cada uno por `tipo/origen`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventBaselines {
+    pub patterns: BTreeMap<String, BTreeMap<String, RateBaseline>>,
+}
+
+impl EventBaselines {
+    /// Leer las líneas base guardadas; sin archivo se empieza de cero
+    pub async fn load(path: &Path) -> Result<Self> {
+        match tokio::fs::read(path).await {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_private(path, &serde_json::to_vec(self)?).await
+    }
+
+    /// Contar un evento en su ventana de `bucket` y evaluarlo
+    pub fn observe(&mut self, pattern_id: &str, key: &str, at: DateTime<Utc>, bucket: Duration) -> RateAssessment {
+        let baseline = self
+            .patterns
+            .entry(pattern_id.to_string())
+            .or_default()
+            .entry(key.to_string())
+            .or_insert_with(|| RateBaseline::new(at));
+
+        baseline.advance(at, bucket);
+        baseline.bucket_count += 1;
+
+        RateAssessment {
+            count: baseline.bucket_count,
+            mean: baseline.mean,
+            z_score: baseline.z_score(baseline.bucket_count),
+            learned: baseline.is_learned(),
+        }
+    }
+
+    /// Descartar las líneas base de patrones que ya no existen
+    pub fn retain_patterns(&mut self, ids: &[&str]) {
+        self.patterns.retain(|id, _| ids.contains(&id.as_str()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_baseline_learns_rate_and_flags_spikes() {
+        let mut baselines = EventBaselines::default();
+        let bucket = Duration::minutes(5);
+        let start = Utc::now();
+
+        // Tres eventos por ventana durante una hora y media
+        for window in 0..18 {
+            for i in 0..3 {
+                let at = start + bucket * window + Duration::seconds(i * 10);
+                baselines.observe("freq", "AuthenticationFailure/api", at, bucket);
+            }
+        }
+
+        let at = start + bucket * 18;
+        let normal = baselines.observe("freq", "AuthenticationFailure/api", at, bucket);
+        assert!(normal.learned);
+        assert!((normal.mean - 3.0).abs() < 0.6);
+        assert!(normal.z_score < 0.0);
+
+        let mut spike = normal;
+        for i in 1..12 {
+            spike = baselines.observe("freq", "AuthenticationFailure/api", at + Duration::seconds(i), bucket);
+        }
+        assert_eq!(spike.count, 12);
+        assert!(spike.z_score > 3.0);
+
+        // Otro origen aún no tiene línea base propia
+        assert!(!baselines.observe("freq", "AuthenticationFailure/batch", at, bucket).learned);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baselines.json");
+        assert!(EventBaselines::load(&path).await.unwrap().is_empty());
+        baselines.save(&path).await.unwrap();
+        let restored = EventBaselines::load(&path).await.unwrap();
+        assert_eq!(restored, baselines);
+        assert_eq!(restored.len(), 2);
+
+        baselines.retain_patterns(&[]);
+        assert!(baselines.is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! Certificados X.509 para mTLS entre componentes SAAI
+//!
+//! Una CA propia del nodo (guardada en `cert_dir`) emite certificados de
+//! corta duración para cada servicio (fabric, métricas, administración), con
+//! uso de servidor y cliente. Los certificados se renuevan antes de caducar
+//! o al cambiar sus nombres, y la CA se regenera cuando le queda menos vida
+//! que a un certificado emitido.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+    SerialNumber,
+};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::keystore::write_private;
+
+/// Nombre común de la CA del nodo
+const CA_COMMON_NAME: &str = "SAAI Node CA";
+
+/// Margen de `not_before` frente a relojes desincronizados
+const CLOCK_SKEW_SECS: i64 = 300;
+
+/// Certificado de servicio emitido por la CA
+#[derive(Debug, Clone)]
+pub struct IssuedCert {
+    pub service: String,
+    pub names: Vec<String>,
+    pub cert_pem: String,
+    pub key_pem: String,
+    pub not_after: DateTime<Utc>,
+}
+
+struct CertAuthority {
+    /// Emisor para rcgen; al cargar la CA se vuelve a firmar y difiere de `pem`
+    cert: Certificate,
+    pem: String,
+    key: KeyPair,
+    not_after: DateTime<Utc>,
+}
+
+/// CA del nodo y certificados vigentes por servicio
+pub struct CertManager {
+    dir: PathBuf,
+    ca: CertAuthority,
+    /// Con una CA recién creada los certificados en disco no son suyos
+    reuse_stored: bool,
+    certs: BTreeMap<String, IssuedCert>,
+    pub rotations_total: u64,
+}
+
+impl CertManager {
+    /// Cargar la CA y los certificados de `dir`, o crear una CA nueva
+    pub async fn open(dir: impl Into<PathBuf>, ca_validity: Duration) -> Result<Self> {
+        let dir = dir.into();
+        let (ca, reuse_stored) = match load_ca(&dir).await? {
+            Some(ca) if ca.not_after > Utc::now() => (ca, true),
+            _ => (generate_ca(&dir, ca_validity).await?, false),
+        };
+
+        Ok(Self {
+            dir,
+            ca,
+            reuse_stored,
+            certs: BTreeMap::new(),
+            rotations_total: 0,
+        })
+    }
+
+    /// CA en PEM, para confiar en ella en los clientes
+    pub fn ca_pem(&self) -> String {
+        self.ca.pem.clone()
+    }
+
+    pub fn ca_expires_at(&self) -> DateTime<Utc> {
+        self.ca.not_after
+    }
+
+    pub fn certificate(&self, service: &str) -> Option<&IssuedCert> {
+        self.certs.get(service)
+    }
+
+    /// Caducidad de la CA (`ca`) y de cada certificado de servicio
+    pub fn expiries(&self) -> BTreeMap<String, DateTime<Utc>> {
+        std::iter::once(("ca".to_string(), self.ca.not_after))
+            .chain(self.certs.values().map(|cert| (cert.service.clone(), cert.not_after)))
+            .collect()
+    }
+
+    /// Emitir un certificado para `service` válido para `names` (DNS o IP)
+    pub async fn issue(&mut self, service: &str, names: &[String], validity: Duration) -> Result<&IssuedCert> {
+        let now = Utc::now();
+        let not_after = now + validity;
+        if not_after > self.ca.not_after {
+            return Err(anyhow!("La CA caduca antes que el certificado de {}", service));
+        }
+
+        let mut params = CertificateParams::new(names.to_vec())?;
+        params.distinguished_name.push(DnType::CommonName, service);
+        params.distinguished_name.push(DnType::OrganizationName, "SAAI");
+        params.serial_number = Some(random_serial()?);
+        params.not_before = to_offset(now - Duration::seconds(CLOCK_SKEW_SECS))?;
+        params.not_after = to_offset(not_after)?;
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature, KeyUsagePurpose::KeyEncipherment];
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth, ExtendedKeyUsagePurpose::ClientAuth];
+        params.use_authority_key_identifier_extension = true;
+
+        let key = KeyPair::generate()?;
+        let cert = params.signed_by(&key, &self.ca.cert, &self.ca.key)?;
+        let issued = IssuedCert {
+            service: service.to_string(),
+            names: names.to_vec(),
+            cert_pem: cert.pem(),
+            key_pem: key.serialize_pem(),
+            not_after,
+        };
+
+        write_private(&self.dir.join(format!("{}.key", service)), issued.key_pem.as_bytes()).await?;
+        write_private(&self.dir.join(format!("{}.pem", service)), issued.cert_pem.as_bytes()).await?;
+
+        self.rotations_total += 1;
+        self.certs.insert(service.to_string(), issued);
+        self.certs
+            .get(service)
+            .ok_or_else(|| anyhow!("Certificado de {} no registrado", service))
+    }
+
+    /// Renovar la CA y los certificados que caducan en menos de `renew_before`
+    ///
+    /// Los certificados aún vigentes en disco se reutilizan al arrancar.
+    /// Devuelve los servicios renovados (`ca` si se regeneró la CA).
+    pub async fn rotate_due(
+        &mut self,
+        services: &BTreeMap<String, Vec<String>>,
+        validity: Duration,
+        ca_validity: Duration,
+        renew_before: Duration,
+    ) -> Result<Vec<String>> {
+        let now = Utc::now();
+        let mut renewed = Vec::new();
+
+        if self.ca.not_after - now < validity + renew_before {
+            self.ca = generate_ca(&self.dir, ca_validity).await?;
+            self.reuse_stored = false;
+            self.certs.clear();
+            self.rotations_total += 1;
+            renewed.push("ca".to_string());
+        }
+
+        self.certs.retain(|service, _| services.contains_key(service));
+
+        for (service, names) in services {
+            if self.reuse_stored && !self.certs.contains_key(service) {
+                if let Some(cert) = self.load_issued(service).await {
+                    self.certs.insert(service.clone(), cert);
+                }
+            }
+
+            let due = self
+                .certs
+                .get(service)
+                .is_none_or(|cert| cert.names != *names || cert.not_after - now < renew_before);
+            if due {
+                self.issue(service, names, validity).await?;
+                renewed.push(service.clone());
+            }
+        }
+
+        // Tras la primera pasada todos los servicios están en memoria
+        self.reuse_stored = false;
+        Ok(renewed)
+    }
+
+    /// Certificado de `service` guardado en disco
+    async fn load_issued(&self, service: &str) -> Option<IssuedCert> {
+        let cert_pem = tokio::fs::read_to_string(self.dir.join(format!("{}.pem", service))).await.ok()?;
+        let key_pem = tokio::fs::read_to_string(self.dir.join(format!("{}.key", service))).await.ok()?;
+        let params = CertificateParams::from_ca_cert_pem(&cert_pem).ok()?;
+
+        Some(IssuedCert {
+            service: service.to_string(),
+            names: params.subject_alt_names.iter().filter_map(san_name).collect(),
+            cert_pem,
+            key_pem,
+            not_after: from_offset(params.not_after)?,
+        })
+    }
+}
+
+async fn load_ca(dir: &Path) -> Result<Option<CertAuthority>> {
+    let (Ok(cert_pem), Ok(key_pem)) = (
+        tokio::fs::read_to_string(dir.join("ca.pem")).await,
+        tokio::fs::read_to_string(dir.join("ca.key")).await,
+    ) else {
+        return Ok(None);
+    };
+
+    let key = KeyPair::from_pem(&key_pem)?;
+    let params = CertificateParams::from_ca_cert_pem(&cert_pem)?;
+    let not_after = from_offset(params.not_after).ok_or_else(|| anyhow!("Caducidad de la CA inválida"))?;
+    // rcgen necesita el certificado del emisor; se reconstruye con el mismo nombre y clave
+    let cert = params.self_signed(&key)?;
+    Ok(Some(CertAuthority { cert, pem: cert_pem, key, not_after }))
+}
+
+async fn generate_ca(dir: &Path, validity: Duration) -> Result<CertAuthority> {
+    let now = Utc::now();
+    let not_after = now + validity;
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(DnType::CommonName, CA_COMMON_NAME);
+    params.distinguished_name.push(DnType::OrganizationName, "SAAI");
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign, KeyUsagePurpose::DigitalSignature];
+    params.serial_number = Some(random_serial()?);
+    params.not_before = to_offset(now - Duration::seconds(CLOCK_SKEW_SECS))?;
+    params.not_after = to_offset(not_after)?;
+
+    let key = KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+    write_private(&dir.join("ca.key"), key.serialize_pem().as_bytes()).await?;
+    let pem = cert.pem();
+    write_private(&dir.join("ca.pem"), pem.as_bytes()).await?;
+
+    Ok(CertAuthority { cert, pem, key, not_after })
+}
+
+fn san_name(san: &rcgen::SanType) -> Option<String> {
+    match san {
+        rcgen::SanType::DnsName(name) => Some(name.as_str().to_string()),
+        rcgen::SanType::IpAddress(ip) => Some(ip.to_string()),
+        _ => None,
+    }
+}
+
+fn random_serial() -> Result<SerialNumber> {
+    let mut serial = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut serial)
+        .map_err(|_| anyhow!("No se pudo generar el número de serie"))?;
+    // Positivo en DER
+    serial[0] &= 0x7f;
+    Ok(SerialNumber::from(serial.to_vec()))
+}
+
+fn to_offset(at: DateTime<Utc>) -> Result<time::OffsetDateTime> {
+    Ok(time::OffsetDateTime::from_unix_timestamp(at.timestamp())?)
+}
+
+fn from_offset(at: time::OffsetDateTime) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(at.unix_timestamp(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_issue_reuse_and_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let services = BTreeMap::from([("fabric".to_string(), vec!["localhost".to_string(), "127.0.0.1".to_string()])]);
+        let (validity, ca_validity, renew_before) = (Duration::hours(24), Duration::days(30), Duration::hours(8));
+
+        let mut certs = CertManager::open(dir.path(), ca_validity).await.unwrap();
+        assert_eq!(certs.rotate_due(&services, validity, ca_validity, renew_before).await.unwrap(), vec!["fabric"]);
+        assert!(certs.rotate_due(&services, validity, ca_validity, renew_before).await.unwrap().is_empty());
+        let issued = certs.certificate("fabric").unwrap().clone();
+        assert!(issued.cert_pem.starts_with("-----BEGIN CERTIFICATE-----"));
+
+        // Al reabrir se reutilizan la CA y los certificados vigentes
+        let mut reopened = CertManager::open(dir.path(), ca_validity).await.unwrap();
+        assert_eq!(reopened.ca_pem(), certs.ca_pem());
+        assert!(reopened.rotate_due(&services, validity, ca_validity, renew_before).await.unwrap().is_empty());
+        assert_eq!(reopened.certificate("fabric").unwrap().names, issued.names);
+
+        // Nombres nuevos o vida restante por debajo del margen renuevan
+        let renamed = BTreeMap::from([("fabric".to_string(), vec!["fabric.saai.local".to_string()])]);
+        assert_eq!(reopened.rotate_due(&renamed, validity, ca_validity, renew_before).await.unwrap(), vec!["fabric"]);
+        assert_eq!(reopened.rotate_due(&renamed, validity, ca_validity, Duration::hours(25)).await.unwrap(), vec!["fabric"]);
+
+        // Una CA que caduca antes que los certificados se regenera con todos ellos
+        let renewed = reopened.rotate_due(&renamed, Duration::days(31), Duration::days(60), renew_before).await.unwrap();
+        assert_eq!(renewed, vec!["ca", "fabric"]);
+        assert_ne!(reopened.ca_pem(), certs.ca_pem());
+        assert_eq!(reopened.expiries().len(), 2);
+    }
+}
@@ -0,0 +1,193 @@
+//! Monitorización de integridad de archivos
+//!
+//! Mantiene una línea base de checksums SHA-256 de las rutas vigiladas (el
+//! binario de saai-core, la configuración, directorios de plugins recorridos
+//! recursivamente) y la compara en cada verificación. Cada cambio se notifica
+//! una sola vez hasta que vuelve a su estado original o se acepta con
+//! `rebaseline`.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use super::IntegrityVerifier;
+
+/// Diferencia de un archivo respecto a la línea base
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityChange {
+    Modified { expected: String, actual: String },
+    Missing,
+    /// Archivo nuevo dentro de un directorio vigilado
+    Added { actual: String },
+}
+
+impl fmt::Display for IntegrityChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityChange::Modified { .. } => write!(f, "modificado"),
+            IntegrityChange::Missing => write!(f, "eliminado"),
+            IntegrityChange::Added { .. } => write!(f, "añadido"),
+        }
+    }
+}
+
+/// Violación de integridad detectada en una verificación
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrityViolation {
+    pub path: PathBuf,
+    pub change: IntegrityChange,
+}
+
+/// Línea base de checksums y estado de las verificaciones
+#[derive(Debug, Default)]
+pub struct IntegrityMonitor {
+    paths: Vec<PathBuf>,
+    baseline: BTreeMap<PathBuf, String>,
+    /// Último estado notificado de cada archivo que difiere (None = eliminado)
+    reported: HashMap<PathBuf, Option<String>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub violations_total: u64,
+}
+
+impl IntegrityMonitor {
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn baseline(&self) -> &BTreeMap<PathBuf, String> {
+        &self.baseline
+    }
+
+    /// Vigilar `paths` tomando su estado actual como línea base
+    pub async fn set_paths(&mut self, paths: Vec<PathBuf>) -> Result<usize> {
+        self.paths = paths;
+        self.rebaseline().await
+    }
+
+    /// Aceptar el estado actual de las rutas como nueva línea base
+    pub async fn rebaseline(&mut self) -> Result<usize> {
+        for path in &self.paths {
+            if tokio::fs::symlink_metadata(path).await.is_err() {
+                warn!("⚠️  Ruta de integridad no encontrada: {}", path.display());
+            }
+        }
+
+        self.baseline = scan(&self.paths).await?;
+        self.reported.clear();
+        Ok(self.baseline.len())
+    }
+
+    /// Comparar el estado actual con la línea base; devuelve solo los cambios nuevos
+    pub async fn verify(&mut self) -> Result<Vec<IntegrityViolation>> {
+        let current = scan(&self.paths).await?;
+        let mut observed: Vec<(PathBuf, Option<String>, IntegrityChange)> = Vec::new();
+
+        for (path, expected) in &self.baseline {
+            match current.get(path) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => observed.push((
+                    path.clone(),
+                    Some(actual.clone()),
+                    IntegrityChange::Modified { expected: expected.clone(), actual: actual.clone() },
+                )),
+                None => observed.push((path.clone(), None, IntegrityChange::Missing)),
+            }
+        }
+
+        for (path, actual) in &current {
+            if !self.baseline.contains_key(path) {
+                observed.push((path.clone(), Some(actual.clone()), IntegrityChange::Added { actual: actual.clone() }));
+            }
+        }
+
+        // Los archivos que vuelven a su estado original dejan de estar notificados
+        self.reported.retain(|path, _| observed.iter().any(|(observed, ..)| observed == path));
+
+        let mut violations = Vec::new();
+        for (path, state, change) in observed {
+            if self.reported.get(&path) != Some(&state) {
+                self.reported.insert(path.clone(), state);
+                violations.push(IntegrityViolation { path, change });
+            }
+        }
+
+        self.verified_at = Some(Utc::now());
+        self.violations_total += violations.len() as u64;
+        Ok(violations)
+    }
+}
+
+/// Checksums de los archivos bajo `paths`; las rutas inexistentes se omiten
+async fn scan(paths: &[PathBuf]) -> Result<BTreeMap<PathBuf, String>> {
+    let mut checksums = BTreeMap::new();
+    let mut pending: Vec<PathBuf> = paths.to_vec();
+
+    while let Some(path) = pending.pop() {
+        // Sin seguir enlaces a directorios para no entrar en ciclos
+        let Ok(metadata) = tokio::fs::symlink_metadata(&path).await else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            let mut entries = tokio::fs::read_dir(&path).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                pending.push(entry.path());
+            }
+        } else if let Some(checksum) = checksum(&path).await {
+            checksums.insert(path, checksum);
+        }
+    }
+
+    Ok(checksums)
+}
+
+async fn checksum(path: &Path) -> Option<String> {
+    match tokio::fs::read(path).await {
+        Ok(data) => Some(IntegrityVerifier::calculate_hash(&data)),
+        // Enlace a un directorio o archivo desaparecido durante el recorrido
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_baseline_detects_changes_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let plugins = dir.join("plugins");
+        tokio::fs::create_dir_all(&plugins).await.unwrap();
+        tokio::fs::write(dir.join("core.toml"), "log_level = \"info\"").await.unwrap();
+        tokio::fs::write(plugins.join("a.so"), b"plugin").await.unwrap();
+
+        let mut monitor = IntegrityMonitor::default();
+        assert_eq!(monitor.set_paths(vec![dir.join("core.toml"), plugins.clone()]).await.unwrap(), 2);
+        assert!(monitor.verify().await.unwrap().is_empty());
+
+        tokio::fs::write(dir.join("core.toml"), "log_level = \"trace\"").await.unwrap();
+        tokio::fs::remove_file(plugins.join("a.so")).await.unwrap();
+        tokio::fs::write(plugins.join("b.so"), b"intruso").await.unwrap();
+
+        let violations = monitor.verify().await.unwrap();
+        assert_eq!(violations.len(), 3);
+        assert!(violations.iter().any(|v| v.path == plugins.join("a.so") && v.change == IntegrityChange::Missing));
+        assert!(violations.iter().any(|v| matches!(v.change, IntegrityChange::Added { .. })));
+        assert!(violations.iter().any(|v| matches!(v.change, IntegrityChange::Modified { .. })));
+
+        // Sin cambios nuevos no se repite la notificación
+        assert!(monitor.verify().await.unwrap().is_empty());
+        tokio::fs::write(dir.join("core.toml"), "log_level = \"info\"").await.unwrap();
+        assert!(monitor.verify().await.unwrap().is_empty());
+        tokio::fs::write(dir.join("core.toml"), "log_level = \"debug\"").await.unwrap();
+        assert_eq!(monitor.verify().await.unwrap().len(), 1);
+        assert_eq!(monitor.violations_total, 4);
+
+        assert_eq!(monitor.rebaseline().await.unwrap(), 2);
+        assert!(monitor.verify().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+//! Claves de encriptación versionadas
+//!
+//! Cada texto cifrado empieza por el identificador de la clave que lo selló.
+//! Tras una rotación se conservan las últimas N claves, de modo que los
+//! mensajes en vuelo siguen descifrándose y los datos de larga vida pueden
+//! re-encriptarse con la clave actual antes de que la suya caduque. El
+//! anillo se exporta como `StoredKeyRing` para que un `KeyProvider` lo
+//! conserve entre reinicios.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use ring::aead;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Bytes del identificador de clave al inicio del texto cifrado
+pub const KEY_ID_LEN: usize = 4;
+
+/// Claves anteriores conservadas por defecto
+pub const DEFAULT_RETAINED_KEYS: usize = 3;
+
+struct VersionedKey {
+    id: u32,
+    key: aead::LessSafeKey,
+    material: Vec<u8>,
+    created_at: DateTime<Utc>,
+}
+
+/// Material de una clave exportada
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredKey {
+    pub id: u32,
+    /// Bytes de la clave en hexadecimal
+    pub material: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Anillo exportado, de la clave más antigua a la actual
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StoredKeyRing {
+    pub keys: Vec<StoredKey>,
+}
+
+/// Clave actual más las anteriores que aún pueden descifrar
+pub struct KeyRing {
+    algorithm: &'static aead::Algorithm,
+    rng: SystemRandom,
+    /// De la más antigua a la actual
+    keys: VecDeque<VersionedKey>,
+    retained: usize,
+}
+
+impl KeyRing {
+    /// Crear con una primera clave aleatoria
+    pub fn new(algorithm: &'static aead::Algorithm, retained: usize) -> Result<Self> {
+        let mut ring = Self {
+            algorithm,
+            rng: SystemRandom::new(),
+            keys: VecDeque::new(),
+            retained,
+        };
+        ring.rotate()?;
+        Ok(ring)
+    }
+
+    /// Reconstruir un anillo exportado con `export`
+    pub fn restore(algorithm: &'static aead::Algorithm, retained: usize, stored: &StoredKeyRing) -> Result<Self> {
+        if stored.keys.is_empty() {
+            return Err(anyhow!("Anillo de claves guardado vacío"));
+        }
+
+        let mut ring = Self {
+            algorithm,
+            rng: SystemRandom::new(),
+            keys: VecDeque::new(),
+            retained,
+        };
+        for key in &stored.keys {
+            let material = hex::decode(&key.material)
+                .map_err(|_| anyhow!("Material de la clave {} no es hexadecimal", key.id))?;
+            ring.keys.push_back(versioned_key(algorithm, key.id, material, key.created_at)?);
+        }
+        ring.set_retained(retained);
+        Ok(ring)
+    }
+
+    /// Exportar el material de todas las claves
+    pub fn export(&self) -> StoredKeyRing {
+        StoredKeyRing {
+            keys: self
+                .keys
+                .iter()
+                .map(|k| StoredKey {
+                    id: k.id,
+                    material: hex::encode(&k.material),
+                    created_at: k.created_at,
+                })
+                .collect(),
+        }
+    }
+
+    /// Generar una clave nueva y descartar las que excedan la retención
+    pub fn rotate(&mut self) -> Result<u32> {
+        let mut material = vec![0u8; self.algorithm.key_len()];
+        self.rng
+            .fill(&mut material)
+            .map_err(|_| anyhow!("No se pudo generar la clave de encriptación"))?;
+
+        let id = self.keys.back().map_or(1, |current| current.id.wrapping_add(1));
+        self.keys.push_back(versioned_key(self.algorithm, id, material, Utc::now())?);
+
+        while self.keys.len() > self.retained + 1 {
+            self.keys.pop_front();
+        }
+
+        Ok(id)
+    }
+
+    pub fn retained(&self) -> usize {
+        self.retained
+    }
+
+    /// Cambiar cuántas claves anteriores se conservan
+    pub fn set_retained(&mut self, retained: usize) {
+        self.retained = retained;
+        while self.keys.len() > retained + 1 {
+            self.keys.pop_front();
+        }
+    }
+
+    fn current(&self) -> Result<&VersionedKey> {
+        self.keys.back().ok_or_else(|| anyhow!("Sin clave de encriptación activa"))
+    }
+
+    pub fn current_id(&self) -> Result<u32> {
+        Ok(self.current()?.id)
+    }
+
+    /// Momento de la última rotación
+    pub fn rotated_at(&self) -> Result<DateTime<Utc>> {
+        Ok(self.current()?.created_at)
+    }
+
+    /// Identificadores disponibles, de la más antigua a la actual
+    pub fn key_ids(&self) -> Vec<u32> {
+        self.keys.iter().map(|k| k.id).collect()
+    }
+
+    /// Identificador de la clave que selló `ciphertext`
+    pub fn key_id(ciphertext: &[u8]) -> Option<u32> {
+        let header: [u8; KEY_ID_LEN] = ciphertext.get(..KEY_ID_LEN)?.try_into().ok()?;
+        Some(u32::from_be_bytes(header))
+    }
+
+    /// Clave actual para sellar fuera del anillo (p. ej. un flujo largo)
+    pub(crate) fn sealing_key(&self) -> Result<(u32, aead::LessSafeKey)> {
+        let current = self.current()?;
+        Ok((current.id, current.key.clone()))
+    }
+
+    /// Clave `id` para abrir fuera del anillo
+    pub(crate) fn opening_key(&self, id: u32) -> Result<aead::LessSafeKey> {
+        Ok(self.find(id)?.key.clone())
+    }
+
+    fn find(&self, id: u32) -> Result<&VersionedKey> {
+        self.keys
+            .iter()
+            .find(|k| k.id == id)
+            .ok_or_else(|| anyhow!("Clave de encriptación {} ya no disponible", id))
+    }
+
+    /// Encriptar con la clave actual: `key_id || nonce || datos + tag`
+    pub fn seal(&self, data: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let current = self.current()?;
+
+        let mut nonce_bytes = [0u8; aead::NONCE_LEN];
+        self.rng
+            .fill(&mut nonce_bytes)
+            .map_err(|_| anyhow!("No se pudo generar el nonce"))?;
+        let nonce = aead::Nonce::assume_unique_for_key(nonce_bytes);
+
+        let header = current.id.to_be_bytes();
+        let mut in_out = data.to_vec();
+        current
+            .key
+            .seal_in_place_append_tag(nonce, aead::Aad::from(aad(&header, associated_data)), &mut in_out)
+            .map_err(|_| anyhow!("Error encriptando datos"))?;
+
+        let mut result = Vec::with_capacity(KEY_ID_LEN + aead::NONCE_LEN + in_out.len());
+        result.extend_from_slice(&header);
+        result.extend_from_slice(&nonce_bytes);
+        result.extend_from_slice(&in_out);
+        Ok(result)
+    }
+
+    /// Desencriptar con la clave indicada en la cabecera
+    pub fn open(&self, ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < KEY_ID_LEN + aead::NONCE_LEN + self.algorithm.tag_len() {
+            return Err(anyhow!("Datos encriptados demasiado cortos"));
+        }
+
+        let (header, rest) = ciphertext.split_at(KEY_ID_LEN);
+        let (nonce_bytes, sealed) = rest.split_at(aead::NONCE_LEN);
+        let id = Self::key_id(header).ok_or_else(|| anyhow!("Cabecera de clave inválida"))?;
+
+        let key = self.find(id)?;
+
+        let nonce = aead::Nonce::try_assume_unique_for_key(nonce_bytes)
+            .map_err(|_| anyhow!("Nonce inválido"))?;
+        let mut in_out = sealed.to_vec();
+        let plaintext = key
+            .key
+            .open_in_place(nonce, aead::Aad::from(aad(header, associated_data)), &mut in_out)
+            .map_err(|_| anyhow!("Error desencriptando datos con la clave {}", id))?;
+
+        Ok(plaintext.to_vec())
+    }
+}
+
+fn versioned_key(
+    algorithm: &'static aead::Algorithm,
+    id: u32,
+    material: Vec<u8>,
+    created_at: DateTime<Utc>,
+) -> Result<VersionedKey> {
+    let unbound = aead::UnboundKey::new(algorithm, &material)
+        .map_err(|_| anyhow!("Clave de encriptación {} inválida", id))?;
+    Ok(VersionedKey {
+        id,
+        key: aead::LessSafeKey::new(unbound),
+        material,
+        created_at,
+    })
+}
+
+/// La cabecera entra en los datos asociados para que no pueda alterarse
+fn aad(header: &[u8], associated_data: &[u8]) -> Vec<u8> {
+    [header, associated_data].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_retained_keys() {
+        let mut ring = KeyRing::new(&aead::AES_256_GCM, 2).unwrap();
+        let first = ring.seal(b"secreto", b"sesion").unwrap();
+        assert_eq!(KeyRing::key_id(&first), Some(1));
+
+        ring.rotate().unwrap();
+        let second = ring.seal(b"secreto", b"sesion").unwrap();
+        assert_eq!(KeyRing::key_id(&second), Some(2));
+
+        // Los mensajes en vuelo con la clave anterior siguen abriéndose
+        assert_eq!(ring.open(&first, b"sesion").unwrap(), b"secreto");
+        assert!(ring.open(&first, b"otra").is_err());
+
+        ring.rotate().unwrap();
+        ring.rotate().unwrap();
+        assert_eq!(ring.key_ids(), vec![2, 3, 4]);
+        assert!(ring.open(&first, b"sesion").is_err());
+        assert_eq!(ring.open(&second, b"sesion").unwrap(), b"secreto");
+
+        // Cambiar la cabecera no permite usar otra clave
+        let mut forged = second.clone();
+        forged[..KEY_ID_LEN].copy_from_slice(&3u32.to_be_bytes());
+        assert!(ring.open(&forged, b"sesion").is_err());
+
+        // Un anillo restaurado abre lo sellado antes de exportarlo
+        let restored = KeyRing::restore(&aead::AES_256_GCM, 2, &ring.export()).unwrap();
+        assert_eq!(restored.key_ids(), vec![2, 3, 4]);
+        assert_eq!(restored.open(&second, b"sesion").unwrap(), b"secreto");
+
+        ring.set_retained(0);
+        assert_eq!(ring.key_ids(), vec![4]);
+    }
+}
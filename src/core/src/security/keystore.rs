@@ -0,0 +1,341 @@
+//! Almacenamiento persistente de claves de encriptación
+//!
+//! Un `KeyProvider` guarda y recupera el anillo de claves exportado para que
+//! lo encriptado sobreviva a los reinicios: en un archivo encriptado con una
+//! clave derivada de una frase de paso, en el llavero del sistema (feature
+//! `os-keyring`) o envuelto por un KMS en la nube. Con `ephemeral` las claves
+//! se generan al arrancar y se pierden al salir.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{aead, pbkdf2};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::keys::StoredKeyRing;
+
+/// Iteraciones de PBKDF2-HMAC-SHA256 para la clave del archivo
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const SALT_LEN: usize = 16;
+
+/// Datos asociados del anillo encriptado en archivo
+const FILE_AAD: &[u8] = b"saai-keyring-v1";
+
+/// Backend de las claves (`[security.key_provider]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum KeyProviderConfig {
+    /// Claves solo en memoria
+    #[default]
+    Ephemeral,
+    /// Archivo encriptado con AES-256-GCM
+    File {
+        path: String,
+        /// Frase de paso (admite referencias `env:`, `file:` y `vault:`)
+        passphrase: String,
+    },
+    /// Llavero del sistema (Secret Service, Keychain, Credential Manager)
+    OsKeyring {
+        #[serde(default = "default_keyring_service")]
+        service: String,
+        #[serde(default = "default_keyring_entry")]
+        entry: String,
+    },
+    /// Anillo envuelto por Cloud KMS y guardado en `path`
+    Kms {
+        /// `projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>`
+        key_name: String,
+        /// Token OAuth2 de acceso (admite referencias a secretos)
+        token: String,
+        #[serde(default = "default_kms_endpoint")]
+        endpoint: String,
+        path: String,
+    },
+}
+
+fn default_keyring_service() -> String {
+    "saai-core".to_string()
+}
+
+fn default_keyring_entry() -> String {
+    "encryption-keys".to_string()
+}
+
+fn default_kms_endpoint() -> String {
+    "https://cloudkms.googleapis.com/v1".to_string()
+}
+
+/// Almacén del anillo de claves
+#[async_trait]
+pub trait KeyProvider: Send + Sync {
+    fn name(&self) -> String;
+
+    /// Anillo guardado, si existe
+    async fn load(&self) -> Result<Option<StoredKeyRing>>;
+
+    async fn store(&self, keys: &StoredKeyRing) -> Result<()>;
+}
+
+/// Proveedor de `config`; `None` para claves efímeras
+pub fn provider_from_config(config: &KeyProviderConfig) -> Result<Option<Arc<dyn KeyProvider>>> {
+    let provider: Arc<dyn KeyProvider> = match config {
+        KeyProviderConfig::Ephemeral => return Ok(None),
+        KeyProviderConfig::File { path, passphrase } => {
+            if passphrase.is_empty() {
+                return Err(anyhow!("El almacén de claves en archivo necesita una frase de paso"));
+            }
+            Arc::new(FileKeyProvider::new(path, passphrase))
+        }
+        #[cfg(feature = "os-keyring")]
+        KeyProviderConfig::OsKeyring { service, entry } => Arc::new(os_keyring::OsKeyringProvider::new(service, entry)),
+        #[cfg(not(feature = "os-keyring"))]
+        KeyProviderConfig::OsKeyring { .. } => {
+            return Err(anyhow!("Llavero del sistema no disponible: compilar con la feature `os-keyring`"))
+        }
+        KeyProviderConfig::Kms { key_name, token, endpoint, path } => {
+            Arc::new(KmsKeyProvider::new(key_name, token, endpoint, path)?)
+        }
+    };
+    Ok(Some(provider))
+}
+
+/// Anillo encriptado en disco: `sal || nonce || anillo JSON + tag`
+pub struct FileKeyProvider {
+    path: PathBuf,
+    passphrase: String,
+}
+
+impl FileKeyProvider {
+    pub fn new(path: impl Into<PathBuf>, passphrase: &str) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    /// Clave AES-256 derivada de la frase de paso
+    fn key(&self, salt: &[u8]) -> Result<aead::LessSafeKey> {
+        let mut material = [0u8; 32];
+        let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).ok_or_else(|| anyhow!("Iteraciones PBKDF2 inválidas"))?;
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, self.passphrase.as_bytes(), &mut material);
+
+        let unbound = aead::UnboundKey::new(&aead::AES_256_GCM, &material)
+            .map_err(|_| anyhow!("Clave del almacén inválida"))?;
+        Ok(aead::LessSafeKey::new(unbound))
+    }
+}
+
+#[async_trait]
+impl KeyProvider for FileKeyProvider {
+    fn name(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+
+    async fn load(&self) -> Result<Option<StoredKeyRing>> {
+        let data = match tokio::fs::read(&self.path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(anyhow!("No se pudo leer el almacén de claves {}: {}", self.path.display(), e)),
+        };
+        if data.len() < SALT_LEN + aead::NONCE_LEN {
+            return Err(anyhow!("Almacén de claves {} corrupto", self.path.display()));
+        }
+
+        let (salt, rest) = data.split_at(SALT_LEN);
+        let (nonce, sealed) = rest.split_at(aead::NONCE_LEN);
+        let nonce = aead::Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("Nonce inválido"))?;
+
+        let mut in_out = sealed.to_vec();
+        let plaintext = self
+            .key(salt)?
+            .open_in_place(nonce, aead::Aad::from(FILE_AAD), &mut in_out)
+            .map_err(|_| anyhow!("Frase de paso incorrecta o almacén de claves {} alterado", self.path.display()))?;
+
+        Ok(Some(serde_json::from_slice(plaintext)?))
+    }
+
+    async fn store(&self, keys: &StoredKeyRing) -> Result<()> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        rng.fill(&mut salt).map_err(|_| anyhow!("No se pudo generar la sal"))?;
+        rng.fill(&mut nonce).map_err(|_| anyhow!("No se pudo generar el nonce"))?;
+
+        let mut in_out = serde_json::to_vec(keys)?;
+        self.key(&salt)?
+            .seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(FILE_AAD), &mut in_out)
+            .map_err(|_| anyhow!("Error encriptando el almacén de claves"))?;
+
+        write_private(&self.path, &[&salt[..], &nonce[..], &in_out].concat()).await
+    }
+}
+
+/// Anillo envuelto con `encrypt`/`decrypt` de la API REST de Cloud KMS
+pub struct KmsKeyProvider {
+    key_name: String,
+    token: String,
+    endpoint: String,
+    path: PathBuf,
+    client: reqwest::Client,
+}
+
+impl KmsKeyProvider {
+    pub fn new(key_name: &str, token: &str, endpoint: &str, path: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            key_name: key_name.to_string(),
+            token: token.to_string(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            path: path.into(),
+            client: reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build()?,
+        })
+    }
+
+    /// Llamar a `:<operation>` y devolver el campo `field` decodificado
+    async fn call(&self, operation: &str, body: serde_json::Value, field: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .post(format!("{}/{}:{}", self.endpoint, self.key_name, operation))
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("KMS respondió {} a {}", response.status(), operation));
+        }
+
+        let document: serde_json::Value = response.json().await?;
+        let encoded = document
+            .get(field)
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| anyhow!("Respuesta de KMS sin `{}`", field))?;
+        Ok(BASE64.decode(encoded)?)
+    }
+}
+
+#[async_trait]
+impl KeyProvider for KmsKeyProvider {
+    fn name(&self) -> String {
+        format!("kms:{}", self.key_name)
+    }
+
+    async fn load(&self) -> Result<Option<StoredKeyRing>> {
+        let wrapped = match tokio::fs::read_to_string(&self.path).await {
+            Ok(wrapped) => wrapped,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(anyhow!("No se pudo leer el anillo envuelto {}: {}", self.path.display(), e)),
+        };
+
+        let plaintext = self
+            .call("decrypt", serde_json::json!({ "ciphertext": wrapped.trim() }), "plaintext")
+            .await?;
+        Ok(Some(serde_json::from_slice(&plaintext)?))
+    }
+
+    async fn store(&self, keys: &StoredKeyRing) -> Result<()> {
+        let plaintext = BASE64.encode(serde_json::to_vec(keys)?);
+        let wrapped = self
+            .call("encrypt", serde_json::json!({ "plaintext": plaintext }), "ciphertext")
+            .await?;
+        write_private(&self.path, BASE64.encode(wrapped).as_bytes()).await
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+mod os_keyring {
+    use super::*;
+
+    pub(super) struct OsKeyringProvider {
+        service: String,
+        entry: String,
+    }
+
+    impl OsKeyringProvider {
+        pub(super) fn new(service: &str, entry: &str) -> Self {
+            Self {
+                service: service.to_string(),
+                entry: entry.to_string(),
+            }
+        }
+
+        fn entry(&self) -> Result<keyring::Entry> {
+            Ok(keyring::Entry::new(&self.service, &self.entry)?)
+        }
+    }
+
+    #[async_trait]
+    impl KeyProvider for OsKeyringProvider {
+        fn name(&self) -> String {
+            format!("keyring:{}/{}", self.service, self.entry)
+        }
+
+        async fn load(&self) -> Result<Option<StoredKeyRing>> {
+            let entry = self.entry()?;
+            // Las APIs del llavero son bloqueantes
+            let secret = tokio::task::spawn_blocking(move || entry.get_password()).await?;
+            match secret {
+                Ok(secret) => Ok(Some(serde_json::from_str(&secret)?)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(anyhow!("Llavero del sistema: {}", e)),
+            }
+        }
+
+        async fn store(&self, keys: &StoredKeyRing) -> Result<()> {
+            let entry = self.entry()?;
+            let secret = serde_json::to_string(keys)?;
+            tokio::task::spawn_blocking(move || entry.set_password(&secret))
+                .await?
+                .map_err(|e| anyhow!("Llavero del sistema: {}", e))
+        }
+    }
+}
+
+/// Escribir de forma atómica, legible solo por el propietario
+pub(super) async fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, data).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600)).await?;
+    }
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::KeyRing;
+
+    #[tokio::test]
+    async fn test_file_provider_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("keyring.enc");
+        let provider = FileKeyProvider::new(&path, "frase de paso");
+        assert!(provider.load().await.unwrap().is_none());
+
+        let ring = KeyRing::new(&aead::AES_256_GCM, 2).unwrap();
+        let sealed = ring.seal(b"snapshot", b"").unwrap();
+        provider.store(&ring.export()).await.unwrap();
+
+        // El material no queda en claro en el archivo
+        let raw = tokio::fs::read(&path).await.unwrap();
+        let material = &ring.export().keys[0].material;
+        assert!(!String::from_utf8_lossy(&raw).contains(material.as_str()));
+
+        let restored = KeyRing::restore(&aead::AES_256_GCM, 2, &provider.load().await.unwrap().unwrap()).unwrap();
+        assert_eq!(restored.open(&sealed, b"").unwrap(), b"snapshot");
+
+        assert!(FileKeyProvider::new(&path, "otra frase").load().await.is_err());
+    }
+}
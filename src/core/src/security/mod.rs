@@ -4,26 +4,222 @@
 //! y detección de amenazas para el ecosistema SAAI.
 
 use anyhow::{Result, anyhow};
-use ring::{aead, digest, rand};
+use async_trait::async_trait;
+use ring::{aead, digest};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeBounds;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+pub mod anomaly;
+pub mod audit;
+pub mod baseline;
+pub mod certs;
+pub mod integrity;
+pub mod keys;
+pub mod keystore;
+pub mod ratelimit;
+pub mod posture;
+pub mod rbac;
+pub mod retention;
+pub mod rules;
+pub mod siem;
+pub mod stream;
+pub mod token;
+
+pub use anomaly::{AccessBaselines, AccessDeviation, ResourceSnapshot};
+pub use audit::{AuditLog, AuditLogOptions, AuditRecord, SecurityEventFilter};
+pub use baseline::{EventBaselines, RateAssessment, RateBaseline};
+pub use certs::{CertManager, IssuedCert};
+pub use integrity::{IntegrityChange, IntegrityMonitor, IntegrityViolation};
+pub use keys::{KeyRing, StoredKey, StoredKeyRing, DEFAULT_RETAINED_KEYS};
+pub use keystore::{FileKeyProvider, KeyProvider, KeyProviderConfig, KmsKeyProvider};
+pub use posture::{PostureHistory, PostureRegression, PostureSample, SecurityPosture};
+pub use ratelimit::{LimitKey, LockoutPolicy, RateDecision, RateLimitRule, RateLimiter};
+pub use rbac::{PermissionPattern, Rbac, RoleDefinition};
+pub use retention::{EventRollup, EventStore, RetentionPolicy};
+pub use rules::{PatternOverride, ThreatRules, THREAT_RULES_SUBJECT};
+pub use siem::{SiemExporterConfig, SiemExporters, SiemTarget, SyslogFormat, SyslogProtocol};
+pub use stream::STREAM_CHUNK_SIZE;
+pub use token::{TokenIdentity, TokenIssuerConfig, TokenValidator};
+
+use crate::config::source::FileSource;
+use crate::config::{ConfigSection, ConfigSource, ConfigSubscriber, CoreConfig, FieldChange};
+use crate::metrics::StatusProvider;
+
 /// Configuración del sistema de seguridad
-#[derive(Debug, Clone)]
+///
+/// Sección `[security]` del archivo; los nombres antiguos (`intrusion_detection`,
+/// `audit_log_enabled`) se siguen aceptando.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct SecurityConfig {
     pub enable_sandboxing: bool,
     pub encryption_enabled: bool,
+    pub encryption_key_size: u32,
+    /// Dónde se conservan las claves entre reinicios (`[security.key_provider]`)
+    pub key_provider: KeyProviderConfig,
     pub integrity_checks: bool,
+    /// Archivos y directorios (recursivos) cuya integridad se vigila
+    pub integrity_paths: Vec<String>,
+    /// Vigilar también el binario en ejecución
+    pub integrity_include_binary: bool,
+    /// Intervalo entre verificaciones de integridad, en segundos
+    pub integrity_check_interval_secs: u64,
+    #[serde(alias = "intrusion_detection")]
     pub threat_detection: bool,
+    #[serde(alias = "audit_log_enabled")]
     pub audit_logging: bool,
+    /// Directorio del registro de auditoría persistente
+    pub audit_log_dir: String,
+    /// Tamaño máximo de cada segmento del registro, en MB
+    pub audit_log_max_file_mb: u64,
+    /// Segmentos del registro conservados
+    pub audit_log_max_files: usize,
+    /// Eventos de seguridad conservados en memoria
+    pub event_retention_max: usize,
+    /// Antigüedad máxima de los eventos en memoria, en horas
+    pub event_retention_hours: u64,
+    /// Intervalo de la poda de eventos, en segundos
+    pub event_prune_interval_secs: u64,
+    /// Roles y sus permisos (`[security.roles.<nombre>]`)
+    pub roles: BTreeMap<String, RoleDefinition>,
+    /// Emisores de JWT aceptados (`[[security.token_issuers]]`)
+    pub token_issuers: Vec<TokenIssuerConfig>,
+    /// Tolerancia de reloj al validar `exp`/`nbf`, en segundos
+    pub token_leeway_secs: u64,
+    /// Duración máxima de una sesión, en segundos (0 = sin límite)
+    pub session_max_lifetime_secs: u64,
+    /// Inactividad tras la que una sesión expira, en segundos (0 = sin límite)
+    pub session_idle_timeout_secs: u64,
+    /// Intervalo del barrido de sesiones expiradas, en segundos
+    pub session_sweep_interval_secs: u64,
+    /// Archivo de reglas de amenazas (TOML/YAML/JSON), vigilado en caliente
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threat_rules_file: Option<String>,
+    /// Archivo JSON donde se conservan las líneas base de frecuencia aprendidas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threat_baseline_file: Option<String>,
+    /// Destinos SIEM de los eventos (`[[security.siem_exporters]]`)
+    pub siem_exporters: Vec<SiemExporterConfig>,
+    /// Límites por acción (`[security.rate_limits.<acción>]`); `default` para el resto
+    pub rate_limits: BTreeMap<String, RateLimitRule>,
+    /// Fallos de autenticación que bloquean una identidad o IP
+    pub lockout_max_failures: u32,
+    /// Ventana en la que se cuentan los fallos, en segundos
+    pub lockout_window_secs: u64,
+    /// Duración del bloqueo, en segundos
+    pub lockout_duration_secs: u64,
+    /// Emitir y rotar certificados mTLS con una CA propia del nodo
+    pub cert_management: bool,
+    /// Directorio de la CA y los certificados emitidos
+    pub cert_dir: String,
+    /// Nombres DNS o IP del certificado de cada servicio (`[security.cert_services]`)
+    pub cert_services: BTreeMap<String, Vec<String>>,
+    /// Validez de los certificados de servicio, en horas
+    pub cert_validity_hours: u64,
+    /// Vida restante por debajo de la que se renuevan, en horas
+    pub cert_renew_before_hours: u64,
+    /// Validez de la CA, en días
+    pub cert_ca_validity_days: u64,
+    /// Intervalo de la comprobación de caducidad, en segundos
+    pub cert_check_interval_secs: u64,
+    /// Intervalo entre muestras de la postura de seguridad, en segundos
+    pub posture_sample_interval_secs: u64,
+    /// Antigüedad máxima del historial de postura, en horas
+    pub posture_history_hours: u64,
+    /// Ventana frente a cuyo máximo se mide una regresión, en minutos
+    pub posture_regression_window_mins: u64,
+    /// Caída de puntos que se notifica como regresión
+    pub posture_regression_threshold: f64,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            enable_sandboxing: true,
+            encryption_enabled: true,
+            encryption_key_size: 256,
+            key_provider: KeyProviderConfig::Ephemeral,
+            integrity_checks: true,
+            integrity_paths: vec!["config".to_string()],
+            integrity_include_binary: true,
+            integrity_check_interval_secs: 300,
+            threat_detection: true,
+            audit_logging: true,
+            audit_log_dir: "data/audit".to_string(),
+            audit_log_max_file_mb: 10,
+            audit_log_max_files: 30,
+            event_retention_max: 10_000,
+            event_retention_hours: 24,
+            event_prune_interval_secs: 60,
+            roles: BTreeMap::new(),
+            token_issuers: Vec::new(),
+            token_leeway_secs: 60,
+            session_max_lifetime_secs: 8 * 3600,
+            session_idle_timeout_secs: 1800,
+            session_sweep_interval_secs: 60,
+            threat_rules_file: None,
+            threat_baseline_file: None,
+            siem_exporters: Vec::new(),
+            rate_limits: BTreeMap::from([(
+                ratelimit::DEFAULT_ACTION.to_string(),
+                RateLimitRule { max_requests: 100, window_secs: 60 },
+            )]),
+            lockout_max_failures: 5,
+            lockout_window_secs: 300,
+            lockout_duration_secs: 900,
+            cert_management: false,
+            cert_dir: "data/certs".to_string(),
+            cert_services: ["fabric", "metrics", "admin"]
+                .into_iter()
+                .map(|service| (service.to_string(), vec!["localhost".to_string(), "127.0.0.1".to_string()]))
+                .collect(),
+            cert_validity_hours: 24,
+            cert_renew_before_hours: 8,
+            cert_ca_validity_days: 365,
+            cert_check_interval_secs: 300,
+            posture_sample_interval_secs: 60,
+            posture_history_hours: 7 * 24,
+            posture_regression_window_mins: 60,
+            posture_regression_threshold: 15.0,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Rutas vigiladas por el monitor de integridad
+    pub fn integrity_targets(&self) -> Vec<std::path::PathBuf> {
+        let binary = self.integrity_include_binary.then(std::env::current_exe).and_then(|exe| exe.ok());
+        binary.into_iter().chain(self.integrity_paths.iter().map(Into::into)).collect()
+    }
+    
+    /// Bloqueo tras fallos de autenticación
+    pub fn lockout_policy(&self) -> LockoutPolicy {
+        LockoutPolicy {
+            max_failures: self.lockout_max_failures,
+            window: chrono::Duration::seconds(self.lockout_window_secs as i64),
+            duration: chrono::Duration::seconds(self.lockout_duration_secs as i64),
+        }
+    }
+    
+    /// Límites de los eventos en memoria
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            max_events: self.event_retention_max,
+            max_age: chrono::Duration::hours(self.event_retention_hours as i64),
+            ..Default::default()
+        }
+    }
 }
 
 /// Niveles de seguridad
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SecurityLevel {
     Public = 0,
     Internal = 1,
@@ -38,9 +234,28 @@ pub struct SecurityContext {
     pub user_id: Option<String>,
     pub session_id: Uuid,
     pub security_level: SecurityLevel,
+    /// Permisos concedidos directamente, además de los de sus roles
     pub permissions: Vec<String>,
+    pub roles: Vec<String>,
     pub source_ip: Option<String>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Última autorización comprobada con esta sesión
+    pub last_activity: chrono::DateTime<chrono::Utc>,
+    /// Límite impuesto por la credencial (p. ej. el `exp` de un JWT)
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SecurityContext {
+    /// Si la sesión superó su duración máxima, su inactividad permitida o su credencial
+    pub fn is_expired(&self, config: &SecurityConfig, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let exceeded = |since: chrono::DateTime<chrono::Utc>, limit_secs: u64| {
+            limit_secs > 0 && now - since > chrono::Duration::seconds(limit_secs as i64)
+        };
+        
+        exceeded(self.timestamp, config.session_max_lifetime_secs)
+            || exceeded(self.last_activity, config.session_idle_timeout_secs)
+            || self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
 }
 
 /// Evento de seguridad
@@ -57,7 +272,7 @@ pub struct SecurityEvent {
 }
 
 /// Tipos de eventos de seguridad
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SecurityEventType {
     AuthenticationFailure,
     AuthorizationDenied,
@@ -70,7 +285,7 @@ pub enum SecurityEventType {
 }
 
 /// Severidad de eventos de seguridad
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SecuritySeverity {
     Info = 0,
     Low = 1,
@@ -79,57 +294,152 @@ pub enum SecuritySeverity {
     Critical = 4,
 }
 
-/// Gestor de encriptación
+/// Gestor de encriptación con claves versionadas
 pub struct EncryptionManager {
-    key: aead::LessSafeKey,
-    algorithm: &'static aead::Algorithm,
+    keys: std::sync::RwLock<KeyRing>,
+    provider: Option<Arc<dyn KeyProvider>>,
+    /// Serializa las rotaciones para que ninguna pise el anillo guardado por otra
+    rotation: Mutex<()>,
 }
 
 impl EncryptionManager {
     /// Crear nuevo gestor de encriptación
     pub fn new() -> Result<Self> {
-        let algorithm = &aead::AES_256_GCM;
-        let rng = rand::SystemRandom::new();
-        let key_bytes = aead::generate_key(algorithm, &rng)?;
-        let key = aead::LessSafeKey::new(key_bytes);
+        Self::with_retained_keys(DEFAULT_RETAINED_KEYS)
+    }
+    
+    /// Crear conservando `retained` claves anteriores tras cada rotación
+    pub fn with_retained_keys(retained: usize) -> Result<Self> {
+        Ok(Self {
+            keys: std::sync::RwLock::new(KeyRing::new(&aead::AES_256_GCM, retained)?),
+            provider: None,
+            rotation: Mutex::new(()),
+        })
+    }
+    
+    /// Cargar las claves de `provider`, o generarlas y guardarlas si no hay ninguna
+    pub async fn with_provider(retained: usize, provider: Arc<dyn KeyProvider>) -> Result<Self> {
+        let keys = match provider.load().await? {
+            Some(stored) => {
+                let keys = KeyRing::restore(&aead::AES_256_GCM, retained, &stored)?;
+                info!("🔑 {} claves de encriptación cargadas de {}", keys.key_ids().len(), provider.name());
+                keys
+            }
+            None => {
+                let keys = KeyRing::new(&aead::AES_256_GCM, retained)?;
+                provider.store(&keys.export()).await?;
+                info!("🔑 Claves de encriptación nuevas guardadas en {}", provider.name());
+                keys
+            }
+        };
         
         Ok(Self {
-            key,
-            algorithm,
+            keys: std::sync::RwLock::new(keys),
+            provider: Some(provider),
+            rotation: Mutex::new(()),
         })
     }
     
-    /// Encriptar datos
+    fn read_keys(&self) -> Result<std::sync::RwLockReadGuard<'_, KeyRing>> {
+        self.keys.read().map_err(|_| anyhow!("Anillo de claves envenenado"))
+    }
+    
+    fn write_keys(&self) -> Result<std::sync::RwLockWriteGuard<'_, KeyRing>> {
+        self.keys.write().map_err(|_| anyhow!("Anillo de claves envenenado"))
+    }
+    
+    /// Encriptar datos con la clave actual
     pub fn encrypt(&self, data: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
-        let rng = rand::SystemRandom::new();
-        let mut nonce_bytes = vec![0u8; self.algorithm.nonce_len()];
-        rng.fill(&mut nonce_bytes)?;
-        
-        let nonce = aead::Nonce::try_assume_unique_for_key(&nonce_bytes)?;
-        let mut in_out = data.to_vec();
-        
-        self.key.seal_in_place_append_tag(nonce, aead::Aad::from(associated_data), &mut in_out)?;
-        
-        // Prepender nonce a los datos encriptados
-        let mut result = nonce_bytes;
-        result.extend_from_slice(&in_out);
-        
-        Ok(result)
+        self.read_keys()?.seal(data, associated_data)
     }
     
-    /// Desencriptar datos
+    /// Desencriptar datos con la clave que los selló
     pub fn decrypt(&self, encrypted_data: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
-        if encrypted_data.len() < self.algorithm.nonce_len() {
-            return Err(anyhow!("Datos encriptados demasiado cortos"));
-        }
+        self.read_keys()?.open(encrypted_data, associated_data)
+    }
+    
+    /// Encriptar un flujo por fragmentos con la clave actual; devuelve los bytes leídos
+    pub async fn encrypt_stream<R, W>(&self, reader: R, writer: W, associated_data: &[u8]) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let (key_id, key) = self.read_keys()?.sealing_key()?;
+        stream::seal_stream(key_id, &key, reader, writer, associated_data).await
+    }
+    
+    /// Desencriptar un flujo de `encrypt_stream`; devuelve los bytes escritos
+    ///
+    /// Si falla, lo ya escrito en `writer` no es fiable y debe descartarse.
+    pub async fn decrypt_stream<R, W>(&self, reader: R, writer: W, associated_data: &[u8]) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        stream::open_stream(reader, writer, associated_data, |key_id| self.read_keys()?.opening_key(key_id)).await
+    }
+    
+    /// Rotar la clave; devuelve el identificador de la nueva
+    ///
+    /// Con proveedor, la rotación solo se da por hecha una vez guardada.
+    pub async fn rotate_keys(&self) -> Result<u32> {
+        let _rotation = self.rotation.lock().await;
+        let Some(provider) = &self.provider else {
+            let id = self.write_keys()?.rotate()?;
+            info!("🔑 Clave de encriptación rotada: versión {}", id);
+            return Ok(id);
+        };
         
-        let (nonce_bytes, ciphertext) = encrypted_data.split_at(self.algorithm.nonce_len());
-        let nonce = aead::Nonce::try_assume_unique_for_key(nonce_bytes)?;
+        let mut candidate = {
+            let keys = self.read_keys()?;
+            KeyRing::restore(&aead::AES_256_GCM, keys.retained(), &keys.export())?
+        };
+        let id = candidate.rotate()?;
+        provider.store(&candidate.export()).await?;
+        *self.write_keys()? = candidate;
         
-        let mut in_out = ciphertext.to_vec();
-        let plaintext = self.key.open_in_place(nonce, aead::Aad::from(associated_data), &mut in_out)?;
+        info!("🔑 Clave de encriptación rotada: versión {} (guardada en {})", id, provider.name());
+        Ok(id)
+    }
+    
+    /// Rotar si la clave actual supera `interval`
+    pub async fn rotate_if_due(&self, interval: chrono::Duration) -> Result<Option<u32>> {
+        if chrono::Utc::now() - self.last_rotation()? < interval {
+            return Ok(None);
+        }
+        self.rotate_keys().await.map(Some)
+    }
+    
+    /// Cambiar cuántas claves anteriores se conservan
+    pub fn set_retained_keys(&self, retained: usize) -> Result<()> {
+        self.write_keys()?.set_retained(retained);
+        Ok(())
+    }
+    
+    pub fn current_key_id(&self) -> Result<u32> {
+        self.read_keys()?.current_id()
+    }
+    
+    pub fn last_rotation(&self) -> Result<chrono::DateTime<chrono::Utc>> {
+        self.read_keys()?.rotated_at()
+    }
+    
+    /// Si los datos se sellaron con una clave que no es la actual
+    pub fn needs_reencryption(&self, encrypted_data: &[u8]) -> Result<bool> {
+        Ok(KeyRing::key_id(encrypted_data) != Some(self.current_key_id()?))
+    }
+    
+    /// Re-encriptar datos de larga vida con la clave actual
+    ///
+    /// Debe hacerse antes de que su clave salga de la retención.
+    pub fn reencrypt(&self, encrypted_data: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let keys = self.read_keys()?;
+        if KeyRing::key_id(encrypted_data) == Some(keys.current_id()?) {
+            return Ok(encrypted_data.to_vec());
+        }
         
-        Ok(plaintext.to_vec())
+        let plaintext = keys.open(encrypted_data, associated_data)?;
+        keys.seal(&plaintext, associated_data)
     }
 }
 
@@ -156,27 +466,52 @@ impl IntegrityVerifier {
     }
 }
 
+/// Antigüedad máxima de una instantánea de recursos para correlacionarla
+const RESOURCE_SNAPSHOT_TTL_SECS: i64 = 300;
+
+/// Tiempo mínimo entre alertas de un mismo patrón para un mismo origen
+const ALERT_COOLDOWN_SECS: i64 = 300;
+
 /// Detector de amenazas
 pub struct ThreatDetector {
     patterns: Arc<RwLock<Vec<ThreatPattern>>>,
-    events: Arc<RwLock<Vec<SecurityEvent>>>,
+    events: Arc<RwLock<EventStore>>,
+    resources: RwLock<HashMap<String, ResourceSnapshot>>,
+    baselines: RwLock<AccessBaselines>,
+    rates: RwLock<EventBaselines>,
+    last_alerts: RwLock<HashMap<(String, String), chrono::DateTime<chrono::Utc>>>,
 }
 
 /// Patrón de amenaza
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreatPattern {
     pub id: String,
     pub name: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(flatten)]
     pub pattern_type: ThreatPatternType,
     pub severity: SecuritySeverity,
+    #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
 /// Tipos de patrones de amenaza
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ThreatPatternType {
-    FrequencyAnomaly { max_events: u32, window_seconds: u64 },
+    /// Con `z_threshold` el umbral se aprende por tipo de evento y origen;
+    /// `max_events` se aplica mientras la línea base no es fiable
+    FrequencyAnomaly {
+        max_events: u32,
+        window_seconds: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        z_threshold: Option<f64>,
+    },
     SuspiciousPattern { keywords: Vec<String> },
     AccessAnomaly { unusual_times: bool, unusual_locations: bool },
     ResourceAbuse { cpu_threshold: f64, memory_threshold: u64 },
@@ -185,37 +520,211 @@ pub enum ThreatPatternType {
 impl ThreatDetector {
     /// Crear nuevo detector de amenazas
     pub fn new() -> Self {
-        let mut patterns = Vec::new();
-        
-        // Patrones predefinidos
-        patterns.push(ThreatPattern {
-            id: "freq_auth_fail".to_string(),
-            name: "Fallos de autenticación frecuentes".to_string(),
-            description: "Múltiples fallos de autenticación en corto tiempo".to_string(),
-            pattern_type: ThreatPatternType::FrequencyAnomaly {
-                max_events: 5,
-                window_seconds: 300,
+        Self::with_retention(RetentionPolicy::default())
+    }
+    
+    /// Patrones predefinidos, sobre los que se aplican las reglas configuradas
+    pub fn builtin_patterns() -> Vec<ThreatPattern> {
+        vec![
+            ThreatPattern {
+                id: "freq_auth_fail".to_string(),
+                name: "Fallos de autenticación frecuentes".to_string(),
+                description: "Múltiples fallos de autenticación en corto tiempo".to_string(),
+                pattern_type: ThreatPatternType::FrequencyAnomaly {
+                    max_events: 5,
+                    window_seconds: 300,
+                    z_threshold: Some(3.0),
+                },
+                severity: SecuritySeverity::High,
+                enabled: true,
             },
-            severity: SecuritySeverity::High,
-            enabled: true,
-        });
-        
-        patterns.push(ThreatPattern {
-            id: "resource_abuse".to_string(),
-            name: "Abuso de recursos".to_string(),
-            description: "Uso excesivo de CPU o memoria".to_string(),
-            pattern_type: ThreatPatternType::ResourceAbuse {
-                cpu_threshold: 90.0,
-                memory_threshold: 1024 * 1024 * 1024, // 1GB
+            ThreatPattern {
+                id: "resource_abuse".to_string(),
+                name: "Abuso de recursos".to_string(),
+                description: "Uso excesivo de CPU o memoria".to_string(),
+                pattern_type: ThreatPatternType::ResourceAbuse {
+                    cpu_threshold: 90.0,
+                    memory_threshold: 1024 * 1024 * 1024, // 1GB
+                },
+                severity: SecuritySeverity::Medium,
+                enabled: true,
             },
-            severity: SecuritySeverity::Medium,
-            enabled: true,
-        });
-        
+            ThreatPattern {
+                id: "access_anomaly".to_string(),
+                name: "Acceso anómalo".to_string(),
+                description: "Acceso a una hora o desde una IP inusual para el usuario".to_string(),
+                pattern_type: ThreatPatternType::AccessAnomaly {
+                    unusual_times: true,
+                    unusual_locations: true,
+                },
+                severity: SecuritySeverity::Medium,
+                enabled: true,
+            },
+        ]
+    }
+    
+    /// Crear detector limitando los eventos que conserva para el análisis
+    pub fn with_retention(policy: RetentionPolicy) -> Self {
         Self {
-            patterns: Arc::new(RwLock::new(patterns)),
-            events: Arc::new(RwLock::new(Vec::new())),
+            patterns: Arc::new(RwLock::new(Self::builtin_patterns())),
+            events: Arc::new(RwLock::new(EventStore::new(policy))),
+            resources: RwLock::new(HashMap::new()),
+            baselines: RwLock::new(AccessBaselines::default()),
+            rates: RwLock::new(EventBaselines::default()),
+            last_alerts: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Patrones vigentes
+    pub async fn patterns(&self) -> Vec<ThreatPattern> {
+        self.patterns.read().await.clone()
+    }
+    
+    /// Sustituir los patrones por los predefinidos con `rules` aplicadas
+    pub async fn apply_rules(&self, rules: &ThreatRules) -> usize {
+        let patterns = rules.apply(Self::builtin_patterns());
+        let enabled = patterns.iter().filter(|p| p.enabled).count();
+        let ids: Vec<&str> = patterns.iter().map(|p| p.id.as_str()).collect();
+        self.rates.write().await.retain_patterns(&ids);
+        *self.patterns.write().await = patterns;
+        enabled
+    }
+    
+    /// Líneas base de frecuencia aprendidas
+    pub async fn export_baselines(&self) -> EventBaselines {
+        self.rates.read().await.clone()
+    }
+    
+    /// Sustituir las líneas base de frecuencia (p. ej. las guardadas antes de reiniciar)
+    pub async fn import_baselines(&self, baselines: EventBaselines) {
+        *self.rates.write().await = baselines;
+    }
+    
+    /// Registrar el uso de recursos de un origen y evaluar `ResourceAbuse`
+    pub async fn record_resource_usage(&self, source: &str, snapshot: ResourceSnapshot) -> Vec<SecurityEvent> {
+        self.resources.write().await.insert(source.to_string(), snapshot);
+        
+        let mut threats = Vec::new();
+        let patterns = self.patterns.read().await;
+        for pattern in patterns.iter().filter(|p| p.enabled) {
+            if let ThreatPatternType::ResourceAbuse { cpu_threshold, memory_threshold } = &pattern.pattern_type {
+                if let Some(threat) = self.resource_abuse(pattern, source, &snapshot, *cpu_threshold, *memory_threshold).await {
+                    threats.push(threat);
+                }
+            }
+        }
+        threats
+    }
+    
+    /// Evaluar un acceso autorizado contra `AccessAnomaly` e incorporarlo a la línea base
+    pub async fn analyze_access(&self, user: &str, ip: Option<&str>) -> Vec<SecurityEvent> {
+        let now = chrono::Utc::now();
+        let mut threats = Vec::new();
+        
+        let patterns = self.patterns.read().await;
+        for pattern in patterns.iter().filter(|p| p.enabled) {
+            if let ThreatPatternType::AccessAnomaly { unusual_times, unusual_locations } = &pattern.pattern_type {
+                if let Some(threat) = self.access_anomaly(pattern, user, ip, now, *unusual_times, *unusual_locations).await {
+                    threats.push(threat);
+                }
+            }
+        }
+        
+        self.baselines.write().await.observe(user, ip, now);
+        threats
+    }
+    
+    /// Si toca alertar de nuevo por `pattern` para `source`
+    async fn should_alert(&self, pattern: &ThreatPattern, source: &str) -> bool {
+        let now = chrono::Utc::now();
+        let mut last_alerts = self.last_alerts.write().await;
+        let key = (pattern.id.clone(), source.to_string());
+        
+        if last_alerts.get(&key).is_some_and(|last| now - *last < chrono::Duration::seconds(ALERT_COOLDOWN_SECS)) {
+            return false;
+        }
+        last_alerts.insert(key, now);
+        true
+    }
+    
+    async fn resource_abuse(
+        &self,
+        pattern: &ThreatPattern,
+        source: &str,
+        snapshot: &ResourceSnapshot,
+        cpu_threshold: f64,
+        memory_threshold: u64,
+    ) -> Option<SecurityEvent> {
+        let mut exceeded = Vec::new();
+        if snapshot.cpu_percent > cpu_threshold {
+            exceeded.push(format!("CPU {:.1}% > {:.1}%", snapshot.cpu_percent, cpu_threshold));
         }
+        if snapshot.memory_bytes > memory_threshold {
+            exceeded.push(format!("memoria {}MB > {}MB", snapshot.memory_bytes / 1024 / 1024, memory_threshold / 1024 / 1024));
+        }
+        
+        if exceeded.is_empty() || !self.should_alert(pattern, source).await {
+            return None;
+        }
+        
+        Some(SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::ThreatDetected,
+            severity: pattern.severity.clone(),
+            source: "threat-detector".to_string(),
+            target: Some(source.to_string()),
+            description: format!("Patrón detectado: {} ({})", pattern.name, exceeded.join(", ")),
+            context: HashMap::from([
+                ("pattern_id".to_string(), pattern.id.clone()),
+                (anomaly::CONTEXT_CPU.to_string(), snapshot.cpu_percent.to_string()),
+                (anomaly::CONTEXT_MEMORY.to_string(), snapshot.memory_bytes.to_string()),
+            ]),
+            timestamp: chrono::Utc::now(),
+        })
+    }
+    
+    async fn access_anomaly(
+        &self,
+        pattern: &ThreatPattern,
+        user: &str,
+        ip: Option<&str>,
+        at: chrono::DateTime<chrono::Utc>,
+        unusual_times: bool,
+        unusual_locations: bool,
+    ) -> Option<SecurityEvent> {
+        let deviations: Vec<AccessDeviation> = self.baselines.read().await
+            .assess(user, ip, at)
+            .into_iter()
+            .filter(|deviation| match deviation {
+                AccessDeviation::UnusualTime => unusual_times,
+                AccessDeviation::UnusualLocation => unusual_locations,
+            })
+            .collect();
+        
+        if deviations.is_empty() || !self.should_alert(pattern, user).await {
+            return None;
+        }
+        
+        let reasons: Vec<&str> = deviations.iter().map(AccessDeviation::as_str).collect();
+        let mut context = HashMap::from([
+            ("pattern_id".to_string(), pattern.id.clone()),
+            ("deviations".to_string(), reasons.join(",")),
+            (anomaly::CONTEXT_USER.to_string(), user.to_string()),
+        ]);
+        if let Some(ip) = ip {
+            context.insert(anomaly::CONTEXT_IP.to_string(), ip.to_string());
+        }
+        
+        Some(SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::AnomalousAccess,
+            severity: pattern.severity.clone(),
+            source: "threat-detector".to_string(),
+            target: Some(user.to_string()),
+            description: format!("Patrón detectado: {} ({})", pattern.name, reasons.join(", ")),
+            context,
+            timestamp: chrono::Utc::now(),
+        })
     }
     
     /// Analizar evento de seguridad
@@ -243,12 +752,40 @@ impl ThreatDetector {
         pattern: &ThreatPattern,
     ) -> Result<Option<SecurityEvent>> {
         match &pattern.pattern_type {
-            ThreatPatternType::FrequencyAnomaly { max_events, window_seconds } => {
-                let window_start = chrono::Utc::now() - chrono::Duration::seconds(*window_seconds as i64);
+            ThreatPatternType::FrequencyAnomaly { max_events, window_seconds, z_threshold } => {
+                let window = chrono::Duration::seconds(*window_seconds as i64);
                 
+                if let Some(z_threshold) = z_threshold {
+                    let key = format!("{:?}/{}", event.event_type, event.source);
+                    let rate = self.rates.write().await.observe(&pattern.id, &key, event.timestamp, window);
+                    
+                    if rate.learned {
+                        if rate.z_score < *z_threshold || !self.should_alert(pattern, &key).await {
+                            return Ok(None);
+                        }
+                        
+                        return Ok(Some(SecurityEvent {
+                            id: Uuid::new_v4(),
+                            event_type: SecurityEventType::ThreatDetected,
+                            severity: pattern.severity.clone(),
+                            source: "threat-detector".to_string(),
+                            target: Some(event.source.clone()),
+                            description: format!("Patrón detectado: {} (z={:.1})", pattern.name, rate.z_score),
+                            context: HashMap::from([
+                                ("pattern_id".to_string(), pattern.id.clone()),
+                                ("event_count".to_string(), rate.count.to_string()),
+                                ("baseline_mean".to_string(), format!("{:.2}", rate.mean)),
+                                ("z_score".to_string(), format!("{:.2}", rate.z_score)),
+                            ]),
+                            timestamp: chrono::Utc::now(),
+                        }));
+                    }
+                }
+                
+                let window_start = chrono::Utc::now() - window;
                 let events = self.events.read().await;
-                let recent_events = events.iter()
-                    .filter(|e| e.timestamp >= window_start && e.event_type == event.event_type)
+                let recent_events = events.since(window_start)
+                    .filter(|e| e.event_type == event.event_type)
                     .count();
                 
                 if recent_events > *max_events as usize {
@@ -289,8 +826,26 @@ impl ThreatDetector {
                 }
             }
             
-            _ => {
-                // TODO: Implementar otros tipos de patrones
+            ThreatPatternType::ResourceAbuse { cpu_threshold, memory_threshold } => {
+                // La medida del propio evento o la última instantánea reciente del origen
+                let snapshot = match ResourceSnapshot::from_context(&event.context, event.timestamp) {
+                    Some(snapshot) => Some(snapshot),
+                    None => self.resources.read().await.get(&event.source).copied().filter(|snapshot| {
+                        event.timestamp - snapshot.timestamp <= chrono::Duration::seconds(RESOURCE_SNAPSHOT_TTL_SECS)
+                    }),
+                };
+                
+                if let Some(snapshot) = snapshot {
+                    return Ok(self.resource_abuse(pattern, &event.source, &snapshot, *cpu_threshold, *memory_threshold).await);
+                }
+            }
+            
+            ThreatPatternType::AccessAnomaly { unusual_times, unusual_locations } => {
+                // Los eventos con usuario (p. ej. fallos de autenticación) se comparan sin aprender de ellos
+                if let Some(user) = event.context.get(anomaly::CONTEXT_USER) {
+                    let ip = event.context.get(anomaly::CONTEXT_IP).map(String::as_str);
+                    return Ok(self.access_anomaly(pattern, user, ip, event.timestamp, *unusual_times, *unusual_locations).await);
+                }
             }
         }
         
@@ -298,41 +853,541 @@ impl ThreatDetector {
     }
 }
 
+/// Usuario e IP de una sesión para el contexto de sus eventos
+fn session_fields(context: &SecurityContext) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    if let Some(user) = &context.user_id {
+        fields.insert(anomaly::CONTEXT_USER.to_string(), user.clone());
+    }
+    if let Some(ip) = &context.source_ip {
+        fields.insert(anomaly::CONTEXT_IP.to_string(), ip.clone());
+    }
+    fields
+}
+
+/// Sujetos de los límites a partir del usuario e IP de un contexto de evento
+fn limit_keys(fields: &HashMap<String, String>) -> Vec<LimitKey> {
+    let identity = fields.get(anomaly::CONTEXT_USER).map(|user| LimitKey::Identity(user.clone()));
+    let ip = fields.get(anomaly::CONTEXT_IP).map(|ip| LimitKey::Ip(ip.clone()));
+    identity.into_iter().chain(ip).collect()
+}
+
 /// Gestor principal de seguridad
 pub struct SecurityManager {
-    config: SecurityConfig,
+    config: Arc<RwLock<SecurityConfig>>,
     encryption: Option<EncryptionManager>,
-    threat_detector: ThreatDetector,
-    security_events: Arc<RwLock<Vec<SecurityEvent>>>,
+    threat_detector: Arc<ThreatDetector>,
+    security_events: Arc<RwLock<EventStore>>,
+    audit_log: Option<AuditLog>,
+    rbac: RwLock<Rbac>,
+    tokens: RwLock<TokenValidator>,
+    siem: RwLock<SiemExporters>,
+    rate_limiter: Arc<RwLock<RateLimiter>>,
+    integrity: RwLock<IntegrityMonitor>,
+    certs: Arc<RwLock<Option<CertManager>>>,
+    posture: RwLock<PostureHistory>,
     active_sessions: Arc<RwLock<HashMap<Uuid, SecurityContext>>>,
+    prune_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    sweep_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    rules_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    integrity_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    cert_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
+    posture_handle: RwLock<Option<tokio::task::JoinHandle<()>>>,
 }
 
 impl SecurityManager {
     /// Crear nuevo gestor de seguridad
     pub async fn new(config: SecurityConfig) -> Result<Self> {
         let encryption = if config.encryption_enabled {
-            Some(EncryptionManager::new()?)
+            match keystore::provider_from_config(&config.key_provider)? {
+                Some(provider) => Some(EncryptionManager::with_provider(DEFAULT_RETAINED_KEYS, provider).await?),
+                None => Some(EncryptionManager::new()?),
+            }
         } else {
             None
         };
         
-        let threat_detector = ThreatDetector::new();
+        let rbac = Rbac::from_roles(&config.roles)?;
+        let tokens = TokenValidator::new(&config.token_issuers, config.token_leeway_secs)?;
+        let siem = SiemExporters::start(&config.siem_exporters)?;
+        let policy = config.retention_policy();
+        let posture = PostureHistory::new(chrono::Duration::hours(config.posture_history_hours as i64));
+        let threat_detector = Arc::new(ThreatDetector::with_retention(policy));
+        if let Some(path) = &config.threat_baseline_file {
+            match EventBaselines::load(std::path::Path::new(path)).await {
+                Ok(baselines) => threat_detector.import_baselines(baselines).await,
+                // Unas líneas base ilegibles se vuelven a aprender
+                Err(e) => warn!("⚠️  Líneas base de amenazas inválidas en {}: {}", path, e),
+            }
+        }
+        
+        let audit_log = if config.audit_logging {
+            Some(AuditLog::open(AuditLogOptions {
+                max_file_bytes: config.audit_log_max_file_mb.max(1) * 1024 * 1024,
+                max_files: config.audit_log_max_files,
+                ..AuditLogOptions::new(&config.audit_log_dir)
+            }).await?)
+        } else {
+            None
+        };
         
         Ok(Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
             encryption,
             threat_detector,
-            security_events: Arc::new(RwLock::new(Vec::new())),
+            security_events: Arc::new(RwLock::new(EventStore::new(policy))),
+            audit_log,
+            rbac: RwLock::new(rbac),
+            tokens: RwLock::new(tokens),
+            siem: RwLock::new(siem),
+            rate_limiter: Arc::new(RwLock::new(RateLimiter::default())),
+            integrity: RwLock::new(IntegrityMonitor::default()),
+            certs: Arc::new(RwLock::new(None)),
+            posture: RwLock::new(posture),
             active_sessions: Arc::new(RwLock::new(HashMap::new())),
+            prune_handle: RwLock::new(None),
+            sweep_handle: RwLock::new(None),
+            rules_handle: RwLock::new(None),
+            integrity_handle: RwLock::new(None),
+            cert_handle: RwLock::new(None),
+            posture_handle: RwLock::new(None),
         })
     }
     
+    /// Aplicar reglas de amenazas recibidas en caliente (p. ej. por el fabric)
+    ///
+    /// Se mantienen hasta la próxima recarga del archivo de reglas.
+    pub async fn apply_threat_rules(&self, rules: &ThreatRules) -> usize {
+        let enabled = self.threat_detector.apply_rules(rules).await;
+        info!("🛡️  Reglas de amenazas aplicadas: {} patrones activos", enabled);
+        enabled
+    }
+    
+    /// Cargar y vigilar `threat_rules_file`; sin archivo se restauran los patrones predefinidos
+    pub async fn start_rules_watch(&self) {
+        let rules_file = self.config.read().await.threat_rules_file.clone();
+        let mut handle = self.rules_handle.write().await;
+        if let Some(previous) = handle.take() {
+            previous.abort();
+        }
+        
+        let Some(rules_file) = rules_file else {
+            self.threat_detector.apply_rules(&ThreatRules::default()).await;
+            return;
+        };
+        
+        let detector = self.threat_detector.clone();
+        let source = FileSource::new(rules_file);
+        
+        *handle = Some(tokio::spawn(async move {
+            let mut revision = 0;
+            
+            loop {
+                let document = if revision == 0 { source.load().await } else { source.watch(revision).await };
+                
+                match document {
+                    Ok(Some(document)) => {
+                        revision = document.revision;
+                        match ThreatRules::parse(&document.content, source.format) {
+                            Ok(rules) => {
+                                let enabled = detector.apply_rules(&rules).await;
+                                info!("🛡️  Reglas de amenazas recargadas de {}: {} patrones activos", source.path.display(), enabled);
+                            }
+                            // Unas reglas inválidas no sustituyen a las vigentes
+                            Err(e) => error!("❌ Reglas de amenazas inválidas en {}: {}", source.path.display(), e),
+                        }
+                    }
+                    Ok(None) if revision == 0 => {
+                        warn!("⚠️  Archivo de reglas de amenazas no encontrado: {}", source.path.display());
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!("⚠️  Error vigilando reglas de amenazas: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        }));
+    }
+    
+    /// Iniciar la poda periódica de eventos en memoria, que también guarda las líneas base
+    pub async fn start_pruning(&self) {
+        let interval_secs = self.config.read().await.event_prune_interval_secs.max(1);
+        let stores = [self.security_events.clone(), self.threat_detector.events.clone()];
+        let config = self.config.clone();
+        let detector = self.threat_detector.clone();
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            
+            loop {
+                interval.tick().await;
+                
+                let now = chrono::Utc::now();
+                for store in &stores {
+                    let pruned = store.write().await.prune(now);
+                    if pruned > 0 {
+                        debug!("🧹 {} eventos de seguridad podados", pruned);
+                    }
+                }
+                
+                let baseline_file = config.read().await.threat_baseline_file.clone();
+                if let Some(path) = baseline_file {
+                    if let Err(e) = detector.export_baselines().await.save(std::path::Path::new(&path)).await {
+                        warn!("⚠️  No se pudieron guardar las líneas base de amenazas en {}: {}", path, e);
+                    }
+                }
+            }
+        });
+        
+        if let Some(previous) = self.prune_handle.write().await.replace(handle) {
+            previous.abort();
+        }
+    }
+    
+    /// Iniciar el barrido periódico que revoca las sesiones expiradas
+    pub async fn start_session_sweeper(&self) {
+        let interval_secs = self.config.read().await.session_sweep_interval_secs.max(1);
+        let config = self.config.clone();
+        let sessions = self.active_sessions.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            
+            loop {
+                interval.tick().await;
+                
+                let config = config.read().await.clone();
+                let now = chrono::Utc::now();
+                let mut sessions = sessions.write().await;
+                let before = sessions.len();
+                sessions.retain(|_, context| !context.is_expired(&config, now));
+                
+                let revoked = before - sessions.len();
+                if revoked > 0 {
+                    info!("⌛ {} sesiones de seguridad expiradas revocadas", revoked);
+                }
+                drop(sessions);
+                
+                let max_window = config.rate_limits.values()
+                    .map(|rule| rule.window_secs)
+                    .chain(std::iter::once(config.lockout_window_secs))
+                    .max()
+                    .unwrap_or_default();
+                rate_limiter.write().await.prune(now, chrono::Duration::seconds(max_window as i64));
+            }
+        });
+        
+        if let Some(previous) = self.sweep_handle.write().await.replace(handle) {
+            previous.abort();
+        }
+    }
+    
+    /// Iniciar la verificación periódica de integridad de `integrity_targets`
+    ///
+    /// La línea base se toma al arrancar y cada vez que cambian las rutas vigiladas.
+    pub async fn start_integrity_monitor(self: &Arc<Self>) {
+        let manager = Arc::downgrade(self);
+        
+        let handle = tokio::spawn(async move {
+            loop {
+                let Some(manager) = manager.upgrade() else {
+                    return;
+                };
+                
+                let config = manager.config.read().await.clone();
+                if config.integrity_checks {
+                    if let Err(e) = manager.verify_integrity().await {
+                        error!("❌ Error verificando integridad: {}", e);
+                    }
+                }
+                drop(manager);
+                
+                tokio::time::sleep(std::time::Duration::from_secs(config.integrity_check_interval_secs.max(1))).await;
+            }
+        });
+        
+        if let Some(previous) = self.integrity_handle.write().await.replace(handle) {
+            previous.abort();
+        }
+    }
+    
+    /// Verificar ahora las rutas vigiladas y registrar las violaciones nuevas
+    pub async fn verify_integrity(&self) -> Result<Vec<IntegrityViolation>> {
+        let targets = self.config.read().await.integrity_targets();
+        let mut integrity = self.integrity.write().await;
+        
+        if integrity.paths() != targets.as_slice() {
+            let files = integrity.set_paths(targets).await?;
+            info!("🔏 Línea base de integridad: {} archivos", files);
+            return Ok(Vec::new());
+        }
+        
+        let violations = integrity.verify().await?;
+        drop(integrity);
+        
+        let binary = std::env::current_exe().ok();
+        for violation in &violations {
+            let severity = if binary.as_ref() == Some(&violation.path) {
+                SecuritySeverity::Critical
+            } else {
+                SecuritySeverity::High
+            };
+            
+            let mut context = HashMap::from([("path".to_string(), violation.path.display().to_string())]);
+            match &violation.change {
+                IntegrityChange::Modified { expected, actual } => {
+                    context.insert("expected_sha256".to_string(), expected.clone());
+                    context.insert("actual_sha256".to_string(), actual.clone());
+                }
+                IntegrityChange::Added { actual } => {
+                    context.insert("actual_sha256".to_string(), actual.clone());
+                }
+                IntegrityChange::Missing => {}
+            }
+            
+            self.log_security_event(SecurityEvent {
+                id: Uuid::new_v4(),
+                event_type: SecurityEventType::IntegrityViolation,
+                severity,
+                source: "integrity-monitor".to_string(),
+                target: Some(violation.path.display().to_string()),
+                description: format!("Archivo {}: {}", violation.change, violation.path.display()),
+                context,
+                timestamp: chrono::Utc::now(),
+            }).await?;
+        }
+        
+        Ok(violations)
+    }
+    
+    /// Aceptar los cambios actuales de las rutas vigiladas (p. ej. tras una actualización)
+    pub async fn accept_integrity_changes(&self) -> Result<usize> {
+        let files = self.integrity.write().await.rebaseline().await?;
+        info!("🔏 Línea base de integridad actualizada: {} archivos", files);
+        Ok(files)
+    }
+    
+    /// Iniciar la emisión y rotación periódica de certificados de `cert_services`
+    pub async fn start_cert_rotation(&self) {
+        let interval_secs = self.config.read().await.cert_check_interval_secs.max(1);
+        let config = self.config.clone();
+        let certs = self.certs.clone();
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            
+            loop {
+                interval.tick().await;
+                
+                let config = config.read().await.clone();
+                if !config.cert_management {
+                    continue;
+                }
+                
+                let validity = chrono::Duration::hours(config.cert_validity_hours as i64);
+                let ca_validity = chrono::Duration::days(config.cert_ca_validity_days as i64);
+                let renew_before = chrono::Duration::hours(config.cert_renew_before_hours as i64);
+                
+                let mut certs = certs.write().await;
+                if certs.is_none() {
+                    match CertManager::open(&config.cert_dir, ca_validity).await {
+                        Ok(manager) => *certs = Some(manager),
+                        Err(e) => {
+                            error!("❌ No se pudo abrir la CA en {}: {}", config.cert_dir, e);
+                            continue;
+                        }
+                    }
+                }
+                let Some(manager) = certs.as_mut() else {
+                    continue;
+                };
+                
+                match manager.rotate_due(&config.cert_services, validity, ca_validity, renew_before).await {
+                    Ok(renewed) if !renewed.is_empty() => {
+                        info!("📜 Certificados renovados en {}: {}", config.cert_dir, renewed.join(", "));
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Error rotando certificados: {}", e),
+                }
+            }
+        });
+        
+        if let Some(previous) = self.cert_handle.write().await.replace(handle) {
+            previous.abort();
+        }
+    }
+    
+    /// Iniciar el muestreo periódico de la postura de seguridad
+    pub async fn start_posture_sampling(self: &Arc<Self>) {
+        let interval_secs = self.config.read().await.posture_sample_interval_secs.max(1);
+        let manager = Arc::downgrade(self);
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            
+            loop {
+                interval.tick().await;
+                
+                let Some(manager) = manager.upgrade() else {
+                    return;
+                };
+                if let Err(e) = manager.sample_posture().await {
+                    error!("❌ Error registrando la postura de seguridad: {}", e);
+                }
+            }
+        });
+        
+        if let Some(previous) = self.posture_handle.write().await.replace(handle) {
+            previous.abort();
+        }
+    }
+    
+    /// Postura actual a partir de los eventos de la última hora y las protecciones activas
+    pub async fn get_posture(&self) -> SecurityPosture {
+        let config = self.config.read().await.clone();
+        let now = chrono::Utc::now();
+        let cutoff = now - chrono::Duration::minutes(posture::ACTIVE_WINDOW_MINUTES);
+        
+        let events = self.security_events.read().await;
+        SecurityPosture::assess(&config, events.since(cutoff), now)
+    }
+    
+    /// Guardar la postura actual en el historial y notificar si ha caído
+    pub async fn sample_posture(&self) -> Result<SecurityPosture> {
+        let posture = self.get_posture().await;
+        let (window, threshold) = {
+            let config = self.config.read().await;
+            (
+                chrono::Duration::minutes(config.posture_regression_window_mins as i64),
+                config.posture_regression_threshold,
+            )
+        };
+        
+        let regression = self.posture.write().await.record(&posture, window, threshold);
+        if let Some(regression) = regression {
+            self.log_security_event(SecurityEvent {
+                id: Uuid::new_v4(),
+                event_type: SecurityEventType::SuspiciousActivity,
+                severity: SecuritySeverity::High,
+                source: "security-posture".to_string(),
+                target: None,
+                description: format!(
+                    "Postura de seguridad degradada de {:.0} a {:.0} ({} amenazas activas)",
+                    regression.from, regression.to, posture.active_threats
+                ),
+                context: HashMap::from([
+                    ("posture_from".to_string(), format!("{:.1}", regression.from)),
+                    ("posture_to".to_string(), format!("{:.1}", regression.to)),
+                ]),
+                timestamp: chrono::Utc::now(),
+            }).await?;
+        }
+        
+        Ok(posture)
+    }
+    
+    /// Historial de la postura desde `since`, para graficarlo
+    pub async fn get_posture_history(&self, since: chrono::DateTime<chrono::Utc>) -> Vec<PostureSample> {
+        self.posture.read().await.since(since).copied().collect()
+    }
+    
+    /// Certificado vigente de un servicio (`fabric`, `metrics`, `admin`)
+    pub async fn certificate(&self, service: &str) -> Option<IssuedCert> {
+        self.certs.read().await.as_ref()?.certificate(service).cloned()
+    }
+    
+    /// CA del nodo en PEM, para verificar a los demás componentes
+    pub async fn ca_certificate(&self) -> Option<String> {
+        Some(self.certs.read().await.as_ref()?.ca_pem())
+    }
+    
+    /// Comprobar y contar `action` para la identidad y la IP del contexto
+    ///
+    /// Devuelve `false` si alguna supera el límite de la acción o está bloqueada.
+    pub async fn check_rate_limit(&self, context: &SecurityContext, action: &str) -> Result<bool> {
+        let keys = limit_keys(&session_fields(context));
+        if keys.is_empty() {
+            return Ok(true);
+        }
+        
+        let rule = {
+            let config = self.config.read().await;
+            match config.rate_limits.get(action).or_else(|| config.rate_limits.get(ratelimit::DEFAULT_ACTION)) {
+                Some(rule) => *rule,
+                None => return Ok(true),
+            }
+        };
+        
+        let decision = self.rate_limiter.write().await.check(&keys, action, rule, chrono::Utc::now());
+        let (key, description) = match decision {
+            RateDecision::Allowed => return Ok(true),
+            RateDecision::Limited { key, retry_after } => {
+                let description = format!("Límite de {} superado por {} (reintentar tras {})", action, key, retry_after);
+                (key, description)
+            }
+            RateDecision::LockedOut { key, until } => {
+                let description = format!("{} bloqueado hasta {} al intentar {}", key, until, action);
+                (key, description)
+            }
+        };
+        
+        let mut fields = session_fields(context);
+        fields.insert("action".to_string(), action.to_string());
+        fields.insert("limit_key".to_string(), key.to_string());
+        self.log_security_event(SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::SuspiciousActivity,
+            severity: SecuritySeverity::Medium,
+            source: context.session_id.to_string(),
+            target: Some(action.to_string()),
+            description,
+            context: fields,
+            timestamp: chrono::Utc::now(),
+        }).await?;
+        
+        Ok(false)
+    }
+    
+    /// Levantar el bloqueo de una identidad o IP
+    pub async fn unlock(&self, key: &LimitKey) -> bool {
+        let unlocked = self.rate_limiter.write().await.unlock(key);
+        if unlocked {
+            info!("🔓 Bloqueo levantado: {}", key);
+        }
+        unlocked
+    }
+    
+    /// Si la sesión sigue activa y dentro de sus límites
+    pub async fn is_session_valid(&self, session_id: Uuid) -> bool {
+        let config = self.config.read().await;
+        self.active_sessions
+            .read()
+            .await
+            .get(&session_id)
+            .is_some_and(|context| !context.is_expired(&config, chrono::Utc::now()))
+    }
+    
+    /// Contexto registrado de una sesión activa y dentro de sus límites
+    pub async fn get_session(&self, session_id: Uuid) -> Option<SecurityContext> {
+        let config = self.config.read().await;
+        self.active_sessions
+            .read()
+            .await
+            .get(&session_id)
+            .filter(|context| !context.is_expired(&config, chrono::Utc::now()))
+            .cloned()
+    }
+    
     /// Crear contexto de seguridad
     pub async fn create_security_context(
         &self,
         user_id: Option<String>,
         security_level: SecurityLevel,
         permissions: Vec<String>,
+        roles: Vec<String>,
         source_ip: Option<String>,
     ) -> SecurityContext {
         let context = SecurityContext {
@@ -340,8 +1395,11 @@ impl SecurityManager {
             session_id: Uuid::new_v4(),
             security_level,
             permissions,
+            roles,
             source_ip,
             timestamp: chrono::Utc::now(),
+            last_activity: chrono::Utc::now(),
+            expires_at: None,
         };
         
         // Registrar sesión activa
@@ -351,6 +1409,62 @@ impl SecurityManager {
         context
     }
     
+    /// Autenticar un JWT y abrir un contexto con el nivel, permisos y roles de sus claims
+    pub async fn authenticate_token(&self, jwt: &str) -> Result<SecurityContext> {
+        let identity = match self.tokens.read().await.validate(jwt) {
+            Ok(identity) => identity,
+            Err(e) => {
+                self.log_security_event(SecurityEvent {
+                    id: Uuid::new_v4(),
+                    event_type: SecurityEventType::AuthenticationFailure,
+                    severity: SecuritySeverity::Medium,
+                    source: "token".to_string(),
+                    target: None,
+                    description: format!("Token rechazado: {}", e),
+                    context: HashMap::new(),
+                    timestamp: chrono::Utc::now(),
+                }).await?;
+                
+                return Err(e);
+            }
+        };
+        
+        debug!("🔐 Token de {:?} aceptado (emisor {})", identity.subject, identity.issuer);
+        let mut context = self.create_security_context(
+            identity.subject,
+            identity.security_level,
+            identity.permissions,
+            identity.roles,
+            None,
+        ).await;
+        
+        context.expires_at = identity.expires_at;
+        if let Some(session) = self.active_sessions.write().await.get_mut(&context.session_id) {
+            session.expires_at = identity.expires_at;
+        }
+        
+        Ok(context)
+    }
+    
+    /// Asignar roles a una sesión activa
+    pub async fn assign_roles(&self, session_id: Uuid, roles: Vec<String>) -> Result<SecurityContext> {
+        {
+            let rbac = self.rbac.read().await;
+            if let Some(unknown) = roles.iter().find(|role| !rbac.has_role(role)) {
+                return Err(anyhow!("Rol no definido: {}", unknown));
+            }
+        }
+        
+        let mut sessions = self.active_sessions.write().await;
+        let context = sessions
+            .get_mut(&session_id)
+            .ok_or_else(|| anyhow!("Sesión no encontrada: {}", session_id))?;
+        context.roles = roles;
+        
+        info!("🔐 Roles de la sesión {:?}: {:?}", session_id, context.roles);
+        Ok(context.clone())
+    }
+    
     /// Verificar autorización
     pub async fn check_authorization(
         &self,
@@ -358,6 +1472,23 @@ impl SecurityManager {
         required_permission: &str,
         required_level: SecurityLevel,
     ) -> Result<bool> {
+        // Verificar que la sesión no haya expirado ni sido revocada
+        if !self.is_session_valid(context.session_id).await {
+            self.active_sessions.write().await.remove(&context.session_id);
+            self.log_security_event(SecurityEvent {
+                id: Uuid::new_v4(),
+                event_type: SecurityEventType::AuthorizationDenied,
+                severity: SecuritySeverity::Medium,
+                source: context.session_id.to_string(),
+                target: None,
+                description: "Sesión expirada o revocada".to_string(),
+                context: session_fields(context),
+                timestamp: chrono::Utc::now(),
+            }).await?;
+            
+            return Ok(false);
+        }
+        
         // Verificar nivel de seguridad
         if context.security_level < required_level {
             self.log_security_event(SecurityEvent {
@@ -368,15 +1499,21 @@ impl SecurityManager {
                 target: None,
                 description: format!("Nivel de seguridad insuficiente: {:?} < {:?}", 
                                    context.security_level, required_level),
-                context: HashMap::new(),
+                context: session_fields(context),
                 timestamp: chrono::Utc::now(),
             }).await?;
             
             return Ok(false);
         }
         
-        // Verificar permisos
-        if !context.permissions.contains(&required_permission.to_string()) {
+        // Verificar permisos; los roles vigentes son los de la sesión activa
+        let roles = match self.active_sessions.read().await.get(&context.session_id) {
+            Some(session) => session.roles.clone(),
+            None => context.roles.clone(),
+        };
+        let allowed = self.rbac.read().await.allows(&roles, &context.permissions, required_permission);
+        
+        if !allowed {
             self.log_security_event(SecurityEvent {
                 id: Uuid::new_v4(),
                 event_type: SecurityEventType::AuthorizationDenied,
@@ -384,13 +1521,20 @@ impl SecurityManager {
                 source: context.session_id.to_string(),
                 target: None,
                 description: format!("Permiso faltante: {}", required_permission),
-                context: HashMap::new(),
+                context: session_fields(context),
                 timestamp: chrono::Utc::now(),
             }).await?;
             
             return Ok(false);
         }
         
+        if let Some(user) = &context.user_id {
+            if self.config.read().await.threat_detection {
+                let threats = self.threat_detector.analyze_access(user, context.source_ip.as_deref()).await;
+                self.record_events(threats).await;
+            }
+        }
+        
         Ok(true)
     }
     
@@ -414,23 +1558,134 @@ impl SecurityManager {
         }
     }
     
+    /// Encriptar un flujo (snapshots, logs) sin cargarlo entero en memoria
+    pub async fn encrypt_data_stream<R, W>(&self, reader: R, writer: W, context: &SecurityContext) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let encryption = self.encryption.as_ref().ok_or_else(|| anyhow!("Encriptación no habilitada"))?;
+        encryption.encrypt_stream(reader, writer, context.session_id.as_bytes()).await
+    }
+    
+    /// Desencriptar un flujo de `encrypt_data_stream`
+    pub async fn decrypt_data_stream<R, W>(&self, reader: R, writer: W, context: &SecurityContext) -> Result<u64>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let encryption = self.encryption.as_ref().ok_or_else(|| anyhow!("Encriptación no habilitada"))?;
+        encryption.decrypt_stream(reader, writer, context.session_id.as_bytes()).await
+    }
+    
+    /// Re-encriptar con la clave actual datos sellados con una anterior
+    pub fn reencrypt_data(&self, encrypted_data: &[u8], context: &SecurityContext) -> Result<Vec<u8>> {
+        if let Some(encryption) = &self.encryption {
+            let associated_data = context.session_id.as_bytes();
+            encryption.reencrypt(encrypted_data, associated_data)
+        } else {
+            Err(anyhow!("Encriptación no habilitada"))
+        }
+    }
+    
+    /// Rotar la clave de encriptación
+    pub async fn rotate_keys(&self) -> Result<u32> {
+        match &self.encryption {
+            Some(encryption) => encryption.rotate_keys().await,
+            None => Err(anyhow!("Encriptación no habilitada")),
+        }
+    }
+    
     /// Registrar evento de seguridad
     pub async fn log_security_event(&self, event: SecurityEvent) -> Result<()> {
-        if self.config.audit_logging {
+        let config = self.config.read().await.clone();
+        if config.audit_logging {
             info!("🚨 Evento de seguridad: {:?} - {}", event.severity, event.description);
         }
         
         // Analizar amenazas
-        if self.config.threat_detection {
-            let threats = self.threat_detector.analyze_event(event.clone()).await?;
-            for threat in threats {
-                warn!("⚠️  Amenaza detectada: {}", threat.description);
-                self.security_events.write().await.push(threat);
+        let threats = if config.threat_detection {
+            self.threat_detector.analyze_event(event.clone()).await?
+        } else {
+            Vec::new()
+        };
+        
+        // Los fallos de autenticación alimentan el bloqueo por fuerza bruta
+        let lockouts = if event.event_type == SecurityEventType::AuthenticationFailure {
+            let keys = limit_keys(&event.context);
+            self.rate_limiter.write().await.record_failure(&keys, config.lockout_policy(), chrono::Utc::now())
+        } else {
+            Vec::new()
+        };
+        
+        let lockout_events: Vec<SecurityEvent> = lockouts.into_iter().map(|(key, until)| SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::SuspiciousActivity,
+            severity: SecuritySeverity::High,
+            source: "rate-limiter".to_string(),
+            target: Some(key.to_string()),
+            description: format!("{} bloqueado hasta {} por fallos de autenticación repetidos", key, until),
+            context: HashMap::from([("limit_key".to_string(), key.to_string())]),
+            timestamp: chrono::Utc::now(),
+        }).collect();
+        
+        self.record_events(threats.into_iter().chain(std::iter::once(event)).chain(lockout_events)).await;
+        Ok(())
+    }
+    
+    /// Guardar eventos ya analizados en memoria y en el registro de auditoría
+    async fn record_events(&self, events: impl IntoIterator<Item = SecurityEvent>) {
+        for event in events {
+            if matches!(event.event_type, SecurityEventType::ThreatDetected | SecurityEventType::AnomalousAccess) {
+                warn!("⚠️  Amenaza detectada: {}", event.description);
             }
+            
+            // Un fallo de disco no debe bloquear la operación auditada
+            if let Some(audit_log) = &self.audit_log {
+                if let Err(e) = audit_log.append(&event).await {
+                    error!("❌ No se pudo escribir en el registro de auditoría: {}", e);
+                }
+            }
+            
+            self.siem.read().await.export(&event);
+            self.security_events.write().await.push(event);
+        }
+    }
+    
+    /// Correlacionar el uso de recursos de un origen con `ResourceAbuse`
+    pub async fn record_resource_usage(&self, source: &str, cpu_percent: f64, memory_bytes: u64) {
+        if !self.config.read().await.threat_detection {
+            return;
         }
         
-        self.security_events.write().await.push(event);
-        Ok(())
+        let snapshot = ResourceSnapshot {
+            cpu_percent,
+            memory_bytes,
+            timestamp: chrono::Utc::now(),
+        };
+        let threats = self.threat_detector.record_resource_usage(source, snapshot).await;
+        self.record_events(threats).await;
+    }
+    
+    /// Buscar eventos en el registro de auditoría persistente
+    ///
+    /// Sin registro persistente se consultan los eventos en memoria.
+    pub async fn get_events(
+        &self,
+        filter: &SecurityEventFilter,
+        range: impl RangeBounds<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<SecurityEvent>> {
+        match &self.audit_log {
+            Some(audit_log) => audit_log.query(filter, range).await,
+            None => Ok(self
+                .security_events
+                .read()
+                .await
+                .iter()
+                .filter(|e| range.contains(&e.timestamp) && filter.matches(e))
+                .cloned()
+                .collect()),
+        }
     }
     
     /// Obtener eventos de seguridad recientes
@@ -440,8 +1695,7 @@ impl SecurityManager {
         self.security_events
             .read()
             .await
-            .iter()
-            .filter(|e| e.timestamp >= cutoff)
+            .since(cutoff)
             .cloned()
             .collect()
     }
@@ -457,6 +1711,19 @@ impl SecurityManager {
     pub async fn shutdown(&self) -> Result<()> {
         info!("🛑 Cerrando SecurityManager");
         
+        for handle in [&self.prune_handle, &self.sweep_handle, &self.rules_handle, &self.integrity_handle, &self.cert_handle, &self.posture_handle] {
+            if let Some(handle) = handle.write().await.take() {
+                handle.abort();
+            }
+        }
+        
+        if let Some(path) = self.config.read().await.threat_baseline_file.clone() {
+            self.threat_detector.export_baselines().await.save(std::path::Path::new(&path)).await?;
+        }
+        
+        // Enviar a los SIEM los eventos pendientes
+        std::mem::take(&mut *self.siem.write().await).shutdown().await;
+        
         // Cerrar sesiones activas
         self.active_sessions.write().await.clear();
         
@@ -466,17 +1733,105 @@ impl SecurityManager {
     
     /// Obtener estadísticas de seguridad
     pub async fn get_security_stats(&self) -> HashMap<String, u64> {
-        let events = self.security_events.read().await;
-        let mut stats = HashMap::new();
-        
-        for event in events.iter() {
-            let key = format!("{:?}", event.event_type);
-            *stats.entry(key).or_insert(0) += 1;
-        }
+        // Incluye los eventos ya podados, resumidos por hora
+        let mut stats = self.security_events.read().await.counts_by_type();
         
         stats.insert("active_sessions".to_string(), 
                      self.active_sessions.read().await.len() as u64);
         
+        let rate_limiter = self.rate_limiter.read().await;
+        stats.insert("rate_limited_total".to_string(), rate_limiter.limited_total);
+        stats.insert("lockouts_total".to_string(), rate_limiter.lockouts_total);
+        stats.insert("active_lockouts".to_string(), rate_limiter.active_lockouts(chrono::Utc::now()) as u64);
+        
+        let integrity = self.integrity.read().await;
+        stats.insert("integrity_files".to_string(), integrity.baseline().len() as u64);
+        stats.insert("integrity_violations_total".to_string(), integrity.violations_total);
+        
+        stats.insert("threat_baselines".to_string(), self.threat_detector.rates.read().await.len() as u64);
+        
+        if let Some(sample) = self.posture.read().await.latest() {
+            stats.insert("posture_score".to_string(), sample.score.round() as u64);
+        }
+        
+        if let Some(certs) = self.certs.read().await.as_ref() {
+            let now = chrono::Utc::now();
+            stats.insert("cert_rotations_total".to_string(), certs.rotations_total);
+            for (name, expires_at) in certs.expiries() {
+                stats.insert(format!("cert_expiry_seconds.{}", name), (expires_at - now).num_seconds().max(0) as u64);
+            }
+        }
+        
         stats
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl StatusProvider for SecurityManager {
+    fn section(&self) -> &'static str {
+        "security"
+    }
+    
+    async fn status(&self) -> serde_json::Value {
+        serde_json::json!(self.get_security_stats().await)
+    }
+}
+
+#[async_trait]
+impl ConfigSubscriber for SecurityManager {
+    fn subscriber_name(&self) -> &str {
+        "security"
+    }
+
+    fn sections(&self) -> &[ConfigSection] {
+        &[ConfigSection::Security]
+    }
+
+    async fn on_config_change(&self, config: &CoreConfig, changes: &[FieldChange]) -> Result<()> {
+        let mut updated = config.security.clone();
+        let mut current = self.config.write().await;
+
+        // Las claves de encriptación no se regeneran en caliente
+        if updated.encryption_enabled != current.encryption_enabled {
+            warn!("⚠️  Cambio de encriptación ignorado hasta el próximo reinicio");
+            updated.encryption_enabled = current.encryption_enabled;
+        }
+        if updated.key_provider != current.key_provider {
+            warn!("⚠️  Cambio de almacén de claves ignorado hasta el próximo reinicio");
+            updated.key_provider = current.key_provider.clone();
+        }
+
+        if updated.roles != current.roles {
+            *self.rbac.write().await = Rbac::from_roles(&updated.roles)?;
+            info!("🔐 Política RBAC recargada: {} roles", updated.roles.len());
+        }
+        
+        if updated.token_issuers != current.token_issuers || updated.token_leeway_secs != current.token_leeway_secs {
+            *self.tokens.write().await = TokenValidator::new(&updated.token_issuers, updated.token_leeway_secs)?;
+            info!("🔐 Emisores de tokens recargados: {}", updated.token_issuers.len());
+        }
+        
+        if updated.siem_exporters != current.siem_exporters {
+            let exporters = SiemExporters::start(&updated.siem_exporters)?;
+            std::mem::replace(&mut *self.siem.write().await, exporters).shutdown().await;
+            info!("📤 Exportadores SIEM recargados: {}", updated.siem_exporters.len());
+        }
+        
+        let rules_changed = updated.threat_rules_file != current.threat_rules_file;
+        
+        let policy = updated.retention_policy();
+        self.security_events.write().await.set_policy(policy);
+        self.threat_detector.events.write().await.set_policy(policy);
+        self.posture.write().await.set_max_age(chrono::Duration::hours(updated.posture_history_hours as i64));
+        
+        *current = updated;
+        drop(current);
+        
+        if rules_changed {
+            self.start_rules_watch().await;
+        }
+        
+        info!("🔐 Configuración de seguridad actualizada: {} cambios", changes.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+//! Postura de seguridad
+//!
+//! Puntuación de 0 a 100 a partir de las amenazas activas, los eventos
+//! recientes y las protecciones deshabilitadas, con el mismo baremo que
+//! SecurityCore. Las muestras periódicas se guardan en `PostureHistory` para
+//! graficarlas y detectar caídas bruscas.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::{SecurityConfig, SecurityEvent, SecurityEventType, SecuritySeverity};
+
+/// Antigüedad de los eventos que cuentan para la postura, en minutos
+pub const ACTIVE_WINDOW_MINUTES: i64 = 60;
+
+/// Penalización máxima por eventos recientes que no son amenazas
+const MAX_EVENT_PENALTY: f64 = 20.0;
+
+/// Postura en un instante
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityPosture {
+    pub score: f64,
+    pub active_threats: usize,
+    pub recent_events: usize,
+    /// Protecciones deshabilitadas en la configuración
+    pub disabled_protections: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SecurityPosture {
+    /// Evaluar la postura con los eventos de la última ventana
+    pub fn assess<'a>(config: &SecurityConfig, events: impl IntoIterator<Item = &'a SecurityEvent>, now: DateTime<Utc>) -> Self {
+        let mut score = 100.0;
+        let mut active_threats = 0;
+        let mut recent_events = 0;
+
+        for event in events {
+            if is_threat(&event.event_type) {
+                active_threats += 1;
+                score -= match event.severity {
+                    SecuritySeverity::Critical => 30.0,
+                    SecuritySeverity::High => 20.0,
+                    SecuritySeverity::Medium => 10.0,
+                    SecuritySeverity::Low => 5.0,
+                    SecuritySeverity::Info => 1.0,
+                };
+            } else {
+                recent_events += 1;
+            }
+        }
+        score -= (recent_events as f64 * 0.5).min(MAX_EVENT_PENALTY);
+
+        let protections = [
+            ("sandboxing", config.enable_sandboxing),
+            ("encryption", config.encryption_enabled),
+            ("integrity_checks", config.integrity_checks),
+            ("threat_detection", config.threat_detection),
+            ("audit_logging", config.audit_logging),
+        ];
+        let disabled_protections: Vec<String> = protections
+            .iter()
+            .filter(|(_, enabled)| !enabled)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        score -= disabled_protections.len() as f64 * 10.0;
+
+        Self {
+            score: f64::clamp(score, 0.0, 100.0),
+            active_threats,
+            recent_events,
+            disabled_protections,
+            timestamp: now,
+        }
+    }
+}
+
+fn is_threat(event_type: &SecurityEventType) -> bool {
+    matches!(
+        event_type,
+        SecurityEventType::ThreatDetected
+            | SecurityEventType::AnomalousAccess
+            | SecurityEventType::IntegrityViolation
+            | SecurityEventType::SandboxBreach
+    )
+}
+
+/// Muestra del historial
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PostureSample {
+    pub timestamp: DateTime<Utc>,
+    pub score: f64,
+}
+
+/// Caída de la puntuación respecto al máximo de la ventana
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostureRegression {
+    pub from: f64,
+    pub to: f64,
+}
+
+/// Historial acotado de la postura
+#[derive(Debug, Default)]
+pub struct PostureHistory {
+    samples: VecDeque<PostureSample>,
+    max_age: Duration,
+    /// Si ya se notificó la regresión en curso
+    regressed: bool,
+}
+
+impl PostureHistory {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            max_age,
+            regressed: false,
+        }
+    }
+
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = max_age;
+    }
+
+    /// Añadir una muestra; devuelve la regresión si la caída frente al máximo
+    /// de `window` alcanza `threshold` puntos y no se había notificado ya
+    pub fn record(&mut self, posture: &SecurityPosture, window: Duration, threshold: f64) -> Option<PostureRegression> {
+        let peak = self
+            .since(posture.timestamp - window)
+            .map(|sample| sample.score)
+            .reduce(f64::max);
+
+        self.samples.push_back(PostureSample {
+            timestamp: posture.timestamp,
+            score: posture.score,
+        });
+        let cutoff = posture.timestamp - self.max_age;
+        while self.samples.front().is_some_and(|sample| sample.timestamp < cutoff) {
+            self.samples.pop_front();
+        }
+
+        let regression = peak
+            .filter(|peak| peak - posture.score >= threshold)
+            .map(|peak| PostureRegression { from: peak, to: posture.score });
+        let notify = regression.is_some() && !self.regressed;
+        self.regressed = regression.is_some();
+        regression.filter(|_| notify)
+    }
+
+    /// Muestras desde `cutoff`, de la más antigua a la más reciente
+    pub fn since(&self, cutoff: DateTime<Utc>) -> impl Iterator<Item = &PostureSample> {
+        self.samples.iter().filter(move |sample| sample.timestamp >= cutoff)
+    }
+
+    pub fn latest(&self) -> Option<&PostureSample> {
+        self.samples.back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn event(event_type: SecurityEventType, severity: SecuritySeverity) -> SecurityEvent {
+        SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type,
+            severity,
+            source: "test".to_string(),
+            target: None,
+            description: String::new(),
+            context: HashMap::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_score_and_regression_history() {
+        let mut config = SecurityConfig::default();
+        let now = Utc::now();
+        assert_eq!(SecurityPosture::assess(&config, &[], now).score, 100.0);
+
+        let events = vec![
+            event(SecurityEventType::ThreatDetected, SecuritySeverity::High),
+            event(SecurityEventType::AuthenticationFailure, SecuritySeverity::Medium),
+            event(SecurityEventType::AuthenticationFailure, SecuritySeverity::Medium),
+        ];
+        config.audit_logging = false;
+        let posture = SecurityPosture::assess(&config, &events, now);
+        assert_eq!((posture.active_threats, posture.recent_events), (1, 2));
+        assert_eq!(posture.score, 100.0 - 20.0 - 1.0 - 10.0);
+        assert_eq!(posture.disabled_protections, vec!["audit_logging"]);
+
+        let mut history = PostureHistory::new(Duration::hours(1));
+        let at = |minutes: i64, score: f64| SecurityPosture {
+            score,
+            timestamp: now + Duration::minutes(minutes),
+            ..posture.clone()
+        };
+        let window = Duration::minutes(30);
+        assert!(history.record(&at(0, 95.0), window, 15.0).is_none());
+        assert!(history.record(&at(1, 90.0), window, 15.0).is_none());
+        assert_eq!(history.record(&at(2, 70.0), window, 15.0), Some(PostureRegression { from: 95.0, to: 70.0 }));
+        // La misma regresión no se repite mientras dura
+        assert!(history.record(&at(3, 65.0), window, 15.0).is_none());
+        // Fuera de la ventana el máximo anterior ya no cuenta
+        assert!(history.record(&at(40, 65.0), window, 15.0).is_none());
+
+        assert_eq!(history.since(now + Duration::minutes(2)).count(), 3);
+        history.record(&at(90, 80.0), window, 15.0);
+        assert_eq!(history.since(now).count(), 2);
+        assert_eq!(history.latest().unwrap().score, 80.0);
+    }
+}
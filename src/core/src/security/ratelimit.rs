@@ -0,0 +1,188 @@
+//! Limitación de frecuencia y bloqueo por fuerza bruta
+//!
+//! Ventanas deslizantes por identidad y por IP de origen para cada acción,
+//! y bloqueo temporal de la identidad o IP que acumula demasiados fallos de
+//! autenticación en poco tiempo.
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// Acción cuyo límite se aplica a las que no tienen uno propio
+pub const DEFAULT_ACTION: &str = "default";
+
+/// Límite de una acción
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RateLimitRule {
+    pub max_requests: u32,
+    pub window_secs: u64,
+}
+
+/// Política de bloqueo tras fallos de autenticación
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LockoutPolicy {
+    pub max_failures: u32,
+    pub window: Duration,
+    pub duration: Duration,
+}
+
+/// Sujeto de un límite
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LimitKey {
+    Identity(String),
+    Ip(String),
+}
+
+impl fmt::Display for LimitKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitKey::Identity(identity) => write!(f, "identity:{}", identity),
+            LimitKey::Ip(ip) => write!(f, "ip:{}", ip),
+        }
+    }
+}
+
+/// Resultado de comprobar un límite
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateDecision {
+    Allowed,
+    /// Límite de la acción superado; se puede reintentar tras `retry_after`
+    Limited { key: LimitKey, retry_after: DateTime<Utc> },
+    /// Bloqueado por fallos de autenticación hasta `until`
+    LockedOut { key: LimitKey, until: DateTime<Utc> },
+}
+
+/// Contadores de ventanas y bloqueos vigentes
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    requests: HashMap<(LimitKey, String), VecDeque<DateTime<Utc>>>,
+    failures: HashMap<LimitKey, VecDeque<DateTime<Utc>>>,
+    lockouts: HashMap<LimitKey, DateTime<Utc>>,
+    pub limited_total: u64,
+    pub lockouts_total: u64,
+}
+
+impl RateLimiter {
+    /// Contar la acción para cada sujeto si ninguno está bloqueado ni al límite
+    pub fn check(&mut self, keys: &[LimitKey], action: &str, rule: RateLimitRule, now: DateTime<Utc>) -> RateDecision {
+        if let Some((key, until)) = keys.iter().find_map(|key| self.locked_until(key, now).map(|until| (key, until))) {
+            return RateDecision::LockedOut { key: key.clone(), until };
+        }
+
+        let window = Duration::seconds(rule.window_secs as i64);
+        for key in keys {
+            let requests = self.requests.entry((key.clone(), action.to_string())).or_default();
+            while requests.front().is_some_and(|at| *at <= now - window) {
+                requests.pop_front();
+            }
+
+            if requests.len() >= rule.max_requests as usize {
+                self.limited_total += 1;
+                let oldest = requests.front().copied().unwrap_or(now);
+                return RateDecision::Limited { key: key.clone(), retry_after: oldest + window };
+            }
+        }
+
+        for key in keys {
+            self.requests.entry((key.clone(), action.to_string())).or_default().push_back(now);
+        }
+        RateDecision::Allowed
+    }
+
+    /// Registrar un fallo de autenticación; devuelve los sujetos recién bloqueados
+    pub fn record_failure(&mut self, keys: &[LimitKey], policy: LockoutPolicy, now: DateTime<Utc>) -> Vec<(LimitKey, DateTime<Utc>)> {
+        let mut locked = Vec::new();
+
+        for key in keys {
+            if self.locked_until(key, now).is_some() {
+                continue;
+            }
+
+            let failures = self.failures.entry(key.clone()).or_default();
+            failures.push_back(now);
+            while failures.front().is_some_and(|at| *at <= now - policy.window) {
+                failures.pop_front();
+            }
+
+            if failures.len() >= policy.max_failures.max(1) as usize {
+                failures.clear();
+                let until = now + policy.duration;
+                self.lockouts.insert(key.clone(), until);
+                self.lockouts_total += 1;
+                locked.push((key.clone(), until));
+            }
+        }
+
+        locked
+    }
+
+    pub fn locked_until(&self, key: &LimitKey, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.lockouts.get(key).copied().filter(|until| *until > now)
+    }
+
+    /// Levantar un bloqueo antes de tiempo
+    pub fn unlock(&mut self, key: &LimitKey) -> bool {
+        self.failures.remove(key);
+        self.lockouts.remove(key).is_some()
+    }
+
+    pub fn active_lockouts(&self, now: DateTime<Utc>) -> usize {
+        self.lockouts.values().filter(|until| **until > now).count()
+    }
+
+    /// Descartar ventanas vacías y bloqueos vencidos
+    pub fn prune(&mut self, now: DateTime<Utc>, max_window: Duration) {
+        self.requests.retain(|_, requests| requests.back().is_some_and(|at| *at > now - max_window));
+        self.failures.retain(|_, failures| failures.back().is_some_and(|at| *at > now - max_window));
+        self.lockouts.retain(|_, until| *until > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_and_lockout() {
+        let mut limiter = RateLimiter::default();
+        let now = Utc::now();
+        let keys = [LimitKey::Identity("ana".to_string()), LimitKey::Ip("10.0.0.7".to_string())];
+        let rule = RateLimitRule { max_requests: 2, window_secs: 60 };
+
+        assert_eq!(limiter.check(&keys, "config.write", rule, now), RateDecision::Allowed);
+        assert_eq!(limiter.check(&keys, "config.write", rule, now), RateDecision::Allowed);
+        assert!(matches!(
+            limiter.check(&keys, "config.write", rule, now),
+            RateDecision::Limited { key: LimitKey::Identity(_), .. }
+        ));
+        // Otra acción y la misma tras la ventana tienen su propio presupuesto
+        assert_eq!(limiter.check(&keys, "metrics.read", rule, now), RateDecision::Allowed);
+        assert_eq!(limiter.check(&keys, "config.write", rule, now + Duration::seconds(61)), RateDecision::Allowed);
+        // La IP comparte ventana con otras identidades
+        let other = [LimitKey::Identity("luis".to_string()), LimitKey::Ip("10.0.0.7".to_string())];
+        assert!(matches!(
+            limiter.check(&other, "metrics.read", RateLimitRule { max_requests: 1, window_secs: 60 }, now),
+            RateDecision::Limited { key: LimitKey::Ip(_), .. }
+        ));
+
+        let policy = LockoutPolicy {
+            max_failures: 3,
+            window: Duration::minutes(5),
+            duration: Duration::minutes(15),
+        };
+        let ip = [LimitKey::Ip("203.0.113.9".to_string())];
+        assert!(limiter.record_failure(&ip, policy, now).is_empty());
+        assert!(limiter.record_failure(&ip, policy, now).is_empty());
+        assert_eq!(limiter.record_failure(&ip, policy, now).len(), 1);
+        assert!(matches!(limiter.check(&ip, "metrics.read", rule, now), RateDecision::LockedOut { .. }));
+        assert_eq!(limiter.active_lockouts(now), 1);
+
+        // El bloqueo vence solo
+        assert_eq!(limiter.check(&ip, "metrics.read", rule, now + Duration::minutes(16)), RateDecision::Allowed);
+        limiter.prune(now + Duration::hours(1), Duration::minutes(5));
+        assert_eq!(limiter.active_lockouts(now), 0);
+        assert_eq!((limiter.limited_total, limiter.lockouts_total), (2, 1));
+    }
+}
@@ -0,0 +1,162 @@
+//! Control de acceso basado en roles
+//!
+//! Los roles se definen en `[security.roles.<nombre>]` con un conjunto de
+//! permisos y, opcionalmente, los roles de los que heredan. Los permisos son
+//! jerárquicos por puntos: `nano_core.network.*` cubre cualquier permiso bajo
+//! `nano_core.network`, `nano_core.*.status` un único nivel y `*` todo.
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Definición de un rol en la configuración
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RoleDefinition {
+    pub permissions: Vec<String>,
+    /// Roles cuyos permisos se incluyen
+    pub inherits: Vec<String>,
+}
+
+/// Permiso concedido, posiblemente con comodines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionPattern {
+    segments: Vec<String>,
+}
+
+impl PermissionPattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let segments: Vec<String> = pattern.split('.').map(str::to_string).collect();
+        if segments.iter().any(String::is_empty) {
+            return Err(anyhow!("Permiso mal formado: {:?}", pattern));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Un `*` intermedio cubre un nivel; uno final, todo el subárbol
+    pub fn matches(&self, permission: &str) -> bool {
+        let requested: Vec<&str> = permission.split('.').collect();
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let last = index + 1 == self.segments.len();
+            match requested.get(index) {
+                _ if segment == "*" && last => return requested.len() > index,
+                Some(_) if segment == "*" => continue,
+                Some(requested) if segment == requested => continue,
+                _ => return false,
+            }
+        }
+
+        requested.len() == self.segments.len()
+    }
+}
+
+/// Roles resueltos con sus permisos heredados
+#[derive(Debug, Clone, Default)]
+pub struct Rbac {
+    roles: HashMap<String, Vec<PermissionPattern>>,
+}
+
+impl Rbac {
+    /// Resolver la herencia de los roles definidos
+    pub fn from_roles(definitions: &BTreeMap<String, RoleDefinition>) -> Result<Self> {
+        let mut roles = HashMap::new();
+
+        for name in definitions.keys() {
+            let mut patterns = Vec::new();
+            collect(definitions, name, &mut Vec::new(), &mut patterns)?;
+            roles.insert(name.clone(), patterns);
+        }
+
+        Ok(Self { roles })
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.contains_key(role)
+    }
+
+    /// Si los roles o los permisos directos conceden `permission`
+    pub fn allows(&self, roles: &[String], direct: &[String], permission: &str) -> bool {
+        let by_role = roles
+            .iter()
+            .filter_map(|role| self.roles.get(role))
+            .flatten()
+            .any(|pattern| pattern.matches(permission));
+
+        by_role
+            || direct
+                .iter()
+                .filter_map(|granted| PermissionPattern::parse(granted).ok())
+                .any(|pattern| pattern.matches(permission))
+    }
+}
+
+fn collect(
+    definitions: &BTreeMap<String, RoleDefinition>,
+    name: &str,
+    path: &mut Vec<String>,
+    patterns: &mut Vec<PermissionPattern>,
+) -> Result<()> {
+    if path.iter().any(|ancestor| ancestor == name) {
+        return Err(anyhow!("Herencia circular en el rol {}", name));
+    }
+
+    let definition = definitions
+        .get(name)
+        .ok_or_else(|| anyhow!("Rol no definido: {}", name))?;
+
+    for permission in &definition.permissions {
+        patterns.push(PermissionPattern::parse(permission)?);
+    }
+
+    path.push(name.to_string());
+    for parent in &definition.inherits {
+        collect(definitions, parent, path, patterns)?;
+    }
+    path.pop();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(permissions: &[&str], inherits: &[&str]) -> RoleDefinition {
+        RoleDefinition {
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            inherits: inherits.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_roles_wildcards_and_inheritance() {
+        let definitions = BTreeMap::from([
+            ("observer".to_string(), role(&["nano_core.*.status", "metrics.read"], &[])),
+            ("network_admin".to_string(), role(&["nano_core.network.*"], &["observer"])),
+            ("admin".to_string(), role(&["*"], &[])),
+        ]);
+        let rbac = Rbac::from_roles(&definitions).unwrap();
+        let network_admin = vec!["network_admin".to_string()];
+
+        assert!(rbac.allows(&network_admin, &[], "nano_core.network.restart"));
+        assert!(rbac.allows(&network_admin, &[], "nano_core.network.config.write"));
+        assert!(!rbac.allows(&network_admin, &[], "nano_core.network"));
+        // Heredado de observer: `*` intermedio cubre un único nivel
+        assert!(rbac.allows(&network_admin, &[], "nano_core.os.status"));
+        assert!(!rbac.allows(&network_admin, &[], "nano_core.os.restart"));
+        assert!(!rbac.allows(&network_admin, &[], "nano_core.os.status.detail"));
+
+        assert!(rbac.allows(&["admin".to_string()], &[], "anything.at.all"));
+        assert!(rbac.allows(&[], &["metrics.*".to_string()], "metrics.write"));
+        assert!(!rbac.allows(&["unknown".to_string()], &[], "metrics.read"));
+
+        let cyclic = BTreeMap::from([
+            ("a".to_string(), role(&[], &["b"])),
+            ("b".to_string(), role(&[], &["a"])),
+        ]);
+        assert!(Rbac::from_roles(&cyclic).is_err());
+        assert!(Rbac::from_roles(&BTreeMap::from([("x".to_string(), role(&["a..b"], &[]))])).is_err());
+    }
+}
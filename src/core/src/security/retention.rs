@@ -0,0 +1,185 @@
+//! Retención de eventos de seguridad
+//!
+//! `EventStore` mantiene en memoria solo los eventos recientes (por número y
+//! antigüedad). Los que se podan se acumulan en resúmenes por hora, de modo
+//! que las estadísticas siguen siendo completas sin crecer sin límite.
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::SecurityEvent;
+
+/// Límites de los eventos en memoria
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionPolicy {
+    pub max_events: usize,
+    pub max_age: Duration,
+    /// Antigüedad máxima de los resúmenes por hora
+    pub rollup_max_age: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_events: 10_000,
+            max_age: Duration::hours(24),
+            rollup_max_age: Duration::days(30),
+        }
+    }
+}
+
+/// Conteos de los eventos podados de una hora
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventRollup {
+    pub by_type: BTreeMap<String, u64>,
+    pub by_severity: BTreeMap<String, u64>,
+}
+
+impl EventRollup {
+    fn add(&mut self, event: &SecurityEvent) {
+        *self.by_type.entry(format!("{:?}", event.event_type)).or_insert(0) += 1;
+        *self.by_severity.entry(format!("{:?}", event.severity)).or_insert(0) += 1;
+    }
+}
+
+/// Eventos recientes en orden de llegada más resúmenes de los podados
+#[derive(Debug, Default)]
+pub struct EventStore {
+    policy: RetentionPolicy,
+    events: VecDeque<SecurityEvent>,
+    rollups: BTreeMap<DateTime<Utc>, EventRollup>,
+}
+
+impl EventStore {
+    pub fn new(policy: RetentionPolicy) -> Self {
+        Self {
+            policy,
+            events: VecDeque::new(),
+            rollups: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: RetentionPolicy) {
+        self.policy = policy;
+    }
+
+    /// Añadir un evento; el límite de número se aplica de inmediato
+    pub fn push(&mut self, event: SecurityEvent) {
+        self.events.push_back(event);
+
+        while self.events.len() > self.policy.max_events.max(1) {
+            if let Some(oldest) = self.events.pop_front() {
+                self.roll_up(&oldest);
+            }
+        }
+    }
+
+    /// Podar por antigüedad; devuelve los eventos retirados
+    pub fn prune(&mut self, now: DateTime<Utc>) -> usize {
+        let cutoff = now - self.policy.max_age;
+        let mut pruned = 0;
+
+        while self.events.front().is_some_and(|e| e.timestamp < cutoff) {
+            if let Some(oldest) = self.events.pop_front() {
+                self.roll_up(&oldest);
+                pruned += 1;
+            }
+        }
+
+        let rollup_cutoff = now - self.policy.rollup_max_age;
+        self.rollups.retain(|hour, _| *hour >= rollup_cutoff);
+
+        pruned
+    }
+
+    fn roll_up(&mut self, event: &SecurityEvent) {
+        let hour = event.timestamp.duration_trunc(Duration::hours(1)).unwrap_or(event.timestamp);
+        self.rollups.entry(hour).or_default().add(event);
+    }
+
+    /// Eventos posteriores a `cutoff`, recorriendo solo la cola reciente
+    pub fn since(&self, cutoff: DateTime<Utc>) -> impl Iterator<Item = &SecurityEvent> {
+        let start = self.events.partition_point(|e| e.timestamp < cutoff);
+        self.events.range(start..)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SecurityEvent> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Resúmenes por hora de los eventos podados
+    pub fn rollups(&self) -> &BTreeMap<DateTime<Utc>, EventRollup> {
+        &self.rollups
+    }
+
+    /// Conteo por tipo de los eventos en memoria y de los resumidos
+    pub fn counts_by_type(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+
+        for event in &self.events {
+            *counts.entry(format!("{:?}", event.event_type)).or_insert(0) += 1;
+        }
+        for rollup in self.rollups.values() {
+            for (event_type, count) in &rollup.by_type {
+                *counts.entry(event_type.clone()).or_insert(0) += count;
+            }
+        }
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{SecurityEventType, SecuritySeverity};
+    use uuid::Uuid;
+
+    fn event(minutes_ago: i64) -> SecurityEvent {
+        SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::AuthorizationDenied,
+            severity: SecuritySeverity::Medium,
+            source: "test".to_string(),
+            target: None,
+            description: "denegado".to_string(),
+            context: HashMap::new(),
+            timestamp: Utc::now() - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn test_retention_by_count_and_age() {
+        let mut store = EventStore::new(RetentionPolicy {
+            max_events: 3,
+            max_age: Duration::minutes(30),
+            rollup_max_age: Duration::days(1),
+        });
+
+        for minutes_ago in [120, 90, 45, 20, 10] {
+            store.push(event(minutes_ago));
+        }
+        assert_eq!(store.len(), 3);
+
+        assert_eq!(store.prune(Utc::now()), 1);
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.since(Utc::now() - Duration::minutes(15)).count(), 1);
+
+        // Los eventos podados siguen contando en las estadísticas
+        assert_eq!(store.counts_by_type()["AuthorizationDenied"], 5);
+        assert_eq!(store.rollups().values().map(|r| r.by_severity["Medium"]).sum::<u64>(), 3);
+
+        // Los resúmenes también caducan
+        store.prune(Utc::now() + Duration::days(2));
+        assert!(store.is_empty() && store.rollups().is_empty());
+    }
+}
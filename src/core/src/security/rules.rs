@@ -0,0 +1,118 @@
+//! Reglas de detección de amenazas
+//!
+//! Un archivo TOML/YAML/JSON (`threat_rules_file`) añade o sustituye
+//! patrones por `id` y ajusta los integrados con `[overrides.<id>]`
+//! (`enabled`, `severity`). Se recarga al cambiar el archivo o al recibir
+//! reglas por el tema `security.threat_rules` del fabric.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+use super::{SecuritySeverity, ThreatPattern};
+use crate::config::ConfigFormat;
+
+/// Tema del fabric por el que se publican reglas nuevas (JSON)
+pub const THREAT_RULES_SUBJECT: &str = "security.threat_rules";
+
+/// Ajustes de un patrón existente
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatternOverride {
+    pub enabled: Option<bool>,
+    pub severity: Option<SecuritySeverity>,
+}
+
+/// Contenido del archivo de reglas
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreatRules {
+    pub patterns: Vec<ThreatPattern>,
+    pub overrides: BTreeMap<String, PatternOverride>,
+}
+
+impl ThreatRules {
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self> {
+        Ok(serde_json::from_value(format.parse(content)?)?)
+    }
+
+    /// Patrones resultantes de aplicar las reglas sobre `base`
+    pub fn apply(&self, mut base: Vec<ThreatPattern>) -> Vec<ThreatPattern> {
+        for pattern in &self.patterns {
+            match base.iter_mut().find(|existing| existing.id == pattern.id) {
+                Some(existing) => *existing = pattern.clone(),
+                None => base.push(pattern.clone()),
+            }
+        }
+
+        for (id, override_) in &self.overrides {
+            let Some(pattern) = base.iter_mut().find(|pattern| pattern.id == *id) else {
+                warn!("⚠️  Override para un patrón de amenaza inexistente: {}", id);
+                continue;
+            };
+            if let Some(enabled) = override_.enabled {
+                pattern.enabled = enabled;
+            }
+            if let Some(severity) = &override_.severity {
+                pattern.severity = severity.clone();
+            }
+        }
+
+        base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{ThreatDetector, ThreatPatternType};
+
+    #[test]
+    fn test_rules_add_replace_and_override() {
+        let rules = ThreatRules::parse(
+            r#"
+[[patterns]]
+id = "sql_injection"
+name = "Inyección SQL"
+severity = "High"
+type = "suspicious_pattern"
+keywords = ["union select", "drop table"]
+
+[[patterns]]
+id = "freq_auth_fail"
+name = "Fallos de autenticación frecuentes"
+severity = "Critical"
+type = "frequency_anomaly"
+max_events = 3
+window_seconds = 60
+
+[overrides.resource_abuse]
+enabled = false
+
+[overrides.access_anomaly]
+severity = "Low"
+"#,
+            ConfigFormat::Toml,
+        )
+        .unwrap();
+
+        let patterns = rules.apply(ThreatDetector::builtin_patterns());
+        let find = |id: &str| patterns.iter().find(|p| p.id == id).unwrap();
+
+        assert!(matches!(
+            &find("sql_injection").pattern_type,
+            ThreatPatternType::SuspiciousPattern { keywords } if keywords.len() == 2
+        ));
+        assert!(find("sql_injection").enabled);
+        assert!(matches!(find("freq_auth_fail").pattern_type, ThreatPatternType::FrequencyAnomaly { max_events: 3, .. }));
+        assert_eq!(find("freq_auth_fail").severity, SecuritySeverity::Critical);
+        assert!(!find("resource_abuse").enabled);
+        assert_eq!(find("access_anomaly").severity, SecuritySeverity::Low);
+        assert_eq!(patterns.len(), ThreatDetector::builtin_patterns().len() + 1);
+
+        let yaml = "overrides:\n  freq_auth_fail:\n    enabled: false\n";
+        let rules = ThreatRules::parse(yaml, ConfigFormat::Yaml).unwrap();
+        assert!(!rules.apply(ThreatDetector::builtin_patterns())[0].enabled);
+    }
+}
@@ -0,0 +1,519 @@
+//! Exportación de eventos a SIEM
+//!
+//! Cada destino de `[[security.siem_exporters]]` (syslog RFC 5424 con mensaje
+//! libre o CEF, webhook HTTPS y, con la feature `kafka`, un topic de Kafka)
+//! recibe los eventos de su severidad mínima por una cola acotada que se
+//! envía por lotes con reintentos y backoff exponencial.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::SecondsFormat;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use super::{SecurityEvent, SecuritySeverity};
+use crate::util::Backoff;
+
+/// Facility syslog `security/authorization` (10)
+const SYSLOG_FACILITY: u8 = 10;
+
+/// Tiempo máximo para vaciar las colas al detener los exportadores
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Transporte syslog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogProtocol {
+    #[default]
+    Udp,
+    /// Con framing por longitud (RFC 6587)
+    Tcp,
+}
+
+/// Contenido del mensaje syslog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogFormat {
+    /// Descripción con datos estructurados RFC 5424
+    #[default]
+    Rfc5424,
+    /// Línea ArcSight CEF como mensaje
+    Cef,
+}
+
+/// Destino de los eventos
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SiemTarget {
+    Syslog {
+        /// `host:puerto`
+        address: String,
+        #[serde(default)]
+        protocol: SyslogProtocol,
+        #[serde(default)]
+        format: SyslogFormat,
+    },
+    Webhook {
+        url: String,
+        /// Token Bearer (admite referencias de secretos)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    Kafka {
+        brokers: String,
+        topic: String,
+    },
+}
+
+/// Exportador configurado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SiemExporterConfig {
+    #[serde(flatten)]
+    pub target: SiemTarget,
+    #[serde(default = "default_min_severity")]
+    pub min_severity: SecuritySeverity,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Eventos en espera; los que no caben se descartan
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_min_severity() -> SecuritySeverity {
+    SecuritySeverity::Low
+}
+
+fn default_batch_size() -> usize {
+    100
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_queue_capacity() -> usize {
+    10_000
+}
+
+/// Envío de un lote a un destino
+#[async_trait]
+trait SiemSink: Send + Sync {
+    fn name(&self) -> String;
+    async fn send(&self, batch: &[SecurityEvent]) -> Result<()>;
+}
+
+/// Exportador en ejecución
+struct SiemExporter {
+    name: String,
+    min_severity: SecuritySeverity,
+    queue: mpsc::Sender<SecurityEvent>,
+    handle: JoinHandle<()>,
+}
+
+/// Conjunto de exportadores de `SecurityManager`
+#[derive(Default)]
+pub struct SiemExporters {
+    exporters: Vec<SiemExporter>,
+}
+
+impl SiemExporters {
+    /// Arrancar un exportador por destino configurado
+    pub fn start(configs: &[SiemExporterConfig]) -> Result<Self> {
+        let mut exporters = Vec::new();
+
+        for config in configs {
+            let sink = build_sink(&config.target)?;
+            let name = sink.name();
+            let (queue, rx) = mpsc::channel(config.queue_capacity.max(1));
+            let handle = tokio::spawn(run_exporter(sink, config.clone(), rx));
+
+            info!("📤 Exportador SIEM iniciado: {}", name);
+            exporters.push(SiemExporter {
+                name,
+                min_severity: config.min_severity.clone(),
+                queue,
+                handle,
+            });
+        }
+
+        Ok(Self { exporters })
+    }
+
+    /// Encolar el evento en los exportadores cuya severidad mínima alcanza
+    pub fn export(&self, event: &SecurityEvent) {
+        for exporter in self.exporters.iter().filter(|e| event.severity >= e.min_severity) {
+            if exporter.queue.try_send(event.clone()).is_err() {
+                warn!("⚠️  Cola del exportador SIEM {} llena: evento descartado", exporter.name);
+            }
+        }
+    }
+
+    /// Vaciar lo pendiente y detener los exportadores
+    pub async fn shutdown(self) {
+        for exporter in self.exporters {
+            drop(exporter.queue);
+            let mut handle = exporter.handle;
+            if tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, &mut handle).await.is_err() {
+                warn!("⚠️  Exportador SIEM {} detenido con eventos pendientes", exporter.name);
+                handle.abort();
+            }
+        }
+    }
+}
+
+fn build_sink(target: &SiemTarget) -> Result<Box<dyn SiemSink>> {
+    Ok(match target {
+        SiemTarget::Syslog { address, protocol, format } => Box::new(SyslogSink {
+            address: address.clone(),
+            protocol: *protocol,
+            format: *format,
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+            tcp: Mutex::new(None),
+        }),
+        SiemTarget::Webhook { url, token, headers } => Box::new(WebhookSink {
+            url: url.clone(),
+            token: token.clone(),
+            headers: headers.clone(),
+            client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?,
+        }),
+        #[cfg(feature = "kafka")]
+        SiemTarget::Kafka { brokers, topic } => Box::new(kafka::KafkaSink::new(brokers, topic)?),
+        #[cfg(not(feature = "kafka"))]
+        SiemTarget::Kafka { .. } => return Err(anyhow!("Exportación a Kafka no disponible: compilar con la feature `kafka`")),
+    })
+}
+
+/// Agrupar la cola en lotes de hasta `batch_size` o `flush_interval_ms` y enviarlos
+async fn run_exporter(sink: Box<dyn SiemSink>, config: SiemExporterConfig, mut rx: mpsc::Receiver<SecurityEvent>) {
+    let batch_size = config.batch_size.max(1);
+    let flush_interval = Duration::from_millis(config.flush_interval_ms);
+    let mut batch = Vec::with_capacity(batch_size);
+
+    while let Some(first) = rx.recv().await {
+        batch.push(first);
+
+        let deadline = tokio::time::Instant::now() + flush_interval;
+        let mut closed = false;
+        while batch.len() < batch_size {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(event)) => batch.push(event),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        send_with_retry(sink.as_ref(), &batch, &config).await;
+        batch.clear();
+
+        if closed {
+            return;
+        }
+    }
+}
+
+async fn send_with_retry(sink: &dyn SiemSink, batch: &[SecurityEvent], config: &SiemExporterConfig) {
+    let mut backoff = Backoff::new(Duration::from_millis(config.retry_backoff_ms)).with_jitter(0.5);
+
+    for attempt in 0..=config.max_retries {
+        match sink.send(batch).await {
+            Ok(()) => {
+                debug!("📤 {} eventos exportados a {}", batch.len(), sink.name());
+                return;
+            }
+            Err(e) if attempt < config.max_retries => {
+                warn!("⚠️  Error exportando a {} (intento {}): {}", sink.name(), attempt + 1, e);
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+            Err(e) => error!("❌ {} eventos descartados tras {} intentos en {}: {}", batch.len(), attempt + 1, sink.name(), e),
+        }
+    }
+}
+
+struct SyslogSink {
+    address: String,
+    protocol: SyslogProtocol,
+    format: SyslogFormat,
+    hostname: String,
+    tcp: Mutex<Option<TcpStream>>,
+}
+
+#[async_trait]
+impl SiemSink for SyslogSink {
+    fn name(&self) -> String {
+        format!("syslog://{}", self.address)
+    }
+
+    async fn send(&self, batch: &[SecurityEvent]) -> Result<()> {
+        let messages: Vec<String> = batch.iter().map(|event| self.message(event)).collect();
+
+        match self.protocol {
+            SyslogProtocol::Udp => {
+                let socket = UdpSocket::bind("0.0.0.0:0").await?;
+                socket.connect(&self.address).await?;
+                for message in &messages {
+                    socket.send(message.as_bytes()).await?;
+                }
+            }
+            SyslogProtocol::Tcp => {
+                let mut tcp = self.tcp.lock().await;
+                if tcp.is_none() {
+                    *tcp = Some(TcpStream::connect(&self.address).await?);
+                }
+                let stream = tcp.as_mut().ok_or_else(|| anyhow!("Sin conexión syslog"))?;
+
+                let mut framed = Vec::new();
+                for message in &messages {
+                    framed.extend_from_slice(format!("{} {}", message.len(), message).as_bytes());
+                }
+                // Una conexión rota se reabre en el siguiente intento
+                if let Err(e) = stream.write_all(&framed).await {
+                    *tcp = None;
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SyslogSink {
+    fn message(&self, event: &SecurityEvent) -> String {
+        let body = match self.format {
+            SyslogFormat::Rfc5424 => event.description.clone(),
+            SyslogFormat::Cef => to_cef(event),
+        };
+        to_rfc5424(event, &self.hostname, &body)
+    }
+}
+
+/// Línea syslog RFC 5424 con el evento como datos estructurados
+pub fn to_rfc5424(event: &SecurityEvent, hostname: &str, message: &str) -> String {
+    let severity = match event.severity {
+        SecuritySeverity::Critical => 2,
+        SecuritySeverity::High => 3,
+        SecuritySeverity::Medium => 4,
+        SecuritySeverity::Low => 5,
+        SecuritySeverity::Info => 6,
+    };
+    let sd_escape = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]");
+
+    let mut data = format!("[saai@32473 id=\"{}\" source=\"{}\"", event.id, sd_escape(&event.source));
+    if let Some(target) = &event.target {
+        data.push_str(&format!(" target=\"{}\"", sd_escape(target)));
+    }
+    data.push(']');
+
+    format!(
+        "<{}>1 {} {} saai-core {} {:?} {} {}",
+        SYSLOG_FACILITY * 8 + severity,
+        event.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        hostname,
+        std::process::id(),
+        event.event_type,
+        data,
+        message
+    )
+}
+
+/// Evento en formato ArcSight CEF
+pub fn to_cef(event: &SecurityEvent) -> String {
+    let header = |value: &str| value.replace('\\', "\\\\").replace('|', "\\|");
+    let extension = |value: &str| {
+        value
+            .replace('\\', "\\\\")
+            .replace('=', "\\=")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    };
+    let severity = match event.severity {
+        SecuritySeverity::Info => 1,
+        SecuritySeverity::Low => 3,
+        SecuritySeverity::Medium => 5,
+        SecuritySeverity::High => 8,
+        SecuritySeverity::Critical => 10,
+    };
+
+    let mut fields = vec![
+        format!("rt={}", event.timestamp.timestamp_millis()),
+        format!("externalId={}", event.id),
+        format!("sourceServiceName={}", extension(&event.source)),
+    ];
+    if let Some(target) = &event.target {
+        fields.push(format!("duid={}", extension(target)));
+    }
+    if let Some(user) = event.context.get(super::anomaly::CONTEXT_USER) {
+        fields.push(format!("suser={}", extension(user)));
+    }
+    if let Some(ip) = event.context.get(super::anomaly::CONTEXT_IP) {
+        fields.push(format!("src={}", extension(ip)));
+    }
+
+    format!(
+        "CEF:0|SAAI|saai-core|{}|{:?}|{}|{}|{}",
+        header(env!("CARGO_PKG_VERSION")),
+        event.event_type,
+        header(&event.description),
+        severity,
+        fields.join(" ")
+    )
+}
+
+struct WebhookSink {
+    url: String,
+    token: Option<String>,
+    headers: BTreeMap<String, String>,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl SiemSink for WebhookSink {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn send(&self, batch: &[SecurityEvent]) -> Result<()> {
+        let mut request = self.client.post(&self.url).json(batch);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Webhook respondió {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::*;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use rdkafka::ClientConfig;
+
+    pub(super) struct KafkaSink {
+        topic: String,
+        producer: FutureProducer,
+    }
+
+    impl KafkaSink {
+        pub(super) fn new(brokers: &str, topic: &str) -> Result<Self> {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .set("message.timeout.ms", "10000")
+                .create()?;
+            Ok(Self {
+                topic: topic.to_string(),
+                producer,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SiemSink for KafkaSink {
+        fn name(&self) -> String {
+            format!("kafka://{}", self.topic)
+        }
+
+        async fn send(&self, batch: &[SecurityEvent]) -> Result<()> {
+            for event in batch {
+                let payload = serde_json::to_vec(event)?;
+                let key = event.id.to_string();
+                self.producer
+                    .send(FutureRecord::to(&self.topic).key(&key).payload(&payload), Duration::from_secs(10))
+                    .await
+                    .map_err(|(e, _)| anyhow!("Kafka: {}", e))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SecurityEventType;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn event(severity: SecuritySeverity, description: &str) -> SecurityEvent {
+        SecurityEvent {
+            id: Uuid::new_v4(),
+            event_type: SecurityEventType::AuthenticationFailure,
+            severity,
+            source: "token".to_string(),
+            target: Some("api|admin".to_string()),
+            description: description.to_string(),
+            context: HashMap::from([("user".to_string(), "ana".to_string())]),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_syslog_cef_export_filters_by_severity() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let exporters = SiemExporters::start(&[SiemExporterConfig {
+            target: SiemTarget::Syslog {
+                address: server.local_addr().unwrap().to_string(),
+                protocol: SyslogProtocol::Udp,
+                format: SyslogFormat::Cef,
+            },
+            min_severity: SecuritySeverity::Medium,
+            batch_size: 10,
+            flush_interval_ms: 20,
+            max_retries: 0,
+            retry_backoff_ms: 1,
+            queue_capacity: 10,
+        }])
+        .unwrap();
+
+        exporters.export(&event(SecuritySeverity::Low, "ignorado"));
+        exporters.export(&event(SecuritySeverity::High, "token a=b | firma inválida"));
+        exporters.shutdown().await;
+
+        let mut buffer = [0u8; 2048];
+        let len = tokio::time::timeout(Duration::from_secs(2), server.recv(&mut buffer)).await.unwrap().unwrap();
+        let message = std::str::from_utf8(&buffer[..len]).unwrap();
+
+        // PRI = facility 10 × 8 + err (3)
+        assert!(message.starts_with("<83>1 "), "{}", message);
+        assert!(message.contains("AuthenticationFailure [saai@32473 id="));
+        assert!(message.contains("target=\"api|admin\""));
+        assert!(message.contains(r"CEF:0|SAAI|saai-core|"));
+        assert!(message.contains(r"|token a=b \| firma inválida|8|"));
+        assert!(message.contains("duid=api|admin suser=ana"));
+
+        // Solo llegó el evento de severidad suficiente
+        assert!(tokio::time::timeout(Duration::from_millis(100), server.recv(&mut buffer)).await.is_err());
+    }
+}
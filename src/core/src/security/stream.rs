@@ -0,0 +1,181 @@
+//! Encriptación por flujo para cargas grandes
+//!
+//! Divide los datos en fragmentos de `STREAM_CHUNK_SIZE` sellados por
+//! separado. La cabecera `key_id || prefijo` va en los datos asociados y el
+//! nonce de cada fragmento es `prefijo || contador || último`, de modo que no
+//! se pueden reordenar, duplicar ni truncar fragmentos sin que falle el
+//! descifrado.
+
+use anyhow::{anyhow, Result};
+use ring::aead;
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::keys::KEY_ID_LEN;
+
+/// Bytes de texto plano por fragmento
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes aleatorios del nonce compartidos por todos los fragmentos
+const NONCE_PREFIX_LEN: usize = aead::NONCE_LEN - 5;
+
+const HEADER_LEN: usize = KEY_ID_LEN + NONCE_PREFIX_LEN;
+
+/// Encriptar `reader` en `writer` con la clave `key_id`; devuelve los bytes leídos
+pub async fn seal_stream<R, W>(
+    key_id: u32,
+    key: &aead::LessSafeKey,
+    mut reader: R,
+    mut writer: W,
+    associated_data: &[u8],
+) -> Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut header = [0u8; HEADER_LEN];
+    header[..KEY_ID_LEN].copy_from_slice(&key_id.to_be_bytes());
+    SystemRandom::new()
+        .fill(&mut header[KEY_ID_LEN..])
+        .map_err(|_| anyhow!("No se pudo generar el nonce"))?;
+    writer.write_all(&header).await?;
+
+    let aad = [&header[..], associated_data].concat();
+    let mut buffer = vec![0u8; STREAM_CHUNK_SIZE + key.algorithm().tag_len()];
+    let mut total = 0u64;
+    let mut counter = 0u32;
+
+    loop {
+        let read = read_full(&mut reader, &mut buffer[..STREAM_CHUNK_SIZE]).await?;
+        // Un fragmento incompleto es el último; si los datos son múltiplo del
+        // tamaño, el último va vacío
+        let last = read < STREAM_CHUNK_SIZE;
+        total += read as u64;
+
+        let mut chunk = buffer[..read].to_vec();
+        key.seal_in_place_append_tag(chunk_nonce(&header, counter, last), aead::Aad::from(&aad), &mut chunk)
+            .map_err(|_| anyhow!("Error encriptando el fragmento {}", counter))?;
+        writer.write_all(&chunk).await?;
+
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Flujo demasiado largo para encriptar"))?;
+    }
+
+    writer.flush().await?;
+    Ok(total)
+}
+
+/// Desencriptar un flujo de `seal_stream`; `key` resuelve la clave de la cabecera
+///
+/// Los fragmentos se escriben según se verifican: si devuelve error, lo ya
+/// escrito en `writer` debe descartarse.
+pub async fn open_stream<R, W, K>(mut reader: R, mut writer: W, associated_data: &[u8], key: K) -> Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    K: FnOnce(u32) -> Result<aead::LessSafeKey>,
+{
+    let mut header = [0u8; HEADER_LEN];
+    if read_full(&mut reader, &mut header).await? < HEADER_LEN {
+        return Err(anyhow!("Flujo encriptado demasiado corto"));
+    }
+    let key_id = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let key = key(key_id)?;
+
+    let aad = [&header[..], associated_data].concat();
+    let tag_len = key.algorithm().tag_len();
+    let mut buffer = vec![0u8; STREAM_CHUNK_SIZE + tag_len];
+    let mut total = 0u64;
+    let mut counter = 0u32;
+
+    loop {
+        let read = read_full(&mut reader, &mut buffer).await?;
+        if read < tag_len {
+            return Err(anyhow!("Flujo encriptado truncado en el fragmento {}", counter));
+        }
+        let last = read < buffer.len();
+
+        let plaintext = key
+            .open_in_place(chunk_nonce(&header, counter, last), aead::Aad::from(&aad), &mut buffer[..read])
+            .map_err(|_| anyhow!("Error desencriptando el fragmento {} con la clave {}", counter, key_id))?;
+        writer.write_all(plaintext).await?;
+        total += plaintext.len() as u64;
+
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Flujo encriptado demasiado largo"))?;
+    }
+
+    writer.flush().await?;
+    Ok(total)
+}
+
+fn chunk_nonce(header: &[u8; HEADER_LEN], counter: u32, last: bool) -> aead::Nonce {
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(&header[KEY_ID_LEN..]);
+    nonce[NONCE_PREFIX_LEN..aead::NONCE_LEN - 1].copy_from_slice(&counter.to_be_bytes());
+    nonce[aead::NONCE_LEN - 1] = last as u8;
+    aead::Nonce::assume_unique_for_key(nonce)
+}
+
+/// Leer hasta llenar `buffer` o llegar al final; devuelve los bytes leídos
+async fn read_full<R: AsyncRead + Unpin>(reader: &mut R, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]).await? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::KeyRing;
+
+    #[tokio::test]
+    async fn test_stream_roundtrip_and_tampering() {
+        let ring = KeyRing::new(&aead::AES_256_GCM, 1).unwrap();
+        let (id, key) = ring.sealing_key().unwrap();
+
+        for len in [0, 10, STREAM_CHUNK_SIZE, 2 * STREAM_CHUNK_SIZE + 17] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let mut sealed = Vec::new();
+            assert_eq!(seal_stream(id, &key, &data[..], &mut sealed, b"snapshot").await.unwrap(), len as u64);
+
+            let mut opened = Vec::new();
+            open_stream(&sealed[..], &mut opened, b"snapshot", |id| ring.opening_key(id)).await.unwrap();
+            assert_eq!(opened, data);
+            assert!(open_stream(&sealed[..], &mut Vec::new(), b"otro", |id| ring.opening_key(id)).await.is_err());
+        }
+
+        let data = vec![7u8; 2 * STREAM_CHUNK_SIZE];
+        let mut sealed = Vec::new();
+        seal_stream(id, &key, &data[..], &mut sealed, b"").await.unwrap();
+        let chunk = STREAM_CHUNK_SIZE + aead::AES_256_GCM.tag_len();
+
+        // Truncar en un límite de fragmento, alterar un byte o intercambiar fragmentos
+        let truncated = &sealed[..HEADER_LEN + chunk];
+        assert!(open_stream(truncated, &mut Vec::new(), b"", |id| ring.opening_key(id)).await.is_err());
+
+        let mut flipped = sealed.clone();
+        flipped[HEADER_LEN + 5] ^= 1;
+        assert!(open_stream(&flipped[..], &mut Vec::new(), b"", |id| ring.opening_key(id)).await.is_err());
+
+        let mut swapped = sealed.clone();
+        let (first, second) = (HEADER_LEN..HEADER_LEN + chunk, HEADER_LEN + chunk..HEADER_LEN + 2 * chunk);
+        let first_chunk = sealed[first.clone()].to_vec();
+        swapped.copy_within(second, first.start);
+        swapped[HEADER_LEN + chunk..HEADER_LEN + 2 * chunk].copy_from_slice(&first_chunk);
+        assert!(open_stream(&swapped[..], &mut Vec::new(), b"", |id| ring.opening_key(id)).await.is_err());
+    }
+}
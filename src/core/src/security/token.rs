@@ -0,0 +1,241 @@
+//! Autenticación por token
+//!
+//! Valida JWT (firma, expiración, emisor y audiencia) contra los emisores de
+//! `[[security.token_issuers]]` y traduce sus claims a nivel de seguridad,
+//! permisos y roles de un `SecurityContext`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+use super::SecurityLevel;
+
+/// Emisor de tokens aceptado
+///
+/// `key` es el secreto HMAC (`HS*`) o la clave pública PEM (`RS*`, `PS*`,
+/// `ES*`, `EdDSA`); admite referencias como `file:/etc/saai/issuer.pem`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TokenIssuerConfig {
+    /// Valor esperado del claim `iss`
+    pub issuer: String,
+    pub algorithm: String,
+    pub key: String,
+    /// Valor exigido del claim `aud`, si lo hay
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Nivel máximo que puede conceder este emisor
+    pub max_level: String,
+    pub level_claim: String,
+    pub permissions_claim: String,
+    pub roles_claim: String,
+}
+
+impl Default for TokenIssuerConfig {
+    fn default() -> Self {
+        Self {
+            issuer: String::new(),
+            algorithm: "HS256".to_string(),
+            key: String::new(),
+            audience: None,
+            max_level: "internal".to_string(),
+            level_claim: "security_level".to_string(),
+            permissions_claim: "permissions".to_string(),
+            roles_claim: "roles".to_string(),
+        }
+    }
+}
+
+/// Identidad extraída de un token válido
+#[derive(Debug, Clone)]
+pub struct TokenIdentity {
+    pub subject: Option<String>,
+    pub issuer: String,
+    pub security_level: SecurityLevel,
+    pub permissions: Vec<String>,
+    pub roles: Vec<String>,
+    /// Claim `exp`; la sesión no sobrevive al token
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+struct Issuer {
+    config: TokenIssuerConfig,
+    algorithm: Algorithm,
+    key: DecodingKey,
+    max_level: SecurityLevel,
+}
+
+/// Validador de los emisores configurados
+#[derive(Default)]
+pub struct TokenValidator {
+    issuers: Vec<Issuer>,
+    leeway_secs: u64,
+}
+
+impl TokenValidator {
+    pub fn new(configs: &[TokenIssuerConfig], leeway_secs: u64) -> Result<Self> {
+        let issuers = configs
+            .iter()
+            .map(|config| {
+                let algorithm = Algorithm::from_str(&config.algorithm)
+                    .map_err(|_| anyhow!("Algoritmo de token no soportado: {}", config.algorithm))?;
+                let pem = config.key.as_bytes();
+                let key = match algorithm {
+                    Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => DecodingKey::from_secret(pem),
+                    Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(pem)?,
+                    Algorithm::EdDSA => DecodingKey::from_ed_pem(pem)?,
+                    _ => DecodingKey::from_rsa_pem(pem)?,
+                };
+                let max_level = parse_level(&Value::String(config.max_level.clone()))
+                    .ok_or_else(|| anyhow!("Nivel máximo inválido para {}: {}", config.issuer, config.max_level))?;
+
+                Ok(Issuer {
+                    config: config.clone(),
+                    algorithm,
+                    key,
+                    max_level,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { issuers, leeway_secs })
+    }
+
+    /// Validar `token` con el emisor de su claim `iss`
+    pub fn validate(&self, token: &str) -> Result<TokenIdentity> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let mut last_error = anyhow!("Ningún emisor configurado acepta el token");
+
+        for issuer in self.issuers.iter().filter(|issuer| issuer.algorithm == header.alg) {
+            let mut validation = Validation::new(issuer.algorithm);
+            validation.leeway = self.leeway_secs;
+            validation.set_issuer(&[&issuer.config.issuer]);
+            validation.set_required_spec_claims(&["exp", "iss"]);
+            match &issuer.config.audience {
+                Some(audience) => validation.set_audience(&[audience]),
+                None => validation.validate_aud = false,
+            }
+
+            match jsonwebtoken::decode::<Map<String, Value>>(token, &issuer.key, &validation) {
+                Ok(data) => return Ok(issuer.identity(data.claims)),
+                Err(e) => last_error = anyhow!("Token rechazado por {}: {}", issuer.config.issuer, e),
+            }
+        }
+
+        Err(last_error)
+    }
+}
+
+impl Issuer {
+    fn identity(&self, claims: Map<String, Value>) -> TokenIdentity {
+        let requested = claims
+            .get(&self.config.level_claim)
+            .and_then(parse_level)
+            .unwrap_or(SecurityLevel::Public);
+
+        TokenIdentity {
+            subject: claims.get("sub").and_then(Value::as_str).map(str::to_string),
+            issuer: self.config.issuer.clone(),
+            security_level: requested.min(self.max_level),
+            permissions: string_list(claims.get(&self.config.permissions_claim)),
+            roles: string_list(claims.get(&self.config.roles_claim)),
+            expires_at: claims
+                .get("exp")
+                .and_then(Value::as_i64)
+                .and_then(|exp| DateTime::from_timestamp(exp, 0)),
+        }
+    }
+}
+
+/// Nivel por nombre (`confidential`, `top_secret`) o número (0–4)
+pub fn parse_level(value: &Value) -> Option<SecurityLevel> {
+    let level = match value {
+        Value::Number(number) => number.as_u64()?,
+        Value::String(name) => match name.to_lowercase().replace(['_', '-'], "").as_str() {
+            "public" => 0,
+            "internal" => 1,
+            "confidential" => 2,
+            "secret" => 3,
+            "topsecret" => 4,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(match level {
+        0 => SecurityLevel::Public,
+        1 => SecurityLevel::Internal,
+        2 => SecurityLevel::Confidential,
+        3 => SecurityLevel::Secret,
+        4 => SecurityLevel::TopSecret,
+        _ => return None,
+    })
+}
+
+/// Lista JSON o texto separado por espacios (estilo `scope` de OAuth)
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        Some(Value::String(text)) => text.split_whitespace().map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    fn sign(claims: Value, secret: &str) -> String {
+        jsonwebtoken::encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn test_validate_and_map_claims() {
+        let validator = TokenValidator::new(
+            &[TokenIssuerConfig {
+                issuer: "https://auth.saai.local".to_string(),
+                key: "secreto-compartido".to_string(),
+                audience: Some("saai-admin".to_string()),
+                max_level: "confidential".to_string(),
+                ..Default::default()
+            }],
+            0,
+        )
+        .unwrap();
+
+        let exp = chrono::Utc::now().timestamp() + 300;
+        let claims = json!({
+            "iss": "https://auth.saai.local",
+            "aud": "saai-admin",
+            "sub": "operador",
+            "exp": exp,
+            "security_level": "top_secret",
+            "permissions": "metrics.read nano_core.network.*",
+            "roles": ["observer"],
+        });
+
+        let identity = validator.validate(&sign(claims.clone(), "secreto-compartido")).unwrap();
+        assert_eq!(identity.subject.as_deref(), Some("operador"));
+        // El emisor no puede conceder más de su nivel máximo
+        assert_eq!(identity.security_level, SecurityLevel::Confidential);
+        assert_eq!(identity.permissions, vec!["metrics.read", "nano_core.network.*"]);
+        assert_eq!(identity.roles, vec!["observer"]);
+        assert_eq!(identity.expires_at.map(|at| at.timestamp()), Some(exp));
+
+        assert!(validator.validate(&sign(claims.clone(), "otro-secreto")).is_err());
+
+        let mut expired = claims.clone();
+        expired["exp"] = json!(exp - 600);
+        assert!(validator.validate(&sign(expired, "secreto-compartido")).is_err());
+
+        let mut foreign = claims;
+        foreign["iss"] = json!("https://evil.example");
+        assert!(validator.validate(&sign(foreign, "secreto-compartido")).is_err());
+    }
+}
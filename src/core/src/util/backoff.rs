@@ -0,0 +1,110 @@
+//! Espera exponencial con jitter
+//!
+//! La espera del intento `n` (desde 0) es `initial * factor^n`, acotada por
+//! `max`, menos una fracción aleatoria de hasta `jitter` para que las réplicas
+//! que fallan a la vez no reintenten sincronizadas. Con `jitter = 0` la espera
+//! es determinista y con `jitter = 1` es el "full jitter" clásico.
+
+use std::time::Duration;
+
+use super::rng;
+
+/// Política de espera entre reintentos
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub factor: u32,
+    /// Fracción de la espera que se recorta al azar (0–1)
+    pub jitter: f64,
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Duplicar `initial` en cada intento, sin tope ni jitter
+    pub fn new(initial: Duration) -> Self {
+        Self {
+            initial,
+            max: Duration::MAX,
+            factor: 2,
+            jitter: 0.0,
+            attempt: 0,
+        }
+    }
+
+    pub fn with_max(mut self, max: Duration) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_factor(mut self, factor: u32) -> Self {
+        self.factor = factor.max(1);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Espera del intento `attempt` sin jitter
+    pub fn ceiling(&self, attempt: u32) -> Duration {
+        self.initial.saturating_mul(self.factor.saturating_pow(attempt)).min(self.max)
+    }
+
+    /// Espera del intento `attempt` con jitter
+    pub fn delay(&self, attempt: u32) -> Duration {
+        rng::jitter(self.ceiling(attempt), self.jitter)
+    }
+
+    /// Espera del siguiente intento; avanza el contador
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Intentos consumidos desde el último `reset`
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Volver al primer intento tras un éxito
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_with_jitter() {
+        let ms = Duration::from_millis;
+
+        // Sin jitter: determinista y acotado, sin desbordar en intentos altos
+        let mut backoff = Backoff::new(ms(100)).with_max(ms(1000));
+        let delays: Vec<_> = (0..6).map(|_| backoff.next_delay()).collect();
+        assert_eq!(delays, [100, 200, 400, 800, 1000, 1000].map(ms));
+        assert_eq!(backoff.attempt(), 6);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), ms(100));
+        assert_eq!(backoff.ceiling(u32::MAX), ms(1000));
+
+        // Con jitter la espera queda entre (1 - jitter) y el techo del intento
+        let backoff = Backoff::new(ms(100)).with_factor(3).with_jitter(0.5);
+        for attempt in 0..4 {
+            let ceiling = backoff.ceiling(attempt);
+            for _ in 0..100 {
+                let delay = backoff.delay(attempt);
+                assert!(delay <= ceiling && delay >= ceiling / 2, "{:?} fuera de rango para {:?}", delay, ceiling);
+            }
+        }
+
+        assert_eq!(rng::jitter(ms(100), 0.0), ms(100));
+        assert!(rng::duration(ms(1), ms(50)) <= ms(50) && rng::duration(ms(1), ms(50)) >= ms(1));
+        assert_eq!(rng::duration(ms(5), ms(1)), ms(5));
+        assert!(rng::chance(1.0) && !rng::chance(0.0));
+    }
+}
@@ -0,0 +1,9 @@
+//! Utilidades compartidas del núcleo
+//!
+//! `rng` envuelve el generador aleatorio de `rand` y `backoff` calcula las
+//! esperas exponenciales con jitter de reintentos, reconexiones y reinicios.
+
+pub mod backoff;
+pub mod rng;
+
+pub use backoff::Backoff;
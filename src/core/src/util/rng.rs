@@ -0,0 +1,32 @@
+//! Números aleatorios del núcleo
+//!
+//! Envoltorios sobre el generador por hilo de `rand`, sembrado desde el
+//! sistema operativo, para el jitter de las esperas y las simulaciones.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// `true` con probabilidad `probability` (acotada a 0–1)
+pub fn chance(probability: f64) -> bool {
+    rand::thread_rng().gen_bool(probability.clamp(0.0, 1.0))
+}
+
+/// Duración uniforme entre `min` y `max`, ambos incluidos
+pub fn duration(min: Duration, max: Duration) -> Duration {
+    if max <= min {
+        return min;
+    }
+    rand::thread_rng().gen_range(min..=max)
+}
+
+/// Recortar `delay` al azar hasta una fracción `fraction` de su valor
+///
+/// Nunca supera `delay`, así que respeta los topes de quien la calcula;
+/// con `fraction = 1.0` la espera es uniforme entre 0 y `delay`.
+pub fn jitter(delay: Duration, fraction: f64) -> Duration {
+    let fraction = fraction.clamp(0.0, 1.0);
+    if fraction == 0.0 || delay.is_zero() {
+        return delay;
+    }
+    delay.mul_f64(1.0 - rand::thread_rng().gen_range(0.0..=fraction))
+}